@@ -0,0 +1,85 @@
+//! Implementation of `cargo gluegun clean`, which removes the crates recorded as generated (see [`crate::state`][]).
+
+use std::collections::BTreeSet;
+
+use anyhow::Context;
+use cargo_metadata::camino::Utf8Path;
+use gluegun_core::codegen::WorkspaceFiles;
+
+use crate::{state::GenerationState, workspace::write_workspace_files, Builder, Selection};
+
+/// Arguments to `cargo gluegun clean`.
+#[derive(clap::Args)]
+pub(crate) struct CleanArgs {
+    #[command(flatten)]
+    pub(crate) selection: Selection,
+}
+
+/// Remove the crates generated for the selected packages by the given plugins (or by any plugin, if none is given),
+/// along with those generated for packages that are no longer members of the workspace, which cannot be selected.
+/// They are removed from the members of the workspace too and, once no crate generated by a plugin remains,
+/// so are the files it added to the workspace.
+pub(crate) fn clean(builder: &Builder, args: &CleanArgs) -> anyhow::Result<()> {
+    let metadata = builder.cargo_metadata(&args.selection)?;
+    let workspace_root = &metadata.workspace_root;
+    let selected: BTreeSet<&str> = builder
+        .selected_packages(&args.selection, &metadata)
+        .into_iter()
+        .map(|package| package.name.as_str())
+        .collect();
+    let members: BTreeSet<&str> = metadata.workspace_packages().iter().map(|p| p.name.as_str()).collect();
+    let plugins = &args.selection.plugins;
+
+    let mut state = GenerationState::load(workspace_root);
+    let removed = state.remove_crates(|generated| {
+        let package = generated.package.as_str();
+        (selected.contains(package) || !members.contains(package))
+            && (plugins.is_empty() || plugins.contains(&generated.plugin))
+    });
+
+    for path in removed.keys() {
+        let crate_path = workspace_root.join(path);
+        if crate_path.exists() {
+            eprintln!("removing {crate_path:?}");
+            std::fs::remove_dir_all(&crate_path).with_context(|| format!("removing `{crate_path}`"))?;
+        }
+        remove_workspace_member(workspace_root, path.as_str())?;
+    }
+
+    let removed_plugins: BTreeSet<&str> = removed.values().map(|generated| generated.plugin.as_str()).collect();
+    for plugin in removed_plugins {
+        if !state.has_plugin(plugin) {
+            write_workspace_files(workspace_root, plugin, &WorkspaceFiles::default())
+                .with_context(|| format!("removing workspace files for plugin `{plugin}`"))?;
+        }
+    }
+    state.save(workspace_root).context("recording the generated crates")?;
+
+    match removed.len() {
+        1 => eprintln!("removed 1 crate"),
+        n => eprintln!("removed {n} crates"),
+    }
+    Ok(())
+}
+
+/// Remove `member` from the `workspace.members` of the manifest at `workspace_root`, where `cargo new` added it
+/// when the crate was generated. Members that a glob (e.g., `"*-java"`) adds are left alone.
+fn remove_workspace_member(workspace_root: &Utf8Path, member: &str) -> anyhow::Result<()> {
+    let manifest_path = workspace_root.join("Cargo.toml");
+    let contents = std::fs::read_to_string(&manifest_path).with_context(|| format!("reading `{manifest_path}`"))?;
+    let mut manifest: toml_edit::DocumentMut =
+        contents.parse().with_context(|| format!("parsing `{manifest_path}`"))?;
+    let Some(members) = manifest
+        .get_mut("workspace")
+        .and_then(|workspace| workspace.get_mut("members"))
+        .and_then(|members| members.as_array_mut())
+    else {
+        return Ok(());
+    };
+    let len = members.len();
+    members.retain(|value| value.as_str() != Some(member));
+    if members.len() == len {
+        return Ok(());
+    }
+    std::fs::write(&manifest_path, manifest.to_string()).with_context(|| format!("writing to file at `{manifest_path}`"))
+}
@@ -0,0 +1,110 @@
+use anyhow::Context;
+use cargo_metadata::camino::Utf8Path;
+
+use crate::merge_metadata;
+
+/// Name of the optional configuration file at the root of a workspace or package.
+const CONFIG_FILE_NAME: &str = "gluegun.toml";
+
+/// The keys interpreted by cargo-gluegun itself; any other key must be a table configuring a plugin.
+const KNOWN_KEYS: &[&str] = &["plugins", "plugin-command", "destination-path", "doc-examples", "doc-hidden", "cfg", "max-module-depth"];
+
+/// The gluegun configuration of a package. It is merged from the following sources,
+/// each of which takes precedence over the ones before it:
+///
+/// 1. `[workspace.metadata.gluegun]` in the workspace `Cargo.toml`
+/// 2. `gluegun.toml` at the workspace root
+/// 3. `[package.metadata.gluegun]` in the package `Cargo.toml`
+/// 4. `gluegun.toml` at the package root
+///
+/// A `gluegun.toml` file has the same contents as the `metadata.gluegun` tables,
+/// e.g., `plugins = ["java"]` at the top level and the options of each plugin in a table
+/// named after it (`[java]`).
+pub(crate) struct GluegunConfig {
+    /// The configuration from each source, in increasing order of precedence.
+    layers: Vec<serde_json::Value>,
+}
+
+impl GluegunConfig {
+    pub(crate) fn load(metadata: &cargo_metadata::Metadata, package: &cargo_metadata::Package) -> anyhow::Result<Self> {
+        let workspace_root = metadata.workspace_root.as_path();
+        let package_root = package.manifest_path.parent().unwrap();
+
+        let Self { mut layers } = Self::load_workspace(metadata)?;
+        layers.extend(package.metadata.get("gluegun").cloned());
+        if package_root != workspace_root {
+            layers.extend(load_config_file(package_root)?);
+        }
+        Ok(Self { layers })
+    }
+
+    /// The configuration of the workspace as a whole, from the first two sources (without those of any package).
+    pub(crate) fn load_workspace(metadata: &cargo_metadata::Metadata) -> anyhow::Result<Self> {
+        let mut layers = vec![];
+        layers.extend(metadata.workspace_metadata.get("gluegun").cloned());
+        layers.extend(load_config_file(&metadata.workspace_root)?);
+        Ok(Self { layers })
+    }
+
+    /// The merged configuration (the `gluegun` table).
+    pub(crate) fn merged(&self) -> anyhow::Result<serde_json::Value> {
+        self.merge(Some)
+            .context("merging workspace and package metadata")
+    }
+
+    /// The merged configuration for `plugin` (the `gluegun.<plugin>` table).
+    pub(crate) fn plugin(&self, plugin: &str) -> anyhow::Result<serde_json::Value> {
+        self.merge(|layer| layer.get(plugin))
+            .with_context(|| format!("merging workspace and package metadata for `{plugin}`"))
+    }
+
+    /// The plugins to run if none are given on the command line (`plugins = [...]`).
+    pub(crate) fn plugins(&self) -> anyhow::Result<Vec<String>> {
+        match self.merged()?.get("plugins") {
+            Some(plugins) => serde_json::from_value(plugins.clone())
+                .context("expected a list of plugin names for `plugins`"),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Warnings for the keys of the merged configuration that cargo-gluegun does not know
+    /// (and which are not plugin tables), e.g., a misspelled `plugin_comand`.
+    pub(crate) fn unknown_key_warnings(&self) -> anyhow::Result<Vec<String>> {
+        let serde_json::Value::Object(map) = self.merged()? else {
+            return Ok(vec![]);
+        };
+
+        let valid: Vec<String> = KNOWN_KEYS.iter().map(|key| format!("`{key}`")).collect();
+        Ok(map
+            .iter()
+            .filter(|(key, value)| !KNOWN_KEYS.contains(&key.as_str()) && !value.is_object())
+            .map(|(key, _)| {
+                format!(
+                    "unknown gluegun configuration key `{key}` (valid keys are {}, along with a table for each plugin)",
+                    valid.join(", ")
+                )
+            })
+            .collect())
+    }
+
+    fn merge<'c>(&'c self, op: impl Fn(&'c serde_json::Value) -> Option<&'c serde_json::Value>) -> anyhow::Result<serde_json::Value> {
+        let mut merged = serde_json::Value::Null;
+        for value in self.layers.iter().filter_map(op) {
+            merged = merge_metadata(Some(&merged).filter(|merged| !merged.is_null()), Some(value))?;
+        }
+        Ok(merged)
+    }
+}
+
+/// Load the `gluegun.toml` file in `dir`, if any.
+fn load_config_file(dir: &Utf8Path) -> anyhow::Result<Option<serde_json::Value>> {
+    let path = dir.join(CONFIG_FILE_NAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = std::fs::read_to_string(&path).with_context(|| format!("reading `{path}`"))?;
+    let value: toml::Value = toml::from_str(&contents).with_context(|| format!("parsing `{path}`"))?;
+    let value = serde_json::to_value(value).with_context(|| format!("converting `{path}`"))?;
+    Ok(Some(value))
+}
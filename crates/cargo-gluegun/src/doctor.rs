@@ -0,0 +1,142 @@
+//! Implementation of `cargo gluegun doctor`, which checks for the external tools that the plugins require
+//! (see [`gluegun_core::cli::DOCTOR_FLAG`][]).
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    process::Stdio,
+};
+
+use anyhow::Context;
+use gluegun_core::{cli::DOCTOR_FLAG, codegen::ToolReport};
+
+use crate::{failure, failure::HelperFailure, Builder, Selection};
+
+/// Arguments to `cargo gluegun doctor`.
+#[derive(clap::Args)]
+pub(crate) struct DoctorArgs {
+    #[command(flatten)]
+    pub(crate) selection: Selection,
+}
+
+/// Report the tools required by each plugin (see [`required_tools`][]),
+/// with their versions or how to install those that are missing.
+pub(crate) fn doctor(builder: &Builder, args: &DoctorArgs) -> anyhow::Result<()> {
+    let reports = required_tools(builder, args)?;
+
+    let mut missing = 0;
+    for (plugin, tools) in &reports {
+        eprintln!("plugin `{plugin}`");
+        if tools.is_empty() {
+            eprintln!("    requires no tools");
+        }
+        for tool in tools {
+            let name = tool.name();
+            match (tool.found(), tool.version()) {
+                (Some(found), Some(version)) => eprintln!("    {name}: {version} (at {found})"),
+                (Some(found), None) => eprintln!("    {name}: {found}"),
+                (None, _) => {
+                    missing += 1;
+                    match tool.help() {
+                        Some(help) => eprintln!("    {name}: missing; {help}"),
+                        None => eprintln!("    {name}: missing"),
+                    }
+                }
+            }
+        }
+    }
+
+    match missing {
+        0 => {
+            eprintln!("found all the required tools");
+            Ok(())
+        }
+        1 => anyhow::bail!("1 required tool is missing"),
+        n => anyhow::bail!("{n} required tools are missing"),
+    }
+}
+
+/// Ask each plugin to apply to the selected packages for the tools it requires with the configuration of each package,
+/// and collect them by plugin. Plugins whose helper cannot be asked (see [`check_plugin`][]) are left out.
+pub(crate) fn required_tools(builder: &Builder, args: &DoctorArgs) -> anyhow::Result<BTreeMap<String, Vec<ToolReport>>> {
+    let mut asked = BTreeSet::new();
+    let mut reports: BTreeMap<String, Vec<ToolReport>> = BTreeMap::new();
+    builder.for_each_plugin(&args.selection, |plugin, _metadata, _package, config| {
+        let gluegun_metadata = config.merged()?;
+        let plugin_metadata = config.plugin(plugin)?;
+        if !asked.insert((plugin.to_string(), plugin_metadata.to_string())) {
+            return Ok(());
+        }
+
+        let Some(tools) = check_plugin(builder, plugin, &gluegun_metadata, &plugin_metadata)
+            .with_context(|| format!("checking the tools required by plugin `{plugin}`"))?
+        else {
+            return Ok(());
+        };
+        let plugin_reports = reports.entry(plugin.to_string()).or_default();
+        for tool in tools {
+            if !plugin_reports.iter().any(|report| report.name() == tool.name()) {
+                plugin_reports.push(tool);
+            }
+        }
+        Ok(())
+    })?;
+    Ok(reports)
+}
+
+/// The tools that `plugin` requires with `plugin_metadata`, or `None` (after a warning) for helpers
+/// that cannot be asked for them (e.g., built against an older gluegun-core).
+fn check_plugin(
+    builder: &Builder,
+    plugin: &str,
+    gluegun_metadata: &serde_json::Value,
+    plugin_metadata: &serde_json::Value,
+) -> anyhow::Result<Option<Vec<ToolReport>>> {
+    let mut plugin_command = (builder.plugin_command)(gluegun_metadata, plugin).context("creating plugin command")?;
+    plugin_command
+        .current_dir(&builder.current_directory)
+        .arg(format!("gg-{plugin}"))
+        .arg(DOCTOR_FLAG)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if builder.backtrace {
+        plugin_command.env("RUST_BACKTRACE", "1");
+    }
+    if builder.verbose {
+        eprintln!("{plugin_command:?}");
+    }
+    let mut child = plugin_command
+        .spawn()
+        .with_context(|| format!("spawning gluegun-{plugin}"))?;
+
+    // This has to be kept in sync with the definition from `gluegun_core::cli`.
+    let Some(mut stdin) = child.stdin.take() else {
+        anyhow::bail!("failed to take stdin");
+    };
+    let input = serde_json::json!({ "metadata": plugin_metadata });
+    writeln!(stdin, "{input}").with_context(|| format!("writing data to gluegun-{plugin}"))?;
+    drop(stdin);
+
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("waiting for gluegun-{plugin}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let failure = HelperFailure::new(plugin, "(doctor)", output.status, &stderr);
+        if !failure.started() {
+            if builder.verbose {
+                eprintln!("{stderr}");
+            }
+            eprintln!("warning: gluegun-{plugin} cannot report the tools it requires");
+            return Ok(None);
+        }
+        failure::forward_stderr(&output.stderr[..], builder.verbose)?;
+        return Err(failure.into());
+    }
+    failure::forward_stderr(&output.stderr[..], builder.verbose)?;
+
+    let tools = serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("parsing the tools reported by gluegun-{plugin}"))?;
+    Ok(Some(tools))
+}
@@ -0,0 +1,177 @@
+//! Forwarding the stderr of helpers, and reporting the failures of helpers that exit with an error or panic.
+
+use std::{
+    fmt,
+    io::{BufRead, BufReader, Read},
+    process::ExitStatus,
+};
+
+use gluegun_core::cli::PHASE_PREFIX;
+
+/// The phase reported when a helper failed before entering any (e.g., one built against an older gluegun-core).
+const STARTING: &str = "starting";
+
+/// Forward the stderr of a helper to ours, line by line, and return all of it.
+/// Unless `verbose`, the phases the helper enters (see [`PHASE_PREFIX`][]) are not shown,
+/// nor is what follows a panic, which [`HelperFailure`][] reports instead.
+pub(crate) fn forward_stderr(stderr: impl Read, verbose: bool) -> std::io::Result<String> {
+    let mut captured = String::new();
+    let mut panicked = false;
+    for line in BufReader::new(stderr).lines() {
+        let line = line?;
+        panicked |= is_panic(&line);
+        if verbose || !(panicked || line.starts_with(PHASE_PREFIX)) {
+            eprintln!("{line}");
+        }
+        captured.push_str(&line);
+        captured.push('\n');
+    }
+    Ok(captured)
+}
+
+/// Rust prints `thread 'main' panicked at src/main.rs:2:5:` (with the id of the thread in recent versions)
+/// and the message on the following lines.
+fn is_panic(line: &str) -> bool {
+    line.starts_with("thread '") && line.contains(" panicked at ")
+}
+
+/// Why a helper did not generate the crate, reported as an error of `cargo gluegun`, e.g.
+///
+/// ```text
+/// gluegun-java panicked
+///     plugin:  java
+///     package: my-crate
+///     phase:   generating code
+///     panic:   not yet implemented
+///              at crates/gluegun-java/src/java_gen.rs:606:44
+///     help:    run `cargo gluegun -v` to see all output of the helper, and with `--backtrace` for a backtrace
+/// ```
+#[derive(Debug)]
+pub(crate) struct HelperFailure {
+    plugin: String,
+    package: String,
+    status: ExitStatus,
+    phase: String,
+    /// The error the helper returned from `main`, one line per cause, unless it panicked.
+    error: Vec<String>,
+    panic: Option<Panic>,
+}
+
+#[derive(Debug)]
+struct Panic {
+    location: String,
+    message: Vec<String>,
+    backtrace: Vec<String>,
+}
+
+impl HelperFailure {
+    /// The failure of the helper for `plugin` on `package`, which exited with `status` after writing `stderr`.
+    pub(crate) fn new(plugin: &str, package: &str, status: ExitStatus, stderr: &str) -> Self {
+        let phase = stderr
+            .lines()
+            .rev()
+            .find_map(|line| line.strip_prefix(PHASE_PREFIX))
+            .unwrap_or(STARTING);
+        Self {
+            plugin: plugin.to_string(),
+            package: package.to_string(),
+            status,
+            phase: phase.to_string(),
+            error: find_error(stderr),
+            panic: Panic::find(stderr),
+        }
+    }
+
+    /// False if the helper failed before entering any phase (e.g., as it does not know the arguments it was given).
+    pub(crate) fn started(&self) -> bool {
+        self.phase != STARTING
+    }
+}
+
+/// The error that the helper printed on returning it from `main` (`Error: generating code`, then the causes
+/// after `Caused by:`), without the backtrace.
+fn find_error(stderr: &str) -> Vec<String> {
+    let mut lines = stderr.lines().skip_while(|line| !line.starts_with("Error: "));
+    let Some(header) = lines.next() else {
+        return vec![];
+    };
+    std::iter::once(&header["Error: ".len()..])
+        .chain(lines.take_while(|line| *line != "Stack backtrace:").map(str::trim))
+        .filter(|line| !line.is_empty() && *line != "Caused by:" && !line.starts_with(PHASE_PREFIX))
+        // With several causes, anyhow numbers them (`0: ...`).
+        .map(|line| match line.split_once(": ") {
+            Some((index, cause)) if index.chars().all(|c| c.is_ascii_digit()) => cause.to_string(),
+            _ => line.to_string(),
+        })
+        .collect()
+}
+
+impl Panic {
+    /// The first panic in `stderr`, if any. Rust's notes about backtraces are left out.
+    fn find(stderr: &str) -> Option<Self> {
+        let mut lines = stderr.lines().skip_while(|line| !is_panic(line));
+        let header = lines.next()?;
+        let location = header.split(" panicked at ").nth(1).unwrap_or_default();
+        let mut panic = Panic {
+            location: location.strip_suffix(':').unwrap_or(location).to_string(),
+            message: vec![],
+            backtrace: vec![],
+        };
+        let mut in_backtrace = false;
+        for line in lines.filter(|line| !line.starts_with("note: ") && !line.starts_with(PHASE_PREFIX)) {
+            if line == "stack backtrace:" {
+                in_backtrace = true;
+            } else if in_backtrace {
+                panic.backtrace.push(line.to_string());
+            } else {
+                panic.message.push(line.to_string());
+            }
+        }
+        Some(panic)
+    }
+}
+
+impl fmt::Display for HelperFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { plugin, package, status, phase, error, panic } = self;
+        match panic {
+            Some(_) => writeln!(f, "gluegun-{plugin} panicked")?,
+            None => writeln!(f, "gluegun-{plugin} failed with code {status}")?,
+        }
+        writeln!(f, "    plugin:  {plugin}")?;
+        writeln!(f, "    package: {package}")?;
+        writeln!(f, "    phase:   {phase}")?;
+        if panic.is_none() {
+            let mut error = error.iter();
+            if let Some(first) = error.next() {
+                writeln!(f, "    error:   {first}")?;
+            }
+            for line in error {
+                writeln!(f, "             {line}")?;
+            }
+        }
+        if let Some(Panic { location, message, backtrace }) = panic {
+            let mut message = message.iter();
+            writeln!(f, "    panic:   {}", message.next().map_or("", |line| line))?;
+            for line in message {
+                writeln!(f, "             {line}")?;
+            }
+            writeln!(f, "             at {location}")?;
+            if !backtrace.is_empty() {
+                writeln!(f, "    backtrace:")?;
+                for line in backtrace {
+                    writeln!(f, "    {line}")?;
+                }
+            }
+        }
+        match panic {
+            Some(panic) if panic.backtrace.is_empty() => write!(
+                f,
+                "    help:    run `cargo gluegun -v` to see all output of the helper, and with `--backtrace` for a backtrace"
+            ),
+            _ => write!(f, "    help:    run `cargo gluegun -v` to see all output of the helper"),
+        }
+    }
+}
+
+impl std::error::Error for HelperFailure {}
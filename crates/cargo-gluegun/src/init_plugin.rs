@@ -0,0 +1,89 @@
+//! Implementation of `cargo gluegun init-plugin`, which scaffolds a new helper crate.
+
+use anyhow::Context;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+
+/// Arguments to `cargo gluegun init-plugin`.
+#[derive(clap::Args)]
+pub(crate) struct InitPluginArgs {
+    /// Name of the plugin (e.g., `swift`); the helper crate will be named `gluegun-{name}`.
+    name: String,
+
+    /// Directory in which to create the crate (defaults to `gluegun-{name}` in the current directory).
+    #[arg(long)]
+    path: Option<Utf8PathBuf>,
+
+    /// Path to a local checkout of gluegun; if given, the gluegun crates are referenced by path
+    /// rather than by version.
+    #[arg(long)]
+    gluegun_path: Option<Utf8PathBuf>,
+}
+
+/// Files in the scaffolded crate and the templates used to generate them.
+const TEMPLATES: &[(&str, &str)] = &[
+    ("Cargo.toml", include_str!("../templates/plugin/Cargo.toml.in")),
+    ("README.md", include_str!("../templates/plugin/README.md.in")),
+    ("src/main.rs", include_str!("../templates/plugin/main.rs.in")),
+    ("src/rs_gen.rs", include_str!("../templates/plugin/rs_gen.rs.in")),
+    ("tests/harness.rs", include_str!("../templates/plugin/harness.rs.in")),
+    ("tests/hello_world/Cargo.toml", include_str!("../templates/plugin/hello_world_Cargo.toml.in")),
+    ("tests/hello_world/src/lib.rs", include_str!("../templates/plugin/hello_world_lib.rs.in")),
+];
+
+pub(crate) fn init_plugin(current_directory: &Utf8Path, args: &InitPluginArgs) -> anyhow::Result<()> {
+    let name = &args.name;
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        anyhow::bail!("plugin name `{name}` must consist of lowercase letters, digits, and `_`");
+    }
+
+    let crate_name = format!("gluegun-{name}");
+    let crate_path = match &args.path {
+        Some(path) => current_directory.join(path),
+        None => current_directory.join(&crate_name),
+    };
+    if crate_path.exists() {
+        anyhow::bail!("`{crate_path}` already exists");
+    }
+
+    let gluegun_path = match &args.gluegun_path {
+        Some(path) => {
+            let path = current_directory.join(path);
+            Some(
+                path.canonicalize_utf8()
+                    .with_context(|| format!("resolving gluegun path `{path}`"))?,
+            )
+        }
+        None => None,
+    };
+    let dependency = |gluegun_crate: &str| match &gluegun_path {
+        Some(gluegun_path) => format!("{{ path = {:?} }}", gluegun_path.join("crates").join(gluegun_crate)),
+        None => format!("{:?}", "0.1"),
+    };
+
+    let substitutions = [
+        ("{{name}}", name.clone()),
+        ("{{Name}}", gluegun_core::idl::Name::from(name).upper_camel_case().text().clone()),
+        ("{{crate_name}}", crate_name.clone()),
+        ("{{gluegun_core_dependency}}", dependency("gluegun-core")),
+        ("{{gluegun_test_harness_dependency}}", dependency("gluegun-test-harness")),
+    ];
+
+    for (path, template) in TEMPLATES {
+        let contents = substitutions
+            .iter()
+            .fold(template.to_string(), |contents, (from, to)| contents.replace(from, to));
+
+        let file_path = crate_path.join(path);
+        if let Some(dir_path) = file_path.parent() {
+            std::fs::create_dir_all(dir_path).with_context(|| format!("creating directory at `{dir_path}`"))?;
+        }
+        std::fs::write(&file_path, contents).with_context(|| format!("writing to file at `{file_path}`"))?;
+    }
+
+    eprintln!("Created plugin crate `{crate_name}` at `{crate_path}`");
+    Ok(())
+}
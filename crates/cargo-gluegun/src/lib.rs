@@ -1,13 +1,32 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::ffi::OsString;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::io::Write;
 use std::path::Path;
-use std::process::{ChildStdin, Command, ExitStatus, Stdio};
+use std::process::{ChildStdin, Command, Output, Stdio};
 
 use anyhow::Context;
 use cargo_metadata::camino::Utf8PathBuf;
 use clap::Parser;
+use gluegun_core::{
+    cli::{CustomSrc, ExtraFiles, MetadataSchema},
+    codegen::{CargoOptions, ToolReport},
+};
 use serde::{Deserialize, Serialize};
 
+mod clean;
+mod config;
+mod doctor;
+mod failure;
+mod init_plugin;
+mod lint;
+mod state;
+mod workspace;
+
+use config::GluegunConfig;
+use failure::HelperFailure;
+use state::GenerationState;
+
 /// Main function for the gluegun CLI.
 pub fn cli_main() -> anyhow::Result<()> {
     Builder::from_env()?.execute()
@@ -21,6 +40,18 @@ pub struct Builder {
         &serde_json::Value,
         &str,
     ) -> anyhow::Result<Command>>,
+
+    /// Set by `--force`: regenerate crates even if their input is unchanged.
+    force: bool,
+
+    /// Set by `--verbose`: show the helper commands and all of their output.
+    verbose: bool,
+
+    /// Set by `--backtrace`: helpers that panic print a backtrace (`RUST_BACKTRACE=1`).
+    backtrace: bool,
+
+    /// Set by `--offline` and `--locked`: how cargo is invoked, by us and by the helpers.
+    cargo_options: CargoOptions,
 }
 
 impl Builder {
@@ -34,6 +65,10 @@ impl Builder {
             current_directory: Utf8PathBuf::try_from(current_directory.as_ref().to_path_buf())?,
             args: args.into_iter().map(Into::into).collect(),
             plugin_command: Box::new(Self::default_plugin_command),
+            force: false,
+            verbose: false,
+            backtrace: false,
+            cargo_options: CargoOptions::new(),
         })
     }
 
@@ -44,7 +79,8 @@ impl Builder {
 
     /// Customize the code to create the plugin command
     /// 
-    /// The function will be invoked with the workspace/package `metadata.gluegun` field
+    /// The function will be invoked with the gluegun configuration of the package
+    /// (merged from the `metadata.gluegun` tables and `gluegun.toml` files)
     /// along with the name of the plugin. It should return a new `Command` object.
     pub fn plugin_command(mut self, 
         plugin_command: impl Fn(
@@ -57,39 +93,205 @@ impl Builder {
     }
 
     /// Execute cargo-gluegun.
-    pub fn execute(self) -> anyhow::Result<()> {
+    pub fn execute(mut self) -> anyhow::Result<()> {
         let cli = Cli::try_parse_from(&self.args)?;
+        self.force = cli.force;
+        self.verbose = cli.verbose;
+        self.backtrace = cli.backtrace;
+        self.cargo_options = match &cli.command {
+            Some(CliCommand::Lint(args)) => args.selection.cargo_options(),
+            Some(CliCommand::Doctor(args)) => args.selection.cargo_options(),
+            Some(CliCommand::Clean(args)) => args.selection.cargo_options(),
+            _ => cli.selection.cargo_options(),
+        };
 
-        let metadata = cli
-            .manifest
-            .metadata()
-            .current_dir(&self.current_directory)
-            .exec()?;
-        let (selected, _excluded) = cli.workspace.partition_packages(&metadata);
+        if let Some(command) = &cli.command {
+            return match command {
+                CliCommand::InitPlugin(args) => init_plugin::init_plugin(&self.current_directory, args),
+                CliCommand::Lint(args) => lint::lint(&self, args),
+                CliCommand::Doctor(args) => doctor::doctor(&self, args),
+                CliCommand::Clean(args) => clean::clean(&self, args),
+            };
+        }
 
-        if selected.is_empty() {
-            anyhow::bail!("no packages selected -- you may have misspelled the package name?");
+        // Once the crates of all the selected packages are generated, each plugin can add files
+        // shared by the crates it generated in the workspace (see `gluegun_core::cli::WORKSPACE_FLAG`).
+        // What was generated is recorded in the workspace (see `state::GenerationState`),
+        // including by the crates generated before a plugin failed.
+        let mut workspace = None;
+        let mut plugins = BTreeSet::new();
+        let mut generated = vec![];
+        let result = self.for_each_plugin(&cli.selection, |plugin, metadata, package, config| {
+            let state = &mut workspace
+                .get_or_insert_with(|| {
+                    let state = GenerationState::load(&metadata.workspace_root);
+                    state.warn_if_generator_changed();
+                    (metadata.clone(), state)
+                })
+                .1;
+            let input = self.apply_plugin(plugin, metadata, package, config)?;
+            state.record(&metadata.workspace_root, &package.name, plugin, &input)?;
+            plugins.insert(plugin.to_string());
+            generated.push((package.name.to_string(), plugin.to_string(), input.crate_path));
+            Ok(())
+        });
+
+        let Some((metadata, state)) = workspace else {
+            return result;
+        };
+        if result.is_ok() {
+            state.warn_stale(&metadata, &generated, cli.selection.plugins.is_empty());
         }
+        state
+            .save(&metadata.workspace_root)
+            .context("recording the generated crates")?;
+        result?;
+
+        for plugin in &plugins {
+            self.generate_workspace_files(plugin, &metadata)
+                .with_context(|| format!("generating workspace files for plugin `{plugin}`"))?;
+        }
+        Ok(())
+    }
+
+    /// Check for the tools that the plugins require, like `cargo gluegun doctor` (which the arguments must invoke),
+    /// but return what was found for each plugin rather than reporting it. Missing tools are not an error.
+    pub fn required_tools(mut self) -> anyhow::Result<BTreeMap<String, Vec<ToolReport>>> {
+        let cli = Cli::try_parse_from(&self.args)?;
+        let Some(CliCommand::Doctor(args)) = &cli.command else {
+            anyhow::bail!("expected the arguments of `cargo gluegun doctor`");
+        };
+        self.verbose = cli.verbose;
+        self.backtrace = cli.backtrace;
+        self.cargo_options = args.selection.cargo_options();
+        doctor::required_tools(&self, args)
+    }
 
-        if cli.plugins.is_empty() {
-            anyhow::bail!("no plugins specified");
+    /// Invoke `op` with each plugin to apply to each selected package (see [`Self::selected_packages`][]),
+    /// along with the package and its gluegun configuration.
+    fn for_each_plugin(
+        &self,
+        selection: &Selection,
+        mut op: impl FnMut(&str, &cargo_metadata::Metadata, &cargo_metadata::Package, &GluegunConfig) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let metadata = self.cargo_metadata(selection)?;
+        let selected = self.selected_packages(selection, &metadata);
+
+        if selected.is_empty() {
+            anyhow::bail!("no packages selected -- you may have misspelled the package name?");
         }
 
         for package in selected {
-            for plugin in &cli.plugins {
-                self.apply_plugin(plugin, &metadata.workspace_metadata, package)?;
+            let config = GluegunConfig::load(&metadata, package)
+                .with_context(|| format!("loading gluegun configuration for `{}`", package.name))?;
+            for warning in config.unknown_key_warnings()? {
+                eprintln!("warning: {}: {warning}", package.name);
+            }
+
+            // Plugins given on the command line override those from the configuration.
+            let plugins = if selection.plugins.is_empty() {
+                config.plugins()?
+            } else {
+                selection.plugins.clone()
+            };
+            if plugins.is_empty() {
+                anyhow::bail!("no plugins specified (on the command line or as `plugins` in the configuration)");
+            }
+
+            for plugin in &plugins {
+                op(plugin, &metadata, package, &config)?;
             }
         }
 
         Ok(())
     }
 
+    /// The metadata of the workspace selected with `--manifest-path` (or containing the current directory).
+    fn cargo_metadata(&self, selection: &Selection) -> anyhow::Result<cargo_metadata::Metadata> {
+        // Features given on the command line (`--features`, `--all-features`, `--no-default-features`)
+        // are forwarded so that the resolved features of each package are those of the build.
+        let mut metadata_command = selection.manifest.metadata();
+        metadata_command.cargo_path(self.cargo_options.cargo_binary());
+        metadata_command.other_options(self.cargo_options.flags());
+        Ok(selection
+            .features
+            .forward_metadata(&mut metadata_command)
+            .current_dir(&self.current_directory)
+            .exec()?)
+    }
+
+    /// The packages to process. Like cargo, `--package`, `--workspace`, and `--exclude` select among the workspace members;
+    /// without them, the current package is selected (see [`Self::current_package`][]) or,
+    /// at the root of a virtual workspace, its default members.
+    fn selected_packages<'m>(&self, selection: &Selection, metadata: &'m cargo_metadata::Metadata) -> Vec<&'m cargo_metadata::Package> {
+        let workspace = &selection.workspace;
+        if workspace.package.is_empty() && !workspace.workspace && !workspace.all {
+            if let Some(package) = self.current_package(&selection.manifest, metadata) {
+                return vec![package];
+            }
+        }
+        let (selected, _excluded) = workspace.partition_packages(metadata);
+        selected
+    }
+
+    /// The workspace member whose manifest is given with `--manifest-path` or, failing that,
+    /// is the first `Cargo.toml` found in the current directory or its ancestors.
+    /// Returns `None` if that manifest is virtual (i.e., has no `[package]`).
+    fn current_package<'m>(
+        &self,
+        manifest: &clap_cargo::Manifest,
+        metadata: &'m cargo_metadata::Metadata,
+    ) -> Option<&'m cargo_metadata::Package> {
+        let manifest_path = match &manifest.manifest_path {
+            Some(path) => self.current_directory.as_std_path().join(path),
+            None => self
+                .current_directory
+                .ancestors()
+                .map(|dir| dir.join("Cargo.toml"))
+                .find(|path| path.is_file())?
+                .into_std_path_buf(),
+        };
+        let manifest_path = manifest_path.canonicalize().ok()?;
+        metadata
+            .workspace_packages()
+            .into_iter()
+            .find(|package| package.manifest_path.canonicalize().is_ok_and(|path| path == manifest_path))
+    }
+
+    /// Generate the crate of `package` with `plugin` and return the input it was generated from.
     fn apply_plugin(
         &self,
         plugin: &str,
-        workspace_metadata: &serde_json::Value,
+        metadata: &cargo_metadata::Metadata,
         package: &cargo_metadata::Package,
-    ) -> anyhow::Result<()> {
+        config: &GluegunConfig,
+    ) -> anyhow::Result<PluginInput> {
+        let input = self.plugin_input(plugin, metadata, package, config, false)?;
+
+        // Execute the plugin
+        let output = self
+            .execute_plugin(plugin, &input, false)
+            .with_context(|| format!("executing plugin `{plugin}`"))?;
+
+        if output.status.success() {
+            Ok(input)
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(HelperFailure::new(plugin, &package.name, output.status, &stderr).into())
+        }
+    }
+
+    /// Compute what to give `plugin` for `package`: its metadata, the IDL, and the destination crate.
+    /// With `error_recovery`, definitions that cannot be parsed are left out of the IDL
+    /// and their errors returned in [`PluginInput::parse_errors`][] (see [`gluegun_idl::Parser::with_error_recovery`][]).
+    fn plugin_input(
+        &self,
+        plugin: &str,
+        metadata: &cargo_metadata::Metadata,
+        package: &cargo_metadata::Package,
+        config: &GluegunConfig,
+        error_recovery: bool,
+    ) -> anyhow::Result<PluginInput> {
         if let Some(_) = package.source {
             anyhow::bail!("{pkg}: can only process local packages", pkg = package.name);
         }
@@ -99,56 +301,189 @@ impl Builder {
         let manifest_dir = cargo_toml_path.parent().unwrap();
         let src_lib_rs = manifest_dir.join("src/lib.rs");
 
-        let idl = gluegun_idl::Parser::new()
-            .parse_crate_named(&package.name, &manifest_dir, &src_lib_rs)
-            .with_context(|| format!("extracting interface from `{src_lib_rs}`"))?;
+        let crate_metadata = gluegun_idl::CrateMetadata::new(
+            Some(package.version.to_string()),
+            package.description.clone(),
+            package.repository.clone(),
+        );
 
-        // Extract gluegun metadata (if any).
-        let gluegun_workspace_metadata = workspace_metadata.get("gluegun");
-        let gluegun_package_metadata = package.metadata.get("gluegun");
-        let gluegun_metadata = merge_metadata(gluegun_workspace_metadata, gluegun_package_metadata)
-            .with_context(|| format!("merging workspace and package metadata"))?;
+        let gluegun_metadata = config.merged()?;
 
-        // Search for `workspace.metadata.gluegun.tool_name` and
-        // `package.metadata.gluegun.tool_name`.
-        let plugin_workspace_metadata = gluegun_workspace_metadata.and_then(|v| v.get(plugin));
-        let plugin_package_metadata = gluegun_package_metadata.and_then(|v| v.get(plugin));
-        let plugin_metadata = merge_metadata(plugin_workspace_metadata, plugin_package_metadata)
-            .with_context(|| format!("merging workspace and package metadata"))?;
+        // Code examples in doc comments are only extracted (for backends to emit as test stubs)
+        // if `doc-examples = true`.
+        let doc_examples = match gluegun_metadata.get("doc-examples") {
+            Some(value) => serde_json::from_value(value.clone()).context("parsing `doc-examples`")?,
+            None => false,
+        };
+
+        // Public items tagged `#[doc(hidden)]` are ignored unless `doc-hidden = true`.
+        let doc_hidden = match gluegun_metadata.get("doc-hidden") {
+            Some(value) => serde_json::from_value(value.clone()).context("parsing `doc-hidden`")?,
+            None => false,
+        };
+
+        // `#[cfg]` options other than features are unknown unless listed in `cfg`, e.g., `cfg = ["unix", "target_os = \"linux\""]`.
+        let cfg_options: Option<Vec<String>> = match gluegun_metadata.get("cfg") {
+            Some(value) => Some(serde_json::from_value(value.clone()).context("parsing `cfg`")?),
+            None => None,
+        };
+
+        // Modules can be nested arbitrarily deep unless `max-module-depth` is given.
+        let max_module_depth: Option<usize> = match gluegun_metadata.get("max-module-depth") {
+            Some(value) => Some(serde_json::from_value(value.clone()).context("parsing `max-module-depth`")?),
+            None => None,
+        };
+
+        // Search for `gluegun.tool_name` (e.g., `package.metadata.gluegun.tool_name`).
+        let plugin_metadata = config.plugin(plugin)?;
+        self.validate_plugin_metadata(plugin, &gluegun_metadata, &plugin_metadata)
+            .with_context(|| format!("validating metadata for plugin `{plugin}`"))?;
+        let plugin_version = self.plugin_version(plugin, &gluegun_metadata)?;
+
+        // Third-party types with a `type-overrides` entry for this plugin are accepted by the parser.
+        let type_overrides = gluegun_core::cli::TypeOverrides::from_metadata(&plugin_metadata)
+            .with_context(|| format!("parsing `type-overrides` for plugin `{plugin}`"))?;
+
+        // Workspace crates that `package` depends on are parsed along with it,
+        // so that their types can be referenced from its API.
+        // Each crate's `#[cfg(feature = ...)]` items are evaluated against its resolved features.
+        // Parse results are cached so that unchanged crates are not parsed again on the next run.
+        // Paths in the IDL are relative to the workspace root, which the helper is given separately,
+        // so that the input (and what the helper generates from it) is the same on any machine.
+        let mut parser = gluegun_idl::Parser::new()
+            .with_source_root(&metadata.workspace_root)
+            .with_crate_metadata(crate_metadata)
+            .with_doc_examples(doc_examples)
+            .with_doc_hidden(doc_hidden)
+            .with_edition(package.edition.as_str().parse()?)
+            .with_cache_dir(metadata.target_directory.join("gluegun/idl-cache"))
+            .with_error_recovery(error_recovery);
+        if let Some(features) = resolved_features(metadata, package) {
+            parser = parser.with_crate_features(&package.name, features);
+        }
+        for (extern_crate_name, extern_package, extern_lib_rs) in workspace_dependencies(metadata, package) {
+            if let Some(features) = resolved_features(metadata, extern_package) {
+                parser = parser.with_crate_features(&extern_crate_name, features);
+            }
+            parser = parser.with_extern_crate(
+                extern_crate_name,
+                extern_package.manifest_path.parent().unwrap(),
+                extern_lib_rs,
+            );
+        }
+        for path in type_overrides.paths() {
+            parser = parser.with_custom_type(path);
+        }
+        for option in cfg_options.iter().flatten() {
+            parser = match option.split_once('=') {
+                Some((name, value)) => parser.with_cfg(name.trim(), Some(value.trim().trim_matches('"'))),
+                None => parser.with_cfg(option.trim(), None),
+            };
+        }
+        if let Some(max_module_depth) = max_module_depth {
+            parser = parser.with_max_module_depth(max_module_depth);
+        }
+
+        let idl = parser
+            .parse_crate_named(&package.name, &manifest_dir, &src_lib_rs)
+            .with_context(|| format!("extracting interface from `{src_lib_rs}`"))?;
 
         // Compute destination crate name and path
         let (crate_name, crate_path) =
             dest_crate_name_and_path(plugin, &gluegun_metadata, package)
                 .with_context(|| format!("computing destination crate name and path"))?;
 
-        // Execute the plugin
-        let exit_status = self
-            .execute_plugin(
-                plugin,
-                &gluegun_metadata,
-                &idl,
-                &plugin_metadata,
-                &crate_name,
-                &crate_path,
-            )
-            .with_context(|| format!("executing plugin `{plugin}`"))?;
+        Ok(PluginInput {
+            gluegun_metadata,
+            plugin_metadata,
+            plugin_version,
+            workspace_root: metadata.workspace_root.clone(),
+            idl,
+            parse_errors: parser.take_recovered_errors(),
+            crate_name,
+            crate_path,
+        })
+    }
 
-        if exit_status.success() {
-            Ok(())
-        } else {
-            anyhow::bail!("gluegun-{plugin} failed with code {exit_status}");
+    /// Ask the plugin for the options it accepts (see [`gluegun_core::cli::DescribeMetadata`][])
+    /// and check `plugin_metadata` against them: unknown options are reported as warnings
+    /// and invalid values as errors. Plugins that cannot describe their options
+    /// (e.g., built against an older gluegun-core) are not checked.
+    fn validate_plugin_metadata(
+        &self,
+        plugin: &str,
+        gluegun_metadata: &serde_json::Value,
+        plugin_metadata: &serde_json::Value,
+    ) -> anyhow::Result<()> {
+        let mut plugin_command = (self.plugin_command)(gluegun_metadata, plugin)
+            .context("creating plugin command")?;
+        let output = plugin_command
+            .current_dir(&self.current_directory)
+            .arg(format!("gg-{plugin}"))
+            .arg(gluegun_core::cli::METADATA_SCHEMA_FLAG)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .with_context(|| format!("spawning gluegun-{plugin}"))?;
+        if !output.status.success() {
+            return Ok(());
+        }
+
+        let schema: MetadataSchema = serde_json::from_slice(&output.stdout)
+            .with_context(|| format!("parsing the metadata schema of gluegun-{plugin}"))?;
+        let mut has_errors = false;
+        for diagnostic in schema.validate(plugin_metadata) {
+            if diagnostic.is_error() {
+                has_errors = true;
+                eprintln!("error: `{plugin}` metadata: {diagnostic}");
+            } else {
+                eprintln!("warning: `{plugin}` metadata: {diagnostic}");
+            }
         }
+        if has_errors {
+            anyhow::bail!("invalid metadata for plugin `{plugin}`");
+        }
+        Ok(())
     }
 
+    /// Ask the helper of `plugin` for its version (see [`gluegun_core::cli::VERSION_FLAG`][]).
+    /// Returns `None` for helpers that cannot be asked (e.g., built against an older gluegun-core).
+    fn plugin_version(&self, plugin: &str, gluegun_metadata: &serde_json::Value) -> anyhow::Result<Option<String>> {
+        let mut plugin_command = (self.plugin_command)(gluegun_metadata, plugin)
+            .context("creating plugin command")?;
+        let output = plugin_command
+            .current_dir(&self.current_directory)
+            .arg(format!("gg-{plugin}"))
+            .arg(gluegun_core::cli::VERSION_FLAG)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .with_context(|| format!("spawning gluegun-{plugin}"))?;
+        let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Some(version).filter(|version| output.status.success() && !version.is_empty()))
+    }
+
+    /// Invoke the helper of `plugin` with `input`. With `lint`, the helper is asked for the problems that keep it
+    /// from generating the crate (see [`gluegun_core::cli::LINT_FLAG`][]), which it writes to stdout;
+    /// otherwise it generates the crate and its stdout is inherited.
+    /// Its stderr is forwarded as it is written (see [`failure::forward_stderr`][]) and returned in the output.
     fn execute_plugin(
         &self,
         plugin: &str,
-        gluegun_metadata: &serde_json::Value,
-        idl: &gluegun_idl::Idl,
-        metadata: &serde_json::Value,
-        crate_name: &str,
-        crate_path: &Utf8PathBuf,
-    ) -> anyhow::Result<ExitStatus> {
+        input: &PluginInput,
+        lint: bool,
+    ) -> anyhow::Result<Output> {
+        let PluginInput {
+            gluegun_metadata,
+            plugin_metadata: metadata,
+            plugin_version,
+            workspace_root,
+            idl,
+            parse_errors: _,
+            crate_name,
+            crate_path,
+        } = input;
+
         // Create the plugin command using the hook supplied by configuration.
         // Default is to run `Self::default_plugin_command` below.
         let mut plugin_command = (self.plugin_command)(
@@ -156,17 +491,33 @@ impl Builder {
             plugin,
         ).with_context(|| format!("creating plugin command"))?;
 
+        // Files that the user asked to bundle with the generated crate, relative to the package directory.
+        let package_dir = workspace_root.as_std_path().join(idl.crate_path());
+        let extra_files = ExtraFiles::resolve(metadata, &package_dir)
+            .with_context(|| format!("resolving `{}`", ExtraFiles::KEY))?;
+        let custom_src = CustomSrc::resolve(metadata, &package_dir)
+            .with_context(|| format!("resolving `{}`", CustomSrc::KEY))?;
+
         // Configure the command.
         plugin_command
             .current_dir(&self.current_directory)
             .arg(format!("gg-{}", plugin))
             .stdin(Stdio::piped()) // Configure stdin
             .stdout(Stdio::inherit()) // Configure stdout
-            .stderr(Stdio::inherit());
-        
+            .stderr(Stdio::piped());
+        if lint {
+            plugin_command
+                .arg(gluegun_core::cli::LINT_FLAG)
+                .stdout(Stdio::piped());
+        }
+        if self.backtrace {
+            plugin_command.env("RUST_BACKTRACE", "1");
+        }
 
         // Execute the helper
-        eprintln!("{plugin_command:?}");
+        if self.verbose {
+            eprintln!("{plugin_command:?}");
+        }
         let mut child = plugin_command 
             .spawn()
             .with_context(|| format!("spawning gluegun-{plugin}"))?;
@@ -176,14 +527,37 @@ impl Builder {
         let Some(stdin) = child.stdin.take() else {
             anyhow::bail!("failed to take stdin");
         };
+        let Some(stderr) = child.stderr.take() else {
+            anyhow::bail!("failed to take stderr");
+        };
+        let verbose = self.verbose;
+        let stderr = std::thread::spawn(move || failure::forward_stderr(stderr, verbose));
         let write_data = |mut stdin: ChildStdin| -> anyhow::Result<()> {
+            let idl = serde_json::to_string(&idl)?;
+            let metadata = serde_json::to_string(&metadata)?;
             writeln!(stdin, r#"{{"#)?;
-            writeln!(stdin, r#"  "idl": {},"#, serde_json::to_string(&idl)?)?;
-            writeln!(
-                stdin,
-                r#"  "metadata": {},"#,
-                serde_json::to_string(&metadata)?
-            )?;
+            // The hash of the input comes first, so that the helper can skip regenerating a crate it generated
+            // from the same input (see `gluegun_core::cli::run`), unless `--force` is given.
+            // Forced runs still get the hash, to record it for the next run.
+            if !lint {
+                let idl_hash = input_hash(
+                    &idl,
+                    &metadata,
+                    plugin_version.as_deref(),
+                    &extra_files,
+                    &custom_src,
+                    crate_name,
+                    crate_path,
+                )?;
+                writeln!(stdin, r#"  "idl_hash": {idl_hash:?},"#)?;
+                writeln!(stdin, r#"  "force": {},"#, self.force)?;
+            }
+            writeln!(stdin, r#"  "workspace_root": {workspace_root:?},"#)?;
+            writeln!(stdin, r#"  "idl": {idl},"#)?;
+            writeln!(stdin, r#"  "metadata": {metadata},"#)?;
+            writeln!(stdin, r#"  "extra_files": {},"#, serde_json::to_string(&extra_files)?)?;
+            writeln!(stdin, r#"  "custom_src": {},"#, serde_json::to_string(&custom_src)?)?;
+            writeln!(stdin, r#"  "cargo_options": {},"#, serde_json::to_string(&self.cargo_options)?)?;
             writeln!(stdin, r#"  "dest_crate": {{"#)?;
             writeln!(stdin, r#"    "crate_name": {crate_name:?},"#)?;
             writeln!(stdin, r#"    "path": {crate_path:?}"#)?;
@@ -192,11 +566,19 @@ impl Builder {
             Ok(())
         };
         write_data(stdin).with_context(|| format!("writing data to gluegun-{plugin}"))?;
-        eprintln!("output data successful");
+        if self.verbose {
+            eprintln!("output data successful");
+        }
 
-        Ok(child
-            .wait()
-            .with_context(|| format!("waiting for gluegun-{plugin}"))?)
+        let mut output = child
+            .wait_with_output()
+            .with_context(|| format!("waiting for gluegun-{plugin}"))?;
+        let stderr = stderr
+            .join()
+            .map_err(|_| anyhow::anyhow!("forwarding the stderr of gluegun-{plugin} panicked"))?
+            .with_context(|| format!("reading the stderr of gluegun-{plugin}"))?;
+        output.stderr = stderr.into_bytes();
+        Ok(output)
     }
 
     fn default_plugin_command(
@@ -244,15 +626,94 @@ impl Builder {
 
 /// A simple Cli you can use for your own parser.
 #[derive(clap::Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+
+    #[command(flatten)]
+    selection: Selection,
+
+    /// Regenerate the crates even if their input has not changed since they were last generated.
+    #[arg(long)]
+    force: bool,
+
+    /// Show the commands run for each plugin and all of their output.
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Print a backtrace if a plugin panics.
+    #[arg(long)]
+    backtrace: bool,
+}
+
+/// Arguments selecting the packages to process and the plugins to apply to them.
+#[derive(clap::Args)]
+struct Selection {
     #[command(flatten)]
     manifest: clap_cargo::Manifest,
 
     #[command(flatten)]
     workspace: clap_cargo::Workspace,
 
+    #[command(flatten)]
+    features: clap_cargo::Features,
+
     /// Specify a list of plugins to use.
     plugins: Vec<String>,
+
+    /// Run cargo without accessing the network, here and in the plugins
+    /// (which then report missing helper commands rather than installing them).
+    #[arg(long)]
+    offline: bool,
+
+    /// Run cargo with `--locked`, here and in the plugins.
+    #[arg(long)]
+    locked: bool,
+}
+
+impl Selection {
+    fn cargo_options(&self) -> CargoOptions {
+        CargoOptions::new().with_offline(self.offline).with_locked(self.locked)
+    }
+}
+
+#[derive(clap::Subcommand)]
+enum CliCommand {
+    /// Create a new gluegun helper crate (a plugin for generating bindings to some language).
+    InitPlugin(init_plugin::InitPluginArgs),
+
+    /// Report everything that keeps the plugins from generating bindings for the selected packages, without generating anything.
+    Lint(lint::LintArgs),
+
+    /// Check that the tools the plugins require to build the bindings of the selected packages (e.g., `javac`) are installed.
+    Doctor(doctor::DoctorArgs),
+
+    /// Remove the crates generated for the selected packages (and those of packages no longer in the workspace).
+    Clean(clean::CleanArgs),
+}
+
+/// What is given to a plugin for a package (see [`Builder::plugin_input`][]).
+struct PluginInput {
+    /// The merged gluegun configuration of the package.
+    gluegun_metadata: serde_json::Value,
+
+    /// The configuration of the plugin.
+    plugin_metadata: serde_json::Value,
+
+    /// The version of the plugin's helper, if it could be asked for it (see [`Builder::plugin_version`][]).
+    plugin_version: Option<String>,
+
+    /// The directory that the paths in `idl` are relative to.
+    workspace_root: Utf8PathBuf,
+
+    idl: gluegun_idl::Idl,
+
+    /// Errors of the definitions left out of `idl`, if parsed with error recovery.
+    parse_errors: Vec<gluegun_idl::Error>,
+
+    crate_name: String,
+    crate_path: Utf8PathBuf,
 }
 
 fn dest_crate_name_and_path(
@@ -286,6 +747,58 @@ fn dest_crate_name_and_path(
     Ok((crate_name, crate_path))
 }
 
+/// Hash of the input given to a helper (the serialized IDL and metadata, the contents of the extra files
+/// and of the custom code, and the destination crate) and of the versions of cargo-gluegun and of the helper, as hex digits.
+fn input_hash(
+    idl: &str,
+    metadata: &str,
+    plugin_version: Option<&str>,
+    extra_files: &ExtraFiles,
+    custom_src: &CustomSrc,
+    crate_name: &str,
+    crate_path: &Utf8PathBuf,
+) -> anyhow::Result<String> {
+    let mut hasher = DefaultHasher::new();
+    (env!("CARGO_PKG_VERSION"), plugin_version, idl, metadata, crate_name, crate_path).hash(&mut hasher);
+    for extra_file in extra_files.iter().chain(custom_src.iter()) {
+        let contents = std::fs::read(extra_file.source())
+            .with_context(|| format!("reading `{}`", extra_file.source().display()))?;
+        (extra_file.path(), contents).hash(&mut hasher);
+    }
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+/// Returns the workspace members that `package` depends on (and that have a library target),
+/// along with the name used to refer to them in Rust code and the path to their library source.
+fn workspace_dependencies<'m>(
+    metadata: &'m cargo_metadata::Metadata,
+    package: &cargo_metadata::Package,
+) -> Vec<(String, &'m cargo_metadata::Package, &'m Utf8PathBuf)> {
+    let workspace_packages = metadata.workspace_packages();
+    package
+        .dependencies
+        .iter()
+        .filter(|dependency| dependency.kind == cargo_metadata::DependencyKind::Normal)
+        .filter(|dependency| dependency.path.is_some())
+        .filter_map(|dependency| {
+            let dependency_package = workspace_packages
+                .iter()
+                .find(|p| p.name == dependency.name)?;
+            let lib_target = dependency_package.targets.iter().find(|t| t.is_lib())?;
+            let crate_name = dependency.rename.as_ref().unwrap_or(&dependency.name).replace('-', "_");
+            Some((crate_name, *dependency_package, &lib_target.src_path))
+        })
+        .collect()
+}
+
+/// Returns the features of `package` enabled in the resolved dependency graph,
+/// or `None` if `metadata` was computed without resolving dependencies.
+fn resolved_features(metadata: &cargo_metadata::Metadata, package: &cargo_metadata::Package) -> Option<Vec<String>> {
+    let resolve = metadata.resolve.as_ref()?;
+    let node = resolve.nodes.iter().find(|node| node.id == package.id)?;
+    Some(node.features.iter().map(|feature| feature.to_string()).collect())
+}
+
 /// Merge metadata from workspace/package
 fn merge_metadata(
     workspace_metadata: Option<&serde_json::Value>,
@@ -0,0 +1,79 @@
+//! Implementation of `cargo gluegun lint`, which reports what keeps plugins from generating bindings.
+
+use anyhow::Context;
+
+use crate::{Builder, PluginInput, Selection};
+
+/// Arguments to `cargo gluegun lint`.
+#[derive(clap::Args)]
+pub(crate) struct LintArgs {
+    #[command(flatten)]
+    pub(crate) selection: Selection,
+
+    /// Fail if any problem is found (e.g., to keep a crate free of them in CI).
+    #[arg(long)]
+    deny_warnings: bool,
+}
+
+/// A problem found in a package, along with the plugins it keeps from generating bindings.
+struct Problem {
+    package: String,
+    message: String,
+    plugins: Vec<String>,
+}
+
+/// Parse each selected package, recovering from errors, and ask each plugin what keeps it from generating
+/// bindings for what could be parsed. Problems are reported as warnings, each once for all plugins it concerns.
+pub(crate) fn lint(builder: &Builder, args: &LintArgs) -> anyhow::Result<()> {
+    let mut problems: Vec<Problem> = vec![];
+    builder.for_each_plugin(&args.selection, |plugin, metadata, package, config| {
+        let input = builder.plugin_input(plugin, metadata, package, config, true)?;
+        let mut messages: Vec<String> = input.parse_errors.iter().map(|error| error.to_string()).collect();
+        messages.extend(
+            lint_plugin(builder, plugin, &input).with_context(|| format!("linting with plugin `{plugin}`"))?,
+        );
+
+        for message in messages {
+            match problems.iter_mut().find(|p| p.package == package.name && p.message == message) {
+                Some(problem) => {
+                    if !problem.plugins.iter().any(|p| p == plugin) {
+                        problem.plugins.push(plugin.to_string());
+                    }
+                }
+                None => problems.push(Problem {
+                    package: package.name.to_string(),
+                    message,
+                    plugins: vec![plugin.to_string()],
+                }),
+            }
+        }
+        Ok(())
+    })?;
+
+    for Problem { package, message, plugins } in &problems {
+        let plugins: Vec<String> = plugins.iter().map(|plugin| format!("`{plugin}`")).collect();
+        eprintln!("warning: {package}: {message}");
+        eprintln!("    blocks: {}", plugins.join(", "));
+    }
+
+    let count = match problems.len() {
+        1 => "1 problem".to_string(),
+        n => format!("{n} problems"),
+    };
+    if args.deny_warnings && !problems.is_empty() {
+        anyhow::bail!("found {count}");
+    }
+    eprintln!("found {count}");
+    Ok(())
+}
+
+/// The problems that `plugin` reports for `input`. Helpers that cannot be asked for them
+/// (e.g., built against an older gluegun-core) are skipped with a warning.
+fn lint_plugin(builder: &Builder, plugin: &str, input: &PluginInput) -> anyhow::Result<Vec<String>> {
+    let output = builder.execute_plugin(plugin, input, true)?;
+    if !output.status.success() {
+        eprintln!("warning: gluegun-{plugin} cannot report problems, so only those found by the parser are reported for it");
+        return Ok(vec![]);
+    }
+    serde_json::from_slice(&output.stdout).with_context(|| format!("parsing the problems reported by gluegun-{plugin}"))
+}
@@ -0,0 +1,184 @@
+//! Recording the crates generated in a workspace (see [`STATE_PATH`][]), so that later runs can tell which
+//! are stale and `cargo gluegun clean` can remove them.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use anyhow::Context;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::PluginInput;
+
+/// Path of the file, relative to the workspace root, recording what was generated and by which versions.
+const STATE_PATH: &str = ".gluegun/state.json";
+
+/// Contents of [`STATE_PATH`][].
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct GenerationState {
+    /// Version of cargo-gluegun that last generated crates.
+    generator_version: Option<String>,
+
+    /// The generated crates, by path relative to the workspace root
+    /// (or absolute, for crates generated outside of it).
+    crates: BTreeMap<Utf8PathBuf, GeneratedCrate>,
+}
+
+/// What a crate was generated from (see [`GenerationState`][]).
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct GeneratedCrate {
+    /// The package that the crate was generated for.
+    pub(crate) package: String,
+
+    pub(crate) plugin: String,
+
+    /// Version of the plugin's helper, if it could be asked for it (see [`gluegun_core::cli::VERSION_FLAG`][]).
+    plugin_version: Option<String>,
+
+    crate_name: String,
+
+    /// Hash of the plugin's configuration, as hex digits.
+    metadata_hash: String,
+
+    /// Hash of the IDL of the package, as hex digits.
+    idl_hash: String,
+}
+
+impl GenerationState {
+    /// Load the state recorded in `workspace_root`. A missing or unreadable file is taken as nothing generated yet.
+    pub(crate) fn load(workspace_root: &Utf8Path) -> Self {
+        std::fs::read(workspace_root.join(STATE_PATH))
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Warn if the crates were last generated by another version of cargo-gluegun: those not regenerated
+    /// this time (e.g., of packages not selected) may not match those that are.
+    pub(crate) fn warn_if_generator_changed(&self) {
+        let current = env!("CARGO_PKG_VERSION");
+        if let Some(previous) = self.generator_version.as_deref().filter(|version| *version != current) {
+            eprintln!(
+                "warning: the crates of this workspace were last generated by cargo-gluegun {previous}, not {current}; \
+                 regenerate them all (e.g., with `--workspace`) to keep them consistent"
+            );
+        }
+    }
+
+    /// Record that `plugin` generated the crate of `input` for `package`, warning if the version of its helper changed.
+    pub(crate) fn record(
+        &mut self,
+        workspace_root: &Utf8Path,
+        package: &str,
+        plugin: &str,
+        input: &PluginInput,
+    ) -> anyhow::Result<()> {
+        let path = relative_path(workspace_root, &input.crate_path);
+        let generated = GeneratedCrate {
+            package: package.to_string(),
+            plugin: plugin.to_string(),
+            plugin_version: input.plugin_version.clone(),
+            crate_name: input.crate_name.clone(),
+            metadata_hash: hash_hex(&serde_json::to_string(&input.plugin_metadata)?),
+            idl_hash: hash_hex(&serde_json::to_string(&input.idl)?),
+        };
+        if let Some(previous) = self.crates.get(&path) {
+            if let (Some(previous), Some(current)) = (&previous.plugin_version, &generated.plugin_version) {
+                if previous != current {
+                    eprintln!("warning: `{path}` was generated by gluegun-{plugin} {previous}, now by {current}");
+                }
+            }
+        }
+        self.generator_version = Some(env!("CARGO_PKG_VERSION").to_string());
+        self.crates.insert(path, generated);
+        Ok(())
+    }
+
+    /// Warn about the recorded crates that the last run made stale: those generated for packages that are
+    /// no longer members of the workspace, for the packages and plugins of `generated` (which the last run generated)
+    /// but at another path, and, if `configured_plugins` (i.e., the plugins were not given on the command line),
+    /// by plugins that are no longer configured for the packages of `generated`.
+    pub(crate) fn warn_stale(
+        &self,
+        metadata: &cargo_metadata::Metadata,
+        generated: &[(String, String, Utf8PathBuf)],
+        configured_plugins: bool,
+    ) {
+        let members: BTreeSet<&str> = metadata.workspace_packages().iter().map(|p| p.name.as_str()).collect();
+        for (path, recorded) in &self.crates {
+            let reason = if !members.contains(recorded.package.as_str()) {
+                format!("`{}` is no longer a member of the workspace", recorded.package)
+            } else if generated.iter().any(|(package, plugin, crate_path)| {
+                *package == recorded.package
+                    && *plugin == recorded.plugin
+                    && relative_path(&metadata.workspace_root, crate_path) != *path
+            }) {
+                format!("gluegun-{} now generates the crate of `{}` elsewhere", recorded.plugin, recorded.package)
+            } else if configured_plugins
+                && generated.iter().any(|(package, _, _)| *package == recorded.package)
+                && !generated
+                    .iter()
+                    .any(|(package, plugin, _)| *package == recorded.package && *plugin == recorded.plugin)
+            {
+                format!("`{}` is no longer configured for `{}`", recorded.plugin, recorded.package)
+            } else {
+                continue;
+            };
+            eprintln!("warning: `{path}` is stale: {reason}; run `cargo gluegun clean` to remove it");
+        }
+    }
+
+    /// Remove the crates for which `select` returns true from the record and return them, by path relative to the workspace root.
+    pub(crate) fn remove_crates(
+        &mut self,
+        mut select: impl FnMut(&GeneratedCrate) -> bool,
+    ) -> BTreeMap<Utf8PathBuf, GeneratedCrate> {
+        let (removed, kept) = std::mem::take(&mut self.crates)
+            .into_iter()
+            .partition(|(_, generated)| select(generated));
+        self.crates = kept;
+        removed
+    }
+
+    /// Whether crates generated by `plugin` remain recorded.
+    pub(crate) fn has_plugin(&self, plugin: &str) -> bool {
+        self.crates.values().any(|generated| generated.plugin == plugin)
+    }
+
+    /// Write the state to `workspace_root`, leaving out the crates that no longer exist.
+    /// If none remain, the file is removed instead (along with its directory, if that is left empty).
+    pub(crate) fn save(mut self, workspace_root: &Utf8Path) -> anyhow::Result<()> {
+        self.crates.retain(|path, _| workspace_root.join(path).exists());
+
+        let state_path = workspace_root.join(STATE_PATH);
+        if self.crates.is_empty() {
+            if state_path.exists() {
+                std::fs::remove_file(&state_path).with_context(|| format!("removing `{state_path}`"))?;
+            }
+            if let Some(dir_path) = state_path.parent() {
+                // Fails (which is fine) if the user put other files there.
+                let _ = std::fs::remove_dir(dir_path);
+            }
+            return Ok(());
+        }
+
+        if let Some(dir_path) = state_path.parent() {
+            std::fs::create_dir_all(dir_path).with_context(|| format!("creating directory at `{dir_path}`"))?;
+        }
+        let json = serde_json::to_vec_pretty(&self).expect("generation state can be serialized");
+        std::fs::write(&state_path, json).with_context(|| format!("writing to file at `{state_path}`"))
+    }
+}
+
+/// `path` relative to `workspace_root`, or as it is if it is outside of it.
+fn relative_path(workspace_root: &Utf8Path, path: &Utf8Path) -> Utf8PathBuf {
+    path.strip_prefix(workspace_root).unwrap_or(path).to_path_buf()
+}
+
+fn hash_hex(data: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
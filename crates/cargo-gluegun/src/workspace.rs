@@ -0,0 +1,168 @@
+//! Generating the files that helpers contribute to the workspace as a whole (see [`gluegun_core::cli::WORKSPACE_FLAG`][]).
+
+use std::{
+    collections::BTreeMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    io::Write,
+    path::PathBuf,
+    process::Stdio,
+};
+
+use anyhow::Context;
+use cargo_metadata::camino::Utf8Path;
+use gluegun_core::{
+    cli::{WorkspaceCrate, WORKSPACE_FLAG},
+    codegen::WorkspaceFiles,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::GluegunConfig, dest_crate_name_and_path, failure, failure::HelperFailure, Builder};
+
+/// Name of the file at the workspace root recording the files written for each plugin,
+/// so that they can be replaced (or removed) the next time, unlike files that the user wrote.
+const WORKSPACE_FILES_NAME: &str = ".gluegun-workspace-files.json";
+
+/// Contents of [`WORKSPACE_FILES_NAME`][].
+#[derive(Default, Serialize, Deserialize)]
+struct WrittenFiles {
+    /// Hash of the contents of each file, by path relative to the workspace root, by plugin.
+    plugins: BTreeMap<String, BTreeMap<PathBuf, u64>>,
+}
+
+impl Builder {
+    /// Once `plugin` generated the crates of the selected packages, ask its helper for the files shared
+    /// by the crates it generated in the workspace and write them.
+    /// Helpers that do not know [`WORKSPACE_FLAG`][] (i.e., built against an older gluegun-core) are skipped.
+    pub(crate) fn generate_workspace_files(&self, plugin: &str, metadata: &cargo_metadata::Metadata) -> anyhow::Result<()> {
+        let config = GluegunConfig::load_workspace(metadata)
+            .context("loading the gluegun configuration of the workspace")?;
+        let gluegun_metadata = config.merged()?;
+        let plugin_metadata = config.plugin(plugin)?;
+
+        // All the crates generated for members of the workspace, including those not selected this time,
+        // so that the files do not depend on the packages selected.
+        let mut crates = vec![];
+        for package in metadata.workspace_packages() {
+            let package_metadata = GluegunConfig::load(metadata, package)?.merged()?;
+            let (crate_name, crate_path) = dest_crate_name_and_path(plugin, &package_metadata, package)?;
+            if crate_path.join("Cargo.toml").is_file() {
+                crates.push(WorkspaceCrate::new(&package.name, crate_name, crate_path));
+            }
+        }
+        crates.sort_by(|a, b| a.package_name().cmp(b.package_name()));
+
+        let mut plugin_command = (self.plugin_command)(&gluegun_metadata, plugin).context("creating plugin command")?;
+        plugin_command
+            .current_dir(&self.current_directory)
+            .arg(format!("gg-{plugin}"))
+            .arg(WORKSPACE_FLAG)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if self.backtrace {
+            plugin_command.env("RUST_BACKTRACE", "1");
+        }
+        if self.verbose {
+            eprintln!("{plugin_command:?}");
+        }
+        let mut child = plugin_command
+            .spawn()
+            .with_context(|| format!("spawning gluegun-{plugin}"))?;
+
+        // This has to be kept in sync with the definition from `gluegun_core::cli`.
+        let Some(mut stdin) = child.stdin.take() else {
+            anyhow::bail!("failed to take stdin");
+        };
+        let input = serde_json::json!({
+            "workspace_root": metadata.workspace_root,
+            "metadata": plugin_metadata,
+            "crates": crates,
+        });
+        writeln!(stdin, "{input}").with_context(|| format!("writing data to gluegun-{plugin}"))?;
+        drop(stdin);
+
+        // The stderr is only forwarded once the helper exits, so that nothing is shown for helpers that do not know the flag.
+        let output = child
+            .wait_with_output()
+            .with_context(|| format!("waiting for gluegun-{plugin}"))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let failure = HelperFailure::new(plugin, "(workspace)", output.status, &stderr);
+            if !failure.started() {
+                if self.verbose {
+                    eprintln!("{stderr}");
+                }
+                return Ok(());
+            }
+            failure::forward_stderr(&output.stderr[..], self.verbose)?;
+            return Err(failure.into());
+        }
+        failure::forward_stderr(&output.stderr[..], self.verbose)?;
+
+        let files: WorkspaceFiles = serde_json::from_slice(&output.stdout)
+            .with_context(|| format!("parsing the workspace files of gluegun-{plugin}"))?;
+        write_workspace_files(&metadata.workspace_root, plugin, &files)
+    }
+}
+
+/// Write the `files` of `plugin` in `workspace_root`, skipping those whose contents on disk are unchanged,
+/// and remove those written the previous time that are no longer among them (see [`WORKSPACE_FILES_NAME`][]).
+pub(crate) fn write_workspace_files(workspace_root: &Utf8Path, plugin: &str, files: &WorkspaceFiles) -> anyhow::Result<()> {
+    let record_path = workspace_root.join(WORKSPACE_FILES_NAME);
+    let mut record: WrittenFiles = std::fs::read(&record_path)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .unwrap_or_default();
+    let previous = record.plugins.remove(plugin).unwrap_or_default();
+
+    let mut written = BTreeMap::new();
+    for (path, contents) in files.iter() {
+        let file_path = workspace_root.as_std_path().join(path);
+        let hash = content_hash(contents.as_bytes());
+        written.insert(path.to_path_buf(), hash);
+        match std::fs::read(&file_path) {
+            Ok(existing) if content_hash(&existing) == hash => continue,
+            Ok(_) if !previous.contains_key(path) => anyhow::bail!(
+                "`{}` was not generated by gluegun-{plugin}; remove it to let gluegun-{plugin} generate it",
+                file_path.display()
+            ),
+            _ => {}
+        }
+
+        eprintln!("writing to {file_path:?}");
+        if let Some(dir_path) = file_path.parent() {
+            std::fs::create_dir_all(dir_path)
+                .with_context(|| format!("creating directory at `{}`", dir_path.display()))?;
+        }
+        std::fs::write(&file_path, contents)
+            .with_context(|| format!("writing to file at `{}`", file_path.display()))?;
+    }
+
+    for path in previous.keys().filter(|path| !written.contains_key(*path)) {
+        let file_path = workspace_root.as_std_path().join(path);
+        eprintln!("removing {file_path:?}");
+        if let Err(error) = std::fs::remove_file(&file_path) {
+            if error.kind() != std::io::ErrorKind::NotFound {
+                return Err(error).with_context(|| format!("removing `{}`", file_path.display()));
+            }
+        }
+    }
+
+    if !written.is_empty() {
+        record.plugins.insert(plugin.to_string(), written);
+    }
+    if record.plugins.is_empty() {
+        if record_path.exists() {
+            std::fs::remove_file(&record_path).with_context(|| format!("removing `{record_path}`"))?;
+        }
+        return Ok(());
+    }
+    let json = serde_json::to_vec_pretty(&record).expect("written files can be serialized");
+    std::fs::write(&record_path, json).with_context(|| format!("writing to file at `{record_path}`"))
+}
+
+fn content_hash(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,131 @@
+use gluegun_core::idl::{
+    Function, Idl, IsAsync, Item, Method, MethodCategory, QualifiedName, RefdTy, SelfKind, Signature, Ty, TypeKind,
+};
+
+/// Number of elements in the sample collections.
+pub(crate) const COLLECTION_SIZE: usize = 100;
+
+/// Creates sample values of IDL types in one of the target languages.
+pub(crate) trait Sampler {
+    /// A sample value, e.g. an expression in the target language.
+    type Sample: Clone;
+
+    /// A sample value of type `ty`, derived from the integer expression `index`
+    /// (which is the loop variable when creating the elements of a collection).
+    /// `depth` is the nesting depth of collections, used to name their loop variables.
+    ///
+    /// Returns `None` if the target language cannot (or does not) create values of type `ty`,
+    /// in which case no benchmark is generated for the function.
+    fn sample(&self, ty: &Ty, index: &str, depth: usize) -> Option<Self::Sample>;
+
+    /// True if objects can be created with their constructor (and not only with static methods).
+    fn constructors(&self) -> bool;
+
+    /// True if methods taking `self` by value can be benchmarked.
+    fn consuming_methods(&self) -> bool;
+}
+
+/// A call to benchmark.
+pub(crate) enum Benchmark<'idl, S> {
+    /// Calls the function `qname` with `arguments`.
+    Function {
+        qname: &'idl QualifiedName,
+        function: &'idl Function,
+        arguments: Vec<S>,
+    },
+
+    /// Creates an object of the resource `qname` by calling `factory` with `factory_arguments`
+    /// (outside of the measured code), then calls `method` on it with `arguments`.
+    Method {
+        qname: &'idl QualifiedName,
+        factory: &'idl Method,
+        factory_arguments: Vec<S>,
+        method: &'idl Method,
+        arguments: Vec<S>,
+    },
+}
+
+impl<S> Benchmark<'_, S> {
+    /// Identifies the benchmark, e.g. `my_crate::greet` or `my_crate::Counter::add` for `sep = "::"`.
+    pub(crate) fn id(&self, sep: &str) -> String {
+        match self {
+            Benchmark::Function { qname, .. } => qname.to_string(sep),
+            Benchmark::Method { qname, method, .. } => format!("{}{sep}{}", qname.to_string(sep), method.name()),
+        }
+    }
+}
+
+/// Select the functions and methods of `idl` to benchmark: those that `sampler` can create arguments for.
+/// Async functions are skipped, as the time to call them does not include the time to run them.
+pub(crate) fn benchmarks<'idl, S: Sampler>(idl: &'idl Idl, sampler: &S) -> Vec<Benchmark<'idl, S::Sample>> {
+    let mut benchmarks = vec![];
+    for (qname, item) in idl.definitions() {
+        match item {
+            Item::Function(function) => {
+                if let Some(arguments) = arguments(sampler, function.signature()) {
+                    benchmarks.push(Benchmark::Function {
+                        qname,
+                        function,
+                        arguments,
+                    });
+                }
+            }
+            Item::Resource(resource) => {
+                let Some((factory, factory_arguments)) = resource
+                    .methods()
+                    .iter()
+                    .filter(|method| is_factory(sampler, qname, method))
+                    .find_map(|method| Some((method, arguments(sampler, method.signature())?)))
+                else {
+                    continue;
+                };
+                for method in resource.methods() {
+                    let Some(self_kind) = method.category().self_kind() else {
+                        continue;
+                    };
+                    match self_kind {
+                        SelfKind::ByRef | SelfKind::ByRefMut => {}
+                        SelfKind::ByValue if sampler.consuming_methods() => {}
+                        _ => continue,
+                    }
+                    if let Some(arguments) = arguments(sampler, method.signature()) {
+                        benchmarks.push(Benchmark::Method {
+                            qname,
+                            factory,
+                            factory_arguments: factory_arguments.clone(),
+                            method,
+                            arguments,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    benchmarks
+}
+
+/// Sample arguments for a call to a function with `signature`, if it can be benchmarked.
+fn arguments<S: Sampler>(sampler: &S, signature: &Signature) -> Option<Vec<S::Sample>> {
+    if let IsAsync::Yes = signature.is_async() {
+        return None;
+    }
+    signature
+        .inputs()
+        .iter()
+        .map(|input| sampler.sample(input.refd_ty().ty(), "1", 0))
+        .collect()
+}
+
+/// True if `method` creates an object of the resource `qname`: a constructor or a static method
+/// that returns the resource itself (not wrapped in a `Result`).
+fn is_factory<S: Sampler>(sampler: &S, qname: &QualifiedName, method: &Method) -> bool {
+    match method.category() {
+        MethodCategory::Constructor if sampler.constructors() => {}
+        MethodCategory::NamedConstructor | MethodCategory::StaticMethod => {}
+        _ => return false,
+    }
+    let output = method.signature().output_ty();
+    output.error_ty().is_none()
+        && matches!(output.main_ty(), RefdTy::Owned(_, ty) if matches!(ty.kind(), TypeKind::UserType { qname: returned, repr: _ } if returned == qname))
+}
@@ -0,0 +1,227 @@
+use std::path::PathBuf;
+
+use gluegun_core::{
+    codegen::{CodeWriter, DirBuilder},
+    idl::{Idl, QualifiedName, Scalar, Ty, TypeKind},
+};
+
+use crate::benchmarks::{self, Benchmark, Sampler, COLLECTION_SIZE};
+
+/// Generates `Benchmarks.java`, a JMH benchmark per function and method
+/// calling it through the Java bindings (from `cargo gluegun java`).
+pub(crate) struct JavaCodeGenerator<'idl> {
+    idl: &'idl Idl,
+}
+
+/// A Java expression and the type of the field it is stored in.
+#[derive(Clone)]
+struct JavaSample {
+    ty: String,
+    expr: String,
+}
+
+impl<'idl> JavaCodeGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl) -> Self {
+        Self { idl }
+    }
+
+    pub(crate) fn generate(self, mut dir: DirBuilder<'_>) -> anyhow::Result<()> {
+        let benchmarks = benchmarks::benchmarks(self.idl, &JavaSampler { idl: self.idl });
+
+        let package = QualifiedName::from(self.idl.crate_name()).camel_case().join("bench");
+        let mut path: PathBuf = package.names().iter().map(|name| name.text()).collect();
+        path.push("Benchmarks.java");
+        let mut file = dir.add_file(path)?;
+
+        write!(file, "package {};", package.dotted())?;
+        write!(file, "")?;
+        write!(file, "import java.util.concurrent.TimeUnit;")?;
+        write!(file, "import org.openjdk.jmh.annotations.*;")?;
+        write!(file, "")?;
+        write!(file, "@State(Scope.Thread)")?;
+        write!(file, "@BenchmarkMode(Mode.AverageTime)")?;
+        write!(file, "@OutputTimeUnit(TimeUnit.NANOSECONDS)")?;
+        write!(file, "public class Benchmarks {{")?;
+
+        // The arguments (and objects) are created once, outside of the measured code.
+        for benchmark in &benchmarks {
+            let id = benchmark.id("_");
+            if let Benchmark::Method { qname, .. } = benchmark {
+                write!(file, "private {} {id}_this;", class_name(qname))?;
+            }
+            for (index, argument) in arguments(benchmark).iter().enumerate() {
+                write!(file, "private {} {id}_arg{index};", argument.ty)?;
+            }
+        }
+
+        write!(file, "")?;
+        write!(file, "@Setup(Level.Trial)")?;
+        write!(file, "public void setup() {{")?;
+        for benchmark in &benchmarks {
+            let id = benchmark.id("_");
+            if let Benchmark::Method { qname, factory, factory_arguments, .. } = benchmark {
+                let factory_arguments: Vec<&str> = factory_arguments.iter().map(|argument| argument.expr.as_str()).collect();
+                write!(
+                    file,
+                    "{id}_this = {class}.{factory}({arguments});",
+                    class = class_name(qname),
+                    factory = factory.name(),
+                    arguments = factory_arguments.join(", "),
+                )?;
+            }
+            for (index, argument) in arguments(benchmark).iter().enumerate() {
+                write!(file, "{id}_arg{index} = {};", argument.expr)?;
+            }
+        }
+        write!(file, "}}")?;
+
+        for benchmark in &benchmarks {
+            self.generate_benchmark(&mut file, benchmark)?;
+        }
+
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// Generate a benchmark method, e.g.
+    ///
+    /// ```java
+    /// @Benchmark
+    /// public Object my_crate_greet() {
+    ///     return myCrate.Functions.greet(my_crate_greet_arg0);
+    /// }
+    /// ```
+    ///
+    /// Results are returned so that JMH consumes them and the JIT cannot eliminate the call.
+    fn generate_benchmark(&self, file: &mut CodeWriter<'_>, benchmark: &Benchmark<'_, JavaSample>) -> anyhow::Result<()> {
+        let id = benchmark.id("_");
+        let names: Vec<String> = (0..arguments(benchmark).len()).map(|index| format!("{id}_arg{index}")).collect();
+        let (output, call) = match benchmark {
+            Benchmark::Function { qname, function, .. } => (
+                function.signature().output_ty(),
+                format!(
+                    "{}.Functions.{}({})",
+                    qname.module_path().camel_case().dotted(),
+                    function.name(),
+                    names.join(", ")
+                ),
+            ),
+            Benchmark::Method { method, .. } => (
+                method.signature().output_ty(),
+                format!("{id}_this.{}({})", method.name(), names.join(", ")),
+            ),
+        };
+
+        write!(file, "")?;
+        write!(file, "@Benchmark")?;
+        if output.is_unit() {
+            write!(file, "public void {id}() {{")?;
+            write!(file, "{call};")?;
+        } else {
+            write!(file, "public Object {id}() {{")?;
+            write!(file, "return {call};")?;
+        }
+        write!(file, "}}")?;
+        Ok(())
+    }
+}
+
+fn arguments<'b>(benchmark: &'b Benchmark<'_, JavaSample>) -> &'b [JavaSample] {
+    match benchmark {
+        Benchmark::Function { arguments, .. } | Benchmark::Method { arguments, .. } => arguments,
+    }
+}
+
+/// The fully qualified name of the Java class for the type `qname`, e.g. `myCrate.Counter`.
+fn class_name(qname: &QualifiedName) -> String {
+    let (package, name) = qname.camel_case().split_module_path();
+    format!("{}.{}", package.dotted(), name.upper_camel_case())
+}
+
+/// Creates Java expressions for sample values.
+///
+/// Options (which can be mapped to `Optional` or not, depending on the configuration of the Java backend)
+/// and tuples are not supported.
+struct JavaSampler<'idl> {
+    idl: &'idl Idl,
+}
+
+impl JavaSampler<'_> {
+    /// Like [`Sampler::sample`][], with the type being the boxed type (e.g., `Integer` rather than `int`)
+    /// if `boxed` is true, as required for type arguments.
+    fn sample_value(&self, ty: &Ty, index: &str, depth: usize, boxed: bool) -> Option<JavaSample> {
+        let sample = |ty: &str, expr: String| Some(JavaSample { ty: ty.to_string(), expr });
+        let scalar = |primitive: &str, boxed_ty: &str| {
+            let ty = if boxed { boxed_ty } else { primitive };
+            sample(ty, format!("({primitive}) {index}"))
+        };
+        let range = format!("java.util.stream.IntStream.range(0, {COLLECTION_SIZE})");
+        let element_index = format!("i{depth}");
+        match ty.kind() {
+            TypeKind::Scalar(Scalar::Boolean) => {
+                sample(if boxed { "Boolean" } else { "boolean" }, format!("{index} % 2 == 0"))
+            }
+            TypeKind::Scalar(Scalar::Char) => sample(if boxed { "Integer" } else { "int" }, "(int) 'a'".to_string()),
+            TypeKind::Scalar(Scalar::I8 | Scalar::U8) => scalar("byte", "Byte"),
+            TypeKind::Scalar(Scalar::I16 | Scalar::U16) => scalar("short", "Short"),
+            TypeKind::Scalar(Scalar::I32 | Scalar::U32) => scalar("int", "Integer"),
+            TypeKind::Scalar(Scalar::I64 | Scalar::U64) => scalar("long", "Long"),
+            TypeKind::Scalar(Scalar::F32) => scalar("float", "Float"),
+            TypeKind::Scalar(Scalar::F64) => scalar("double", "Double"),
+            TypeKind::String { .. } | TypeKind::Path { .. } => sample("String", format!("\"item\" + {index}")),
+            TypeKind::Vec { element, .. } => {
+                let element = self.sample_value(element, &element_index, depth + 1, true)?;
+                sample(
+                    &format!("java.util.List<{}>", element.ty),
+                    format!("{range}.mapToObj({element_index} -> {}).toList()", element.expr),
+                )
+            }
+            TypeKind::Set { element, .. } => {
+                let element = self.sample_value(element, &element_index, depth + 1, true)?;
+                sample(
+                    &format!("java.util.Set<{}>", element.ty),
+                    format!(
+                        "{range}.mapToObj({element_index} -> {}).collect(java.util.stream.Collectors.toSet())",
+                        element.expr
+                    ),
+                )
+            }
+            TypeKind::Map { key, value, .. } => {
+                let key = self.sample_value(key, &element_index, depth + 1, true)?;
+                let value = self.sample_value(value, &element_index, depth + 1, true)?;
+                sample(
+                    &format!("java.util.Map<{}, {}>", key.ty, value.ty),
+                    format!(
+                        "{range}.mapToObj({element_index} -> java.util.Map.entry({}, {})).collect(java.util.stream.Collectors.toMap(java.util.Map.Entry::getKey, java.util.Map.Entry::getValue, (first, second) -> first))",
+                        key.expr, value.expr
+                    ),
+                )
+            }
+            TypeKind::UserType { qname, repr: _ } => {
+                let (_, newtype) = self.idl.newtype(ty)?;
+                let value = self.sample_value(newtype.ty(), index, depth, true)?;
+                let class = class_name(qname);
+                sample(&class, format!("new {class}({})", value.expr))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Sampler for JavaSampler<'_> {
+    type Sample = JavaSample;
+
+    fn sample(&self, ty: &Ty, index: &str, depth: usize) -> Option<JavaSample> {
+        self.sample_value(ty, index, depth, false)
+    }
+
+    /// Constructors are not yet supported by the Java backend.
+    fn constructors(&self) -> bool {
+        false
+    }
+
+    /// The objects are shared by all iterations of a benchmark, so they cannot be consumed.
+    fn consuming_methods(&self) -> bool {
+        false
+    }
+}
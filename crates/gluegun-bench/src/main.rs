@@ -0,0 +1,58 @@
+use anyhow::Context;
+use gluegun_core::{
+    cli::{GenerateCx, GlueGunHelper},
+    codegen::LibraryCrate,
+};
+
+mod benchmarks;
+mod java_gen;
+mod py_gen;
+mod rs_gen;
+
+pub fn main() -> anyhow::Result<()> {
+    gluegun_core::cli::run(GlueGunBench)
+}
+
+/// Generates micro-benchmarks of the calls across the bindings: a criterion benchmark calling the Rust code directly
+/// (the baseline), and pytest-benchmark and JMH benchmarks calling it through the Python and Java bindings.
+/// Each function (and each method of a resource that can be created) is called with synthetic arguments.
+#[derive(Clone)]
+struct GlueGunBench;
+
+impl GlueGunHelper for GlueGunBench {
+    type Metadata = ();
+
+    fn name(&self) -> String {
+        "bench".to_string()
+    }
+
+    fn generate(self, cx: &mut GenerateCx, _metadata: &(), output: &mut LibraryCrate) -> anyhow::Result<()> {
+        output.add_dependency("criterion").version("0.5");
+
+        rs_gen::RustCodeGenerator::new(cx.idl())
+            .generate(output)
+            .context("generating Rust benchmarks")?;
+
+        let python_dir = output.add_dir("python").context("adding `python` dir")?;
+        py_gen::PythonCodeGenerator::new(cx.idl())
+            .generate(python_dir)
+            .context("generating Python benchmarks")?;
+
+        let java_dir = output.add_dir("java_bench").context("adding `java_bench` dir")?;
+        java_gen::JavaCodeGenerator::new(cx.idl())
+            .generate(java_dir)
+            .context("generating Java benchmarks")?;
+
+        output.add_build_step("Run the benchmarks of the Rust functions themselves", "cargo run --release -- --bench");
+        output.add_build_step(
+            "Run the benchmarks of the Python bindings, with the module from `cargo gluegun py` installed",
+            "pytest python/test_benchmarks.py",
+        );
+        output.add_build_step(
+            "Run the benchmarks of the Java bindings: build `java_bench` with JMH against the classes from `cargo gluegun java`",
+            "java -cp <classpath> org.openjdk.jmh.Main",
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeSet;
+
+use gluegun_core::{
+    codegen::DirBuilder,
+    idl::{Idl, MethodCategory, QualifiedName, Scalar, Ty, TypeKind},
+};
+
+use crate::benchmarks::{self, Benchmark, Sampler, COLLECTION_SIZE};
+
+/// Generates `test_benchmarks.py`, a pytest-benchmark test per function and method
+/// calling it through the Python bindings (from `cargo gluegun py`).
+pub(crate) struct PythonCodeGenerator<'idl> {
+    idl: &'idl Idl,
+}
+
+impl<'idl> PythonCodeGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl) -> Self {
+        Self { idl }
+    }
+
+    pub(crate) fn generate(self, mut dir: DirBuilder<'_>) -> anyhow::Result<()> {
+        let benchmarks = benchmarks::benchmarks(self.idl, &PythonSampler { idl: self.idl });
+
+        let mut file = dir.add_file("test_benchmarks.py")?;
+
+        let imports: BTreeSet<(String, String)> = benchmarks
+            .iter()
+            .map(|benchmark| {
+                let qname = match benchmark {
+                    Benchmark::Function { qname, .. } | Benchmark::Method { qname, .. } => qname,
+                };
+                import(qname)
+            })
+            .collect();
+        for (module, name) in &imports {
+            write!(file, "from {module} import {name}")?;
+        }
+
+        for benchmark in &benchmarks {
+            write!(file, "")?;
+            write!(file, "")?;
+            write!(file, "def test_{}(benchmark):", benchmark.id("_"))?;
+            match benchmark {
+                Benchmark::Function { qname, arguments, .. } => {
+                    write!(file, "    benchmark({}{})", qname.tail_name(), leading_comma(arguments))?;
+                }
+                Benchmark::Method { qname, factory, factory_arguments, method, arguments } => {
+                    let class = qname.tail_name();
+                    let factory_call = match factory.category() {
+                        MethodCategory::Constructor => format!("{class}({})", factory_arguments.join(", ")),
+                        _ => format!("{class}.{}({})", factory.name(), factory_arguments.join(", ")),
+                    };
+                    if method.consumes_self() {
+                        // The object can only be used once, so create a new one for each round.
+                        let setup_arguments = [&[factory_call][..], arguments].concat().join(", ");
+                        write!(file, "    def setup():")?;
+                        write!(file, "        return ({setup_arguments},), {{}}")?;
+                        write!(file, "")?;
+                        write!(file, "    benchmark.pedantic({class}.{}, setup=setup, rounds=100)", method.name())?;
+                    } else if let MethodCategory::Getter(_) = method.category() {
+                        write!(file, "    this = {factory_call}")?;
+                        write!(file, "    benchmark(lambda: this.{})", method.name())?;
+                    } else {
+                        write!(file, "    this = {factory_call}")?;
+                        write!(file, "    benchmark(this.{}{})", method.name(), leading_comma(arguments))?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The module to import the function or class `qname` from, and its name.
+fn import(qname: &QualifiedName) -> (String, String) {
+    (qname.module_path().dotted(), qname.tail_name().to_string())
+}
+
+/// `, a, b` for `arguments` `[a, b]`, to be appended to the callable passed to `benchmark`.
+fn leading_comma(arguments: &[String]) -> String {
+    arguments.iter().map(|argument| format!(", {argument}")).collect()
+}
+
+/// Creates Python expressions for sample values.
+struct PythonSampler<'idl> {
+    idl: &'idl Idl,
+}
+
+impl Sampler for PythonSampler<'_> {
+    type Sample = String;
+
+    fn sample(&self, ty: &Ty, index: &str, depth: usize) -> Option<String> {
+        let element_index = format!("i{depth}");
+        let each = format!("for i{depth} in range({COLLECTION_SIZE})");
+        match ty.kind() {
+            TypeKind::Scalar(Scalar::Boolean) => Some(format!("{index} % 2 == 0")),
+            TypeKind::Scalar(Scalar::Char) => Some("\"a\"".to_string()),
+            TypeKind::Scalar(Scalar::F32 | Scalar::F64) => Some(format!("float({index})")),
+            TypeKind::Scalar(_) => Some(index.to_string()),
+            TypeKind::String { .. } | TypeKind::Path { .. } => Some(format!("f\"item{{{index}}}\"")),
+            TypeKind::Vec { element, .. } => Some(format!("[{} {each}]", self.sample(element, &element_index, depth + 1)?)),
+            TypeKind::Set { element, .. } => Some(format!("{{{} {each}}}", self.sample(element, &element_index, depth + 1)?)),
+            TypeKind::Map { key, value, .. } => Some(format!(
+                "{{{}: {} {each}}}",
+                self.sample(key, &element_index, depth + 1)?,
+                self.sample(value, &element_index, depth + 1)?,
+            )),
+            // `None` would not exercise the conversion of the value.
+            TypeKind::Option { element, .. } => self.sample(element, index, depth),
+            TypeKind::Tuple { elements, .. } => {
+                let elements = elements
+                    .iter()
+                    .map(|element| self.sample(element, index, depth))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(format!("({},)", elements.join(", ")))
+            }
+            // Newtypes are passed as the value of their field.
+            TypeKind::UserType { .. } => {
+                let (_, newtype) = self.idl.newtype(ty)?;
+                self.sample(newtype.ty(), index, depth)
+            }
+            _ => None,
+        }
+    }
+
+    fn constructors(&self) -> bool {
+        true
+    }
+
+    fn consuming_methods(&self) -> bool {
+        true
+    }
+}
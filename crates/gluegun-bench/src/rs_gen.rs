@@ -0,0 +1,277 @@
+use std::cell::RefCell;
+
+use gluegun_core::{
+    codegen::{CodeWriter, LibraryCrate},
+    idl::{
+        Idl, Item, MapSetRepr, Method, MethodCategory, QualifiedName, RefKind, RefdTy, Scalar, SelfKind, Signature, Ty,
+        TypeKind, UserTypeRepr,
+    },
+    visit::{self, Visitor},
+};
+
+use crate::benchmarks::{self, Benchmark, Sampler, COLLECTION_SIZE};
+
+/// Generates `src/main.rs`: a criterion benchmark per function and method, calling the Rust code directly.
+/// These are the baseline that the overhead of the foreign bindings is measured against.
+pub(crate) struct RustCodeGenerator<'idl> {
+    idl: &'idl Idl,
+}
+
+impl<'idl> RustCodeGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl) -> Self {
+        Self { idl }
+    }
+
+    pub(crate) fn generate(self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        // The library target has to be a `cdylib`, which the binary cannot link to,
+        // so the benchmarks live in `main.rs`.
+        let mut lib_rs = lib.add_file("src/lib.rs")?;
+        write!(lib_rs, "//! The benchmarks are in `main.rs`; run them with `cargo run --release -- --bench`.")?;
+        drop(lib_rs);
+
+        let mut main_rs = lib.add_file("src/main.rs")?;
+        write!(main_rs, "fn benchmarks(c: &mut criterion::Criterion) {{")?;
+        for benchmark in benchmarks::benchmarks(self.idl, &RustSampler::new(self.idl)) {
+            self.generate_benchmark(&mut main_rs, &benchmark)?;
+        }
+        write!(main_rs, "}}")?;
+        write!(main_rs, "")?;
+        write!(main_rs, "criterion::criterion_group!(benches, benchmarks);")?;
+        write!(main_rs, "criterion::criterion_main!(benches);")?;
+        Ok(())
+    }
+
+    /// Generate a benchmark that creates the arguments (and object) in the setup of each iteration,
+    /// so that only the call itself is measured, e.g.
+    ///
+    /// ```rust,ignore
+    /// c.bench_function("my_crate::greet", |b| {
+    ///     b.iter_batched(|| (format!("item{}", 1),), |(a0,)| my_crate::greet(&a0), criterion::BatchSize::SmallInput)
+    /// });
+    /// ```
+    fn generate_benchmark(&self, main_rs: &mut CodeWriter<'_>, benchmark: &Benchmark<'_, String>) -> anyhow::Result<()> {
+        let (setup, call) = match benchmark {
+            Benchmark::Function { qname, function, arguments } => {
+                let names = argument_names(arguments.len());
+                (
+                    tuple(arguments),
+                    format!("{}({})", qname.colon_colon(), call_arguments(function.signature(), &names)),
+                )
+            }
+            Benchmark::Method { qname, factory, factory_arguments, method, arguments } => {
+                let factory_call = format!(
+                    "{}::{}({})",
+                    qname.colon_colon(),
+                    factory.name(),
+                    call_arguments(factory.signature(), factory_arguments),
+                );
+                let names = argument_names(arguments.len());
+                let call = match method.category() {
+                    MethodCategory::Getter(field) => format!("&this.{field}"),
+                    _ => format!("this.{}({})", method.name(), call_arguments(method.signature(), &names)),
+                };
+                (tuple(&[&[factory_call], &arguments[..]].concat()), call)
+            }
+        };
+
+        // The closure must return an owned value: borrowed results could point into the arguments.
+        let output = match benchmark {
+            Benchmark::Function { function, .. } => function.signature().output_ty(),
+            Benchmark::Method { method, .. } => method.signature().output_ty(),
+        };
+        let call = if output.requires_copy_out() {
+            format!("std::borrow::ToOwned::to_owned({call})")
+        } else {
+            call
+        };
+
+        let pattern = match benchmark {
+            Benchmark::Function { arguments, .. } => pattern(None, arguments.len()),
+            Benchmark::Method { method, arguments, .. } => pattern(Some(method), arguments.len()),
+        };
+
+        write!(main_rs, "c.bench_function({:?}, |b| {{", benchmark.id("::"))?;
+        write!(main_rs, "b.iter_batched(|| {setup}, |{pattern}| {call}, criterion::BatchSize::SmallInput)")?;
+        write!(main_rs, "}});")?;
+        Ok(())
+    }
+}
+
+/// A tuple expression (or pattern) of `values`, e.g. `(1 as u32,)`.
+fn tuple(values: &[String]) -> String {
+    if values.is_empty() {
+        return "()".to_string();
+    }
+    format!("({},)", values.join(", "))
+}
+
+/// The names the arguments are bound to: `a0`, `a1`, ...
+fn argument_names(arguments: usize) -> Vec<String> {
+    (0..arguments).map(|index| format!("a{index}")).collect()
+}
+
+/// The pattern binding the tuple created by the setup: `(a0, a1,)`, or `(this, a0,)` for a method,
+/// with `mut this` if the method takes `&mut self`.
+fn pattern(method: Option<&Method>, arguments: usize) -> String {
+    let this = match method.map(Method::category) {
+        Some(MethodCategory::InstanceMethod(SelfKind::ByRefMut) | MethodCategory::BuilderMethod(SelfKind::ByRefMut)) => {
+            Some("mut this".to_string())
+        }
+        Some(_) => Some("this".to_string()),
+        None => None,
+    };
+    tuple(&this.into_iter().chain(argument_names(arguments)).collect::<Vec<_>>())
+}
+
+/// The arguments of a call to a function with `signature`, passing references where it expects them.
+/// `names` are the variables the arguments are bound to, or the expressions creating them.
+fn call_arguments(signature: &Signature, names: &[String]) -> String {
+    signature
+        .inputs()
+        .iter()
+        .zip(names)
+        .map(|(input, name)| match input.refd_ty() {
+            RefdTy::Ref(RefKind::AnonRef, _) => format!("&{name}"),
+            _ => name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Creates Rust expressions for sample values.
+///
+/// Values of recursive types are kept finite: within the sample of a user type, options and collections
+/// that could contain that type again are empty (e.g., a sample `Tree` has no `children`).
+struct RustSampler<'idl> {
+    idl: &'idl Idl,
+
+    /// The user types whose samples are being created.
+    enclosing: RefCell<Vec<QualifiedName>>,
+}
+
+impl<'idl> RustSampler<'idl> {
+    fn new(idl: &'idl Idl) -> Self {
+        Self { idl, enclosing: RefCell::new(vec![]) }
+    }
+
+    /// True if a value of `ty` may contain one of the user types whose samples are being created.
+    fn may_contain_enclosing(&self, ty: &Ty) -> bool {
+        struct FindUserType<'a>(&'a [QualifiedName], bool);
+
+        impl<'idl> Visitor<'idl> for FindUserType<'_> {
+            fn visit_ty(&mut self, ty: &'idl Ty) -> anyhow::Result<()> {
+                if let TypeKind::UserType { qname, repr: _ } = ty.kind() {
+                    self.1 |= self.0.contains(qname);
+                }
+                visit::walk_ty(self, ty)
+            }
+        }
+
+        let enclosing = self.enclosing.borrow();
+        let mut visitor = FindUserType(&enclosing, false);
+        visitor.visit_ty(ty).is_ok() && visitor.1
+    }
+
+    /// Sample the fields of the user type `qname` with `sample_fields`. Returns `None` if `qname`
+    /// is already being sampled, as a type that contains itself other than through an option or
+    /// a collection has no finite value.
+    fn sample_user_type(&self, qname: &QualifiedName, sample_fields: impl FnOnce() -> Option<String>) -> Option<String> {
+        if self.enclosing.borrow().contains(qname) {
+            return None;
+        }
+        self.enclosing.borrow_mut().push(qname.clone());
+        let sample = sample_fields();
+        self.enclosing.borrow_mut().pop();
+        sample
+    }
+}
+
+impl Sampler for RustSampler<'_> {
+    type Sample = String;
+
+    fn sample(&self, ty: &Ty, index: &str, depth: usize) -> Option<String> {
+        let element_index = format!("i{depth}");
+        let collect = |element: String, ty: &Ty| {
+            // The samples of collections ignore their index.
+            let binding = if element.contains(&element_index) { &element_index[..] } else { "_" };
+            format!(
+                "(0..{COLLECTION_SIZE}).map(|{binding}| {element}).collect::<{}>()",
+                ty.rust_owned_spelling()
+            )
+        };
+        match ty.kind() {
+            TypeKind::Scalar(Scalar::Boolean) => Some(format!("{index} % 2 == 0")),
+            TypeKind::Scalar(Scalar::Char) => Some("'a'".to_string()),
+            TypeKind::Scalar(scalar) => Some(format!("{index} as {scalar}")),
+            TypeKind::String { .. } => Some(format!("format!(\"item{{}}\", {index})")),
+            TypeKind::Path { .. } => Some(format!("std::path::PathBuf::from(format!(\"item{{}}\", {index}))")),
+            TypeKind::Set { repr: MapSetRepr::Index, .. } | TypeKind::Map { repr: MapSetRepr::Index, .. } => None,
+            TypeKind::Vec { .. } | TypeKind::Set { .. } | TypeKind::Map { .. } | TypeKind::Option { .. }
+                if self.may_contain_enclosing(ty) =>
+            {
+                Some(format!("<{}>::default()", ty.rust_owned_spelling()))
+            }
+            TypeKind::Vec { element, .. } => Some(collect(self.sample(element, &element_index, depth + 1)?, ty)),
+            TypeKind::Set { element, .. } => Some(collect(self.sample(element, &element_index, depth + 1)?, ty)),
+            TypeKind::Map { key, value, .. } => {
+                let key = self.sample(key, &element_index, depth + 1)?;
+                let value = self.sample(value, &element_index, depth + 1)?;
+                Some(collect(format!("({key}, {value})"), ty))
+            }
+            TypeKind::Option { element, .. } => Some(format!("Some({})", self.sample(element, index, depth)?)),
+            TypeKind::Tuple { elements, .. } => {
+                let elements = elements
+                    .iter()
+                    .map(|element| self.sample(element, index, depth))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(tuple(&elements))
+            }
+            TypeKind::UserType { qname, repr } => {
+                let sample = match self.idl.item(qname)? {
+                    Item::Record(record) => self.sample_user_type(qname, || {
+                        // Braces work for tuple structs too, e.g. `Point { 0: 1.0, 1: 2.0 }`.
+                        let fields = record
+                            .fields()
+                            .iter()
+                            .enumerate()
+                            .map(|(field_index, field)| {
+                                Some(format!("{}: {}", record.member(field_index), self.sample(field.ty(), index, depth)?))
+                            })
+                            .collect::<Option<Vec<_>>>()?;
+                        Some(format!("{} {{ {} }}", qname.colon_colon(), fields.join(", ")))
+                    })?,
+                    // There is no telling which values the `TryFrom` impl of a validated newtype accepts.
+                    Item::Newtype(newtype) if *newtype.validated() => return None,
+                    Item::Newtype(newtype) => self.sample_user_type(qname, || {
+                        Some(format!("{}({})", qname.colon_colon(), self.sample(newtype.ty(), index, depth)?))
+                    })?,
+                    Item::Enum(enum_) => format!("{}::{}", qname.colon_colon(), enum_.arms().first()?.name()),
+                    Item::Variant(variant) => self.sample_user_type(qname, || {
+                        // Braces work for all kinds of variants, e.g. `Shape::Rect { 0: 1, 1: 2 }`.
+                        let arm = variant.arms().first()?;
+                        let fields = arm
+                            .fields()
+                            .iter()
+                            .map(|field| Some(format!("{}: {}", field.name(), self.sample(field.ty(), index, depth)?)))
+                            .collect::<Option<Vec<_>>>()?;
+                        Some(format!("{}::{} {{ {} }}", qname.colon_colon(), arm.name(), fields.join(", ")))
+                    })?,
+                    _ => return None,
+                };
+                match repr {
+                    UserTypeRepr::Boxed => Some(format!("Box::new({sample})")),
+                    _ => Some(sample),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn constructors(&self) -> bool {
+        true
+    }
+
+    fn consuming_methods(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,47 @@
+use anyhow::Context;
+use gluegun_core::{
+    cli::{GenerateCx, GlueGunHelper},
+    codegen::LibraryCrate,
+};
+
+mod rs_gen;
+
+pub fn main() -> anyhow::Result<()> {
+    gluegun_core::cli::run(GlueGunCli)
+}
+
+/// Generates a command-line program with one subcommand per function,
+/// handy for debugging or scripting against a library's API.
+#[derive(Clone)]
+struct GlueGunCli;
+
+impl GlueGunHelper for GlueGunCli {
+    type Metadata = ();
+
+    fn name(&self) -> String {
+        "cli".to_string()
+    }
+
+    fn generate(self, cx: &mut GenerateCx, _metadata: &(), output: &mut LibraryCrate) -> anyhow::Result<()> {
+        // The generated crate is a binary, not a library.
+        output.set_cargo_new_command(|this| {
+            let mut cargo_command = std::process::Command::new("cargo");
+            cargo_command.arg("new");
+            cargo_command.arg("--bin");
+            cargo_command.arg(this.crate_path());
+            cargo_command.arg("--name");
+            cargo_command.arg(this.crate_name());
+            cargo_command
+        });
+
+        output.add_dependency("clap").version("4").feature("derive");
+        output.add_dependency("serde").version("1");
+        output.add_dependency("serde_json").version("1");
+
+        rs_gen::RustCodeGenerator::new(cx.idl())
+            .generate(output)
+            .context("generating Rust sources")?;
+
+        Ok(())
+    }
+}
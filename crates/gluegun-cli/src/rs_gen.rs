@@ -0,0 +1,264 @@
+use gluegun_core::{
+    codegen::{CodeWriter, LibraryCrate},
+    idl::{
+        Function, FunctionInput, Idl, Item, MapSetRepr, NetAddrRepr, OptionRepr, PathRepr, QualifiedName, RefdTy,
+        ResultRepr, Scalar, StringRepr, TupleRepr, Ty, TypeKind, VecRepr,
+    },
+    type_mapper::{self, TypeMapper},
+};
+
+/// Generates `src/main.rs`: a clap `Command` enum with one subcommand per function,
+/// whose arguments become `--flags`, and a `main` that invokes the function and prints its result as JSON.
+pub(crate) struct RustCodeGenerator<'idl> {
+    idl: &'idl Idl,
+}
+
+/// How the value of a flag is parsed.
+enum ArgParser {
+    /// Using clap's default parser for the type (e.g., `FromStr`). Also covers `Option<T>`
+    /// (an optional flag) and `Vec<T>` (a flag that can be repeated) of such types.
+    Default,
+
+    /// As JSON, e.g. `--counts '{"a": 1}'`. As with [`ArgParser::Default`][], an outer `Option`
+    /// or `Vec` is handled by clap.
+    Json,
+}
+
+impl<'idl> RustCodeGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl) -> Self {
+        Self { idl }
+    }
+
+    pub(crate) fn generate(mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        self.generate_main_rs(lib)
+    }
+
+    fn generate_main_rs(&mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        let mut functions = vec![];
+        for (qname, item) in self.idl.definitions() {
+            match item {
+                Item::Function(function) => functions.push((qname, function)),
+                _ => anyhow::bail!(
+                    "`{}`: only functions are currently supported by the cli backend",
+                    qname.colon_colon()
+                ),
+            }
+        }
+
+        let mut main_rs = lib.add_file("src/main.rs")?;
+
+        write!(main_rs, "use clap::Parser;")?;
+        write!(main_rs, "")?;
+
+        write!(main_rs, "#[derive(clap::Parser)]")?;
+        write!(main_rs, "#[command(name = {:?})]", self.idl.crate_name().to_string())?;
+        write!(main_rs, "enum Command {{")?;
+        for &(qname, function) in &functions {
+            main_rs.with_span(function.span(), |main_rs| self.generate_subcommand(main_rs, qname, function))?;
+        }
+        write!(main_rs, "}}")?;
+
+        write!(main_rs, "")?;
+        write!(main_rs, "#[allow(dead_code)]")?;
+        write!(
+            main_rs,
+            "fn parse_json<T: serde::de::DeserializeOwned>(text: &str) -> Result<T, serde_json::Error> {{"
+        )?;
+        write!(main_rs, "serde_json::from_str(text)")?;
+        write!(main_rs, "}}")?;
+
+        write!(main_rs, "")?;
+        write!(main_rs, "fn main() -> Result<(), Box<dyn std::error::Error>> {{")?;
+        write!(main_rs, "match Command::parse() {{")?;
+        for &(qname, function) in &functions {
+            main_rs.with_span(function.span(), |main_rs| self.generate_invocation(main_rs, qname, function))?;
+        }
+        write!(main_rs, "}}")?;
+        write!(main_rs, "Ok(())")?;
+        write!(main_rs, "}}")?;
+
+        Ok(())
+    }
+
+    /// Generate the variant of the `Command` enum for `function`, e.g.
+    ///
+    /// ```rust,ignore
+    /// #[command(name = "geometry-area")]
+    /// GeometryArea { #[arg(long)] width: u32, #[arg(long)] height: u32 },
+    /// ```
+    fn generate_subcommand(
+        &mut self,
+        main_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        function: &Function,
+    ) -> anyhow::Result<()> {
+        write!(main_rs, "#[command(name = {:?})]", subcommand_name(qname))?;
+        write!(main_rs, "{} {{", variant_name(qname))?;
+        for input in function.signature().inputs() {
+            let ty = input.refd_ty().ty();
+            match arg_parser(input)? {
+                ArgParser::Default => write!(main_rs, "#[arg(long)]")?,
+                ArgParser::Json => write!(main_rs, "#[arg(long, value_parser = parse_json::<{}>)]", leaf_ty(ty).rust_owned_spelling())?,
+            }
+            write!(main_rs, "{}: {},", input.name(), ty.rust_owned_spelling())?;
+        }
+        write!(main_rs, "}},")?;
+        Ok(())
+    }
+
+    /// Generate the match arm that invokes `function` and prints its result.
+    fn generate_invocation(
+        &mut self,
+        main_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        function: &Function,
+    ) -> anyhow::Result<()> {
+        let signature = function.signature();
+        let names = signature.inputs().iter().map(|input| input.name().to_string()).collect::<Vec<_>>();
+        write!(main_rs, "Command::{} {{ {} }} => {{", variant_name(qname), names.join(", "))?;
+
+        let output_ty = signature.output_ty();
+        let main_ty = output_ty.main_ty().ty();
+        JsonCheck.map_ty(main_ty)?;
+
+        // Nothing to print for `()`, so unit-returning functions are called as a statement.
+        let binding = if output_ty.is_unit() { "" } else { "let result = " };
+        write!(main_rs, "{binding}{}(", qname.colon_colon())?;
+        for input in signature.inputs() {
+            let name = input.name();
+            match input.refd_ty() {
+                RefdTy::Owned(..) => write!(main_rs, "{name},")?,
+                RefdTy::Ref(..) => write!(main_rs, "&{name},")?,
+            }
+        }
+        write!(main_rs, ")")?;
+        if output_ty.error_ty().is_some() {
+            // Errors are reported through their `Display` impl, so they need not be serializable.
+            write!(main_rs, ".map_err(|err| err.to_string())?")?;
+        }
+        write!(main_rs, ";")?;
+
+        if !output_ty.is_unit() {
+            write!(main_rs, "println!(\"{{}}\", serde_json::to_string_pretty(&result)?);")?;
+        }
+        write!(main_rs, "}}")?;
+        Ok(())
+    }
+}
+
+/// The name of the subcommand for the function `qname`, e.g., `geometry-area` for `my_crate::geometry::area`.
+fn subcommand_name(qname: &QualifiedName) -> String {
+    qname.names()[1..]
+        .iter()
+        .map(|name| name.text().replace('_', "-"))
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The name of the `Command` variant for the function `qname`, e.g., `MyCrateGeometryArea`.
+fn variant_name(qname: &QualifiedName) -> String {
+    qname.upper_camel_case().to_string("")
+}
+
+/// The type whose values are parsed from a single flag: clap handles an outer `Option` or `Vec` itself.
+fn leaf_ty(ty: &Ty) -> &Ty {
+    match ty.kind() {
+        TypeKind::Option { element, repr: OptionRepr::Option }
+        | TypeKind::Vec { element, repr: VecRepr::Vec | VecRepr::SliceRef | VecRepr::ImplIntoIterator } => element,
+        _ => ty,
+    }
+}
+
+fn arg_parser(input: &FunctionInput) -> anyhow::Result<ArgParser> {
+    let ty = input.refd_ty().ty();
+    JsonCheck.map_ty(ty)?;
+    let leaf = leaf_ty(ty);
+    match leaf.kind() {
+        TypeKind::Scalar(_) | TypeKind::String { .. } | TypeKind::Path { .. } | TypeKind::NetAddr { .. } => {
+            Ok(ArgParser::Default)
+        }
+
+        // clap gives special meaning to `Option<Vec<T>>` and friends, so don't go there.
+        TypeKind::Option { .. } | TypeKind::Vec { .. } if !std::ptr::eq(leaf, ty) => anyhow::bail!(
+            "{span}: unsupported type for `{name}`: {ty}",
+            span = input.span(),
+            name = input.name(),
+        ),
+
+        _ => Ok(ArgParser::Json),
+    }
+}
+
+/// Accepts the types that serde can convert to and from JSON out of the box.
+/// User types are rejected, as they need not implement `Serialize`/`Deserialize`.
+struct JsonCheck;
+
+impl TypeMapper for JsonCheck {
+    type Output = ();
+
+    fn map_map(&mut self, ty: &Ty, _key: (), _value: (), repr: &MapSetRepr) -> anyhow::Result<()> {
+        match repr {
+            MapSetRepr::Hash | MapSetRepr::BTree => Ok(()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_vec(&mut self, ty: &Ty, _element: (), repr: &VecRepr) -> anyhow::Result<()> {
+        match repr {
+            VecRepr::Vec | VecRepr::SliceRef | VecRepr::ImplIntoIterator => Ok(()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_set(&mut self, ty: &Ty, _element: (), repr: &MapSetRepr) -> anyhow::Result<()> {
+        match repr {
+            MapSetRepr::Hash | MapSetRepr::BTree => Ok(()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_path(&mut self, ty: &Ty, repr: &PathRepr) -> anyhow::Result<()> {
+        match repr {
+            PathRepr::PathBuf | PathRepr::PathRef => Ok(()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    /// The `std::net` types implement `Serialize` and `Deserialize` (as strings, in human-readable formats).
+    /// `uuid::Uuid` is rejected, as it only does with the `serde` feature of `uuid`.
+    fn map_net_addr(&mut self, _ty: &Ty, _repr: &NetAddrRepr) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn map_string(&mut self, ty: &Ty, repr: &StringRepr) -> anyhow::Result<()> {
+        match repr {
+            StringRepr::String | StringRepr::StrRef => Ok(()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_option(&mut self, ty: &Ty, _element: (), repr: &OptionRepr) -> anyhow::Result<()> {
+        match repr {
+            OptionRepr::Option => Ok(()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_result(&mut self, ty: &Ty, _ok: (), _err: (), repr: &ResultRepr) -> anyhow::Result<()> {
+        match repr {
+            ResultRepr::Result => Ok(()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_tuple(&mut self, ty: &Ty, _elements: Vec<()>, repr: &TupleRepr) -> anyhow::Result<()> {
+        match repr {
+            TupleRepr::Tuple(_) => Ok(()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_scalar(&mut self, _ty: &Ty, _scalar: &Scalar) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
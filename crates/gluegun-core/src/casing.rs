@@ -0,0 +1,62 @@
+//! Case conversions of Rust identifiers, used by [`Case`](crate::naming::Case).
+//!
+//! An identifier is split into words at underscores (and dashes) and at case changes.
+//! A run of capitals is a single word (an acronym), except for its last capital when a lowercase letter
+//! follows it, which starts the next word: `HTTPServer` is `HTTP` + `Server`.
+//! Digits belong to the word they follow: `Ipv4Addr` is `Ipv4` + `Addr`, and `to_utf8` is `to` + `utf8`.
+//!
+//! | Rust name    | [`camel_case`] | [`upper_camel_case`] | [`snake_case`] |
+//! | ------------ | -------------- | -------------------- | -------------- |
+//! | `HTTPServer` | `httpServer`   | `HttpServer`         | `http_server`  |
+//! | `Ipv4Addr`   | `ipv4Addr`     | `Ipv4Addr`           | `ipv4_addr`    |
+//! | `to_utf8`    | `toUtf8`       | `ToUtf8`             | `to_utf8`      |
+//! | `f0`         | `f0`           | `F0`                 | `f0`           |
+
+/// The words of `ident`, as they appear in it.
+pub fn words(ident: &str) -> Vec<&str> {
+    let mut words = vec![];
+    for segment in ident.split(['_', '-']).filter(|segment| !segment.is_empty()) {
+        let chars: Vec<(usize, char)> = segment.char_indices().collect();
+        let mut start = 0;
+        for (i, &(offset, c)) in chars.iter().enumerate().skip(1) {
+            let previous = chars[i - 1].1;
+            let next = chars.get(i + 1).map(|&(_, c)| c);
+            let starts_word = c.is_uppercase()
+                && (previous.is_lowercase()
+                    || previous.is_ascii_digit()
+                    || (previous.is_uppercase() && next.is_some_and(char::is_lowercase)));
+            if starts_word {
+                words.push(&segment[start..offset]);
+                start = offset;
+            }
+        }
+        words.push(&segment[start..]);
+    }
+    words
+}
+
+/// Convert `ident` to `camelCase`: acronyms are capitalized like any other word (`HTTPServer` becomes `httpServer`).
+pub fn camel_case(ident: &str) -> String {
+    let mut words = words(ident).into_iter();
+    let mut result = words.next().map(str::to_lowercase).unwrap_or_default();
+    words.for_each(|word| result.push_str(&capitalize(word)));
+    result
+}
+
+/// Convert `ident` to `UpperCamelCase` (`HTTPServer` becomes `HttpServer`).
+pub fn upper_camel_case(ident: &str) -> String {
+    words(ident).into_iter().map(capitalize).collect()
+}
+
+/// Convert `ident` to `snake_case` (`HTTPServer` becomes `http_server`).
+pub fn snake_case(ident: &str) -> String {
+    words(ident).into_iter().map(str::to_lowercase).collect::<Vec<_>>().join("_")
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
@@ -2,13 +2,45 @@
 //! some other language. Most GlueGun CLI crates can use the Clap structs defined
 //! in this file.
 
-use std::path::PathBuf;
+use std::{io::Read, path::PathBuf};
 
 use accessors_rs::Accessors;
 use anyhow::Context;
 use serde::{de::DeserializeOwned, Deserialize};
 
-use crate::{codegen::LibraryCrate, idl::Idl};
+use crate::{
+    codegen::{AddDependency, CargoOptions, LibraryCrate, RequiredTools, WorkspaceFiles},
+    idl::Idl,
+    naming::Naming,
+};
+
+mod custom_src;
+pub use custom_src::*;
+
+mod extra_files;
+pub use extra_files::*;
+
+mod filter;
+use filter::Filter;
+
+mod input_hash;
+use input_hash::InputHash;
+
+mod lint;
+
+mod metadata_schema;
+pub use metadata_schema::*;
+
+mod standalone;
+
+mod target_gates;
+pub use target_gates::*;
+
+mod type_overrides;
+pub use type_overrides::*;
+
+mod workspace;
+pub use workspace::*;
 
 /// Trait implemented by gluegun helper applications.
 /// Your `main` function should invoke [`run`][].
@@ -17,14 +49,66 @@ pub trait GlueGunHelper {
     /// The metadata type used by this helper.
     /// This metadata will be extracted from the `Cargo.toml``.
     /// You can use `serde_json::Value` if you would like to just capture free-form.
-    type Metadata: DeserializeOwned;
+    /// If the user provides no metadata, `Default::default()` is used.
+    ///
+    /// The `include` and `exclude` keys are reserved: they are interpreted by gluegun itself
+    /// to filter the definitions in the IDL and are not included in the metadata given to the helper.
+    /// So is `type-overrides`, which is available from [`GenerateCx::type_overrides`][],
+    /// `extra-files`, which is available from [`GenerateCx::extra_files`][],
+    /// `custom-src`, whose files are copied into the generated crate (see [`CustomSrc`][]),
+    /// `target-gates`, which is available from [`GenerateCx::target_gates`][],
+    /// `file-header`, which is prepended to the generated files (see [`LibraryCrate::set_file_header`][]),
+    /// `bindings-manifest` (see [`LibraryCrate::enable_bindings_manifest`][]),
+    /// `repr-c-records` (see [`LibraryCrate::enable_repr_c_records`][]),
+    /// `debug-bindings` (see [`LibraryCrate::enable_debug_bindings`][]),
+    /// and `pack-params` (see [`PACK_PARAMS_KEY`][]).
+    ///
+    /// The options the type accepts are described by [`DescribeMetadata`][],
+    /// which `cargo gluegun` uses to validate the user's metadata before invoking the helper.
+    type Metadata: DeserializeOwned + Default + DescribeMetadata;
 
     /// By default, we add the user's library as a dependency of the generated code.
     const INCLUDE_USER_LIB_DEPENDENCY: bool = true;
 
+    /// Whether the helper wraps the code it generates for gated definitions in `#[cfg]` (see [`GenerateCx::target_gates`][]).
+    /// If not, users cannot configure `target-gates` for it.
+    const SUPPORTS_TARGET_GATES: bool = false;
+
+    /// Whether the helper counts the wrappers of Rust objects it generates when [`LibraryCrate::debug_bindings_enabled`][].
+    /// If not, users cannot configure `debug-bindings` for it.
+    const SUPPORTS_DEBUG_BINDINGS: bool = false;
+
+    /// Whether the helper generates a form of the functions taking the record that packs their parameters,
+    /// for the records that [`Idl::pack_params`](`crate::idl::Idl::pack_params`) adds to the IDL (see [`PACK_PARAMS_KEY`][]).
+    /// If not, users cannot configure `pack-params` for it.
+    const SUPPORTS_PACK_PARAMS: bool = false;
+
     /// Returns the helper name that users provide to invoke this, e.g., for `gluegun-java`, returns `"java"`.
     fn name(&self) -> String;
 
+    /// The version printed by `gluegun-{name} --version`.
+    /// The default is the version of `gluegun-core`, which the helpers in the gluegun repository share.
+    fn version(&self) -> String {
+        env!("CARGO_PKG_VERSION").to_string()
+    }
+
+    /// How the helper converts Rust names into identifiers of the target language.
+    /// Before [`Self::generate`][] is invoked, gluegun checks that no two names become the same identifier
+    /// (see [`Naming::check`][]). The default uses names as they are, in the namespace of their module.
+    fn naming(&self, metadata: &Self::Metadata) -> Naming {
+        let _ = metadata;
+        Naming::new()
+    }
+
+    /// The target that the crates generated with `metadata` are always built for, if any (e.g., WebAssembly),
+    /// described by the configuration options that hold on it. Definitions whose `target-gates` are false on it
+    /// are left out of the IDL (see [`TargetGates`][]). The default is `None`: the crates are built for any target,
+    /// so gated definitions are kept.
+    fn target(&self, metadata: &Self::Metadata) -> Option<TargetCfg> {
+        let _ = metadata;
+        None
+    }
+
     /// Generate a helper crate `output` from the given `idl` and `metadata`
     /// 
     /// # Parameters
@@ -39,62 +123,470 @@ pub trait GlueGunHelper {
         metadata: &Self::Metadata,
         output: &mut LibraryCrate,
     ) -> anyhow::Result<()>;
+
+    /// Generate the files shared by the crates generated for the members of a workspace
+    /// (e.g., a build file aggregating them), which go in `output`.
+    /// `cargo gluegun` invokes it once the crates of all the selected packages are generated
+    /// (see [`WORKSPACE_FLAG`][]), with the metadata configured for the workspace as a whole.
+    /// The default contributes no files.
+    ///
+    /// # Parameters
+    ///
+    /// * `cx`, the workspace root and the crates generated in it
+    /// * `metadata`, metadata provided by the user for your plugin in `[workspace.metadata.gluegun]`
+    ///   or the `gluegun.toml` at the workspace root
+    /// * `output`, the [`WorkspaceFiles`][] to add files to, relative to the workspace root
+    fn generate_workspace(
+        &self,
+        cx: &WorkspaceCx,
+        metadata: &Self::Metadata,
+        output: &mut WorkspaceFiles,
+    ) -> anyhow::Result<()> {
+        let _ = (cx, metadata, output);
+        Ok(())
+    }
+
+    /// Declare the external tools (and environment variables) that the crates generated with `metadata` need
+    /// to build, e.g. `javac`, by adding them to `tools`. `cargo gluegun doctor` checks for them
+    /// (see [`DOCTOR_FLAG`][]) and suggests how to install those that are missing. The default requires none.
+    fn required_tools(&self, metadata: &Self::Metadata, tools: &mut RequiredTools) {
+        let _ = (metadata, tools);
+    }
 }
 
 /// The "main" function for a gluegun helper. Defines standard argument parsing.
+///
+/// When run by hand rather than by `cargo gluegun`, the helper answers `--help`, `--version`,
+/// and `--print-metadata-schema` (which prints its [`MetadataSchema`][] as JSON).
+///
+/// The helper is cloned when `cargo gluegun lint` asks it to check the definitions one at a time.
 pub fn run<G>(helper: G) -> anyhow::Result<()>
 where
-    G: GlueGunHelper,
+    G: GlueGunHelper + Clone,
 {
     // cargo-gluegun will invoke us with `gg` as argument and a JSON doc on stdin.
     let mut args = std::env::args();
     let Some(_arg0) = args.next() else {
         anyhow::bail!("expected to give given an argument");
     };
-    let Some(arg1) = args.next() else {
-        anyhow::bail!("expected to give given an argument");
-    };
-    if arg1 != format!("gg-{}", helper.name()) {
-        anyhow::bail!("expected to be invoked by `cargo gluegun`");
+    match args.next() {
+        Some(arg1) if arg1 == format!("gg-{}", helper.name()) => {}
+        _ => return standalone::run_standalone(&helper),
+    }
+
+    // With `--metadata-schema`, cargo-gluegun asks for the options we accept (as JSON on stdout).
+    // With `--lint`, it asks what keeps us from generating the crate (see `lint::lint`).
+    // With `--workspace`, it asks for the files shared by the crates of the workspace (see `run_workspace`).
+    // With `--doctor`, it asks for the tools we require and whether they are installed (see `run_doctor`).
+    // With `--version`, it asks for our version, to record what generated the crate.
+    let mut lint = false;
+    if let Some(arg2) = args.next() {
+        if arg2 == METADATA_SCHEMA_FLAG {
+            println!("{}", serde_json::to_string(&G::Metadata::describe())?);
+            return Ok(());
+        } else if arg2 == VERSION_FLAG {
+            println!("{}", helper.version());
+            return Ok(());
+        } else if arg2 == WORKSPACE_FLAG {
+            return run_workspace(helper);
+        } else if arg2 == DOCTOR_FLAG {
+            return run_doctor(helper);
+        } else if arg2 == LINT_FLAG {
+            lint = true;
+        } else {
+            anyhow::bail!("unexpected argument `{arg2}`");
+        }
+    }
+
+    // Read the input from stdin. If cargo-gluegun sent a hash of it and the destination crate
+    // was last generated from the same input, there is nothing to do, unless the run is forced.
+    // When linting, the destination crate is left alone.
+    enter_phase("reading the input");
+    let mut stdin = String::new();
+    std::io::stdin().read_to_string(&mut stdin)?;
+    let header: GlueGunInputHeader = serde_json::from_str(&stdin)?;
+    let input_hash = header.idl_hash.map(|idl_hash| InputHash::new(&helper.name(), &idl_hash));
+    if let Some(input_hash) = &input_hash {
+        if !header.force && input_hash.is_recorded_in(&header.dest_crate.path) {
+            eprintln!("`{}` is up to date", header.dest_crate.crate_name);
+            return Ok(());
+        }
+    }
+    if !lint {
+        InputHash::forget(&header.dest_crate.path)?;
+    }
+
+    // Parse the input
+    let input: GlueGunInput = serde_json::from_str(&stdin)?;
+
+    // Extract the `include`/`exclude` filters and the type overrides and then parse the remaining metadata.
+    enter_phase("parsing the metadata");
+    let HelperMetadata {
+        filter,
+        type_overrides,
+        target_gates,
+        file_header,
+        bindings_manifest,
+        repr_c_records,
+        debug_bindings,
+        pack_params,
+        metadata,
+    } = HelperMetadata::<G::Metadata>::parse(input.metadata)?;
+    if !G::SUPPORTS_TARGET_GATES && !target_gates.is_empty() {
+        anyhow::bail!("the `{}` plugin does not support `{}`", helper.name(), TargetGates::KEY);
+    }
+    if !G::SUPPORTS_DEBUG_BINDINGS && debug_bindings {
+        anyhow::bail!("the `{}` plugin does not support `{DEBUG_BINDINGS_KEY}`", helper.name());
+    }
+    if !G::SUPPORTS_PACK_PARAMS && pack_params.is_some() {
+        anyhow::bail!("the `{}` plugin does not support `{PACK_PARAMS_KEY}`", helper.name());
+    }
+
+    enter_phase("filtering definitions");
+    let mut idl = input.idl;
+    filter.apply(&mut idl).context("applying `include`/`exclude` filters")?;
+    if let Some(target) = helper.target(&metadata) {
+        target_gates.apply(&mut idl, &target);
+    }
+    if let Some(max_params) = pack_params {
+        idl.pack_params(max_params).with_context(|| format!("applying `{PACK_PARAMS_KEY}`"))?;
+    }
+
+    // Report the problems as JSON on stdout, as a list of messages.
+    if lint {
+        enter_phase("linting");
+        let cx = GenerateCx {
+            idl,
+            workspace_root: input.workspace_root,
+            type_overrides,
+            target_gates,
+            extra_files: input.extra_files,
+        };
+        let diagnostics = lint::lint(helper, &metadata, cx, &input.dest_crate);
+        println!("{}", serde_json::to_string(&diagnostics)?);
+        return Ok(());
     }
 
-    // Parse the input from stdin
-    let stdin = std::io::stdin();
-    let input: GlueGunInput<G::Metadata> = serde_json::from_reader(stdin.lock())?;
+    // Items may reference types that are not exported, e.g. as they are tagged `#[gluegun::ignore]` or gated off the target.
+    for diagnostic in idl.validate() {
+        eprintln!("warning: {diagnostic}");
+    }
+
+    // Report names that the helper would turn into the same identifier before writing anything.
+    enter_phase("checking names");
+    helper.naming(&metadata).check(&idl)?;
 
     // Create `output` and add user lib as a dependency
     let mut output = LibraryCrate::from_args(&input.dest_crate);
+    output.set_cargo_options(input.cargo_options);
+    if let Some(file_header) = file_header {
+        output.set_file_header(file_header);
+    }
+    if bindings_manifest {
+        output.enable_bindings_manifest();
+    }
+    if repr_c_records {
+        output.enable_repr_c_records();
+    }
+    if debug_bindings {
+        output.enable_debug_bindings();
+    }
     if G::INCLUDE_USER_LIB_DEPENDENCY {
-        output.add_dependency(input.idl.crate_name().text()).path(input.idl.crate_path());
+        // If the features the IDL was parsed with are known, depend on the crates with exactly those
+        // features, so that the items the IDL includes (or omits) due to `#[cfg]` match.
+        // Their paths are relative to the workspace root.
+        let mut dependency = output
+            .add_dependency(idl.crate_name().text())
+            .path(input.workspace_root.join(idl.crate_path()));
+        // The bindings were generated from this exact version of the crate.
+        if let Some(version) = idl.crate_metadata().version() {
+            dependency = dependency.version(format!("={version}"));
+        }
+        with_features(dependency, idl.crate_features());
+
+        // Generated code may also reference definitions from extern crates.
+        for extern_crate in idl.extern_crates() {
+            let dependency = output
+                .add_dependency(extern_crate.crate_name().text())
+                .path(input.workspace_root.join(extern_crate.crate_path()));
+            with_features(dependency, extern_crate.features());
+        }
     }
 
     // Invoke the user's code
-    helper.generate(&mut GenerateCx { idl: input.idl }, &input.metadata, &mut output)?;
+    enter_phase("generating code");
+    let mut cx = GenerateCx {
+        idl,
+        workspace_root: input.workspace_root,
+        type_overrides,
+        target_gates,
+        extra_files: input.extra_files,
+    };
+    helper.generate(&mut cx, &metadata, &mut output)?;
+
+    // Extra files the helper did not place somewhere else go in the root of the crate.
+    for extra_file in cx.extra_files.iter() {
+        if !output.has_extra_file(extra_file) {
+            output.add_extra_file(extra_file, "")?;
+        }
+    }
 
-    Ok(output.generate().with_context(|| {
+    // The user's own code goes in `src/custom/`, declared from the `src/lib.rs` the helper generated.
+    output.add_custom_src(&input.custom_src)?;
+
+    // Document the usages the helper registered, if any.
+    output.add_readme(&cx.idl)?;
+    output.add_bindings_manifest(&cx.idl)?;
+    output.add_repr_c_records(&cx.idl)?;
+    output.add_debug_bindings()?;
+
+    enter_phase("writing the crate");
+    output.generate().with_context(|| {
         format!(
             "generating output crate `{}` at `{}`",
             input.dest_crate.crate_name,
             input.dest_crate.path.display()
         )
-    })?)
+    })?;
+
+    if let Some(input_hash) = input_hash {
+        input_hash.record(&input.dest_crate.path)?;
+    }
+    Ok(())
+}
+
+/// Answer [`WORKSPACE_FLAG`][]: read the [`GlueGunWorkspaceInput`][] from stdin
+/// and write the [`WorkspaceFiles`][] of the helper as JSON on stdout, for `cargo gluegun` to write.
+fn run_workspace<G: GlueGunHelper>(helper: G) -> anyhow::Result<()> {
+    enter_phase("reading the input");
+    let input: GlueGunWorkspaceInput = serde_json::from_reader(std::io::stdin())?;
+
+    enter_phase("parsing the metadata");
+    let HelperMetadata { file_header, metadata, .. } = HelperMetadata::<G::Metadata>::parse(input.metadata)?;
+
+    enter_phase("generating workspace files");
+    let cx = WorkspaceCx { workspace_root: input.workspace_root, crates: input.crates };
+    let mut output = WorkspaceFiles::default();
+    if let Some(file_header) = file_header {
+        output.set_file_header(file_header);
+    }
+    helper.generate_workspace(&cx, &metadata, &mut output)?;
+    println!("{}", serde_json::to_string(&output)?);
+    Ok(())
+}
+
+/// Answer [`DOCTOR_FLAG`][]: read the [`GlueGunDoctorInput`][] from stdin and write the [`ToolReport`](`crate::codegen::ToolReport`)
+/// of each tool the helper requires (see [`GlueGunHelper::required_tools`][]) as JSON on stdout.
+fn run_doctor<G: GlueGunHelper>(helper: G) -> anyhow::Result<()> {
+    enter_phase("reading the input");
+    let input: GlueGunDoctorInput = serde_json::from_reader(std::io::stdin())?;
+
+    enter_phase("parsing the metadata");
+    let HelperMetadata { metadata, .. } = HelperMetadata::<G::Metadata>::parse(input.metadata)?;
+
+    enter_phase("checking the required tools");
+    let mut tools = RequiredTools::default();
+    helper.required_tools(&metadata, &mut tools);
+    println!("{}", serde_json::to_string(&tools.check())?);
+    Ok(())
+}
+
+/// The metadata of a helper, with the keys interpreted by gluegun itself taken out (see [`GlueGunHelper::Metadata`][]).
+struct HelperMetadata<M> {
+    filter: Filter,
+    type_overrides: TypeOverrides,
+    target_gates: TargetGates,
+    file_header: Option<String>,
+    bindings_manifest: bool,
+    repr_c_records: bool,
+    debug_bindings: bool,
+    pack_params: Option<usize>,
+    metadata: M,
+}
+
+impl<M: DeserializeOwned + Default> HelperMetadata<M> {
+    fn parse(metadata: serde_json::Value) -> anyhow::Result<Self> {
+        let mut map = match metadata {
+            serde_json::Value::Object(map) => map,
+            serde_json::Value::Null => serde_json::Map::new(),
+            other => anyhow::bail!("expected a table of metadata, found `{other}`"),
+        };
+        let filter = Filter::extract(&mut map)?;
+        let type_overrides = TypeOverrides::extract(&mut map)?;
+        let target_gates = TargetGates::extract(&mut map)?;
+        // The extra files and the custom sources are sent separately.
+        map.remove(ExtraFiles::KEY);
+        map.remove(CustomSrc::KEY);
+        let file_header = take_key(&mut map, FILE_HEADER_KEY)?;
+        let bindings_manifest = take_key(&mut map, BINDINGS_MANIFEST_KEY)?.unwrap_or(false);
+        let repr_c_records = take_key(&mut map, REPR_C_RECORDS_KEY)?.unwrap_or(false);
+        let debug_bindings = take_key(&mut map, DEBUG_BINDINGS_KEY)?.unwrap_or(false);
+        let pack_params = take_key(&mut map, PACK_PARAMS_KEY)?;
+        // Without other keys, the plugin's default metadata is used.
+        let metadata = if map.is_empty() {
+            Default::default()
+        } else {
+            serde_json::from_value(serde_json::Value::Object(map)).context("parsing metadata")?
+        };
+        Ok(Self {
+            filter,
+            type_overrides,
+            target_gates,
+            file_header,
+            bindings_manifest,
+            repr_c_records,
+            debug_bindings,
+            pack_params,
+            metadata,
+        })
+    }
+}
+
+/// Enable exactly `features` on `dependency`, if they are known.
+fn with_features(mut dependency: AddDependency<'_>, features: &Option<Vec<String>>) {
+    if let Some(features) = features {
+        dependency = dependency.no_default_features();
+        for feature in features {
+            dependency = dependency.feature(feature);
+        }
+    }
+}
+
+/// Remove `key` from the plugin `metadata` and return its value, if any.
+pub(crate) fn take_key<T: DeserializeOwned>(
+    metadata: &mut serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> anyhow::Result<Option<T>> {
+    metadata
+        .remove(key)
+        .map(|value| serde_json::from_value(value).with_context(|| format!("parsing `{key}`")))
+        .transpose()
+}
+
+/// Name of the key in the plugin metadata with a text (e.g., a license notice) to prepend
+/// to each generated file (see [`LibraryCrate::set_file_header`][]).
+pub const FILE_HEADER_KEY: &str = "file-header";
+
+/// Name of the key in the plugin metadata that enables the `bindings.json` manifest
+/// (see [`LibraryCrate::enable_bindings_manifest`][]).
+pub const BINDINGS_MANIFEST_KEY: &str = "bindings-manifest";
+
+/// Name of the key in the plugin metadata that enables the `#[repr(C)]` mirrors of records
+/// (see [`LibraryCrate::enable_repr_c_records`][]).
+pub const REPR_C_RECORDS_KEY: &str = "repr-c-records";
+
+/// Name of the key in the plugin metadata that enables the counting of live wrappers
+/// (see [`LibraryCrate::enable_debug_bindings`][]).
+pub const DEBUG_BINDINGS_KEY: &str = "debug-bindings";
+
+/// Name of the key in the plugin metadata with the number of parameters above which the parameters of a function
+/// are packed into a record (see [`Idl::pack_params`](`crate::idl::Idl::pack_params`)), e.g. `pack-params = 6`.
+/// Helpers supporting it (see [`GlueGunHelper::SUPPORTS_PACK_PARAMS`][]) generate both the usual form
+/// of such functions and one taking the record.
+pub const PACK_PARAMS_KEY: &str = "pack-params";
+
+/// Argument given (after `gg-{name}`) to ask a helper for its [`MetadataSchema`][].
+pub const METADATA_SCHEMA_FLAG: &str = "--metadata-schema";
+
+/// Argument given (after `gg-{name}`, along with the usual input on stdin) to ask a helper
+/// for the problems that keep it from generating the crate, rather than generating it.
+pub const LINT_FLAG: &str = "--lint";
+
+/// Argument given (after `gg-{name}`) once the crates of all the selected packages are generated,
+/// to ask a helper for the files shared by the crates of the workspace (see [`GlueGunHelper::generate_workspace`][]).
+/// The helper reads a JSON object with the `workspace_root`, its `metadata` for the workspace, and the generated `crates`
+/// (see [`WorkspaceCrate`][]) from stdin, and writes its [`WorkspaceFiles`][] as JSON on stdout.
+pub const WORKSPACE_FLAG: &str = "--workspace";
+
+/// Argument given (after `gg-{name}`) by `cargo gluegun doctor` to ask a helper for the tools it requires
+/// (see [`GlueGunHelper::required_tools`][]). The helper reads a JSON object with its `metadata` from stdin
+/// and writes a [`ToolReport`](`crate::codegen::ToolReport`) for each tool as a JSON list on stdout.
+pub const DOCTOR_FLAG: &str = "--doctor";
+
+/// Argument given (after `gg-{name}`) to ask a helper for its [version](`GlueGunHelper::version`),
+/// which it prints on stdout. `cargo gluegun` records it along with the crates the helper generates.
+pub const VERSION_FLAG: &str = "--version";
+
+/// Prefix of the lines a helper writes to stderr when it enters a phase of its work (e.g., `gluegun-phase: generating code`),
+/// so that `cargo gluegun` can tell where a helper that failed or panicked got to. They are not shown to the user.
+pub const PHASE_PREFIX: &str = "gluegun-phase: ";
+
+/// Tell `cargo gluegun` that the helper entered `phase` (see [`PHASE_PREFIX`][]).
+fn enter_phase(phase: &str) {
+    eprintln!("{PHASE_PREFIX}{phase}");
 }
 
 /// These are the subcommands executed by our system.
 /// Your extension should be able to respond to them.
 #[derive(Deserialize)]
-struct GlueGunInput<M> {
+struct GlueGunInput {
+    /// The directory that the paths in the IDL are relative to; empty if they are absolute.
+    #[serde(default)]
+    workspace_root: PathBuf,
     idl: Idl,
-    metadata: M,
+    /// Null if the user did not configure any metadata for this helper.
+    metadata: serde_json::Value,
+    /// The files matched by the `extra-files` patterns in the metadata.
+    #[serde(default)]
+    extra_files: ExtraFiles,
+    /// The files in the `custom-src` directory of the metadata.
+    #[serde(default)]
+    custom_src: CustomSrc,
+    dest_crate: GlueGunDestinationCrate,
+    /// How to invoke cargo when generating the crate.
+    #[serde(default)]
+    cargo_options: CargoOptions,
+}
+
+/// The start of the [`GlueGunInput`][], read before the rest of it is parsed.
+#[derive(Deserialize)]
+struct GlueGunInputHeader {
+    /// Hash of the input, recorded in the generated crate (absent from the input of older versions of cargo-gluegun).
+    #[serde(default)]
+    idl_hash: Option<String>,
+    /// True if the crate should be generated even if it was last generated from the same input
+    /// (with `cargo gluegun --force`).
+    #[serde(default)]
+    force: bool,
     dest_crate: GlueGunDestinationCrate,
 }
 
+/// The input given with [`WORKSPACE_FLAG`][].
+#[derive(Deserialize)]
+struct GlueGunWorkspaceInput {
+    workspace_root: PathBuf,
+    /// Null if the user did not configure any metadata for this helper.
+    metadata: serde_json::Value,
+    crates: Vec<WorkspaceCrate>,
+}
+
+/// The input given with [`DOCTOR_FLAG`][].
+#[derive(Deserialize)]
+struct GlueGunDoctorInput {
+    /// Null if the user did not configure any metadata for this helper.
+    metadata: serde_json::Value,
+}
+
 /// Context provided to the [`GlueGunHelper::generate`][] implementation.
 #[derive(Accessors)]
 #[accessors(get)]
 pub struct GenerateCx {
     /// The IDL from the source crate
     idl: Idl,
+
+    /// The directory that the paths in the IDL (e.g., those of spans) are relative to,
+    /// normally the workspace root; empty if they are absolute
+    workspace_root: PathBuf,
+
+    /// The mappings the user configured for third-party types
+    type_overrides: TypeOverrides,
+
+    /// The targets on which the user configured definitions to be available;
+    /// helpers that support them put [`TargetGates::cfg_attr`][] on the Rust items generated for those definitions
+    target_gates: TargetGates,
+
+    /// The files the user asked to bundle with the generated crate
+    extra_files: ExtraFiles,
 }
 
 /// The arguments that identify where the crate should be generated.
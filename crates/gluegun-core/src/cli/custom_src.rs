@@ -0,0 +1,80 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use super::ExtraFile;
+
+/// Hand-written Rust code that users can ask a plugin to include in the generated crate
+/// (e.g., a custom conversion), given as a directory in the metadata of any plugin, e.g.
+///
+/// ```toml
+/// [package.metadata.gluegun.py]
+/// custom-src = "glue/py"
+/// ```
+///
+/// The directory is relative to the package directory and must contain a `mod.rs`.
+/// `cargo gluegun` gives the helper all the files in it, which are copied into `src/custom/`
+/// and declared as `mod custom;` at the end of the generated `src/lib.rs`,
+/// so that they are put back each time the crate is regenerated.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CustomSrc {
+    files: Vec<ExtraFile>,
+}
+
+impl CustomSrc {
+    /// Name of the key in the plugin metadata.
+    pub const KEY: &'static str = "custom-src";
+
+    /// Resolve the directory in the plugin `metadata` (if any) against `package_dir`.
+    /// A directory that does not exist or has no `mod.rs` is an error.
+    pub fn resolve(metadata: &serde_json::Value, package_dir: &Path) -> anyhow::Result<Self> {
+        let dir: PathBuf = match metadata.get(Self::KEY) {
+            Some(value) => serde_json::from_value(value.clone()).context("expected a path")?,
+            None => return Ok(Self::default()),
+        };
+
+        let root = package_dir.join(&dir);
+        if !root.is_dir() {
+            anyhow::bail!("`{}` is not a directory in `{}`", dir.display(), package_dir.display());
+        }
+        if !root.join("mod.rs").is_file() {
+            anyhow::bail!("`{}` has no `mod.rs`", dir.display());
+        }
+
+        let mut files = vec![];
+        Self::collect(&root, Path::new(""), &mut files)?;
+        Ok(Self { files })
+    }
+
+    /// Add the files in `root.join(path)` and its subdirectories to `files`, in alphabetical order.
+    fn collect(root: &Path, path: &Path, files: &mut Vec<ExtraFile>) -> anyhow::Result<()> {
+        let dir = root.join(path);
+        let mut names = vec![];
+        for entry in std::fs::read_dir(&dir).with_context(|| format!("reading `{}`", dir.display()))? {
+            names.push(entry?.file_name());
+        }
+        names.sort();
+
+        for name in names {
+            let path = path.join(name);
+            let source = root.join(&path);
+            if source.is_dir() {
+                Self::collect(root, &path, files)?;
+            } else if source.is_file() {
+                files.push(ExtraFile::new(path, source));
+            }
+        }
+        Ok(())
+    }
+
+    /// True if the user did not give a directory.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// The files in the directory, with their [paths](`ExtraFile::path`) relative to it.
+    pub fn iter(&self) -> impl Iterator<Item = &ExtraFile> {
+        self.files.iter()
+    }
+}
@@ -0,0 +1,130 @@
+use std::path::{Path, PathBuf};
+
+use accessors_rs::Accessors;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use super::filter::glob_matches;
+
+/// Non-Rust files that users can ask a plugin to bundle with the generated crate
+/// (e.g., a logo, a license header, or extra Java sources), listed in the metadata of any plugin, e.g.
+///
+/// ```toml
+/// [package.metadata.gluegun.java]
+/// extra-files = ["java/custom/*.java"]
+/// ```
+///
+/// Patterns are paths relative to the package directory, where `*` matches any sequence of characters
+/// and `?` any single character within one component of the path. `cargo gluegun` resolves them and
+/// gives the helper the files they match, which are copied into the generated crate
+/// (see [`LibraryCrate::add_extra_file`](`crate::codegen::LibraryCrate::add_extra_file`)).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ExtraFiles {
+    files: Vec<ExtraFile>,
+}
+
+/// A file matched by one of the patterns of [`ExtraFiles`][].
+#[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
+#[accessors(get)]
+pub struct ExtraFile {
+    /// The path of the file relative to the directory that the pattern starts with,
+    /// up to the first component with a `*` or `?` (e.g., `Util.java` for `java/custom/Util.java`).
+    path: PathBuf,
+
+    /// The absolute path of the file.
+    source: PathBuf,
+}
+
+impl ExtraFile {
+    /// The file at `source`, to be copied to `path` (see [`CustomSrc`](`super::CustomSrc`)).
+    pub(super) fn new(path: PathBuf, source: PathBuf) -> Self {
+        Self { path, source }
+    }
+}
+
+impl ExtraFiles {
+    /// Name of the key in the plugin metadata.
+    pub const KEY: &'static str = "extra-files";
+
+    /// Resolve the patterns in the plugin `metadata` (if any) against the files in `package_dir`.
+    /// A pattern that matches no file is an error.
+    pub fn resolve(metadata: &serde_json::Value, package_dir: &Path) -> anyhow::Result<Self> {
+        let patterns: Vec<String> = match metadata.get(Self::KEY) {
+            Some(value) => serde_json::from_value(value.clone()).context("expected a list of paths")?,
+            None => vec![],
+        };
+
+        let mut files: Vec<ExtraFile> = vec![];
+        for pattern in &patterns {
+            let matched = Self::resolve_pattern(pattern, package_dir)
+                .with_context(|| format!("resolving `{pattern}`"))?;
+            if matched.is_empty() {
+                anyhow::bail!("`{pattern}` does not match any file in `{}`", package_dir.display());
+            }
+            for file in matched {
+                if !files.iter().any(|f| f.source == file.source) {
+                    files.push(file);
+                }
+            }
+        }
+        Ok(Self { files })
+    }
+
+    /// The files in `package_dir` matched by `pattern`, in alphabetical order.
+    fn resolve_pattern(pattern: &str, package_dir: &Path) -> anyhow::Result<Vec<ExtraFile>> {
+        let components: Vec<&str> = pattern.split('/').filter(|c| !c.is_empty() && *c != ".").collect();
+        let base_len = components
+            .iter()
+            .take_while(|c| !c.contains(['*', '?']))
+            .count()
+            .min(components.len().saturating_sub(1));
+
+        // Each path matched so far, relative to `package_dir`.
+        let mut matched = vec![PathBuf::new()];
+        for component in &components {
+            let mut next = vec![];
+            for path in &matched {
+                if !component.contains(['*', '?']) {
+                    next.push(path.join(component));
+                    continue;
+                }
+
+                let dir = package_dir.join(path);
+                if !dir.is_dir() {
+                    continue;
+                }
+                let mut names = vec![];
+                for entry in std::fs::read_dir(&dir).with_context(|| format!("reading `{}`", dir.display()))? {
+                    let name = entry?.file_name();
+                    if let Some(name) = name.to_str() {
+                        if glob_matches(component, name) {
+                            names.push(name.to_string());
+                        }
+                    }
+                }
+                names.sort();
+                next.extend(names.into_iter().map(|name| path.join(name)));
+            }
+            matched = next;
+        }
+
+        Ok(matched
+            .into_iter()
+            .filter(|path| package_dir.join(path).is_file())
+            .map(|path| ExtraFile {
+                path: path.components().skip(base_len).collect(),
+                source: package_dir.join(&path),
+            })
+            .collect())
+    }
+
+    /// True if there are no extra files.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// The extra files, in the order of the patterns that matched them.
+    pub fn iter(&self) -> impl Iterator<Item = &ExtraFile> {
+        self.files.iter()
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeSet;
+
+use crate::idl::{Diagnostic, Idl, Item, Method, QualifiedName};
+
+use super::take_key;
+
+/// Selection filters that users can add to the metadata of any plugin to curate
+/// which definitions are exported to that language, e.g.
+///
+/// ```toml
+/// [package.metadata.gluegun.java]
+/// include = ["api_*"]
+/// exclude = ["*_internal", "Client::reset"]
+/// ```
+///
+/// Patterns are matched against the path of each definition, either relative to its crate (`api_call`, `Foo`)
+/// or including the crate name (`my_crate::Foo`), and against the path of each method (`Foo::new`).
+/// They are matched name by name: `*` matches any sequence of characters within a name, `?` any single character,
+/// and a `**` name any number of names (`shapes::**` matches everything in the module `shapes`).
+/// A pattern without `::` matches the last name, at any depth (`new` matches the methods named `new` of all types).
+///
+/// If `include` is non-empty, only definitions matching at least one of its patterns are exported,
+/// with all of their methods, along with the definitions whose methods match (with only those methods).
+/// Definitions and methods matching any pattern in `exclude` are never exported.
+/// The methods of interfaces are all kept, as Rust code calls them on the foreign implementations.
+#[derive(Debug, Default)]
+pub(crate) struct Filter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl Filter {
+    /// Remove the `include` and `exclude` keys from the plugin `metadata` and return the filter they describe.
+    pub(crate) fn extract(metadata: &mut serde_json::Map<String, serde_json::Value>) -> anyhow::Result<Self> {
+        Ok(Self {
+            include: take_key(metadata, "include")?.unwrap_or_default(),
+            exclude: take_key(metadata, "exclude")?.unwrap_or_default(),
+        })
+    }
+
+    /// Remove definitions and methods that are not selected by this filter from `idl`.
+    ///
+    /// Patterns that match nothing are reported as warnings, as filters configured for the whole workspace
+    /// may not apply to every crate. It is an error if `include` selects nothing at all, or if a definition
+    /// that is kept references a type that is not (e.g., a function taking a struct that `exclude` matches).
+    pub(crate) fn apply(&self, idl: &mut Idl) -> anyhow::Result<()> {
+        let crate_name = idl.crate_name().clone();
+        let paths: Vec<QualifiedName> = idl
+            .definitions()
+            .iter()
+            .flat_map(|(qname, item)| {
+                let members = item.methods().iter().map(|method| qname.join(method.name().clone()));
+                std::iter::once(qname.clone()).chain(members)
+            })
+            .collect();
+        for (key, patterns) in [("include", &self.include), ("exclude", &self.exclude)] {
+            for pattern in patterns {
+                if !paths.iter().any(|path| path_matches(pattern, path)) {
+                    eprintln!("warning: the `{key}` pattern `{pattern}` matches nothing in `{crate_name}`");
+                }
+            }
+        }
+
+        let defined: BTreeSet<QualifiedName> = idl.definitions().keys().cloned().collect();
+        idl.retain_definitions(|qname, item| self.is_item_selected(qname, item));
+        idl.retain_methods(|qname, method| self.is_method_selected(qname, method));
+        if !self.include.is_empty() && idl.definitions().is_empty() {
+            anyhow::bail!("`include` selects none of the definitions of `{crate_name}`");
+        }
+
+        let dangling: Vec<String> = idl
+            .validate()
+            .into_iter()
+            .filter(|diagnostic| match diagnostic {
+                Diagnostic::UnexportedType { ty, .. } => defined.contains(ty),
+                _ => false,
+            })
+            .map(|diagnostic| format!("\n    {diagnostic}"))
+            .collect();
+        if !dangling.is_empty() {
+            anyhow::bail!(
+                "definitions kept by `include`/`exclude` reference types that are left out:{}\n\
+                 include those types, or exclude the definitions referencing them",
+                dangling.concat(),
+            );
+        }
+        Ok(())
+    }
+
+    /// True if `item` is exported, with all its methods or (if `include` only matches some of them) with those.
+    fn is_item_selected(&self, qname: &QualifiedName, item: &Item) -> bool {
+        let included = |path: &QualifiedName| self.include.iter().any(|pattern| path_matches(pattern, path));
+        let excluded = self.exclude.iter().any(|pattern| path_matches(pattern, qname));
+        !excluded
+            && (self.include.is_empty()
+                || included(qname)
+                || item.methods().iter().any(|method| included(&qname.join(method.name().clone()))))
+    }
+
+    /// True if `method`, attached to the definition `qname`, is exported along with it.
+    fn is_method_selected(&self, qname: &QualifiedName, method: &Method) -> bool {
+        let path = qname.join(method.name().clone());
+        let matches = |pattern: &String| path_matches(pattern, &path);
+        let item_included = self.include.iter().any(|pattern| path_matches(pattern, qname));
+        (self.include.is_empty() || item_included || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+}
+
+/// Returns true if `pattern` matches the path of `qname`, either relative to its crate or including the crate name.
+/// The names of the path are matched against those of the pattern, separated by `::`, by [`glob_matches`][],
+/// except for `**`, which matches any number of names. A pattern without `::` is matched against the last name only.
+pub(super) fn path_matches(pattern: &str, qname: &QualifiedName) -> bool {
+    let names: Vec<&str> = qname.names().iter().map(|name| name.text().as_str()).collect();
+    if !pattern.contains("::") {
+        return names.last().is_some_and(|name| glob_matches(pattern, name));
+    }
+    let pattern: Vec<&str> = pattern.split("::").collect();
+    names_match(&pattern, &names[1..]) || names_match(&pattern, &names)
+}
+
+/// Returns true if `names` match the names of a `pattern` (see [`path_matches`][]).
+fn names_match(pattern: &[&str], names: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => names.is_empty(),
+        Some((&"**", rest)) => (0..=names.len()).any(|skipped| names_match(rest, &names[skipped..])),
+        Some((first, rest)) => names
+            .split_first()
+            .is_some_and(|(name, names)| glob_matches(first, name) && names_match(rest, names)),
+    }
+}
+
+/// Returns true if `text` matches the glob `pattern`,
+/// where `*` matches any sequence of characters and `?` any single character.
+pub(super) fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    // Position in `pattern` just after the last `*` we saw and the position in `text` it is matched against.
+    let mut backtrack = None;
+    let (mut p, mut t) = (0, 0);
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            p += 1;
+            backtrack = Some((p, t));
+        } else if let Some((star_p, star_t)) = backtrack {
+            // Let the last `*` absorb one more character and try again.
+            p = star_p;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,59 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::Path,
+};
+
+use anyhow::Context;
+
+use crate::codegen::generated_files_exist;
+
+/// Name of the file in the generated crate that records the [`InputHash`][] it was last generated from.
+const INPUT_HASH_NAME: &str = ".gluegun-hash";
+
+/// Identifies the input a crate is generated from: the hash of the IDL, metadata, and destination
+/// that cargo-gluegun sends (as `idl_hash`), combined with the name of the helper and the modification time
+/// of its executable, so that rebuilding the helper also regenerates its crates.
+pub(super) struct InputHash {
+    hash: String,
+}
+
+impl InputHash {
+    pub(super) fn new(helper_name: &str, idl_hash: &str) -> Self {
+        let executable_modified = std::env::current_exe()
+            .and_then(|path| path.metadata())
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        let mut hasher = DefaultHasher::new();
+        (helper_name, idl_hash, env!("CARGO_PKG_VERSION"), executable_modified).hash(&mut hasher);
+        Self {
+            hash: format!("{:016x}", hasher.finish()),
+        }
+    }
+
+    /// True if the crate at `crate_path` was last generated from this input and its files are still there.
+    pub(super) fn is_recorded_in(&self, crate_path: &Path) -> bool {
+        let Ok(recorded) = std::fs::read_to_string(crate_path.join(INPUT_HASH_NAME)) else {
+            return false;
+        };
+        recorded.trim() == self.hash && generated_files_exist(crate_path)
+    }
+
+    /// Record that the crate at `crate_path` was generated from this input.
+    pub(super) fn record(&self, crate_path: &Path) -> anyhow::Result<()> {
+        let path = crate_path.join(INPUT_HASH_NAME);
+        std::fs::write(&path, format!("{}\n", self.hash)).with_context(|| format!("writing to file at `{}`", path.display()))
+    }
+
+    /// Remove the input recorded in the crate at `crate_path` (if any), before it is regenerated,
+    /// so that it is not considered up to date if generation fails.
+    pub(super) fn forget(crate_path: &Path) -> anyhow::Result<()> {
+        let path = crate_path.join(INPUT_HASH_NAME);
+        match std::fs::remove_file(&path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+                Err(error).with_context(|| format!("removing `{}`", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{codegen::LibraryCrate, idl::Idl};
+
+use super::{GenerateCx, GlueGunDestinationCrate, GlueGunHelper};
+
+/// Report everything that keeps `helper` from generating a crate for `cx`, without writing anything.
+///
+/// Helpers give up on the first unsupported construct they meet, so if generating the whole crate fails,
+/// each definition is generated again on its own (along with the definitions it references, see
+/// [`Idl::dependencies`](`crate::idl::Idl::dependencies`)) and the distinct errors are collected.
+/// The problems found by [`Idl::validate`](`crate::idl::Idl::validate`) and the helper's
+/// [naming](`GlueGunHelper::naming`) are included as well.
+pub(super) fn lint<G>(
+    helper: G,
+    metadata: &G::Metadata,
+    cx: GenerateCx,
+    dest_crate: &GlueGunDestinationCrate,
+) -> Vec<String>
+where
+    G: GlueGunHelper + Clone,
+{
+    let mut diagnostics: Vec<String> = cx.idl.validate().iter().map(|diagnostic| diagnostic.to_string()).collect();
+    if let Err(error) = helper.naming(metadata).check(&cx.idl) {
+        diagnostics.push(format!("{error:#}"));
+    }
+
+    let generate = |idl: Idl| {
+        let mut cx = GenerateCx {
+            idl,
+            workspace_root: cx.workspace_root.clone(),
+            type_overrides: cx.type_overrides.clone(),
+            target_gates: cx.target_gates.clone(),
+            extra_files: cx.extra_files.clone(),
+        };
+        let mut output = LibraryCrate::from_args(dest_crate);
+        helper.clone().generate(&mut cx, metadata, &mut output)
+    };
+    if generate(cx.idl.clone()).is_ok() {
+        return diagnostics;
+    }
+
+    for qname in cx.idl.definitions().keys() {
+        let dependencies = cx.idl.dependencies(qname);
+        let mut idl = cx.idl.clone();
+        idl.retain_definitions(|qname, _| dependencies.contains(qname));
+        if let Err(error) = generate(idl) {
+            let diagnostic = format!("{error:#}");
+            if !diagnostics.contains(&diagnostic) {
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+    diagnostics
+}
@@ -0,0 +1,233 @@
+use accessors_rs::Accessors;
+use serde::{Deserialize, Serialize};
+
+/// Implemented by the [`Metadata`](`super::GlueGunHelper::Metadata`) type of a helper
+/// to describe the options it accepts. `cargo gluegun` validates the user's metadata
+/// against this description before invoking the helper, so that a misspelled option is
+/// reported (naming the valid ones) rather than silently replaced by its default.
+pub trait DescribeMetadata {
+    fn describe() -> MetadataSchema;
+}
+
+/// Helpers without metadata accept no options (other than the reserved `include`, `exclude`, `type-overrides`, `target-gates`, `extra-files`, and `file-header`).
+impl DescribeMetadata for () {
+    fn describe() -> MetadataSchema {
+        MetadataSchema::new()
+    }
+}
+
+/// Free-form metadata accepts any options.
+impl DescribeMetadata for serde_json::Value {
+    fn describe() -> MetadataSchema {
+        MetadataSchema::free_form()
+    }
+}
+
+/// The options accepted in the metadata of a helper (e.g., in `[package.metadata.gluegun.java]`).
+#[derive(Accessors, Clone, Debug, Default, Serialize, Deserialize)]
+#[accessors(get)]
+pub struct MetadataSchema {
+    /// If true, any option is accepted and `options` is informative only.
+    is_free_form: bool,
+
+    /// The known options.
+    options: Vec<MetadataOption>,
+}
+
+/// An option accepted in the metadata of a helper.
+#[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
+#[accessors(get)]
+pub struct MetadataOption {
+    /// Name of the option as written by the user (e.g., `option`).
+    name: String,
+
+    /// The values the option accepts.
+    kind: MetadataKind,
+
+    /// One-line description of the option.
+    description: String,
+}
+
+/// The values accepted by a [`MetadataOption`][].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum MetadataKind {
+    /// `true` or `false`.
+    Bool,
+
+    /// A non-negative integer.
+    Count,
+
+    /// Any string.
+    String,
+
+    /// A list of strings.
+    StringList,
+
+    /// A table (with any contents).
+    Table,
+
+    /// One of the given strings.
+    OneOf(Vec<String>),
+
+    /// Any value.
+    Any,
+}
+
+/// A problem found by [`MetadataSchema::validate`][].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MetadataDiagnostic {
+    /// The metadata contains the option `name`, which the helper does not know.
+    /// Reported as a warning.
+    UnknownOption { name: String, valid: Vec<String> },
+
+    /// The value of the option `name` is not one of the values it accepts.
+    /// Reported as an error.
+    InvalidValue { name: String, expected: MetadataKind },
+}
+
+/// Options accepted by every helper, with the values they accept;
+/// they are interpreted by gluegun itself (see [`super::run`][]).
+const RESERVED_OPTIONS: &[(&str, MetadataKind)] = &[
+    ("include", MetadataKind::StringList),
+    ("exclude", MetadataKind::StringList),
+    (super::TypeOverrides::KEY, MetadataKind::Table),
+    (super::TargetGates::KEY, MetadataKind::Table),
+    (super::ExtraFiles::KEY, MetadataKind::StringList),
+    (super::CustomSrc::KEY, MetadataKind::String),
+    (super::FILE_HEADER_KEY, MetadataKind::String),
+    (super::BINDINGS_MANIFEST_KEY, MetadataKind::Bool),
+    (super::REPR_C_RECORDS_KEY, MetadataKind::Bool),
+    (super::DEBUG_BINDINGS_KEY, MetadataKind::Bool),
+    (super::PACK_PARAMS_KEY, MetadataKind::Count),
+];
+
+impl MetadataSchema {
+    /// A schema with no options; add them with [`Self::option`][].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A schema that accepts any options.
+    pub fn free_form() -> Self {
+        Self {
+            is_free_form: true,
+            options: vec![],
+        }
+    }
+
+    /// Add an option named `name` accepting values of `kind`.
+    pub fn option(mut self, name: impl ToString, kind: MetadataKind, description: impl ToString) -> Self {
+        self.options.push(MetadataOption {
+            name: name.to_string(),
+            kind,
+            description: description.to_string(),
+        });
+        self
+    }
+
+    /// Validate the user-provided `metadata` (null if the user provided none) against this schema.
+    pub fn validate(&self, metadata: &serde_json::Value) -> Vec<MetadataDiagnostic> {
+        let serde_json::Value::Object(map) = metadata else {
+            return vec![];
+        };
+
+        let mut diagnostics = vec![];
+        for (name, value) in map {
+            if let Some((_, kind)) = RESERVED_OPTIONS.iter().find(|(reserved, _)| reserved == name) {
+                if !kind.accepts(value) {
+                    diagnostics.push(MetadataDiagnostic::InvalidValue {
+                        name: name.clone(),
+                        expected: kind.clone(),
+                    });
+                }
+                continue;
+            }
+
+            match self.options.iter().find(|option| option.name == *name) {
+                Some(option) => {
+                    if !option.kind.accepts(value) {
+                        diagnostics.push(MetadataDiagnostic::InvalidValue {
+                            name: name.clone(),
+                            expected: option.kind.clone(),
+                        });
+                    }
+                }
+                None if self.is_free_form => {}
+                None => diagnostics.push(MetadataDiagnostic::UnknownOption {
+                    name: name.clone(),
+                    valid: self
+                        .options
+                        .iter()
+                        .map(|option| option.name.clone())
+                        .chain(RESERVED_OPTIONS.iter().map(|(name, _)| name.to_string()))
+                        .collect(),
+                }),
+            }
+        }
+        diagnostics
+    }
+}
+
+impl MetadataKind {
+    /// Accepts one of the strings in `values`.
+    pub fn one_of(values: impl IntoIterator<Item = impl ToString>) -> Self {
+        MetadataKind::OneOf(values.into_iter().map(|value| value.to_string()).collect())
+    }
+
+    fn accepts(&self, value: &serde_json::Value) -> bool {
+        match self {
+            MetadataKind::Bool => value.is_boolean(),
+            MetadataKind::Count => value.is_u64(),
+            MetadataKind::String => value.is_string(),
+            MetadataKind::StringList => value
+                .as_array()
+                .is_some_and(|elements| elements.iter().all(|element| element.is_string())),
+            MetadataKind::Table => value.is_object(),
+            MetadataKind::OneOf(values) => value.as_str().is_some_and(|value| values.iter().any(|v| v == value)),
+            MetadataKind::Any => true,
+        }
+    }
+}
+
+impl std::fmt::Display for MetadataKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataKind::Bool => write!(f, "a boolean"),
+            MetadataKind::Count => write!(f, "a non-negative integer"),
+            MetadataKind::String => write!(f, "a string"),
+            MetadataKind::StringList => write!(f, "a list of strings"),
+            MetadataKind::Table => write!(f, "a table"),
+            MetadataKind::OneOf(values) => {
+                let values: Vec<String> = values.iter().map(|value| format!("`{value}`")).collect();
+                write!(f, "one of {}", values.join(", "))
+            }
+            MetadataKind::Any => write!(f, "any value"),
+        }
+    }
+}
+
+impl MetadataDiagnostic {
+    /// True if the diagnostic is an error (as opposed to a warning).
+    pub fn is_error(&self) -> bool {
+        match self {
+            MetadataDiagnostic::UnknownOption { .. } => false,
+            MetadataDiagnostic::InvalidValue { .. } => true,
+        }
+    }
+}
+
+impl std::fmt::Display for MetadataDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataDiagnostic::UnknownOption { name, valid } => {
+                let valid: Vec<String> = valid.iter().map(|name| format!("`{name}`")).collect();
+                write!(f, "unknown option `{name}` (valid options are {})", valid.join(", "))
+            }
+            MetadataDiagnostic::InvalidValue { name, expected } => {
+                write!(f, "invalid value for option `{name}`: expected {expected}")
+            }
+        }
+    }
+}
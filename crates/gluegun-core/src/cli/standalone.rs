@@ -0,0 +1,48 @@
+use clap::{CommandFactory, FromArgMatches};
+
+use super::{DescribeMetadata, GlueGunHelper, MetadataSchema};
+
+/// The command line of a helper run by hand, rather than by `cargo gluegun` (which passes `gg-{name}` first),
+/// e.g. `gluegun-java --print-metadata-schema`.
+#[derive(clap::Parser)]
+struct StandaloneArgs {
+    /// Print the options accepted in the metadata of the helper (e.g., in `[package.metadata.gluegun.java]`), as JSON
+    #[arg(long)]
+    print_metadata_schema: bool,
+}
+
+/// Answer a command line that did not come from `cargo gluegun`: `--help`, `--version`, and `--print-metadata-schema`
+/// are handled (by printing to stdout and exiting, for the first two); anything else is an error.
+pub(super) fn run_standalone<G: GlueGunHelper>(helper: &G) -> anyhow::Result<()> {
+    let name = helper.name();
+    let command = StandaloneArgs::command()
+        .name(format!("gluegun-{name}"))
+        .version(helper.version())
+        .about(format!(
+            "The `{name}` helper of gluegun, which generates the crate `<crate>-{name}` when `cargo gluegun {name}` invokes it"
+        ))
+        .after_help(options_help(&G::Metadata::describe()));
+    let args = StandaloneArgs::from_arg_matches(&command.get_matches())?;
+
+    if args.print_metadata_schema {
+        println!("{}", serde_json::to_string_pretty(&G::Metadata::describe())?);
+        return Ok(());
+    }
+    anyhow::bail!("expected to be invoked by `cargo gluegun {name}` (see `--help`)")
+}
+
+/// List the options of `schema` for `--help`, e.g. `  option: one of `nullable`, `optional``
+/// followed by the description of the option on the next line.
+fn options_help(schema: &MetadataSchema) -> String {
+    if *schema.is_free_form() {
+        return "Metadata options: any".to_string();
+    }
+    if schema.options().is_empty() {
+        return "Metadata options: none".to_string();
+    }
+    let mut help = "Metadata options:".to_string();
+    for option in schema.options() {
+        help.push_str(&format!("\n  {}: {}\n          {}", option.name(), option.kind(), option.description()));
+    }
+    help
+}
@@ -0,0 +1,272 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::idl::{Idl, QualifiedName};
+
+use super::{filter::path_matches, take_key};
+
+/// Conditions on the target under which definitions are available, which users can add to the metadata
+/// of the plugins that support them (see [`GlueGunHelper::SUPPORTS_TARGET_GATES`](`super::GlueGunHelper::SUPPORTS_TARGET_GATES`)), e.g.
+///
+/// ```toml
+/// [package.metadata.gluegun.py.target-gates]
+/// "fs::*" = 'not(target_family = "wasm")'
+/// "open_tty" = "unix"
+/// ```
+///
+/// Each key is a pattern matched against the path of each definition, as for `include` and `exclude`,
+/// and each value is a `cfg` predicate. The code generated for a matching definition is wrapped in `#[cfg(...)]`
+/// with its predicate (or, if several patterns match, with `all` of theirs; see [`Self::cfg_attr`][]).
+/// Definitions whose predicate is false on the target the plugin always builds for, if any
+/// (see [`GlueGunHelper::target`](`super::GlueGunHelper::target`)), are left out of the IDL altogether.
+#[derive(Clone, Debug, Default)]
+pub struct TargetGates {
+    gates: BTreeMap<String, CfgPredicate>,
+}
+
+impl TargetGates {
+    /// Name of the key in the plugin metadata.
+    pub const KEY: &'static str = "target-gates";
+
+    /// Remove the gates from the plugin `metadata` and return them.
+    pub(crate) fn extract(metadata: &mut serde_json::Map<String, serde_json::Value>) -> anyhow::Result<Self> {
+        let Some(entries) = take_key::<BTreeMap<String, String>>(metadata, Self::KEY)? else {
+            return Ok(Self::default());
+        };
+        let mut gates = BTreeMap::new();
+        for (pattern, predicate) in entries {
+            let predicate = CfgPredicate::parse(&predicate).map_err(|message| {
+                anyhow::anyhow!("parsing `{}`: `{pattern}`: invalid `cfg` predicate `{predicate}`: {message}", Self::KEY)
+            })?;
+            gates.insert(pattern, predicate);
+        }
+        Ok(Self { gates })
+    }
+
+    /// True if no definitions are gated.
+    pub fn is_empty(&self) -> bool {
+        self.gates.is_empty()
+    }
+
+    /// The predicate under which the definition `qname` is available, if it is gated.
+    pub fn predicate(&self, qname: &QualifiedName) -> Option<CfgPredicate> {
+        let mut predicates: Vec<CfgPredicate> = self
+            .gates
+            .iter()
+            .filter(|(pattern, _)| path_matches(pattern, qname))
+            .map(|(_, predicate)| predicate.clone())
+            .collect();
+        match predicates.len() {
+            0 => None,
+            1 => predicates.pop(),
+            _ => Some(CfgPredicate::All(predicates)),
+        }
+    }
+
+    /// The attribute to put on each Rust item generated for the definition `qname`
+    /// (e.g., `#[cfg(not(target_family = "wasm"))]`), if it is gated.
+    pub fn cfg_attr(&self, qname: &QualifiedName) -> Option<String> {
+        self.predicate(qname).map(|predicate| format!("#[cfg({predicate})]"))
+    }
+
+    /// Remove the definitions that are not available on `target` from `idl`.
+    pub(crate) fn apply(&self, idl: &mut Idl, target: &TargetCfg) {
+        idl.retain_definitions(|qname, _item| {
+            self.predicate(qname)
+                .is_none_or(|predicate| predicate.evaluate(target) != Some(false))
+        });
+    }
+}
+
+/// A `cfg` predicate, e.g. `all(unix, not(target_os = "macos"))`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CfgPredicate {
+    /// A configuration option without a value, e.g. `unix`.
+    Name(String),
+
+    /// A configuration option with a value, e.g. `target_os = "linux"`.
+    NameValue(String, String),
+
+    All(Vec<CfgPredicate>),
+    Any(Vec<CfgPredicate>),
+    Not(Box<CfgPredicate>),
+}
+
+impl CfgPredicate {
+    /// Parse `text`, reporting what is wrong with it if it is not a predicate.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut tokens = CfgTokens::new(text);
+        let predicate = tokens.predicate()?;
+        match tokens.next()? {
+            None => Ok(predicate),
+            Some(token) => Err(format!("unexpected `{token}`")),
+        }
+    }
+
+    /// The value of the predicate on `target`, or `None` if it depends on more than the target
+    /// (e.g., `feature = "x"` or `debug_assertions`). `all` and `any` are decided by any of their predicates
+    /// being false or true, respectively, even if the others are unknown.
+    pub fn evaluate(&self, target: &TargetCfg) -> Option<bool> {
+        match self {
+            CfgPredicate::Name(name) if TargetCfg::is_target_name(name) => Some(target.names.contains_key(name)),
+            CfgPredicate::NameValue(name, value) if TargetCfg::is_target_name(name) => {
+                Some(target.names.get(name).is_some_and(|values| values.contains(value)))
+            }
+            CfgPredicate::Name(_) | CfgPredicate::NameValue(..) => None,
+            CfgPredicate::All(predicates) => combine(predicates, target, false),
+            CfgPredicate::Any(predicates) => combine(predicates, target, true),
+            CfgPredicate::Not(predicate) => predicate.evaluate(target).map(|value| !value),
+        }
+    }
+}
+
+/// Evaluate the `predicates` of an `all` (`decisive = false`) or `any` (`decisive = true`):
+/// the result is `decisive` if any value is, unknown if any value is unknown, and `!decisive` otherwise.
+fn combine(predicates: &[CfgPredicate], target: &TargetCfg, decisive: bool) -> Option<bool> {
+    let values: Vec<Option<bool>> = predicates.iter().map(|predicate| predicate.evaluate(target)).collect();
+    if values.contains(&Some(decisive)) {
+        Some(decisive)
+    } else if values.iter().all(Option::is_some) {
+        Some(!decisive)
+    } else {
+        None
+    }
+}
+
+impl fmt::Display for CfgPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |f: &mut fmt::Formatter<'_>, name: &str, predicates: &[CfgPredicate]| {
+            let predicates: Vec<String> = predicates.iter().map(|predicate| predicate.to_string()).collect();
+            write!(f, "{name}({})", predicates.join(", "))
+        };
+        match self {
+            CfgPredicate::Name(name) => write!(f, "{name}"),
+            CfgPredicate::NameValue(name, value) => write!(f, "{name} = {value:?}"),
+            CfgPredicate::All(predicates) => list(f, "all", predicates),
+            CfgPredicate::Any(predicates) => list(f, "any", predicates),
+            CfgPredicate::Not(predicate) => write!(f, "not({predicate})"),
+        }
+    }
+}
+
+/// The configuration options that hold on a target, e.g. `target_arch = "wasm32"` on `wasm32-wasip1`
+/// (see [`GlueGunHelper::target`](`super::GlueGunHelper::target`)).
+/// Only options that depend on the target (`unix`, `windows`, and those starting with `target_`) are considered.
+#[derive(Clone, Debug, Default)]
+pub struct TargetCfg {
+    /// The values of each option, by name; options without a value have none.
+    names: BTreeMap<String, Vec<String>>,
+}
+
+impl TargetCfg {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an option without a value, e.g. `unix`.
+    pub fn name(mut self, name: &str) -> Self {
+        self.names.entry(name.to_string()).or_default();
+        self
+    }
+
+    /// Add an option with a value, e.g. `target_os = "wasi"`. An option (e.g., `target_feature`) may have several.
+    pub fn name_value(mut self, name: &str, value: &str) -> Self {
+        self.names.entry(name.to_string()).or_default().push(value.to_string());
+        self
+    }
+
+    fn is_target_name(name: &str) -> bool {
+        name == "unix" || name == "windows" || name.starts_with("target_")
+    }
+}
+
+/// The tokens of a `cfg` predicate: identifiers, string literals, and the punctuation `(`, `)`, `,`, and `=`.
+struct CfgTokens<'t> {
+    text: &'t str,
+    peeked: Option<String>,
+}
+
+impl<'t> CfgTokens<'t> {
+    fn new(text: &'t str) -> Self {
+        Self { text, peeked: None }
+    }
+
+    fn predicate(&mut self) -> Result<CfgPredicate, String> {
+        let Some(name) = self.next()? else {
+            return Err("expected a predicate".to_string());
+        };
+        if !name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            return Err(format!("expected a predicate, found `{name}`"));
+        }
+
+        match self.peek()?.as_deref() {
+            Some("=") => {
+                self.next()?;
+                match self.next()? {
+                    Some(value) if value.starts_with('"') => {
+                        Ok(CfgPredicate::NameValue(name, value.trim_matches('"').to_string()))
+                    }
+                    _ => Err(format!("expected a string after `{name} =`")),
+                }
+            }
+            Some("(") => {
+                self.next()?;
+                let mut predicates = vec![];
+                while self.peek()?.as_deref() != Some(")") {
+                    predicates.push(self.predicate()?);
+                    if self.peek()?.as_deref() == Some(",") {
+                        self.next()?;
+                    } else if self.peek()?.as_deref() != Some(")") {
+                        return Err(format!("expected `,` or `)` in `{name}(...)`"));
+                    }
+                }
+                self.next()?;
+                match name.as_str() {
+                    "all" => Ok(CfgPredicate::All(predicates)),
+                    "any" => Ok(CfgPredicate::Any(predicates)),
+                    "not" if predicates.len() == 1 => Ok(CfgPredicate::Not(Box::new(predicates.remove(0)))),
+                    "not" => Err("`not` takes exactly one predicate".to_string()),
+                    _ => Err(format!("expected `all`, `any`, or `not`, found `{name}`")),
+                }
+            }
+            _ => Ok(CfgPredicate::Name(name)),
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<String>, String> {
+        if self.peeked.is_none() {
+            self.peeked = self.read()?;
+        }
+        Ok(self.peeked.clone())
+    }
+
+    fn next(&mut self) -> Result<Option<String>, String> {
+        match self.peeked.take() {
+            Some(token) => Ok(Some(token)),
+            None => self.read(),
+        }
+    }
+
+    fn read(&mut self) -> Result<Option<String>, String> {
+        self.text = self.text.trim_start();
+        let Some(c) = self.text.chars().next() else {
+            return Ok(None);
+        };
+        let len = if "(),=".contains(c) {
+            1
+        } else if c == '"' {
+            match self.text[1..].find('"') {
+                Some(end) => end + 2,
+                None => return Err("unterminated string".to_string()),
+            }
+        } else if c.is_alphanumeric() || c == '_' {
+            self.text
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(self.text.len())
+        } else {
+            return Err(format!("unexpected `{c}`"));
+        };
+        let (token, rest) = self.text.split_at(len);
+        self.text = rest;
+        Ok(Some(token.to_string()))
+    }
+}
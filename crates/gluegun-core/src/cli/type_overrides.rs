@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use accessors_rs::Accessors;
+use anyhow::Context;
+use serde::Deserialize;
+
+use super::take_key;
+
+/// Mappings for third-party types that gluegun does not know, which users can add to the metadata
+/// of any plugin, e.g.
+///
+/// ```toml
+/// [package.metadata.gluegun.py.type-overrides]
+/// "chrono::DateTime" = { ty = "datetime.datetime", repr = "String", to-repr = "{}.to_rfc3339()", from-repr = "{}.parse().unwrap()" }
+/// ```
+///
+/// Each key is the path of a type (without generic arguments); types with that path (or whose name is its last segment)
+/// are accepted by the parser as [`TypeKind::Custom`](`crate::idl::TypeKind::Custom`).
+/// Backends that support overrides map them according to the [`TypeOverride`][];
+/// the others report an error.
+#[derive(Clone, Debug, Default)]
+pub struct TypeOverrides {
+    overrides: BTreeMap<String, TypeOverride>,
+}
+
+/// How a third-party type is mapped. Can be written as just a string, which is the [`Self::ty`][].
+#[derive(Accessors, Deserialize, Debug, Clone)]
+#[accessors(get)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct TypeOverride {
+    /// The type in the target language (e.g., `datetime.datetime`).
+    ty: String,
+
+    /// The Rust type exchanged with the target language. If `None`, the overridden type itself is exchanged
+    /// (which requires the backend's binding framework to support it).
+    #[serde(default)]
+    repr: Option<String>,
+
+    /// Rust expression converting a value of the overridden type, written `{}`, into the `repr` type.
+    #[serde(default)]
+    to_repr: Option<String>,
+
+    /// Rust expression converting a value of the `repr` type, written `{}`, into the overridden type.
+    #[serde(default)]
+    from_repr: Option<String>,
+}
+
+impl TypeOverrides {
+    /// Name of the key in the plugin metadata.
+    pub const KEY: &'static str = "type-overrides";
+
+    /// Read the overrides in the plugin `metadata` (leaving it unchanged).
+    pub fn from_metadata(metadata: &serde_json::Value) -> anyhow::Result<Self> {
+        match metadata.get(Self::KEY) {
+            Some(value) => Self::from_value(value.clone()),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Remove the overrides from the plugin `metadata` and return them.
+    pub(crate) fn extract(metadata: &mut serde_json::Map<String, serde_json::Value>) -> anyhow::Result<Self> {
+        match take_key(metadata, Self::KEY)? {
+            Some(value) => Self::from_value(value).with_context(|| format!("parsing `{}`", Self::KEY)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    fn from_value(value: serde_json::Value) -> anyhow::Result<Self> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Entry {
+            Ty(String),
+            Override(TypeOverride),
+        }
+
+        let entries: BTreeMap<String, Entry> = serde_json::from_value(value)?;
+        let overrides = entries
+            .into_iter()
+            .map(|(path, entry)| {
+                let type_override = match entry {
+                    Entry::Ty(ty) => TypeOverride {
+                        ty,
+                        repr: None,
+                        to_repr: None,
+                        from_repr: None,
+                    },
+                    Entry::Override(type_override) => type_override,
+                };
+                (path, type_override)
+            })
+            .collect();
+        Ok(Self { overrides })
+    }
+
+    /// The paths of the overridden types.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.overrides.keys().map(|path| path.as_str())
+    }
+
+    /// The override for the type at `path`.
+    pub fn get(&self, path: &str) -> Option<&TypeOverride> {
+        self.overrides.get(path)
+    }
+
+    /// Like [`Self::get`][] but reports an error mentioning the span of `ty` if there is no override.
+    pub fn expect(&self, ty: &crate::idl::Ty, path: &str) -> anyhow::Result<&TypeOverride> {
+        match self.get(path) {
+            Some(type_override) => Ok(type_override),
+            None => anyhow::bail!(
+                "{span}: no `{key}` entry for third-party type `{path}`",
+                span = ty.span(),
+                key = Self::KEY
+            ),
+        }
+    }
+}
+
+impl TypeOverride {
+    /// The Rust type exchanged with the target language, given the `path` of the overridden type.
+    pub fn repr_or<'o>(&'o self, path: &'o str) -> &'o str {
+        self.repr.as_deref().unwrap_or(path)
+    }
+
+    /// Rust expression converting `expr`, a value of the overridden type, into the `repr` type.
+    pub fn to_repr_expr(&self, expr: &str) -> String {
+        apply_snippet(self.to_repr.as_deref(), expr)
+    }
+
+    /// Rust expression converting `expr`, a value of the `repr` type, into the overridden type.
+    pub fn from_repr_expr(&self, expr: &str) -> String {
+        apply_snippet(self.from_repr.as_deref(), expr)
+    }
+}
+
+/// Substitute `expr` for `{}` in `snippet`, parenthesizing it unless it is a plain variable or field access.
+fn apply_snippet(snippet: Option<&str>, expr: &str) -> String {
+    let Some(snippet) = snippet else {
+        return expr.to_string();
+    };
+    if expr.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+        snippet.replace("{}", expr)
+    } else {
+        snippet.replace("{}", &format!("({expr})"))
+    }
+}
@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use accessors_rs::Accessors;
+use serde::{Deserialize, Serialize};
+
+/// Context provided to the [`GlueGunHelper::generate_workspace`](`super::GlueGunHelper::generate_workspace`) implementation.
+#[derive(Accessors, Debug)]
+#[accessors(get)]
+pub struct WorkspaceCx {
+    /// The directory of the workspace, to which the [`WorkspaceFiles`](`crate::codegen::WorkspaceFiles`) are relative.
+    pub(super) workspace_root: PathBuf,
+
+    /// The crates generated by the helper for the members of the workspace, ordered by package name.
+    /// Members whose crate was not generated (e.g., that were never selected) are not included.
+    pub(super) crates: Vec<WorkspaceCrate>,
+}
+
+/// A crate generated by a helper for a member of the workspace (see [`WorkspaceCx::crates`][]).
+#[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
+#[accessors(get)]
+pub struct WorkspaceCrate {
+    /// The name of the package the crate was generated for.
+    package_name: String,
+
+    /// The name of the generated crate.
+    crate_name: String,
+
+    /// The directory of the generated crate.
+    path: PathBuf,
+}
+
+impl WorkspaceCrate {
+    pub fn new(package_name: impl Into<String>, crate_name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            package_name: package_name.into(),
+            crate_name: crate_name.into(),
+            path: path.into(),
+        }
+    }
+}
@@ -1,6 +1,21 @@
+mod bindings_manifest;
+
+mod cargo_options;
+pub use cargo_options::*;
+
 mod code_writer;
 pub use code_writer::CodeWriter;
 
+mod copy_out;
+pub use copy_out::*;
+
+mod debug_bindings;
+
+mod feature_set;
+pub use feature_set::*;
+
+mod file_header;
+
 mod library_crate;
 pub use library_crate::*;
 
@@ -8,4 +23,14 @@ mod helper_command;
 pub use helper_command::*;
 
 mod separator;
-pub use separator::*;
\ No newline at end of file
+pub use separator::*;
+
+mod source_map;
+pub use source_map::*;
+
+mod workspace_files;
+pub use workspace_files::*;
+
+mod readme;
+
+mod repr_c;
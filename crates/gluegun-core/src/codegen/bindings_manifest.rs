@@ -0,0 +1,141 @@
+use serde::Serialize;
+
+use crate::idl::{Idl, Item, Method, MethodCategory, Name, QualifiedName, Span};
+
+/// The symbols a helper generated for the items of the [`Idl`][], registered through
+/// [`LibraryCrate::add_binding`](`super::LibraryCrate::add_binding`) and
+/// [`LibraryCrate::add_member_binding`](`super::LibraryCrate::add_member_binding`),
+/// from which the `bindings.json` of the generated crate is assembled.
+#[derive(Default)]
+pub(super) struct BindingsManifest {
+    bindings: Vec<Binding>,
+}
+
+/// A symbol in the target language and the Rust item (or method of one) it binds.
+struct Binding {
+    symbol: String,
+    qname: QualifiedName,
+    member: Option<Name>,
+}
+
+/// The contents of `bindings.json`.
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct Manifest<'idl> {
+    crate_name: &'idl str,
+    rust_crate: String,
+    rust_crate_version: Option<&'idl String>,
+    bindings: Vec<ManifestEntry<'idl>>,
+}
+
+#[derive(Serialize)]
+struct ManifestEntry<'idl> {
+    /// The fully qualified name of the symbol in the target language, e.g. `myCrate.Functions.greet`.
+    symbol: &'idl str,
+
+    /// What the Rust item is, e.g. `function`, `resource`, or `method`.
+    kind: &'static str,
+
+    /// The path of the Rust item, e.g. `my_crate::greet` or `my_crate::Request::retries`.
+    rust: String,
+
+    /// Where the Rust item is defined.
+    span: &'idl Span,
+}
+
+impl BindingsManifest {
+    pub(super) fn add_binding(&mut self, symbol: String, qname: &QualifiedName, member: Option<&Name>) {
+        self.bindings.push(Binding {
+            symbol,
+            qname: qname.clone(),
+            member: member.cloned(),
+        });
+    }
+
+    /// True if the helper registered nothing, in which case no manifest is generated.
+    pub(super) fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    /// Render the `bindings.json` for the crate `crate_name` generated from `idl`.
+    /// Bindings of items (or members) that are not in `idl` are errors.
+    pub(super) fn render(&self, crate_name: &str, idl: &Idl) -> anyhow::Result<String> {
+        let mut bindings = vec![];
+        for binding in &self.bindings {
+            let Some(item) = idl.item(&binding.qname) else {
+                anyhow::bail!("binding `{}` to `{}`, which is not defined", binding.symbol, binding.qname.colon_colon());
+            };
+            let entry = match &binding.member {
+                None => ManifestEntry {
+                    symbol: &binding.symbol,
+                    kind: item_kind(item),
+                    rust: binding.qname.colon_colon(),
+                    span: item.span(),
+                },
+                Some(member) => {
+                    let Some(method) = methods(item).iter().find(|method| method.name() == member) else {
+                        anyhow::bail!(
+                            "binding `{}` to `{}::{member}`, which is not defined",
+                            binding.symbol,
+                            binding.qname.colon_colon()
+                        );
+                    };
+                    ManifestEntry {
+                        symbol: &binding.symbol,
+                        kind: method_kind(method),
+                        rust: format!("{}::{member}", binding.qname.colon_colon()),
+                        span: method.span(),
+                    }
+                }
+            };
+            bindings.push(entry);
+        }
+        bindings.sort_by(|a, b| a.symbol.cmp(b.symbol));
+
+        let manifest = Manifest {
+            crate_name,
+            rust_crate: idl.crate_name().to_string(),
+            rust_crate_version: idl.crate_metadata().version().as_ref(),
+            bindings,
+        };
+        let mut out = serde_json::to_string_pretty(&manifest)?;
+        out.push('\n');
+        Ok(out)
+    }
+}
+
+fn item_kind(item: &Item) -> &'static str {
+    match item {
+        Item::Resource(_) => "resource",
+        Item::Record(_) => "record",
+        Item::Newtype(_) => "newtype",
+        Item::Variant(_) => "variant",
+        Item::Enum(_) => "enum",
+        Item::Function(_) => "function",
+        Item::Interface(_) => "interface",
+        _ => "item",
+    }
+}
+
+fn method_kind(method: &Method) -> &'static str {
+    match method.category() {
+        MethodCategory::Constructor => "constructor",
+        MethodCategory::NamedConstructor => "named-constructor",
+        MethodCategory::BuilderMethod(_) => "builder-method",
+        MethodCategory::StaticMethod => "static-method",
+        MethodCategory::Getter(_) => "getter",
+        _ => "method",
+    }
+}
+
+fn methods(item: &Item) -> &[Method] {
+    match item {
+        Item::Resource(resource) => resource.methods(),
+        Item::Record(record) => record.methods(),
+        Item::Newtype(newtype) => newtype.methods(),
+        Item::Variant(variant) => variant.methods(),
+        Item::Enum(an_enum) => an_enum.methods(),
+        Item::Interface(interface) => interface.methods(),
+        _ => &[],
+    }
+}
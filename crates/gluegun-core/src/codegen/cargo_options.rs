@@ -0,0 +1,74 @@
+use std::{ffi::OsString, path::PathBuf, process::Command};
+
+use accessors_rs::Accessors;
+use serde::{Deserialize, Serialize};
+
+/// How the cargo commands run while generating a crate (`cargo new`, `cargo locate-project`,
+/// and `cargo install` for helper commands) are invoked, e.g. in a sandboxed CI without network access.
+///
+/// `cargo gluegun` sets them from its `--offline` and `--locked` flags and the cargo that invoked it
+/// (see [`LibraryCrate::set_cargo_options`](`super::LibraryCrate::set_cargo_options`)).
+#[derive(Accessors, Clone, Debug, Default, Serialize, Deserialize)]
+#[accessors(get)]
+#[serde(default)]
+pub struct CargoOptions {
+    /// The cargo binary to run; if `None`, the one in `$CARGO` (set when run by cargo), or else `cargo` on the `PATH`.
+    cargo: Option<PathBuf>,
+
+    /// Pass `--offline`. Missing helper commands are then reported rather than installed.
+    offline: bool,
+
+    /// Pass `--locked`.
+    locked: bool,
+}
+
+impl CargoOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `cargo` instead of the default (see [`Self::cargo`][]).
+    pub fn with_cargo(mut self, cargo: impl Into<PathBuf>) -> Self {
+        self.cargo = Some(cargo.into());
+        self
+    }
+
+    /// Pass `--offline` to cargo commands.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Pass `--locked` to cargo commands.
+    pub fn with_locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// The cargo binary to run (see [`Self::cargo`][]).
+    pub fn cargo_binary(&self) -> OsString {
+        match &self.cargo {
+            Some(cargo) => cargo.clone().into_os_string(),
+            None => std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()),
+        }
+    }
+
+    /// The flags passed to cargo commands, e.g. `["--offline"]`.
+    pub fn flags(&self) -> Vec<String> {
+        let mut flags = vec![];
+        if self.offline {
+            flags.push("--offline".to_string());
+        }
+        if self.locked {
+            flags.push("--locked".to_string());
+        }
+        flags
+    }
+
+    /// A command running `cargo {subcommand}` with these options, to which the caller adds the arguments of the subcommand.
+    pub fn command(&self, subcommand: &str) -> Command {
+        let mut command = Command::new(self.cargo_binary());
+        command.arg(subcommand).args(self.flags());
+        command
+    }
+}
@@ -1,5 +1,8 @@
 use std::io::Write;
 
+use super::library_crate::LibraryFileWriter;
+use crate::idl::Span;
+
 /// A `CodeWriter` can be used with the [`std::fmt::write`][] macro to generate indented code.
 ///
 /// Example:
@@ -19,16 +22,36 @@ use std::io::Write;
 /// }
 /// ```
 pub struct CodeWriter<'w> {
-    writer: Box<dyn Write + 'w>,
+    writer: LibraryFileWriter<'w>,
     indent: usize,
+
+    /// Number of lines written so far.
+    lines: usize,
 }
 
 impl<'w> CodeWriter<'w> {
-    pub(crate) fn new(writer: impl Write + 'w) -> Self {
+    pub(super) fn new(writer: LibraryFileWriter<'w>) -> Self {
         Self {
-            writer: Box::new(writer),
+            writer,
             indent: 0,
+            lines: 0,
+        }
+    }
+
+    /// Invoke `op` to write the code generated from the Rust source at `span`
+    /// (typically the item being bound), recording the lines it writes in the file's
+    /// [`SourceMap`](`super::SourceMap`). Calls may nest (e.g., for each method of a type).
+    pub fn with_span<R>(
+        &mut self,
+        span: &Span,
+        op: impl FnOnce(&mut Self) -> anyhow::Result<R>,
+    ) -> anyhow::Result<R> {
+        let start_line = self.lines + 1;
+        let result = op(self)?;
+        if self.lines >= start_line {
+            self.writer.source_map.push(start_line, self.lines, span);
         }
+        Ok(result)
     }
 
     pub fn write_fmt(&mut self, fmt: std::fmt::Arguments<'_>) -> anyhow::Result<()> {
@@ -39,6 +62,23 @@ impl<'w> CodeWriter<'w> {
             self.indent -= 1;
         }
 
+        self.write_line(&string)?;
+
+        if string.ends_with("{") || string.ends_with("(") || string.ends_with("[") {
+            self.indent += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Write `line` at the current indentation without adjusting the indentation for
+    /// any brackets it starts or ends with. Use this for text that is not code
+    /// in the language being written, such as comments containing Rust code.
+    pub fn write_verbatim(&mut self, line: impl std::fmt::Display) -> anyhow::Result<()> {
+        self.write_line(&line.to_string())
+    }
+
+    fn write_line(&mut self, string: &str) -> anyhow::Result<()> {
         write!(
             self.writer,
             "{:indent$}{}\n",
@@ -46,11 +86,7 @@ impl<'w> CodeWriter<'w> {
             string,
             indent = self.indent * 4
         )?;
-
-        if string.ends_with("{") || string.ends_with("(") || string.ends_with("[") {
-            self.indent += 1;
-        }
-
+        self.lines += string.matches('\n').count() + 1;
         Ok(())
     }
 }
@@ -0,0 +1,14 @@
+use crate::idl::FunctionOutput;
+
+/// Rust expression for the value returned by `call`, a call to a function with the given `output`,
+/// with borrowed data (e.g., `-> &str`) copied out into the corresponding owned type (e.g., `String`)
+/// so that it can be returned to the foreign language (see [`FunctionOutput::requires_copy_out`][]).
+///
+/// If the function returns a `Result`, `call` must already yield its success value (e.g., with `?`).
+pub fn copy_out(output: &FunctionOutput, call: &str) -> String {
+    if output.requires_copy_out() {
+        format!("std::borrow::ToOwned::to_owned({call})")
+    } else {
+        call.to_string()
+    }
+}
@@ -0,0 +1,60 @@
+use super::CodeWriter;
+
+/// Generates `src/gluegun_debug.rs`, which counts the wrappers of Rust objects (e.g., resources)
+/// held by the foreign language, e.g.
+///
+/// ```rust,ignore
+/// pub struct LiveObject(());
+///
+/// impl LiveObject {
+///     pub fn new() -> Self { /* count one more live object */ }
+/// }
+///
+/// impl Drop for LiveObject { /* count one less */ }
+///
+/// pub fn live_objects() -> usize { /* the count */ }
+/// ```
+///
+/// Helpers put a `LiveObject` in each wrapper they generate (or call `acquire` and `release`
+/// when they create and free one) and expose `live_objects` to the foreign language.
+pub(super) fn generate(gluegun_debug_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+    write!(gluegun_debug_rs, "//! Counts the wrappers of Rust objects that the foreign language has not freed yet.")?;
+    write!(gluegun_debug_rs, "")?;
+    write!(
+        gluegun_debug_rs,
+        "static LIVE_OBJECTS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);"
+    )?;
+    write!(gluegun_debug_rs, "")?;
+    write!(gluegun_debug_rs, "/// Count a wrapper created for the foreign language.")?;
+    write!(gluegun_debug_rs, "pub fn acquire() {{")?;
+    write!(gluegun_debug_rs, "LIVE_OBJECTS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);")?;
+    write!(gluegun_debug_rs, "}}")?;
+    write!(gluegun_debug_rs, "")?;
+    write!(gluegun_debug_rs, "/// Uncount a wrapper freed by the foreign language.")?;
+    write!(gluegun_debug_rs, "pub fn release() {{")?;
+    write!(gluegun_debug_rs, "LIVE_OBJECTS.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);")?;
+    write!(gluegun_debug_rs, "}}")?;
+    write!(gluegun_debug_rs, "")?;
+    write!(gluegun_debug_rs, "/// The number of wrappers created for the foreign language that it has not freed yet.")?;
+    write!(gluegun_debug_rs, "pub fn live_objects() -> usize {{")?;
+    write!(gluegun_debug_rs, "LIVE_OBJECTS.load(std::sync::atomic::Ordering::Relaxed)")?;
+    write!(gluegun_debug_rs, "}}")?;
+    write!(gluegun_debug_rs, "")?;
+    write!(gluegun_debug_rs, "/// Held by a wrapper: counted as live from its creation until it is dropped.")?;
+    write!(gluegun_debug_rs, "pub struct LiveObject(());")?;
+    write!(gluegun_debug_rs, "")?;
+    write!(gluegun_debug_rs, "impl LiveObject {{")?;
+    write!(gluegun_debug_rs, "#[allow(clippy::new_without_default)]")?;
+    write!(gluegun_debug_rs, "pub fn new() -> Self {{")?;
+    write!(gluegun_debug_rs, "acquire();")?;
+    write!(gluegun_debug_rs, "Self(())")?;
+    write!(gluegun_debug_rs, "}}")?;
+    write!(gluegun_debug_rs, "}}")?;
+    write!(gluegun_debug_rs, "")?;
+    write!(gluegun_debug_rs, "impl Drop for LiveObject {{")?;
+    write!(gluegun_debug_rs, "fn drop(&mut self) {{")?;
+    write!(gluegun_debug_rs, "release();")?;
+    write!(gluegun_debug_rs, "}}")?;
+    write!(gluegun_debug_rs, "}}")?;
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use std::collections::BTreeSet;
+
+/// An optional capability of a generated crate that needs a dependency of its own,
+/// declared with [`LibraryCrate::require_feature`](`super::LibraryCrate::require_feature`).
+///
+/// Each one becomes a Cargo feature of the generated crate (e.g., `indexmap`), enabled by default,
+/// that enables an optional dependency along with the features of other dependencies that go with it
+/// (see [`AddDependency::feature_with`](`super::AddDependency::feature_with`)).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Feature {
+    /// `IndexMap` and `IndexSet`, from the `indexmap` crate.
+    IndexMap,
+
+    /// Dates and times from the `chrono` crate.
+    Chrono,
+
+    /// An async runtime (`tokio`) to drive the futures returned by `async` functions.
+    AsyncRuntime,
+
+    /// Streams (`impl Stream<Item = T>`), from the `futures` crate.
+    Streams,
+}
+
+impl Feature {
+    /// Name of the Cargo feature in the generated crate.
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::IndexMap => "indexmap",
+            Feature::Chrono => "chrono",
+            Feature::AsyncRuntime => "async-runtime",
+            Feature::Streams => "streams",
+        }
+    }
+
+    /// The crate providing the capability, the version to request, and the features it needs.
+    pub(crate) fn dependency(self) -> (&'static str, &'static str, &'static [&'static str]) {
+        match self {
+            Feature::IndexMap => ("indexmap", "2", &[]),
+            Feature::Chrono => ("chrono", "0.4", &[]),
+            Feature::AsyncRuntime => ("tokio", "1", &["rt-multi-thread"]),
+            Feature::Streams => ("futures", "0.3", &[]),
+        }
+    }
+}
+
+/// The [features](`Feature`) required by the code of a generated crate.
+/// Code generators that discover them while generating code can collect them here
+/// and hand them to the [`LibraryCrate`](`super::LibraryCrate`) afterwards.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeatureSet {
+    features: BTreeSet<Feature>,
+}
+
+impl FeatureSet {
+    /// Record that `feature` is required. Returns false if it already was.
+    pub fn insert(&mut self, feature: Feature) -> bool {
+        self.features.insert(feature)
+    }
+
+    /// True if `feature` is required.
+    pub fn contains(&self, feature: Feature) -> bool {
+        self.features.contains(&feature)
+    }
+
+    /// True if no feature is required.
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    /// The required features, in a canonical order.
+    pub fn iter(&self) -> impl Iterator<Item = Feature> + '_ {
+        self.features.iter().copied()
+    }
+}
+
+impl Extend<Feature> for FeatureSet {
+    fn extend<T: IntoIterator<Item = Feature>>(&mut self, iter: T) {
+        self.features.extend(iter);
+    }
+}
+
+impl<'a> IntoIterator for &'a FeatureSet {
+    type Item = Feature;
+    type IntoIter = std::iter::Copied<std::collections::btree_set::Iter<'a, Feature>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.features.iter().copied()
+    }
+}
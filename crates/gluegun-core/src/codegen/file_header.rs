@@ -0,0 +1,35 @@
+use std::path::Path;
+
+/// The header (see [`LibraryCrate::set_file_header`](`super::LibraryCrate::set_file_header`)) for the file at `path`:
+/// the lines of `text` as comments in the syntax its extension calls for, followed by an empty line.
+/// Returns `None` for files whose format has no comments (e.g., JSON) or is unknown.
+pub(super) fn file_header(text: &str, path: &Path) -> Option<Vec<String>> {
+    let (start, line_prefix, end) = comment_syntax(path)?;
+
+    let mut lines = vec![];
+    if let Some(start) = start {
+        lines.push(start.to_string());
+    }
+    for line in text.trim_end().lines() {
+        lines.push(format!("{line_prefix}{line}").trim_end().to_string());
+    }
+    if let Some(end) = end {
+        lines.push(end.to_string());
+    }
+    lines.push(String::new());
+    Some(lines)
+}
+
+/// The comment syntax of the file at `path`, by extension: the line opening a block comment (if any),
+/// the prefix of each line of the comment, and the line closing the block comment (if any).
+fn comment_syntax(path: &Path) -> Option<(Option<&'static str>, &'static str, Option<&'static str>)> {
+    let extension = path.extension()?.to_str()?;
+    match extension {
+        "rs" | "java" | "kt" | "scala" | "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "go" | "swift" | "dart"
+        | "js" | "mjs" | "ts" | "proto" | "wit" => Some((None, "// ", None)),
+        "py" | "pyi" | "toml" | "yaml" | "yml" | "sh" | "rb" | "r" | "cmake" => Some((None, "# ", None)),
+        "lua" | "sql" | "hs" => Some((None, "-- ", None)),
+        "html" | "xml" => Some((Some("<!--"), "  ", Some("-->"))),
+        _ => None,
+    }
+}
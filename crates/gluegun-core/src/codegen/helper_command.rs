@@ -1,6 +1,9 @@
-use std::process::Command;
+use std::{collections::BTreeMap, path::Path, process::Command};
 
 use accessors_rs::Accessors;
+use serde::{Deserialize, Serialize};
+
+use super::CargoOptions;
 
 /// Options for configuring and registering helper utilities that ought to be available.
 /// These are extra commands, like `cargo-component` for WASM, that need to be installed
@@ -32,8 +35,8 @@ impl HelperCommand {
         )
     }
 
-    /// Install the helper command if necessary.
-    pub(crate) fn install_if_needed(&self) -> anyhow::Result<()> {
+    /// Install the helper command if necessary, with cargo invoked according to `cargo_options`.
+    pub(crate) fn install_if_needed(&self, cargo_options: &CargoOptions) -> anyhow::Result<()> {
         if which::which(&self.name).is_ok() {
             // Command is already present on the PATH
             return Ok(());
@@ -53,9 +56,15 @@ impl HelperCommand {
                     message
                 );
             }
+            InstallOption::CargoInstall { ref crate_name } if *cargo_options.offline() => {
+                anyhow::bail!(
+                    "helper command `{}` is not installed and cannot be installed offline; run `cargo install {crate_name}`",
+                    self.name
+                );
+            }
             InstallOption::CargoInstall { ref crate_name } => {
-                let status = Command::new("cargo")
-                    .arg("install")
+                let status = cargo_options
+                    .command("install")
                     .arg(crate_name)
                     .status()
                     .map_err(|e| {
@@ -70,6 +79,43 @@ impl HelperCommand {
 
         Ok(())
     }
+
+    /// How to install the helper command, as configured with [`HelperCommandGuard`][], if known.
+    fn install_help(&self) -> Option<String> {
+        match &self.install_option {
+            InstallOption::Fail => None,
+            InstallOption::FailWithMessage(message) => Some(message.clone()),
+            InstallOption::CargoInstall { crate_name } => Some(format!("install it with `cargo install {crate_name}`")),
+        }
+    }
+
+    /// Look for the helper command on the `PATH` and ask it for its version, without installing it.
+    pub(crate) fn report(&self) -> ToolReport {
+        let path = which::which(&self.name).ok();
+        ToolReport {
+            name: self.name.clone(),
+            version: path.as_deref().and_then(version_line),
+            found: path.map(|path| path.display().to_string()),
+            help: self.install_help(),
+        }
+    }
+}
+
+/// The first line that the command at `path` prints when invoked with `--version`
+/// (on stdout or, as some tools do, on stderr), if it succeeds.
+fn version_line(path: &Path) -> Option<String> {
+    let output = Command::new(path).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    stdout
+        .lines()
+        .chain(stderr.lines())
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
 }
 
 #[derive(Debug)]
@@ -118,3 +164,60 @@ impl<'g> HelperCommandGuard<'g> {
         self
     }
 }
+
+/// The external tools that the crates generated by a helper need to build (e.g., `javac`),
+/// which `cargo gluegun doctor` checks for (see [`GlueGunHelper::required_tools`](`crate::cli::GlueGunHelper::required_tools`)).
+#[derive(Default)]
+pub struct RequiredTools {
+    helper_commands: BTreeMap<String, HelperCommand>,
+
+    /// How to set each environment variable, by name.
+    env_vars: BTreeMap<String, String>,
+}
+
+impl RequiredTools {
+    /// Require the command `name` on the `PATH`. Configure how to install it
+    /// as for [`LibraryCrate::require_helper_command`](`super::LibraryCrate::require_helper_command`),
+    /// which `cargo gluegun doctor` suggests if it is missing.
+    pub fn require_helper_command(&mut self, name: &str) -> HelperCommandGuard<'_> {
+        let command = self
+            .helper_commands
+            .entry(name.to_string())
+            .or_insert_with(|| HelperCommand::new(name.to_string()));
+        HelperCommandGuard::new(command)
+    }
+
+    /// Require the environment variable `name` to be set, where `help` says how to set it.
+    pub fn require_env_var(&mut self, name: &str, help: impl Into<String>) {
+        self.env_vars.insert(name.to_string(), help.into());
+    }
+
+    /// Check for each of the required commands, then environment variables, ordered by name.
+    pub(crate) fn check(&self) -> Vec<ToolReport> {
+        let commands = self.helper_commands.values().map(HelperCommand::report);
+        let env_vars = self.env_vars.iter().map(|(name, help)| ToolReport {
+            name: name.clone(),
+            found: std::env::var(name).ok().filter(|value| !value.is_empty()),
+            version: None,
+            help: Some(help.clone()),
+        });
+        commands.chain(env_vars).collect()
+    }
+}
+
+/// Whether a required tool (see [`RequiredTools`][]) is available, as reported to `cargo gluegun doctor`.
+#[derive(Accessors, Debug, Serialize, Deserialize)]
+#[accessors(get)]
+pub struct ToolReport {
+    /// The name of the command (e.g., `javac`) or environment variable (e.g., `JAVA_HOME`).
+    name: String,
+
+    /// Where the command was found or the value of the variable, or `None` if it is missing.
+    found: Option<String>,
+
+    /// The first line that the command printed when asked for its version, if any.
+    version: Option<String>,
+
+    /// How to install the command or set the variable, if known.
+    help: Option<String>,
+}
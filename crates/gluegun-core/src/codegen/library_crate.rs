@@ -1,10 +1,14 @@
-use super::{CodeWriter, HelperCommand, HelperCommandGuard};
-use crate::cli::GlueGunDestinationCrate;
+use super::{bindings_manifest::BindingsManifest, readme::Readme, repr_c::ReprCGenerator, CargoOptions, CodeWriter, Feature, FeatureSet, HelperCommand, HelperCommandGuard, SourceMap};
+use crate::{
+    cli::{CustomSrc, ExtraFile, GlueGunDestinationCrate},
+    idl::{Idl, Name, QualifiedName},
+};
 use accessors_rs::Accessors;
 use anyhow::Context;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
+    hash::{DefaultHasher, Hash, Hasher},
     path::{Path, PathBuf},
     process::Command,
 };
@@ -24,9 +28,37 @@ pub struct LibraryCrate {
 
     helper_commands: BTreeMap<String, HelperCommand>,
     cargo_new_command: Box<dyn Fn(&Self) -> Command>,
+
+    /// How cargo is invoked (see [`Self::set_cargo_options`][]).
+    #[accessors(get)]
+    cargo_options: CargoOptions,
+
     dependencies: Vec<Dependency>,
+
+    /// The optional capabilities required by the generated code (see [`Self::require_feature`][]).
+    #[accessors(get)]
+    features: FeatureSet,
+
     directories: Vec<PathBuf>,
     files: BTreeMap<PathBuf, Vec<u8>>,
+
+    /// The sources of the extra files added with [`Self::add_extra_file`][].
+    extra_files: BTreeSet<PathBuf>,
+
+    /// The text prepended to each file added with [`Self::add_file`][] (see [`Self::set_file_header`][]).
+    file_header: Option<String>,
+
+    readme: Readme,
+
+    /// Whether to generate `bindings.json` (see [`Self::enable_bindings_manifest`][]).
+    bindings_manifest_enabled: bool,
+    bindings_manifest: BindingsManifest,
+
+    /// Whether to generate `src/repr_c.rs` (see [`Self::enable_repr_c_records`][]).
+    repr_c_records_enabled: bool,
+
+    /// Whether to generate `src/gluegun_debug.rs` (see [`Self::enable_debug_bindings`][]).
+    debug_bindings_enabled: bool,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -34,6 +66,15 @@ pub enum CrateType {
     CDyLib,
 }
 
+impl CrateType {
+    /// The name of the crate type in the manifest (e.g., `cdylib`).
+    fn as_str(&self) -> &'static str {
+        match self {
+            CrateType::CDyLib => "cdylib",
+        }
+    }
+}
+
 impl LibraryCrate {
     /// Create an instance from a [`GlueGunDestinationCrate`][].
     /// This has no immediate effect.
@@ -45,14 +86,14 @@ impl LibraryCrate {
             crate_path: args.path.clone(),
             helper_commands: BTreeMap::default(),
             cargo_new_command: Box::new(|this| {
-                let mut cargo_command = std::process::Command::new("cargo");
-                cargo_command.arg("new");
+                let mut cargo_command = this.cargo_options().command("new");
                 cargo_command.arg("--lib");
                 cargo_command.arg(this.crate_path());
                 cargo_command.arg("--name");
                 cargo_command.arg(this.crate_name());
                 cargo_command
             }),
+            cargo_options: Default::default(),
             lib_configuration: TargetConfiguration {
                 crate_types: vec![CrateType::CDyLib],
                 name: None,
@@ -60,7 +101,15 @@ impl LibraryCrate {
             },
             directories: Default::default(),
             files: Default::default(),
+            extra_files: Default::default(),
+            file_header: None,
             dependencies: Default::default(),
+            features: Default::default(),
+            readme: Default::default(),
+            bindings_manifest_enabled: false,
+            bindings_manifest: Default::default(),
+            repr_c_records_enabled: false,
+            debug_bindings_enabled: false,
         }
     }
 
@@ -71,6 +120,23 @@ impl LibraryCrate {
         self.cargo_new_command = Box::new(cargo_command);
     }
 
+    /// Configure how cargo is invoked by [`Self::generate`][], including by the default `cargo new` command
+    /// (see [`CargoOptions`][]).
+    ///
+    /// gluegun sets them from the flags given to `cargo gluegun` before invoking the helper.
+    pub fn set_cargo_options(&mut self, cargo_options: CargoOptions) {
+        self.cargo_options = cargo_options;
+    }
+
+    /// Set a `text` (e.g., a license notice) to prepend to every file added afterwards with [`Self::add_file`][],
+    /// as a comment in the syntax of the file's extension (e.g., `// ` for `.rs` and `.java`, `# ` for `.py`).
+    /// Files whose format has no comments (e.g., JSON) or is unknown are left as they are, as are extra files.
+    ///
+    /// gluegun sets it from the `file-header` key of the plugin metadata before invoking the helper.
+    pub fn set_file_header(&mut self, text: impl ToString) {
+        self.file_header = Some(text.to_string());
+    }
+
     /// Add a required helper command needed by create creation, such as `cargo-component` for WASM.
     /// The name of the command must be an executable.
     /// Before beginning to create the crate, we will probe for the executable in PATH and, if it is not found, attempt to install it.
@@ -85,13 +151,11 @@ impl LibraryCrate {
     }
 
     /// Generate the crate on disk. May fail.
+    ///
+    /// If the crate was generated before, it is updated in place: files whose contents are unchanged
+    /// are not rewritten (so that their modification times are preserved and downstream builds
+    /// are not invalidated), and files generated the previous time but not this time are removed.
     pub fn generate(mut self) -> anyhow::Result<()> {
-        // FIXME: we shouldn't just delete the old thing
-        if self.crate_path.exists() {
-            std::fs::remove_dir_all(&self.crate_path)
-                .with_context(|| format!("removing {}", self.crate_path.display()))?;
-        }
-
         self.execute()
             .with_context(|| format!("generating crate at path {}", self.crate_path.display()))
     }
@@ -102,51 +166,195 @@ impl LibraryCrate {
 
         self.install_helper_commands()?;
 
-        let mut cargo_new_command = (self.cargo_new_command)(self);
-        eprintln!("cargo_command: {:?}", cargo_new_command);
-        let status = cargo_new_command.status()?;
-        if !status.success() {
-            anyhow::bail!(
-                "cargo command `{cargo_new_command:?}` failed with exit status `{status}`",
-            );
-        }
-
         let cargo_toml_path = self.crate_path.join("Cargo.toml");
-        self.lib_configuration
-            .emit_target(&cargo_toml_path, "[lib]")?;
+        if !cargo_toml_path.exists() {
+            // Something other than a crate is in the way; `cargo new` requires the directory not to exist.
+            if self.crate_path.exists() {
+                std::fs::remove_dir_all(&self.crate_path)
+                    .with_context(|| format!("removing {}", self.crate_path.display()))?;
+            }
 
-        for dependency in &self.dependencies {
-            eprintln!("adding {dependency:?}");
-            dependency.execute_cargo_add(&self.crate_name)?;
+            let mut cargo_new_command = (self.cargo_new_command)(self);
+            eprintln!("cargo_command: {:?}", cargo_new_command);
+            let status = cargo_new_command.status()?;
+            if !status.success() {
+                anyhow::bail!(
+                    "cargo command `{cargo_new_command:?}` failed with exit status `{status}`",
+                );
+            }
         }
 
+        let manifest = self.manifest_with_dependencies(&cargo_toml_path)?;
+
         for directory in &self.directories {
             let crate_directory = self.crate_path.join(directory);
-            eprintln!("creating {crate_directory:?}");
             std::fs::create_dir_all(&crate_directory).with_context(|| {
                 format!("creating directory at `{}`", crate_directory.display())
             })?;
         }
 
-        for (path, data) in &self.files {
-            let file_path = self.crate_path.join(path);
-            eprintln!("writing to {file_path:?}");
+        let mut files: Vec<(&Path, &[u8])> = self
+            .files
+            .iter()
+            .map(|(path, data)| (path.as_path(), &data[..]))
+            .collect();
+        files.push((Path::new("Cargo.toml"), manifest.as_bytes()));
+        self.write_files(&files)
+    }
+
+    /// Returns the contents of the manifest at `cargo_toml_path` with its dependency tables
+    /// replaced by the dependencies added to this crate. All dependencies are added in one edit
+    /// (rather than running `cargo add` for each), in a canonical order so that the resulting
+    /// `Cargo.toml` does not depend on the order in which the helper happened to declare them.
+    fn manifest_with_dependencies(&self, cargo_toml_path: &Path) -> anyhow::Result<String> {
+        let contents = std::fs::read_to_string(cargo_toml_path)
+            .with_context(|| format!("failed to read `{}`", cargo_toml_path.display()))?;
+        let mut manifest: toml_edit::DocumentMut = contents
+            .parse()
+            .with_context(|| format!("failed to parse `{}`", cargo_toml_path.display()))?;
+
+        let manifest_dir = self
+            .crate_path
+            .canonicalize()
+            .with_context(|| format!("resolving `{}`", self.crate_path.display()))?;
+
+        let mut dependencies: Vec<&Dependency> = self.dependencies.iter().collect();
+
+        // The dependencies of the required features, unless also added as regular dependencies.
+        let feature_dependencies: Vec<(Feature, Dependency)> = self
+            .features
+            .iter()
+            .filter_map(|feature| {
+                let (crate_name, version, features) = feature.dependency();
+                if self.dependencies.iter().any(|dependency| dependency.kind.is_none() && dependency.crate_name == crate_name) {
+                    return None;
+                }
+                let dependency = Dependency {
+                    crate_name: crate_name.to_string(),
+                    version: Some(version.to_string()),
+                    features: features.iter().map(|feature| feature.to_string()).collect(),
+                    optional: true,
+                    ..Default::default()
+                };
+                Some((feature, dependency))
+            })
+            .collect();
+        dependencies.extend(feature_dependencies.iter().map(|(_, dependency)| dependency));
+        dependencies.sort_by(|a, b| (&a.kind, &a.crate_name).cmp(&(&b.kind, &b.crate_name)));
+
+        for table_name in ["lib", "dependencies", "build-dependencies", "dev-dependencies", "features"] {
+            manifest.remove(table_name);
+        }
+        manifest.insert("lib", self.lib_configuration.to_toml());
+        if let Some(package) = manifest.get_mut("package").and_then(|package| package.as_table_like_mut()) {
+            package.insert("edition", toml_edit::value(self.lib_configuration.edition()));
+        }
+        for dependency in dependencies {
+            let table_name = match dependency.kind {
+                None => "dependencies",
+                Some(DependencyKind::Build) => "build-dependencies",
+                Some(DependencyKind::Dev) => "dev-dependencies",
+            };
+            let table = manifest
+                .entry(table_name)
+                .or_insert_with(toml_edit::table)
+                .as_table_mut()
+                .expect("dependency tables are tables");
+            table.insert(&dependency.crate_name, dependency.to_toml(&manifest_dir)?);
+        }
+
+        if !self.features.is_empty() {
+            manifest.insert("features", self.features_table(&feature_dependencies));
+        }
 
+        Ok(manifest.to_string())
+    }
+
+    /// The `[features]` table of the manifest: a feature for each required [`Feature`][], enabled by default,
+    /// which enables its optional dependency (if any, see `feature_dependencies`) and the features of
+    /// other dependencies that go with it (see [`AddDependency::feature_with`][]), e.g.
+    ///
+    /// ```toml
+    /// [features]
+    /// default = ["indexmap"]
+    /// indexmap = ["dep:indexmap", "pyo3/indexmap"]
+    /// ```
+    fn features_table(&self, feature_dependencies: &[(Feature, Dependency)]) -> toml_edit::Item {
+        let mut table = toml_edit::Table::new();
+        table.insert("default", toml_edit::value(self.features.iter().map(Feature::name).collect::<toml_edit::Array>()));
+        for feature in &self.features {
+            let mut enables: Vec<String> = feature_dependencies
+                .iter()
+                .filter(|(f, _)| *f == feature)
+                .map(|(_, dependency)| format!("dep:{}", dependency.crate_name))
+                .collect();
+            let mut dependency_features: BTreeSet<String> = BTreeSet::new();
+            for dependency in &self.dependencies {
+                for (f, dependency_feature) in &dependency.features_with {
+                    if *f == feature {
+                        dependency_features.insert(format!("{}/{dependency_feature}", dependency.crate_name));
+                    }
+                }
+            }
+            enables.extend(dependency_features);
+            table.insert(feature.name(), toml_edit::value(enables.into_iter().collect::<toml_edit::Array>()));
+        }
+        toml_edit::Item::Table(table)
+    }
+
+    /// Write `files` (paths relative to the crate) in parallel, skipping those whose contents on disk
+    /// already have the same hash. Files recorded as generated the previous time (see [`GENERATED_FILES_NAME`][])
+    /// but not among `files` are removed.
+    fn write_files(&self, files: &[(&Path, &[u8])]) -> anyhow::Result<()> {
+        let record_path = self.crate_path.join(GENERATED_FILES_NAME);
+        let previous: GeneratedFiles = std::fs::read(&record_path)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .unwrap_or_default();
+
+        let mut generated = GeneratedFiles::default();
+        for &(path, data) in files {
+            generated.files.insert(path.to_path_buf(), content_hash(data));
+        }
+
+        for path in previous.files.keys() {
+            if !generated.files.contains_key(path) {
+                let file_path = self.crate_path.join(path);
+                eprintln!("removing {file_path:?}");
+                if let Err(error) = std::fs::remove_file(&file_path) {
+                    if error.kind() != std::io::ErrorKind::NotFound {
+                        return Err(error).with_context(|| format!("removing `{}`", file_path.display()));
+                    }
+                }
+            }
+        }
+
+        let crate_path = &self.crate_path;
+        for_each_parallel(files, |&(path, data)| {
+            let file_path = crate_path.join(path);
+            if let Ok(existing) = std::fs::read(&file_path) {
+                if content_hash(&existing) == generated.files[path] {
+                    return Ok(());
+                }
+            }
+
+            eprintln!("writing to {file_path:?}");
             if let Some(dir_path) = file_path.parent() {
                 std::fs::create_dir_all(dir_path)
                     .with_context(|| format!("creating directory at `{}`", dir_path.display()))?;
             }
-
             std::fs::write(&file_path, data)
-                .with_context(|| format!("writing to file at `{}`", file_path.display()))?;
-        }
+                .with_context(|| format!("writing to file at `{}`", file_path.display()))
+        })?;
 
-        Ok(())
+        let json = serde_json::to_vec_pretty(&generated).expect("generated files can be serialized");
+        std::fs::write(&record_path, json)
+            .with_context(|| format!("writing to file at `{}`", record_path.display()))
     }
 
     fn install_helper_commands(&mut self) -> anyhow::Result<()> {
         for helper_command in self.helper_commands.values() {
-            helper_command.install_if_needed()?;
+            helper_command.install_if_needed(&self.cargo_options)?;
         }
         Ok(())
     }
@@ -176,8 +384,10 @@ impl LibraryCrate {
             root: PathBuf,
         }
 
-        let output = Command::new("cargo")
-            .args(["locate-project", "--workspace"])
+        let output = self
+            .cargo_options
+            .command("locate-project")
+            .arg("--workspace")
             .output()
             .context("failed to execute cargo locate-project")?;
 
@@ -195,8 +405,20 @@ impl LibraryCrate {
         Ok(project_info.root)
     }
 
+    /// Declare that the generated code requires an optional capability,
+    /// such as support for `IndexMap`. The crate gets a Cargo feature for it, enabled by default,
+    /// with the dependency that provides it (see [`Feature`][]). Requiring a feature again has no effect.
+    pub fn require_feature(&mut self, feature: Feature) {
+        self.features.insert(feature);
+    }
+
     /// Add a dependency to the crate with the given name.
     /// Returns a builder that can be used to configure additional options.
+    ///
+    /// Give the dependency a [`version`](`AddDependency::version`), a [`path`](`AddDependency::path`), or both
+    /// (crates with path dependencies can only be published if they have a version too).
+    /// Without either, the generated crate accepts any version (`"*"`), which cargo resolves to the latest one
+    /// but which crates.io does not accept either.
     pub fn add_dependency(&mut self, crate_name: &str) -> AddDependency<'_> {
         AddDependency {
             krate: self,
@@ -208,10 +430,36 @@ impl LibraryCrate {
                 features: Default::default(),
                 no_default_features: Default::default(),
                 optional: Default::default(),
+                features_with: Default::default(),
             },
         }
     }
 
+    /// Add a dependency on a crate published to crates.io (e.g., a runtime support crate for your helper),
+    /// unless `local_path` contains a crate, in which case that is used via a path dependency instead
+    /// (keeping the version, so that the generated crate can still be published).
+    ///
+    /// This lets a helper that is running from its own workspace (e.g., in tests) depend on the local,
+    /// possibly unpublished, version of the crate. Typically `local_path` is computed relative to
+    /// `env!("CARGO_MANIFEST_DIR")`; once the helper is installed with `cargo install`,
+    /// that points into cargo's registry, where the probe fails and the published version is used.
+    ///
+    /// Returns a builder that can be used to configure additional options.
+    pub fn add_dependency_published_or_path(
+        &mut self,
+        crate_name: &str,
+        version: impl ToString,
+        local_path: impl AsRef<Path>,
+    ) -> AddDependency<'_> {
+        let local_path = local_path.as_ref();
+        let dependency = self.add_dependency(crate_name).version(version);
+        if local_path.join("Cargo.toml").is_file() {
+            dependency.path(local_path)
+        } else {
+            dependency
+        }
+    }
+
     /// Create a directory (and all required parent directories)
     /// within the crate. Returns a builder which can be used to populate
     /// that directory with files.
@@ -244,11 +492,188 @@ impl LibraryCrate {
             anyhow::bail!("duplicate path: `{}`", path.display());
         }
 
-        Ok(CodeWriter::new(LibraryFileWriter {
+        let header = self
+            .file_header
+            .as_deref()
+            .and_then(|text| super::file_header::file_header(text, path));
+        let mut writer = CodeWriter::new(LibraryFileWriter {
             krate: self,
             path: path.to_path_buf(),
             contents: Default::default(),
-        }))
+            source_map: Default::default(),
+        });
+        for line in header.into_iter().flatten() {
+            writer.write_verbatim(line)?;
+        }
+        Ok(writer)
+    }
+
+    /// Copy one of the [extra files](`crate::cli::GenerateCx::extra_files`) the user asked to bundle
+    /// into the crate, at its [path](`ExtraFile::path`) within `dir` (relative to the root of the crate).
+    /// Extra files that the helper does not add are copied into the root of the crate.
+    ///
+    /// No changes on disk occur until [`Self::generate`][] is called.
+    pub fn add_extra_file(&mut self, file: &ExtraFile, dir: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = dir.as_ref().join(file.path());
+        if self.files.contains_key(&path) {
+            anyhow::bail!("duplicate path: `{}`", path.display());
+        }
+
+        let contents = std::fs::read(file.source())
+            .with_context(|| format!("reading `{}`", file.source().display()))?;
+        self.files.insert(path, contents);
+        self.extra_files.insert(file.source().clone());
+        Ok(())
+    }
+
+    /// Copy the user's [custom code](`CustomSrc`) into `src/custom/` and declare it as `mod custom;`
+    /// at the end of the `src/lib.rs` the helper generated, so that it can refer to the generated items
+    /// (e.g., to implement a conversion for them). Does nothing if there is no custom code.
+    pub(crate) fn add_custom_src(&mut self, custom_src: &CustomSrc) -> anyhow::Result<()> {
+        if custom_src.is_empty() {
+            return Ok(());
+        }
+
+        let lib_rs_path = Path::new("src/lib.rs");
+        let Some(lib_rs) = self.files.get_mut(lib_rs_path) else {
+            anyhow::bail!("`{}` requires the plugin to generate `src/lib.rs`", CustomSrc::KEY);
+        };
+        lib_rs.extend(b"\nmod custom;\n");
+
+        for file in custom_src.iter() {
+            self.add_extra_file(file, "src/custom")?;
+        }
+        Ok(())
+    }
+
+    /// True if `file` was added with [`Self::add_extra_file`][].
+    pub fn has_extra_file(&self, file: &ExtraFile) -> bool {
+        self.extra_files.contains(file.source())
+    }
+
+    /// Add a step to the build instructions in the generated `README.md`,
+    /// e.g. `("Build and install the Python package", "maturin develop")`.
+    ///
+    /// A `README.md` is only generated if the helper adds build steps or usages
+    /// (and does not add a `README.md` of its own).
+    pub fn add_build_step(&mut self, description: impl ToString, command: impl ToString) {
+        self.readme.add_build_step(description.to_string(), command.to_string());
+    }
+
+    /// Add a `snippet` of code in `language` (the info string of the fenced code block, e.g. `python`)
+    /// showing how to use the item `qname` from the target language.
+    /// It appears in the generated `README.md` under the Rust declaration of the item.
+    pub fn add_usage(&mut self, qname: &QualifiedName, language: &str, snippet: impl ToString) {
+        self.readme.add_usage(qname, language.to_string(), snippet.to_string());
+    }
+
+    /// Assemble the build steps and usages added by the helper into a `README.md` describing `idl`.
+    pub(crate) fn add_readme(&mut self, idl: &Idl) -> anyhow::Result<()> {
+        let readme_path = Path::new("README.md");
+        if self.readme.is_empty() || self.files.contains_key(readme_path) {
+            return Ok(());
+        }
+
+        let contents = self.readme.render(&self.crate_name, idl)?;
+        self.files.insert(readme_path.to_path_buf(), contents.into_bytes());
+        Ok(())
+    }
+
+    /// Generate a `bindings.json` in the root of the crate, listing the symbols registered with
+    /// [`Self::add_binding`][] and [`Self::add_member_binding`][] along with the Rust items they bind
+    /// and where those are defined, so that tools (e.g., documentation sites or IDE plugins)
+    /// can navigate from the generated symbols to the Rust source.
+    ///
+    /// gluegun enables it if the plugin metadata sets `bindings-manifest = true`.
+    pub fn enable_bindings_manifest(&mut self) {
+        self.bindings_manifest_enabled = true;
+    }
+
+    /// Record that `symbol`, the fully qualified name of a class, function, etc. in the target language
+    /// (e.g., `myCrate.Functions`), binds the item `qname`. Listed in `bindings.json`, if enabled.
+    pub fn add_binding(&mut self, qname: &QualifiedName, symbol: impl ToString) {
+        self.bindings_manifest.add_binding(symbol.to_string(), qname, None);
+    }
+
+    /// Record that `symbol`, the fully qualified name of a method, function, etc. in the target language
+    /// (e.g., `myCrate.Request.retries`), binds the method `member` of the item `qname`.
+    /// Listed in `bindings.json`, if enabled.
+    pub fn add_member_binding(&mut self, qname: &QualifiedName, member: &Name, symbol: impl ToString) {
+        self.bindings_manifest.add_binding(symbol.to_string(), qname, Some(member));
+    }
+
+    /// Write the bindings registered by the helper to `bindings.json`, if enabled.
+    pub(crate) fn add_bindings_manifest(&mut self, idl: &Idl) -> anyhow::Result<()> {
+        if !self.bindings_manifest_enabled || self.bindings_manifest.is_empty() {
+            return Ok(());
+        }
+
+        let contents = self
+            .bindings_manifest
+            .render(&self.crate_name, idl)
+            .context("generating `bindings.json`")?;
+        self.files.insert(PathBuf::from("bindings.json"), contents.into_bytes());
+        Ok(())
+    }
+
+    /// Generate a module `repr_c` with a `#[repr(C)]` mirror of each POD-compatible record
+    /// (see [`Record::pod`](`crate::idl::Record::pod`)) and conversions between the two,
+    /// for code that exchanges them through the C ABI (e.g., without copying them field by field).
+    /// The module goes in `src/repr_c.rs` and is declared at the end of the `src/lib.rs` the helper generated.
+    ///
+    /// gluegun enables it if the plugin metadata sets `repr-c-records = true`.
+    pub fn enable_repr_c_records(&mut self) {
+        self.repr_c_records_enabled = true;
+    }
+
+    /// Write the mirrors of the POD-compatible records of `idl` to `src/repr_c.rs`, if enabled and there are any.
+    pub(crate) fn add_repr_c_records(&mut self, idl: &Idl) -> anyhow::Result<()> {
+        let generator = ReprCGenerator::new(idl);
+        if !self.repr_c_records_enabled || !generator.has_records() {
+            return Ok(());
+        }
+
+        let lib_rs_path = Path::new("src/lib.rs");
+        let Some(lib_rs) = self.files.get_mut(lib_rs_path) else {
+            anyhow::bail!("`repr-c-records` requires the plugin to generate `src/lib.rs`");
+        };
+        lib_rs.extend(b"\npub mod repr_c;\n");
+
+        let mut repr_c_rs = self.add_file("src/repr_c.rs")?;
+        generator.generate(&mut repr_c_rs).context("generating `src/repr_c.rs`")
+    }
+
+    /// Generate a module `gluegun_debug` counting the wrappers of Rust objects that the foreign language holds,
+    /// so that tests can check that the bindings free them (e.g., once the foreign objects are garbage collected).
+    /// The module goes in `src/gluegun_debug.rs` and is declared at the end of the `src/lib.rs` the helper generated.
+    /// The helper checks [`Self::debug_bindings_enabled`][] to count its wrappers with `gluegun_debug::LiveObject`
+    /// and expose `gluegun_debug::live_objects()` to the foreign language.
+    ///
+    /// gluegun enables it if the plugin metadata sets `debug-bindings = true`
+    /// and the helper supports it (see [`GlueGunHelper::SUPPORTS_DEBUG_BINDINGS`](`crate::cli::GlueGunHelper::SUPPORTS_DEBUG_BINDINGS`)).
+    pub fn enable_debug_bindings(&mut self) {
+        self.debug_bindings_enabled = true;
+    }
+
+    /// Whether the helper should count the wrappers it generates (see [`Self::enable_debug_bindings`][]).
+    pub fn debug_bindings_enabled(&self) -> bool {
+        self.debug_bindings_enabled
+    }
+
+    /// Write `src/gluegun_debug.rs`, if enabled.
+    pub(crate) fn add_debug_bindings(&mut self) -> anyhow::Result<()> {
+        if !self.debug_bindings_enabled {
+            return Ok(());
+        }
+
+        let lib_rs_path = Path::new("src/lib.rs");
+        let Some(lib_rs) = self.files.get_mut(lib_rs_path) else {
+            anyhow::bail!("`debug-bindings` requires the plugin to generate `src/lib.rs`");
+        };
+        lib_rs.extend(b"\npub mod gluegun_debug;\n");
+
+        let mut gluegun_debug_rs = self.add_file("src/gluegun_debug.rs")?;
+        super::debug_bindings::generate(&mut gluegun_debug_rs).context("generating `src/gluegun_debug.rs`")
     }
 }
 
@@ -302,10 +727,11 @@ impl DirBuilder<'_> {
     }
 }
 
-struct LibraryFileWriter<'w> {
+pub(super) struct LibraryFileWriter<'w> {
     krate: &'w mut LibraryCrate,
     path: PathBuf,
     contents: Vec<u8>,
+    pub(super) source_map: SourceMap,
 }
 
 impl std::io::Write for LibraryFileWriter<'_> {
@@ -324,6 +750,12 @@ impl Drop for LibraryFileWriter<'_> {
         self.krate
             .files
             .insert(self.path.clone(), self.contents.clone());
+
+        if !self.source_map.is_empty() {
+            self.krate
+                .files
+                .insert(SourceMap::sidecar_path(&self.path), self.source_map.to_json());
+        }
     }
 }
 
@@ -337,70 +769,135 @@ struct Dependency {
     features: Vec<String>,
     no_default_features: bool,
     optional: bool,
+
+    /// Features of the dependency enabled by a [`Feature`][] of the generated crate, if it is required.
+    features_with: Vec<(Feature, String)>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 enum DependencyKind {
     Build,
     Dev,
 }
 
 impl Dependency {
-    fn execute_cargo_add(&self, to_crate_name: &str) -> anyhow::Result<()> {
+    /// The manifest entry for this dependency, in the form `cargo add` would write it.
+    /// A path dependency is written relative to `manifest_dir`, along with its version (if any),
+    /// which `cargo publish` requires. Without a path or a version, the dependency accepts any version (`"*"`).
+    fn to_toml(&self, manifest_dir: &Path) -> anyhow::Result<toml_edit::Item> {
         let Self {
             crate_name,
-            kind,
+            kind: _,
             path,
             version,
             features,
             no_default_features,
             optional,
+            features_with: _,
         } = self;
 
-        let mut command = std::process::Command::new("cargo");
-        command.arg("add");
-
-        command.arg("-p");
-        command.arg(to_crate_name);
-
+        let mut entry = toml_edit::InlineTable::new();
         if let Some(path) = &path {
-            command.arg("--path").arg(path);
-        } else if let Some(version) = &version {
-            command.arg(&format!("{}@{}", crate_name, version));
-        } else {
-            panic!("dependency `{crate_name}` needs either a path or a version");
+            let path = path
+                .canonicalize()
+                .with_context(|| format!("resolving path `{}` of dependency `{crate_name}`", path.display()))?;
+            let path = relative_path(manifest_dir, &path);
+            entry.insert("path", path.to_string_lossy().replace('\\', "/").into());
         }
+        match version {
+            Some(version) => entry.insert("version", version.into()),
+            None if path.is_none() => entry.insert("version", "*".into()),
+            None => None,
+        };
 
         if !features.is_empty() {
-            command.arg("--features");
-            command.arg(features.join(","));
+            // Sort (and dedup) the features for deterministic output.
+            let features: BTreeSet<&str> = features.iter().map(|f| &f[..]).collect();
+            entry.insert("features", features.into_iter().collect::<toml_edit::Array>().into());
         }
 
         if *no_default_features {
-            command.arg("--no-default-features");
-        }
-
-        if let Some(kind) = kind {
-            match kind {
-                DependencyKind::Build => command.arg("--build"),
-                DependencyKind::Dev => command.arg("--dev"),
-            };
+            entry.insert("default-features", false.into());
         }
 
         if *optional {
-            command.arg("--optional");
+            entry.insert("optional", true.into());
         }
 
-        let status = command.status()?;
-        if !status.success() {
-            anyhow::bail!(
-                "cargo command `{:?}` failed with exit status `{}`",
-                command,
-                status,
-            );
+        // Like `cargo add`, use the short form `name = "version"` when only a version is given.
+        if entry.len() == 1 {
+            if let Some(version) = entry.get("version") {
+                return Ok(toml_edit::Item::Value(version.clone()));
+            }
         }
-        Ok(())
+        Ok(toml_edit::value(entry))
+    }
+}
+
+/// The path to `path` relative to the directory `base` (both absolute).
+fn relative_path(base: &Path, path: &Path) -> PathBuf {
+    let base_components: Vec<_> = base.components().collect();
+    let path_components: Vec<_> = path.components().collect();
+    let common = base_components
+        .iter()
+        .zip(&path_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..base_components.len() {
+        relative.push("..");
+    }
+    for component in &path_components[common..] {
+        relative.push(component);
     }
+    relative
+}
+
+/// Name of the file in the generated crate that records the files generated the last time,
+/// so that they can be skipped if unchanged and removed if no longer generated.
+const GENERATED_FILES_NAME: &str = ".gluegun-files.json";
+
+/// Contents of [`GENERATED_FILES_NAME`][].
+#[derive(Default, Serialize, Deserialize)]
+struct GeneratedFiles {
+    /// Hash of the contents of each generated file, by path relative to the crate.
+    files: BTreeMap<PathBuf, u64>,
+}
+
+/// True if the crate at `crate_path` has a manifest and all the files recorded as generated the last time.
+pub(crate) fn generated_files_exist(crate_path: &Path) -> bool {
+    let Some(generated) = std::fs::read(crate_path.join(GENERATED_FILES_NAME))
+        .ok()
+        .and_then(|json| serde_json::from_slice::<GeneratedFiles>(&json).ok())
+    else {
+        return false;
+    };
+    crate_path.join("Cargo.toml").is_file() && generated.files.keys().all(|path| crate_path.join(path).is_file())
+}
+
+fn content_hash(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Apply `op` to each of `items`, dividing them among one thread per available core.
+/// Returns the first error, if any.
+fn for_each_parallel<T: Sync>(items: &[T], op: impl Fn(&T) -> anyhow::Result<()> + Sync) -> anyhow::Result<()> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = items.len().div_ceil(threads).max(1);
+    let op = &op;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().try_for_each(op)))
+            .collect();
+        handles.into_iter().try_for_each(|handle| match handle.join() {
+            Ok(result) => result,
+            Err(panic) => std::panic::resume_unwind(panic),
+        })
+    })
 }
 
 /// Builder returned by [`LibraryCrate::add_dependency`][].
@@ -417,6 +914,13 @@ impl AddDependency<'_> {
         self
     }
 
+    /// Enable `dependency_feature` of the dependency along with `feature`, if the generated code requires it
+    /// (e.g., `pyo3`'s `indexmap` feature along with [`Feature::IndexMap`][]).
+    pub fn feature_with(mut self, feature: Feature, dependency_feature: impl ToString) -> Self {
+        self.dependency.features_with.push((feature, dependency_feature.to_string()));
+        self
+    }
+
     /// Add a required feature for the dependency
     pub fn no_default_features(mut self) -> Self {
         self.dependency.no_default_features = true;
@@ -435,15 +939,15 @@ impl AddDependency<'_> {
         self
     }
 
-    /// Mark this as a dev dependency
+    /// Path to the crate, e.g. in the same workspace
     pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
         self.dependency.path = Some(path.into());
         self
     }
 
     /// Version to request
-    pub fn version(mut self, path: impl ToString) -> Self {
-        self.dependency.version = Some(path.to_string());
+    pub fn version(mut self, version: impl ToString) -> Self {
+        self.dependency.version = Some(version.to_string());
         self
     }
 
@@ -463,36 +967,27 @@ impl Drop for AddDependency<'_> {
 }
 
 impl TargetConfiguration {
-    /// Generate the `[lib]` or other similar secton from `self`, appending it to the `Cargo.toml`
-    fn emit_target(&self, cargo_toml_path: &Path, target_name: &str) -> anyhow::Result<()> {
-        use std::fmt::Write;
-
-        let mut cargo_toml_text = std::fs::read_to_string(cargo_toml_path)
-            .with_context(|| format!("failed to read `{}`", cargo_toml_path.display()))?;
+    /// The edition of the crate. It defaults to 2021 rather than to the latest (which `cargo new` sets),
+    /// since the code generated by some macros that the helpers use (e.g., `#[no_mangle]`) is rejected by later editions.
+    fn edition(&self) -> &str {
+        self.edition.as_deref().unwrap_or("2021")
+    }
 
-        writeln!(cargo_toml_text)?;
-        writeln!(cargo_toml_text, r#"{target_name}"#)?;
+    /// The `[lib]` table of the manifest, e.g.
+    ///
+    /// ```toml
+    /// [lib]
+    /// crate-type = ["cdylib"]
+    /// ```
+    fn to_toml(&self) -> toml_edit::Item {
+        let mut table = toml_edit::Table::new();
         if let Some(name) = &self.name {
-            writeln!(cargo_toml_text, r#"name = {name:?}"#)?;
+            table.insert("name", toml_edit::value(name));
         }
-        writeln!(
-            cargo_toml_text,
-            "crate-type = [{}]",
-            self.crate_types
-                .iter()
-                .map(|c| format!("{c:?}"))
-                .collect::<Vec<_>>()
-                .join(", ")
-        )?;
-        writeln!(
-            cargo_toml_text,
-            "edition = {:?}",
-            match &self.edition {
-                Some(edition) => edition,
-                None => "2021",
-            }
-        )?;
-
-        Ok(())
+        table.insert(
+            "crate-type",
+            toml_edit::value(self.crate_types.iter().map(CrateType::as_str).collect::<toml_edit::Array>()),
+        );
+        toml_edit::Item::Table(table)
     }
 }
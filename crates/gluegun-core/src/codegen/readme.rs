@@ -0,0 +1,216 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::idl::{Field, Idl, IsAsync, Item, Method, MethodCategory, QualifiedName, SelfKind, Signature};
+
+/// Usage documentation registered by a helper through [`LibraryCrate::add_build_step`](`super::LibraryCrate::add_build_step`)
+/// and [`LibraryCrate::add_usage`](`super::LibraryCrate::add_usage`), from which the `README.md`
+/// of the generated crate is assembled.
+///
+/// The README lists every item of the [`Idl`][] with its Rust declaration, followed by the usage snippets
+/// registered for it, so it stays in sync with the interface without being written by hand.
+#[derive(Default)]
+pub(super) struct Readme {
+    build_steps: Vec<BuildStep>,
+    usages: BTreeMap<QualifiedName, Vec<Snippet>>,
+}
+
+struct BuildStep {
+    description: String,
+    command: String,
+}
+
+struct Snippet {
+    language: String,
+    code: String,
+}
+
+impl Readme {
+    pub(super) fn add_build_step(&mut self, description: String, command: String) {
+        self.build_steps.push(BuildStep { description, command });
+    }
+
+    pub(super) fn add_usage(&mut self, qname: &QualifiedName, language: String, code: String) {
+        self.usages
+            .entry(qname.clone())
+            .or_default()
+            .push(Snippet { language, code });
+    }
+
+    /// True if the helper registered nothing, in which case no README is generated.
+    pub(super) fn is_empty(&self) -> bool {
+        self.build_steps.is_empty() && self.usages.is_empty()
+    }
+
+    /// Render the `README.md` for the crate `crate_name` generated from `idl`.
+    pub(super) fn render(&self, crate_name: &str, idl: &Idl) -> anyhow::Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# {crate_name}")?;
+        writeln!(out)?;
+        write!(out, "Bindings for the Rust crate `{}`", idl.crate_name())?;
+        if let Some(version) = idl.crate_metadata().version() {
+            write!(out, " (version {version})")?;
+        }
+        writeln!(out, ", generated by gluegun. Do not edit: this file is regenerated along with the bindings.")?;
+        if let Some(description) = idl.crate_metadata().description() {
+            writeln!(out)?;
+            writeln!(out, "> {description}")?;
+        }
+
+        if !self.build_steps.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "## Building")?;
+            for (index, step) in self.build_steps.iter().enumerate() {
+                writeln!(out)?;
+                writeln!(out, "{number}. {description}", number = index + 1, description = step.description)?;
+                writeln!(out)?;
+                write_fenced(&mut out, "   ", "sh", &step.command)?;
+            }
+        }
+
+        writeln!(out)?;
+        writeln!(out, "## API")?;
+        for (qname, item) in idl.definitions() {
+            writeln!(out)?;
+            writeln!(out, "### `{}`", qname.colon_colon())?;
+            writeln!(out)?;
+            write_fenced(&mut out, "", "rust", &declaration(qname, item))?;
+            for snippet in self.usages.get(qname).into_iter().flatten() {
+                writeln!(out)?;
+                write_fenced(&mut out, "", &snippet.language, &snippet.code)?;
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+/// Write `code` as a fenced code block in `language`, with each line prefixed by `indent`.
+fn write_fenced(out: &mut String, indent: &str, language: &str, code: &str) -> std::fmt::Result {
+    writeln!(out, "{indent}```{language}")?;
+    for line in code.lines() {
+        writeln!(out, "{indent}{line}")?;
+    }
+    writeln!(out, "{indent}```")
+}
+
+/// The Rust declaration of `item` as it appears in the public interface, without bodies.
+fn declaration(qname: &QualifiedName, item: &Item) -> String {
+    let name = qname.tail_name();
+    match item {
+        Item::Function(function) => format!("pub {}", signature(&name.to_string(), None, function.signature())),
+        Item::Record(record) if *record.tuple() => {
+            let fields: Vec<String> = record.fields().iter().map(|field| format!("pub {}", field.ty())).collect();
+            with_methods(format!("pub struct {name}({});", fields.join(", ")), &name.to_string(), record.methods())
+        }
+        Item::Record(record) => {
+            let fields: Vec<String> = record.fields().iter().map(field).collect();
+            with_methods(format!("pub struct {name} {{ {} }}", fields.join(", ")), &name.to_string(), record.methods())
+        }
+        Item::Newtype(newtype) if *newtype.validated() => with_methods(
+            format!("#[gluegun::marshal_as({})] pub struct {name}(..);", newtype.ty()),
+            &name.to_string(),
+            newtype.methods(),
+        ),
+        Item::Newtype(newtype) => with_methods(
+            format!("pub struct {name}(pub {});", newtype.ty()),
+            &name.to_string(),
+            newtype.methods(),
+        ),
+        Item::Resource(resource) => {
+            with_methods(format!("pub struct {name} {{ .. }}"), &name.to_string(), resource.methods())
+        }
+        Item::Enum(an_enum) => {
+            let arms: Vec<String> = an_enum.arms().iter().map(|arm| arm.name().to_string()).collect();
+            with_methods(format!("pub enum {name} {{ {} }}", arms.join(", ")), &name.to_string(), an_enum.methods())
+        }
+        Item::Variant(variant) => {
+            let arms: Vec<String> = variant
+                .arms()
+                .iter()
+                .map(|arm| {
+                    if arm.fields().is_empty() {
+                        arm.name().to_string()
+                    } else {
+                        let fields: Vec<String> = arm.fields().iter().map(field).collect();
+                        format!("{} {{ {} }}", arm.name(), fields.join(", "))
+                    }
+                })
+                .collect();
+            with_methods(format!("pub enum {name} {{ {} }}", arms.join(", ")), &name.to_string(), variant.methods())
+        }
+        Item::Interface(interface) => {
+            let mut out = format!("pub trait {name} {{");
+            for method in interface.methods() {
+                let self_param = match method.category() {
+                    MethodCategory::InstanceMethod(SelfKind::ByRefMut) => "&mut self",
+                    _ => "&self",
+                };
+                let signature = signature(&method.name().to_string(), Some(self_param), method.signature());
+                out.push_str(&format!("\n    {signature};"));
+            }
+            out.push_str("\n}");
+            out
+        }
+        _ => format!("// `{name}` (not yet described)"),
+    }
+}
+
+fn field(field: &Field) -> String {
+    format!("{}: {}", field.name(), field.ty())
+}
+
+/// Append an `impl` block listing the signatures of `methods` (if any) to `declaration`.
+fn with_methods(declaration: String, self_name: &str, methods: &[Method]) -> String {
+    if methods.is_empty() {
+        return declaration;
+    }
+    let mut out = declaration;
+    out.push_str(&format!("\n\nimpl {self_name} {{"));
+    for method in methods {
+        let self_param = match method.category() {
+            MethodCategory::Constructor | MethodCategory::NamedConstructor | MethodCategory::StaticMethod => None,
+            MethodCategory::BuilderMethod(self_kind) | MethodCategory::InstanceMethod(self_kind) => {
+                Some(match self_kind {
+                    SelfKind::ByValue => "self",
+                    SelfKind::ByRef => "&self",
+                    SelfKind::ByRefMut => "&mut self",
+                    SelfKind::ByArc => "self: Arc<Self>",
+                    SelfKind::ByRc => "self: Rc<Self>",
+                    _ => "self",
+                })
+            }
+            // Listed as a method reading the field.
+            MethodCategory::Getter(_) => Some("&self"),
+            _ => None,
+        };
+        let signature = signature(&method.name().to_string(), self_param, method.signature());
+        out.push_str(&format!("\n    pub {signature};"));
+    }
+    out.push_str("\n}");
+    out
+}
+
+/// The signature of the function or method `name`, e.g. `fn greet(name: &str) -> String`.
+fn signature(name: &str, self_param: Option<&str>, signature: &Signature) -> String {
+    let inputs: Vec<String> = self_param
+        .map(|self_param| self_param.to_string())
+        .into_iter()
+        .chain(
+            signature
+                .inputs()
+                .iter()
+                .map(|input| format!("{}: {}", input.name(), input.refd_ty())),
+        )
+        .collect();
+    let async_kw = match signature.is_async() {
+        IsAsync::Yes => "async ",
+        IsAsync::No => "",
+    };
+    let output_ty = signature.output_ty();
+    let output = match (output_ty.is_unit(), output_ty.error_ty()) {
+        (true, None) => String::new(),
+        (_, None) => format!(" -> {}", output_ty.main_ty()),
+        (_, Some(error_ty)) => format!(" -> Result<{}, {error_ty}>", output_ty.main_ty()),
+    };
+    format!("{async_kw}fn {name}({}){output}", inputs.join(", "))
+}
@@ -0,0 +1,133 @@
+use crate::idl::{Idl, Item, QualifiedName, Record, Ty, TypeKind};
+
+use super::CodeWriter;
+
+/// Generates `src/repr_c.rs`, with a `#[repr(C)]` mirror of each POD-compatible record
+/// (see [`Record::pod`](`crate::idl::Record::pod`)) and conversions to and from the record, e.g.
+///
+/// ```rust,ignore
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Debug)]
+/// pub struct my_crate_Point { pub x: f64, pub y: f64 }
+///
+/// impl From<my_crate::Point> for my_crate_Point { /* copy each field */ }
+/// impl From<my_crate_Point> for my_crate::Point { /* copy each field */ }
+/// ```
+///
+/// Fields of other POD-compatible records are their mirrors, and newtypes are unwrapped.
+pub(super) struct ReprCGenerator<'idl> {
+    idl: &'idl Idl,
+}
+
+impl<'idl> ReprCGenerator<'idl> {
+    pub(super) fn new(idl: &'idl Idl) -> Self {
+        Self { idl }
+    }
+
+    /// True if `idl` has POD-compatible records to mirror.
+    pub(super) fn has_records(&self) -> bool {
+        self.idl.records().any(|(_, record)| *record.pod())
+    }
+
+    pub(super) fn generate(&self, repr_c_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(repr_c_rs, "//! `#[repr(C)]` mirrors of the records whose fields all have a stable layout.")?;
+        for (qname, record) in self.idl.records().filter(|(_, record)| *record.pod()) {
+            repr_c_rs.with_span(record.span(), |repr_c_rs| self.generate_record(repr_c_rs, qname, record))?;
+        }
+        Ok(())
+    }
+
+    fn generate_record(&self, repr_c_rs: &mut CodeWriter<'_>, qname: &QualifiedName, record: &Record) -> anyhow::Result<()> {
+        let mirror = mirror_name(qname);
+        let rust_path = qname.colon_colon();
+        write!(repr_c_rs, "")?;
+        write!(repr_c_rs, "/// Mirror of [`{rust_path}`] with a stable layout.")?;
+        write!(repr_c_rs, "#[repr(C)]")?;
+        write!(repr_c_rs, "#[derive(Clone, Copy, Debug)]")?;
+        write!(repr_c_rs, "#[allow(non_camel_case_types)]")?;
+        write!(repr_c_rs, "pub struct {mirror} {{")?;
+        for field in record.fields() {
+            write!(repr_c_rs, "pub {}: {},", field.name(), self.mirror_ty(field.ty())?)?;
+        }
+        write!(repr_c_rs, "}}")?;
+
+        write!(repr_c_rs, "impl From<{rust_path}> for {mirror} {{")?;
+        write!(repr_c_rs, "fn from(value: {rust_path}) -> Self {{")?;
+        write!(repr_c_rs, "Self {{")?;
+        for (index, field) in record.fields().iter().enumerate() {
+            let value = self.mirror_value(&format!("value.{}", record.member(index)), field.ty())?;
+            write!(repr_c_rs, "{}: {value},", field.name())?;
+        }
+        write!(repr_c_rs, "}}")?;
+        write!(repr_c_rs, "}}")?;
+        write!(repr_c_rs, "}}")?;
+
+        write!(repr_c_rs, "impl From<{mirror}> for {rust_path} {{")?;
+        write!(repr_c_rs, "fn from(value: {mirror}) -> Self {{")?;
+        write!(repr_c_rs, "Self {{")?;
+        for (index, field) in record.fields().iter().enumerate() {
+            let value = self.unmirror_value(&format!("value.{}", field.name()), field.ty())?;
+            write!(repr_c_rs, "{}: {value},", record.member(index))?;
+        }
+        write!(repr_c_rs, "}}")?;
+        write!(repr_c_rs, "}}")?;
+        write!(repr_c_rs, "}}")?;
+        Ok(())
+    }
+
+    /// The type of the mirror field for a field of type `ty`.
+    fn mirror_ty(&self, ty: &Ty) -> anyhow::Result<String> {
+        match self.pod_ty(ty)? {
+            PodTy::Scalar => Ok(ty.rust_spelling()),
+            PodTy::Record(qname) => Ok(mirror_name(qname)),
+            PodTy::Newtype(field_ty) => self.mirror_ty(field_ty),
+        }
+    }
+
+    /// Convert `expr`, a value of type `ty`, into the type of the mirror field.
+    fn mirror_value(&self, expr: &str, ty: &Ty) -> anyhow::Result<String> {
+        match self.pod_ty(ty)? {
+            PodTy::Scalar => Ok(expr.to_string()),
+            PodTy::Record(_) => Ok(format!("From::from({expr})")),
+            PodTy::Newtype(field_ty) => self.mirror_value(&format!("{expr}.0"), field_ty),
+        }
+    }
+
+    /// Convert `expr`, a value of the type of the mirror field, back into type `ty`.
+    fn unmirror_value(&self, expr: &str, ty: &Ty) -> anyhow::Result<String> {
+        match self.pod_ty(ty)? {
+            PodTy::Scalar => Ok(expr.to_string()),
+            PodTy::Record(_) => Ok(format!("From::from({expr})")),
+            PodTy::Newtype(field_ty) => {
+                let TypeKind::UserType { qname, .. } = ty.kind() else { unreachable!() };
+                Ok(format!("{}({})", qname.colon_colon(), self.unmirror_value(expr, field_ty)?))
+            }
+        }
+    }
+
+    /// What the type `ty` of a field of a POD-compatible record is.
+    fn pod_ty<'ty>(&'ty self, ty: &'ty Ty) -> anyhow::Result<PodTy<'ty>> {
+        match ty.kind() {
+            TypeKind::Scalar(_) => Ok(PodTy::Scalar),
+            TypeKind::UserType { qname, .. } => match self.idl.item(qname) {
+                Some(Item::Record(_)) => Ok(PodTy::Record(qname)),
+                Some(Item::Newtype(newtype)) => Ok(PodTy::Newtype(newtype.ty())),
+                _ => anyhow::bail!("{}: `{}` is not POD-compatible", ty.span(), qname.colon_colon()),
+            },
+            _ => anyhow::bail!("{}: `{}` is not POD-compatible", ty.span(), ty.rust_spelling()),
+        }
+    }
+}
+
+enum PodTy<'ty> {
+    Scalar,
+    Record(&'ty QualifiedName),
+
+    /// A newtype, whose field has the given type.
+    Newtype(&'ty Ty),
+}
+
+/// The name of the mirror of the record `qname`, e.g. `my_crate_Point`.
+fn mirror_name(qname: &QualifiedName) -> String {
+    qname.to_string("_")
+}
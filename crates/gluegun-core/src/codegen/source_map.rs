@@ -0,0 +1,90 @@
+use std::{
+    cmp::Reverse,
+    path::{Path, PathBuf},
+};
+
+use accessors_rs::Accessors;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::idl::Span;
+
+/// Maps ranges of lines in a generated file to the [`Span`][] of the Rust source they were generated from.
+///
+/// Spans are recorded with [`CodeWriter::with_span`](`super::CodeWriter::with_span`).
+/// If a file records any spans, its source map is written alongside it as a sidecar
+/// (see [`Self::sidecar_path`][]), so that errors reported in the generated code
+/// can be traced back to the definitions that produced them.
+#[derive(Accessors, Clone, Debug, Default, Serialize, Deserialize)]
+#[accessors(get)]
+pub struct SourceMap {
+    /// The mappings, ordered by their first line.
+    /// Mappings may nest (e.g., a method within a type), but do not otherwise overlap.
+    mappings: Vec<SourceMapping>,
+}
+
+/// A range of lines in a generated file and the span it was generated from.
+#[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
+#[accessors(get)]
+pub struct SourceMapping {
+    /// First generated line (1-indexed).
+    start_line: usize,
+
+    /// Last generated line (1-indexed, inclusive).
+    end_line: usize,
+
+    /// Span of the Rust source the lines were generated from.
+    span: Span,
+}
+
+impl SourceMap {
+    /// Path of the source map for the generated file at `path`, e.g., `src/lib.rs.map.json` for `src/lib.rs`.
+    pub fn sidecar_path(path: &Path) -> PathBuf {
+        let mut sidecar_path = path.as_os_str().to_owned();
+        sidecar_path.push(".map.json");
+        PathBuf::from(sidecar_path)
+    }
+
+    /// Load the source map for the generated file at `path`, if it has one.
+    pub fn load(path: &Path) -> anyhow::Result<Option<Self>> {
+        let sidecar_path = Self::sidecar_path(path);
+        if !sidecar_path.exists() {
+            return Ok(None);
+        }
+
+        let json = std::fs::read_to_string(&sidecar_path)
+            .with_context(|| format!("reading source map `{}`", sidecar_path.display()))?;
+        let source_map = serde_json::from_str(&json)
+            .with_context(|| format!("parsing source map `{}`", sidecar_path.display()))?;
+        Ok(Some(source_map))
+    }
+
+    /// The span that the given generated line (1-indexed) came from.
+    /// If mappings nest, returns the span of the innermost one.
+    pub fn span_for_line(&self, line: usize) -> Option<&Span> {
+        self.mappings
+            .iter()
+            .filter(|mapping| mapping.start_line <= line && line <= mapping.end_line)
+            .min_by_key(|mapping| mapping.end_line - mapping.start_line)
+            .map(|mapping| &mapping.span)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    pub(crate) fn push(&mut self, start_line: usize, end_line: usize, span: &Span) {
+        self.mappings.push(SourceMapping {
+            start_line,
+            end_line,
+            span: span.clone(),
+        });
+    }
+
+    /// Serialize to JSON, ordering mappings by their first line (outermost first).
+    pub(crate) fn to_json(&self) -> Vec<u8> {
+        let mut mappings = self.mappings.clone();
+        mappings.sort_by_key(|mapping| (mapping.start_line, Reverse(mapping.end_line)));
+        serde_json::to_vec_pretty(&SourceMap { mappings }).expect("source maps can be serialized")
+    }
+}
@@ -0,0 +1,61 @@
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The files a helper contributes to the workspace as a whole, rather than to one of the crates it generated
+/// (e.g., a build file aggregating them), in [`GlueGunHelper::generate_workspace`](`crate::cli::GlueGunHelper::generate_workspace`).
+///
+/// The helper sends them to `cargo gluegun`, which writes them relative to the workspace root
+/// and removes those it wrote the previous time that are no longer among them.
+/// It refuses to overwrite files that it did not write itself.
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct WorkspaceFiles {
+    files: BTreeMap<PathBuf, String>,
+
+    /// The text prepended to each file (see [`Self::set_file_header`][]).
+    #[serde(skip)]
+    file_header: Option<String>,
+}
+
+impl WorkspaceFiles {
+    /// Prepend `text` to each file added afterwards, as for [`LibraryCrate::set_file_header`](`super::LibraryCrate::set_file_header`).
+    pub fn set_file_header(&mut self, text: impl Into<String>) {
+        self.file_header = Some(text.into());
+    }
+
+    /// Add a file with the given `contents`.
+    ///
+    /// # Parameters
+    ///
+    /// * `path`, path of the file relative to the workspace root; it cannot leave it (e.g., with `..`)
+    pub fn add_file(&mut self, path: impl AsRef<Path>, contents: impl Into<String>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if !path.components().all(|component| matches!(component, Component::Normal(_))) {
+            anyhow::bail!("workspace files must be relative to the workspace root: `{}`", path.display());
+        }
+        if self.files.contains_key(path) {
+            anyhow::bail!("duplicate path: `{}`", path.display());
+        }
+
+        let mut text = String::new();
+        let header = self
+            .file_header
+            .as_deref()
+            .and_then(|header| super::file_header::file_header(header, path));
+        for line in header.into_iter().flatten() {
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text.push_str(&contents.into());
+        self.files.insert(path.to_path_buf(), text);
+        Ok(())
+    }
+
+    /// The files added so far and their contents, ordered by path.
+    pub fn iter(&self) -> impl Iterator<Item = (&Path, &str)> {
+        self.files.iter().map(|(path, contents)| (path.as_path(), contents.as_str()))
+    }
+}
@@ -12,3 +12,15 @@ pub mod cli;
 
 /// Utility structs for generating "vaguely well formatted" code.
 pub mod codegen;
+
+/// A [`Visitor`](visit::Visitor) trait for traversing the IDL.
+pub mod visit;
+
+/// Case conversions of identifiers, handling acronyms (`HTTPServer` becomes `httpServer`).
+pub mod casing;
+
+/// [`Naming`](naming::Naming) conventions, checked for names that collide in the target language.
+pub mod naming;
+
+/// A [`TypeMapper`](type_mapper::TypeMapper) framework for mapping IDL types to target-language types.
+pub mod type_mapper;
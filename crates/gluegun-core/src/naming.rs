@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::casing;
+use crate::idl::{Field, Idl, Item, Method, ModulePath, Name, Signature, Span};
+
+/// A case convention applied to Rust names in the target language, see [`casing`](crate::casing).
+///
+/// In plugin metadata, a case is written as in `#[serde(rename_all = "...")]`:
+/// `"camelCase"`, `"PascalCase"`, `"snake_case"`, or `"preserve"` for Rust names as is.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[non_exhaustive]
+pub enum Case {
+    /// The Rust name is used as is.
+    #[default]
+    #[serde(rename = "preserve")]
+    Preserve,
+
+    /// `camelCase`, see [`casing::camel_case`][].
+    #[serde(rename = "camelCase")]
+    Camel,
+
+    /// `UpperCamelCase`, see [`casing::upper_camel_case`][].
+    #[serde(rename = "PascalCase")]
+    UpperCamel,
+
+    /// `snake_case`, see [`casing::snake_case`][].
+    #[serde(rename = "snake_case")]
+    Snake,
+}
+
+impl Case {
+    /// The names of the cases in plugin metadata, e.g. for [`MetadataKind::one_of`](crate::cli::MetadataKind::one_of).
+    pub const METADATA_NAMES: [&'static str; 4] = ["camelCase", "PascalCase", "snake_case", "preserve"];
+
+    /// Convert `name` to this case.
+    pub fn apply(self, name: &Name) -> Name {
+        match self {
+            Case::Preserve => name.clone(),
+            Case::Camel => Name::from(casing::camel_case(name.text())),
+            Case::UpperCamel => Name::from(casing::upper_camel_case(name.text())),
+            Case::Snake => Name::from(casing::snake_case(name.text())),
+        }
+    }
+}
+
+/// How a helper names the methods of a group of overloads (see [`Method::overload`][]),
+/// e.g. `from_str` and `from_bytes`, both tagged `#[gluegun::overload(from)]`.
+///
+/// In plugin metadata, a policy is written `"overload"`, `"suffix"`, or `"separate"`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum OverloadPolicy {
+    /// Each method keeps its Rust name, ignoring its group.
+    #[default]
+    Separate,
+
+    /// The methods of a group are all named after it (`from`), for languages that tell overloads apart
+    /// by the types of their parameters.
+    Overload,
+
+    /// The methods of a group are named after it, followed by a suffix that tells them apart:
+    /// the rest of their Rust name (`from_str` and `from_bytes` stay as they are) or, if their Rust name
+    /// does not start with the group, all of it (`parse` becomes `from_parse`).
+    Suffix,
+}
+
+impl OverloadPolicy {
+    /// The names of the policies in plugin metadata, e.g. for [`MetadataKind::one_of`](crate::cli::MetadataKind::one_of).
+    pub const METADATA_NAMES: [&'static str; 3] = ["overload", "suffix", "separate"];
+
+    /// The name of `method` under this policy, before any [`Case`][] is applied.
+    pub fn method_name(self, method: &Method) -> Name {
+        let Some(group) = method.overload() else {
+            return method.name().clone();
+        };
+        match self {
+            OverloadPolicy::Separate => method.name().clone(),
+            OverloadPolicy::Overload => group.clone(),
+            OverloadPolicy::Suffix => {
+                let name = method.name().text();
+                match name.strip_prefix(group.text()) {
+                    Some(suffix) if suffix.is_empty() || suffix.starts_with('_') => method.name().clone(),
+                    _ => Name::from(format!("{group}_{name}")),
+                }
+            }
+        }
+    }
+}
+
+/// How a helper derives the identifiers of the target language from the names in the IDL,
+/// returned by [`GlueGunHelper::naming`](`crate::cli::GlueGunHelper::naming`).
+///
+/// Converting names can map distinct Rust names to the same identifier (e.g., `my_item` and `myItem`
+/// both become `myItem` in camelCase), as can dropping the module of a definition (e.g., `a::Config` and `b::Config`
+/// both become `Config`). [`Self::check`][] reports such collisions before any code is generated, e.g.
+///
+/// ```rust,ignore
+/// Naming::new().modules(Case::Camel).types(Case::UpperCamel).fields(Case::Camel)
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Naming {
+    modules: Case,
+    types: Case,
+    functions: Case,
+    fields: Case,
+    parameters: Case,
+    overloads: OverloadPolicy,
+    flatten_types: bool,
+}
+
+impl Naming {
+    /// Names are used as is, each definition in the namespace of its module.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The case of module (package) names.
+    pub fn modules(mut self, case: Case) -> Self {
+        self.modules = case;
+        self
+    }
+
+    /// The case of type names and of the arms of enums.
+    pub fn types(mut self, case: Case) -> Self {
+        self.types = case;
+        self
+    }
+
+    /// The case of function and method names.
+    pub fn functions(mut self, case: Case) -> Self {
+        self.functions = case;
+        self
+    }
+
+    /// The case of field names.
+    pub fn fields(mut self, case: Case) -> Self {
+        self.fields = case;
+        self
+    }
+
+    /// The case of parameter names.
+    pub fn parameters(mut self, case: Case) -> Self {
+        self.parameters = case;
+        self
+    }
+
+    /// How the methods of a group of overloads are named.
+    pub fn overloads(mut self, policy: OverloadPolicy) -> Self {
+        self.overloads = policy;
+        self
+    }
+
+    /// All types of a crate share one namespace, regardless of their module
+    /// (functions remain in the namespace of their module).
+    pub fn flatten_types(mut self) -> Self {
+        self.flatten_types = true;
+        self
+    }
+
+    /// Check that no two definitions, members of a definition, or parameters of a function in `idl`
+    /// have the same identifier in the target language. The error lists each collision,
+    /// with the spans of the Rust names involved.
+    pub fn check(&self, idl: &Idl) -> anyhow::Result<()> {
+        let mut identifiers = Identifiers::default();
+
+        for (qname, item) in idl.definitions() {
+            let (namespace, case, module) = match item {
+                Item::Function(_) => ("function", self.functions, self.module_scope(&qname.module_path())),
+                _ if self.flatten_types => ("type", self.types, qname.names()[0].to_string()),
+                _ => ("type", self.types, self.module_scope(&qname.module_path())),
+            };
+            identifiers.insert(
+                format!("{namespace} in `{module}`"),
+                case.apply(&qname.tail_name()),
+                qname.colon_colon(),
+                item.span(),
+            );
+
+            let owner = format!("`{}`", qname.colon_colon());
+            match item {
+                Item::Resource(resource) => {
+                    let inherited = idl.inherited_methods(resource).into_iter().map(|(_, method)| method);
+                    self.check_methods(&mut identifiers, &owner, resource.methods().iter().chain(inherited));
+                }
+                Item::Record(record) => {
+                    self.check_fields(&mut identifiers, &owner, record.fields());
+                    self.check_methods(&mut identifiers, &owner, record.methods());
+                }
+                Item::Newtype(newtype) => self.check_methods(&mut identifiers, &owner, newtype.methods()),
+                Item::Variant(variant) => {
+                    for arm in variant.arms() {
+                        identifiers.insert(format!("arm of {owner}"), self.types.apply(arm.name()), arm.name().to_string(), arm.span());
+                        self.check_fields(&mut identifiers, &format!("`{}::{}`", qname.colon_colon(), arm.name()), arm.fields());
+                    }
+                    self.check_methods(&mut identifiers, &owner, variant.methods());
+                }
+                Item::Enum(an_enum) => {
+                    for arm in an_enum.arms() {
+                        identifiers.insert(format!("arm of {owner}"), self.types.apply(arm.name()), arm.name().to_string(), arm.span());
+                    }
+                    self.check_methods(&mut identifiers, &owner, an_enum.methods());
+                }
+                Item::Function(function) => self.check_parameters(&mut identifiers, &owner, function.signature()),
+                Item::Interface(interface) => self.check_methods(&mut identifiers, &owner, interface.methods()),
+                _ => {}
+            }
+        }
+
+        identifiers.into_result()
+    }
+
+    /// The namespace of the definitions in the module `module_path`, in the target language.
+    fn module_scope(&self, module_path: &ModulePath) -> String {
+        let names: Vec<String> = module_path.names().iter().map(|name| self.modules.apply(name).to_string()).collect();
+        names.join(".")
+    }
+
+    fn check_methods<'idl>(&self, identifiers: &mut Identifiers<'idl>, owner: &str, methods: impl IntoIterator<Item = &'idl Method>) {
+        for method in methods {
+            // The methods of a group of overloads share their identifier on purpose, so they count as one name.
+            let rust_name = match (self.overloads, method.overload()) {
+                (OverloadPolicy::Overload, Some(group)) => group.to_string(),
+                _ => method.name().to_string(),
+            };
+            let identifier = self.functions.apply(&self.overloads.method_name(method));
+            identifiers.insert(format!("method of {owner}"), identifier, rust_name, method.span());
+            self.check_parameters(identifiers, &format!("{owner}::{}", method.name()), method.signature());
+        }
+    }
+
+    fn check_fields<'idl>(&self, identifiers: &mut Identifiers<'idl>, owner: &str, fields: &'idl [Field]) {
+        for field in fields {
+            identifiers.insert(format!("field of {owner}"), self.fields.apply(field.name()), field.name().to_string(), field.span());
+        }
+    }
+
+    fn check_parameters<'idl>(&self, identifiers: &mut Identifiers<'idl>, owner: &str, signature: &'idl Signature) {
+        for input in signature.inputs() {
+            identifiers.insert(format!("parameter of {owner}"), self.parameters.apply(input.name()), input.name().to_string(), input.span());
+        }
+    }
+}
+
+/// The Rust names with each identifier of the target language, by namespace (e.g., "method of `my_crate::Counter`").
+#[derive(Default)]
+struct Identifiers<'idl> {
+    names: BTreeMap<(String, Name), BTreeMap<String, &'idl Span>>,
+}
+
+impl<'idl> Identifiers<'idl> {
+    fn insert(&mut self, namespace: String, identifier: Name, rust_name: String, span: &'idl Span) {
+        self.names.entry((namespace, identifier)).or_default().entry(rust_name).or_insert(span);
+    }
+
+    fn into_result(self) -> anyhow::Result<()> {
+        let mut message = String::new();
+        for ((namespace, identifier), rust_names) in &self.names {
+            if rust_names.len() < 2 {
+                continue;
+            }
+            message.push_str(&format!("\n{} Rust names become the same {namespace}, `{identifier}`:", rust_names.len()));
+            for (name, span) in rust_names {
+                message.push_str(&format!("\n  {span}: `{name}`"));
+            }
+        }
+        if message.is_empty() {
+            return Ok(());
+        }
+        anyhow::bail!(
+            "names collide in the target language:{message}\n\
+             rename them, or tag all but one `#[gluegun::ignore]` or leave them out with `exclude`"
+        )
+    }
+}
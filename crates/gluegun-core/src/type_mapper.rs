@@ -0,0 +1,193 @@
+//! Mapping IDL types to types in some target language.
+//!
+//! Implement [`TypeMapper`][] with one method per kind of type. Nested types (e.g., the element
+//! type of a `Vec`) are mapped first, by calling [`TypeMapper::map_ty`][] recursively, and the
+//! results are given to the handler for the outer type. The default handlers report an
+//! "unsupported type" error that includes the span of the type.
+//!
+//! To handle some kind of type without mapping its nested types first (or to treat the
+//! outermost type specially), override [`TypeMapper::map_ty`][] and call [`walk_ty`][] for the
+//! remaining cases.
+
+use crate::idl::{
+    ErrorRepr, FutureRepr, MapSetRepr, NetAddrRepr, OptionRepr, PathRepr, QualifiedName, ResultRepr, Scalar,
+    StreamRepr, StringRepr, TimestampRepr, TraitObjectRepr, TupleRepr, Ty, TypeKind, UuidRepr, VecRepr,
+};
+
+/// Maps IDL types to values of type `Self::Output` (typically a `String` with the name of the
+/// type in the target language).
+pub trait TypeMapper {
+    type Output;
+
+    /// Map `ty`. The default implementation calls [`walk_ty`][].
+    fn map_ty(&mut self, ty: &Ty) -> anyhow::Result<Self::Output> {
+        walk_ty(self, ty)
+    }
+
+    fn map_map(
+        &mut self,
+        ty: &Ty,
+        key: Self::Output,
+        value: Self::Output,
+        repr: &MapSetRepr,
+    ) -> anyhow::Result<Self::Output> {
+        let _ = (key, value, repr);
+        unsupported(ty)
+    }
+
+    fn map_vec(&mut self, ty: &Ty, element: Self::Output, repr: &VecRepr) -> anyhow::Result<Self::Output> {
+        let _ = (element, repr);
+        unsupported(ty)
+    }
+
+    fn map_set(&mut self, ty: &Ty, element: Self::Output, repr: &MapSetRepr) -> anyhow::Result<Self::Output> {
+        let _ = (element, repr);
+        unsupported(ty)
+    }
+
+    fn map_path(&mut self, ty: &Ty, repr: &PathRepr) -> anyhow::Result<Self::Output> {
+        let _ = repr;
+        unsupported(ty)
+    }
+
+    fn map_net_addr(&mut self, ty: &Ty, repr: &NetAddrRepr) -> anyhow::Result<Self::Output> {
+        let _ = repr;
+        unsupported(ty)
+    }
+
+    fn map_uuid(&mut self, ty: &Ty, repr: &UuidRepr) -> anyhow::Result<Self::Output> {
+        let _ = repr;
+        unsupported(ty)
+    }
+
+    fn map_timestamp(&mut self, ty: &Ty, repr: &TimestampRepr) -> anyhow::Result<Self::Output> {
+        let _ = repr;
+        unsupported(ty)
+    }
+
+    fn map_string(&mut self, ty: &Ty, repr: &StringRepr) -> anyhow::Result<Self::Output> {
+        let _ = repr;
+        unsupported(ty)
+    }
+
+    fn map_option(&mut self, ty: &Ty, element: Self::Output, repr: &OptionRepr) -> anyhow::Result<Self::Output> {
+        let _ = (element, repr);
+        unsupported(ty)
+    }
+
+    fn map_result(
+        &mut self,
+        ty: &Ty,
+        ok: Self::Output,
+        err: Self::Output,
+        repr: &ResultRepr,
+    ) -> anyhow::Result<Self::Output> {
+        let _ = (ok, err, repr);
+        unsupported(ty)
+    }
+
+    fn map_tuple(&mut self, ty: &Ty, elements: Vec<Self::Output>, repr: &TupleRepr) -> anyhow::Result<Self::Output> {
+        let _ = (elements, repr);
+        unsupported(ty)
+    }
+
+    fn map_scalar(&mut self, ty: &Ty, scalar: &Scalar) -> anyhow::Result<Self::Output> {
+        let _ = scalar;
+        unsupported(ty)
+    }
+
+    fn map_future(&mut self, ty: &Ty, output: Self::Output, repr: &FutureRepr) -> anyhow::Result<Self::Output> {
+        let _ = (output, repr);
+        unsupported(ty)
+    }
+
+    fn map_stream(&mut self, ty: &Ty, item: Self::Output, repr: &StreamRepr) -> anyhow::Result<Self::Output> {
+        let _ = (item, repr);
+        unsupported(ty)
+    }
+
+    fn map_error(&mut self, ty: &Ty, repr: &ErrorRepr) -> anyhow::Result<Self::Output> {
+        let _ = repr;
+        unsupported(ty)
+    }
+
+    fn map_user_type(&mut self, ty: &Ty, qname: &QualifiedName) -> anyhow::Result<Self::Output> {
+        let _ = qname;
+        unsupported(ty)
+    }
+
+    /// Map a trait object (see [`TypeKind::TraitObject`][]) of the interface `qname`.
+    fn map_trait_object(&mut self, ty: &Ty, qname: &QualifiedName, repr: &TraitObjectRepr) -> anyhow::Result<Self::Output> {
+        let _ = (qname, repr);
+        unsupported(ty)
+    }
+
+    /// Map a third-party type (see [`TypeKind::Custom`][]). Backends that support them
+    /// look up `path` in the [`TypeOverrides`](`crate::cli::TypeOverrides`) configured by the user.
+    fn map_custom(&mut self, ty: &Ty, path: &str) -> anyhow::Result<Self::Output> {
+        anyhow::bail!(
+            "{span}: third-party type `{path}` is not supported by this backend, even with a `type-overrides` entry",
+            span = ty.span()
+        )
+    }
+}
+
+/// Map the types nested in `ty` with [`TypeMapper::map_ty`][] and then invoke
+/// the handler for the kind of `ty`.
+pub fn walk_ty<M: TypeMapper + ?Sized>(mapper: &mut M, ty: &Ty) -> anyhow::Result<M::Output> {
+    match ty.kind() {
+        TypeKind::Map { key, value, repr } => {
+            let key = mapper.map_ty(key)?;
+            let value = mapper.map_ty(value)?;
+            mapper.map_map(ty, key, value, repr)
+        }
+        TypeKind::Vec { element, repr } => {
+            let element = mapper.map_ty(element)?;
+            mapper.map_vec(ty, element, repr)
+        }
+        TypeKind::Set { element, repr } => {
+            let element = mapper.map_ty(element)?;
+            mapper.map_set(ty, element, repr)
+        }
+        TypeKind::Path { repr } => mapper.map_path(ty, repr),
+        TypeKind::NetAddr { repr } => mapper.map_net_addr(ty, repr),
+        TypeKind::Uuid { repr } => mapper.map_uuid(ty, repr),
+        TypeKind::Timestamp { repr } => mapper.map_timestamp(ty, repr),
+        TypeKind::String { repr } => mapper.map_string(ty, repr),
+        TypeKind::Option { element, repr } => {
+            let element = mapper.map_ty(element)?;
+            mapper.map_option(ty, element, repr)
+        }
+        TypeKind::Result { ok, err, repr } => {
+            let ok = mapper.map_ty(ok)?;
+            let err = mapper.map_ty(err)?;
+            mapper.map_result(ty, ok, err, repr)
+        }
+        TypeKind::Tuple { elements, repr } => {
+            let elements = elements
+                .iter()
+                .map(|element| mapper.map_ty(element))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            mapper.map_tuple(ty, elements, repr)
+        }
+        TypeKind::Scalar(scalar) => mapper.map_scalar(ty, scalar),
+        TypeKind::Future { output, repr } => {
+            let output = mapper.map_ty(output)?;
+            mapper.map_future(ty, output, repr)
+        }
+        TypeKind::Stream { item, repr } => {
+            let item = mapper.map_ty(item)?;
+            mapper.map_stream(ty, item, repr)
+        }
+        TypeKind::Error { repr } => mapper.map_error(ty, repr),
+        TypeKind::UserType { qname, repr: _ } => mapper.map_user_type(ty, qname),
+        TypeKind::Custom { path } => mapper.map_custom(ty, path),
+        TypeKind::TraitObject { qname, repr } => mapper.map_trait_object(ty, qname, repr),
+        _ => unsupported(ty),
+    }
+}
+
+/// Report that `ty` is not supported by this mapper.
+pub fn unsupported<T>(ty: &Ty) -> anyhow::Result<T> {
+    anyhow::bail!("{span}: unsupported type `{ty}` (`{kind:?}`)", span = ty.span(), kind = ty.kind())
+}
@@ -0,0 +1,285 @@
+//! Traversal of the IDL.
+//!
+//! Implement [`Visitor`][] and override the methods for the parts of the IDL you care about;
+//! the default implementations recurse into the children of each node by calling the
+//! corresponding `walk_*` function. When overriding a method, call the `walk_*` function
+//! yourself if you want traversal to continue into the children.
+//!
+//! ```rust,ignore
+//! struct CountRecords(usize);
+//!
+//! impl<'idl> Visitor<'idl> for CountRecords {
+//!     fn visit_record(&mut self, qname: &'idl QualifiedName, record: &'idl Record) -> anyhow::Result<()> {
+//!         self.0 += 1;
+//!         walk_record(self, qname, record)
+//!     }
+//! }
+//! ```
+
+use crate::idl::{
+    Enum, EnumArm, Field, Function, FunctionInput, FunctionOutput, Idl, Interface, Item, Method, Newtype, QualifiedName,
+    Record, RefdTy, Resource, Signature, Ty, TypeKind, Variant, VariantArm,
+};
+
+/// Visits the items, methods, and types of an [`Idl`][].
+///
+/// Each item-level method is given the qualified name of the item being visited
+/// (for methods and arms, the name of the item they belong to).
+pub trait Visitor<'idl> {
+    fn visit_idl(&mut self, idl: &'idl Idl) -> anyhow::Result<()> {
+        walk_idl(self, idl)
+    }
+
+    fn visit_item(&mut self, qname: &'idl QualifiedName, item: &'idl Item) -> anyhow::Result<()> {
+        walk_item(self, qname, item)
+    }
+
+    fn visit_resource(&mut self, qname: &'idl QualifiedName, resource: &'idl Resource) -> anyhow::Result<()> {
+        walk_resource(self, qname, resource)
+    }
+
+    fn visit_record(&mut self, qname: &'idl QualifiedName, record: &'idl Record) -> anyhow::Result<()> {
+        walk_record(self, qname, record)
+    }
+
+    fn visit_newtype(&mut self, qname: &'idl QualifiedName, newtype: &'idl Newtype) -> anyhow::Result<()> {
+        walk_newtype(self, qname, newtype)
+    }
+
+    fn visit_variant(&mut self, qname: &'idl QualifiedName, variant: &'idl Variant) -> anyhow::Result<()> {
+        walk_variant(self, qname, variant)
+    }
+
+    fn visit_variant_arm(&mut self, qname: &'idl QualifiedName, arm: &'idl VariantArm) -> anyhow::Result<()> {
+        walk_variant_arm(self, qname, arm)
+    }
+
+    fn visit_enum(&mut self, qname: &'idl QualifiedName, an_enum: &'idl Enum) -> anyhow::Result<()> {
+        walk_enum(self, qname, an_enum)
+    }
+
+    fn visit_enum_arm(&mut self, qname: &'idl QualifiedName, arm: &'idl EnumArm) -> anyhow::Result<()> {
+        let _ = (qname, arm);
+        Ok(())
+    }
+
+    fn visit_interface(&mut self, qname: &'idl QualifiedName, interface: &'idl Interface) -> anyhow::Result<()> {
+        walk_interface(self, qname, interface)
+    }
+
+    fn visit_function(&mut self, qname: &'idl QualifiedName, function: &'idl Function) -> anyhow::Result<()> {
+        walk_function(self, qname, function)
+    }
+
+    fn visit_method(&mut self, qname: &'idl QualifiedName, method: &'idl Method) -> anyhow::Result<()> {
+        walk_method(self, qname, method)
+    }
+
+    fn visit_field(&mut self, field: &'idl Field) -> anyhow::Result<()> {
+        walk_field(self, field)
+    }
+
+    fn visit_signature(&mut self, signature: &'idl Signature) -> anyhow::Result<()> {
+        walk_signature(self, signature)
+    }
+
+    fn visit_function_input(&mut self, input: &'idl FunctionInput) -> anyhow::Result<()> {
+        walk_function_input(self, input)
+    }
+
+    fn visit_function_output(&mut self, output: &'idl FunctionOutput) -> anyhow::Result<()> {
+        walk_function_output(self, output)
+    }
+
+    fn visit_refd_ty(&mut self, refd_ty: &'idl RefdTy) -> anyhow::Result<()> {
+        walk_refd_ty(self, refd_ty)
+    }
+
+    fn visit_ty(&mut self, ty: &'idl Ty) -> anyhow::Result<()> {
+        walk_ty(self, ty)
+    }
+}
+
+/// Visit each definition in the IDL (in order of qualified name).
+pub fn walk_idl<'idl, V: Visitor<'idl> + ?Sized>(visitor: &mut V, idl: &'idl Idl) -> anyhow::Result<()> {
+    for (qname, item) in idl.definitions() {
+        visitor.visit_item(qname, item)?;
+    }
+    Ok(())
+}
+
+pub fn walk_item<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    qname: &'idl QualifiedName,
+    item: &'idl Item,
+) -> anyhow::Result<()> {
+    match item {
+        Item::Resource(resource) => visitor.visit_resource(qname, resource),
+        Item::Record(record) => visitor.visit_record(qname, record),
+        Item::Newtype(newtype) => visitor.visit_newtype(qname, newtype),
+        Item::Variant(variant) => visitor.visit_variant(qname, variant),
+        Item::Enum(an_enum) => visitor.visit_enum(qname, an_enum),
+        Item::Function(function) => visitor.visit_function(qname, function),
+        Item::Interface(interface) => visitor.visit_interface(qname, interface),
+        _ => anyhow::bail!("unsupported item: {item:?}"),
+    }
+}
+
+pub fn walk_resource<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    qname: &'idl QualifiedName,
+    resource: &'idl Resource,
+) -> anyhow::Result<()> {
+    walk_methods(visitor, qname, resource.methods())
+}
+
+pub fn walk_record<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    qname: &'idl QualifiedName,
+    record: &'idl Record,
+) -> anyhow::Result<()> {
+    for field in record.fields() {
+        visitor.visit_field(field)?;
+    }
+    walk_methods(visitor, qname, record.methods())
+}
+
+pub fn walk_newtype<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    qname: &'idl QualifiedName,
+    newtype: &'idl Newtype,
+) -> anyhow::Result<()> {
+    visitor.visit_ty(newtype.ty())?;
+    walk_methods(visitor, qname, newtype.methods())
+}
+
+pub fn walk_variant<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    qname: &'idl QualifiedName,
+    variant: &'idl Variant,
+) -> anyhow::Result<()> {
+    for arm in variant.arms() {
+        visitor.visit_variant_arm(qname, arm)?;
+    }
+    walk_methods(visitor, qname, variant.methods())
+}
+
+pub fn walk_variant_arm<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    _qname: &'idl QualifiedName,
+    arm: &'idl VariantArm,
+) -> anyhow::Result<()> {
+    for field in arm.fields() {
+        visitor.visit_field(field)?;
+    }
+    Ok(())
+}
+
+pub fn walk_enum<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    qname: &'idl QualifiedName,
+    an_enum: &'idl Enum,
+) -> anyhow::Result<()> {
+    for arm in an_enum.arms() {
+        visitor.visit_enum_arm(qname, arm)?;
+    }
+    walk_methods(visitor, qname, an_enum.methods())
+}
+
+pub fn walk_interface<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    qname: &'idl QualifiedName,
+    interface: &'idl Interface,
+) -> anyhow::Result<()> {
+    walk_methods(visitor, qname, interface.methods())
+}
+
+pub fn walk_function<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    _qname: &'idl QualifiedName,
+    function: &'idl Function,
+) -> anyhow::Result<()> {
+    visitor.visit_signature(function.signature())
+}
+
+pub fn walk_method<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    _qname: &'idl QualifiedName,
+    method: &'idl Method,
+) -> anyhow::Result<()> {
+    visitor.visit_signature(method.signature())
+}
+
+fn walk_methods<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    qname: &'idl QualifiedName,
+    methods: &'idl [Method],
+) -> anyhow::Result<()> {
+    for method in methods {
+        visitor.visit_method(qname, method)?;
+    }
+    Ok(())
+}
+
+pub fn walk_field<'idl, V: Visitor<'idl> + ?Sized>(visitor: &mut V, field: &'idl Field) -> anyhow::Result<()> {
+    visitor.visit_ty(field.ty())
+}
+
+pub fn walk_signature<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    signature: &'idl Signature,
+) -> anyhow::Result<()> {
+    for input in signature.inputs() {
+        visitor.visit_function_input(input)?;
+    }
+    visitor.visit_function_output(signature.output_ty())
+}
+
+pub fn walk_function_input<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    input: &'idl FunctionInput,
+) -> anyhow::Result<()> {
+    visitor.visit_refd_ty(input.refd_ty())
+}
+
+pub fn walk_function_output<'idl, V: Visitor<'idl> + ?Sized>(
+    visitor: &mut V,
+    output: &'idl FunctionOutput,
+) -> anyhow::Result<()> {
+    visitor.visit_refd_ty(output.main_ty())?;
+    if let Some(error_ty) = output.error_ty() {
+        visitor.visit_ty(error_ty)?;
+    }
+    Ok(())
+}
+
+pub fn walk_refd_ty<'idl, V: Visitor<'idl> + ?Sized>(visitor: &mut V, refd_ty: &'idl RefdTy) -> anyhow::Result<()> {
+    visitor.visit_ty(refd_ty.ty())
+}
+
+/// Visit the types nested within `ty` (e.g., the element type of a `Vec`).
+/// User types are not followed to their definitions.
+pub fn walk_ty<'idl, V: Visitor<'idl> + ?Sized>(visitor: &mut V, ty: &'idl Ty) -> anyhow::Result<()> {
+    match ty.kind() {
+        TypeKind::Map { key, value, repr: _ } => {
+            visitor.visit_ty(key)?;
+            visitor.visit_ty(value)?;
+        }
+        TypeKind::Vec { element, repr: _ }
+        | TypeKind::Set { element, repr: _ }
+        | TypeKind::Option { element, repr: _ } => visitor.visit_ty(element)?,
+        TypeKind::Result { ok, err, repr: _ } => {
+            visitor.visit_ty(ok)?;
+            visitor.visit_ty(err)?;
+        }
+        TypeKind::Tuple { elements, repr: _ } => {
+            for element in elements {
+                visitor.visit_ty(element)?;
+            }
+        }
+        TypeKind::Future { output, repr: _ } => visitor.visit_ty(output)?,
+        TypeKind::Stream { item, repr: _ } => visitor.visit_ty(item)?,
+        _ => {}
+    }
+    Ok(())
+}
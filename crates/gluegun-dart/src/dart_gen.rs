@@ -0,0 +1,399 @@
+use gluegun_core::{
+    codegen::{CodeWriter, DirBuilder},
+    idl::{Idl, Item, MethodCategory, QualifiedName, Resource},
+};
+
+use crate::ffi::{self, Callable, FfiTy};
+
+/// Generates a Dart package (`pubspec.yaml` and `lib/<crate>.dart`) whose functions and classes
+/// invoke the exported Rust functions through `dart:ffi`.
+pub(crate) struct DartCodeGenerator<'idl> {
+    idl: &'idl Idl,
+
+    /// Name of the native library to load (e.g., `my_crate_dart` for `libmy_crate_dart.so`).
+    library_name: String,
+
+    /// Whether to generate the `GlueGunDebug` class (see `generate_debug_class`).
+    debug_bindings: bool,
+}
+
+impl<'idl> DartCodeGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl, library_name: String, debug_bindings: bool) -> Self {
+        Self { idl, library_name, debug_bindings }
+    }
+
+    pub(crate) fn generate(mut self, mut dir: DirBuilder<'_>) -> anyhow::Result<()> {
+        self.generate_pubspec(&mut dir)?;
+
+        let mut functions = vec![];
+        let mut resources = vec![];
+        for (qname, item) in self.idl.definitions() {
+            match item {
+                Item::Function(function) => {
+                    functions.push((qname, Callable::function(self.idl, qname, function.signature())?));
+                }
+                Item::Resource(resource) => resources.push((qname, resource)),
+                _ => anyhow::bail!(
+                    "`{}`: only functions and resources are currently supported by the dart backend",
+                    qname.colon_colon()
+                ),
+            }
+        }
+
+        let mut file = dir.add_file(format!("lib/{}.dart", self.idl.crate_name()))?;
+        self.generate_support(&mut file)?;
+        if self.debug_bindings {
+            write!(file, "")?;
+            self.generate_debug_class(&mut file)?;
+        }
+
+        for (qname, callable) in &functions {
+            write!(file, "")?;
+            self.generate_lookup(&mut file, callable)?;
+            write!(file, "")?;
+            self.generate_wrapper(&mut file, "", &ffi::dart_function_name(qname), callable)?;
+        }
+
+        for (qname, resource) in resources {
+            write!(file, "")?;
+            self.generate_class(&mut file, qname, resource)?;
+        }
+
+        Ok(())
+    }
+
+    fn generate_pubspec(&mut self, dir: &mut DirBuilder<'_>) -> anyhow::Result<()> {
+        let metadata = self.idl.crate_metadata();
+        let mut file = dir.add_file("pubspec.yaml")?;
+        write!(file, "name: {}", self.idl.crate_name())?;
+        match metadata.description() {
+            Some(description) => write!(file, "description: {description:?}")?,
+            None => write!(file, "description: Dart bindings for the `{}` Rust crate.", self.idl.crate_name())?,
+        }
+        write!(file, "version: {}", metadata.version().as_deref().unwrap_or("0.1.0"))?;
+        if let Some(repository) = metadata.repository() {
+            write!(file, "repository: {repository}")?;
+        }
+        write!(file, "")?;
+        write!(file, "environment:")?;
+        write!(file, "  sdk: ^3.0.0")?;
+        write!(file, "")?;
+        write!(file, "dependencies:")?;
+        write!(file, "  ffi: ^2.1.0")?;
+        Ok(())
+    }
+
+    /// Generate the `GlueGunDebug` class, whose `liveObjects` returns the number of Rust objects
+    /// that Dart has not freed yet, so that tests can check that none are leaked.
+    fn generate_debug_class(&mut self, file: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(
+            file,
+            "final _liveObjects = _lib.lookupFunction<Size Function(), int Function()>('{}');",
+            ffi::live_objects_symbol(self.idl)
+        )?;
+        write!(file, "")?;
+        write!(file, "/// Counts the Rust objects owned by Dart objects (generated with `debug-bindings`).")?;
+        write!(file, "abstract final class GlueGunDebug {{")?;
+        write!(file, "/// The number of Rust objects that have been neither disposed nor garbage collected.")?;
+        write!(file, "static int liveObjects() => _liveObjects();")?;
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// Generate the imports, the library lookup, and the helpers shared by all wrappers.
+    fn generate_support(&mut self, file: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        let library_name = &self.library_name;
+
+        write!(file, "// ignore_for_file: non_constant_identifier_names")?;
+        write!(file, "")?;
+        write!(file, "import 'dart:convert';")?;
+        write!(file, "import 'dart:ffi';")?;
+        write!(file, "import 'dart:io';")?;
+        write!(file, "import 'dart:isolate';")?;
+        write!(file, "")?;
+        write!(file, "import 'package:ffi/ffi.dart';")?;
+        write!(file, "")?;
+        write!(file, "final DynamicLibrary _lib = DynamicLibrary.open(_libraryFileName());")?;
+        write!(file, "")?;
+        write!(file, "String _libraryFileName() {{")?;
+        write!(file, "if (Platform.isMacOS || Platform.isIOS) {{")?;
+        write!(file, "return 'lib{library_name}.dylib';")?;
+        write!(file, "}} else if (Platform.isWindows) {{")?;
+        write!(file, "return '{library_name}.dll';")?;
+        write!(file, "}} else {{")?;
+        write!(file, "return 'lib{library_name}.so';")?;
+        write!(file, "}}")?;
+        write!(file, "}}")?;
+
+        write!(file, "")?;
+        write!(file, "/// Thrown when a Rust function returns an error, carrying the error's message.")?;
+        write!(file, "class GlueGunException implements Exception {{")?;
+        write!(file, "final String message;")?;
+        write!(file, "")?;
+        write!(file, "GlueGunException(this.message);")?;
+        write!(file, "")?;
+        write!(file, "@override")?;
+        write!(file, "String toString() => 'GlueGunException: $message';")?;
+        write!(file, "}}")?;
+
+        write!(file, "")?;
+        write!(
+            file,
+            "final _freeBytes = _lib.lookupFunction<Void Function(Pointer<Uint8>, Size), void Function(Pointer<Uint8>, int)>('{}');",
+            ffi::free_bytes_symbol(self.idl)
+        )?;
+
+        // Strings are passed to Rust as UTF-8 (pointer, length) pairs. One extra byte is allocated
+        // so that the pointer is never null, even for empty strings.
+        write!(file, "")?;
+        write!(file, "(Pointer<Uint8>, int) _stringIn(String value, Arena arena) {{")?;
+        write!(file, "final bytes = utf8.encode(value);")?;
+        write!(file, "final ptr = arena<Uint8>(bytes.length + 1);")?;
+        write!(file, "ptr.asTypedList(bytes.length).setAll(0, bytes);")?;
+        write!(file, "return (ptr, bytes.length);")?;
+        write!(file, "}}")?;
+
+        write!(file, "")?;
+        write!(file, "String _stringOut(Pointer<Uint8> ptr, int len) {{")?;
+        write!(file, "try {{")?;
+        write!(file, "return utf8.decode(ptr.asTypedList(len));")?;
+        write!(file, "}} finally {{")?;
+        write!(file, "_freeBytes(ptr, len);")?;
+        write!(file, "}}")?;
+        write!(file, "}}")?;
+
+        write!(file, "")?;
+        write!(file, "void _checkError(Pointer<Pointer<Uint8>> errPtr, Pointer<Size> errLen) {{")?;
+        write!(file, "if (errPtr.value != nullptr) {{")?;
+        write!(file, "throw GlueGunException(_stringOut(errPtr.value, errLen.value));")?;
+        write!(file, "}}")?;
+        write!(file, "}}")?;
+
+        Ok(())
+    }
+
+    /// Generate a class wrapping a pointer to a boxed instance of the resource `qname`.
+    /// The instance is dropped when the object is garbage collected or explicitly disposed.
+    fn generate_class(&mut self, file: &mut CodeWriter<'_>, qname: &QualifiedName, resource: &Resource) -> anyhow::Result<()> {
+        let class = ffi::class_name(qname);
+        let drop_symbol = ffi::drop_symbol(qname);
+
+        let methods = resource
+            .methods()
+            .iter()
+            .map(|method| Ok((method, Callable::method(self.idl, qname, method)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        write!(
+            file,
+            "final _{drop_symbol} = _lib.lookupFunction<Void Function(Pointer<Void>), void Function(Pointer<Void>)>('{drop_symbol}');"
+        )?;
+        for (_, callable) in &methods {
+            write!(file, "")?;
+            self.generate_lookup(file, callable)?;
+        }
+
+        write!(file, "")?;
+        write!(file, "class {class} implements Finalizable {{")?;
+        write!(
+            file,
+            "static final _finalizer = NativeFinalizer(_lib.lookup<NativeFunction<Void Function(Pointer<Void>)>>('{drop_symbol}'));"
+        )?;
+        write!(file, "")?;
+        write!(file, "Pointer<Void> _handle;")?;
+        write!(file, "")?;
+        write!(file, "{class}._(this._handle) {{")?;
+        write!(file, "_finalizer.attach(this, _handle, detach: this);")?;
+        write!(file, "}}")?;
+        write!(file, "")?;
+        write!(file, "Pointer<Void> get _live {{")?;
+        write!(file, "if (_handle == nullptr) {{")?;
+        write!(file, "throw StateError('`{class}` used after `dispose`');")?;
+        write!(file, "}}")?;
+        write!(file, "return _handle;")?;
+        write!(file, "}}")?;
+        write!(file, "")?;
+        write!(file, "/// Drop the Rust value now rather than when this object is garbage collected.")?;
+        write!(file, "void dispose() {{")?;
+        write!(file, "if (_handle != nullptr) {{")?;
+        write!(file, "_finalizer.detach(this);")?;
+        write!(file, "_{drop_symbol}(_handle);")?;
+        write!(file, "_handle = nullptr;")?;
+        write!(file, "}}")?;
+        write!(file, "}}")?;
+
+        for (method, callable) in &methods {
+            write!(file, "")?;
+            let name = method.name().camel_case().to_string();
+            match method.category() {
+                MethodCategory::Constructor => self.generate_wrapper(file, "factory ", &class, callable)?,
+                MethodCategory::NamedConstructor | MethodCategory::StaticMethod => self.generate_wrapper(file, "static ", &name, callable)?,
+                _ => self.generate_wrapper(file, "", &name, callable)?,
+            }
+        }
+
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// Generate the lookup of the exported symbol of `callable`, e.g.
+    ///
+    /// ```dart
+    /// final _gluegun__my_crate__add = _lib.lookupFunction<Int32 Function(Int32, Int32), int Function(int, int)>('...');
+    /// ```
+    fn generate_lookup(&mut self, file: &mut CodeWriter<'_>, callable: &Callable<'_>) -> anyhow::Result<()> {
+        let mut native = vec![];
+        let mut dart = vec![];
+        if callable.receiver.is_some() {
+            native.push("Pointer<Void>".to_string());
+            dart.push("Pointer<Void>".to_string());
+        }
+        for (_, ffi_ty) in &callable.inputs {
+            match ffi_ty {
+                FfiTy::Scalar(scalar) => {
+                    native.push(ffi::scalar_native_ty(scalar)?.to_string());
+                    dart.push(ffi::scalar_dart_ty(scalar)?.to_string());
+                }
+                FfiTy::String => {
+                    native.extend(["Pointer<Uint8>".to_string(), "Size".to_string()]);
+                    dart.extend(["Pointer<Uint8>".to_string(), "int".to_string()]);
+                }
+                FfiTy::Resource(_) => {
+                    native.push("Pointer<Void>".to_string());
+                    dart.push("Pointer<Void>".to_string());
+                }
+                FfiTy::Unit => unreachable!("unit inputs"),
+            }
+        }
+        if let FfiTy::String = callable.output {
+            native.push("Pointer<Size>".to_string());
+            dart.push("Pointer<Size>".to_string());
+        }
+        native.extend(["Pointer<Pointer<Uint8>>".to_string(), "Pointer<Size>".to_string()]);
+        dart.extend(["Pointer<Pointer<Uint8>>".to_string(), "Pointer<Size>".to_string()]);
+        let (native_output, dart_output) = match &callable.output {
+            FfiTy::Scalar(scalar) => (ffi::scalar_native_ty(scalar)?, ffi::scalar_dart_ty(scalar)?),
+            FfiTy::String => ("Pointer<Uint8>", "Pointer<Uint8>"),
+            FfiTy::Resource(_) => ("Pointer<Void>", "Pointer<Void>"),
+            FfiTy::Unit => ("Void", "void"),
+        };
+        write!(
+            file,
+            "final _{symbol} = _lib.lookupFunction<{native_output} Function({}), {dart_output} Function({})>('{symbol}');",
+            native.join(", "),
+            dart.join(", "),
+            symbol = callable.symbol,
+        )?;
+        Ok(())
+    }
+
+    /// Generate the public wrapper `name` for `callable` (with `modifier` being `static `, `factory `, or empty).
+    /// Async functions are run on a separate isolate (see [`Isolate.run`][]), so they do not block
+    /// the caller's event loop; their synchronous body is generated as a separate private function.
+    ///
+    /// [`Isolate.run`]: https://api.dart.dev/stable/dart-isolate/Isolate/run.html
+    fn generate_wrapper(
+        &mut self,
+        file: &mut CodeWriter<'_>,
+        modifier: &str,
+        name: &str,
+        callable: &Callable<'_>,
+    ) -> anyhow::Result<()> {
+        let output_ty = match &callable.output {
+            FfiTy::Scalar(scalar) => ffi::scalar_dart_ty(scalar)?.to_string(),
+            FfiTy::String => "String".to_string(),
+            FfiTy::Resource(resource) => ffi::class_name(resource),
+            FfiTy::Unit => "void".to_string(),
+        };
+        let mut parameters = vec![];
+        let mut parameter_names = vec![];
+        for (input, ffi_ty) in &callable.inputs {
+            let dart_ty = match ffi_ty {
+                FfiTy::Scalar(scalar) => ffi::scalar_dart_ty(scalar)?.to_string(),
+                FfiTy::String => "String".to_string(),
+                FfiTy::Resource(resource) => ffi::class_name(resource),
+                FfiTy::Unit => unreachable!("unit inputs"),
+            };
+            let name = input.name().camel_case();
+            parameters.push(format!("{dart_ty} {name}"));
+            parameter_names.push(name.to_string());
+        }
+        let parameters = parameters.join(", ");
+
+        // Constructors are declared without a return type.
+        let signature = |output_ty: &str, name: &str| {
+            if modifier == "factory " {
+                format!("{modifier}{name}({parameters})")
+            } else {
+                format!("{modifier}{output_ty} {name}({parameters})")
+            }
+        };
+
+        if callable.is_async() {
+            let sync_name = format!("_{}_sync", callable.symbol);
+            write!(
+                file,
+                "{} => Isolate.run(() => {sync_name}({}));",
+                signature(&format!("Future<{output_ty}>"), name),
+                parameter_names.join(", ")
+            )?;
+            write!(file, "")?;
+            // The synchronous body is a top-level function so that the closure sent to the isolate
+            // does not capture `this`.
+            write!(file, "{output_ty} {sync_name}({parameters}) {{")?;
+        } else {
+            write!(file, "{} {{", signature(&output_ty, name))?;
+        }
+        self.generate_body(file, callable)?;
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    fn generate_body(&mut self, file: &mut CodeWriter<'_>, callable: &Callable<'_>) -> anyhow::Result<()> {
+        write!(file, "return using((arena) {{")?;
+
+        let mut arguments = vec![];
+        if callable.receiver.is_some() {
+            arguments.push("_live".to_string());
+        }
+        for (input, ffi_ty) in &callable.inputs {
+            let name = input.name().camel_case();
+            match ffi_ty {
+                FfiTy::Scalar(_) => arguments.push(name.to_string()),
+                FfiTy::String => {
+                    write!(file, "final ({name}Ptr, {name}Len) = _stringIn({name}, arena);")?;
+                    arguments.push(format!("{name}Ptr"));
+                    arguments.push(format!("{name}Len"));
+                }
+                FfiTy::Resource(_) => arguments.push(format!("{name}._live")),
+                FfiTy::Unit => unreachable!("unit inputs"),
+            }
+        }
+        if let FfiTy::String = callable.output {
+            write!(file, "final outLen = arena<Size>();")?;
+            arguments.push("outLen".to_string());
+        }
+        // Every function can report an error, as panics are reported like errors.
+        write!(file, "final errPtr = arena<Pointer<Uint8>>();")?;
+        write!(file, "errPtr.value = nullptr;")?;
+        write!(file, "final errLen = arena<Size>();")?;
+        arguments.push("errPtr".to_string());
+        arguments.push("errLen".to_string());
+
+        let call = format!("_{}({})", callable.symbol, arguments.join(", "));
+        match &callable.output {
+            FfiTy::Unit => write!(file, "{call};")?,
+            _ => write!(file, "final result = {call};")?,
+        }
+        write!(file, "_checkError(errPtr, errLen);")?;
+        match &callable.output {
+            FfiTy::Scalar(_) => write!(file, "return result;")?,
+            FfiTy::String => write!(file, "return _stringOut(result, outLen.value);")?,
+            FfiTy::Resource(resource) => write!(file, "return {}._(result);", ffi::class_name(resource))?,
+            FfiTy::Unit => {}
+        }
+
+        write!(file, "}});")?;
+        Ok(())
+    }
+}
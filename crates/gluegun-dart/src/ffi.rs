@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+
+use gluegun_core::idl::{
+    FunctionInput, FunctionOutput, Idl, IsAsync, Item, Method, MethodCategory, Name, QualifiedName, RefdTy, Scalar,
+    SelfKind, Signature, StringRepr, Ty, TypeKind, UserTypeRepr,
+};
+
+/// How a value crosses the C ABI boundary between Dart (via `dart:ffi`) and Rust.
+pub(crate) enum FfiTy<'idl> {
+    /// A primitive, passed by value.
+    Scalar(Scalar),
+
+    /// A UTF-8 string. Inputs are passed as a pointer and a length;
+    /// outputs are returned as a pointer to a Rust-allocated buffer whose length is written
+    /// to an out-parameter (the buffer must be released with the free function, see [`free_bytes_symbol`][]).
+    String,
+
+    /// An instance of a resource, passed as a pointer to a boxed value owned by a Dart object.
+    /// Inputs must be references (`&T`) since Dart cannot give up ownership;
+    /// outputs must be owned.
+    Resource(&'idl QualifiedName),
+
+    /// No value (return types only).
+    Unit,
+}
+
+impl<'idl> FfiTy<'idl> {
+    pub(crate) fn for_input(idl: &Idl, input: &'idl FunctionInput) -> anyhow::Result<Self> {
+        let ffi_ty = Self::for_ty(idl, input.refd_ty().ty())?;
+        if let (FfiTy::Resource(_), RefdTy::Owned(..)) = (&ffi_ty, input.refd_ty()) {
+            anyhow::bail!(
+                "{}: resources can only be passed by reference in the dart backend",
+                input.refd_ty().ty().span()
+            );
+        }
+        Ok(ffi_ty)
+    }
+
+    pub(crate) fn for_output(idl: &Idl, output: &'idl FunctionOutput) -> anyhow::Result<Self> {
+        let main_ty = output.main_ty().ty();
+        if main_ty.is_unit() {
+            return Ok(FfiTy::Unit);
+        }
+        let ffi_ty = Self::for_ty(idl, main_ty)?;
+        if let (FfiTy::Resource(_), true) = (&ffi_ty, output.requires_copy_out()) {
+            anyhow::bail!("{}: resources can only be returned by value in the dart backend", main_ty.span());
+        }
+        Ok(ffi_ty)
+    }
+
+    fn for_ty(idl: &Idl, ty: &'idl Ty) -> anyhow::Result<Self> {
+        match ty.kind() {
+            TypeKind::Scalar(Scalar::Char) => unsupported(ty),
+            TypeKind::Scalar(scalar) => Ok(FfiTy::Scalar(*scalar)),
+            TypeKind::String {
+                repr: StringRepr::String | StringRepr::StrRef,
+            } => Ok(FfiTy::String),
+            TypeKind::UserType { qname, repr: UserTypeRepr::Direct } => match idl.item(qname) {
+                Some(Item::Resource(_)) => Ok(FfiTy::Resource(qname)),
+                _ => unsupported(ty),
+            },
+            _ => unsupported(ty),
+        }
+    }
+}
+
+fn unsupported<T>(ty: &Ty) -> anyhow::Result<T> {
+    anyhow::bail!("{}: type `{ty}` is not yet supported by the dart backend", ty.span())
+}
+
+/// A function or method, as exported from Rust and wrapped in Dart.
+pub(crate) struct Callable<'idl> {
+    /// The exported C symbol.
+    pub(crate) symbol: String,
+
+    /// The path to invoke from Rust, e.g. `my_crate::Counter::increment`.
+    pub(crate) rust_path: String,
+
+    /// For instance methods, the resource type and whether it is borrowed mutably.
+    pub(crate) receiver: Option<(&'idl QualifiedName, bool)>,
+
+    pub(crate) signature: &'idl Signature,
+    pub(crate) inputs: Vec<(&'idl FunctionInput, FfiTy<'idl>)>,
+    pub(crate) output: FfiTy<'idl>,
+}
+
+impl<'idl> Callable<'idl> {
+    pub(crate) fn function(idl: &Idl, qname: &'idl QualifiedName, signature: &'idl Signature) -> anyhow::Result<Self> {
+        Self::new(idl, function_symbol(qname), qname.colon_colon(), None, signature)
+    }
+
+    pub(crate) fn method(idl: &Idl, resource: &'idl QualifiedName, method: &'idl Method) -> anyhow::Result<Self> {
+        let receiver = match method.category() {
+            MethodCategory::Constructor | MethodCategory::NamedConstructor | MethodCategory::StaticMethod => None,
+            MethodCategory::InstanceMethod(SelfKind::ByRef) => Some((resource, false)),
+            MethodCategory::InstanceMethod(SelfKind::ByRefMut) => Some((resource, true)),
+            _ => anyhow::bail!(
+                "{}: only `&self` and `&mut self` methods are supported by the dart backend",
+                method.span()
+            ),
+        };
+        Self::new(
+            idl,
+            format!("{}__{}", function_symbol(resource), method.name()),
+            format!("{}::{}", resource.colon_colon(), method.name()),
+            receiver,
+            method.signature(),
+        )
+    }
+
+    fn new(
+        idl: &Idl,
+        symbol: String,
+        rust_path: String,
+        receiver: Option<(&'idl QualifiedName, bool)>,
+        signature: &'idl Signature,
+    ) -> anyhow::Result<Self> {
+        let inputs = signature
+            .inputs()
+            .iter()
+            .map(|input| Ok((input, FfiTy::for_input(idl, input)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let output = FfiTy::for_output(idl, signature.output_ty())?;
+
+        // Async functions run on a separate isolate, and `Pointer`s cannot be sent between isolates.
+        if let IsAsync::Yes = signature.is_async() {
+            let uses_resources = receiver.is_some()
+                || matches!(output, FfiTy::Resource(_))
+                || inputs.iter().any(|(_, ffi_ty)| matches!(ffi_ty, FfiTy::Resource(_)));
+            if uses_resources {
+                anyhow::bail!("`{rust_path}`: async functions involving resources are not yet supported by the dart backend");
+            }
+        }
+
+        Ok(Self {
+            symbol,
+            rust_path,
+            receiver,
+            signature,
+            inputs,
+            output,
+        })
+    }
+
+    pub(crate) fn is_async(&self) -> bool {
+        matches!(self.signature.is_async(), IsAsync::Yes)
+    }
+
+    pub(crate) fn is_fallible(&self) -> bool {
+        self.signature.output_ty().error_ty().is_some()
+    }
+}
+
+/// Resources map to Dart classes, which are all defined in the same library,
+/// so their names must be unique.
+pub(crate) fn check_class_names(idl: &Idl) -> anyhow::Result<()> {
+    let mut names = BTreeMap::new();
+    for (qname, item) in idl.definitions() {
+        if let Item::Resource(_) = item {
+            if let Some(previous) = names.insert(class_name(qname), qname) {
+                anyhow::bail!(
+                    "`{}` and `{}` have the same name, which is not supported by the dart backend",
+                    previous.colon_colon(),
+                    qname.colon_colon()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The Dart class for the resource `qname`, e.g., `Counter` for `my_crate::counters::Counter`.
+pub(crate) fn class_name(qname: &QualifiedName) -> String {
+    qname.tail_name().upper_camel_case().to_string()
+}
+
+/// The Dart function for the Rust function `qname`. All functions are defined in the same library,
+/// so functions in modules are prefixed with the module path, e.g., `geometryArea` for `my_crate::geometry::area`.
+pub(crate) fn dart_function_name(qname: &QualifiedName) -> String {
+    let names: Vec<String> = qname.names()[1..].iter().map(|name| name.to_string()).collect();
+    Name::from(names.join("_")).camel_case().to_string()
+}
+
+/// Rust type used for a scalar in the `extern "C"` signature.
+pub(crate) fn scalar_rust_ty(scalar: &Scalar) -> anyhow::Result<&'static str> {
+    Ok(match scalar {
+        Scalar::Boolean => "bool",
+        Scalar::I8 => "i8",
+        Scalar::I16 => "i16",
+        Scalar::I32 => "i32",
+        Scalar::I64 => "i64",
+        Scalar::U8 => "u8",
+        Scalar::U16 => "u16",
+        Scalar::U32 => "u32",
+        Scalar::U64 => "u64",
+        Scalar::F32 => "f32",
+        Scalar::F64 => "f64",
+        _ => anyhow::bail!("unsupported scalar type: `{scalar}`"),
+    })
+}
+
+/// `dart:ffi` native type describing a scalar.
+pub(crate) fn scalar_native_ty(scalar: &Scalar) -> anyhow::Result<&'static str> {
+    Ok(match scalar {
+        Scalar::Boolean => "Bool",
+        Scalar::I8 => "Int8",
+        Scalar::I16 => "Int16",
+        Scalar::I32 => "Int32",
+        Scalar::I64 => "Int64",
+        Scalar::U8 => "Uint8",
+        Scalar::U16 => "Uint16",
+        Scalar::U32 => "Uint32",
+        Scalar::U64 => "Uint64",
+        Scalar::F32 => "Float",
+        Scalar::F64 => "Double",
+        _ => anyhow::bail!("unsupported scalar type: `{scalar}`"),
+    })
+}
+
+/// Dart type used for a scalar. Dart's `int` is 64 bits, so `u64` values above `i64::MAX`
+/// are seen as negative numbers.
+pub(crate) fn scalar_dart_ty(scalar: &Scalar) -> anyhow::Result<&'static str> {
+    Ok(match scalar {
+        Scalar::Boolean => "bool",
+        Scalar::I8 | Scalar::I16 | Scalar::I32 | Scalar::I64 => "int",
+        Scalar::U8 | Scalar::U16 | Scalar::U32 | Scalar::U64 => "int",
+        Scalar::F32 | Scalar::F64 => "double",
+        _ => anyhow::bail!("unsupported scalar type: `{scalar}`"),
+    })
+}
+
+/// Name of the exported C symbol for the function (or type) `qname`, e.g. `gluegun__my_crate__module__function`.
+pub(crate) fn function_symbol(qname: &QualifiedName) -> String {
+    format!("gluegun__{}", qname.to_string("__"))
+}
+
+/// Name of the exported C symbol that drops an instance of the resource `qname`.
+pub(crate) fn drop_symbol(qname: &QualifiedName) -> String {
+    format!("{}__drop", function_symbol(qname))
+}
+
+/// Name of the exported C symbol that returns the number of live resources, with debug bindings.
+pub(crate) fn live_objects_symbol(idl: &Idl) -> String {
+    format!("gluegun__{}__live_objects", idl.crate_name())
+}
+
+/// Name of the exported C symbol that releases a buffer returned from Rust.
+/// It includes the crate name so that several generated libraries can be loaded into the same process.
+pub(crate) fn free_bytes_symbol(idl: &Idl) -> String {
+    format!("gluegun__{}__free_bytes", idl.crate_name())
+}
@@ -0,0 +1,58 @@
+use anyhow::Context;
+use gluegun_core::{
+    cli::{GenerateCx, GlueGunHelper},
+    codegen::LibraryCrate,
+    naming::{Case, Naming},
+};
+
+mod dart_gen;
+mod ffi;
+mod rs_gen;
+
+pub fn main() -> anyhow::Result<()> {
+    gluegun_core::cli::run(GlueGunDart)
+}
+
+/// Generates a Dart package that calls into the Rust crate through `dart:ffi`
+/// (and can therefore be used from Flutter apps): the Rust crate exports plain `extern "C"` functions
+/// which the Dart library looks up and wraps in idiomatic functions and classes.
+#[derive(Clone)]
+struct GlueGunDart;
+
+impl GlueGunHelper for GlueGunDart {
+    type Metadata = ();
+
+    const SUPPORTS_DEBUG_BINDINGS: bool = true;
+
+    fn name(&self) -> String {
+        "dart".to_string()
+    }
+
+    /// All classes are defined in the same library (see `ffi::class_name`).
+    fn naming(&self, _metadata: &()) -> Naming {
+        Naming::new()
+            .types(Case::UpperCamel)
+            .flatten_types()
+            .functions(Case::Camel)
+            .parameters(Case::Camel)
+    }
+
+    fn generate(self, cx: &mut GenerateCx, _metadata: &(), output: &mut LibraryCrate) -> anyhow::Result<()> {
+        ffi::check_class_names(cx.idl())?;
+
+        // The generated crate is a `cdylib` (the default) whose file name is derived from the crate name.
+        let library_name = output.crate_name().replace('-', "_");
+        let debug_bindings = output.debug_bindings_enabled();
+
+        let dart_dir = output.add_dir("dart").context("adding `dart` dir")?;
+        dart_gen::DartCodeGenerator::new(cx.idl(), library_name, debug_bindings)
+            .generate(dart_dir)
+            .context("generating Dart sources")?;
+
+        rs_gen::RustCodeGenerator::new(cx.idl(), debug_bindings)
+            .generate(output)
+            .context("generating Rust sources")?;
+
+        Ok(())
+    }
+}
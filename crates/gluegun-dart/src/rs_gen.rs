@@ -0,0 +1,285 @@
+use gluegun_core::{
+    codegen::{CodeWriter, LibraryCrate},
+    idl::{Idl, Item, QualifiedName, RefdTy, Resource},
+};
+
+use crate::ffi::{self, Callable, FfiTy};
+
+/// Generates `extern "C"` wrappers around each function and resource method,
+/// to be looked up from Dart through `DynamicLibrary`.
+pub(crate) struct RustCodeGenerator<'idl> {
+    idl: &'idl Idl,
+
+    /// Whether boxed resources are counted while live (see `LibraryCrate::debug_bindings_enabled`).
+    debug_bindings: bool,
+}
+
+impl<'idl> RustCodeGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl, debug_bindings: bool) -> Self {
+        Self { idl, debug_bindings }
+    }
+
+    pub(crate) fn generate(mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        let mut callables = vec![];
+        let mut resources = vec![];
+        for (qname, item) in self.idl.definitions() {
+            match item {
+                Item::Function(function) => {
+                    callables.push(Callable::function(self.idl, qname, function.signature())?);
+                }
+                Item::Resource(resource) => {
+                    for method in resource.methods() {
+                        callables.push(Callable::method(self.idl, qname, method)?);
+                    }
+                    resources.push((qname, resource));
+                }
+                _ => anyhow::bail!(
+                    "`{}`: only functions and resources are currently supported by the dart backend",
+                    qname.colon_colon()
+                ),
+            }
+        }
+
+        let mut lib_rs = lib.add_file("src/lib.rs")?;
+        self.generate_support(&mut lib_rs, callables.iter().any(|callable| callable.is_async()))?;
+        if self.debug_bindings {
+            self.generate_live_objects(&mut lib_rs)?;
+        }
+        for (qname, resource) in resources {
+            lib_rs.with_span(resource.span(), |lib_rs| self.generate_drop(lib_rs, qname, resource))?;
+        }
+        for callable in &callables {
+            self.generate_callable(&mut lib_rs, callable)?;
+        }
+
+        Ok(())
+    }
+
+    /// Generate the helpers for returning strings: they are returned as a leaked `Box<[u8]>`
+    /// whose length is written to an out-parameter, and Dart frees them once copied.
+    /// `gluegun_catch_panic` reports a panic like an error, as unwinding out of an `extern "C"` function aborts the process.
+    /// Async functions are driven to completion on the calling thread (Dart invokes them from
+    /// a separate isolate), which requires a minimal executor.
+    fn generate_support(&mut self, lib_rs: &mut CodeWriter<'_>, uses_async: bool) -> anyhow::Result<()> {
+        write!(lib_rs, "fn gluegun_string_out(value: String, out_len: *mut usize) -> *mut u8 {{")?;
+        write!(lib_rs, "let bytes = value.into_bytes().into_boxed_slice();")?;
+        write!(lib_rs, "unsafe {{ *out_len = bytes.len(); }}")?;
+        write!(lib_rs, "Box::into_raw(bytes) as *mut u8")?;
+        write!(lib_rs, "}}")?;
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "/// # Safety")?;
+        write!(lib_rs, "///")?;
+        write!(lib_rs, "/// `ptr` and `len` must have been returned by one of the other functions in this library.")?;
+        write!(lib_rs, "#[unsafe(no_mangle)]")?;
+        write!(
+            lib_rs,
+            "pub unsafe extern \"C\" fn {free}(ptr: *mut u8, len: usize) {{",
+            free = ffi::free_bytes_symbol(self.idl),
+        )?;
+        write!(lib_rs, "drop(unsafe {{ Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)) }});")?;
+        write!(lib_rs, "}}")?;
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "fn gluegun_catch_panic<T>(err_ptr: *mut *mut u8, err_len: *mut usize, f: impl FnOnce() -> T) -> Option<T> {{")?;
+        write!(lib_rs, "let payload = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {{")?;
+        write!(lib_rs, "Ok(value) => return Some(value),")?;
+        write!(lib_rs, "Err(payload) => payload,")?;
+        write!(lib_rs, "}};")?;
+        write!(lib_rs, "let message = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {{")?;
+        write!(lib_rs, "(Some(message), _) => message,")?;
+        write!(lib_rs, "(None, Some(message)) => message.as_str(),")?;
+        write!(lib_rs, "(None, None) => \"(no message)\",")?;
+        write!(lib_rs, "}};")?;
+        write!(lib_rs, "unsafe {{ *err_ptr = gluegun_string_out(format!(\"Rust panicked: {{message}}\"), err_len); }}")?;
+        write!(lib_rs, "None")?;
+        write!(lib_rs, "}}")?;
+
+        if uses_async {
+            write!(lib_rs, "")?;
+            write!(lib_rs, "struct GlueGunThreadWaker(std::thread::Thread);")?;
+            write!(lib_rs, "")?;
+            write!(lib_rs, "impl std::task::Wake for GlueGunThreadWaker {{")?;
+            write!(lib_rs, "fn wake(self: std::sync::Arc<Self>) {{")?;
+            write!(lib_rs, "self.0.unpark();")?;
+            write!(lib_rs, "}}")?;
+            write!(lib_rs, "}}")?;
+            write!(lib_rs, "")?;
+            write!(lib_rs, "fn gluegun_block_on<F: std::future::Future>(future: F) -> F::Output {{")?;
+            write!(lib_rs, "let mut future = std::pin::pin!(future);")?;
+            write!(
+                lib_rs,
+                "let waker = std::task::Waker::from(std::sync::Arc::new(GlueGunThreadWaker(std::thread::current())));"
+            )?;
+            write!(lib_rs, "let mut cx = std::task::Context::from_waker(&waker);")?;
+            write!(lib_rs, "loop {{")?;
+            write!(lib_rs, "match future.as_mut().poll(&mut cx) {{")?;
+            write!(lib_rs, "std::task::Poll::Ready(output) => return output,")?;
+            write!(lib_rs, "std::task::Poll::Pending => std::thread::park(),")?;
+            write!(lib_rs, "}}")?;
+            write!(lib_rs, "}}")?;
+            write!(lib_rs, "}}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Export the number of boxed resources that Dart has not dropped yet (see `gluegun_debug::acquire`).
+    fn generate_live_objects(&mut self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[unsafe(no_mangle)]")?;
+        write!(lib_rs, "pub extern \"C\" fn {}() -> usize {{", ffi::live_objects_symbol(self.idl))?;
+        write!(lib_rs, "crate::gluegun_debug::live_objects()")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Instances of resources are boxed; the Dart object owning the box drops it
+    /// when it is disposed or garbage collected. With debug bindings, boxes are counted while live.
+    fn generate_drop(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, _resource: &Resource) -> anyhow::Result<()> {
+        write!(lib_rs, "")?;
+        write!(lib_rs, "/// # Safety")?;
+        write!(lib_rs, "///")?;
+        write!(lib_rs, "/// `this` must have been returned by one of the other functions in this library.")?;
+        write!(lib_rs, "#[unsafe(no_mangle)]")?;
+        write!(
+            lib_rs,
+            "pub unsafe extern \"C\" fn {}(this: *mut {}) {{",
+            ffi::drop_symbol(qname),
+            qname.colon_colon()
+        )?;
+        write!(lib_rs, "drop(unsafe {{ Box::from_raw(this) }});")?;
+        if self.debug_bindings {
+            write!(lib_rs, "crate::gluegun_debug::release();")?;
+        }
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    fn generate_callable(&mut self, lib_rs: &mut CodeWriter<'_>, callable: &Callable<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "")?;
+        write!(lib_rs, "/// # Safety")?;
+        write!(lib_rs, "///")?;
+        write!(
+            lib_rs,
+            "/// String arguments must be valid UTF-8 (`ptr`, `len`) pairs and resources must be live instances."
+        )?;
+        write!(lib_rs, "#[unsafe(no_mangle)]")?;
+        write!(lib_rs, "pub unsafe extern \"C\" fn {}(", callable.symbol)?;
+        if let Some((resource, mutable)) = callable.receiver {
+            let pointer = if mutable { "*mut" } else { "*const" };
+            write!(lib_rs, "this: {pointer} {},", resource.colon_colon())?;
+        }
+        for (input, ffi_ty) in &callable.inputs {
+            let name = input.name();
+            match ffi_ty {
+                FfiTy::Scalar(scalar) => write!(lib_rs, "{name}: {},", ffi::scalar_rust_ty(scalar)?)?,
+                FfiTy::String => write!(lib_rs, "{name}_ptr: *const u8, {name}_len: usize,")?,
+                FfiTy::Resource(resource) => write!(lib_rs, "{name}: *const {},", resource.colon_colon())?,
+                FfiTy::Unit => unreachable!("unit inputs"),
+            }
+        }
+        if let FfiTy::String = callable.output {
+            write!(lib_rs, "out_len: *mut usize,")?;
+        }
+        // Errors and panics are reported through the `err_ptr` out-parameter.
+        write!(lib_rs, "err_ptr: *mut *mut u8, err_len: *mut usize,")?;
+        match &callable.output {
+            FfiTy::Scalar(scalar) => write!(lib_rs, ") -> {} {{", ffi::scalar_rust_ty(scalar)?)?,
+            FfiTy::String => write!(lib_rs, ") -> *mut u8 {{")?,
+            FfiTy::Resource(resource) => write!(lib_rs, ") -> *mut {} {{", resource.colon_colon())?,
+            FfiTy::Unit => write!(lib_rs, ") {{")?,
+        }
+
+        // Convert pointers back into references.
+        match callable.receiver {
+            Some((_, true)) => write!(lib_rs, "let this = unsafe {{ &mut *this }};")?,
+            Some((_, false)) => write!(lib_rs, "let this = unsafe {{ &*this }};")?,
+            None => {}
+        }
+        for (input, ffi_ty) in &callable.inputs {
+            let name = input.name();
+            match ffi_ty {
+                FfiTy::String => write!(
+                    lib_rs,
+                    "let {name} = unsafe {{ std::str::from_utf8_unchecked(std::slice::from_raw_parts({name}_ptr, {name}_len)) }};"
+                )?,
+                FfiTy::Resource(_) => write!(lib_rs, "let {name} = unsafe {{ &*{name} }};")?,
+                FfiTy::Scalar(_) | FfiTy::Unit => {}
+            }
+        }
+
+        let mut arguments = vec![];
+        if callable.receiver.is_some() {
+            arguments.push("this".to_string());
+        }
+        for (input, ffi_ty) in &callable.inputs {
+            let name = input.name();
+            arguments.push(match (ffi_ty, input.refd_ty()) {
+                (FfiTy::String, RefdTy::Owned(..)) => format!("{name}.to_string()"),
+                (FfiTy::Scalar(_), RefdTy::Ref(..)) => format!("&{name}"),
+                _ => name.to_string(),
+            });
+        }
+        let mut call = format!("{}({})", callable.rust_path, arguments.join(", "));
+        if callable.is_async() {
+            call = format!("gluegun_block_on({call})");
+        }
+        let call = format!("gluegun_catch_panic(err_ptr, err_len, || {call})");
+
+        // Unit-returning functions (including `Result<(), E>`) have nothing to return.
+        if let FfiTy::Unit = callable.output {
+            if callable.is_fallible() {
+                write!(lib_rs, "if let Some(Err(err)) = {call} {{")?;
+                write!(lib_rs, "unsafe {{ *err_ptr = gluegun_string_out(err.to_string(), err_len); }}")?;
+                write!(lib_rs, "}}")?;
+            } else {
+                write!(lib_rs, "{call};")?;
+            }
+            write!(lib_rs, "}}")?;
+            return Ok(());
+        }
+
+        // Errors are reported through the `err_ptr` out-parameter, with a placeholder return value;
+        // `gluegun_catch_panic` already reported a panic.
+        let placeholder = match &callable.output {
+            FfiTy::Scalar(_) => "Default::default()",
+            FfiTy::String | FfiTy::Resource(_) => "std::ptr::null_mut()",
+            FfiTy::Unit => unreachable!("handled above"),
+        };
+        if callable.is_fallible() {
+            write!(lib_rs, "let result = match {call} {{")?;
+            write!(lib_rs, "Some(Ok(value)) => value,")?;
+            write!(lib_rs, "Some(Err(err)) => {{")?;
+            write!(lib_rs, "unsafe {{ *err_ptr = gluegun_string_out(err.to_string(), err_len); }}")?;
+            write!(lib_rs, "return {placeholder};")?;
+            write!(lib_rs, "}}")?;
+            write!(lib_rs, "None => return {placeholder},")?;
+            write!(lib_rs, "}};")?;
+        } else {
+            write!(lib_rs, "let Some(result) = {call} else {{")?;
+            write!(lib_rs, "return {placeholder};")?;
+            write!(lib_rs, "}};")?;
+        }
+
+        // Borrowed data (e.g., `-> &str`) is copied out into an owned value.
+        if callable.signature.output_ty().requires_copy_out() {
+            write!(lib_rs, "let result = std::borrow::ToOwned::to_owned(result);")?;
+        }
+
+        match callable.output {
+            FfiTy::Scalar(_) => write!(lib_rs, "result")?,
+            FfiTy::Unit => unreachable!("handled above"),
+            FfiTy::String => write!(lib_rs, "gluegun_string_out(result, out_len)")?,
+            FfiTy::Resource(_) => {
+                if self.debug_bindings {
+                    write!(lib_rs, "crate::gluegun_debug::acquire();")?;
+                }
+                write!(lib_rs, "Box::into_raw(Box::new(result))")?
+            }
+        }
+        write!(lib_rs, "}}")?;
+
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ pub fn main() -> anyhow::Result<()> {
     gluegun_core::cli::run(GlueGunDummy)
 }
 
+#[derive(Clone)]
 struct GlueGunDummy;
 
 impl GlueGunHelper for GlueGunDummy {
@@ -0,0 +1,48 @@
+use anyhow::Context;
+use gluegun_core::{
+    cli::{GenerateCx, GlueGunHelper},
+    codegen::LibraryCrate,
+};
+
+mod proto_gen;
+mod rs_gen;
+mod util;
+
+pub fn main() -> anyhow::Result<()> {
+    gluegun_core::cli::run(GlueGunGrpc)
+}
+
+/// Generates a `.proto` file describing the API and a tonic-based server crate implementing it,
+/// so that the library can be used over the network.
+#[derive(Clone)]
+struct GlueGunGrpc;
+
+impl GlueGunHelper for GlueGunGrpc {
+    type Metadata = ();
+
+    fn name(&self) -> String {
+        "grpc".to_string()
+    }
+
+    fn generate(self, cx: &mut GenerateCx, _metadata: &(), output: &mut LibraryCrate) -> anyhow::Result<()> {
+        util::check_message_names(cx.idl())?;
+
+        // library dependencies
+        output.add_dependency("prost").version("0.14");
+        output.add_dependency("tonic").version("0.14");
+        output.add_dependency("tonic-prost").version("0.14");
+
+        // build-rs dependencies
+        output.add_dependency("tonic-prost-build").version("0.14").build();
+
+        proto_gen::ProtoGenerator::new(cx.idl())
+            .generate(output)
+            .context("generating protobuf definitions")?;
+
+        rs_gen::RustCodeGenerator::new(cx.idl())
+            .generate(output)
+            .context("generating Rust sources")?;
+
+        Ok(())
+    }
+}
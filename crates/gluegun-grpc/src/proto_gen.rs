@@ -0,0 +1,125 @@
+use gluegun_core::{
+    codegen::{CodeWriter, LibraryCrate},
+    idl::{Enum, Function, Idl, Item, Newtype, QualifiedName, Record},
+};
+
+use crate::util::{self, FieldKind};
+
+/// Generates `proto/<crate>.proto`: a message per record, an enum per enum, and a service
+/// with an RPC per function (taking a `FooRequest` with its arguments and returning a `FooResponse`
+/// with its result).
+pub(crate) struct ProtoGenerator<'idl> {
+    idl: &'idl Idl,
+}
+
+impl<'idl> ProtoGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl) -> Self {
+        Self { idl }
+    }
+
+    pub(crate) fn generate(self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        let mut proto = lib.add_file(format!("proto/{}.proto", util::package_name(self.idl)))?;
+
+        write!(proto, "syntax = \"proto3\";")?;
+        write!(proto, "")?;
+        write!(proto, "package {};", util::package_name(self.idl))?;
+
+        let mut functions = vec![];
+        for (qname, item) in self.idl.definitions() {
+            match item {
+                Item::Record(record) => self.generate_record(&mut proto, qname, record)?,
+                Item::Newtype(newtype) => self.generate_newtype(&mut proto, qname, newtype)?,
+                Item::Enum(an_enum) => self.generate_enum(&mut proto, qname, an_enum)?,
+                Item::Function(function) => {
+                    self.generate_function_messages(&mut proto, qname, function)?;
+                    functions.push(qname);
+                }
+                _ => anyhow::bail!(
+                    "`{}`: only records, newtypes, enums, and functions are currently supported by the grpc backend",
+                    qname.colon_colon()
+                ),
+            }
+        }
+
+        write!(proto, "")?;
+        write!(proto, "service {} {{", util::service_name(self.idl))?;
+        for qname in functions {
+            let rpc = util::rpc_name(qname);
+            write!(proto, "rpc {rpc}({rpc}Request) returns ({rpc}Response);")?;
+        }
+        write!(proto, "}}")?;
+
+        Ok(())
+    }
+
+    fn generate_record(&self, proto: &mut CodeWriter<'_>, qname: &QualifiedName, record: &Record) -> anyhow::Result<()> {
+        util::check_named_fields(record.span(), record.fields())?;
+        write!(proto, "")?;
+        write!(proto, "message {} {{", util::message_name(qname))?;
+        for (field, tag) in record.fields().iter().zip(1..) {
+            let kind = FieldKind::new(self.idl, field.ty())?;
+            write!(proto, "{} {} = {tag};", kind.proto_ty(), field.name())?;
+        }
+        write!(proto, "}}")?;
+        Ok(())
+    }
+
+    /// Protobuf has no type aliases, so newtypes become a message with a single `value` field
+    /// (like the well-known wrapper types, e.g. `google.protobuf.UInt64Value`).
+    fn generate_newtype(&self, proto: &mut CodeWriter<'_>, qname: &QualifiedName, newtype: &Newtype) -> anyhow::Result<()> {
+        let kind = FieldKind::new(self.idl, newtype.ty())?;
+        write!(proto, "")?;
+        write!(proto, "message {} {{", util::message_name(qname))?;
+        write!(proto, "{} value = 1;", kind.proto_ty())?;
+        write!(proto, "}}")?;
+        Ok(())
+    }
+
+    fn generate_enum(&self, proto: &mut CodeWriter<'_>, qname: &QualifiedName, an_enum: &Enum) -> anyhow::Result<()> {
+        write!(proto, "")?;
+        write!(proto, "enum {} {{", util::message_name(qname))?;
+        for (arm, value) in an_enum.arms().iter().zip(0..) {
+            write!(proto, "{} = {value};", util::enum_value_name(qname, arm))?;
+        }
+        write!(proto, "}}")?;
+        Ok(())
+    }
+
+    fn generate_function_messages(
+        &self,
+        proto: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        function: &Function,
+    ) -> anyhow::Result<()> {
+        let rpc = util::rpc_name(qname);
+        let signature = function.signature();
+
+        write!(proto, "")?;
+        write!(proto, "message {rpc}Request {{")?;
+        for (input, tag) in signature.inputs().iter().zip(1..) {
+            let kind = FieldKind::new(self.idl, input.refd_ty().ty())?;
+            write!(proto, "{} {} = {tag};", kind.proto_ty(), input.name())?;
+        }
+        write!(proto, "}}")?;
+
+        // Functions returning `()` get an empty response. Errors are reported as a gRPC status.
+        write!(proto, "")?;
+        write!(proto, "message {rpc}Response {{")?;
+        if let Some(kind) = output_kind(self.idl, function)? {
+            write!(proto, "{} value = 1;", kind.proto_ty())?;
+        }
+        write!(proto, "}}")?;
+
+        Ok(())
+    }
+}
+
+/// The kind of the `value` field of the response message of `function`, if any.
+pub(crate) fn output_kind<'idl>(idl: &Idl, function: &'idl Function) -> anyhow::Result<Option<FieldKind<'idl>>> {
+    let output_ty = function.signature().output_ty();
+    if output_ty.is_unit() {
+        Ok(None)
+    } else {
+        Ok(Some(FieldKind::new(idl, output_ty.main_ty().ty())?))
+    }
+}
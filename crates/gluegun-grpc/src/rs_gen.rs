@@ -0,0 +1,370 @@
+use gluegun_core::{
+    codegen::{copy_out, CodeWriter, LibraryCrate},
+    idl::{Enum, Function, Idl, IsAsync, Item, Newtype, QualifiedName, Record, RefdTy, Scalar},
+};
+
+use crate::{
+    proto_gen,
+    util::{self, ElementKind, FieldKind},
+};
+
+/// Generates `build.rs` (compiling the `.proto` file with `tonic-prost-build`) and `src/lib.rs`,
+/// which implements the service by calling into the original library and offers a `serve` function.
+pub(crate) struct RustCodeGenerator<'idl> {
+    idl: &'idl Idl,
+}
+
+impl<'idl> RustCodeGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl) -> Self {
+        Self { idl }
+    }
+
+    pub(crate) fn generate(mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        self.generate_build_rs(lib)?;
+        self.generate_lib_rs(lib)
+    }
+
+    fn generate_build_rs(&mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        let mut build_rs = lib.add_file("build.rs")?;
+        write!(build_rs, "fn main() -> Result<(), Box<dyn std::error::Error>> {{")?;
+        write!(
+            build_rs,
+            "tonic_prost_build::compile_protos(\"proto/{}.proto\")?;",
+            util::package_name(self.idl)
+        )?;
+        write!(build_rs, "Ok(())")?;
+        write!(build_rs, "}}")?;
+        Ok(())
+    }
+
+    fn generate_lib_rs(&mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        let mut lib_rs = lib.add_file("src/lib.rs")?;
+        let service = util::service_name(self.idl);
+        let server_module = util::server_module_name(self.idl);
+
+        write!(lib_rs, "pub mod proto {{")?;
+        write!(lib_rs, "tonic::include_proto!(\"{}\");", util::package_name(self.idl))?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "")?;
+
+        self.generate_support(&mut lib_rs)?;
+
+        let mut functions = vec![];
+        for (qname, item) in self.idl.definitions() {
+            match item {
+                Item::Record(record) => lib_rs.with_span(record.span(), |lib_rs| self.generate_record_conversions(lib_rs, qname, record))?,
+                Item::Newtype(newtype) => lib_rs.with_span(newtype.span(), |lib_rs| self.generate_newtype_conversions(lib_rs, qname, newtype))?,
+                Item::Enum(an_enum) => lib_rs.with_span(an_enum.span(), |lib_rs| self.generate_enum_conversions(lib_rs, qname, an_enum))?,
+                Item::Function(function) => functions.push((qname, function)),
+                _ => anyhow::bail!(
+                    "`{}`: only records, newtypes, enums, and functions are currently supported by the grpc backend",
+                    qname.colon_colon()
+                ),
+            }
+        }
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "/// Implements the `{service}` service by invoking the library.")?;
+        write!(lib_rs, "#[derive(Default)]")?;
+        write!(lib_rs, "pub struct Service;")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[tonic::async_trait]")?;
+        write!(lib_rs, "impl proto::{server_module}::{service} for Service {{")?;
+        for (qname, function) in functions {
+            lib_rs.with_span(function.span(), |lib_rs| self.generate_rpc(lib_rs, qname, function))?;
+        }
+        write!(lib_rs, "}}")?;
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "/// Serve the `{service}` service at `addr`.")?;
+        write!(
+            lib_rs,
+            "pub async fn serve(addr: std::net::SocketAddr) -> Result<(), tonic::transport::Error> {{"
+        )?;
+        write!(lib_rs, "tonic::transport::Server::builder()")?;
+        write!(lib_rs, ".add_service(proto::{server_module}::{service}Server::new(Service))")?;
+        write!(lib_rs, ".serve(addr)")?;
+        write!(lib_rs, ".await")?;
+        write!(lib_rs, "}}")?;
+
+        Ok(())
+    }
+
+    /// Helpers for converting protobuf values back into Rust values, which can fail.
+    fn generate_support(&mut self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "#[allow(dead_code)]")?;
+        write!(
+            lib_rs,
+            "fn narrow<T: TryFrom<U>, U: Copy + std::fmt::Display>(value: U) -> Result<T, tonic::Status> {{"
+        )?;
+        write!(
+            lib_rs,
+            "T::try_from(value).map_err(|_| tonic::Status::invalid_argument(format!(\"value out of range: {{value}}\")))"
+        )?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[allow(dead_code)]")?;
+        write!(lib_rs, "fn required<T>(value: Option<T>) -> Result<T, tonic::Status> {{")?;
+        write!(
+            lib_rs,
+            "value.ok_or_else(|| tonic::Status::invalid_argument(\"missing required field\"))"
+        )?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    fn generate_record_conversions(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        record: &Record,
+    ) -> anyhow::Result<()> {
+        let rust = qname.colon_colon();
+        let proto = format!("proto::{}", util::message_name(qname));
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "impl From<{rust}> for {proto} {{")?;
+        write!(lib_rs, "fn from(value: {rust}) -> Self {{")?;
+        write!(lib_rs, "Self {{")?;
+        for field in record.fields() {
+            let name = field.name();
+            let kind = FieldKind::new(self.idl, field.ty())?;
+            let boxed = kind.is_boxed_by_prost(self.idl, qname);
+            write!(lib_rs, "{name}: {},", field_to_proto(&format!("value.{name}"), &kind, boxed))?;
+        }
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "impl TryFrom<{proto}> for {rust} {{")?;
+        write!(lib_rs, "type Error = tonic::Status;")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "fn try_from(value: {proto}) -> Result<Self, tonic::Status> {{")?;
+        write!(lib_rs, "Ok(Self {{")?;
+        for field in record.fields() {
+            let name = field.name();
+            let kind = FieldKind::new(self.idl, field.ty())?;
+            let boxed = kind.is_boxed_by_prost(self.idl, qname);
+            write!(lib_rs, "{name}: {},", field_from_proto(&format!("value.{name}"), &kind, boxed))?;
+        }
+        write!(lib_rs, "}})")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    fn generate_newtype_conversions(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        newtype: &Newtype,
+    ) -> anyhow::Result<()> {
+        let rust = qname.colon_colon();
+        let proto = format!("proto::{}", util::message_name(qname));
+        let kind = FieldKind::new(self.idl, newtype.ty())?;
+        let boxed = kind.is_boxed_by_prost(self.idl, qname);
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "impl From<{rust}> for {proto} {{")?;
+        write!(lib_rs, "fn from(value: {rust}) -> Self {{")?;
+        write!(lib_rs, "Self {{")?;
+        write!(lib_rs, "value: {},", field_to_proto(&newtype.unwrap_expr("value"), &kind, boxed))?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "impl TryFrom<{proto}> for {rust} {{")?;
+        write!(lib_rs, "type Error = tonic::Status;")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "fn try_from(value: {proto}) -> Result<Self, tonic::Status> {{")?;
+        // Values rejected by the `TryFrom` impl of a validated newtype are an invalid argument.
+        let field = field_from_proto("value.value", &kind, boxed);
+        if *newtype.validated() {
+            write!(
+                lib_rs,
+                "{}.map_err(|err| tonic::Status::invalid_argument(err.to_string()))",
+                newtype.wrap_expr("Self", &field),
+            )?;
+        } else {
+            write!(lib_rs, "Ok({})", newtype.wrap_expr("Self", &field))?;
+        }
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    fn generate_enum_conversions(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        an_enum: &Enum,
+    ) -> anyhow::Result<()> {
+        let rust = qname.colon_colon();
+        let proto = format!("proto::{}", util::message_name(qname));
+        for (source, target, to_proto) in [(&rust, &proto, true), (&proto, &rust, false)] {
+            write!(lib_rs, "")?;
+            write!(lib_rs, "impl From<{source}> for {target} {{")?;
+            write!(lib_rs, "fn from(value: {source}) -> Self {{")?;
+            write!(lib_rs, "match value {{")?;
+            for arm in an_enum.arms() {
+                let (rust_arm, proto_arm) = (arm.name().to_string(), util::prost_variant_name(arm));
+                let (source_arm, target_arm) = if to_proto {
+                    (rust_arm, proto_arm)
+                } else {
+                    (proto_arm, rust_arm)
+                };
+                write!(lib_rs, "{source}::{source_arm} => {target}::{target_arm},")?;
+            }
+            write!(lib_rs, "}}")?;
+            write!(lib_rs, "}}")?;
+            write!(lib_rs, "}}")?;
+        }
+        Ok(())
+    }
+
+    fn generate_rpc(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, function: &Function) -> anyhow::Result<()> {
+        let rpc = util::rpc_name(qname);
+        let signature = function.signature();
+
+        write!(lib_rs, "")?;
+        write!(
+            lib_rs,
+            "async fn {}(&self, request: tonic::Request<proto::{rpc}Request>) -> Result<tonic::Response<proto::{rpc}Response>, tonic::Status> {{",
+            util::rpc_method_name(qname),
+        )?;
+        if signature.inputs().is_empty() {
+            write!(lib_rs, "let proto::{rpc}Request {{}} = request.into_inner();")?;
+        } else {
+            write!(lib_rs, "let request = request.into_inner();")?;
+        }
+
+        // Arguments are converted to owned values and borrowed if the function expects a reference.
+        for input in signature.inputs() {
+            let name = input.name();
+            let ty = input.refd_ty().ty();
+            let kind = FieldKind::new(self.idl, ty)?;
+            write!(
+                lib_rs,
+                "let {name}: {} = {};",
+                ty.rust_owned_spelling(),
+                field_from_proto(&format!("request.{name}"), &kind, false)
+            )?;
+        }
+
+        // Unit-returning functions are called as a statement and answered with an empty response.
+        let output_ty = signature.output_ty();
+        let arguments: Vec<String> = signature
+            .inputs()
+            .iter()
+            .map(|input| match input.refd_ty() {
+                RefdTy::Owned(..) => input.name().to_string(),
+                RefdTy::Ref(..) => format!("&{}", input.name()),
+            })
+            .collect();
+        let mut call = format!("{}({})", qname.colon_colon(), arguments.join(","));
+        if let IsAsync::Yes = signature.is_async() {
+            call.push_str(".await");
+        }
+        if output_ty.error_ty().is_some() {
+            call.push_str(".map_err(|err| tonic::Status::internal(err.to_string()))?");
+        }
+        if !output_ty.is_unit() {
+            write!(lib_rs, "let result = ")?;
+        }
+        write!(lib_rs, "{};", copy_out(output_ty, &call))?;
+
+        match proto_gen::output_kind(self.idl, function)? {
+            Some(kind) => write!(
+                lib_rs,
+                "Ok(tonic::Response::new(proto::{rpc}Response {{ value: {} }}))",
+                field_to_proto("result", &kind, false)
+            )?,
+            None => write!(lib_rs, "Ok(tonic::Response::new(proto::{rpc}Response {{}}))")?,
+        }
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+}
+
+/// Expression converting `expr` (a Rust value) into the value of a protobuf field,
+/// whose message prost puts in a `Box` if `boxed` (see [`FieldKind::is_boxed_by_prost`][]).
+fn field_to_proto(expr: &str, kind: &FieldKind<'_>, boxed: bool) -> String {
+    let boxed_to_proto = |expr: &str, element: &ElementKind<'_>| {
+        let value = element_to_proto(expr, element);
+        if boxed { format!("Box::new({value})") } else { value }
+    };
+    match kind {
+        // prost represents singular message fields as an `Option`.
+        FieldKind::Singular(element @ (ElementKind::Message(_) | ElementKind::BoxedMessage(_))) => {
+            format!("Some({})", boxed_to_proto(expr, element))
+        }
+        FieldKind::Singular(element) => element_to_proto(expr, element),
+        FieldKind::Optional(element) => format!("{expr}.map(|e| {})", boxed_to_proto("e", element)),
+        FieldKind::Repeated(element) => {
+            format!("{expr}.into_iter().map(|e| {}).collect()", element_to_proto("e", element))
+        }
+        FieldKind::Map(key, value) => format!(
+            "{expr}.into_iter().map(|(k, v)| ({}, {})).collect()",
+            element_to_proto("k", key),
+            element_to_proto("v", value)
+        ),
+    }
+}
+
+fn element_to_proto(expr: &str, kind: &ElementKind<'_>) -> String {
+    match kind {
+        ElementKind::Scalar(Scalar::I8 | Scalar::I16) => format!("i32::from({expr})"),
+        ElementKind::Scalar(Scalar::U8 | Scalar::U16 | Scalar::Char) => format!("u32::from({expr})"),
+        ElementKind::Scalar(_) | ElementKind::String => expr.to_string(),
+        ElementKind::Path => format!("{expr}.display().to_string()"),
+        ElementKind::Message(qname) => format!("proto::{}::from({expr})", util::message_name(qname)),
+        ElementKind::BoxedMessage(qname) => format!("proto::{}::from(*{expr})", util::message_name(qname)),
+        ElementKind::Enum(qname) => format!("proto::{}::from({expr}) as i32", util::message_name(qname)),
+    }
+}
+
+/// Expression converting the protobuf field `expr` into a Rust value, propagating errors with `?`
+/// (the surrounding function must return `Result<_, tonic::Status>`).
+/// If `boxed`, prost puts the message of the field in a `Box` (see [`FieldKind::is_boxed_by_prost`][]).
+fn field_from_proto(expr: &str, kind: &FieldKind<'_>, boxed: bool) -> String {
+    let unboxed = |expr: String| if boxed { format!("*{expr}") } else { expr };
+    match kind {
+        FieldKind::Singular(element @ (ElementKind::Message(_) | ElementKind::BoxedMessage(_))) => {
+            element_from_proto(&unboxed(format!("required({expr})?")), element)
+        }
+        FieldKind::Singular(element) => element_from_proto(expr, element),
+        FieldKind::Optional(element) => format!(
+            "{expr}.map(|e| Ok::<_, tonic::Status>({})).transpose()?",
+            element_from_proto(&unboxed("e".to_string()), element)
+        ),
+        FieldKind::Repeated(element) => format!(
+            "{expr}.into_iter().map(|e| Ok::<_, tonic::Status>({})).collect::<Result<_, tonic::Status>>()?",
+            element_from_proto("e", element)
+        ),
+        FieldKind::Map(key, value) => format!(
+            "{expr}.into_iter().map(|(k, v)| Ok::<_, tonic::Status>(({}, {}))).collect::<Result<_, tonic::Status>>()?",
+            element_from_proto("k", key),
+            element_from_proto("v", value)
+        ),
+    }
+}
+
+fn element_from_proto(expr: &str, kind: &ElementKind<'_>) -> String {
+    match kind {
+        ElementKind::Scalar(Scalar::I8 | Scalar::I16 | Scalar::U8 | Scalar::U16) => format!("narrow({expr})?"),
+        ElementKind::Scalar(Scalar::Char) => format!(
+            "char::from_u32({expr}).ok_or_else(|| tonic::Status::invalid_argument(\"invalid char\"))?"
+        ),
+        ElementKind::Scalar(_) | ElementKind::String => expr.to_string(),
+        ElementKind::Path => format!("std::path::PathBuf::from({expr})"),
+        ElementKind::Message(qname) => format!("{}::try_from({expr})?", qname.colon_colon()),
+        ElementKind::BoxedMessage(qname) => format!("Box::new({}::try_from({expr})?)", qname.colon_colon()),
+        ElementKind::Enum(qname) => format!(
+            "{}::from(proto::{}::try_from({expr}).map_err(|_| tonic::Status::invalid_argument(\"invalid enum value\"))?)",
+            qname.colon_colon(),
+            util::message_name(qname),
+        ),
+    }
+}
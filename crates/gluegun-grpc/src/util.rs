@@ -0,0 +1,247 @@
+use std::collections::BTreeSet;
+
+use gluegun_core::idl::{
+    EnumArm, Field, Idl, Item, MapSetRepr, Name, OptionRepr, PathRepr, QualifiedName, Scalar, Span, StringRepr,
+    Ty, TypeKind, UserTypeRepr, VecRepr,
+};
+
+/// The protobuf package (and the module generated by prost), e.g., `my_crate`.
+pub(crate) fn package_name(idl: &Idl) -> String {
+    idl.crate_name().to_string()
+}
+
+/// The gRPC service exposing all functions, e.g., `MyCrate`.
+pub(crate) fn service_name(idl: &Idl) -> Name {
+    idl.crate_name().upper_camel_case()
+}
+
+/// The module generated by tonic for the server side of the service, e.g., `my_crate_server`.
+pub(crate) fn server_module_name(idl: &Idl) -> String {
+    format!("{}_server", idl.crate_name())
+}
+
+/// The RPC for the function `qname`, e.g., `GeometryArea` for `my_crate::geometry::area`.
+pub(crate) fn rpc_name(qname: &QualifiedName) -> String {
+    qname.names()[1..]
+        .iter()
+        .map(|name| name.upper_camel_case().to_string())
+        .collect()
+}
+
+/// The method tonic generates for the RPC of the function `qname`, e.g., `geometry_area`.
+pub(crate) fn rpc_method_name(qname: &QualifiedName) -> String {
+    qname.names()[1..]
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// The message (or enum) for the user type `qname`, e.g., `Point` for `my_crate::geometry::Point`.
+/// Protobuf has nested packages but we keep things simple and define all types in one package,
+/// so these must be unique (see [`check_message_names`][]).
+pub(crate) fn message_name(qname: &QualifiedName) -> String {
+    qname.tail_name().upper_camel_case().to_string()
+}
+
+/// Check that user types can all be defined in the same protobuf package.
+pub(crate) fn check_message_names(idl: &Idl) -> anyhow::Result<()> {
+    let mut names = std::collections::BTreeMap::new();
+    for (qname, item) in idl.definitions() {
+        if let Item::Function(_) = item {
+            continue;
+        }
+        if let Some(previous) = names.insert(message_name(qname), qname) {
+            anyhow::bail!(
+                "`{}` and `{}` have the same name, which is not supported by the grpc backend",
+                previous.colon_colon(),
+                qname.colon_colon()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The protobuf enum value for `arm`. Enum values are scoped to the package rather than the
+/// enum, so by convention they are prefixed with the enum name, e.g., `COLOR_RED`.
+pub(crate) fn enum_value_name(qname: &QualifiedName, arm: &EnumArm) -> String {
+    format!(
+        "{}_{}",
+        qname.tail_name().screaming_snake_case(),
+        arm.name().screaming_snake_case()
+    )
+}
+
+/// The name of the variant prost generates for `arm` (it strips the prefix from [`enum_value_name`][]).
+pub(crate) fn prost_variant_name(arm: &EnumArm) -> String {
+    arm.name().screaming_snake_case().upper_camel_case().to_string()
+}
+
+/// The IDL names the fields of tuple structs `f0`, `f1`, etc, but we need to
+/// access them by name in conversions.
+pub(crate) fn check_named_fields(span: &Span, fields: &[Field]) -> anyhow::Result<()> {
+    let tuple_like = !fields.is_empty()
+        && fields
+            .iter()
+            .enumerate()
+            .all(|(index, field)| *field.name().text() == format!("f{index}"));
+    if tuple_like {
+        anyhow::bail!("{span}: tuple structs are not supported by the grpc backend");
+    }
+    Ok(())
+}
+
+/// How a Rust type is represented in a protobuf message field.
+pub(crate) enum FieldKind<'ty> {
+    /// A single value, see [`ElementKind`][].
+    Singular(ElementKind<'ty>),
+
+    /// An `Option<T>`, as an `optional` field.
+    Optional(ElementKind<'ty>),
+
+    /// A `Vec<T>`, `&[T]`, or set, as a `repeated` field.
+    Repeated(ElementKind<'ty>),
+
+    /// A map, as a `map<K, V>` field.
+    Map(ElementKind<'ty>, ElementKind<'ty>),
+}
+
+/// How a Rust type is represented as a protobuf scalar, enum or message.
+pub(crate) enum ElementKind<'ty> {
+    Scalar(Scalar),
+    String,
+    Path,
+    Message(&'ty QualifiedName),
+
+    /// A `Box<T>` of a record or newtype, as the message of `T`.
+    BoxedMessage(&'ty QualifiedName),
+
+    Enum(&'ty QualifiedName),
+}
+
+impl<'ty> FieldKind<'ty> {
+    pub(crate) fn new(idl: &Idl, ty: &'ty Ty) -> anyhow::Result<Self> {
+        match ty.kind() {
+            TypeKind::Option {
+                element,
+                repr: OptionRepr::Option,
+            } => Ok(FieldKind::Optional(ElementKind::new(idl, element)?)),
+            TypeKind::Vec {
+                element,
+                repr: VecRepr::Vec | VecRepr::SliceRef | VecRepr::ImplIntoIterator,
+            }
+            | TypeKind::Set {
+                element,
+                repr: MapSetRepr::Hash | MapSetRepr::BTree,
+            } => Ok(FieldKind::Repeated(ElementKind::new(idl, element)?)),
+            TypeKind::Map {
+                key,
+                value,
+                repr: MapSetRepr::Hash | MapSetRepr::BTree,
+            } => {
+                let key_kind = ElementKind::new(idl, key)?;
+                match key_kind {
+                    // Protobuf only permits integral and string keys.
+                    ElementKind::Scalar(Scalar::F32 | Scalar::F64 | Scalar::Char)
+                    | ElementKind::Path
+                    | ElementKind::Message(_)
+                    | ElementKind::BoxedMessage(_)
+                    | ElementKind::Enum(_) => unsupported(key),
+                    _ => Ok(FieldKind::Map(key_kind, ElementKind::new(idl, value)?)),
+                }
+            }
+            _ => Ok(FieldKind::Singular(ElementKind::new(idl, ty)?)),
+        }
+    }
+
+    /// The protobuf type of the field, including its label (e.g., `repeated string`).
+    pub(crate) fn proto_ty(&self) -> String {
+        match self {
+            FieldKind::Singular(element) => element.proto_ty(),
+            FieldKind::Optional(element) => format!("optional {}", element.proto_ty()),
+            FieldKind::Repeated(element) => format!("repeated {}", element.proto_ty()),
+            FieldKind::Map(key, value) => format!("map<{}, {}>", key.proto_ty(), value.proto_ty()),
+        }
+    }
+
+    /// True if prost represents this field of the message `message` as a `Box`, which it does
+    /// for singular and optional message fields whose message contains `message`, directly or
+    /// through other such fields (e.g., `next` in `message Node { optional Node next = 1; }`).
+    pub(crate) fn is_boxed_by_prost(&self, idl: &Idl, message: &QualifiedName) -> bool {
+        let mut stack: Vec<&QualifiedName> = self.singular_message().into_iter().collect();
+        let mut visited = BTreeSet::new();
+        while let Some(qname) = stack.pop() {
+            if qname == message {
+                return true;
+            }
+            if !visited.insert(qname) {
+                continue;
+            }
+            let field_tys: Vec<&Ty> = match idl.item(qname) {
+                Some(Item::Record(record)) => record.fields().iter().map(|field| field.ty()).collect(),
+                Some(Item::Newtype(newtype)) => vec![newtype.ty()],
+                _ => vec![],
+            };
+            for ty in field_tys {
+                if let Ok(kind) = FieldKind::new(idl, ty) {
+                    stack.extend(kind.singular_message());
+                }
+            }
+        }
+        false
+    }
+
+    /// The message of a singular or optional message field.
+    fn singular_message(&self) -> Option<&'ty QualifiedName> {
+        match self {
+            FieldKind::Singular(ElementKind::Message(qname) | ElementKind::BoxedMessage(qname))
+            | FieldKind::Optional(ElementKind::Message(qname) | ElementKind::BoxedMessage(qname)) => Some(qname),
+            _ => None,
+        }
+    }
+}
+
+impl<'ty> ElementKind<'ty> {
+    fn new(idl: &Idl, ty: &'ty Ty) -> anyhow::Result<Self> {
+        match ty.kind() {
+            TypeKind::Scalar(scalar) => Ok(ElementKind::Scalar(*scalar)),
+            TypeKind::String {
+                repr: StringRepr::String | StringRepr::StrRef,
+            } => Ok(ElementKind::String),
+            TypeKind::Path {
+                repr: PathRepr::PathBuf | PathRepr::PathRef,
+            } => Ok(ElementKind::Path),
+            TypeKind::UserType { qname, repr } => match (idl.item(qname), repr) {
+                (Some(Item::Record(_) | Item::Newtype(_)), UserTypeRepr::Boxed) => Ok(ElementKind::BoxedMessage(qname)),
+                (Some(Item::Record(_) | Item::Newtype(_)), _) => Ok(ElementKind::Message(qname)),
+                (Some(Item::Enum(_)), UserTypeRepr::Direct) => Ok(ElementKind::Enum(qname)),
+                _ => unsupported(ty),
+            },
+            _ => unsupported(ty),
+        }
+    }
+
+    pub(crate) fn proto_ty(&self) -> String {
+        match self {
+            ElementKind::Scalar(scalar) => match scalar {
+                Scalar::Boolean => "bool",
+                Scalar::I8 | Scalar::I16 | Scalar::I32 => "int32",
+                Scalar::I64 => "int64",
+                Scalar::U8 | Scalar::U16 | Scalar::U32 | Scalar::Char => "uint32",
+                Scalar::U64 => "uint64",
+                Scalar::F32 => "float",
+                Scalar::F64 => "double",
+                _ => unreachable!("unknown scalar `{scalar}`"),
+            }
+            .to_string(),
+            ElementKind::String | ElementKind::Path => "string".to_string(),
+            ElementKind::Message(qname) | ElementKind::BoxedMessage(qname) | ElementKind::Enum(qname) => {
+                message_name(qname)
+            }
+        }
+    }
+}
+
+fn unsupported<T>(ty: &Ty) -> anyhow::Result<T> {
+    anyhow::bail!("{}: type `{ty}` is not supported by the grpc backend", ty.span())
+}
@@ -28,13 +28,13 @@ pub enum Error {
     #[error("{0}: unrecognized Rust item")]
     UnrecognizedItem(Span),
 
-    #[error("{0}: recognised this type but not number of arguments (expected {1}, found {2}")]
+    #[error("{0}: wrong number of type arguments (expected {1}, found {2})")]
     UnsupportedNumberOfArguments(Span, usize, usize),
 
     #[error("{0}: unsupported Rust item; consider using `#[gluegun::ignore]`")]
     UnsupportedItem(Span),
 
-    #[error("{0}: only `self`, `&self`, and `&mut self` are supported")]
+    #[error("{0}: only `self`, `&self`, `&mut self`, `self: Arc<Self>`, and `self: Rc<Self>` are supported")]
     ExplicitSelfNotSupported(Span),
 
     #[error("{0}: macro invocations not supported")]
@@ -52,12 +52,36 @@ pub enum Error {
     #[error("{0}: expected a Rust type, not this")]
     NotType(Span),
 
+    #[error("{0}: expected a public trait of the crate")]
+    NotInterface(Span),
+
+    #[error("{0}: interface methods must take `&self` or `&mut self` and cannot be `async` or return a stream")]
+    UnsupportedInterfaceMethod(Span),
+
     #[error("{0}: anonymous fields unsupported")]
     AnonymousField(Span),
 
+    #[error("{0}: `&mut` arguments are not supported, as the caller would not see the changes; {1}")]
+    OutParameter(Span, &'static str),
+
+    #[error("{0}: `&'static` arguments are not supported, as the caller's data is converted into a temporary; take an owned value instead")]
+    StaticReferenceArgument(Span),
+
+    #[error("{0}: `impl Trait` results cannot borrow for a lifetime other than `'static`, as bindings copy data out when the call returns")]
+    BorrowingImplTrait(Span),
+
     #[error("{0}: unsupported function input pattern, must be a single identifier")]
     UnsupportedInputPattern(Span),
 
+    #[error("{0}: file not found for module (expected `name.rs` or `name/mod.rs`, or a `#[path]` attribute)")]
+    ModuleNotFound(Span),
+
+    #[error("{0}: module is nested deeper than the maximum depth of {1}")]
+    ModuleTooDeep(Span, usize),
+
+    #[error("unknown Rust edition `{0}`")]
+    UnknownEdition(String),
+
     #[error("{0}: expected to be invoked with a path like `foo/src/../*.rs`, found")]
     InvalidPath(PathBuf),
 
@@ -67,8 +91,23 @@ pub enum Error {
     #[error("async functions cannot return `impl Future`")]
     DoubleAsync(Span),
 
+    #[error("{0}: streams are only supported as the result of a function or method, not as an argument or nested in another type")]
+    MisplacedStream(Span),
+
     #[error("{0}: only owned types are permitted here, not `{1}`-types")]
     ReferenceType(Span, RefKind),
+
+    #[error("{0}: type `{1}` has no JSON representation")]
+    NoJsonRepresentation(Span, String),
+
+    #[error("{0}: expected `#[gluegun::overload(name)]`, naming the group of overloads")]
+    InvalidOverload(Span),
+
+    #[error("{0}: expected `#[gluegun::marshal_as(Type)]`, naming the type the struct is passed as")]
+    InvalidMarshalAs(Span),
+
+    #[error("{0}: cannot pack the parameters into `{1}`, which is already defined")]
+    ParamsRecordConflict(Span, String),
 }
 
 impl From<syn::Error> for Error {
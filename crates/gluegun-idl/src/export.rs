@@ -0,0 +1,3 @@
+//! Descriptions of the IDL in formats consumed by other toolchains.
+
+pub mod json_schema;
@@ -0,0 +1,253 @@
+//! Describe the IDL using [JSON Schema](https://json-schema.org/draft/2020-12).
+//!
+//! Values are described as they are encoded by serde's default representation
+//! (e.g., enums with data as `{"Circle": {"radius": 1.0}}`), so the schemas match the JSON
+//! produced by `#[derive(Serialize)]` on the original types. Resources are opaque and
+//! have no JSON representation, so they are omitted.
+
+use serde_json::{json, Map, Value};
+
+use crate::{Error, Field, Function, Idl, Item, NetAddrRepr, QualifiedName, Result, Scalar, Ty, TypeKind};
+
+/// A JSON Schema document with a definition in `$defs` for each record, variant, and enum,
+/// keyed by its dotted name (e.g., `my_crate.geometry.Point`).
+pub fn json_schema(idl: &Idl) -> Result<Value> {
+    let reference = |qname: &QualifiedName| format!("#/$defs/{}", qname.dotted());
+    Ok(json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": idl.crate_name().to_string(),
+        "$defs": definitions(idl, &reference)?,
+    }))
+}
+
+/// An [OpenAPI 3.1](https://spec.openapis.org/oas/v3.1.0) document describing each function as an
+/// operation, at a path derived from its name (e.g., `POST /geometry/area` for `my_crate::geometry::area`),
+/// whose request body has a property per argument and whose response is the result.
+/// Records, variants, and enums are described in `components`, keyed by their dotted name.
+pub fn openapi(idl: &Idl) -> Result<Value> {
+    let reference = |qname: &QualifiedName| format!("#/components/schemas/{}", qname.dotted());
+
+    let mut paths = Map::new();
+    for (qname, item) in idl.definitions() {
+        if let Item::Function(function) = item {
+            let path: String = qname.names()[1..].iter().map(|name| format!("/{name}")).collect();
+            paths.insert(path, json!({ "post": operation(idl, qname, function, &reference)? }));
+        }
+    }
+
+    Ok(json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": idl.crate_name().to_string(),
+            "version": idl.crate_metadata().version().as_deref().unwrap_or("0.0.0"),
+        },
+        "paths": paths,
+        "components": { "schemas": definitions(idl, &reference)? },
+    }))
+}
+
+/// The schemas for each record, newtype, variant, and enum, keyed by dotted name.
+/// `reference` gives the `$ref` URI for the schema of a user type.
+pub fn definitions(idl: &Idl, reference: &dyn Fn(&QualifiedName) -> String) -> Result<Map<String, Value>> {
+    let mut definitions = Map::new();
+    for (qname, item) in idl.definitions() {
+        if let Some(schema) = item_schema(idl, item, reference)? {
+            definitions.insert(qname.dotted(), schema);
+        }
+    }
+    Ok(definitions)
+}
+
+/// The schema for a record, newtype, variant, or enum; `None` for other items, which are not values.
+pub fn item_schema(idl: &Idl, item: &Item, reference: &dyn Fn(&QualifiedName) -> String) -> Result<Option<Value>> {
+    match item {
+        // Like serde, tuple structs are represented by an array of their fields.
+        Item::Record(record) if *record.tuple() => {
+            Ok(Some(tuple_schema(idl, record.fields().iter().map(|field| field.ty()), reference)?))
+        }
+        Item::Record(record) => Ok(Some(fields_schema(idl, record.fields(), reference)?)),
+        // Like serde, newtypes are represented by their field.
+        Item::Newtype(newtype) => Ok(Some(ty_schema(idl, newtype.ty(), reference)?)),
+        Item::Variant(variant) => {
+            let mut one_of = vec![];
+            for arm in variant.arms() {
+                let name = arm.name().to_string();
+                if arm.fields().is_empty() {
+                    one_of.push(json!({ "const": name }));
+                } else {
+                    one_of.push(json!({
+                        "type": "object",
+                        "properties": { name.clone(): fields_schema(idl, arm.fields(), reference)? },
+                        "required": [name],
+                        "additionalProperties": false,
+                    }));
+                }
+            }
+            Ok(Some(json!({ "oneOf": one_of })))
+        }
+        Item::Enum(an_enum) => {
+            let arms: Vec<String> = an_enum.arms().iter().map(|arm| arm.name().to_string()).collect();
+            Ok(Some(json!({ "type": "string", "enum": arms })))
+        }
+        Item::Resource(_) | Item::Function(_) | Item::Interface(_) => Ok(None),
+    }
+}
+
+/// The schema for values of type `ty`.
+/// `reference` gives the `$ref` URI for the schema of a user type.
+pub fn ty_schema(idl: &Idl, ty: &Ty, reference: &dyn Fn(&QualifiedName) -> String) -> Result<Value> {
+    Ok(match ty.kind() {
+        TypeKind::Map { value, .. } => {
+            // serde encodes map keys as strings (integers are stringified).
+            json!({ "type": "object", "additionalProperties": ty_schema(idl, value, reference)? })
+        }
+        TypeKind::Vec { element, .. } => json!({ "type": "array", "items": ty_schema(idl, element, reference)? }),
+        TypeKind::Set { element, .. } => {
+            json!({ "type": "array", "items": ty_schema(idl, element, reference)?, "uniqueItems": true })
+        }
+        TypeKind::Path { .. } | TypeKind::String { .. } => json!({ "type": "string" }),
+        TypeKind::NetAddr { repr } => match repr {
+            NetAddrRepr::Ipv4Addr => json!({ "type": "string", "format": "ipv4" }),
+            NetAddrRepr::Ipv6Addr => json!({ "type": "string", "format": "ipv6" }),
+            NetAddrRepr::IpAddr => json!({
+                "type": "string",
+                "anyOf": [{ "format": "ipv4" }, { "format": "ipv6" }],
+            }),
+            // JSON Schema has no format for an address with a port.
+            NetAddrRepr::SocketAddr | NetAddrRepr::SocketAddrV4 | NetAddrRepr::SocketAddrV6 => {
+                json!({ "type": "string" })
+            }
+        },
+        TypeKind::Uuid { .. } => json!({ "type": "string", "format": "uuid" }),
+        TypeKind::Timestamp { repr } if repr.is_date() => json!({ "type": "string", "format": "date" }),
+        TypeKind::Timestamp { .. } => json!({ "type": "string", "format": "date-time" }),
+        TypeKind::Option { element, .. } => {
+            json!({ "anyOf": [ty_schema(idl, element, reference)?, { "type": "null" }] })
+        }
+        TypeKind::Result { ok, err, .. } => json!({
+            "oneOf": [
+                externally_tagged("Ok", ty_schema(idl, ok, reference)?),
+                externally_tagged("Err", ty_schema(idl, err, reference)?),
+            ],
+        }),
+        TypeKind::Tuple { elements, .. } if elements.is_empty() => json!({ "type": "null" }),
+        TypeKind::Tuple { elements, .. } => tuple_schema(idl, elements.iter(), reference)?,
+        TypeKind::Scalar(scalar) => scalar_schema(*scalar),
+        TypeKind::UserType { qname, repr: _ } => match idl.item(qname) {
+            Some(Item::Record(_) | Item::Newtype(_) | Item::Variant(_) | Item::Enum(_)) => json!({ "$ref": reference(qname) }),
+            _ => return Err(Error::NoJsonRepresentation(ty.span().clone(), ty.to_string())),
+        },
+        // The representation is up to the type override configured for the backend, so accept anything.
+        TypeKind::Custom { .. } => json!({}),
+        TypeKind::Future { .. } | TypeKind::Stream { .. } | TypeKind::Error { .. } | TypeKind::TraitObject { .. } => {
+            return Err(Error::NoJsonRepresentation(ty.span().clone(), ty.to_string()))
+        }
+    })
+}
+
+fn scalar_schema(scalar: Scalar) -> Value {
+    match scalar {
+        Scalar::Boolean => json!({ "type": "boolean" }),
+        Scalar::Char => json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+        Scalar::I8 | Scalar::I16 | Scalar::I32 => json!({ "type": "integer", "format": "int32" }),
+        Scalar::I64 => json!({ "type": "integer", "format": "int64" }),
+        Scalar::U8 | Scalar::U16 | Scalar::U32 | Scalar::U64 => json!({ "type": "integer", "minimum": 0 }),
+        Scalar::F32 => json!({ "type": "number", "format": "float" }),
+        Scalar::F64 => json!({ "type": "number", "format": "double" }),
+    }
+}
+
+/// An object with a property per field. `Option` fields may be omitted, as serde permits.
+fn fields_schema(idl: &Idl, fields: &[Field], reference: &dyn Fn(&QualifiedName) -> String) -> Result<Value> {
+    let mut properties = Map::new();
+    let mut required = vec![];
+    for field in fields {
+        properties.insert(field.name().to_string(), ty_schema(idl, field.ty(), reference)?);
+        if !matches!(field.ty().kind(), TypeKind::Option { .. }) {
+            required.push(field.name().to_string());
+        }
+    }
+    Ok(json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    }))
+}
+
+/// An array with an element of each of the types `elements`, as serde encodes tuples and tuple structs.
+fn tuple_schema<'ty>(
+    idl: &Idl,
+    elements: impl ExactSizeIterator<Item = &'ty Ty>,
+    reference: &dyn Fn(&QualifiedName) -> String,
+) -> Result<Value> {
+    let len = elements.len();
+    let items = elements
+        .map(|element| ty_schema(idl, element, reference))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(json!({
+        "type": "array",
+        "prefixItems": items,
+        "minItems": len,
+        "maxItems": len,
+    }))
+}
+
+/// An object with a single property `tag`, as serde encodes enum variants with data.
+fn externally_tagged(tag: &str, schema: Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": { tag: schema },
+        "required": [tag],
+        "additionalProperties": false,
+    })
+}
+
+fn operation(
+    idl: &Idl,
+    qname: &QualifiedName,
+    function: &Function,
+    reference: &dyn Fn(&QualifiedName) -> String,
+) -> Result<Value> {
+    let signature = function.signature();
+
+    let mut properties = Map::new();
+    let mut required = vec![];
+    for input in signature.inputs() {
+        let ty = input.refd_ty().ty();
+        properties.insert(input.name().to_string(), ty_schema(idl, ty, reference)?);
+        if !matches!(ty.kind(), TypeKind::Option { .. }) {
+            required.push(input.name().to_string());
+        }
+    }
+
+    let output_ty = signature.output_ty();
+    let mut responses = Map::new();
+    if output_ty.is_unit() {
+        // Nothing to send back for `()` (or `Result<(), E>`).
+        responses.insert("204".to_string(), json!({ "description": "The function returned successfully." }));
+    } else {
+        responses.insert(
+            "200".to_string(),
+            json!({
+                "description": "The function returned successfully.",
+                "content": { "application/json": { "schema": ty_schema(idl, output_ty.main_ty().ty(), reference)? } },
+            }),
+        );
+    }
+    if output_ty.error_ty().is_some() {
+        responses.insert("default".to_string(), json!({ "description": "The function returned an error." }));
+    }
+
+    Ok(json!({
+        "operationId": qname.names()[1..].iter().map(|name| name.to_string()).collect::<Vec<_>>().join("_"),
+        "requestBody": {
+            "required": true,
+            "content": {
+                "application/json": {
+                    "schema": { "type": "object", "properties": properties, "required": required },
+                },
+            },
+        },
+        "responses": responses,
+    }))
+}
@@ -5,10 +5,12 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::{
     collections::BTreeMap,
-    ffi::{OsStr, OsString}, path::PathBuf,
+    ffi::{OsStr, OsString},
+    hash::{DefaultHasher, Hash, Hasher},
+    path::PathBuf,
 };
 
-use crate::{Error, RefdTy, Span, Ty};
+use crate::{AutoTraits, Error, RefdTy, Span, Ty, TypeKind, UserTypeRepr};
 
 #[serde_as]
 #[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
@@ -20,11 +22,272 @@ pub struct Idl {
     /// Path to the crate from which IDL was generated
     pub(crate) crate_path: PathBuf,
 
+    /// Package-level information about the crate (version etc), typically taken from its `Cargo.toml`.
+    #[serde(default)]
+    pub(crate) crate_metadata: CrateMetadata,
+
+    /// The features of the crate that were enabled when parsing it (see [`crate::Parser::with_crate_features`]),
+    /// or `None` if they are unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) crate_features: Option<Vec<String>>,
+
+    /// Other crates (e.g., sibling crates in the same workspace) whose public definitions
+    /// are included in `definitions` because they may be referenced from this crate's API.
+    #[serde(default)]
+    pub(crate) extern_crates: Vec<ExternCrate>,
+
     /// A list of definitions to be exported. Each of them will be located within the crate in question.
     #[serde_as(as = "Vec<(_, _)>")]
     pub(crate) definitions: BTreeMap<QualifiedName, Item>,
 }
 
+impl Idl {
+    /// Retain only the definitions for which `f` returns true.
+    pub fn retain_definitions(&mut self, mut f: impl FnMut(&QualifiedName, &Item) -> bool) {
+        self.definitions.retain(|qname, item| f(qname, item));
+    }
+
+    /// Retain only the methods for which `f` returns true, given the name of the definition they are attached to.
+    /// The methods of interfaces are all kept, as the adapters implementing the trait need each of them.
+    pub fn retain_methods(&mut self, mut f: impl FnMut(&QualifiedName, &Method) -> bool) {
+        for (qname, item) in &mut self.definitions {
+            let methods = match item {
+                Item::Resource(resource) => &mut resource.methods,
+                Item::Record(record) => &mut record.methods,
+                Item::Newtype(newtype) => &mut newtype.methods,
+                Item::Variant(variant) => &mut variant.methods,
+                Item::Enum(an_enum) => &mut an_enum.methods,
+                Item::Function(_) | Item::Interface(_) => continue,
+            };
+            methods.retain(|method| f(qname, method));
+        }
+    }
+
+    /// A hash of the interface that bindings generated from this IDL expose: the crate name and its definitions,
+    /// leaving out what does not affect the bindings (spans and doc examples).
+    /// Backends embed it in both the Rust crate and the code in the other language, and check at load time
+    /// that the two sides were generated from the same interface.
+    pub fn interface_hash(&self) -> u64 {
+        fn strip(value: &mut serde_json::Value) {
+            match value {
+                serde_json::Value::Object(map) => {
+                    map.remove("span");
+                    map.remove("examples");
+                    map.values_mut().for_each(strip);
+                }
+                serde_json::Value::Array(values) => values.iter_mut().for_each(strip),
+                _ => {}
+            }
+        }
+
+        let definitions: Vec<_> = self.definitions.iter().collect();
+        let mut definitions = serde_json::to_value(definitions).expect("the IDL can be serialized");
+        strip(&mut definitions);
+
+        let mut hasher = DefaultHasher::new();
+        (&self.crate_name, definitions.to_string()).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Pack the parameters of the functions taking more than `max_params` of them into a record,
+    /// added to the definitions next to the function and named after it (e.g., `CreateUserParams` for `create_user`),
+    /// whose fields are the parameters in order (see [`Record::params_of`]). The functions are left as they are:
+    /// backends supporting this generate both the usual form of the function and one taking the record.
+    /// Methods are not packed.
+    pub fn pack_params(&mut self, max_params: usize) -> crate::Result<()> {
+        let mut records = vec![];
+        for (qname, function) in self.functions() {
+            let inputs = function.signature.inputs();
+            if inputs.len() <= max_params {
+                continue;
+            }
+            let record_qname = qname.module_path().join(format!("{}Params", function.name.upper_camel_case()));
+            if self.definitions.contains_key(&record_qname) || records.iter().any(|(other, _)| *other == record_qname) {
+                return Err(Error::ParamsRecordConflict(function.span.clone(), record_qname.colon_colon()));
+            }
+            let fields = inputs
+                .iter()
+                .map(|input| Field { span: input.span.clone(), name: input.name.clone(), ty: input.refd_ty.ty().clone() })
+                .collect();
+            let record = Record {
+                span: function.span.clone(),
+                name: record_qname.tail_name(),
+                fields,
+                methods: vec![],
+                pod: false,
+                tuple: false,
+                params_of: Some(qname.clone()),
+            };
+            records.push((record_qname, record));
+        }
+        self.definitions.extend(records.into_iter().map(|(qname, record)| (qname, Item::Record(record))));
+        Ok(())
+    }
+
+    /// The record packing the parameters of the function `function`, if [`Idl::pack_params`][] added one.
+    pub fn params_record(&self, function: &QualifiedName) -> Option<(&QualifiedName, &Record)> {
+        self.records().find(|(_, record)| record.params_of.as_ref() == Some(function))
+    }
+
+    /// The definition named `qname`, if any.
+    pub fn item(&self, qname: &QualifiedName) -> Option<&Item> {
+        self.definitions.get(qname)
+    }
+
+    /// If `ty` is a user type, return its name and definition, e.g. to decide how to pass it across the boundary.
+    /// Returns `None` for other types and for user types not defined in the IDL (see [`Idl::validate`][]).
+    pub fn user_type<'idl>(&'idl self, ty: &'idl Ty) -> Option<(&'idl QualifiedName, &'idl Item)> {
+        let TypeKind::UserType { qname, repr: _ } = ty.kind() else {
+            return None;
+        };
+        Some((qname, self.definitions.get(qname)?))
+    }
+
+    /// The resources among the definitions, ordered by name.
+    pub fn resources(&self) -> impl Iterator<Item = (&QualifiedName, &Resource)> {
+        self.definitions.iter().filter_map(|(qname, item)| match item {
+            Item::Resource(resource) => Some((qname, resource)),
+            _ => None,
+        })
+    }
+
+    /// The records among the definitions, ordered by name.
+    pub fn records(&self) -> impl Iterator<Item = (&QualifiedName, &Record)> {
+        self.definitions.iter().filter_map(|(qname, item)| match item {
+            Item::Record(record) => Some((qname, record)),
+            _ => None,
+        })
+    }
+
+    /// The newtypes among the definitions, ordered by name.
+    pub fn newtypes(&self) -> impl Iterator<Item = (&QualifiedName, &Newtype)> {
+        self.definitions.iter().filter_map(|(qname, item)| match item {
+            Item::Newtype(newtype) => Some((qname, newtype)),
+            _ => None,
+        })
+    }
+
+    /// The variants among the definitions, ordered by name.
+    pub fn variants(&self) -> impl Iterator<Item = (&QualifiedName, &Variant)> {
+        self.definitions.iter().filter_map(|(qname, item)| match item {
+            Item::Variant(variant) => Some((qname, variant)),
+            _ => None,
+        })
+    }
+
+    /// The enums among the definitions, ordered by name.
+    pub fn enums(&self) -> impl Iterator<Item = (&QualifiedName, &Enum)> {
+        self.definitions.iter().filter_map(|(qname, item)| match item {
+            Item::Enum(an_enum) => Some((qname, an_enum)),
+            _ => None,
+        })
+    }
+
+    /// The functions among the definitions, ordered by name.
+    pub fn functions(&self) -> impl Iterator<Item = (&QualifiedName, &Function)> {
+        self.definitions.iter().filter_map(|(qname, item)| match item {
+            Item::Function(function) => Some((qname, function)),
+            _ => None,
+        })
+    }
+
+    /// The interfaces among the definitions, ordered by name.
+    pub fn interfaces(&self) -> impl Iterator<Item = (&QualifiedName, &Interface)> {
+        self.definitions.iter().filter_map(|(qname, item)| match item {
+            Item::Interface(interface) => Some((qname, interface)),
+            _ => None,
+        })
+    }
+
+    /// If `ty` is a user type defined as a [`Newtype`][] (and not boxed), return its name and definition.
+    pub fn newtype<'idl>(&'idl self, ty: &'idl Ty) -> Option<(&'idl QualifiedName, &'idl Newtype)> {
+        let TypeKind::UserType { qname, repr: UserTypeRepr::Direct } = ty.kind() else {
+            return None;
+        };
+        match self.definitions.get(qname) {
+            Some(Item::Newtype(newtype)) => Some((qname, newtype)),
+            _ => None,
+        }
+    }
+
+    /// If `ty` is a user enum that implements `std::error::Error` (a [`Variant`][] or an [`Enum`][]), return its name and definition.
+    /// The parser recognizes `#[derive(Error)]` (e.g., from `thiserror`) and `impl Error for Type` in the module defining the enum.
+    /// When such an enum is the error type of a function, backends can raise an exception for each of its arms
+    /// rather than one carrying only its message.
+    pub fn error_item<'idl>(&'idl self, ty: &'idl Ty) -> Option<(&'idl QualifiedName, &'idl Item)> {
+        let TypeKind::UserType { qname, repr: UserTypeRepr::Direct } = ty.kind() else {
+            return None;
+        };
+        match self.definitions.get(qname)? {
+            item @ Item::Variant(Variant { implements_error: true, .. })
+            | item @ Item::Enum(Enum { implements_error: true, .. }) => Some((qname, item)),
+            _ => None,
+        }
+    }
+
+    /// The methods of the interfaces `resource` implements (see [`Resource::implements`]) that it does not define itself,
+    /// along with the name of their interface. Backends generate them on the class so that it has the methods of its interfaces.
+    pub fn inherited_methods<'idl>(&'idl self, resource: &'idl Resource) -> Vec<(&'idl QualifiedName, &'idl Method)> {
+        let mut methods = vec![];
+        for qname in &resource.implements {
+            let Some(Item::Interface(interface)) = self.definitions.get(qname) else {
+                continue;
+            };
+            for method in &interface.methods {
+                if resource.methods.iter().all(|m| m.name != method.name) {
+                    methods.push((qname, method));
+                }
+            }
+        }
+        methods
+    }
+}
+
+/// A crate whose definitions are included in an [`Idl`][] alongside those of the main crate.
+#[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
+#[accessors(get)]
+pub struct ExternCrate {
+    /// The name of the crate as it appears in Rust code (e.g., `other_crate`).
+    pub(crate) crate_name: Name,
+
+    /// Path to the crate, normally its manifest directory.
+    pub(crate) crate_path: PathBuf,
+
+    /// The features of the crate that were enabled when parsing it, or `None` if they are unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) features: Option<Vec<String>>,
+}
+
+/// Package-level information about the crate whose API is being bound,
+/// so that backends can stamp it onto the artifacts they generate (e.g., a `pyproject.toml`).
+/// Each field is `None` if it is unknown (e.g., not specified in the `Cargo.toml`).
+#[derive(Accessors, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[accessors(get)]
+pub struct CrateMetadata {
+    /// The crate version, e.g. `1.0.0`
+    pub(crate) version: Option<String>,
+
+    /// The crate description
+    pub(crate) description: Option<String>,
+
+    /// URL of the crate's source repository
+    pub(crate) repository: Option<String>,
+}
+
+impl CrateMetadata {
+    pub fn new(
+        version: Option<String>,
+        description: Option<String>,
+        repository: Option<String>,
+    ) -> Self {
+        Self {
+            version,
+            description,
+            repository,
+        }
+    }
+}
+
 #[derive(Accessors, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[accessors(get)]
 pub struct QualifiedName {
@@ -88,35 +351,97 @@ impl QualifiedName {
         self.names.last().unwrap().clone()
     }
 
-    /// The qualified name minus its last component
-    pub fn split_module_name(&self) -> (QualifiedName, Name) {
-        assert!(!self.names.is_empty());
-        (self.module_name(), self.tail_name())
+    /// The module containing the item named by `self`, and the name of the item within it
+    pub fn split_module_path(&self) -> (ModulePath, Name) {
+        (self.module_path(), self.tail_name())
+    }
+
+    /// The module containing the item named by `self` (see [`ModulePath::containing`][])
+    pub fn module_path(&self) -> ModulePath {
+        ModulePath::containing(self)
     }
+}
+
+/// The path of a module, e.g. `my_crate::shapes` for the module containing `my_crate::shapes::Circle`.
+///
+/// Module paths are only ever constructed anew, from the name of the crate, of the module itself,
+/// or of an item it contains, so that a path cannot be confused with that of an item (or left over from another one).
+/// The empty path is the root that the crates themselves are in.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModulePath {
+    qname: QualifiedName,
+}
 
-    /// The qualified name minus its last component
-    pub fn module_name(&self) -> QualifiedName {
-        assert!(!self.names.is_empty());
-        QualifiedName {
-            names: self.names[0..self.names.len() - 1].to_vec(),
+impl ModulePath {
+    /// The root that the crates are in, against which paths that begin with a crate name are resolved
+    pub fn root() -> Self {
+        ModulePath { qname: QualifiedName::new(vec![]) }
+    }
+
+    /// The root module of the crate `crate_name`
+    pub fn of_crate(crate_name: impl Into<Name>) -> Self {
+        ModulePath { qname: QualifiedName::new(vec![crate_name.into()]) }
+    }
+
+    /// The module whose own qualified name is `qname` (e.g., `my_crate::shapes`)
+    pub fn named(qname: QualifiedName) -> Self {
+        ModulePath { qname }
+    }
+
+    /// The module containing `item` (i.e., its qualified name minus the last component);
+    /// panics if `item` is empty
+    pub fn containing(item: &QualifiedName) -> Self {
+        assert!(!item.names.is_empty());
+        ModulePath {
+            qname: QualifiedName::new(item.names[..item.names.len() - 1].to_vec()),
         }
     }
 
-    /// Set the name to whatever the module is that contains `name` (removes the last item;
-    /// errors if `name` is empty).
-    pub(crate) fn set_to_module_of(&mut self, name: &QualifiedName) {
-        assert!(!name.names.is_empty());
-        let len = name.names.len();
-        self.names.extend(name.names.iter().take(len - 1).cloned());
+    /// The module as a qualified name, e.g. to look it up among the items
+    pub fn qname(&self) -> &QualifiedName {
+        &self.qname
+    }
+
+    /// The names of the module, starting with the crate name
+    pub fn names(&self) -> &[Name] {
+        &self.qname.names
+    }
+
+    /// The qualified name of the item `name` of this module
+    pub fn join(&self, name: impl Into<Name>) -> QualifiedName {
+        self.qname.join(name)
+    }
+
+    /// The root module of the crate this module belongs to; panics for [`Self::root`][]
+    pub fn crate_root(&self) -> ModulePath {
+        ModulePath::of_crate(self.qname.names[0].clone())
+    }
+
+    /// Return a version of the path separated by `.` (e.g., `my_crate.shapes`)
+    pub fn dotted(&self) -> String {
+        self.qname.dotted()
+    }
+
+    /// Return a version of the path separated by `::` (e.g., `my_crate::shapes`)
+    pub fn colon_colon(&self) -> String {
+        self.qname.colon_colon()
     }
 
-    /// Clear to an empty list
-    pub(crate) fn clear(&mut self) {
-        self.names.clear();
+    /// Convert all names to "camelCase".
+    pub fn camel_case(&self) -> ModulePath {
+        ModulePath { qname: self.qname.camel_case() }
     }
 
-    pub(crate) fn just_crate(&self) -> QualifiedName {
-        QualifiedName::new(vec![self.names[0].clone()])
+    /// Return a version of the path in which the root module `crate_name` is replaced by the `.`-separated
+    /// `root` (e.g., `com::example::mylib::shapes` for `my_crate::shapes`); paths in other crates are unchanged
+    pub fn with_crate_root(&self, crate_name: &Name, root: &str) -> ModulePath {
+        match self.qname.names.split_first() {
+            Some((first, rest)) if first == crate_name => {
+                let names = root.split('.').map(Name::from).chain(rest.iter().cloned()).collect();
+                ModulePath { qname: QualifiedName::new(names) }
+            }
+            _ => self.clone(),
+        }
     }
 }
 
@@ -162,6 +487,20 @@ impl Name {
         }
     }
 
+    /// Convert name to "snake_case".
+    pub fn snake_case(&self) -> Name {
+        Name {
+            text: self.text.to_case(Case::Snake),
+        }
+    }
+
+    /// Convert name to "SCREAMING_SNAKE_CASE".
+    pub fn screaming_snake_case(&self) -> Name {
+        Name {
+            text: self.text.to_case(Case::ScreamingSnake),
+        }
+    }
+
     pub fn output() -> Self {
         Self::from("Output")
     }
@@ -239,6 +578,10 @@ pub enum Item {
     /// It should map to a value type if that is available.
     Record(Record),
 
+    /// A *Newtype* is a tuple struct with a single public field, like `pub struct UserId(pub u64)`.
+    /// It should map to the type of its field, keeping a distinct name if that is available.
+    Newtype(Newtype),
+
     /// A *Variant* is corresponds to a general Rust enum.
     /// It should map to a value type if that is available.
     Variant(Variant),
@@ -250,6 +593,10 @@ pub enum Item {
     /// A *Function* is a standalone function that can be called.
     /// Note that each of the various types can also have attached methods.
     Function(Function),
+
+    /// An *Interface* is a trait whose methods foreign code can implement.
+    /// Its implementations are passed to Rust as trait objects (e.g., `&dyn Listener`).
+    Interface(Interface),
 }
 
 impl Item {
@@ -257,9 +604,36 @@ impl Item {
         match self {
             Item::Resource(r) => &r.name,
             Item::Record(r) => &r.name,
+            Item::Newtype(n) => &n.name,
             Item::Variant(v) => &v.name,
             Item::Enum(e) => &e.name,
             Item::Function(f) => &f.name,
+            Item::Interface(i) => &i.name,
+        }
+    }
+
+    pub fn span(&self) -> &Span {
+        match self {
+            Item::Resource(r) => &r.span,
+            Item::Record(r) => &r.span,
+            Item::Newtype(n) => &n.span,
+            Item::Variant(v) => &v.span,
+            Item::Enum(e) => &e.span,
+            Item::Function(f) => &f.span,
+            Item::Interface(i) => &i.span,
+        }
+    }
+
+    /// The methods attached to the item; functions have none.
+    pub fn methods(&self) -> &[Method] {
+        match self {
+            Item::Resource(r) => &r.methods,
+            Item::Record(r) => &r.methods,
+            Item::Newtype(n) => &n.methods,
+            Item::Variant(v) => &v.methods,
+            Item::Enum(e) => &e.methods,
+            Item::Function(_) => &[],
+            Item::Interface(i) => &i.methods,
         }
     }
 }
@@ -272,6 +646,24 @@ pub struct Function {
     /// Name in Rust syntax, like `crate::foo::bar`, relative
     pub(crate) name: Name,
     pub(crate) signature: Signature,
+
+    /// Code examples from the function's doc comment.
+    /// Only extracted if requested (see [`Parser::with_doc_examples`](`crate::Parser::with_doc_examples`)).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) examples: Vec<DocExample>,
+}
+
+/// A Rust code example (a fenced code block, as run by rustdoc) from a doc comment.
+/// Backends can emit these as test stubs in the target language.
+#[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
+#[accessors(get)]
+pub struct DocExample {
+    /// Span of the doc comment line that opens the code block.
+    pub(crate) span: Span,
+
+    /// The code of the example, including the lines that rustdoc hides (`# ...`),
+    /// which are needed to run it.
+    pub(crate) code: String,
 }
 
 /// A *Resource* is a structure with opaque contents and methods.
@@ -283,6 +675,24 @@ pub struct Resource {
     pub(crate) span: Span,
     pub(crate) name: Name,
     pub(crate) methods: Vec<Method>,
+
+    /// The auto traits the type implements, as far as the parser can tell. Whether it is `Send` and `Sync`
+    /// determines if the foreign object may be used from, or shared across, other threads.
+    #[serde(default)]
+    pub(crate) auto_traits: AutoTraits,
+
+    /// The [interfaces](`Interface`) the type implements (via `impl Trait for Type` in the module defining it),
+    /// so that backends can make the class an instance of them in the target language.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) implements: Vec<QualifiedName>,
+}
+
+impl Resource {
+    /// True if some method consumes the resource (see [`Method::consumes_self`]),
+    /// in which case foreign handles to it can become invalid.
+    pub fn has_consuming_methods(&self) -> bool {
+        self.methods.iter().any(Method::consumes_self)
+    }
 }
 
 /// A *Variant* is corresponds to a general Rust enum.
@@ -295,6 +705,10 @@ pub struct Variant {
     pub(crate) name: Name,
     pub(crate) arms: Vec<VariantArm>,
     pub(crate) methods: Vec<Method>,
+
+    /// True if the enum implements `std::error::Error` (see [`Idl::error_item`]).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) implements_error: bool,
 }
 
 #[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
@@ -316,6 +730,10 @@ pub struct Enum {
     pub(crate) name: Name,
     pub(crate) arms: Vec<EnumArm>,
     pub(crate) methods: Vec<Method>,
+
+    /// True if the enum implements `std::error::Error` (see [`Idl::error_item`]).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) implements_error: bool,
 }
 
 #[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
@@ -325,6 +743,18 @@ pub struct EnumArm {
     pub(crate) name: Name,
 }
 
+/// An *Interface* is a public trait. Foreign code implements its methods,
+/// which all take `&self` or `&mut self`, and passes the implementation to Rust as a trait object
+/// (see [`TypeKind::TraitObject`]).
+#[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
+#[accessors(get)]
+pub struct Interface {
+    /// Span identifying this item in Rust source (currently its name).
+    pub(crate) span: Span,
+    pub(crate) name: Name,
+    pub(crate) methods: Vec<Method>,
+}
+
 /// *Methods* can be attached to various types.
 /// They include
 #[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
@@ -341,6 +771,20 @@ pub struct Method {
 
     /// Method signature.
     pub(crate) signature: Signature,
+
+    /// The group of overloads the method belongs to, named with `#[gluegun::overload(name)]`.
+    /// Methods like `from_str` and `from_bytes` tagged `#[gluegun::overload(from)]` can then become overloads
+    /// of `from` in languages that have overloading; how each backend names them is configurable in its metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) overload: Option<Name>,
+}
+
+impl Method {
+    /// True if the method takes `self` by value, consuming the object.
+    /// Once it has been called, the foreign handle to the object must not be used again.
+    pub fn consumes_self(&self) -> bool {
+        self.category.self_kind().is_some_and(SelfKind::consumes)
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
@@ -351,10 +795,16 @@ pub enum MethodCategory {
     /// It can be fallible.
     Constructor,
 
+    /// A method with no `self` other than the constructor that creates an instance of the type,
+    /// like `fn from_path(path: &Path) -> Result<Self, Error>`. Backends can surface it as a factory
+    /// (e.g., a Java static method returning the class or a Python class method).
+    NamedConstructor,
+
     /// Builder methods have a signature in Rust that looks like
     /// `fn method(self, ...) -> Self`. They can be treated as ordinary methods
     /// but in some cases you may wish to map types that have builder methods
-    /// in some other way.
+    /// in some other way (e.g., the Java and Python backends make them fluent,
+    /// returning the foreign object itself, which takes over the value the method returns).
     BuilderMethod(SelfKind),
 
     /// Some kind of method that takes `self`, `&self`, or `&mut self`.
@@ -363,6 +813,22 @@ pub enum MethodCategory {
 
     /// A method with no `self`.
     StaticMethod,
+
+    /// Reads the public field `0` (e.g., `name`, or `0` for a tuple struct) of an opaque resource
+    /// (see `#[gluegun::opaque]`). There is no such method in Rust: backends access the field
+    /// directly (`&this.name`). The signature has no inputs and returns a reference to the field.
+    Getter(Name),
+}
+
+impl MethodCategory {
+    /// The kind of `self` parameter, if the method has one.
+    pub fn self_kind(&self) -> Option<&SelfKind> {
+        match self {
+            MethodCategory::BuilderMethod(self_kind) | MethodCategory::InstanceMethod(self_kind) => Some(self_kind),
+            MethodCategory::Getter(_) => Some(&SelfKind::ByRef),
+            MethodCategory::Constructor | MethodCategory::NamedConstructor | MethodCategory::StaticMethod => None,
+        }
+    }
 }
 
 //// Defines a `self` parameter type
@@ -377,10 +843,25 @@ pub enum SelfKind {
 
     /// `fn(&mut self)`
     ByRefMut,
+
+    /// `fn(self: Arc<Self>)`
+    ByArc,
+
+    /// `fn(self: Rc<Self>)`
+    ByRc,
+}
+
+impl SelfKind {
+    /// True for `self` by value, which moves the object into the method.
+    pub fn consumes(&self) -> bool {
+        matches!(self, SelfKind::ByValue)
+    }
 }
 
 /// A *Record* is a structure with a known (and fixed) set of fields and types.
 /// It should map to a value type if that is available.
+/// A struct without fields (e.g., the unit struct `pub struct Marker;`) is a record without fields,
+/// which backends map to a marker class.
 #[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
 #[accessors(get)]
 pub struct Record {
@@ -395,6 +876,86 @@ pub struct Record {
 
     /// Methods attached to this record.
     pub(crate) methods: Vec<Method>,
+
+    /// True if the record is "POD-compatible": it has fields, all of which are scalars other than `char`,
+    /// POD-compatible records, or newtypes of those, so that it can be mirrored by a `#[repr(C)]` struct
+    /// with a stable layout. Computed once all definitions are elaborated.
+    pub(crate) pod: bool,
+
+    /// True if the record is a tuple struct with several fields, like `pub struct Point(pub f64, pub f64)`,
+    /// whose fields are named `f0`, `f1`, etc. in the IDL. Backends can map it to a tuple-like type
+    /// constructed from its fields by position; on the Rust side, the fields are `0`, `1`, etc.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) tuple: bool,
+
+    /// The function whose parameters the record packs, if gluegun generated it (see [`Idl::pack_params`]).
+    /// Such a record has no Rust definition: backends only define it in the target language, along with
+    /// a form of the function that takes the record and passes its fields to the function.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) params_of: Option<QualifiedName>,
+}
+
+impl Record {
+    /// The Rust member of the field at `index`: its name, or its position for a tuple struct (e.g., `0` for `f0`),
+    /// as in `value.0` or `Point { 0: x, 1: y }`.
+    pub fn member(&self, index: usize) -> String {
+        if self.tuple {
+            index.to_string()
+        } else {
+            self.fields[index].name.to_string()
+        }
+    }
+}
+
+/// A *Newtype* is a tuple struct with a single public field, like `pub struct UserId(pub u64)`.
+/// Backends map it to the type of its field (converting with `UserId(value)` and `value.0`
+/// on the Rust side), keeping a distinct name where the target language has a notion of one
+/// (e.g., a Java record or a Python `NewType`). Like serde, the JSON representation is that of the field.
+///
+/// A struct tagged `#[gluegun::marshal_as(String)]` is a *validated* newtype instead, whatever its fields:
+/// it is passed as a `String`, converting with `TryFrom<String>` and `From<UserId> for String`
+/// (see [`Newtype::wrap_expr`] and [`Newtype::unwrap_expr`]).
+#[derive(Accessors, Clone, Debug, Serialize, Deserialize)]
+#[accessors(get)]
+pub struct Newtype {
+    /// Span identifying this item in Rust source (currently its name).
+    pub(crate) span: Span,
+
+    /// Name of the newtype.
+    pub(crate) name: Name,
+
+    /// Type of the wrapped field (for validated newtypes, the type named by `#[gluegun::marshal_as]`).
+    pub(crate) ty: Ty,
+
+    /// Methods attached to this newtype.
+    pub(crate) methods: Vec<Method>,
+
+    /// True if the newtype is tagged `#[gluegun::marshal_as(Type)]`: its fields may be private,
+    /// and converting a `Type` into it checks that the value is valid (and may fail).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) validated: bool,
+}
+
+impl Newtype {
+    /// Rust expression wrapping `expr`, a value of [`Newtype::ty`], in the newtype at `path`.
+    /// For validated newtypes, the expression is a `Result` whose error is that of the `TryFrom` impl,
+    /// which backends report to the caller (e.g., as an exception); it implements `Display`.
+    pub fn wrap_expr(&self, path: &str, expr: &str) -> String {
+        if self.validated {
+            format!("<{path} as TryFrom<{}>>::try_from({expr})", self.ty.rust_owned_spelling())
+        } else {
+            format!("{path}({expr})")
+        }
+    }
+
+    /// Rust expression unwrapping `expr`, a value of the newtype, into a value of [`Newtype::ty`].
+    pub fn unwrap_expr(&self, expr: &str) -> String {
+        if self.validated {
+            format!("<{}>::from({expr})", self.ty.rust_owned_spelling())
+        } else {
+            format!("{expr}.0")
+        }
+    }
 }
 
 /// A field in a record.
@@ -459,3 +1020,18 @@ pub struct FunctionOutput {
     /// Type of value returned on error.
     pub(crate) error_ty: Option<Ty>,
 }
+
+impl FunctionOutput {
+    /// True if the function returns borrowed data (e.g., `-> &str` or `-> &[T]`).
+    /// Borrowed data cannot be returned across the FFI boundary, so backends must copy it out
+    /// (via `ToOwned::to_owned`) into the type given by [`Ty::rust_owned_spelling`][].
+    pub fn requires_copy_out(&self) -> bool {
+        matches!(self.main_ty, RefdTy::Ref(..))
+    }
+
+    /// True if the function returns no value on success, i.e., it returns `()` or `Result<(), E>`.
+    /// Backends map such functions to `void` functions (which throw if there is an error type).
+    pub fn is_unit(&self) -> bool {
+        self.main_ty.ty().is_unit()
+    }
+}
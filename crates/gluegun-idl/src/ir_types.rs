@@ -30,6 +30,15 @@ impl Ty {
         )
     }
 
+    pub(crate) fn io_error(span: Span) -> Self {
+        Ty::new(
+            span,
+            TypeKind::Error {
+                repr: ErrorRepr::IoError,
+            },
+        )
+    }
+
     pub fn kind(&self) -> &TypeKind {
         &*self.kind
     }
@@ -42,11 +51,17 @@ impl Ty {
         )
     }
 
+    /// True if this is the unit type `()`.
+    pub fn is_unit(&self) -> bool {
+        matches!(self.kind(), TypeKind::Tuple { elements, repr: _ } if elements.is_empty())
+    }
+
     pub(crate) fn user(span: Span, qname: &QualifiedName) -> Self {
         Ty::new(
             span,
             TypeKind::UserType {
-                qname: qname.clone()
+                qname: qname.clone(),
+                repr: UserTypeRepr::Direct,
             },
         )
     }
@@ -60,6 +75,122 @@ impl Ty {
     pub fn owned(self) -> RefdTy {
         RefdTy::Owned(OwnedKind::Owned, self)
     }
+
+    /// The Rust spelling of this type as it appears in the source (e.g., `str` for the `str`
+    /// in `&str`; see [`RefdTy::rust_spelling`][] for the full type including the reference).
+    /// Paths are fully qualified (e.g., `std::collections::HashMap<K, V>`, `my_crate::Foo`)
+    /// so that they can be used from a generated crate.
+    pub fn rust_spelling(&self) -> String {
+        self.spelling(false, false)
+    }
+
+    /// The Rust spelling of an owned type that can be used to hold a value of this type
+    /// (e.g., `String` for `str`, `Vec<T>` for `[T]`, `PathBuf` for `Path`).
+    pub fn rust_owned_spelling(&self) -> String {
+        self.spelling(true, false)
+    }
+
+    /// Like [`Self::rust_spelling`][], but with the paths of user types starting with `::`
+    /// (e.g., `::my_crate::Foo`), so that they still name the crate where a module of the same name is in scope.
+    pub fn rust_absolute_spelling(&self) -> String {
+        self.spelling(false, true)
+    }
+
+    /// Like [`Self::rust_owned_spelling`][], but with absolute paths (see [`Self::rust_absolute_spelling`][]).
+    pub fn rust_owned_absolute_spelling(&self) -> String {
+        self.spelling(true, true)
+    }
+
+    fn spelling(&self, owned: bool, absolute: bool) -> String {
+        let list = |tys: &[&Ty]| tys.iter().map(|ty| ty.spelling(owned, absolute)).collect::<Vec<_>>().join(", ");
+        let user_path = |qname: &QualifiedName| if absolute { format!("::{}", qname.colon_colon()) } else { qname.colon_colon() };
+        match &*self.kind {
+            TypeKind::Map { key, value, repr } => {
+                let name = match repr {
+                    MapSetRepr::Hash => "std::collections::HashMap",
+                    MapSetRepr::BTree => "std::collections::BTreeMap",
+                    MapSetRepr::Index => "indexmap::IndexMap",
+                };
+                format!("{name}<{}>", list(&[key, value]))
+            }
+            TypeKind::Vec { element, repr } => match repr {
+                VecRepr::SliceRef if !owned => format!("[{}]", element.spelling(owned, absolute)),
+                VecRepr::ImplIntoIterator if !owned => format!("impl IntoIterator<Item = {}>", element.spelling(owned, absolute)),
+                VecRepr::Vec | VecRepr::SliceRef | VecRepr::ImplIntoIterator => format!("Vec<{}>", element.spelling(owned, absolute)),
+            },
+            TypeKind::Set { element, repr } => {
+                let name = match repr {
+                    MapSetRepr::Hash => "std::collections::HashSet",
+                    MapSetRepr::BTree => "std::collections::BTreeSet",
+                    MapSetRepr::Index => "indexmap::IndexSet",
+                };
+                format!("{name}<{}>", element.spelling(owned, absolute))
+            }
+            TypeKind::Path { repr } => match repr {
+                PathRepr::PathRef if !owned => "std::path::Path".to_string(),
+                PathRepr::PathRef | PathRepr::PathBuf => "std::path::PathBuf".to_string(),
+            },
+            TypeKind::NetAddr { repr } => repr.rust_path(),
+            TypeKind::Uuid { repr: UuidRepr::Uuid } => "uuid::Uuid".to_string(),
+            TypeKind::Timestamp { repr } => repr.rust_path().to_string(),
+            TypeKind::String { repr } => match repr {
+                StringRepr::StrRef if !owned => "str".to_string(),
+                StringRepr::ImplToString if !owned => "impl ToString".to_string(),
+                StringRepr::String | StringRepr::StrRef | StringRepr::ImplToString => "String".to_string(),
+            },
+            TypeKind::Option { element, repr: OptionRepr::Option } => format!("Option<{}>", element.spelling(owned, absolute)),
+            TypeKind::Result { ok, err, repr: ResultRepr::Result } => format!("Result<{}>", list(&[ok, err])),
+            TypeKind::Tuple { elements, repr: TupleRepr::Tuple(_) } => match &elements[..] {
+                [element] => format!("({},)", element.spelling(owned, absolute)),
+                _ => format!("({})", list(&elements.iter().collect::<Vec<_>>())),
+            },
+            TypeKind::Scalar(scalar) => scalar.to_string(),
+            TypeKind::Future { output, repr } => match repr {
+                FutureRepr::ImplFuture(auto_traits) if !owned => format!(
+                    "impl std::future::Future<Output = {}>{}",
+                    output.spelling(owned, absolute),
+                    auto_traits.bounds(),
+                ),
+                FutureRepr::ImplFuture(auto_traits) | FutureRepr::PinBoxDynFuture(auto_traits) => format!(
+                    "std::pin::Pin<Box<dyn std::future::Future<Output = {}>{}>>",
+                    output.spelling(owned, absolute),
+                    auto_traits.bounds(),
+                ),
+            },
+            TypeKind::Stream { item, repr } => match repr {
+                StreamRepr::ImplStream(auto_traits) if !owned => format!(
+                    "impl futures::Stream<Item = {}>{}",
+                    item.spelling(owned, absolute),
+                    auto_traits.bounds(),
+                ),
+                StreamRepr::ImplStream(auto_traits) => format!(
+                    "std::pin::Pin<Box<dyn futures::Stream<Item = {}>{}>>",
+                    item.spelling(owned, absolute),
+                    auto_traits.bounds(),
+                ),
+            },
+            TypeKind::Error { repr } => match repr {
+                ErrorRepr::AnyhowError => "anyhow::Error".to_string(),
+                ErrorRepr::IoError => "std::io::Error".to_string(),
+                ErrorRepr::BoxDynError(auto_traits) => {
+                    format!("Box<dyn std::error::Error{}>", auto_traits.bounds())
+                }
+            },
+            TypeKind::UserType { qname, repr } => match repr {
+                UserTypeRepr::Direct => user_path(qname),
+                UserTypeRepr::Boxed => format!("Box<{}>", user_path(qname)),
+            },
+            TypeKind::TraitObject { qname, repr } => match repr {
+                TraitObjectRepr::Dyn(auto_traits) if !owned => {
+                    format!("dyn {}{}", user_path(qname), auto_traits.bounds())
+                }
+                TraitObjectRepr::Dyn(auto_traits) | TraitObjectRepr::BoxDyn(auto_traits) => {
+                    format!("Box<dyn {}{}>", user_path(qname), auto_traits.bounds())
+                }
+            },
+            TypeKind::Custom { path } => path.clone(),
+        }
+    }
 }
 
 impl std::fmt::Display for Ty {
@@ -69,6 +200,9 @@ impl std::fmt::Display for Ty {
             TypeKind::Vec { element, repr: _ } => write!(f, "Vec<{}>", element),
             TypeKind::Set { element , repr: _} => write!(f, "Set<{}>", element),
             TypeKind::Path { repr: _ } => write!(f, "Path"),
+            TypeKind::NetAddr { repr } => write!(f, "{}", repr.name()),
+            TypeKind::Uuid { repr: _ } => write!(f, "Uuid"),
+            TypeKind::Timestamp { repr } => write!(f, "{}", repr.name()),
             TypeKind::String { repr: _ } => write!(f, "String"),
             TypeKind::Option { element, repr: _ } => write!(f, "Option<{}>", element),
             TypeKind::Result { ok, err, repr: _ } => write!(f, "Result<{}, {}>", ok, err),
@@ -81,13 +215,20 @@ impl std::fmt::Display for Ty {
                     }
                     s.push_str(&e.to_string());
                 }
+                // A 1-tuple needs a trailing comma to be distinguished from a parenthesized type.
+                if elements.len() == 1 {
+                    s.push(',');
+                }
                 s.push(')');
                 write!(f, "{}", s)
             }
             TypeKind::Scalar(s) => write!(f, "{}", s),
             TypeKind::Future { output, repr: _ } => write!(f, "impl Future<Output = {}>", output),
+            TypeKind::Stream { item, repr: _ } => write!(f, "impl Stream<Item = {}>", item),
             TypeKind::Error { repr: _ } => write!(f, "Error"),
-            TypeKind::UserType { qname  } => write!(f, "{}", qname.to_string("::")),
+            TypeKind::UserType { qname, repr: _ } => write!(f, "{}", qname.to_string("::")),
+            TypeKind::TraitObject { qname, repr: _ } => write!(f, "dyn {}", qname.to_string("::")),
+            TypeKind::Custom { path } => write!(f, "{path}"),
         }
     }
 }
@@ -111,6 +252,24 @@ pub enum TypeKind {
     Path {
         repr: PathRepr,
     },
+
+    /// An IP or socket address from `std::net`, exchanged as its string form
+    /// (e.g., `127.0.0.1`, `::1`, or `[::1]:8080`).
+    NetAddr {
+        repr: NetAddrRepr,
+    },
+
+    /// A `uuid::Uuid`, exchanged as its hyphenated string form
+    /// (e.g., `67e55044-10b1-426f-9247-bb680e5fe0c8`).
+    Uuid {
+        repr: UuidRepr,
+    },
+
+    /// A point in time (e.g., `chrono::DateTime<Utc>`) or a calendar date (`chrono::NaiveDate`).
+    Timestamp {
+        repr: TimestampRepr,
+    },
+
     String {
         repr: StringRepr,
     },
@@ -135,6 +294,13 @@ pub enum TypeKind {
         repr: FutureRepr,
     },
 
+    /// Values produced asynchronously one after the other, like `impl Stream<Item = T>` from the `futures` crate
+    /// (e.g., the events of a resource). Only accepted as the result of a function or method.
+    Stream {
+        item: Ty,
+        repr: StreamRepr,
+    },
+
     // Represents a generic exception/error type.
     Error {
         repr: ErrorRepr,
@@ -143,6 +309,23 @@ pub enum TypeKind {
     /// Type defined by the user
     UserType {
         qname: QualifiedName,
+        #[serde(default, skip_serializing_if = "UserTypeRepr::is_direct")]
+        repr: UserTypeRepr,
+    },
+
+    /// A trait object of an [`Interface`](`crate::Interface`) defined by the user,
+    /// through which Rust calls an implementation provided by foreign code.
+    TraitObject {
+        qname: QualifiedName,
+        repr: TraitObjectRepr,
+    },
+
+    /// A type from another crate that gluegun does not know, like `chrono::DateTime<Utc>`.
+    /// It is only accepted if `path` (without generic arguments) was given to
+    /// [`Parser::with_custom_type`](`crate::Parser::with_custom_type`); backends map it
+    /// according to the type overrides configured by the user.
+    Custom {
+        path: String,
     },
 }
 
@@ -165,6 +348,9 @@ impl std::fmt::Display for TypeKind {
             TypeKind::Vec { element, repr: _ } => write!(f, "Vec<{}>", element)?,
             TypeKind::Set { element, repr: _ } => write!(f, "Set<{}>", element)?,
             TypeKind::Path { repr: _ } => write!(f, "Path")?,
+            TypeKind::NetAddr { repr } => write!(f, "{}", repr.name())?,
+            TypeKind::Uuid { repr: _ } => write!(f, "Uuid")?,
+            TypeKind::Timestamp { repr } => write!(f, "{}", repr.name())?,
             TypeKind::String { repr: _ } => write!(f, "String")?, 
             TypeKind::Option { element, repr: _ } => write!(f, "Option<{}>", element)?,
             TypeKind::Result { ok, err, repr: _ } => write!(f, "Result<{}, {}>", ok, err)?,
@@ -177,13 +363,19 @@ impl std::fmt::Display for TypeKind {
                     }
                     s.push_str(&e.to_string());
                 }
+                if elements.len() == 1 {
+                    s.push(',');
+                }
                 s.push(')');
                 write!(f, "{}", s)?
             },
             TypeKind::Scalar(scalar) => write!(f, "{}", scalar)?,
             TypeKind::Future { output, repr: _ } => write!(f, "impl Future<Output = {}>", output)?,
+            TypeKind::Stream { item, repr: _ } => write!(f, "impl Stream<Item = {}>", item)?,
             TypeKind::Error { repr: _ } => write!(f, "Error")?,
-            TypeKind::UserType { qname } => write!(f, "{}", qname.to_string("::"))?,
+            TypeKind::UserType { qname, repr: _ } => write!(f, "{}", qname.to_string("::"))?,
+            TypeKind::TraitObject { qname, repr: _ } => write!(f, "dyn {}", qname.to_string("::"))?,
+            TypeKind::Custom { path } => write!(f, "{path}")?,
         }
         Ok(())
     }
@@ -212,14 +404,22 @@ pub enum VecRepr {
 
     /// `&[T]` (of some kind)
     SliceRef,
+
+    /// `impl IntoIterator<Item = T>`, only accepted as a function argument, which is given a `Vec<T>`
+    ImplIntoIterator,
 }
 
 /// Different patterns that we recognize as being a "Map" in Rust code.
 #[non_exhaustive]
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub enum MapSetRepr {
+    /// `HashMap` or `HashSet`
     Hash,
+
+    /// `BTreeMap` or `BTreeSet`
     BTree,
+
+    /// `IndexMap` or `IndexSet` from the `indexmap` crate
     Index,
 }
 
@@ -234,6 +434,100 @@ pub enum PathRepr {
     PathBuf,
 }
 
+/// The address types of `std::net` that we recognize (see [`TypeKind::NetAddr`][]).
+#[non_exhaustive]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum NetAddrRepr {
+    /// `IpAddr`, either an IPv4 or an IPv6 address
+    IpAddr,
+
+    /// `Ipv4Addr`
+    Ipv4Addr,
+
+    /// `Ipv6Addr`
+    Ipv6Addr,
+
+    /// `SocketAddr`, an IP address (either kind) and a port
+    SocketAddr,
+
+    /// `SocketAddrV4`
+    SocketAddrV4,
+
+    /// `SocketAddrV6`
+    SocketAddrV6,
+}
+
+impl NetAddrRepr {
+    /// The name of the Rust type (e.g., `Ipv4Addr`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            NetAddrRepr::IpAddr => "IpAddr",
+            NetAddrRepr::Ipv4Addr => "Ipv4Addr",
+            NetAddrRepr::Ipv6Addr => "Ipv6Addr",
+            NetAddrRepr::SocketAddr => "SocketAddr",
+            NetAddrRepr::SocketAddrV4 => "SocketAddrV4",
+            NetAddrRepr::SocketAddrV6 => "SocketAddrV6",
+        }
+    }
+
+    /// The fully qualified path of the Rust type (e.g., `std::net::Ipv4Addr`).
+    pub fn rust_path(&self) -> String {
+        format!("std::net::{}", self.name())
+    }
+
+    /// True for the socket addresses, which include a port.
+    pub fn is_socket(&self) -> bool {
+        matches!(self, NetAddrRepr::SocketAddr | NetAddrRepr::SocketAddrV4 | NetAddrRepr::SocketAddrV6)
+    }
+}
+
+/// Different patterns that we recognize as being a "Uuid" in Rust code.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum UuidRepr {
+    /// `uuid::Uuid`
+    Uuid,
+}
+
+/// The date and time types that we recognize (see [`TypeKind::Timestamp`][]).
+#[non_exhaustive]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum TimestampRepr {
+    /// `chrono::DateTime<Utc>`
+    ChronoDateTimeUtc,
+
+    /// `chrono::NaiveDate`, a date without a time or time zone
+    ChronoNaiveDate,
+
+    /// `time::OffsetDateTime`, a point in time with a UTC offset
+    TimeOffsetDateTime,
+}
+
+impl TimestampRepr {
+    /// The name of the Rust type (e.g., `NaiveDate`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimestampRepr::ChronoDateTimeUtc => "DateTime<Utc>",
+            TimestampRepr::ChronoNaiveDate => "NaiveDate",
+            TimestampRepr::TimeOffsetDateTime => "OffsetDateTime",
+        }
+    }
+
+    /// The fully qualified path of the Rust type (e.g., `chrono::NaiveDate`).
+    pub fn rust_path(&self) -> &'static str {
+        match self {
+            TimestampRepr::ChronoDateTimeUtc => "chrono::DateTime<chrono::Utc>",
+            TimestampRepr::ChronoNaiveDate => "chrono::NaiveDate",
+            TimestampRepr::TimeOffsetDateTime => "time::OffsetDateTime",
+        }
+    }
+
+    /// True for the types that represent a calendar date rather than a point in time.
+    pub fn is_date(&self) -> bool {
+        matches!(self, TimestampRepr::ChronoNaiveDate)
+    }
+}
+
 /// Different patterns that we recognize as being a "Option" in Rust code.
 #[non_exhaustive]
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
@@ -258,6 +552,25 @@ pub enum TupleRepr {
     Tuple(usize),
 }
 
+/// Different patterns that we recognize as being a type defined by the user in Rust code.
+#[non_exhaustive]
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum UserTypeRepr {
+    /// `T`
+    #[default]
+    Direct,
+
+    /// `Box<T>`, typically used for a field of a recursive type (e.g., `next: Option<Box<Node>>`)
+    Boxed,
+}
+
+impl UserTypeRepr {
+    /// True for a type spelled `T`, i.e., not boxed.
+    pub fn is_direct(&self) -> bool {
+        matches!(self, UserTypeRepr::Direct)
+    }
+}
+
 /// Different patterns that we recognize as being a "Future" in Rust code.
 #[non_exhaustive]
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
@@ -269,6 +582,25 @@ pub enum FutureRepr {
     PinBoxDynFuture(AutoTraits),
 }
 
+/// Different patterns that we recognize as being a "Stream" in Rust code.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum StreamRepr {
+    /// `impl Stream<Item = T>`, which backends box as a `Pin<Box<dyn Stream<Item = T>>>`
+    ImplStream(AutoTraits),
+}
+
+/// Different patterns that we recognize as being a trait object in Rust code.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum TraitObjectRepr {
+    /// `dyn Trait`, behind a reference (e.g., `&dyn Trait`)
+    Dyn(AutoTraits),
+
+    /// `Box<dyn Trait>`
+    BoxDyn(AutoTraits),
+}
+
 /// Different patterns that we recognize as being an "Error" in Rust code.
 #[non_exhaustive]
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
@@ -276,6 +608,9 @@ pub enum ErrorRepr {
     /// `anyhow::Error`
     AnyhowError,
 
+    /// `std::io::Error`
+    IoError,
+
     /// `Box<dyn Error>`
     BoxDynError(AutoTraits),
 }
@@ -284,9 +619,19 @@ pub enum ErrorRepr {
 #[derive(Accessors, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 #[accessors(get_copy)]
 pub struct AutoTraits {
-    send: bool,
-    sync: bool,
-    unpin: bool,
+    pub(crate) send: bool,
+    pub(crate) sync: bool,
+    pub(crate) unpin: bool,
+}
+
+impl AutoTraits {
+    /// Additional bounds like ` + Send + Sync`, to be appended to a `dyn` or `impl` type.
+    fn bounds(&self) -> String {
+        [(self.send, " + Send"), (self.sync, " + Sync"), (self.unpin, " + Unpin")]
+            .into_iter()
+            .filter_map(|(present, bound)| present.then_some(bound))
+            .collect()
+    }
 }
 
 /// Recognized scalar types.
@@ -359,6 +704,25 @@ impl RefdTy {
         }
     }
 
+    /// The Rust spelling of this type including any reference (e.g., `&str`, `impl AsRef<std::path::Path>`).
+    /// See [`Ty::rust_spelling`][].
+    pub fn rust_spelling(&self) -> String {
+        match self {
+            RefdTy::Owned(OwnedKind::Owned, ty) => ty.rust_spelling(),
+            RefdTy::Ref(RefKind::AnonRef, ty) => format!("&{}", ty.rust_spelling()),
+            RefdTy::Ref(RefKind::ImplAsRef, ty) => format!("impl AsRef<{}>", ty.rust_spelling()),
+        }
+    }
+
+    /// Like [`Self::rust_spelling`][], but with absolute paths (see [`Ty::rust_absolute_spelling`][]).
+    pub fn rust_absolute_spelling(&self) -> String {
+        match self {
+            RefdTy::Owned(OwnedKind::Owned, ty) => ty.rust_absolute_spelling(),
+            RefdTy::Ref(RefKind::AnonRef, ty) => format!("&{}", ty.rust_absolute_spelling()),
+            RefdTy::Ref(RefKind::ImplAsRef, ty) => format!("impl AsRef<{}>", ty.rust_absolute_spelling()),
+        }
+    }
+
     /// If this is an owned type, return it, else return an error.
     /// Used when backends do not support reference types in a particular position.
     pub fn owned_or_err(&self) -> crate::Result<&Ty> {
@@ -385,7 +749,7 @@ impl std::fmt::Display for RefdTy {
 }
 
 /// Indicates the style of ownership. For now this is always just `T`
-/// but we may expand in the future to include `Box<T>`, for example.
+/// (a boxed user type is a [`TypeKind::UserType`][] with [`UserTypeRepr::Boxed`][]).
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum OwnedKind {
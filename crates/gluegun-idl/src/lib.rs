@@ -1,11 +1,15 @@
 mod error;
+pub mod export;
 mod ir_items;
 mod ir_types;
 mod parse;
 mod span;
+pub mod text;
+mod validate;
 
 pub use error::*;
 pub use ir_items::*;
 pub use ir_types::*;
 pub use parse::*;
 pub use span::*;
+pub use validate::*;
@@ -1,16 +1,171 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use crate::{Error, Idl, Name, QualifiedName, SourcePath};
+use serde::{Deserialize, Serialize};
+
+use crate::{CrateMetadata, Error, ExternCrate, Idl, Name, QualifiedName, SourcePath};
 
 pub struct Parser {
+    crate_metadata: CrateMetadata,
+    extern_crates: Vec<(ExternCrate, PathBuf)>,
+    cache_dir: Option<PathBuf>,
+    doc_examples: bool,
+    custom_types: Vec<String>,
+    crate_features: BTreeMap<Name, Vec<String>>,
+    cfg_options: Option<BTreeSet<(String, Option<String>)>>,
+    edition: Edition,
+    max_module_depth: Option<usize>,
+    doc_hidden: bool,
+    source_root: Option<PathBuf>,
+    error_recovery: bool,
+    recovered_errors: Vec<Error>,
 }
 
 impl Parser {
     pub fn new() -> Self {
         Self {
+            crate_metadata: CrateMetadata::default(),
+            extern_crates: vec![],
+            cache_dir: None,
+            doc_examples: false,
+            custom_types: vec![],
+            crate_features: BTreeMap::new(),
+            cfg_options: None,
+            edition: Edition::default(),
+            max_module_depth: None,
+            doc_hidden: false,
+            source_root: None,
+            error_recovery: false,
+            recovered_errors: vec![],
         }
     }
 
+    /// Include the crate `crate_name`, whose `lib.rs` is at `rs_path`, when parsing.
+    /// Its public definitions can be referenced from the main crate
+    /// (e.g., `other_crate::Config` or `pub use other_crate::Config`)
+    /// and are included in the resulting [`Idl`][].
+    ///
+    /// * `crate_name`, name of the crate in Rust code
+    /// * `crate_path`, path to include in output as the path to the crate, normally the manifest dir
+    /// * `rs_path`, path to the crate's `lib.rs`
+    pub fn with_extern_crate(
+        mut self,
+        crate_name: impl Into<Name>,
+        crate_path: impl Into<PathBuf>,
+        rs_path: impl Into<PathBuf>,
+    ) -> Self {
+        let extern_crate = ExternCrate {
+            crate_name: crate_name.into(),
+            crate_path: crate_path.into(),
+            features: None,
+        };
+        self.extern_crates.push((extern_crate, rs_path.into()));
+        self
+    }
+
+    /// Set the package-level metadata (version etc) to include in the resulting [`Idl`][].
+    pub fn with_crate_metadata(mut self, crate_metadata: CrateMetadata) -> Self {
+        self.crate_metadata = crate_metadata;
+        self
+    }
+
+    /// Cache the results of parsing in `cache_dir` (e.g., `target/gluegun/idl-cache`).
+    /// Each module whose source and whose view of the other modules are unchanged since
+    /// a previous parse is loaded from the cache rather than being parsed again.
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    /// Extract the code examples from the doc comments of functions
+    /// (see [`Function::examples`](`crate::Function::examples`)). Off by default.
+    pub fn with_doc_examples(mut self, doc_examples: bool) -> Self {
+        self.doc_examples = doc_examples;
+        self
+    }
+
+    /// Accept the third-party type at `path` (e.g., `chrono::DateTime`), which gluegun does not know,
+    /// as a [`TypeKind::Custom`](`crate::TypeKind::Custom`). Any generic arguments are ignored.
+    /// A type written with a single identifier (`DateTime`) matches the last segment of `path`.
+    pub fn with_custom_type(mut self, path: impl ToString) -> Self {
+        self.custom_types.push(path.to_string());
+        self
+    }
+
+    /// Set the enabled features of the crate `crate_name` (the main crate or an extern crate),
+    /// against which `#[cfg]` attributes on its items are evaluated: e.g., an item with
+    /// `#[cfg(feature = "serde")]` is only included if `serde` is among `features`.
+    /// For crates whose features are not given, items whose `#[cfg]` depends on a feature are ignored.
+    pub fn with_crate_features(
+        mut self,
+        crate_name: impl Into<Name>,
+        features: impl IntoIterator<Item = impl ToString>,
+    ) -> Self {
+        self.crate_features
+            .insert(crate_name.into(), features.into_iter().map(|feature| feature.to_string()).collect());
+        self
+    }
+
+    /// Set the cfg option `name`, or `name = "value"` if `value` is given (e.g., `unix` or `target_os = "linux"`),
+    /// against which `#[cfg]` attributes on the items of all crates are evaluated (features are set with
+    /// [`Self::with_crate_features`][]). Once any option is set, those that are not are disabled, as when compiling;
+    /// otherwise, items whose `#[cfg]` depends on an option are ignored.
+    pub fn with_cfg(mut self, name: impl ToString, value: Option<&str>) -> Self {
+        self.cfg_options
+            .get_or_insert_with(BTreeSet::new)
+            .insert((name.to_string(), value.map(str::to_string)));
+        self
+    }
+
+    /// Set the Rust edition of the crates, which determines how the paths of `use` items are resolved
+    /// (see [`Edition`][]). [`Edition::E2021`][] by default.
+    pub fn with_edition(mut self, edition: Edition) -> Self {
+        self.edition = edition;
+        self
+    }
+
+    /// Fail if a module is nested more than `max_depth` modules below the root of its crate (whose depth is 0),
+    /// e.g., because of a cycle of `#[path]` attributes. Unlimited by default.
+    pub fn with_max_module_depth(mut self, max_depth: usize) -> Self {
+        self.max_module_depth = Some(max_depth);
+        self
+    }
+
+    /// Include the public items tagged `#[doc(hidden)]`, which are otherwise ignored
+    /// like those tagged `#[gluegun::ignore]`, as they are usually internals that are public
+    /// for technical reasons (e.g., for the use of macros). Off by default.
+    pub fn with_doc_hidden(mut self, doc_hidden: bool) -> Self {
+        self.doc_hidden = doc_hidden;
+        self
+    }
+
+    /// Record the paths in the resulting [`Idl`][] (those of the crates and those of the source files in spans)
+    /// relative to `root` (e.g., the workspace root), with `/` as separator, so that the IDL is the same
+    /// wherever the sources are. Paths outside of `root` are recorded as given.
+    pub fn with_source_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.source_root = Some(root.into());
+        self
+    }
+
+    /// Rather than failing on the first definition or method that cannot be elaborated
+    /// (e.g., a function with an unsupported argument type), leave it out of the resulting [`Idl`][]
+    /// and carry on, so that all such problems can be reported at once (see [`Self::take_recovered_errors`][]).
+    /// Errors that prevent recognizing the definitions of a crate (e.g., a syntax error) still fail the parse.
+    /// Parse results are not cached while recovering from errors. Off by default.
+    pub fn with_error_recovery(mut self, error_recovery: bool) -> Self {
+        self.error_recovery = error_recovery;
+        self
+    }
+
+    /// Return the errors that were recovered from since the last call, in the order of the definitions they concern
+    /// (see [`Self::with_error_recovery`][]).
+    pub fn take_recovered_errors(&mut self) -> Vec<Error> {
+        std::mem::take(&mut self.recovered_errors)
+    }
+
     /// Parse the crate with the given name and the path to its `lib.rs`.
     /// 
     /// * `crate_name`, name of the crate in Rust code
@@ -23,20 +178,84 @@ impl Parser {
         rs_path: impl AsRef<Path>,
     ) -> crate::Result<Idl> {
         let crate_name: Name = crate_name.into();
-        let rs_path: &Path = rs_path.as_ref();
-        let arena = AstArena::default();
-        let ast = arena.parse_file(rs_path)?;
-        let crate_qname = QualifiedName::from(&crate_name);
-        let source = SourcePath::new(rs_path);
-        let recognized = pass1::Recognizer::new(&source, crate_qname, ast).into_recognized()?;
-        let elaborated = pass2::Elaborator::new(recognized).into_elaborated_items()?;
+        let extern_crate_names: Vec<Name> = self
+            .extern_crates
+            .iter()
+            .map(|(extern_crate, _)| extern_crate.crate_name.clone())
+            .collect();
+
+        // Read the modules of each crate. The definitions from all of them are recognized
+        // into one shared universe, so that names can be resolved across modules and crates.
+        let mut modules = vec![];
+        for (extern_crate, extern_rs_path) in &self.extern_crates {
+            modules.extend(modules::ModuleSource::read_crate(
+                &extern_crate.crate_name,
+                extern_rs_path,
+                true,
+                &self.cfg_set(&extern_crate.crate_name),
+                &extern_crate_names,
+                self,
+            )?);
+        }
+        modules.extend(modules::ModuleSource::read_crate(
+            &crate_name,
+            rs_path.as_ref(),
+            false,
+            &self.cfg_set(&crate_name),
+            &extern_crate_names,
+            self,
+        )?);
+
+        // Only the definitions that can be reached from outside of their crate are part of the interface,
+        // and they are named by the path they are reached by.
+        let (mut modules, scans): (Vec<_>, Vec<_>) = modules.into_iter().unzip();
+        let public_paths = reachability::PublicPaths::new(&scans);
+        for module in &mut modules {
+            module.public_paths = public_paths.in_module(&module.module_path);
+        }
+
+        let cache = self
+            .cache_dir
+            .as_ref()
+            .filter(|_| !self.error_recovery)
+            .map(|cache_dir| cache::ParseCache::new(cache_dir, &extern_crate_names));
+        let (mut elaborated, recovered_errors) =
+            modules::elaborate_modules(&modules, &extern_crate_names, &Arc::new(public_paths), cache.as_ref())?;
+        pod::mark_pod_records(&mut elaborated);
+        self.recovered_errors.extend(recovered_errors);
+        let crate_features = self.crate_features.get(&crate_name).cloned();
         Ok(Idl {
             crate_name,
-            crate_path: crate_path.into(),
+            crate_path: self.relative_path(&crate_path.into()),
+            crate_metadata: self.crate_metadata.clone(),
+            crate_features,
+            extern_crates: self
+                .extern_crates
+                .iter()
+                .map(|(extern_crate, _)| ExternCrate {
+                    crate_path: self.relative_path(&extern_crate.crate_path),
+                    features: self.crate_features.get(&extern_crate.crate_name).cloned(),
+                    ..extern_crate.clone()
+                })
+                .collect(),
             definitions: elaborated,
         })
     }
 
+    /// What the `#[cfg]` attributes of the crate `crate_name` are evaluated against.
+    fn cfg_set(&self, crate_name: &Name) -> cfg::CfgSet {
+        cfg::CfgSet {
+            features: self.crate_features.get(crate_name).cloned(),
+            options: self.cfg_options.clone(),
+        }
+    }
+
+    /// `path` as recorded in the resulting [`Idl`][] (see [`Self::with_source_root`][]).
+    fn relative_path(&self, path: &Path) -> PathBuf {
+        crate::span::relative_path(self.source_root.as_deref(), path)
+    }
+
+
     /// Convenient function to add the crate at `rs_path`, inferring the crate name,
     /// and then invoke [`Self::parse_crate_named`][].
     pub fn parse_crate(&mut self, crate_path: impl AsRef<Path>) -> crate::Result<Idl> {
@@ -46,6 +265,33 @@ impl Parser {
     }
 }
 
+/// The Rust edition of the parsed crates (see [`Parser::with_edition`]).
+/// In the 2015 edition, the paths of `use` items are relative to the root of the crate
+/// (e.g., `use shapes::Circle` in any module imports `crate::shapes::Circle`).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Edition {
+    E2015,
+    E2018,
+    #[default]
+    E2021,
+    E2024,
+}
+
+impl std::str::FromStr for Edition {
+    type Err = Error;
+
+    /// Parse an edition as written in `Cargo.toml` (e.g., `2021`).
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s {
+            "2015" => Ok(Edition::E2015),
+            "2018" => Ok(Edition::E2018),
+            "2021" => Ok(Edition::E2021),
+            "2024" => Ok(Edition::E2024),
+            _ => Err(Error::UnknownEdition(s.to_string())),
+        }
+    }
+}
+
 /// We deduce the crate name based on the directory.
 /// We expect `path` to be a `.rs` file found in some `src` directory;
 /// the parent of the src is the crate name.
@@ -87,19 +333,6 @@ fn extract_crate_name(rs_path: &Path) -> crate::Result<Name> {
     Ok(Name::try_from(crate_name)?)
 }
 
-#[derive(Default)]
-struct AstArena {
-    files: typed_arena::Arena<syn::File>,
-}
-
-impl AstArena {
-    fn parse_file(&self, path: &Path) -> crate::Result<&syn::File> {
-        let contents = std::fs::read_to_string(path)?;
-        let file = syn::parse_file(&contents)?;
-        Ok(self.files.alloc(file))
-    }
-}
-
 /// Internal intermediate structure representing some kind of public user-visible definition.
 struct Definition<'p> {
     /// The syn module from which this was parsed.
@@ -120,9 +353,14 @@ enum DefinitionKind<'p> {
     Resource(&'p syn::ItemStruct),
 
     /// *Records* are "struct-like" structures defined by their fields.
-    /// In Rust, they are represented by a struct with public fields and no `#[non_exhaustive]` attribute.
+    /// In Rust, they are represented by a struct with public fields and no `#[non_exhaustive]` attribute,
+    /// or by a struct without fields (e.g., `pub struct Marker;`).
     Record(&'p syn::ItemStruct),
 
+    /// *Newtypes* wrap a single value.
+    /// In Rust, they are represented by a tuple struct with a single public field.
+    Newtype(&'p syn::ItemStruct),
+
     /// *Variants* are "enum-like" structures with data-carrying fields.
     /// In Rust, they are represented by a non-C-like enum that is not marked with `#[non_exhaustive]`.
     Variant(&'p syn::ItemEnum, Vec<&'p syn::Variant>),
@@ -134,12 +372,62 @@ enum DefinitionKind<'p> {
     /// *Functions* are top-level, callable functions (!).
     Function(&'p syn::ItemFn),
 
-    /// *Modules* are public Rust modules; unlike the other variants, these are not mapped to output items,
+    /// *Interfaces* are sets of methods that foreign code can implement.
+    /// In Rust, they are represented by a public trait.
+    Interface(&'p syn::ItemTrait),
+
+    /// *Modules* are Rust modules declared with `mod name;` (including the root of an extern crate);
+    /// unlike the other variants, these are not mapped to output items,
     /// but they are used in name resolution.
-    #[expect(dead_code)]
     FileModule,
+
+    /// *Re-exports* are imports like `pub use crate::shapes::Circle` or `use other_crate::Config` that make a definition
+    /// available under another name. They are not mapped to output items
+    /// (the definition itself is, by its public path), but they are used in name resolution.
+    Reexport(QualifiedName),
+}
+
+impl Definition<'_> {
+    /// What other modules need to know about this definition to resolve names that refer to it.
+    fn export(&self) -> Export {
+        match &self.kind {
+            DefinitionKind::Resource(_)
+            | DefinitionKind::Record(_)
+            | DefinitionKind::Newtype(_)
+            | DefinitionKind::Variant(..)
+            | DefinitionKind::Enum(..) => Export::Type,
+            DefinitionKind::Function(_) => Export::Function,
+            DefinitionKind::Interface(_) => Export::Interface,
+            DefinitionKind::FileModule => Export::Module,
+            DefinitionKind::Reexport(target) => Export::Reexport(target.clone()),
+        }
+    }
+}
+
+/// Internal intermediate structure summarizing a [`Definition`][] for name resolution.
+/// Unlike definitions, exports do not reference the syn AST,
+/// so they can be shared between the threads elaborating each module and cached on disk.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+enum Export {
+    /// A resource, record, variant, or enum.
+    Type,
+
+    /// A function.
+    Function,
+
+    /// A trait, which can only be used as a trait object (e.g., `&dyn Trait`).
+    Interface,
+
+    /// A module.
+    Module,
+
+    /// A re-export of the given definition.
+    Reexport(QualifiedName),
 }
 
+/// Which definitions can be reached from outside of their crate, and by which path.
+mod reachability;
+
 /// Pass 1: Recognize types, imports, and things. Don't fill out the details (fields, methods).
 mod pass1;
 
@@ -150,4 +438,19 @@ mod known_rust;
 
 mod util;
 
-mod modifier;
\ No newline at end of file
+/// Evaluation of `#[cfg]` attributes.
+mod cfg;
+
+/// Heuristics for the auto traits (`Send`, `Sync`) of resources.
+mod auto_traits;
+
+/// Which records can be mirrored by `#[repr(C)]` structs.
+mod pod;
+
+mod modifier;
+
+/// Parse and elaborate each module on its own thread.
+mod modules;
+
+/// On-disk cache of elaborated modules.
+mod cache;
\ No newline at end of file
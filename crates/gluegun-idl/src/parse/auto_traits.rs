@@ -0,0 +1,93 @@
+use syn::visit::Visit;
+
+use crate::AutoTraits;
+
+/// Determine which auto traits the struct `item`, defined in `module`, implements.
+///
+/// We cannot run the trait solver, so this is a heuristic: the struct is assumed to be `Send`, `Sync`,
+/// and `Unpin` unless
+///
+/// * it is tagged `#[gluegun::not_threadsafe]`, which makes it neither `Send` nor `Sync`;
+/// * one of its fields names a standard type that is known not to be (e.g., `Rc`, `RefCell`, or a raw pointer).
+///
+/// Explicit `unsafe impl Send for Type {}` (or `Sync`) in `module` override the heuristic.
+pub(super) fn auto_traits(item: &syn::ItemStruct, module: &syn::File) -> AutoTraits {
+    let mut auto_traits = AutoTraits {
+        send: true,
+        sync: true,
+        unpin: true,
+    };
+
+    let mut visitor = FieldTypeVisitor {
+        auto_traits: &mut auto_traits,
+    };
+    for field in &item.fields {
+        visitor.visit_type(&field.ty);
+    }
+
+    if item.attrs.iter().any(|attr| is_not_threadsafe(attr.path())) {
+        auto_traits.send = false;
+        auto_traits.sync = false;
+    }
+
+    for impl_item in module.items.iter().filter_map(|module_item| match module_item {
+        syn::Item::Impl(impl_item) => Some(impl_item),
+        _ => None,
+    }) {
+        let Some((None, trait_path, _)) = &impl_item.trait_ else {
+            continue;
+        };
+        let syn::Type::Path(self_ty) = &*impl_item.self_ty else {
+            continue;
+        };
+        if impl_item.unsafety.is_none() || !self_ty.path.is_ident(&item.ident) {
+            continue;
+        }
+        match trait_path.segments.last().map(|segment| segment.ident.to_string()).as_deref() {
+            Some("Send") => auto_traits.send = true,
+            Some("Sync") => auto_traits.sync = true,
+            _ => {}
+        }
+    }
+
+    auto_traits
+}
+
+/// True for `gluegun::not_threadsafe` (or just `not_threadsafe`).
+fn is_not_threadsafe(path: &syn::Path) -> bool {
+    let segments: Vec<String> = path.segments.iter().map(|segment| segment.ident.to_string()).collect();
+    segments == ["not_threadsafe"] || segments == ["gluegun", "not_threadsafe"]
+}
+
+/// Clears the auto traits that the types it visits are known not to implement.
+struct FieldTypeVisitor<'a> {
+    auto_traits: &'a mut AutoTraits,
+}
+
+impl<'ast> Visit<'ast> for FieldTypeVisitor<'_> {
+    fn visit_type_ptr(&mut self, type_ptr: &'ast syn::TypePtr) {
+        self.auto_traits.send = false;
+        self.auto_traits.sync = false;
+        syn::visit::visit_type_ptr(self, type_ptr);
+    }
+
+    fn visit_path_segment(&mut self, segment: &'ast syn::PathSegment) {
+        match segment.ident.to_string().as_str() {
+            "Rc" | "NonNull" => {
+                self.auto_traits.send = false;
+                self.auto_traits.sync = false;
+            }
+            "MutexGuard" | "RwLockReadGuard" | "RwLockWriteGuard" => {
+                self.auto_traits.send = false;
+            }
+            "Cell" | "RefCell" | "UnsafeCell" | "OnceCell" | "Receiver" => {
+                self.auto_traits.sync = false;
+            }
+            "PhantomPinned" => {
+                self.auto_traits.unpin = false;
+            }
+            _ => {}
+        }
+        syn::visit::visit_path_segment(self, segment);
+    }
+}
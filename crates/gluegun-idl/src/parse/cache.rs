@@ -0,0 +1,92 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Name;
+
+use super::modules::{ElaboratedModule, ModuleSource};
+
+/// Version of the format of the cached modules, which is part of [`ParseCache::source_key`][].
+/// Bump it whenever pass 1 or the IR changes what an [`ElaboratedModule`][] holds for the same source,
+/// since the version of gluegun is not bumped along with it.
+const CACHE_SCHEMA_VERSION: u32 = 1;
+
+/// Caches each [`ElaboratedModule`][] in a JSON file named after the module,
+/// tagged with a hash of the module's source. Entries whose source no longer matches are ignored
+/// (and overwritten when the module is elaborated again).
+pub(super) struct ParseCache {
+    dir: PathBuf,
+    extern_crate_names: Vec<Name>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<M> {
+    /// See [`ParseCache::source_key`].
+    source_key: u64,
+
+    module: M,
+}
+
+impl ParseCache {
+    pub(super) fn new(dir: &Path, extern_crate_names: &[Name]) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            extern_crate_names: extern_crate_names.to_vec(),
+        }
+    }
+
+    /// Load the cached module, if any. Entries that are missing, stale, or unreadable
+    /// (e.g., written by another version of gluegun) are all treated as a cache miss.
+    pub(super) fn load(&self, module: &ModuleSource) -> Option<ElaboratedModule> {
+        let json = std::fs::read_to_string(self.entry_path(module)).ok()?;
+        let entry: CacheEntry<ElaboratedModule> = serde_json::from_str(&json).ok()?;
+        if entry.source_key != self.source_key(module) {
+            return None;
+        }
+        Some(entry.module)
+    }
+
+    pub(super) fn store(&self, module: &ModuleSource, elaborated: &ElaboratedModule) -> crate::Result<()> {
+        let entry = CacheEntry {
+            source_key: self.source_key(module),
+            module: elaborated,
+        };
+        let json = serde_json::to_string(&entry).map_err(std::io::Error::from)?;
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.entry_path(module), json)?;
+        Ok(())
+    }
+
+    fn entry_path(&self, module: &ModuleSource) -> PathBuf {
+        let key = hash(&(&module.module_path, module.source.path()));
+        self.dir.join(format!("{key:016x}.json"))
+    }
+
+    /// Hash of everything that pass 1 depends on (along with the version of gluegun that ran it
+    /// and [`CACHE_SCHEMA_VERSION`][]).
+    fn source_key(&self, module: &ModuleSource) -> u64 {
+        hash(&(
+            env!("CARGO_PKG_VERSION"),
+            CACHE_SCHEMA_VERSION,
+            &module.module_path,
+            module.source.path(),
+            &module.contents,
+            module.is_extern_crate,
+            module.doc_examples,
+            &module.custom_types,
+            &module.cfg,
+            module.edition,
+            module.doc_hidden,
+            &self.extern_crate_names,
+        ))
+    }
+}
+
+pub(super) fn hash(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
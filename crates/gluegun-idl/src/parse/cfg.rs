@@ -0,0 +1,87 @@
+use std::collections::BTreeSet;
+
+use syn::{punctuated::Punctuated, Token};
+
+/// What the `#[cfg]` attributes of a crate are evaluated against.
+#[derive(Clone, Debug, Default, Hash)]
+pub(super) struct CfgSet {
+    /// Enabled features of the crate, if known (see [`crate::Parser::with_crate_features`]).
+    pub(super) features: Option<Vec<String>>,
+
+    /// The other options that are set, with their value if any (e.g., `unix` or `target_os = "linux"`),
+    /// if known (see [`crate::Parser::with_cfg`]).
+    pub(super) options: Option<BTreeSet<(String, Option<String>)>>,
+}
+
+/// Returns true if the `#[cfg(...)]` attribute `attr` is enabled with `cfg`.
+///
+/// `feature = "x"` predicates are evaluated against the features of `cfg`, `test` (and `doc`, `doctest`) are false,
+/// other predicates against its options, and `all`, `any`, and `not` are combined as usual.
+/// Feature predicates when the features are not known, other predicates when the options are not known
+/// (e.g., `target_os = "linux"`), and predicates that cannot be parsed are unknown; if the attribute as a whole is unknown,
+/// it is considered disabled (i.e., the item is ignored).
+pub(super) fn cfg_enabled(attr: &syn::Attribute, cfg: &CfgSet) -> bool {
+    match attr.parse_args::<syn::Meta>() {
+        Ok(predicate) => evaluate(&predicate, cfg).unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+/// Evaluate `predicate`, returning `None` if its value is unknown.
+fn evaluate(predicate: &syn::Meta, cfg: &CfgSet) -> Option<bool> {
+    match predicate {
+        syn::Meta::Path(path) => {
+            if ["test", "doc", "doctest"].iter().any(|name| path.is_ident(name)) {
+                Some(false)
+            } else {
+                let name = path.get_ident()?.to_string();
+                cfg.options.as_ref().map(|options| options.contains(&(name, None)))
+            }
+        }
+
+        syn::Meta::NameValue(name_value) => {
+            let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(value),
+                ..
+            }) = &name_value.value
+            else {
+                return None;
+            };
+            if name_value.path.is_ident("feature") {
+                return cfg.features.as_ref().map(|features| features.contains(&value.value()));
+            }
+            let name = name_value.path.get_ident()?.to_string();
+            cfg.options.as_ref().map(|options| options.contains(&(name, Some(value.value()))))
+        }
+
+        syn::Meta::List(list) => {
+            let predicates = list
+                .parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+                .ok()?;
+            let values: Vec<Option<bool>> = predicates.iter().map(|predicate| evaluate(predicate, cfg)).collect();
+            if list.path.is_ident("all") {
+                // False if any predicate is false, even if others are unknown.
+                combine(&values, false)
+            } else if list.path.is_ident("any") {
+                // True if any predicate is true, even if others are unknown.
+                combine(&values, true)
+            } else if list.path.is_ident("not") && predicates.len() == 1 {
+                evaluate(&predicates[0], cfg).map(|value| !value)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Combine the `values` of the predicates of an `all` (`decisive = false`) or `any` (`decisive = true`):
+/// the result is `decisive` if any value is, unknown if any value is unknown, and `!decisive` otherwise.
+fn combine(values: &[Option<bool>], decisive: bool) -> Option<bool> {
+    if values.contains(&Some(decisive)) {
+        Some(decisive)
+    } else if values.iter().all(Option::is_some) {
+        Some(!decisive)
+    } else {
+        None
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::BTreeMap;
 
-use crate::{AutoTraits, Error, Name, RefdTy, Scalar, Span, StringRepr, Ty, TypeKind};
+use crate::{AutoTraits, Error, Name, NetAddrRepr, RefdTy, Scalar, Span, StringRepr, TimestampRepr, Ty, TypeKind};
 
 use super::modifier::Modifier;
 
@@ -58,31 +58,31 @@ macro_rules! known_rust_types {
                         }
 
                         // Extract the arguments, erroring if the number provided doesn't match expectations
-                        #[allow(unused_mut)]
-                        let mut expected_args = 0;
+                        // (at the first extra argument, if there are too many).
+                        let expected_tys: &[&str] = &[$(stringify!($ty)),*];
+                        if tys.len() != expected_tys.len() {
+                            let span = tys.get(expected_tys.len()).map_or(span, |extra| extra.span().clone());
+                            return Err($crate::Error::UnsupportedNumberOfArguments(span, expected_tys.len(), tys.len()));
+                        }
+                        #[allow(unused_mut, unused_variables)]
+                        let mut args = tys.iter().cloned();
                         $(
-                            let Some($ty) = tys.get(expected_args).cloned() else {
-                                return Err($crate::Error::UnsupportedUseOfType(span));
-                            };
-                            expected_args += 1;
+                            let $ty = args.next().unwrap();
                         )*
-                        if tys.len() != expected_args {
-                            return Err($crate::Error::UnsupportedUseOfType(span.clone()));
-                        }
 
-                        // Same for bindings.
-                        #[allow(unused_mut)]
-                        let mut expected_bindings = 0;
+                        // Same for bindings, erroring at the type of an unexpected binding.
+                        let expected_bindings: &[&str] = &[$(stringify!($binding)),*];
+                        if let Some((_, unexpected)) =
+                            bindings.iter().find(|(name, _)| !expected_bindings.contains(&name.text.as_str()))
+                        {
+                            return Err($crate::Error::BindingNotExpected(unexpected.span().clone()));
+                        }
                         $(
                             let binding_name = $crate::Name::from(stringify!($binding));
                             let Some($tyb) = bindings.get(&binding_name).cloned() else {
                                 return Err(Error::BindingNotFound(span.clone(), binding_name));
                             };
-                            expected_bindings += 1;
                         )*
-                        if bindings.len() != expected_bindings {
-                            return Err($crate::Error::UnsupportedUseOfType(span.clone()));
-                        }
 
                         // Assign span to the user's variable
                         let $s = span;
@@ -111,13 +111,28 @@ pub(super) const KNOWN_RUST_TYPES: &[KnownRustType] = known_rust_types! {
     [Modifier::Ref(r)] str[][] @ span => TypeKind::String { repr: StringRepr::StrRef }.refd(span, r),
 
     [] std::vec::Vec[element][] @ span => TypeKind::Vec { element, repr: crate::VecRepr::Vec, }.not_refd(span),
-    [] std::collections::HashMap[key, value][] @ span =>TypeKind::Map { key, value, repr: crate::MapSetRepr::BTree }.not_refd(span),
+    [] std::collections::HashMap[key, value][] @ span => TypeKind::Map { key, value, repr: crate::MapSetRepr::Hash }.not_refd(span),
     [] std::collections::BTreeMap[key, value][] @ span => TypeKind::Map { key, value, repr: crate::MapSetRepr::BTree }.not_refd(span),
-    [] std::collections::HashSet[element][] @ span =>TypeKind::Set { element, repr: crate::MapSetRepr::BTree }.not_refd(span),
+    [] indexmap::IndexMap[key, value][] @ span => TypeKind::Map { key, value, repr: crate::MapSetRepr::Index }.not_refd(span),
+    [] std::collections::HashSet[element][] @ span => TypeKind::Set { element, repr: crate::MapSetRepr::Hash }.not_refd(span),
     [] std::collections::BTreeSet[element][] @ span => TypeKind::Set { element, repr: crate::MapSetRepr::BTree }.not_refd(span),
+    [] indexmap::IndexSet[element][] @ span => TypeKind::Set { element, repr: crate::MapSetRepr::Index }.not_refd(span),
     [Modifier::Ref(r)] std::path::Path[][] @ span => TypeKind::Path { repr: crate::PathRepr::PathRef }.refd(span, r),
     [] std::path::PathBuf[][] @ span => TypeKind::Path { repr: crate::PathRepr::PathBuf }.not_refd(span),
-
+    [] std::net::IpAddr[][] @ span => TypeKind::NetAddr { repr: NetAddrRepr::IpAddr }.not_refd(span),
+    [] std::net::Ipv4Addr[][] @ span => TypeKind::NetAddr { repr: NetAddrRepr::Ipv4Addr }.not_refd(span),
+    [] std::net::Ipv6Addr[][] @ span => TypeKind::NetAddr { repr: NetAddrRepr::Ipv6Addr }.not_refd(span),
+    [] std::net::SocketAddr[][] @ span => TypeKind::NetAddr { repr: NetAddrRepr::SocketAddr }.not_refd(span),
+    [] std::net::SocketAddrV4[][] @ span => TypeKind::NetAddr { repr: NetAddrRepr::SocketAddrV4 }.not_refd(span),
+    [] std::net::SocketAddrV6[][] @ span => TypeKind::NetAddr { repr: NetAddrRepr::SocketAddrV6 }.not_refd(span),
+    [] uuid::Uuid[][] @ span => TypeKind::Uuid { repr: crate::UuidRepr::Uuid }.not_refd(span),
+    [] chrono::NaiveDate[][] @ span => TypeKind::Timestamp { repr: TimestampRepr::ChronoNaiveDate }.not_refd(span),
+    [] time::OffsetDateTime[][] @ span => TypeKind::Timestamp { repr: TimestampRepr::TimeOffsetDateTime }.not_refd(span),
+    // `chrono::DateTime<Utc>` is matched by `Elaborator::elaborate_chrono_date_time`, as `Utc` is not a type we know.
+
+    [] bool[][] @ span => TypeKind::Scalar(Scalar::Boolean).not_refd(span),
+    [] char[][] @ span => TypeKind::Scalar(Scalar::Char).not_refd(span),
+    [] u8[][] @ span => TypeKind::Scalar(Scalar::U8).not_refd(span),
     [] u16[][] @ span => TypeKind::Scalar(Scalar::U16).not_refd(span),
     [] u32[][] @ span => TypeKind::Scalar(Scalar::U32).not_refd(span),
     [] u64[][] @ span => TypeKind::Scalar(Scalar::U64).not_refd(span),
@@ -136,6 +151,12 @@ pub(super) const KNOWN_RUST_TYPES: &[KnownRustType] = known_rust_types! {
 pub(super) const KNOWN_RUST_IMPL_TRAIT_TYPES: &[KnownRustType] = known_rust_types! {
     [] std::string::ToString[][] @ span => TypeKind::String { repr: StringRepr::ImplToString }.not_refd(span),
     [] std::task::Future[][Output = output] @ span => TypeKind::Future { output, repr: crate::FutureRepr::ImplFuture(AutoTraits::default()) }.not_refd(span),
+    [] std::iter::IntoIterator[][Item = element] @ span => TypeKind::Vec { element, repr: crate::VecRepr::ImplIntoIterator }.not_refd(span),
+    // The auto traits of streams (e.g., `+ Send`) are filled in by `Elaborator::elaborate_impl_trait_ty`.
+    [] futures::Stream[][Item = item] @ span => TypeKind::Stream { item, repr: crate::StreamRepr::ImplStream(AutoTraits::default()) }.not_refd(span),
+    [] futures::stream::Stream[][Item = item] @ span => TypeKind::Stream { item, repr: crate::StreamRepr::ImplStream(AutoTraits::default()) }.not_refd(span),
+    [] futures_core::Stream[][Item = item] @ span => TypeKind::Stream { item, repr: crate::StreamRepr::ImplStream(AutoTraits::default()) }.not_refd(span),
+    [] futures_core::stream::Stream[][Item = item] @ span => TypeKind::Stream { item, repr: crate::StreamRepr::ImplStream(AutoTraits::default()) }.not_refd(span),
 
     ---
     
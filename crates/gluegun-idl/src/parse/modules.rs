@@ -0,0 +1,330 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::{mpsc, Arc},
+    thread::ScopedJoinHandle,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::{Edition, Item, ModulePath, Name, QualifiedName, SourcePath};
+
+use super::{
+    cache::ParseCache,
+    cfg::CfgSet,
+    pass1, pass2,
+    reachability::{ModuleScan, PublicPaths},
+    Definition, DefinitionKind, Export,
+};
+
+/// The source of a module to parse.
+pub(super) struct ModuleSource {
+    /// Path of the module (e.g., `my_crate` for the root of a crate, or `my_crate::shapes`).
+    pub(super) module_path: ModulePath,
+
+    /// Path the module was read from, as recorded in spans (see [`crate::Parser::with_source_root`]).
+    pub(super) source: SourcePath,
+
+    /// Contents of the module's `.rs` file.
+    pub(super) contents: String,
+
+    /// True if the module is the root of an extern crate.
+    pub(super) is_extern_crate: bool,
+
+    /// True if code examples are to be extracted from doc comments.
+    pub(super) doc_examples: bool,
+
+    /// Paths of the third-party types to accept (see [`crate::Parser::with_custom_type`]).
+    pub(super) custom_types: Vec<String>,
+
+    /// What the `#[cfg]` attributes of the crate are evaluated against
+    /// (see [`crate::Parser::with_crate_features`] and [`crate::Parser::with_cfg`]).
+    pub(super) cfg: CfgSet,
+
+    /// The edition of the crate (see [`crate::Parser::with_edition`]).
+    pub(super) edition: Edition,
+
+    /// True if items tagged `#[doc(hidden)]` are to be included (see [`crate::Parser::with_doc_hidden`]).
+    pub(super) doc_hidden: bool,
+
+    /// True if errors in definitions are to be recorded rather than failing (see [`crate::Parser::with_error_recovery`]).
+    pub(super) error_recovery: bool,
+
+    /// The public paths of the definitions of the module that are reachable from outside of the crate,
+    /// keyed by the path where they are defined (see [`PublicPaths`]); the others are not recognized.
+    pub(super) public_paths: BTreeMap<QualifiedName, QualifiedName>,
+}
+
+impl ModuleSource {
+    /// Read the crate whose root module `crate_name` is at `rs_path`, along with the modules it declares
+    /// with `mod name;` (in `name.rs` or `name/mod.rs`, or in the file given by a `#[path]` attribute),
+    /// to be parsed with the options of `parser`. Fails if modules are nested deeper than [`crate::Parser::with_max_module_depth`] allows.
+    /// Returns each module along with what it declares (see [`ModuleScan`]), from which the public paths are found.
+    pub(super) fn read_crate(
+        crate_name: &Name,
+        rs_path: &Path,
+        is_extern_crate: bool,
+        cfg: &CfgSet,
+        extern_crate_names: &[Name],
+        parser: &crate::Parser,
+    ) -> crate::Result<Vec<(Self, ModuleScan)>> {
+        let mut modules = vec![];
+        let mut pending = vec![(ModulePath::of_crate(crate_name), rs_path.to_path_buf(), true)];
+        while let Some((module_path, rs_path, is_mod_rs)) = pending.pop() {
+            let module = Self {
+                module_path,
+                source: SourcePath::new(parser.relative_path(&rs_path)),
+                contents: std::fs::read_to_string(&rs_path)?,
+                is_extern_crate: is_extern_crate && modules.is_empty(),
+                doc_examples: parser.doc_examples,
+                custom_types: parser.custom_types.clone(),
+                cfg: cfg.clone(),
+                edition: parser.edition,
+                doc_hidden: parser.doc_hidden,
+                error_recovery: parser.error_recovery,
+                public_paths: BTreeMap::new(),
+            };
+            let ast = syn::parse_file(&module.contents)?;
+            let scan = ModuleScan::new(
+                &module.module_path,
+                &module.source,
+                &ast,
+                extern_crate_names,
+                cfg,
+                parser.doc_hidden,
+                parser.edition,
+            );
+
+            // Like rustc, look for submodules next to a crate root or a `mod.rs`, and in a directory named after other modules.
+            let dir = rs_path.parent().unwrap_or(Path::new(""));
+            let submodule_dir = if is_mod_rs {
+                dir.to_path_buf()
+            } else {
+                dir.join(rs_path.file_stem().unwrap_or_default())
+            };
+            for submodule in scan.submodules.iter().rev() {
+                let module_path = ModulePath::named(module.module_path.join(submodule.name.clone()));
+                if let Some(max_depth) = parser.max_module_depth {
+                    // The depth of the crate root is 0.
+                    if module_path.qname().names.len() - 1 > max_depth {
+                        return Err(crate::Error::ModuleTooDeep(submodule.span.clone(), max_depth));
+                    }
+                }
+                let candidates = match &submodule.path_attr {
+                    Some(path) => vec![(dir.join(path), true)],
+                    None => vec![
+                        (submodule_dir.join(format!("{}.rs", submodule.name.text)), false),
+                        (submodule_dir.join(&submodule.name.text).join("mod.rs"), true),
+                    ],
+                };
+                let Some((rs_path, is_mod_rs)) = candidates.into_iter().find(|(path, _)| path.is_file()) else {
+                    return Err(crate::Error::ModuleNotFound(submodule.span.clone()));
+                };
+                pending.push((module_path, rs_path, is_mod_rs));
+            }
+
+            modules.push((module, scan));
+        }
+        Ok(modules)
+    }
+}
+
+/// The result of parsing and elaborating a module.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub(super) struct ElaboratedModule {
+    /// The module's exports, which other modules use to resolve names.
+    #[serde_as(as = "Vec<(_, _)>")]
+    pub(super) exports: BTreeMap<QualifiedName, Export>,
+
+    /// Hash of the exports of all modules and of the public paths at the time the module was elaborated
+    /// (see [`universe_key`]); the items are only valid for the same exports and public paths.
+    pub(super) universe_key: u64,
+
+    /// The elaborated items.
+    #[serde_as(as = "Vec<(_, _)>")]
+    pub(super) items: BTreeMap<QualifiedName, Item>,
+}
+
+/// Parse and elaborate `modules`, returning the items from all of them, named by their `public_paths`,
+/// along with the errors that were recovered from (see [`ModuleSource::error_recovery`]).
+///
+/// Modules found in `cache` are not parsed again. The others are parsed and elaborated in parallel,
+/// one thread per module; each thread recognizes the definitions in its module (pass 1)
+/// and then waits for the exports of all modules before elaborating them (pass 2).
+pub(super) fn elaborate_modules(
+    modules: &[ModuleSource],
+    extern_crate_names: &[Name],
+    public_paths: &Arc<PublicPaths>,
+    cache: Option<&ParseCache>,
+) -> crate::Result<(BTreeMap<QualifiedName, Item>, Vec<crate::Error>)> {
+    let elaborated = std::thread::scope(|scope| -> crate::Result<Vec<(ElaboratedModule, Vec<crate::Error>, bool)>> {
+        // Spawn a thread for each module missing from the cache; it sends back its exports
+        // and then waits for the universe of exports from all modules.
+        let mut states: Vec<ModuleState<'_>> = modules
+            .iter()
+            .map(|module| match cache.and_then(|cache| cache.load(module)) {
+                Some(cached) => ModuleState::Cached(cached),
+                None => {
+                    let (exports_tx, exports_rx) = mpsc::channel();
+                    let (universe_tx, universe_rx) = mpsc::channel();
+                    let public_paths = public_paths.clone();
+                    let handle = scope.spawn(move || {
+                        elaborate_module(module, extern_crate_names, public_paths, |exports| {
+                            exports_tx.send(exports.clone()).ok()?;
+                            universe_rx.recv().ok().flatten()
+                        })
+                    });
+                    ModuleState::Recognizing { exports_rx, universe_tx, handle }
+                }
+            })
+            .collect();
+
+        // Gather the exports of all modules. A thread that fails in pass 1
+        // drops its sender without sending anything; we then report its error.
+        let exports: Option<Vec<_>> = states
+            .iter()
+            .map(|state| match state {
+                ModuleState::Cached(cached) => Some(cached.exports.clone()),
+                ModuleState::Recognizing { exports_rx, .. } => exports_rx.recv().ok(),
+                ModuleState::Elaborating(_) => unreachable!("no threads spawned after pass 1 yet"),
+            })
+            .collect();
+        let Some(exports) = exports else {
+            return Err(first_error(states));
+        };
+        let universe: BTreeMap<_, _> = exports.into_iter().flatten().collect();
+        let universe_key = universe_key(&universe, public_paths);
+        let universe = Arc::new(universe);
+
+        for (module, state) in modules.iter().zip(&mut states) {
+            match state {
+                ModuleState::Cached(cached) if cached.universe_key != universe_key => {
+                    // The module was elaborated against different exports, so elaborate it again.
+                    let universe = universe.clone();
+                    let public_paths = public_paths.clone();
+                    let handle = scope.spawn(move || {
+                        elaborate_module(module, extern_crate_names, public_paths, |_| Some(universe))
+                    });
+                    *state = ModuleState::Elaborating(handle);
+                }
+                ModuleState::Cached(_) | ModuleState::Elaborating(_) => {}
+                ModuleState::Recognizing { universe_tx, .. } => {
+                    universe_tx
+                        .send(Some(universe.clone()))
+                        .expect("thread waits for the universe");
+                }
+            }
+        }
+
+        states
+            .into_iter()
+            .map(|state| match state {
+                ModuleState::Cached(cached) => Ok((cached, vec![], false)),
+                ModuleState::Recognizing { handle, .. } | ModuleState::Elaborating(handle) => {
+                    let (exports, items, errors) = join(handle)?.expect("universe was sent to all threads");
+                    Ok((ElaboratedModule { exports, universe_key, items }, errors, true))
+                }
+            })
+            .collect()
+    })?;
+
+    let mut items = BTreeMap::new();
+    let mut errors = vec![];
+    for (module, (elaborated, module_errors, fresh)) in modules.iter().zip(elaborated) {
+        if let Some(cache) = cache.filter(|_| fresh) {
+            cache.store(module, &elaborated)?;
+        }
+        items.extend(elaborated.items);
+        errors.extend(module_errors);
+    }
+    Ok((items, errors))
+}
+
+/// The progress of parsing and elaborating a module.
+enum ModuleState<'scope> {
+    /// The module was loaded from the cache.
+    Cached(ElaboratedModule),
+
+    /// A thread is recognizing the module's definitions; it sends back the module's exports
+    /// and waits to receive the universe of exports (or `None` if some other thread failed).
+    Recognizing {
+        exports_rx: mpsc::Receiver<BTreeMap<QualifiedName, Export>>,
+        universe_tx: mpsc::Sender<Option<Arc<BTreeMap<QualifiedName, Export>>>>,
+        handle: ModuleThread<'scope>,
+    },
+
+    /// A thread is elaborating the module.
+    Elaborating(ModuleThread<'scope>),
+}
+
+type ModuleThread<'scope> = ScopedJoinHandle<'scope, crate::Result<Option<ModuleOutput>>>;
+
+/// The exports and elaborated items of a module, and the errors recovered from while elaborating them.
+type ModuleOutput = (BTreeMap<QualifiedName, Export>, BTreeMap<QualifiedName, Item>, Vec<crate::Error>);
+
+/// Called when some thread failed in pass 1: tells the other threads to stop
+/// and returns the error from the first failing module.
+fn first_error(states: Vec<ModuleState<'_>>) -> crate::Error {
+    let mut first_error = None;
+    for state in states {
+        if let ModuleState::Recognizing { universe_tx, handle, .. } = state {
+            let _ = universe_tx.send(None);
+            if let Err(error) = join(handle) {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+    first_error.expect("some thread failed")
+}
+
+fn join<T>(handle: ScopedJoinHandle<'_, T>) -> T {
+    match handle.join() {
+        Ok(result) => result,
+        Err(panic) => std::panic::resume_unwind(panic),
+    }
+}
+
+/// Parse `module`, recognize its definitions, and then elaborate them
+/// against the universe of exports returned by `universe`, naming them by their `public_paths`.
+/// Returns `Ok(None)` if `universe` returns `None`, because elaborating some other module failed.
+fn elaborate_module(
+    module: &ModuleSource,
+    extern_crate_names: &[Name],
+    public_paths: Arc<PublicPaths>,
+    universe: impl FnOnce(&BTreeMap<QualifiedName, Export>) -> Option<Arc<BTreeMap<QualifiedName, Export>>>,
+) -> crate::Result<Option<ModuleOutput>> {
+    let ast = syn::parse_file(&module.contents)?;
+
+    let mut recognized = BTreeMap::new();
+    if module.is_extern_crate {
+        recognized.insert(
+            module.module_path.qname().clone(),
+            Definition {
+                module: &ast,
+                source: module.source.clone(),
+                kind: DefinitionKind::FileModule,
+            },
+        );
+    }
+    let (definitions, mut errors) = pass1::Recognizer::new(module, &ast, extern_crate_names).into_recognized()?;
+    recognized.extend(definitions);
+
+    let exports: BTreeMap<QualifiedName, Export> = recognized
+        .iter()
+        .map(|(qname, definition)| (qname.clone(), definition.export()))
+        .collect();
+    let Some(universe) = universe(&exports) else {
+        return Ok(None);
+    };
+
+    let (items, pass2_errors) = pass2::Elaborator::new(module, recognized, universe, public_paths).into_elaborated_items()?;
+    errors.extend(pass2_errors);
+    Ok(Some((exports, items, errors)))
+}
+
+/// Hash identifying a universe of exports, along with the public paths of the definitions.
+fn universe_key(universe: &BTreeMap<QualifiedName, Export>, public_paths: &PublicPaths) -> u64 {
+    super::cache::hash(&(universe, public_paths))
+}
@@ -1,28 +1,51 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::collections::BTreeMap;
 
 use syn::spanned::Spanned;
 
-use crate::{Error, Span, QualifiedName, SourcePath};
+use crate::{Edition, Error, ModulePath, Name, Span, QualifiedName, SourcePath};
 
-use super::{util, Definition, DefinitionKind};
+use super::{cfg::CfgSet, modules::ModuleSource, util, Definition, DefinitionKind};
 
 pub(super) struct Recognizer<'ast> {
     source: SourcePath,
-    module_name: QualifiedName,
+    module_path: ModulePath,
     ast: &'ast syn::File,
+    extern_crates: Vec<Name>,
+
+    /// The edition of the crate, which determines how `use` paths are resolved.
+    edition: Edition,
+
+    /// What `#[cfg]` attributes are evaluated against.
+    cfg: CfgSet,
+
+    /// If true, public items tagged `#[doc(hidden)]` are not ignored (see [`crate::Parser::with_doc_hidden`]).
+    doc_hidden: bool,
+
+    /// If true, items that fail to be recognized are left out and their errors
+    /// recorded in `recovered_errors` (see [`crate::Parser::with_error_recovery`]).
+    error_recovery: bool,
+    recovered_errors: Vec<Error>,
+
+    /// The public paths of the definitions of the module that are reachable from outside of the crate
+    /// (see [`super::reachability::PublicPaths`]); the others are not recognized.
+    public_paths: BTreeMap<QualifiedName, QualifiedName>,
+
     recognized: BTreeMap<QualifiedName, Definition<'ast>>,
 }
 
 impl<'ast> Recognizer<'ast> {
-    pub(super) fn new(
-        source: &SourcePath,
-        module_name: QualifiedName,
-        ast: &'ast syn::File,
-    ) -> Self {
+    pub(super) fn new(module: &ModuleSource, ast: &'ast syn::File, extern_crates: &[Name]) -> Self {
         Self {
-            source: source.clone(),
-            module_name,
+            source: module.source.clone(),
+            module_path: module.module_path.clone(),
             ast,
+            extern_crates: extern_crates.to_vec(),
+            edition: module.edition,
+            cfg: module.cfg.clone(),
+            doc_hidden: module.doc_hidden,
+            error_recovery: module.error_recovery,
+            recovered_errors: vec![],
+            public_paths: module.public_paths.clone(),
             recognized: BTreeMap::new(),
         }
     }
@@ -39,11 +62,23 @@ impl<'ast> Recognizer<'ast> {
         variant(self.source.span(spanned))
     }
 
-    pub(super) fn into_recognized(mut self) -> crate::Result<Arc<BTreeMap<QualifiedName, Definition<'ast>>>> {
+    /// True if the public item `ident` cannot be reached from outside of the crate (e.g., it is in a private module),
+    /// so that it is not part of the interface.
+    fn unreachable(&self, ident: &syn::Ident) -> bool {
+        !self.public_paths.contains_key(&self.module_path.join(util::recognize_name(ident)))
+    }
+
+    /// Recognize the items of the module, returning the definitions
+    /// and the errors recovered from (which is empty unless error recovery is enabled).
+    pub(super) fn into_recognized(mut self) -> crate::Result<(BTreeMap<QualifiedName, Definition<'ast>>, Vec<Error>)> {
         for item in &self.ast.items {
-            self.recognize_item(item)?;
+            match self.recognize_item(item) {
+                Ok(()) => {}
+                Err(error) if self.error_recovery => self.recovered_errors.push(error),
+                Err(error) => return Err(error),
+            }
         }
-        Ok(Arc::new(self.recognized))
+        Ok((self.recognized, self.recovered_errors))
     }
 
     fn recognize_item(&mut self, item: &'ast syn::Item) -> crate::Result<()> {
@@ -65,17 +100,23 @@ impl<'ast> Recognizer<'ast> {
             // Ignore impls
             syn::Item::Impl(_item) => Ok(()),
 
-            _ => Err(self.error(crate::Error::UnsupportedItem, item)),
+            // Other items (e.g., constants) are only rejected in the root of the crate;
+            // those of other modules are only part of the interface if they are re-exported there.
+            _ if self.module_path.names().len() == 1 => Err(self.error(crate::Error::UnsupportedItem, item)),
+            _ => Ok(()),
         }
     }
 
     fn recognize_struct(&mut self, item: &'ast syn::ItemStruct) -> crate::Result<()> {
-        if util::ignore(&item.vis, &item.attrs) {
+        if util::ignore(&item.vis, &item.attrs, &self.cfg, self.doc_hidden) {
+            return Ok(());
+        }
+        if self.unreachable(&item.ident) {
             return Ok(());
         }
 
         let name = util::recognize_name(&item.ident);
-        let qname = self.module_name.join(&name);
+        let qname = self.module_path.join(&name);
 
         if item.generics.params.len() > 0 {
             return Err(self.error(Error::GenericsNotPermitted, &item.generics));
@@ -87,7 +128,38 @@ impl<'ast> Recognizer<'ast> {
             .filter(|field| util::is_public(&field.vis))
             .count();
 
-        if public_fields > 0 && public_fields == item.fields.len() {
+        let marshal_as = util::marshal_as(&item.attrs).map_err(|attr| self.error(Error::InvalidMarshalAs, attr))?;
+
+        if marshal_as.is_some() {
+            // Passed as the type named by the attribute, converting with `TryFrom` and `From`, whatever its fields.
+            self.recognized.insert(
+                qname,
+                self.definition(DefinitionKind::Newtype(item)),
+            );
+            Ok(())
+        } else if util::is_opaque(&item.attrs) {
+            // Passed by handle regardless of its fields: this is a class, with getters for the public fields.
+            self.recognized.insert(
+                qname,
+                self.definition(DefinitionKind::Resource(item)),
+            );
+            Ok(())
+        } else if public_fields == 1 && matches!(item.fields, syn::Fields::Unnamed(_)) && item.fields.len() == 1 {
+            // A single public field in a tuple struct: this is a newtype.
+            self.recognized.insert(
+                qname,
+                self.definition(DefinitionKind::Newtype(item)),
+            );
+            Ok(())
+        } else if item.fields.is_empty() {
+            // No fields at all (e.g., the unit struct `pub struct Marker;`): this is a record without fields,
+            // which backends map to a marker class. Tag it `#[gluegun::opaque]` to pass it by handle instead.
+            self.recognized.insert(
+                qname,
+                self.definition(DefinitionKind::Record(item)),
+            );
+            Ok(())
+        } else if public_fields > 0 && public_fields == item.fields.len() {
             // All public fields: this is a struct.
             //
             // It can have methods, but they have to be `&self` or `self`.
@@ -111,7 +183,10 @@ impl<'ast> Recognizer<'ast> {
     }
 
     fn recognize_enum(&mut self, item: &'ast syn::ItemEnum) -> crate::Result<()> {
-        if util::ignore(&item.vis, &item.attrs) {
+        if util::ignore(&item.vis, &item.attrs, &self.cfg, self.doc_hidden) {
+            return Ok(());
+        }
+        if self.unreachable(&item.ident) {
             return Ok(());
         }
 
@@ -122,7 +197,7 @@ impl<'ast> Recognizer<'ast> {
         let unignored_variants = item
             .variants
             .iter()
-            .filter(|variant| !util::ignore_from_attrs(&variant.attrs))
+            .filter(|variant| !util::ignore_from_attrs(&variant.attrs, &self.cfg))
             .collect::<Vec<_>>();
 
         let variants_have_args = unignored_variants.iter().any(|v| match &v.fields {
@@ -131,7 +206,7 @@ impl<'ast> Recognizer<'ast> {
         });
 
         let name = util::recognize_name(&item.ident);
-        let qname = self.module_name.join(&name);
+        let qname = self.module_path.join(&name);
 
         if variants_have_args {
             self.recognized.insert(
@@ -149,14 +224,17 @@ impl<'ast> Recognizer<'ast> {
     }
 
     fn recognize_fn(&mut self, item: &'ast syn::ItemFn) -> crate::Result<()> {
-        if util::ignore(&item.vis, &item.attrs) {
+        if util::ignore(&item.vis, &item.attrs, &self.cfg, self.doc_hidden) {
+            return Ok(());
+        }
+        if self.unreachable(&item.sig.ident) {
             return Ok(());
         }
 
         let name = util::recognize_name(&item.sig.ident);
-        let qname = self.module_name.join(&name);
+        let qname = self.module_path.join(&name);
 
-        if item.sig.generics.params.len() > 0 {
+        if !util::lifetimes_only(&item.sig.generics) {
             return Err(self.error(Error::GenericsNotPermitted, &item.sig.generics));
         }
 
@@ -167,35 +245,89 @@ impl<'ast> Recognizer<'ast> {
         Ok(())
     }
 
-    fn recognize_mod(&self, item: &syn::ItemMod) -> Result<(), Error> {
-        if util::ignore(&item.vis, &item.attrs) {
+    fn recognize_mod(&mut self, item: &syn::ItemMod) -> Result<(), Error> {
+        if util::cfg_disabled(&item.attrs, &self.cfg) {
+            return Ok(());
+        }
+
+        if item.content.is_none() {
+            // A module in another file, which is parsed on its own (see `ModuleSource::read_crate`).
+            // Private modules are recorded too, as their definitions may be re-exported.
+            let name = util::recognize_name(&item.ident);
+            self.recognized.insert(
+                self.module_path.join(name),
+                self.definition(DefinitionKind::FileModule),
+            );
+            return Ok(());
+        }
+
+        if util::ignore(&item.vis, &item.attrs, &self.cfg, self.doc_hidden) || self.unreachable(&item.ident) {
             return Ok(());
         }
 
         Err(self.error(crate::Error::UnsupportedItem, item))
     }
 
-    fn recognize_trait(&self, item: &syn::ItemTrait) -> Result<(), Error> {
-        if util::ignore(&item.vis, &item.attrs) {
+    fn recognize_trait(&mut self, item: &'ast syn::ItemTrait) -> Result<(), Error> {
+        if util::ignore(&item.vis, &item.attrs, &self.cfg, self.doc_hidden) {
+            return Ok(());
+        }
+        if self.unreachable(&item.ident) {
             return Ok(());
         }
 
-        Err(self.error(crate::Error::UnsupportedItem, item))
+        if !item.generics.params.is_empty() {
+            return Err(self.error(Error::GenericsNotPermitted, &item.generics));
+        }
+
+        if let Some(unsafety) = &item.unsafety {
+            return Err(self.error(Error::UnsupportedItem, unsafety));
+        }
+
+        let name = util::recognize_name(&item.ident);
+        let qname = self.module_path.join(&name);
+        self.recognized.insert(
+            qname,
+            self.definition(DefinitionKind::Interface(item)),
+        );
+        Ok(())
     }
 
     fn recognize_type(&self, item: &syn::ItemType) -> Result<(), Error> {
-        if util::ignore(&item.vis, &item.attrs) {
+        if util::ignore(&item.vis, &item.attrs, &self.cfg, self.doc_hidden) || self.unreachable(&item.ident) {
             return Ok(());
         }
 
         Err(self.error(crate::Error::UnsupportedItem, item))
     }
 
-    fn recognize_use(&self, item: &syn::ItemUse) -> Result<(), Error> {
-        if util::ignore(&item.vis, &item.attrs) {
+    fn recognize_use(&mut self, item: &syn::ItemUse) -> Result<(), Error> {
+        if util::ignore_from_attrs(&item.attrs, &self.cfg) {
             return Ok(());
         }
 
-        Err(self.error(crate::Error::UnsupportedItem, item))
+        // Imports from the crate itself or from extern crates are recorded (whether or not they are `pub`)
+        // so that the names they introduce can be resolved; `pub` ones also make definitions reachable
+        // (see `reachability::PublicPaths`). Other imports (e.g., from `std`) only matter if they are `pub`.
+        match util::use_reexports(&item.tree, &self.module_path, &self.extern_crates, &self.ast.items, self.edition) {
+            Ok(reexports) => {
+                for (name, target) in reexports {
+                    self.recognize_reexport(name, target);
+                }
+                Ok(())
+            }
+            Err(tree) if !util::ignore(&item.vis, &item.attrs, &self.cfg, self.doc_hidden) => {
+                Err(self.error(crate::Error::UnsupportedItem, tree))
+            }
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn recognize_reexport(&mut self, name: Name, target: QualifiedName) {
+        let qname = self.module_path.join(&name);
+        self.recognized.insert(
+            qname,
+            self.definition(DefinitionKind::Reexport(target)),
+        );
     }
 }
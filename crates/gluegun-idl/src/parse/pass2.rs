@@ -1,32 +1,83 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 
 use syn::spanned::Spanned;
 
 use crate::{
-    Enum, Error, Field, Function, FunctionInput, FunctionOutput, IsAsync, Item, Method, MethodCategory, Name, OwnedKind, QualifiedName, Record, RefdTy, Resource, SelfKind, Signature, Span, Ty, TypeKind, Variant, VariantArm
+    AutoTraits, DocExample, Enum, Error, ErrorRepr, Field, Function, FunctionInput, FunctionOutput, Interface, IsAsync, Item, Method, MethodCategory, ModulePath, Name, Newtype, OwnedKind, QualifiedName, Record, RefKind, RefdTy, Resource, SelfKind, Signature, Span, StreamRepr, TimestampRepr, TraitObjectRepr, Ty, TypeKind, UserTypeRepr, Variant, VariantArm
 };
 
 use super::{
+    auto_traits,
+    cfg::CfgSet,
     known_rust::{
         KnownRustFn, KnownRustType, RustPath, KNOWN_RUST_IMPL_TRAIT_TYPES, KNOWN_RUST_TYPES,
     },
     modifier::Modifier,
-    util, Definition, DefinitionKind, SourcePath,
+    modules::ModuleSource,
+    reachability::PublicPaths,
+    util, Definition, DefinitionKind, Export, SourcePath,
 };
 
 pub(super) struct Elaborator<'arena> {
     source: Option<SourcePath>,
-    module_qname: QualifiedName,
-    recognized: Arc<BTreeMap<QualifiedName, Definition<'arena>>>,
+    module_path: Option<ModulePath>,
+
+    /// The definitions to elaborate (typically those of a single module).
+    recognized: BTreeMap<QualifiedName, Definition<'arena>>,
+
+    /// The exports of all modules, used to resolve names.
+    exports: Arc<BTreeMap<QualifiedName, Export>>,
+
+    /// The paths by which the definitions of all modules are named in the output.
+    public_paths: Arc<PublicPaths>,
+
+    /// If true, extract code examples from the doc comments of functions.
+    doc_examples: bool,
+
+    /// Paths of the third-party types to accept as [`TypeKind::Custom`], split on `::`.
+    custom_types: Vec<Vec<String>>,
+
+    /// What `#[cfg]` attributes are evaluated against.
+    cfg: CfgSet,
+
+    /// If true, public items tagged `#[doc(hidden)]` are not ignored (see [`crate::Parser::with_doc_hidden`]).
+    doc_hidden: bool,
+
+    /// If true, definitions and methods that fail to elaborate are left out and their errors
+    /// recorded in `recovered_errors` (see [`crate::Parser::with_error_recovery`]).
+    error_recovery: bool,
+    recovered_errors: RefCell<Vec<Error>>,
+
     out_items: BTreeMap<QualifiedName, Item>,
 }
 
 impl<'arena> Elaborator<'arena> {
-    pub(super) fn new(recognized: Arc<BTreeMap<QualifiedName, Definition<'arena>>>) -> Self {
+    pub(super) fn new(
+        module: &ModuleSource,
+        recognized: BTreeMap<QualifiedName, Definition<'arena>>,
+        exports: Arc<BTreeMap<QualifiedName, Export>>,
+        public_paths: Arc<PublicPaths>,
+    ) -> Self {
         Self {
             recognized,
+            exports,
+            public_paths,
+            doc_examples: module.doc_examples,
+            custom_types: module
+                .custom_types
+                .iter()
+                .map(|path| path.split("::").map(|ident| ident.to_string()).collect())
+                .collect(),
+            cfg: module.cfg.clone(),
+            doc_hidden: module.doc_hidden,
+            error_recovery: module.error_recovery,
+            recovered_errors: RefCell::new(vec![]),
             source: None,
-            module_qname: QualifiedName::new(vec![]),
+            module_path: None,
             out_items: BTreeMap::new(),
         }
     }
@@ -37,25 +88,49 @@ impl<'arena> Elaborator<'arena> {
         self.source.as_ref().unwrap()
     }
 
+    /// The module of the current definition;
+    /// should only be used when processing a definition (which is almost always)
+    fn module_path(&self) -> &ModulePath {
+        self.module_path.as_ref().unwrap()
+    }
+
     fn error(&self, variant: fn(Span) -> Error, spanned: impl Spanned) -> Error {
         variant(self.source().span(spanned))
     }
 
-    pub(super) fn into_elaborated_items(mut self) -> crate::Result<BTreeMap<QualifiedName, Item>> {
-        let recognized = self.recognized.clone();
+    /// With error recovery, record the error of a failed `result` and return `None`,
+    /// so that the caller leaves out what failed to elaborate; otherwise, just propagate it.
+    fn recover<T>(&self, result: crate::Result<T>) -> crate::Result<Option<T>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if self.error_recovery => {
+                self.recovered_errors.borrow_mut().push(error);
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Elaborate the recognized definitions, returning the resulting items
+    /// and the errors recovered from (which is empty unless error recovery is enabled).
+    pub(super) fn into_elaborated_items(mut self) -> crate::Result<(BTreeMap<QualifiedName, Item>, Vec<Error>)> {
+        let recognized = std::mem::take(&mut self.recognized);
         for (qname, definition) in recognized.iter() {
             self.source = Some(definition.source.clone());
-            self.module_qname.set_to_module_of(qname);
-
-            // Convert the input definition and produce the output definition.
-            if let Some(item) = self.elaborate_definition(qname, definition)? {
-                self.out_items.insert(qname.clone(), item);
+            self.module_path = Some(qname.module_path());
+
+            // Convert the input definition and produce the output definition,
+            // named by the path at which it is reachable (e.g., where it is re-exported).
+            let public_path = self.public_paths.public_path(qname).unwrap_or(qname).clone();
+            let item = self.elaborate_definition(&public_path, definition);
+            if let Some(item) = self.recover(item)?.flatten() {
+                self.out_items.insert(public_path, item);
             }
 
             self.source = None;
-            self.module_qname.clear();
+            self.module_path = None;
         }
-        Ok(self.out_items)
+        Ok((self.out_items, self.recovered_errors.into_inner()))
     }
 
     fn elaborate_definition(
@@ -67,6 +142,9 @@ impl<'arena> Elaborator<'arena> {
             DefinitionKind::Record(item) => Ok(Some(Item::Record(
                 self.elaborate_record(qname, definition, item)?,
             ))),
+            DefinitionKind::Newtype(item) => Ok(Some(Item::Newtype(
+                self.elaborate_newtype(qname, definition, item)?,
+            ))),
             DefinitionKind::Resource(item) => Ok(Some(Item::Resource(
                 self.elaborate_resource(qname, definition, item)?,
             ))),
@@ -79,10 +157,17 @@ impl<'arena> Elaborator<'arena> {
             DefinitionKind::Function(item_fn) => Ok(Some(Item::Function(
                 self.elaborate_function(qname, definition, item_fn)?,
             ))),
+            DefinitionKind::Interface(item) => Ok(Some(Item::Interface(
+                self.elaborate_interface(qname, item)?,
+            ))),
             DefinitionKind::FileModule => {
                 // We don't do model modules explicitly in the output, they are inferred by the set of public definitions.
                 Ok(None)
             }
+            DefinitionKind::Reexport(_) => {
+                // Re-exported definitions are elaborated under their original name.
+                Ok(None)
+            }
         }
     }
 
@@ -105,6 +190,39 @@ impl<'arena> Elaborator<'arena> {
             name: qname.tail_name(),
             fields: self.elaborate_record_fields(&self_ty, item)?,
             methods,
+            pod: false,
+            tuple: matches!(item.fields, syn::Fields::Unnamed(_)) && !item.fields.is_empty(),
+            params_of: None,
+        })
+    }
+
+    fn elaborate_newtype(
+        &mut self,
+        qname: &QualifiedName,
+        definition: &Definition<'arena>,
+        item: &syn::ItemStruct,
+    ) -> crate::Result<Newtype> {
+        if !item.generics.params.is_empty() {
+            return Err(self.error(Error::GenericsNotPermitted, &item.generics));
+        }
+
+        let span = self.source().span(&item.ident);
+        let self_ty = Ty::user(span.clone(), qname);
+        let methods = self.elaborate_methods(definition.module, &self_ty, &item.ident)?;
+
+        // Newtypes tagged `#[gluegun::marshal_as(Type)]` are passed as `Type`, whatever their fields.
+        let marshal_as = util::marshal_as(&item.attrs).map_err(|attr| self.error(Error::InvalidMarshalAs, attr))?;
+        let (ty, validated) = match &marshal_as {
+            Some(ty) => (ty, true),
+            None => (&item.fields.iter().next().unwrap().ty, false),
+        };
+
+        Ok(Newtype {
+            span,
+            name: qname.tail_name(),
+            ty: self.elaborate_owned_ty(Some(&self_ty), &mut vec![], ty)?,
+            methods,
+            validated,
         })
     }
 
@@ -148,17 +266,105 @@ impl<'arena> Elaborator<'arena> {
         definition: &Definition<'arena>,
         item: &syn::ItemStruct,
     ) -> crate::Result<Resource> {
-        let span = || self.source().span(&item.ident);
-        let self_ty = Ty::user(span(), qname);
-        let methods = self.elaborate_methods(definition.module, &self_ty, &item.ident)?;
+        let span = self.source().span(&item.ident);
+        let self_ty = Ty::user(span.clone(), qname);
+        let mut methods = self.elaborate_methods(definition.module, &self_ty, &item.ident)?;
+        if util::is_opaque(&item.attrs) {
+            let getters = self.elaborate_getters(&self_ty, item, &methods)?;
+            methods.extend(getters);
+        }
 
         Ok(Resource {
-            span: span(),
+            span,
             name: qname.tail_name(),
             methods,
+            auto_traits: auto_traits::auto_traits(item, definition.module),
+            implements: self.elaborate_implemented_interfaces(definition.module, &item.ident),
         })
     }
 
+    /// The exported interfaces implemented by `impl Trait for Ident` blocks in `module`.
+    /// Other trait impls (e.g., of `Display`, or of traits that are not exported) are not recorded.
+    fn elaborate_implemented_interfaces(&self, module: &'arena syn::File, ident: &syn::Ident) -> Vec<QualifiedName> {
+        let mut interfaces = vec![];
+        for item_impl in self.find_impls(module, ident) {
+            let Some((None, trait_path, _)) = &item_impl.trait_ else {
+                // Inherent or negative impl
+                continue;
+            };
+            // Interfaces cannot be generic (see `pass1::recognize_trait`).
+            if trait_path.segments.iter().any(|segment| !segment.arguments.is_none()) {
+                continue;
+            }
+            let idents: Vec<syn::Ident> = trait_path.segments.iter().map(|segment| segment.ident.clone()).collect();
+            // A trait we cannot resolve is not one of ours, so not an interface either.
+            let Ok(Some(ty)) = self.elaborate_user_type(&item_impl.self_ty, &idents, &[]) else {
+                continue;
+            };
+            let TypeKind::UserType { qname, repr: _ } = ty.kind() else {
+                unreachable!("user types are `TypeKind::UserType`")
+            };
+            if self.export(qname) == Some(&Export::Interface) && !interfaces.contains(qname) {
+                interfaces.push(qname.clone());
+            }
+        }
+        interfaces
+    }
+
+    /// True if the enum `item`, defined in `module`, implements `std::error::Error`: it derives `Error`
+    /// (e.g., `#[derive(thiserror::Error)]`) or `module` contains `impl Error for Type` (or `std::error::Error`, etc.).
+    /// As with the auto traits, we cannot run the trait solver, so any trait named `Error` counts.
+    fn implements_error(&self, module: &'arena syn::File, item: &syn::ItemEnum) -> bool {
+        let is_error = |path: &syn::Path| path.segments.last().is_some_and(|segment| segment.ident == "Error");
+        let derives_error = item.attrs.iter().filter(|attr| attr.path().is_ident("derive")).any(|attr| {
+            attr.parse_args_with(syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated)
+                .is_ok_and(|paths| paths.iter().any(is_error))
+        });
+        derives_error
+            || self.find_impls(module, &item.ident).iter().any(|item_impl| {
+                matches!(&item_impl.trait_, Some((None, trait_path, _)) if is_error(trait_path))
+            })
+    }
+
+    /// The getters of the public fields of an opaque resource (see [`util::is_opaque`]),
+    /// except for fields tagged `#[gluegun::ignore]` and those with the same name as a method.
+    fn elaborate_getters(
+        &mut self,
+        self_ty: &Ty,
+        item: &syn::ItemStruct,
+        methods: &[Method],
+    ) -> crate::Result<Vec<Method>> {
+        let mut getters = vec![];
+        for (field, index) in item.fields.iter().zip(0..) {
+            if util::ignore(&field.vis, &field.attrs, &self.cfg, self.doc_hidden) {
+                continue;
+            }
+            let Field { span, name, ty } = self.elaborate_record_field(self_ty, index, field)?;
+            if methods.iter().any(|method| method.name == name) {
+                continue;
+            }
+            let member = match &field.ident {
+                Some(ident) => util::recognize_name(ident),
+                None => Name::from(index.to_string()),
+            };
+            getters.push(Method {
+                span,
+                category: MethodCategory::Getter(member),
+                name,
+                signature: Signature {
+                    is_async: IsAsync::No,
+                    inputs: vec![],
+                    output_ty: FunctionOutput {
+                        main_ty: ty.refd(RefKind::AnonRef),
+                        error_ty: None,
+                    },
+                },
+                overload: None,
+            });
+        }
+        Ok(getters)
+    }
+
     fn elaborate_variant(
         &mut self,
         qname: &QualifiedName,
@@ -178,6 +384,7 @@ impl<'arena> Elaborator<'arena> {
             name: util::recognize_name(&item.ident),
             arms,
             methods,
+            implements_error: self.implements_error(definition.module, item),
         })
     }
 
@@ -241,9 +448,145 @@ impl<'arena> Elaborator<'arena> {
             name: util::recognize_name(&item.ident),
             arms,
             methods,
+            implements_error: self.implements_error(definition.module, item),
+        })
+    }
+
+    /// An "interface" is a trait whose methods foreign code implements.
+    fn elaborate_interface(
+        &mut self,
+        qname: &QualifiedName,
+        item: &syn::ItemTrait,
+    ) -> crate::Result<Interface> {
+        // `Send`, `Sync`, and `'static` only constrain the implementations, which foreign adapters satisfy.
+        for bound in &item.supertraits {
+            if !self.elaborate_auto_trait_bound(bound, &mut AutoTraits::default())? {
+                return Err(self.error(Error::UnsupportedType, bound));
+            }
+        }
+
+        let mut methods = vec![];
+        for trait_item in &item.items {
+            match trait_item {
+                syn::TraitItem::Fn(fn_item) => {
+                    if util::cfg_disabled(&fn_item.attrs, &self.cfg) {
+                        continue;
+                    }
+                    if util::ignore_from_attrs(&fn_item.attrs, &self.cfg) {
+                        // Foreign implementations cannot provide ignored methods, so Rust must.
+                        if fn_item.default.is_none() {
+                            return Err(self.error(Error::UnsupportedItem, &fn_item.sig.ident));
+                        }
+                        continue;
+                    }
+                    let method = self.elaborate_fn_sig(None, &fn_item.sig)?;
+                    let is_supported = matches!(
+                        method.category,
+                        MethodCategory::InstanceMethod(SelfKind::ByRef | SelfKind::ByRefMut)
+                    ) && matches!(method.signature.is_async, IsAsync::No)
+                        && !matches!(method.signature.output_ty.main_ty.ty().kind(), TypeKind::Stream { .. });
+                    if !is_supported {
+                        return Err(self.error(Error::UnsupportedInterfaceMethod, &fn_item.sig));
+                    }
+                    methods.push(method);
+                }
+
+                syn::TraitItem::Const(trait_item) => {
+                    if !util::ignore_from_attrs(&trait_item.attrs, &self.cfg) {
+                        return Err(self.error(Error::UnsupportedItem, trait_item));
+                    }
+                }
+                syn::TraitItem::Type(trait_item) => {
+                    if !util::ignore_from_attrs(&trait_item.attrs, &self.cfg) {
+                        return Err(self.error(Error::UnsupportedItem, trait_item));
+                    }
+                }
+                syn::TraitItem::Macro(trait_item) => {
+                    if !util::ignore_from_attrs(&trait_item.attrs, &self.cfg) {
+                        return Err(self.error(Error::UnsupportedItem, trait_item));
+                    }
+                }
+
+                syn::TraitItem::Verbatim(trait_item) => {
+                    return Err(self.error(Error::UnsupportedItem, trait_item));
+                }
+
+                _ => return Err(self.error(Error::UnrecognizedItem, trait_item)),
+            }
+        }
+
+        Ok(Interface {
+            span: self.source().span(&item.ident),
+            name: qname.tail_name(),
+            methods,
         })
     }
 
+    /// If `bound` is `Send`, `Sync`, or `'static`, record it in `auto_traits` and return true.
+    fn elaborate_auto_trait_bound(
+        &self,
+        bound: &syn::TypeParamBound,
+        auto_traits: &mut AutoTraits,
+    ) -> crate::Result<bool> {
+        match bound {
+            syn::TypeParamBound::Trait(bound)
+                if bound.lifetimes.is_none() && matches!(bound.modifier, syn::TraitBoundModifier::None) =>
+            {
+                let path = self.elaborate_path(None, &bound.path)?;
+                if !path.tys.is_empty() || !path.bindings.is_empty() {
+                    Ok(false)
+                } else if self.type_path_matches(&path, &["std", "marker", "Send"]) {
+                    auto_traits.send = true;
+                    Ok(true)
+                } else if self.type_path_matches(&path, &["std", "marker", "Sync"]) {
+                    auto_traits.sync = true;
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            }
+            syn::TypeParamBound::Lifetime(lifetime) => Ok(lifetime.ident == "static"),
+            _ => Ok(false),
+        }
+    }
+
+    /// Elaborate the trait object `dyn Trait` (with optional `Send`, `Sync`, and `'static` bounds) appearing in `ty`,
+    /// where `Trait` is an interface, returning its name and the auto traits.
+    fn elaborate_trait_object(
+        &self,
+        ty: &syn::Type,
+        trait_object: &syn::TypeTraitObject,
+    ) -> crate::Result<(QualifiedName, AutoTraits)> {
+        let mut auto_traits = AutoTraits::default();
+        let mut interface = None;
+        for bound in &trait_object.bounds {
+            if self.elaborate_auto_trait_bound(bound, &mut auto_traits)? {
+                continue;
+            }
+            let syn::TypeParamBound::Trait(trait_bound) = bound else {
+                return Err(self.error(Error::UnsupportedType, bound));
+            };
+            if interface.is_some() {
+                return Err(self.error(Error::UnsupportedType, bound));
+            }
+            let path = self.elaborate_path(None, &trait_bound.path)?;
+            let Some(user_ty) = self.elaborate_user_type(ty, &path.idents, &path.tys)? else {
+                return Err(self.error(Error::UnresolvedName, bound));
+            };
+            let TypeKind::UserType { qname, repr: _ } = user_ty.kind() else {
+                unreachable!("user types are `TypeKind::UserType`")
+            };
+            if self.export(qname) != Some(&Export::Interface) {
+                return Err(self.error(Error::NotInterface, bound));
+            }
+            interface = Some(qname.clone());
+        }
+        match interface {
+            Some(qname) => Ok((qname, auto_traits)),
+            None => Err(self.error(Error::NotInterface, trait_object)),
+        }
+    }
+
     fn elaborate_methods(
         &self,
         module: &syn::File,
@@ -256,33 +599,39 @@ impl<'arena> Elaborator<'arena> {
 
         for impl_item in inherent_impls {
             for item_in_impl in &impl_item.items {
-                match item_in_impl {
-                    syn::ImplItem::Fn(fn_item) => {
-                        self.parse_method(&mut methods, self_ty, impl_item, fn_item)?;
-                    }
+                // With error recovery, an unsupported method is left out and the others are still elaborated.
+                let result = match item_in_impl {
+                    syn::ImplItem::Fn(fn_item) => self.parse_method(&mut methods, self_ty, impl_item, fn_item),
 
                     syn::ImplItem::Const(item_in_impl) => {
-                        if !util::ignore(&item_in_impl.vis, &item_in_impl.attrs) {
-                            return Err(self.error(Error::UnsupportedItem, &item_in_impl));
+                        let cfg = &self.cfg;
+                        if !util::ignore(&item_in_impl.vis, &item_in_impl.attrs, cfg, self.doc_hidden) {
+                            Err(self.error(Error::UnsupportedItem, &item_in_impl))
+                        } else {
+                            Ok(())
                         }
                     }
                     syn::ImplItem::Type(item_in_impl) => {
-                        if !util::ignore(&item_in_impl.vis, &item_in_impl.attrs) {
-                            return Err(self.error(Error::UnsupportedItem, &item_in_impl));
+                        let cfg = &self.cfg;
+                        if !util::ignore(&item_in_impl.vis, &item_in_impl.attrs, cfg, self.doc_hidden) {
+                            Err(self.error(Error::UnsupportedItem, &item_in_impl))
+                        } else {
+                            Ok(())
                         }
                     }
                     syn::ImplItem::Macro(item_in_impl) => {
-                        if !util::ignore_from_attrs(&item_in_impl.attrs) {
-                            return Err(self.error(Error::UnsupportedItem, &item_in_impl));
+                        if !util::ignore_from_attrs(&item_in_impl.attrs, &self.cfg) {
+                            Err(self.error(Error::UnsupportedItem, &item_in_impl))
+                        } else {
+                            Ok(())
                         }
                     }
 
-                    syn::ImplItem::Verbatim(impl_item) => {
-                        return Err(self.error(Error::UnsupportedItem, &impl_item));
-                    }
+                    syn::ImplItem::Verbatim(impl_item) => Err(self.error(Error::UnsupportedItem, &impl_item)),
 
-                    _ => return Err(self.error(Error::UnrecognizedItem, &item_in_impl)),
-                }
+                    _ => Err(self.error(Error::UnrecognizedItem, &item_in_impl)),
+                };
+                self.recover(result)?;
             }
         }
 
@@ -296,7 +645,7 @@ impl<'arena> Elaborator<'arena> {
         impl_item: &syn::ItemImpl,
         fn_item: &syn::ImplItemFn,
     ) -> crate::Result<()> {
-        if util::ignore(&fn_item.vis, &fn_item.attrs) {
+        if util::ignore(&fn_item.vis, &fn_item.attrs, &self.cfg, self.doc_hidden) {
             return Ok(());
         }
 
@@ -304,7 +653,9 @@ impl<'arena> Elaborator<'arena> {
             return Err(self.error(Error::GenericsNotPermitted, &impl_item.generics));
         }
 
-        let method = self.elaborate_fn_sig(Some(self_ty), &fn_item.sig)?;
+        let mut method = self.elaborate_fn_sig(Some(self_ty), &fn_item.sig)?;
+        method.overload =
+            util::overload_group(&fn_item.attrs).map_err(|attr| self.error(Error::InvalidOverload, attr))?;
         methods.push(method);
         Ok(())
     }
@@ -364,10 +715,16 @@ impl<'arena> Elaborator<'arena> {
 
             syn::Type::Path(type_path) => {
                 let rust_path = self.elaborate_type_path(self_ty, type_path)?;
-                if let Some((main_ty, err_ty)) = self.match_type_path(self_ty, &mut vec![], ty, &rust_path, &["std", "result", "Result"])? {
+                if let Some((main_ty, err_ty)) = self.match_type_path(self_ty, &mut vec![], &rust_path, &["std", "result", "Result"])? {
                     let err_ty = err_ty.owned_or_err()?.clone();
                     Ok(FunctionOutput { main_ty, error_ty: Some(err_ty) })
-                } else if let Some(main_ty) = self.match_type_path(self_ty, &mut vec![], ty, &rust_path, &["anyhow", "Result"])? {
+                } else if self.is_io_path(&rust_path, "Result") {
+                    let Some(main_ty) = self.match_type_path(self_ty, &mut vec![], &rust_path, &["std", "io", "Result"])? else {
+                        return Err(Error::UnsupportedNumberOfArguments(span, 1, rust_path.tys.len()));
+                    };
+                    let err_ty = Ty::io_error(span);
+                    Ok(FunctionOutput { main_ty, error_ty: Some(err_ty) })
+                } else if let Some(main_ty) = self.match_type_path(self_ty, &mut vec![], &rust_path, &["anyhow", "Result"])? {
                     let err_ty = Ty::anyhow_error(span);
                     Ok(FunctionOutput { main_ty, error_ty: Some(err_ty) })
                 } else {
@@ -375,9 +732,24 @@ impl<'arena> Elaborator<'arena> {
                 }
             }
 
-            syn::Type::ImplTrait(_) => {
+            syn::Type::ImplTrait(impl_trait_ty) => {
+                // A result like `impl Future<Output = String> + 'a` would have to borrow from an argument after the call.
+                let borrowed = impl_trait_ty.bounds.iter().find_map(|bound| match bound {
+                    syn::TypeParamBound::Lifetime(lifetime) if lifetime.ident != "static" => Some(lifetime),
+                    _ => None,
+                });
+                if let Some(lifetime) = borrowed {
+                    return Err(self.error(Error::BorrowingImplTrait, lifetime));
+                }
+
                 // FIXME: we want to detect `-> impl Future` and treat it as equivalent to an async function.
-                fallback()
+                let output = fallback()?;
+
+                // `impl IntoIterator` is only accepted as an argument (backends pass a `Vec`), not as a result.
+                if let TypeKind::Vec { repr: crate::VecRepr::ImplIntoIterator, .. } = output.main_ty.ty().kind() {
+                    return Err(self.error(Error::UnsupportedUseOfType, ty));
+                }
+                Ok(output)
             }
 
             _ => fallback(),
@@ -416,12 +788,8 @@ impl<'arena> Elaborator<'arena> {
                     return Err(self.error(Error::UnsupportedType, &m));
                 }
 
-                if let Some(m) = &ty.lifetime {
-                    // Do not permit named lifetimes for now (do they do any harm though?)
-                    return Err(self.error(Error::UnsupportedType, &m));
-                }
-
                 // `&T` is the same from an abstract point of view, only the Rust representation is affected.
+                // So is `&'a T`: the data is copied across the boundary either way (see `check_static_argument` for `&'static T`).
                 Self::with_modifier(
                     modifiers,
                     Modifier::Ref(crate::RefKind::AnonRef),
@@ -445,6 +813,20 @@ impl<'arena> Elaborator<'arena> {
                 }
             }
 
+            syn::Type::TraitObject(trait_object) => {
+                // `dyn Trait` is only supported behind a reference (`&dyn Trait`); see `elaborate_box` for boxes.
+                let span = self.source().span(ty);
+                let [Modifier::Ref(r)] = &**modifiers else {
+                    return Err(Error::UnsupportedUseOfType(span));
+                };
+                let (qname, auto_traits) = self.elaborate_trait_object(ty, trait_object)?;
+                Ok(TypeKind::TraitObject {
+                    qname,
+                    repr: TraitObjectRepr::Dyn(auto_traits),
+                }
+                .refd(span, r.clone()))
+            }
+
             syn::Type::Tuple(ty) => {
                 // Tuples are first-class in our IR
 
@@ -486,19 +868,46 @@ impl<'arena> Elaborator<'arena> {
             } else {
                 Err(self.error(Error::UnresolvedName, &ty))
             }
+        } else if let Some(custom_path) = self.custom_type_path(&rust_path) {
+            // Found a third-party type the user configured an override for.
+            // Its generic arguments (if any) are up to the override.
+            // This takes precedence over well-known types like `uuid::Uuid`, so that overrides keep working.
+            let custom_ty = Ty::new(
+                self.source().span(ty),
+                TypeKind::Custom {
+                    path: custom_path.join("::"),
+                },
+            );
+            self.maybe_referenced(modifiers, ty, custom_ty)
         } else if let Some(rust_ty) =
             self.elaborate_rust_type(self_ty, modifiers, ty, &rust_path, &KNOWN_RUST_TYPES)?
         {
             // Found a well-known Rust type.
             Ok(rust_ty)
+        } else if let Some(date_time_ty) = self.elaborate_chrono_date_time(self_ty, modifiers, ty, &rust_path)? {
+            Ok(date_time_ty)
+        } else if let Some(trait_object_ty) = self.elaborate_box(self_ty, modifiers, ty, &rust_path)? {
+            Ok(trait_object_ty)
+        } else if self.is_io_path(&rust_path, "Error") {
+            if !rust_path.tys.is_empty() {
+                return Err(Error::UnsupportedNumberOfArguments(self.source().span(ty), 0, rust_path.tys.len()));
+            }
+            self.maybe_referenced(modifiers, ty, Ty::io_error(self.source().span(ty)))
         } else if let Some(user_ty) =
             self.elaborate_user_type(ty, &rust_path.idents, &rust_path.tys)?
         {
             // Found a type defined by the user in the input somewhere.
 
             // Currently we don't have any kind of user types etc that support bindings.
-            if !rust_path.bindings.is_empty() {
-                return Err(self.error(Error::BindingNotExpected, ty));
+            if let Some(binding_ty) = rust_path.bindings.values().next() {
+                return Err(Error::BindingNotExpected(binding_ty.span().clone()));
+            }
+
+            // A trait can only be used as a trait object.
+            if let TypeKind::UserType { qname, repr: _ } = user_ty.kind() {
+                if self.export(qname) == Some(&Export::Interface) {
+                    return Err(self.error(Error::NotType, ty));
+                }
             }
 
             self.maybe_referenced(modifiers, ty, user_ty)
@@ -508,6 +917,130 @@ impl<'arena> Elaborator<'arena> {
         }
     }
 
+    /// Returns the path of the third-party type (see [`TypeKind::Custom`]) that `path` refers to, if any.
+    /// A single identifier (e.g., `DateTime`) matches the last segment of the path,
+    /// unless it names a definition of the current module.
+    fn custom_type_path(&self, path: &RustPath<'_>) -> Option<&Vec<String>> {
+        if let [ident] = &path.idents[..] {
+            if self.exports.contains_key(&self.module_path().join(Name::from_ident(ident))) {
+                return None;
+            }
+        }
+        self.custom_types.iter().find(|custom_path| {
+            let custom_path: Vec<&str> = custom_path.iter().map(|ident| ident.as_str()).collect();
+            self.type_path_matches(path, &custom_path)
+        })
+    }
+
+    /// Matches `chrono::DateTime<Utc>`, whose argument is a time zone rather than a type that can be elaborated
+    /// (hence it is not in [`KNOWN_RUST_TYPES`][]). Other time zones are an error.
+    fn elaborate_chrono_date_time(
+        &self,
+        self_ty: Option<&Ty>,
+        modifiers: &Vec<Modifier>,
+        ty: &syn::Type,
+        path: &RustPath<'_>,
+    ) -> crate::Result<Option<RefdTy>> {
+        if !self.type_path_matches(path, &["chrono", "DateTime"]) {
+            return Ok(None);
+        }
+        let [syn::Type::Path(time_zone)] = &path.tys[..] else {
+            return Err(self.error(Error::UnsupportedUseOfType, ty));
+        };
+        let time_zone_path = self.elaborate_path(self_ty, &time_zone.path)?;
+        if !time_zone_path.tys.is_empty() || !self.type_path_matches(&time_zone_path, &["chrono", "Utc"]) {
+            return Err(self.error(Error::UnsupportedUseOfType, time_zone));
+        }
+        let date_time_ty = Ty::new(
+            self.source().span(ty),
+            TypeKind::Timestamp {
+                repr: TimestampRepr::ChronoDateTimeUtc,
+            },
+        );
+        Ok(Some(self.maybe_referenced(modifiers, ty, date_time_ty)?))
+    }
+
+    /// If `trait_object` is `dyn Error` (with optional `Send`, `Sync`, and `'static` bounds), returns its auto traits.
+    /// A bare `Error` does not match if the current module defines an `Error` of its own.
+    fn elaborate_error_trait_object(&self, trait_object: &syn::TypeTraitObject) -> crate::Result<Option<AutoTraits>> {
+        let mut auto_traits = AutoTraits::default();
+        let mut is_error = false;
+        for bound in &trait_object.bounds {
+            if self.elaborate_auto_trait_bound(bound, &mut auto_traits)? {
+                continue;
+            }
+            let syn::TypeParamBound::Trait(trait_bound) = bound else {
+                return Ok(None);
+            };
+            let path = self.elaborate_path(None, &trait_bound.path)?;
+            if let [ident] = &path.idents[..] {
+                if self.exports.contains_key(&self.module_path().join(Name::from_ident(ident))) {
+                    return Ok(None);
+                }
+            }
+            if is_error || !path.tys.is_empty() || !self.type_path_matches(&path, &["std", "error", "Error"]) {
+                return Ok(None);
+            }
+            is_error = true;
+        }
+        Ok(is_error.then_some(auto_traits))
+    }
+
+    /// True if `path` names `std::io::{name}` (e.g., `io::Error`). Unlike the entries in [`KNOWN_RUST_TYPES`][],
+    /// a single identifier does not match: a bare `Error` or `Result` is more likely the crate's own.
+    fn is_io_path(&self, path: &RustPath<'_>, name: &str) -> bool {
+        path.idents.len() > 1 && self.type_path_matches(path, &["std", "io", name])
+    }
+
+    /// Matches `Box<dyn Trait>`, where `Trait` is an interface, `Box<dyn Error>` (see [`ErrorRepr::BoxDynError`]),
+    /// and `Box<T>`, where `T` is a type defined by the user
+    /// (as needed for recursive types, e.g. `next: Option<Box<Node>>`). Other boxes are not supported.
+    fn elaborate_box(
+        &self,
+        self_ty: Option<&Ty>,
+        modifiers: &Vec<Modifier>,
+        ty: &syn::Type,
+        path: &RustPath<'_>,
+    ) -> crate::Result<Option<RefdTy>> {
+        if !self.type_path_matches(path, &["std", "boxed", "Box"]) {
+            return Ok(None);
+        }
+        if let [syn::Type::TraitObject(trait_object)] = &path.tys[..] {
+            if let Some(auto_traits) = self.elaborate_error_trait_object(trait_object)? {
+                let error_ty = Ty::new(
+                    self.source().span(ty),
+                    TypeKind::Error {
+                        repr: ErrorRepr::BoxDynError(auto_traits),
+                    },
+                );
+                return Ok(Some(self.maybe_referenced(modifiers, ty, error_ty)?));
+            }
+        }
+        let boxed_ty = match &path.tys[..] {
+            [syn::Type::TraitObject(trait_object)] => {
+                let (qname, auto_traits) = self.elaborate_trait_object(ty, trait_object)?;
+                TypeKind::TraitObject {
+                    qname,
+                    repr: TraitObjectRepr::BoxDyn(auto_traits),
+                }
+            }
+            [element] => match self.elaborate_owned_ty(self_ty, &mut vec![], element)?.kind() {
+                TypeKind::UserType { qname, repr: UserTypeRepr::Direct } => TypeKind::UserType {
+                    qname: qname.clone(),
+                    repr: UserTypeRepr::Boxed,
+                },
+                _ => return Err(self.error(Error::UnsupportedUseOfType, element)),
+            },
+            tys => {
+                // Point at the first extra argument, if there are too many.
+                let span = tys.get(1).map_or_else(|| self.source().span(ty), |extra| self.source().span(extra));
+                return Err(Error::UnsupportedNumberOfArguments(span, 1, tys.len()));
+            }
+        };
+        let boxed_ty = Ty::new(self.source().span(ty), boxed_ty);
+        Ok(Some(self.maybe_referenced(modifiers, ty, boxed_ty)?))
+    }
+
     /// Match the path, deconstructed into `idents` and `tys`, that appears in `ty` against the list `krts` of known Rust types.
     /// Returns `Ok(Some(ty))` if the match is successful or `Ok(None)` if there is no match.
     /// Returns an error if there is a match for the name but the arity is wrong or some other similar situation.
@@ -537,7 +1070,7 @@ impl<'arena> Elaborator<'arena> {
             }
             KnownRustFn::Modifier(modifier) => {
                 if path.tys.len() != 1 {
-                    return Err(self.error(Error::UnsupportedType, &ty));
+                    return Err(Error::UnsupportedNumberOfArguments(span, 1, path.tys.len()));
                 }
                 Self::with_modifier(modifiers, modifier.clone(), |modifiers| {
                     let ty = path.tys[0];
@@ -556,21 +1089,22 @@ impl<'arena> Elaborator<'arena> {
             // and we won't notice. Oh well, I'm lazy.
             path.idents[0] == known_rust_path.last().unwrap()
         } else {
-                path.idents.len() == known_rust_path.len()
-                    && path.idents.iter().zip(known_rust_path).all(|(a, b)| a == b)
+            // Otherwise, the path must be a suffix of the known path, so that `io::Result` matches `std::io::Result`.
+            path.idents.len() <= known_rust_path.len()
+                && path.idents.iter().rev().zip(known_rust_path.iter().rev()).all(|(a, b)| a == b)
         }
     }
 
     /// If `path` matches against `known_rust_path`, returns the type parameters in vector form
-    fn match_type_path<M>(&self, self_ty: Option<&Ty>, modifiers: &mut Vec<Modifier>, ty: &syn::Type, path: &RustPath<'_>, known_rust_path: &[&str]) -> crate::Result<Option<M>> 
+    fn match_type_path<M>(&self, self_ty: Option<&Ty>, modifiers: &mut Vec<Modifier>, path: &RustPath<'_>, known_rust_path: &[&str]) -> crate::Result<Option<M>> 
     where 
     M: MatchArity,{
         if !self.type_path_matches(path, known_rust_path) {
             return Ok(None);
         }
 
-        if !path.bindings.is_empty() {
-            return Err(self.error(Error::BindingNotExpected, ty));
+        if let Some(binding_ty) = path.bindings.values().next() {
+            return Err(Error::BindingNotExpected(binding_ty.span().clone()));
         }
 
         let tys = path
@@ -601,7 +1135,7 @@ impl<'arena> Elaborator<'arena> {
                         &rust_path,
                         KNOWN_RUST_IMPL_TRAIT_TYPES,
                     )? {
-                        return Ok(ty);
+                        return self.elaborate_stream_auto_traits(ty, impl_trait_ty);
                     } else {
                         return Err(self.error(Error::UnsupportedType, &bound));
                     }
@@ -625,6 +1159,20 @@ impl<'arena> Elaborator<'arena> {
         return Err(self.error(Error::UnsupportedType, &ty));
     }
 
+    /// If `ty` is a stream, record the auto traits among the other bounds of `impl_trait_ty`
+    /// (e.g., the `Send` of `impl Stream<Item = T> + Send`), which backends need to box it.
+    fn elaborate_stream_auto_traits(&self, ty: RefdTy, impl_trait_ty: &syn::TypeImplTrait) -> crate::Result<RefdTy> {
+        let TypeKind::Stream { item, repr: _ } = ty.ty().kind() else {
+            return Ok(ty);
+        };
+        let mut auto_traits = AutoTraits::default();
+        for bound in impl_trait_ty.bounds.iter().skip(1) {
+            self.elaborate_auto_trait_bound(bound, &mut auto_traits)?;
+        }
+        let repr = StreamRepr::ImplStream(auto_traits);
+        Ok(TypeKind::Stream { item: item.clone(), repr }.not_refd(ty.ty().span().clone()))
+    }
+
     /// Try to resolve the path type `ty`, broken down into `idents` and `tys`,
     /// against the user-defined this we are aware of. `idents` can be an absolute or relative path.
     ///
@@ -650,22 +1198,47 @@ impl<'arena> Elaborator<'arena> {
 
         if ident0 == "crate" {
             // A path beginning with `crate::foo` is an absolute path relative to the crate name.
-            let crate_name = self.module_qname.just_crate();
+            let crate_root = self.module_path().crate_root();
             match self.elaborate_user_ty_in_module_relative_to(
                 ty,
-                &crate_name,
+                &crate_root,
                 idents_rest,
                 &tys,
             )? {
                 Some(ty) => Ok(Some(ty)),
-                None => Err(self.error(Error::UnresolvedName, &ty)),
+                None => Err(self.unresolved_name(ty, idents_rest)),
             }
+        } else if let Some(ty) =
+            self.elaborate_user_ty_in_module_relative_to(ty, self.module_path(), idents, &tys)?
+        {
+            // Other paths are relative to the current module...
+            Ok(Some(ty))
         } else {
-            // Other paths are relative to the current module.
-            self.elaborate_user_ty_in_module_relative_to(ty, &self.module_qname, idents, &tys)
+            // ...or, like `other_crate::Foo`, begin with the name of an extern crate.
+            self.elaborate_user_ty_in_module_relative_to(ty, &ModulePath::root(), idents, &tys)
         }
     }
 
+    /// The export of the definition named `qname` in the output (see [`PublicPaths`]).
+    fn export(&self, qname: &QualifiedName) -> Option<&Export> {
+        let definition_path = self.public_paths.definition_path(qname).unwrap_or(qname);
+        self.exports.get(definition_path)
+    }
+
+    /// If `path` names a re-export (e.g., `pub use other_crate::Config`),
+    /// returns the path of the definition that is re-exported; otherwise returns `path`.
+    fn resolve_reexports(&self, ty: &syn::Type, mut path: QualifiedName) -> crate::Result<QualifiedName> {
+        let mut visited = BTreeSet::new();
+        while let Some(Export::Reexport(target)) = self.exports.get(&path) {
+            if !visited.insert(path.clone()) {
+                // Cycle of re-exports
+                return Err(self.error(Error::UnresolvedName, ty));
+            }
+            path = target.clone();
+        }
+        Ok(path)
+    }
+
     /// Try to resolve the remainder of a path against the list of exports from this module.
     ///
     /// Returns
@@ -676,7 +1249,7 @@ impl<'arena> Elaborator<'arena> {
     fn elaborate_user_ty_in_module_relative_to(
         &self,
         ty: &syn::Type,
-        qname: &QualifiedName,
+        module: &ModulePath,
         idents: &[syn::Ident],
         tys: &[Ty],
     ) -> crate::Result<Option<Ty>> {
@@ -684,37 +1257,46 @@ impl<'arena> Elaborator<'arena> {
             return Ok(None);
         };
 
-        let path = qname.join(&Name::from_ident(ident0));
-        match self.recognized.get(&path) {
+        let path = self.resolve_reexports(ty, module.join(&Name::from_ident(ident0)))?;
+        match self.exports.get(&path) {
             None => Ok(None),
 
-            Some(definition) => match &definition.kind {
-                DefinitionKind::FileModule => {
+            Some(export) => match export {
+                Export::Module => {
                     match self.elaborate_user_ty_in_module_relative_to(
                         ty,
-                        &path,
+                        &ModulePath::named(path.clone()),
                         idents_rest,
                         tys,
                     )? {
-                        None => Err(self.error(Error::UnresolvedName, &ty)),
+                        None => Err(self.unresolved_name(ty, idents_rest)),
                         Some(ty) => Ok(Some(ty)),
                     }
                 }
-                DefinitionKind::Record(_)
-                | DefinitionKind::Variant(..)
-                | DefinitionKind::Enum(..)
-                | DefinitionKind::Resource(_) => {
-                    if !tys.is_empty() {
-                        Err(self.error(Error::GenericsNotPermitted, &ty))
+                // Interfaces are resolved like types; only trait objects may refer to them (see `elaborate_trait_object`).
+                Export::Type | Export::Interface => {
+                    if let Some(generic_ty) = tys.first() {
+                        Err(Error::GenericsNotPermitted(generic_ty.span().clone()))
                     } else {
-                        Ok(Some(Ty::user(self.source().span(ident0), &path)))
+                        let public_path = self.public_paths.public_path(&path).unwrap_or(&path);
+                        Ok(Some(Ty::user(self.source().span(ty), public_path)))
                     }
                 }
-                DefinitionKind::Function(_) => Err(self.error(Error::NotType, &ty)),
+                Export::Function => Err(self.error(Error::NotType, &ty)),
+                Export::Reexport(_) => unreachable!("re-exports are resolved above"),
             },
         }
     }
 
+    /// The error for a path in `ty` whose remaining `idents` do not resolve, located at the first of them
+    /// (or at `ty` if none remain, i.e. the path names a module).
+    fn unresolved_name(&self, ty: &syn::Type, idents: &[syn::Ident]) -> Error {
+        match idents.first() {
+            Some(ident) => self.error(Error::UnresolvedName, ident),
+            None => self.error(Error::UnresolvedName, ty),
+        }
+    }
+
     fn elaborate_type_path<'syn>(
         &self,
         self_ty: Option<&Ty>,
@@ -809,6 +1391,18 @@ impl<'arena> Elaborator<'arena> {
         &self,
         module: &'arena syn::File,
         ident: &syn::Ident,
+    ) -> Vec<&'arena syn::ItemImpl> {
+        self.find_impls(module, ident)
+            .into_iter()
+            .filter(|item_impl| item_impl.trait_.is_none())
+            .collect()
+    }
+
+    /// The `impl` blocks for the type `ident` in `module`, inherent or not, unless ignored.
+    fn find_impls(
+        &self,
+        module: &'arena syn::File,
+        ident: &syn::Ident,
     ) -> Vec<&'arena syn::ItemImpl> {
         module
             .items
@@ -820,7 +1414,7 @@ impl<'arena> Elaborator<'arena> {
                     None
                 }
             })
-            .filter(|item_impl| item_impl.trait_.is_none())
+            .filter(|item_impl| !util::ignore_from_attrs(&item_impl.attrs, &self.cfg))
             .filter(|item_impl| {
                 if let syn::Type::Path(path) = &*item_impl.self_ty {
                     path.path.is_ident(ident)
@@ -842,20 +1436,68 @@ impl<'arena> Elaborator<'arena> {
             category: _,
             name,
             signature,
+            overload: _,
         } = self.elaborate_fn_sig(None, &item_fn.sig)?;
+        let examples = if self.doc_examples {
+            self.elaborate_doc_examples(&item_fn.attrs)
+        } else {
+            vec![]
+        };
         Ok(Function {
             span,
             name,
             signature,
+            examples,
         })
     }
 
+    fn elaborate_doc_examples(&self, attrs: &[syn::Attribute]) -> Vec<DocExample> {
+        util::doc_examples(attrs)
+            .into_iter()
+            .map(|(attr, code)| DocExample {
+                span: self.source().span(attr),
+                code,
+            })
+            .collect()
+    }
+
+    /// Report arguments the function writes its results to (e.g., `buf: &mut [u8]`) with a dedicated error,
+    /// which suggests returning them instead, rather than the generic [`Error::UnsupportedType`][].
+    fn check_out_parameter(&self, ty: &syn::Type) -> crate::Result<()> {
+        match ty {
+            syn::Type::Group(ty) => self.check_out_parameter(&ty.elem),
+            syn::Type::Paren(ty) => self.check_out_parameter(&ty.elem),
+            syn::Type::Reference(reference) if reference.mutability.is_some() => match &*reference.elem {
+                // `&mut dyn Trait` is not an out-parameter.
+                syn::Type::TraitObject(_) => Ok(()),
+                syn::Type::Slice(_) => Err(Error::OutParameter(
+                    self.source().span(ty),
+                    "return the data instead, e.g. as a `Vec<T>`",
+                )),
+                _ => Err(Error::OutParameter(
+                    self.source().span(ty),
+                    "return the new value instead, in a tuple if the function has other results",
+                )),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Report arguments borrowed for `'static` (e.g., `name: &'static str`), which the caller cannot provide,
+    /// as what it passes is converted into a temporary for the duration of the call.
+    fn check_static_argument(&self, ty: &syn::Type) -> crate::Result<()> {
+        match util::static_reference(ty) {
+            Some(lifetime) => Err(self.error(Error::StaticReferenceArgument, lifetime)),
+            None => Ok(()),
+        }
+    }
+
     fn elaborate_fn_sig(
         &self,
         self_ty: Option<&Ty>,
         sig: &syn::Signature,
     ) -> crate::Result<Method> {
-        if !sig.generics.params.is_empty() {
+        if !util::lifetimes_only(&sig.generics) {
             return Err(self.error(Error::GenericsNotPermitted, &sig.generics));
         }
 
@@ -864,10 +1506,8 @@ impl<'arena> Elaborator<'arena> {
         // Check for `&self` and friends
         let self_kind = if let Some(syn::FnArg::Receiver(receiver)) = sig.inputs.first() {
             if let Some(colon) = receiver.colon_token {
-                return Err(self.error(Error::ExplicitSelfNotSupported, &colon));
-            }
-
-            if receiver.reference.is_none() {
+                Some(self.elaborate_explicit_self(self_ty, colon, &receiver.ty)?)
+            } else if receiver.reference.is_none() {
                 Some(SelfKind::ByValue)
             } else if receiver.mutability.is_none() {
                 Some(SelfKind::ByRef)
@@ -888,6 +1528,8 @@ impl<'arena> Elaborator<'arena> {
                 }
 
                 syn::FnArg::Typed(input) => {
+                    self.check_out_parameter(&input.ty)?;
+                    self.check_static_argument(&input.ty)?;
                     let ty = self.elaborate_ty(self_ty, &mut vec![], &input.ty)?;
                     inputs.push(FunctionInput {
                         span: self.source().span(&input.pat),
@@ -906,6 +1548,19 @@ impl<'arena> Elaborator<'arena> {
 
         let output_ty = self.elaborate_return_ty(&mut is_async, self_ty, &sig.output)?;
 
+        // Streams are only accepted as the result itself (possibly with an error), not as an argument or nested in the result.
+        let misplaced_stream = inputs
+            .iter()
+            .find_map(|input| stream_span(input.refd_ty.ty()))
+            .or_else(|| match output_ty.main_ty.ty().kind() {
+                TypeKind::Stream { item, repr: _ } => stream_span(item),
+                _ => stream_span(output_ty.main_ty.ty()),
+            })
+            .or_else(|| output_ty.error_ty.as_ref().and_then(stream_span));
+        if let Some(span) = misplaced_stream {
+            return Err(Error::MisplacedStream(span.clone()));
+        }
+
         // Detect if the return type is an owned copy of the self type;
         // this will be used to decide whether to categorize this as a builder
         // method.
@@ -918,6 +1573,7 @@ impl<'arena> Elaborator<'arena> {
         // Categorize the function
         let category = match self_kind {
             None if sig.ident == "new" && output_is_self => MethodCategory::Constructor,
+            None if output_is_self => MethodCategory::NamedConstructor,
             None => MethodCategory::StaticMethod,
             Some(SelfKind::ByValue) if output_is_self => {
                 MethodCategory::BuilderMethod(self_kind.unwrap())
@@ -934,8 +1590,54 @@ impl<'arena> Elaborator<'arena> {
                 inputs,
                 output_ty,
             },
+            overload: None,
         })
     }
+
+    /// Elaborate an explicit receiver like `self: Arc<Self>`.
+    /// Only shared-ownership receivers (`Arc<Self>` and `Rc<Self>`) are supported.
+    fn elaborate_explicit_self(
+        &self,
+        self_ty: Option<&Ty>,
+        colon: syn::Token![:],
+        ty: &syn::Type,
+    ) -> crate::Result<SelfKind> {
+        if let syn::Type::Path(type_path) = ty {
+            let path = self.elaborate_type_path(self_ty, type_path)?;
+            let self_kind = if self.type_path_matches(&path, &["std", "sync", "Arc"]) {
+                Some(SelfKind::ByArc)
+            } else if self.type_path_matches(&path, &["std", "rc", "Rc"]) {
+                Some(SelfKind::ByRc)
+            } else {
+                None
+            };
+            let is_self = |ty: &syn::Type| {
+                matches!(ty, syn::Type::Path(p) if p.qself.is_none() && p.path.is_ident("Self"))
+            };
+            if let Some(self_kind) = self_kind {
+                if path.bindings.is_empty() && matches!(&path.tys[..], [ty] if is_self(ty)) {
+                    return Ok(self_kind);
+                }
+            }
+        }
+
+        Err(self.error(Error::ExplicitSelfNotSupported, colon))
+    }
+}
+
+/// The span of the first stream mentioned by `ty`, if any.
+fn stream_span(ty: &Ty) -> Option<&Span> {
+    match ty.kind() {
+        TypeKind::Stream { .. } => Some(ty.span()),
+        TypeKind::Map { key, value, repr: _ } => stream_span(key).or_else(|| stream_span(value)),
+        TypeKind::Vec { element, repr: _ } | TypeKind::Set { element, repr: _ } | TypeKind::Option { element, repr: _ } => {
+            stream_span(element)
+        }
+        TypeKind::Result { ok, err, repr: _ } => stream_span(ok).or_else(|| stream_span(err)),
+        TypeKind::Tuple { elements, repr: _ } => elements.iter().find_map(stream_span),
+        TypeKind::Future { output, repr: _ } => stream_span(output),
+        _ => None,
+    }
 }
 
 pub trait MatchArity: Sized {
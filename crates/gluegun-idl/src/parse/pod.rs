@@ -0,0 +1,53 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Item, QualifiedName, Scalar, Ty, TypeKind};
+
+/// Set [`Record::pod`](`crate::Record::pod`) on the records of `definitions` that are POD-compatible.
+///
+/// Records may contain each other (in any order), so we start from all records with fields
+/// and drop those with a field that is not POD-compatible until no more are dropped.
+pub(super) fn mark_pod_records(definitions: &mut BTreeMap<QualifiedName, Item>) {
+    let mut pod: BTreeSet<QualifiedName> = definitions
+        .iter()
+        .filter(|(_, item)| matches!(item, Item::Record(record) if !record.fields.is_empty()))
+        .map(|(qname, _)| qname.clone())
+        .collect();
+    loop {
+        let dropped: Vec<QualifiedName> = pod
+            .iter()
+            .filter(|qname| {
+                let Some(Item::Record(record)) = definitions.get(*qname) else {
+                    return true;
+                };
+                !record.fields.iter().all(|field| is_pod_ty(&field.ty, definitions, &pod))
+            })
+            .cloned()
+            .collect();
+        if dropped.is_empty() {
+            break;
+        }
+        for qname in dropped {
+            pod.remove(&qname);
+        }
+    }
+
+    for (qname, item) in definitions.iter_mut() {
+        if let Item::Record(record) = item {
+            record.pod = pod.contains(qname);
+        }
+    }
+}
+
+/// True for scalars other than `char` (which C has no equivalent of), the records in `pod`,
+/// and (unvalidated) newtypes of those. Boxed user types are pointers, so they are not.
+fn is_pod_ty(ty: &Ty, definitions: &BTreeMap<QualifiedName, Item>, pod: &BTreeSet<QualifiedName>) -> bool {
+    match ty.kind() {
+        TypeKind::Scalar(scalar) => *scalar != Scalar::Char,
+        TypeKind::UserType { qname, repr } if repr.is_direct() => match definitions.get(qname) {
+            Some(Item::Record(_)) => pod.contains(qname),
+            Some(Item::Newtype(newtype)) => !newtype.validated && is_pod_ty(&newtype.ty, definitions, pod),
+            _ => false,
+        },
+        _ => false,
+    }
+}
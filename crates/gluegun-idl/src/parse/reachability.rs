@@ -0,0 +1,267 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Edition, ModulePath, Name, QualifiedName, SourcePath, Span};
+
+use super::{cfg::CfgSet, util};
+
+/// What a module declares, as far as reachability is concerned (see [`PublicPaths::new`]).
+/// Built from the syntax alone, before the definitions are recognized, as that only needs to be done for the reachable ones.
+pub(super) struct ModuleScan {
+    module_path: ModulePath,
+
+    /// The names the module declares or imports.
+    entries: Vec<(Name, Entry)>,
+
+    /// The modules declared with `mod name;`, whose contents are in another file.
+    pub(super) submodules: Vec<Submodule>,
+}
+
+/// A module declared with `mod name;` (see [`ModuleScan::submodules`]).
+pub(super) struct Submodule {
+    pub(super) name: Name,
+
+    /// The file given by a `#[path = "..."]` attribute, if any.
+    pub(super) path_attr: Option<String>,
+
+    /// Where the module is declared.
+    pub(super) span: Span,
+}
+
+enum Entry {
+    /// A public definition (e.g., `pub struct Config`), whether or not it can be recognized.
+    Definition,
+
+    /// A module, whose public definitions are reachable through it if it is `public`.
+    Module { public: bool },
+
+    /// An import of the given path, which it makes reachable if it is `public` (e.g., `pub use crate::settings::Config`).
+    Reexport { target: QualifiedName, public: bool },
+}
+
+impl ModuleScan {
+    /// Scan the items of the module `module_path` parsed from `source`.
+    /// Items are skipped as in pass 1: those whose `#[cfg]` is not enabled with `cfg` in all cases,
+    /// and those that are not public or are ignored (see [`util::ignore`]) unless they are modules or imports,
+    /// which are still used to resolve names.
+    pub(super) fn new(
+        module_path: &ModulePath,
+        source: &SourcePath,
+        ast: &syn::File,
+        extern_crates: &[Name],
+        cfg: &CfgSet,
+        doc_hidden: bool,
+        edition: Edition,
+    ) -> Self {
+        let mut entries = vec![];
+        let mut submodules = vec![];
+        for item in &ast.items {
+            let (vis, attrs, ident) = match item {
+                syn::Item::Struct(item) => (&item.vis, &item.attrs, &item.ident),
+                syn::Item::Enum(item) => (&item.vis, &item.attrs, &item.ident),
+                syn::Item::Fn(item) => (&item.vis, &item.attrs, &item.sig.ident),
+                syn::Item::Trait(item) => (&item.vis, &item.attrs, &item.ident),
+                syn::Item::Type(item) => (&item.vis, &item.attrs, &item.ident),
+                syn::Item::Mod(item) => (&item.vis, &item.attrs, &item.ident),
+                syn::Item::Use(item) => {
+                    if util::ignore_from_attrs(&item.attrs, cfg) {
+                        continue;
+                    }
+                    let public = !util::ignore(&item.vis, &item.attrs, cfg, doc_hidden);
+                    // Imports that cannot be resolved are reported by pass 1, if they matter.
+                    if let Ok(reexports) = util::use_reexports(&item.tree, module_path, extern_crates, &ast.items, edition) {
+                        entries.extend(
+                            reexports
+                                .into_iter()
+                                .map(|(name, target)| (name, Entry::Reexport { target, public })),
+                        );
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            if util::cfg_disabled(attrs, cfg) {
+                continue;
+            }
+            let public = !util::ignore(vis, attrs, cfg, doc_hidden);
+            let name = util::recognize_name(ident);
+            match item {
+                syn::Item::Mod(item_mod) if item_mod.content.is_none() => {
+                    submodules.push(Submodule {
+                        name: name.clone(),
+                        path_attr: path_attr(&item_mod.attrs),
+                        span: source.span(item_mod),
+                    });
+                    entries.push((name, Entry::Module { public }));
+                }
+                _ if public => entries.push((name, Entry::Definition)),
+                _ => {}
+            }
+        }
+
+        Self {
+            module_path: module_path.clone(),
+            entries,
+            submodules,
+        }
+    }
+}
+
+/// The value of the `#[path = "..."]` attribute in `attrs`, if any.
+fn path_attr(attrs: &[syn::Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(text), .. }),
+            ..
+        }) if path.is_ident("path") => Some(text.value()),
+        _ => None,
+    })
+}
+
+/// The path by which each public definition can be reached from outside of its crate.
+///
+/// Being `pub` is not enough: the modules leading to the definition must be `pub` too,
+/// or it must be re-exported with a `pub use` from a module that is reachable (e.g., `pub use crate::shapes::Circle`),
+/// so definitions in private modules and those only imported with `pub(crate) use` are left out.
+/// If a definition can be reached by several paths, the shortest one is its public path
+/// (preferring the path where it is defined, and then the first in alphabetical order),
+/// by which it is then named in the IDL.
+#[derive(Default, Hash)]
+pub(super) struct PublicPaths {
+    /// The public path of each reachable definition, keyed by the path where it is defined.
+    public_paths: BTreeMap<QualifiedName, QualifiedName>,
+
+    /// The path where each reachable definition is defined, keyed by its public path.
+    definition_paths: BTreeMap<QualifiedName, QualifiedName>,
+}
+
+impl PublicPaths {
+    /// Find the public paths of the definitions in the modules of `scans`, starting from the root of each crate.
+    pub(super) fn new(scans: &[ModuleScan]) -> Self {
+        let mut reachability = Reachability {
+            entries: BTreeMap::new(),
+            modules: BTreeMap::new(),
+            candidates: BTreeMap::new(),
+        };
+        for scan in scans {
+            reachability.modules.insert(scan.module_path.qname().clone(), &scan.entries);
+            for (name, entry) in &scan.entries {
+                reachability.entries.insert(scan.module_path.join(name.clone()), entry);
+            }
+        }
+
+        for scan in scans {
+            let root = scan.module_path.qname();
+            if root.names.len() == 1 {
+                reachability.walk(root, root, &mut vec![root.clone()]);
+            }
+        }
+
+        let public_paths = reachability.candidates;
+        let definition_paths = public_paths
+            .iter()
+            .map(|(definition, public)| (public.clone(), definition.clone()))
+            .collect();
+        Self { public_paths, definition_paths }
+    }
+
+    /// The public path of the definition at `definition`, if it is reachable.
+    pub(super) fn public_path(&self, definition: &QualifiedName) -> Option<&QualifiedName> {
+        self.public_paths.get(definition)
+    }
+
+    /// The path where the definition reachable at `public` is defined, if any.
+    pub(super) fn definition_path(&self, public: &QualifiedName) -> Option<&QualifiedName> {
+        self.definition_paths.get(public)
+    }
+
+    /// The public paths of the reachable definitions of `module`, keyed by the path where they are defined.
+    pub(super) fn in_module(&self, module: &ModulePath) -> BTreeMap<QualifiedName, QualifiedName> {
+        self.public_paths
+            .iter()
+            .filter(|(definition, _)| definition.module_path() == *module)
+            .map(|(definition, public)| (definition.clone(), public.clone()))
+            .collect()
+    }
+}
+
+/// The state of [`PublicPaths::new`][].
+struct Reachability<'s> {
+    /// The entry for each name declared or imported by a module, keyed by its path in the module.
+    entries: BTreeMap<QualifiedName, &'s Entry>,
+
+    /// The entries of each module (including crate roots), keyed by its path.
+    modules: BTreeMap<QualifiedName, &'s Vec<(Name, Entry)>>,
+
+    /// The best public path found so far for each definition, keyed by the path where it is defined.
+    candidates: BTreeMap<QualifiedName, QualifiedName>,
+}
+
+impl Reachability<'_> {
+    /// Visit the public entries of `module`, which is reachable at `public` (and defined at the paths in `stack`).
+    fn walk(&mut self, module: &QualifiedName, public: &QualifiedName, stack: &mut Vec<QualifiedName>) {
+        let Some(entries) = self.modules.get(module).copied() else {
+            return;
+        };
+        for (name, entry) in entries {
+            let is_public = match entry {
+                Entry::Definition => true,
+                Entry::Module { public } | Entry::Reexport { public, .. } => *public,
+            };
+            if !is_public {
+                continue;
+            }
+
+            let Some(target) = self.resolve(&module.join(name.clone()), 0) else {
+                continue;
+            };
+            // Definitions of other crates are reachable through their own crate.
+            if target.names[0] != module.names[0] {
+                continue;
+            }
+
+            let path = public.join(name.clone());
+            if self.modules.contains_key(&target) {
+                if !stack.contains(&target) {
+                    stack.push(target.clone());
+                    self.walk(&target, &path, stack);
+                    stack.pop();
+                }
+            } else if matches!(self.entries.get(&target), Some(Entry::Definition)) {
+                let better = match self.candidates.get(&target) {
+                    None => true,
+                    Some(best) => preference(&target, &path) < preference(&target, best),
+                };
+                if better {
+                    self.candidates.insert(target, path);
+                }
+            }
+        }
+    }
+
+    /// Follow the imports in `path` (e.g., `my_crate::api::Config` for `pub use crate::settings as api`)
+    /// to the path where what it refers to is declared. Returns `None` for a cycle of imports.
+    fn resolve(&self, path: &QualifiedName, depth: usize) -> Option<QualifiedName> {
+        if depth > self.entries.len() {
+            return None;
+        }
+        let mut resolved = QualifiedName::new(vec![]);
+        for name in &path.names {
+            resolved = resolved.join(name.clone());
+            let mut visited = BTreeSet::new();
+            while let Some(Entry::Reexport { target, .. }) = self.entries.get(&resolved) {
+                if !visited.insert(resolved.clone()) {
+                    return None;
+                }
+                resolved = self.resolve(target, depth + 1)?;
+            }
+        }
+        Some(resolved)
+    }
+}
+
+/// Orders the public paths of the definition at `definition` from best to worst (see [`PublicPaths`][]).
+fn preference<'p>(definition: &QualifiedName, path: &'p QualifiedName) -> (usize, bool, &'p QualifiedName) {
+    (path.names.len(), path != definition, path)
+}
@@ -1,19 +1,53 @@
-use crate::Name;
+use crate::{Edition, ModulePath, Name, QualifiedName};
+
+use super::cfg::{self, CfgSet};
 
 /// If true, ignore this item.
-pub(super) fn ignore(vis: &syn::Visibility, attrs: &[syn::Attribute]) -> bool {
+/// `cfg` is what the `#[cfg]` attributes of the crate are evaluated against (see [`ignore_from_attrs`]).
+/// Unless `doc_hidden`, items tagged `#[doc(hidden)]` are ignored too: they are public only
+/// for technical reasons (see [`crate::Parser::with_doc_hidden`]).
+pub(super) fn ignore(
+    vis: &syn::Visibility,
+    attrs: &[syn::Attribute],
+    cfg: &CfgSet,
+    doc_hidden: bool,
+) -> bool {
     // Only look at public things
     if !is_public(vis) {
         return true;
     }
 
-    ignore_from_attrs(attrs)
+    if !doc_hidden && is_doc_hidden(attrs) {
+        return true;
+    }
+
+    ignore_from_attrs(attrs, cfg)
 }
 
-pub(super) fn ignore_from_attrs(attrs: &[syn::Attribute]) -> bool {
-    // Only look at things that are not cfg(test)
-    if attrs.iter().any(|attr| attr.path().is_ident("cfg")) {
-        // FIXME: check that the attribute is test
+/// True if the item with the given attributes is tagged `#[doc(hidden)]`.
+fn is_doc_hidden(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        let syn::Meta::List(list) = &attr.meta else {
+            return false;
+        };
+        if !list.path.is_ident("doc") {
+            return false;
+        }
+        let mut hidden = false;
+        let _ = list.parse_nested_meta(|meta| {
+            hidden |= meta.path.is_ident("hidden");
+            Ok(())
+        });
+        hidden
+    })
+}
+
+/// If true, ignore the item with the given attributes: it is either tagged with `gluegun::ignore`
+/// or has a `#[cfg]` attribute that is not enabled with `cfg`.
+/// If the features are not known, any `#[cfg]` that depends on a feature is considered disabled (and likewise for other options).
+pub(super) fn ignore_from_attrs(attrs: &[syn::Attribute], cfg: &CfgSet) -> bool {
+    // Only look at things that are compiled in (e.g., not `cfg(test)` or `cfg(feature = "x")` for a disabled `x`)
+    if cfg_disabled(attrs, cfg) {
         return true;
     }
 
@@ -26,6 +60,196 @@ pub(super) fn ignore_from_attrs(attrs: &[syn::Attribute]) -> bool {
     false
 }
 
+/// True if the item with the given attributes has a `#[cfg]` attribute that is not enabled with `cfg`,
+/// i.e., it is not compiled in.
+pub(super) fn cfg_disabled(attrs: &[syn::Attribute], cfg: &CfgSet) -> bool {
+    attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("cfg") && !cfg::cfg_enabled(attr, cfg))
+}
+
+/// True if the struct with the given attributes is tagged `#[gluegun::opaque]` (or just `#[opaque]`):
+/// it is a resource even if it has public fields, which are exposed as getters.
+pub(super) fn is_opaque(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        let segments: Vec<String> = attr.path().segments.iter().map(|segment| segment.ident.to_string()).collect();
+        segments == ["opaque"] || segments == ["gluegun", "opaque"]
+    })
+}
+
+/// The overload group named by a `#[gluegun::overload(name)]` (or just `#[overload(name)]`) attribute
+/// in `attrs`, if any (see [`Method::overload`](`crate::Method::overload`)).
+/// If the attribute does not name a group, it is returned as the error.
+pub(super) fn overload_group(attrs: &[syn::Attribute]) -> Result<Option<Name>, &syn::Attribute> {
+    let Some(attr) = attrs.iter().find(|attr| {
+        let segments: Vec<String> = attr.path().segments.iter().map(|segment| segment.ident.to_string()).collect();
+        segments == ["overload"] || segments == ["gluegun", "overload"]
+    }) else {
+        return Ok(None);
+    };
+    match attr.parse_args::<syn::Ident>() {
+        Ok(ident) => Ok(Some(recognize_name(&ident))),
+        Err(_) => Err(attr),
+    }
+}
+
+/// The type named by a `#[gluegun::marshal_as(Type)]` (or just `#[marshal_as(Type)]`) attribute in `attrs`, if any
+/// (see [`Newtype::validated`](`crate::Newtype::validated`)).
+/// If the attribute does not name a type, it is returned as the error.
+pub(super) fn marshal_as(attrs: &[syn::Attribute]) -> Result<Option<syn::Type>, &syn::Attribute> {
+    let Some(attr) = attrs.iter().find(|attr| {
+        let segments: Vec<String> = attr.path().segments.iter().map(|segment| segment.ident.to_string()).collect();
+        segments == ["marshal_as"] || segments == ["gluegun", "marshal_as"]
+    }) else {
+        return Ok(None);
+    };
+    match attr.parse_args::<syn::Type>() {
+        Ok(ty) => Ok(Some(ty)),
+        Err(_) => Err(attr),
+    }
+}
+
+/// The names that the `use` item `tree` in `module` imports, along with the paths of the definitions they refer to
+/// (e.g., `Config` and `my_crate::settings::Config` for `use crate::settings::Config`).
+/// Paths can begin with `crate`, `self`, `super`, the name of one of the `extern_crates`,
+/// or that of a submodule declared among `items` (the items of `module`).
+/// In the 2015 `edition`, paths other than those are instead relative to the root of the crate (as are the standard crates,
+/// which cannot be resolved either way).
+/// Returns the part of the tree that cannot be resolved (e.g., a glob, or a path into `std`) as the error.
+pub(super) fn use_reexports<'t>(
+    tree: &'t syn::UseTree,
+    module: &ModulePath,
+    extern_crates: &[Name],
+    items: &[syn::Item],
+    edition: Edition,
+) -> Result<Vec<(Name, QualifiedName)>, &'t syn::UseTree> {
+    let syn::UseTree::Path(path) = tree else {
+        return Err(tree);
+    };
+
+    // Where the path starts, and the rest of it.
+    let (prefix, rest) = if path.ident == "crate" {
+        (module.crate_root().qname().clone(), &*path.tree)
+    } else if path.ident == "self" {
+        (module.qname().clone(), &*path.tree)
+    } else if extern_crates.iter().any(|name| path.ident == name.text) {
+        (QualifiedName::new(vec![]), tree)
+    } else if edition == Edition::E2015 && !["super", "std", "core", "alloc"].iter().any(|name| path.ident == name) {
+        (module.crate_root().qname().clone(), tree)
+    } else if items.iter().any(|item| matches!(item, syn::Item::Mod(item_mod) if item_mod.ident == path.ident)) {
+        (module.qname().clone(), tree)
+    } else if path.ident == "super" {
+        let mut prefix = module.qname().clone();
+        let mut rest = tree;
+        while let syn::UseTree::Path(path) = rest {
+            if path.ident != "super" {
+                break;
+            }
+            if prefix.names.len() < 2 {
+                // Above the root of the crate
+                return Err(rest);
+            }
+            prefix = ModulePath::containing(&prefix).qname().clone();
+            rest = &path.tree;
+        }
+        (prefix, rest)
+    } else {
+        return Err(tree);
+    };
+
+    let mut reexports = vec![];
+    collect_use_tree(&prefix, rest, &mut reexports)?;
+    Ok(reexports)
+}
+
+/// Add the names imported by `tree` to `reexports`, where `prefix` is the path leading up to `tree` (see [`use_reexports`]).
+fn collect_use_tree<'t>(
+    prefix: &QualifiedName,
+    tree: &'t syn::UseTree,
+    reexports: &mut Vec<(Name, QualifiedName)>,
+) -> Result<(), &'t syn::UseTree> {
+    match tree {
+        syn::UseTree::Path(path) => collect_use_tree(&prefix.join(recognize_name(&path.ident)), &path.tree, reexports),
+        // `use crate::settings::{self, Config}` imports the module `settings` itself.
+        syn::UseTree::Name(name) if name.ident == "self" && !prefix.names.is_empty() => {
+            reexports.push((prefix.tail_name(), prefix.clone()));
+            Ok(())
+        }
+        syn::UseTree::Name(name) => {
+            let name = recognize_name(&name.ident);
+            reexports.push((name.clone(), prefix.join(name)));
+            Ok(())
+        }
+        syn::UseTree::Rename(rename) => {
+            reexports.push((recognize_name(&rename.rename), prefix.join(recognize_name(&rename.ident))));
+            Ok(())
+        }
+        syn::UseTree::Group(group) => {
+            for tree in &group.items {
+                collect_use_tree(prefix, tree, reexports)?;
+            }
+            Ok(())
+        }
+        syn::UseTree::Glob(_) => Err(tree),
+    }
+}
+
+/// Extract the Rust code examples from the doc comment in `attrs`:
+/// the fenced code blocks that rustdoc would compile, i.e., those with no language
+/// or with `rust` and that are not marked `ignore` or `compile_fail`.
+/// Returns each example along with the attribute containing its opening fence.
+pub(super) fn doc_examples(attrs: &[syn::Attribute]) -> Vec<(&syn::Attribute, String)> {
+    // Each `///` line is a separate `#[doc = "..."]` attribute; a `/** */` comment is one attribute with many lines.
+    let lines = attrs.iter().filter(|attr| attr.path().is_ident("doc")).flat_map(|attr| {
+        let text = match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(text), .. }),
+                ..
+            }) => text.value(),
+            _ => String::new(),
+        };
+        text.lines().map(|line| (attr, line.to_string())).collect::<Vec<_>>()
+    });
+
+    let mut examples = vec![];
+    let mut open: Option<(&syn::Attribute, &'static str, bool, Vec<String>)> = None;
+    for (attr, line) in lines {
+        let trimmed = line.trim();
+        match &mut open {
+            None => {
+                let Some(fence) = ["```", "~~~"].into_iter().find(|fence| trimmed.starts_with(fence)) else {
+                    continue;
+                };
+                let is_rust = trimmed[fence.len()..]
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|word| !word.is_empty())
+                    .all(|word| {
+                        matches!(word, "rust" | "no_run" | "should_panic") || word.starts_with("edition")
+                    });
+                open = Some((attr, fence, is_rust, vec![]));
+            }
+            Some((_, fence, _, _)) if trimmed.starts_with(*fence) => {
+                let (attr, _, is_rust, code) = open.take().unwrap();
+                if is_rust {
+                    examples.push((attr, code.join("\n")));
+                }
+            }
+            Some((_, _, _, code)) => {
+                // rustdoc hides lines that are `#` or start with `# `, and `##` escapes a `#`.
+                let line = line.strip_prefix(' ').unwrap_or(&line);
+                let code_line = match line.trim_start().strip_prefix('#') {
+                    Some(rest) if rest.is_empty() || rest.starts_with([' ', '#']) => {
+                        rest.strip_prefix(' ').unwrap_or(rest)
+                    }
+                    _ => line,
+                };
+                code.push(code_line.to_string());
+            }
+        }
+    }
+    examples
+}
+
 /// Returns true if this is fully public.
 /// Non-public items don't concern us.
 pub(super) fn is_public(vis: &syn::Visibility) -> bool {
@@ -41,3 +265,29 @@ pub(super) fn recognize_name(ident: &syn::Ident) -> Name {
         text: ident.to_string(),
     }
 }
+
+/// The first `'static` lifetime of a reference in `ty` (e.g., in `Option<&'static str>`), if any.
+pub(super) fn static_reference(ty: &syn::Type) -> Option<&syn::Lifetime> {
+    struct StaticReferenceVisitor<'ast> {
+        found: Option<&'ast syn::Lifetime>,
+    }
+
+    impl<'ast> syn::visit::Visit<'ast> for StaticReferenceVisitor<'ast> {
+        fn visit_type_reference(&mut self, reference: &'ast syn::TypeReference) {
+            match &reference.lifetime {
+                Some(lifetime) if lifetime.ident == "static" => self.found = self.found.or(Some(lifetime)),
+                _ => syn::visit::visit_type_reference(self, reference),
+            }
+        }
+    }
+
+    let mut visitor = StaticReferenceVisitor { found: None };
+    syn::visit::Visit::visit_type(&mut visitor, ty);
+    visitor.found
+}
+
+/// True if the generics of a function only declare lifetimes, without where-clauses
+/// (e.g., `fn get<'a>(&'a self) -> &'a str`), which are permitted as references are copied across the boundary.
+pub(super) fn lifetimes_only(generics: &syn::Generics) -> bool {
+    generics.params.iter().all(|param| matches!(param, syn::GenericParam::Lifetime(_))) && generics.where_clause.is_none()
+}
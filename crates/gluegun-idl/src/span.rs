@@ -1,4 +1,7 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use accessors_rs::Accessors;
 use serde::{Deserialize, Serialize};
@@ -39,6 +42,19 @@ pub struct ErrorLocation {
     pub(crate) column: usize,
 }
 
+/// `path` relative to `root` (if any), with `/` as separator; `.` for `root` itself.
+/// Paths outside of `root` are returned as given.
+pub(crate) fn relative_path(root: Option<&Path>, path: &Path) -> PathBuf {
+    let Some(relative) = root.and_then(|root| path.strip_prefix(root).ok()) else {
+        return path.to_path_buf();
+    };
+    let components: Vec<_> = relative.components().map(|component| component.as_os_str().to_string_lossy()).collect();
+    if components.is_empty() {
+        return PathBuf::from(".");
+    }
+    PathBuf::from(components.join("/"))
+}
+
 /// Wrapper around a source path for constructing ErrorSpans.
 #[derive(Clone, Debug)]
 pub(crate) struct SourcePath {
@@ -52,6 +68,10 @@ impl SourcePath {
         }
     }
 
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
     /// Create an error span from a [`Span`][].
     /// There is no stable API to access path info, so pass that separately.
     pub(crate) fn span(&self, span: impl Spanned) -> Span {
@@ -0,0 +1,94 @@
+//! A compact, human-readable rendering of the IDL, in a syntax close to Rust (and to WIT), e.g.
+//!
+//! ```text
+//! package greetings;
+//! path "src/lib.rs";
+//! version "0.1.0";
+//!
+//! resource greetings::Greetings: Send + Sync + Unpin @1:12-1:21 {
+//!     constructor fn new() -> greetings::Greetings @6:12-6:15;
+//!     builder fn language(self, language: &str) -> greetings::Greetings @12:12-12:20;
+//!     fn greet(&self, name: String) -> String throws anyhow::Error @17:12-17:17;
+//! }
+//!
+//! pod record greetings::Point @22:12-22:17 {
+//!     x: f64 @23:9-23:10,
+//!     y: f64 @24:9-24:10,
+//! }
+//! ```
+//!
+//! Items are listed by their qualified name, and types are spelled as in Rust, keeping the form they
+//! have in the source (e.g., `&str`, `impl ToString`, or `String`), except that user types are
+//! fully qualified. Functions and methods that return an error type list it after `throws`.
+//! Records that are POD-compatible (see [`Record::pod`](`crate::Record::pod`)) are marked `pod`,
+//! and those packing the parameters of a function (see [`Record::params_of`](`crate::Record::params_of`))
+//! are prefixed with `params(function)`. Newtypes tagged `#[gluegun::marshal_as]`
+//! (see [`Newtype::validated`](`crate::Newtype::validated`)) are marked `validated`.
+//! Methods in a group of overloads (see [`Method::overload`](`crate::Method::overload`)) are prefixed
+//! with `overload(group)`.
+//!
+//! Declarations end with their span (`@line:column-line:column`) in the file named by the preceding
+//! `file` directive (the crate's `path` if there is none). Byte offsets and the spans of types are not
+//! recorded: when reading, types are given the span of their declaration. Spans may be left out
+//! when writing an IDL by hand, in which case they are `@0:0-0:0` (and are left out when written back).
+//!
+//! [`TextOptions`][] canonicalize the rendering for snapshots (e.g., in tests), without changing the [`Idl`][]:
+//! paths can be made relative to a root, and spans left out so that moving code around does not change the text.
+
+mod reader;
+mod writer;
+
+use std::path::PathBuf;
+
+use crate::Idl;
+
+/// How [`to_text_with`][] renders the paths and spans of an [`Idl`][].
+/// The default renders them as recorded.
+#[derive(Clone, Debug)]
+pub struct TextOptions {
+    source_root: Option<PathBuf>,
+    spans: bool,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self { source_root: None, spans: true }
+    }
+}
+
+impl TextOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render the paths of the crates and source files relative to `root`, with `/` as separator
+    /// (as [`Parser::with_source_root`](`crate::Parser::with_source_root`) records them). Paths outside of `root`
+    /// are rendered as recorded.
+    pub fn with_source_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.source_root = Some(root.into());
+        self
+    }
+
+    /// Whether to render the spans of declarations (on by default). Without them, the text only changes
+    /// when the definitions do, not when they move in the source; reading it back gives `@0:0-0:0` spans.
+    pub fn with_spans(mut self, spans: bool) -> Self {
+        self.spans = spans;
+        self
+    }
+}
+
+/// Render `idl` in the text format.
+pub fn to_text(idl: &Idl) -> String {
+    to_text_with(idl, &TextOptions::default())
+}
+
+/// Render `idl` in the text format, with its paths and spans rendered as `options` say.
+pub fn to_text_with(idl: &Idl, options: &TextOptions) -> String {
+    writer::write(idl, options)
+}
+
+/// Read an [`Idl`][] written in the text format (e.g., by [`to_text`][]).
+/// Errors are [`Error::Parse`](`crate::Error::Parse`) errors with the line and column where reading failed.
+pub fn from_text(text: &str) -> crate::Result<Idl> {
+    reader::read(text)
+}
@@ -0,0 +1,867 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::{
+    AutoTraits, CrateMetadata, DocExample, Enum, EnumArm, Error, ErrorLocation, ErrorRepr, ExternCrate, Field, Function,
+    FunctionInput, FunctionOutput, FutureRepr, Idl, Interface, IsAsync, Item, MapSetRepr, Method, MethodCategory,
+    Name, NetAddrRepr, Newtype, OptionRepr, PathRepr, QualifiedName, Record, RefKind, RefdTy, Resource, ResultRepr,
+    Scalar, SelfKind, Signature, Span, StreamRepr, StringRepr, TimestampRepr, TraitObjectRepr, TupleRepr, Ty, TypeKind, UuidRepr,
+    UserTypeRepr, Variant, VariantArm, VecRepr,
+};
+
+pub(super) fn read(text: &str) -> crate::Result<Idl> {
+    let tokens = tokenize(text)?;
+    Reader { tokens, position: 0, file: PathBuf::new() }.idl()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    /// An identifier, keyword, or number, e.g. `fn`, `my_crate`, or `12`.
+    Word(String),
+
+    /// A string literal, unescaped.
+    Str(String),
+
+    /// `::`
+    PathSep,
+
+    /// `->`
+    Arrow,
+
+    /// Any other punctuation, e.g. `{` or `@`.
+    Punct(char),
+
+    Eof,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "`{word}`"),
+            Token::Str(text) => write!(f, "{text:?}"),
+            Token::PathSep => write!(f, "`::`"),
+            Token::Arrow => write!(f, "`->`"),
+            Token::Punct(c) => write!(f, "`{c}`"),
+            Token::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+/// A token along with the line and column (both 1-indexed) where it starts.
+struct Located {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+fn tokenize(text: &str) -> crate::Result<Vec<Located>> {
+    let mut tokens = vec![];
+    let mut chars = text.char_indices().peekable();
+    let (mut line, mut line_start) = (1, 0);
+    while let Some((index, c)) = chars.next() {
+        let column = text[line_start..index].chars().count() + 1;
+        let token = match c {
+            '\n' => {
+                line += 1;
+                line_start = index + 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            c if c.is_alphanumeric() || c == '_' || c == '#' => {
+                let mut word = c.to_string();
+                while let Some((_, c)) = chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_' || *c == '#') {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+            '"' => {
+                // Find the closing quote, skipping escaped characters, and unescape as JSON.
+                let mut end = None;
+                while let Some((index, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => {
+                            end = Some(index);
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                let Some(end) = end else {
+                    return Err(Error::Parse(format!("{line}:{column}: unterminated string")));
+                };
+                let literal = &text[index..=end];
+                let value = serde_json::from_str(literal)
+                    .map_err(|error| Error::Parse(format!("{line}:{column}: invalid string: {error}")))?;
+                Token::Str(value)
+            }
+            ':' if chars.next_if(|(_, c)| *c == ':').is_some() => Token::PathSep,
+            '-' if chars.next_if(|(_, c)| *c == '>').is_some() => Token::Arrow,
+            c => Token::Punct(c),
+        };
+        tokens.push(Located { token, line, column });
+    }
+
+    let column = text[line_start..].chars().count() + 1;
+    tokens.push(Located { token: Token::Eof, line, column });
+    Ok(tokens)
+}
+
+struct Reader {
+    tokens: Vec<Located>,
+    position: usize,
+
+    /// The file that spans refer to (see the `file` directive).
+    file: PathBuf,
+}
+
+impl Reader {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].token
+    }
+
+    fn peek_nth(&self, n: usize) -> &Token {
+        let index = (self.position + n).min(self.tokens.len() - 1);
+        &self.tokens[index].token
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.peek().clone();
+        if token != Token::Eof {
+            self.position += 1;
+        }
+        token
+    }
+
+    /// An error at the current token, which was not the `expected` one.
+    fn unexpected(&self, expected: &str) -> Error {
+        let Located { token, line, column } = &self.tokens[self.position];
+        Error::Parse(format!("{line}:{column}: expected {expected}, found {token}"))
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Token::Word(w) if w == word)
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let found = self.is_word(word);
+        if found {
+            self.next();
+        }
+        found
+    }
+
+    fn expect_word(&mut self, word: &str) -> crate::Result<()> {
+        if self.eat_word(word) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{word}`")))
+        }
+    }
+
+    fn eat(&mut self, token: Token) -> bool {
+        let found = *self.peek() == token;
+        if found {
+            self.next();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: Token) -> crate::Result<()> {
+        if self.eat(token.clone()) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&token.to_string()))
+        }
+    }
+
+    fn expect_punct(&mut self, c: char) -> crate::Result<()> {
+        self.expect(Token::Punct(c))
+    }
+
+    fn word(&mut self) -> crate::Result<String> {
+        match self.peek() {
+            Token::Word(word) => {
+                let word = word.clone();
+                self.next();
+                Ok(word)
+            }
+            _ => Err(self.unexpected("a name")),
+        }
+    }
+
+    fn name(&mut self) -> crate::Result<Name> {
+        Ok(Name::from(self.word()?))
+    }
+
+    fn string(&mut self) -> crate::Result<String> {
+        match self.peek() {
+            Token::Str(text) => {
+                let text = text.clone();
+                self.next();
+                Ok(text)
+            }
+            _ => Err(self.unexpected("a string")),
+        }
+    }
+
+    fn number(&mut self) -> crate::Result<usize> {
+        match self.peek() {
+            Token::Word(word) if word.parse::<usize>().is_ok() => {
+                let number = word.parse().unwrap();
+                self.next();
+                Ok(number)
+            }
+            _ => Err(self.unexpected("a number")),
+        }
+    }
+
+    /// `a::b::C`
+    fn qname(&mut self) -> crate::Result<QualifiedName> {
+        let mut names = vec![self.name()?];
+        while self.eat(Token::PathSep) {
+            names.push(self.name()?);
+        }
+        Ok(QualifiedName::new(names))
+    }
+
+    /// ` "a", "b"` (possibly empty)
+    fn strings(&mut self) -> crate::Result<Vec<String>> {
+        let mut values = vec![];
+        if matches!(self.peek(), Token::Str(_)) {
+            values.push(self.string()?);
+            while self.eat(Token::Punct(',')) {
+                values.push(self.string()?);
+            }
+        }
+        Ok(values)
+    }
+
+    /// `@line:column-line:column`, if present.
+    fn span(&mut self) -> crate::Result<Span> {
+        let mut location = [0; 4];
+        if self.eat(Token::Punct('@')) {
+            location[0] = self.number()?;
+            self.expect_punct(':')?;
+            location[1] = self.number()?;
+            self.expect_punct('-')?;
+            location[2] = self.number()?;
+            self.expect_punct(':')?;
+            location[3] = self.number()?;
+        }
+        let [start_line, start_column, end_line, end_column] = location;
+        Ok(Span {
+            path: self.file.clone(),
+            start: ErrorLocation { byte: 0, line: start_line, column: start_column },
+            end: ErrorLocation { byte: 0, line: end_line, column: end_column },
+        })
+    }
+
+    fn idl(mut self) -> crate::Result<Idl> {
+        self.expect_word("package")?;
+        let crate_name = self.name()?;
+        self.expect_punct(';')?;
+        self.expect_word("path")?;
+        let crate_path = PathBuf::from(self.string()?);
+        self.expect_punct(';')?;
+        self.file = crate_path.clone();
+
+        let mut crate_metadata = CrateMetadata::default();
+        let mut crate_features = None;
+        let mut extern_crates = vec![];
+        loop {
+            if self.eat_word("version") {
+                crate_metadata.version = Some(self.string()?);
+            } else if self.eat_word("description") {
+                crate_metadata.description = Some(self.string()?);
+            } else if self.eat_word("repository") {
+                crate_metadata.repository = Some(self.string()?);
+            } else if self.eat_word("features") {
+                crate_features = Some(self.strings()?);
+            } else if self.eat_word("extern") {
+                self.expect_word("crate")?;
+                let crate_name = self.name()?;
+                self.expect_word("path")?;
+                let crate_path = PathBuf::from(self.string()?);
+                let features = if self.eat_word("features") { Some(self.strings()?) } else { None };
+                extern_crates.push(ExternCrate { crate_name, crate_path, features });
+            } else {
+                break;
+            }
+            self.expect_punct(';')?;
+        }
+
+        let mut definitions = BTreeMap::new();
+        while *self.peek() != Token::Eof {
+            if self.eat_word("file") {
+                self.file = PathBuf::from(self.string()?);
+                self.expect_punct(';')?;
+                continue;
+            }
+            let (qname, item) = self.item()?;
+            definitions.insert(qname, item);
+        }
+
+        Ok(Idl { crate_name, crate_path, crate_metadata, crate_features, extern_crates, definitions })
+    }
+
+    fn item(&mut self) -> crate::Result<(QualifiedName, Item)> {
+        if self.is_word("fn") || self.is_word("async") {
+            return self.function();
+        }
+
+        // Records packing the parameters of a function are written `params(function) record`.
+        let params_of = if self.eat_word("params") {
+            self.expect_punct('(')?;
+            let function = self.qname()?;
+            self.expect_punct(')')?;
+            if !self.is_word("record") {
+                return Err(self.unexpected("`record`"));
+            }
+            Some(function)
+        } else {
+            None
+        };
+
+        let mut keyword = self.word().map_err(|_| self.unexpected("an item"))?;
+        // Enums implementing `std::error::Error` are written `error variant` or `error enum`.
+        let implements_error = keyword == "error";
+        if implements_error {
+            keyword = self.word().map_err(|_| self.unexpected("`variant` or `enum`"))?;
+            if keyword != "variant" && keyword != "enum" {
+                return Err(self.unexpected("`variant` or `enum`"));
+            }
+        }
+        // POD-compatible records are written `pod record`, and tuple structs `tuple record` (or `pod tuple record`).
+        let pod = keyword == "pod";
+        let tuple = if pod { self.eat_word("tuple") } else { keyword == "tuple" };
+        if pod || tuple {
+            if !self.eat_word("record") {
+                return Err(self.unexpected("`record`"));
+            }
+            keyword = "record".to_string();
+        }
+        // Newtypes tagged `#[gluegun::marshal_as]` are written `validated newtype`.
+        let validated = keyword == "validated";
+        if validated {
+            if !self.eat_word("newtype") {
+                return Err(self.unexpected("`newtype`"));
+            }
+            keyword = "newtype".to_string();
+        }
+        let qname = self.qname()?;
+        let name = qname.tail_name();
+        let item = match &keyword[..] {
+            "resource" => {
+                let auto_traits = if self.eat(Token::Punct(':')) { self.bounds(true)? } else { AutoTraits::default() };
+                let span = self.span()?;
+                self.expect_punct('{')?;
+                let mut implements = vec![];
+                while self.eat_word("implements") {
+                    implements.push(self.qname()?);
+                    self.expect_punct(';')?;
+                }
+                let methods = self.methods()?;
+                Item::Resource(Resource { span, name, methods, auto_traits, implements })
+            }
+            "record" => {
+                let span = self.span()?;
+                self.expect_punct('{')?;
+                let fields = self.fields()?;
+                let methods = self.methods()?;
+                Item::Record(Record { span, name, fields, methods, pod, tuple, params_of })
+            }
+            "newtype" => {
+                self.expect_punct('(')?;
+                let ty_start = self.position;
+                let span = {
+                    // The type is given the span of the declaration, which follows it.
+                    self.skip_to_matching(')')?;
+                    self.span()?
+                };
+                let after_span = self.position;
+                self.position = ty_start;
+                let ty = self.ty(&span)?;
+                self.expect_punct(')')?;
+                self.position = after_span;
+                self.expect_punct('{')?;
+                let methods = self.methods()?;
+                Item::Newtype(Newtype { span, name, ty, methods, validated })
+            }
+            "variant" => {
+                let span = self.span()?;
+                self.expect_punct('{')?;
+                let mut arms = vec![];
+                while matches!(self.peek(), Token::Word(_)) && !self.is_method() {
+                    let name = self.name()?;
+                    let span = self.span()?;
+                    let fields = if self.eat(Token::Punct('{')) {
+                        let fields = self.fields()?;
+                        self.expect_punct('}')?;
+                        fields
+                    } else {
+                        self.expect_punct(',')?;
+                        vec![]
+                    };
+                    arms.push(VariantArm { span, name, fields });
+                }
+                let methods = self.methods()?;
+                Item::Variant(Variant { span, name, arms, methods, implements_error })
+            }
+            "enum" => {
+                let span = self.span()?;
+                self.expect_punct('{')?;
+                let mut arms = vec![];
+                while matches!(self.peek(), Token::Word(_)) && !self.is_method() {
+                    let name = self.name()?;
+                    let span = self.span()?;
+                    self.expect_punct(',')?;
+                    arms.push(EnumArm { span, name });
+                }
+                let methods = self.methods()?;
+                Item::Enum(Enum { span, name, arms, methods, implements_error })
+            }
+            "interface" => {
+                let span = self.span()?;
+                self.expect_punct('{')?;
+                let methods = self.methods()?;
+                Item::Interface(Interface { span, name, methods })
+            }
+            _ => {
+                self.position -= 1;
+                return Err(self.unexpected("an item"));
+            }
+        };
+        Ok((qname, item))
+    }
+
+    /// `async fn a::f(x: T) -> U @1:2-1:3;` or, with examples, `... { example @1:2-1:3 "code"; }`
+    fn function(&mut self) -> crate::Result<(QualifiedName, Item)> {
+        let is_async = if self.eat_word("async") { IsAsync::Yes } else { IsAsync::No };
+        self.expect_word("fn")?;
+        let qname = self.qname()?;
+        let (self_kind, signature, span) = self.signature(is_async)?;
+        if self_kind.is_some() {
+            return Err(Error::Parse(format!("{span}: functions have no `self` parameter")));
+        }
+
+        let mut examples = vec![];
+        if self.eat(Token::Punct('{')) {
+            while self.eat_word("example") {
+                let span = self.span()?;
+                let code = self.string()?;
+                self.expect_punct(';')?;
+                examples.push(DocExample { span, code });
+            }
+            self.expect_punct('}')?;
+        } else {
+            self.expect_punct(';')?;
+        }
+
+        let name = qname.tail_name();
+        Ok((qname, Item::Function(Function { span, name, signature, examples })))
+    }
+
+    /// True if the next tokens start a method (see [`Self::methods`]).
+    fn is_method(&self) -> bool {
+        ["fn", "async", "constructor", "named", "builder", "static", "getter"].iter().any(|word| self.is_word(word))
+    }
+
+    /// The methods up to the `}` that closes the body of an item (consumed), e.g. `builder fn f(self) -> T @1:2-1:3;`
+    /// or `overload(from) named constructor fn from_str(s: &str) -> T @1:2-1:3;`.
+    fn methods(&mut self) -> crate::Result<Vec<Method>> {
+        let mut methods = vec![];
+        while !self.eat(Token::Punct('}')) {
+            let overload = if self.eat_word("overload") {
+                self.expect_punct('(')?;
+                let group = self.name()?;
+                self.expect_punct(')')?;
+                Some(group)
+            } else {
+                None
+            };
+            let prefix = if self.eat_word("getter") {
+                self.expect_punct('(')?;
+                let field = self.name()?;
+                self.expect_punct(')')?;
+                Some(MethodCategory::Getter(field))
+            } else if self.eat_word("constructor") {
+                Some(MethodCategory::Constructor)
+            } else if self.eat_word("named") {
+                self.expect_word("constructor")?;
+                Some(MethodCategory::NamedConstructor)
+            } else if self.eat_word("static") {
+                Some(MethodCategory::StaticMethod)
+            } else if self.eat_word("builder") {
+                // The kind of `self` is filled in below.
+                Some(MethodCategory::BuilderMethod(SelfKind::ByValue))
+            } else {
+                None
+            };
+            let is_async = if self.eat_word("async") { IsAsync::Yes } else { IsAsync::No };
+            if !self.is_word("fn") {
+                return Err(self.unexpected("a method or `}`"));
+            }
+            self.next();
+            let name = self.name()?;
+            let (self_kind, signature, span) = self.signature(is_async)?;
+            self.expect_punct(';')?;
+
+            let category = match (prefix, self_kind) {
+                (Some(MethodCategory::BuilderMethod(_)), Some(self_kind)) => MethodCategory::BuilderMethod(self_kind),
+                (Some(MethodCategory::Getter(field)), Some(SelfKind::ByRef)) => MethodCategory::Getter(field),
+                (
+                    Some(
+                        category @ (MethodCategory::Constructor
+                        | MethodCategory::NamedConstructor
+                        | MethodCategory::StaticMethod),
+                    ),
+                    None,
+                ) => category,
+                (None, Some(self_kind)) => MethodCategory::InstanceMethod(self_kind),
+                _ => return Err(Error::Parse(format!("{span}: unexpected `self` parameter for method `{name}`"))),
+            };
+            methods.push(Method { span, category, name, signature, overload });
+        }
+        Ok(methods)
+    }
+
+    /// `(self, x: T) -> U throws E @1:2-1:3`, returning the kind of `self` parameter (if any)
+    /// along with the signature and the span that ends it.
+    fn signature(&mut self, is_async: IsAsync) -> crate::Result<(Option<SelfKind>, Signature, Span)> {
+        // The types are given the span of the declaration, which follows them.
+        let start = self.position;
+        self.expect_punct('(')?;
+        self.skip_to_matching(')')?;
+        while !matches!(self.peek(), Token::Punct('@' | ';' | '{') | Token::Eof) {
+            self.next();
+        }
+        let span = self.span()?;
+        let end = self.position;
+        self.position = start;
+
+        self.expect_punct('(')?;
+        let self_kind = self.self_param()?;
+        let mut inputs = vec![];
+        if self_kind.is_none() || self.eat(Token::Punct(',')) {
+            while !matches!(self.peek(), Token::Punct(')')) {
+                let name = self.name()?;
+                self.expect_punct(':')?;
+                let refd_ty = self.refd_ty(&span)?;
+                inputs.push(FunctionInput { span: span.clone(), name, refd_ty });
+                if !self.eat(Token::Punct(',')) {
+                    break;
+                }
+            }
+        }
+        self.expect_punct(')')?;
+
+        let output_ty = if self.eat(Token::Arrow) {
+            let main_ty = self.refd_ty(&span)?;
+            let error_ty = if self.eat_word("throws") { Some(self.ty(&span)?) } else { None };
+            FunctionOutput { main_ty, error_ty }
+        } else {
+            FunctionOutput { main_ty: Ty::unit(span.clone()).owned(), error_ty: None }
+        };
+        if self.position != end && !matches!(self.peek(), Token::Punct('@')) {
+            return Err(self.unexpected("`@`, `;`, or `{`"));
+        }
+        self.position = end;
+
+        Ok((self_kind, Signature { is_async, inputs, output_ty }, span))
+    }
+
+    /// `self`, `&self`, `&mut self`, `self: Arc<Self>`, or `self: Rc<Self>`, if present.
+    fn self_param(&mut self) -> crate::Result<Option<SelfKind>> {
+        let self_kind = match (self.peek(), self.peek_nth(1)) {
+            (Token::Word(word), _) if word == "self" => {
+                self.next();
+                if self.eat(Token::Punct(':')) {
+                    let (pointer, self_kind) = match self.peek() {
+                        Token::Word(word) if word == "Rc" => ("Rc", SelfKind::ByRc),
+                        _ => ("Arc", SelfKind::ByArc),
+                    };
+                    self.expect_word(pointer)?;
+                    self.expect_punct('<')?;
+                    self.expect_word("Self")?;
+                    self.expect_punct('>')?;
+                    self_kind
+                } else {
+                    SelfKind::ByValue
+                }
+            }
+            (Token::Punct('&'), Token::Word(word)) if word == "self" => {
+                self.next();
+                self.next();
+                SelfKind::ByRef
+            }
+            (Token::Punct('&'), Token::Word(word)) if word == "mut" => {
+                self.next();
+                self.next();
+                self.expect_word("self")?;
+                SelfKind::ByRefMut
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(self_kind))
+    }
+
+    /// `name: T @1:2-1:3,` up to the next token that does not start a field.
+    fn fields(&mut self) -> crate::Result<Vec<Field>> {
+        let mut fields = vec![];
+        while matches!(self.peek(), Token::Word(_)) && *self.peek_nth(1) == Token::Punct(':') {
+            let name = self.name()?;
+            self.expect_punct(':')?;
+            // The type is given the span of the field, which follows it.
+            let ty_start = self.position;
+            while !matches!(self.peek(), Token::Punct('@' | ',') | Token::Eof) {
+                self.skip_token()?;
+            }
+            let span = self.span()?;
+            let after_span = self.position;
+            self.position = ty_start;
+            let ty = self.ty(&span)?;
+            self.position = after_span;
+            self.expect_punct(',')?;
+            fields.push(Field { span, name, ty });
+        }
+        Ok(fields)
+    }
+
+    /// Skip a token, or everything up to the matching closing bracket if it opens one.
+    fn skip_token(&mut self) -> crate::Result<()> {
+        match self.next() {
+            Token::Punct('(') => self.skip_to_matching(')'),
+            Token::Punct('[') => self.skip_to_matching(']'),
+            Token::Punct('<') => self.skip_to_matching('>'),
+            _ => Ok(()),
+        }
+    }
+
+    /// Skip to just after the `close` bracket matching one that was just consumed.
+    fn skip_to_matching(&mut self, close: char) -> crate::Result<()> {
+        loop {
+            match self.peek() {
+                Token::Eof => return Err(self.unexpected(&format!("`{close}`"))),
+                Token::Punct(c) if *c == close => {
+                    self.next();
+                    return Ok(());
+                }
+                _ => self.skip_token()?,
+            }
+        }
+    }
+
+    /// `&T`, `impl AsRef<T>`, or `T`.
+    fn refd_ty(&mut self, span: &Span) -> crate::Result<RefdTy> {
+        if self.eat(Token::Punct('&')) {
+            Ok(self.ty(span)?.refd(RefKind::AnonRef))
+        } else if self.is_word("impl") && *self.peek_nth(1) == Token::Word("AsRef".to_string()) {
+            self.next();
+            self.next();
+            self.expect_punct('<')?;
+            let ty = self.ty(span)?;
+            self.expect_punct('>')?;
+            Ok(ty.refd(RefKind::ImplAsRef))
+        } else {
+            Ok(self.ty(span)?.owned())
+        }
+    }
+
+    /// A type, spelled as written by `writer::ty`. Its span is `span`, as is that of the types in it.
+    fn ty(&mut self, span: &Span) -> crate::Result<Ty> {
+        let kind = if self.eat(Token::Punct('(')) {
+            let mut elements = vec![];
+            while !self.eat(Token::Punct(')')) {
+                elements.push(self.ty(span)?);
+                if !self.eat(Token::Punct(',')) {
+                    self.expect_punct(')')?;
+                    break;
+                }
+            }
+            TypeKind::Tuple { repr: TupleRepr::Tuple(elements.len()), elements }
+        } else if self.eat(Token::Punct('[')) {
+            let element = self.ty(span)?;
+            self.expect_punct(']')?;
+            TypeKind::Vec { element, repr: VecRepr::SliceRef }
+        } else if self.eat_word("impl") {
+            if self.eat_word("ToString") {
+                TypeKind::String { repr: StringRepr::ImplToString }
+            } else if self.eat_word("IntoIterator") {
+                let element = self.associated_ty("Item", span)?;
+                TypeKind::Vec { element, repr: VecRepr::ImplIntoIterator }
+            } else if self.eat_word("Future") {
+                let output = self.associated_ty("Output", span)?;
+                TypeKind::Future { output, repr: FutureRepr::ImplFuture(self.bounds(false)?) }
+            } else if self.eat_word("Stream") {
+                let item = self.associated_ty("Item", span)?;
+                TypeKind::Stream { item, repr: StreamRepr::ImplStream(self.bounds(false)?) }
+            } else {
+                return Err(self.unexpected("`ToString`, `IntoIterator`, `Future`, or `Stream`"));
+            }
+        } else if self.eat_word("dyn") {
+            let qname = self.qname()?;
+            TypeKind::TraitObject { qname, repr: TraitObjectRepr::Dyn(self.bounds(false)?) }
+        } else if self.eat_word("custom") {
+            TypeKind::Custom { path: self.string()? }
+        } else {
+            let start = self.position;
+            let qname = self.qname()?;
+            let names: Vec<&str> = qname.names().iter().map(|name| name.text.as_str()).collect();
+            match names[..] {
+                ["anyhow", "Error"] => TypeKind::Error { repr: ErrorRepr::AnyhowError },
+                ["std", "io", "Error"] => TypeKind::Error { repr: ErrorRepr::IoError },
+                [_, _, ..] => TypeKind::UserType { qname, repr: UserTypeRepr::Direct },
+                [name] => match self.builtin_ty(name, span)? {
+                    Some(kind) => kind,
+                    None => {
+                        self.position = start;
+                        return Err(self.unexpected("a type"));
+                    }
+                },
+                [] => unreachable!("qualified names have a name"),
+            }
+        };
+        Ok(Ty::new(span.clone(), kind))
+    }
+
+    /// The type named `name` (e.g., `Vec`), whose arguments follow; `None` if there is no such type.
+    fn builtin_ty(&mut self, name: &str, span: &Span) -> crate::Result<Option<TypeKind>> {
+        let scalar = |scalar| Ok(Some(TypeKind::Scalar(scalar)));
+        let map = |this: &mut Self, repr| {
+            let [key, value] = this.ty_args(span)?;
+            Ok(Some(TypeKind::Map { key, value, repr }))
+        };
+        let set = |this: &mut Self, repr| {
+            let [element] = this.ty_args(span)?;
+            Ok(Some(TypeKind::Set { element, repr }))
+        };
+        let net_addr = |repr| Ok(Some(TypeKind::NetAddr { repr }));
+        let timestamp = |repr| Ok(Some(TypeKind::Timestamp { repr }));
+        match name {
+            "bool" => scalar(Scalar::Boolean),
+            "char" => scalar(Scalar::Char),
+            "i8" => scalar(Scalar::I8),
+            "i16" => scalar(Scalar::I16),
+            "i32" => scalar(Scalar::I32),
+            "i64" => scalar(Scalar::I64),
+            "u8" => scalar(Scalar::U8),
+            "u16" => scalar(Scalar::U16),
+            "u32" => scalar(Scalar::U32),
+            "u64" => scalar(Scalar::U64),
+            "f32" => scalar(Scalar::F32),
+            "f64" => scalar(Scalar::F64),
+            "String" => Ok(Some(TypeKind::String { repr: StringRepr::String })),
+            "str" => Ok(Some(TypeKind::String { repr: StringRepr::StrRef })),
+            "Path" => Ok(Some(TypeKind::Path { repr: PathRepr::PathRef })),
+            "PathBuf" => Ok(Some(TypeKind::Path { repr: PathRepr::PathBuf })),
+            "Uuid" => Ok(Some(TypeKind::Uuid { repr: UuidRepr::Uuid })),
+            "IpAddr" => net_addr(NetAddrRepr::IpAddr),
+            "Ipv4Addr" => net_addr(NetAddrRepr::Ipv4Addr),
+            "Ipv6Addr" => net_addr(NetAddrRepr::Ipv6Addr),
+            "SocketAddr" => net_addr(NetAddrRepr::SocketAddr),
+            "SocketAddrV4" => net_addr(NetAddrRepr::SocketAddrV4),
+            "SocketAddrV6" => net_addr(NetAddrRepr::SocketAddrV6),
+            "DateTime" => {
+                self.expect_punct('<')?;
+                self.expect_word("Utc")?;
+                self.expect_punct('>')?;
+                timestamp(TimestampRepr::ChronoDateTimeUtc)
+            }
+            "NaiveDate" => timestamp(TimestampRepr::ChronoNaiveDate),
+            "OffsetDateTime" => timestamp(TimestampRepr::TimeOffsetDateTime),
+            "HashMap" => map(self, MapSetRepr::Hash),
+            "BTreeMap" => map(self, MapSetRepr::BTree),
+            "IndexMap" => map(self, MapSetRepr::Index),
+            "HashSet" => set(self, MapSetRepr::Hash),
+            "BTreeSet" => set(self, MapSetRepr::BTree),
+            "IndexSet" => set(self, MapSetRepr::Index),
+            "Vec" => {
+                let [element] = self.ty_args(span)?;
+                Ok(Some(TypeKind::Vec { element, repr: VecRepr::Vec }))
+            }
+            "Option" => {
+                let [element] = self.ty_args(span)?;
+                Ok(Some(TypeKind::Option { element, repr: OptionRepr::Option }))
+            }
+            "Result" => {
+                let [ok, err] = self.ty_args(span)?;
+                Ok(Some(TypeKind::Result { ok, err, repr: ResultRepr::Result }))
+            }
+            "Box" => {
+                self.expect_punct('<')?;
+                let kind = if self.eat_word("dyn") {
+                    let qname = self.qname()?;
+                    let auto_traits = self.bounds(false)?;
+                    if qname.names().len() == 1 && qname.names()[0].text == "Error" {
+                        TypeKind::Error { repr: ErrorRepr::BoxDynError(auto_traits) }
+                    } else {
+                        TypeKind::TraitObject { qname, repr: TraitObjectRepr::BoxDyn(auto_traits) }
+                    }
+                } else {
+                    TypeKind::UserType { qname: self.qname()?, repr: UserTypeRepr::Boxed }
+                };
+                self.expect_punct('>')?;
+                Ok(Some(kind))
+            }
+            "Pin" => {
+                for (word, punct) in [("Box", '<'), ("dyn", '<'), ("Future", '<')] {
+                    if word == "Box" {
+                        self.expect_punct(punct)?;
+                    }
+                    self.expect_word(word)?;
+                }
+                let output = self.associated_ty("Output", span)?;
+                let auto_traits = self.bounds(false)?;
+                self.expect_punct('>')?;
+                self.expect_punct('>')?;
+                Ok(Some(TypeKind::Future { output, repr: FutureRepr::PinBoxDynFuture(auto_traits) }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// `<A, B, ...>` with exactly `N` types.
+    fn ty_args<const N: usize>(&mut self, span: &Span) -> crate::Result<[Ty; N]> {
+        self.expect_punct('<')?;
+        let mut tys = vec![];
+        for index in 0..N {
+            if index > 0 {
+                self.expect_punct(',')?;
+            }
+            tys.push(self.ty(span)?);
+        }
+        self.expect_punct('>')?;
+        Ok(tys.try_into().unwrap_or_else(|_| unreachable!("`N` types were read")))
+    }
+
+    /// `<Name = T>`
+    fn associated_ty(&mut self, name: &str, span: &Span) -> crate::Result<Ty> {
+        self.expect_punct('<')?;
+        self.expect_word(name)?;
+        self.expect_punct('=')?;
+        let ty = self.ty(span)?;
+        self.expect_punct('>')?;
+        Ok(ty)
+    }
+
+    /// Auto traits like ` + Send + Sync`, or, if `first` (after a `:`), `Send + Sync`.
+    fn bounds(&mut self, first: bool) -> crate::Result<AutoTraits> {
+        let mut auto_traits = AutoTraits::default();
+        let mut expect_trait = first;
+        while expect_trait || self.eat(Token::Punct('+')) {
+            expect_trait = false;
+            match &self.word()?[..] {
+                "Send" => auto_traits.send = true,
+                "Sync" => auto_traits.sync = true,
+                "Unpin" => auto_traits.unpin = true,
+                _ => {
+                    self.position -= 1;
+                    return Err(self.unexpected("`Send`, `Sync`, or `Unpin`"));
+                }
+            }
+        }
+        Ok(auto_traits)
+    }
+}
@@ -0,0 +1,309 @@
+use std::{fmt::Write, path::Path};
+
+use super::TextOptions;
+use crate::{
+    AutoTraits, ErrorRepr, Field, FutureRepr, Idl, IsAsync, Item, MapSetRepr, Method, MethodCategory, OwnedKind,
+    PathRepr, QualifiedName, RefKind, RefdTy, SelfKind, Signature, Span, StreamRepr, StringRepr, TraitObjectRepr, Ty, TypeKind,
+    UserTypeRepr, VecRepr,
+};
+
+const INDENT: &str = "    ";
+
+pub(super) fn write(idl: &Idl, options: &TextOptions) -> String {
+    let writer = Writer { options };
+    let mut out = String::new();
+    writer.write_header(&mut out, idl);
+
+    let mut file = idl.crate_path().as_path();
+    for (qname, item) in idl.definitions() {
+        let path = item.span().path();
+        if path != file {
+            writeln!(out).unwrap();
+            writeln!(out, "file {};", writer.path(path)).unwrap();
+            file = path;
+        }
+        writeln!(out).unwrap();
+        writer.write_item(&mut out, qname, item);
+    }
+    out
+}
+
+/// Renders what depends on the [`TextOptions`][]: paths and spans.
+struct Writer<'o> {
+    options: &'o TextOptions,
+}
+
+impl Writer<'_> {
+    fn write_header(&self, out: &mut String, idl: &Idl) {
+        writeln!(out, "package {};", idl.crate_name()).unwrap();
+        writeln!(out, "path {};", self.path(idl.crate_path())).unwrap();
+
+        let metadata = idl.crate_metadata();
+        for (key, value) in [
+            ("version", metadata.version()),
+            ("description", metadata.description()),
+            ("repository", metadata.repository()),
+        ] {
+            if let Some(value) = value {
+                writeln!(out, "{key} {};", string(value)).unwrap();
+            }
+        }
+        if let Some(features) = idl.crate_features() {
+            writeln!(out, "features{};", strings(features)).unwrap();
+        }
+
+        for extern_crate in idl.extern_crates() {
+            write!(out, "extern crate {} path {}", extern_crate.crate_name(), self.path(extern_crate.crate_path())).unwrap();
+            if let Some(features) = extern_crate.features() {
+                write!(out, " features{}", strings(features)).unwrap();
+            }
+            writeln!(out, ";").unwrap();
+        }
+    }
+
+    fn write_item(&self, out: &mut String, qname: &QualifiedName, item: &Item) {
+        let qname = qname.colon_colon();
+        let mut body = vec![];
+        match item {
+            Item::Function(function) => {
+                let header = signature(&qname, None, function.signature());
+                if function.examples().is_empty() {
+                    writeln!(out, "{header}{};", self.span(function.span())).unwrap();
+                    return;
+                }
+                write!(out, "{header}{}", self.span(function.span())).unwrap();
+                for example in function.examples() {
+                    body.push(format!("example{} {};", self.span(example.span()), string(example.code())));
+                }
+            }
+            Item::Resource(resource) => {
+                write!(out, "resource {qname}{}{}", bounds(": ", resource.auto_traits()), self.span(resource.span())).unwrap();
+                for interface in resource.implements() {
+                    body.push(format!("implements {};", interface.colon_colon()));
+                }
+                body.extend(resource.methods().iter().map(|m| self.method(m)));
+            }
+            Item::Record(record) => {
+                let pod = if *record.pod() { "pod " } else { "" };
+                let tuple = if *record.tuple() { "tuple " } else { "" };
+                let params = match record.params_of() {
+                    Some(function) => format!("params({}) ", function.colon_colon()),
+                    None => String::new(),
+                };
+                write!(out, "{params}{pod}{tuple}record {qname}{}", self.span(record.span())).unwrap();
+                body.extend(record.fields().iter().map(|f| self.field(f)));
+                body.extend(record.methods().iter().map(|m| self.method(m)));
+            }
+            Item::Newtype(newtype) => {
+                let validated = if *newtype.validated() { "validated " } else { "" };
+                write!(out, "{validated}newtype {qname}({}){}", ty(newtype.ty()), self.span(newtype.span())).unwrap();
+                body.extend(newtype.methods().iter().map(|m| self.method(m)));
+            }
+            Item::Variant(variant) => {
+                let error = if *variant.implements_error() { "error " } else { "" };
+                write!(out, "{error}variant {qname}{}", self.span(variant.span())).unwrap();
+                for arm in variant.arms() {
+                    if arm.fields().is_empty() {
+                        body.push(format!("{}{},", arm.name(), self.span(arm.span())));
+                    } else {
+                        body.push(format!("{}{} {{", arm.name(), self.span(arm.span())));
+                        body.extend(arm.fields().iter().map(|f| format!("{INDENT}{}", self.field(f))));
+                        body.push("}".to_string());
+                    }
+                }
+                body.extend(variant.methods().iter().map(|m| self.method(m)));
+            }
+            Item::Enum(an_enum) => {
+                let error = if *an_enum.implements_error() { "error " } else { "" };
+                write!(out, "{error}enum {qname}{}", self.span(an_enum.span())).unwrap();
+                body.extend(an_enum.arms().iter().map(|arm| format!("{}{},", arm.name(), self.span(arm.span()))));
+                body.extend(an_enum.methods().iter().map(|m| self.method(m)));
+            }
+            Item::Interface(interface) => {
+                write!(out, "interface {qname}{}", self.span(interface.span())).unwrap();
+                body.extend(interface.methods().iter().map(|m| self.method(m)));
+            }
+        }
+
+        if body.is_empty() {
+            writeln!(out, " {{}}").unwrap();
+        } else {
+            writeln!(out, " {{").unwrap();
+            for line in body {
+                writeln!(out, "{INDENT}{line}").unwrap();
+            }
+            writeln!(out, "}}").unwrap();
+        }
+    }
+
+    fn field(&self, field: &Field) -> String {
+        format!("{}: {}{},", field.name(), ty(field.ty()), self.span(field.span()))
+    }
+
+    fn method(&self, method: &Method) -> String {
+        let overload = match method.overload() {
+            Some(group) => format!("overload({group}) "),
+            None => String::new(),
+        };
+        let prefix = match method.category() {
+            MethodCategory::Constructor => "constructor ".to_string(),
+            MethodCategory::NamedConstructor => "named constructor ".to_string(),
+            MethodCategory::BuilderMethod(_) => "builder ".to_string(),
+            MethodCategory::StaticMethod => "static ".to_string(),
+            MethodCategory::Getter(field) => format!("getter({field}) "),
+            MethodCategory::InstanceMethod(_) => String::new(),
+        };
+        let self_param = method.category().self_kind().map(|self_kind| match self_kind {
+            SelfKind::ByValue => "self",
+            SelfKind::ByRef => "&self",
+            SelfKind::ByRefMut => "&mut self",
+            SelfKind::ByArc => "self: Arc<Self>",
+            SelfKind::ByRc => "self: Rc<Self>",
+        });
+        format!(
+            "{overload}{prefix}{}{};",
+            signature(&method.name().to_string(), self_param, method.signature()),
+            self.span(method.span())
+        )
+    }
+
+    /// ` @line:column-line:column`, or nothing if spans are left out (see [`TextOptions::with_spans`][])
+    /// or unknown (`@0:0-0:0`, which is what reading a declaration without span gives).
+    fn span(&self, span: &Span) -> String {
+        if !self.options.spans || (*span.start().line() == 0 && *span.end().line() == 0) {
+            return String::new();
+        }
+        format!(" @{}:{}-{}:{}", span.start().line(), span.start().column(), span.end().line(), span.end().column())
+    }
+
+    /// `path` as a string literal, relative to the source root of the options, if any.
+    fn path(&self, path: &Path) -> String {
+        string(&crate::span::relative_path(self.options.source_root.as_deref(), path).display().to_string())
+    }
+}
+
+/// E.g. `async fn name(&self, a: &str) -> String throws anyhow::Error`.
+fn signature(name: &str, self_param: Option<&str>, signature: &Signature) -> String {
+    let inputs: Vec<String> = self_param
+        .map(str::to_string)
+        .into_iter()
+        .chain(
+            signature
+                .inputs()
+                .iter()
+                .map(|input| format!("{}: {}", input.name(), refd_ty(input.refd_ty()))),
+        )
+        .collect();
+    let async_kw = match signature.is_async() {
+        IsAsync::Yes => "async ",
+        IsAsync::No => "",
+    };
+    let output = signature.output_ty();
+    let mut text = format!("{async_kw}fn {name}({})", inputs.join(", "));
+    if !output.is_unit() || output.error_ty().is_some() {
+        write!(text, " -> {}", refd_ty(output.main_ty())).unwrap();
+    }
+    if let Some(error_ty) = output.error_ty() {
+        write!(text, " throws {}", ty(error_ty)).unwrap();
+    }
+    text
+}
+
+fn refd_ty(refd_ty: &RefdTy) -> String {
+    match refd_ty {
+        RefdTy::Owned(OwnedKind::Owned, t) => ty(t),
+        RefdTy::Ref(RefKind::AnonRef, t) => format!("&{}", ty(t)),
+        RefdTy::Ref(RefKind::ImplAsRef, t) => format!("impl AsRef<{}>", ty(t)),
+    }
+}
+
+fn ty(ty: &Ty) -> String {
+    let map_set = |repr: &MapSetRepr, kind: &str| {
+        let prefix = match repr {
+            MapSetRepr::Hash => "Hash",
+            MapSetRepr::BTree => "BTree",
+            MapSetRepr::Index => "Index",
+        };
+        format!("{prefix}{kind}")
+    };
+    match ty.kind() {
+        TypeKind::Map { key, value, repr } => format!("{}<{}, {}>", map_set(repr, "Map"), self::ty(key), self::ty(value)),
+        TypeKind::Set { element, repr } => format!("{}<{}>", map_set(repr, "Set"), self::ty(element)),
+        TypeKind::Vec { element, repr } => match repr {
+            VecRepr::Vec => format!("Vec<{}>", self::ty(element)),
+            VecRepr::SliceRef => format!("[{}]", self::ty(element)),
+            VecRepr::ImplIntoIterator => format!("impl IntoIterator<Item = {}>", self::ty(element)),
+        },
+        TypeKind::Path { repr } => match repr {
+            PathRepr::PathRef => "Path".to_string(),
+            PathRepr::PathBuf => "PathBuf".to_string(),
+        },
+        TypeKind::NetAddr { repr } => repr.name().to_string(),
+        TypeKind::Uuid { repr: _ } => "Uuid".to_string(),
+        TypeKind::Timestamp { repr } => repr.name().to_string(),
+        TypeKind::String { repr } => match repr {
+            StringRepr::String => "String".to_string(),
+            StringRepr::StrRef => "str".to_string(),
+            StringRepr::ImplToString => "impl ToString".to_string(),
+        },
+        TypeKind::Option { element, repr: _ } => format!("Option<{}>", self::ty(element)),
+        TypeKind::Result { ok, err, repr: _ } => format!("Result<{}, {}>", self::ty(ok), self::ty(err)),
+        TypeKind::Tuple { elements, repr: _ } => match &elements[..] {
+            [element] => format!("({},)", self::ty(element)),
+            _ => format!("({})", elements.iter().map(self::ty).collect::<Vec<_>>().join(", ")),
+        },
+        TypeKind::Scalar(scalar) => scalar.to_string(),
+        TypeKind::Future { output, repr } => match repr {
+            FutureRepr::ImplFuture(auto_traits) => {
+                format!("impl Future<Output = {}>{}", self::ty(output), bounds(" + ", auto_traits))
+            }
+            FutureRepr::PinBoxDynFuture(auto_traits) => {
+                format!("Pin<Box<dyn Future<Output = {}>{}>>", self::ty(output), bounds(" + ", auto_traits))
+            }
+        },
+        TypeKind::Stream { item, repr } => match repr {
+            StreamRepr::ImplStream(auto_traits) => {
+                format!("impl Stream<Item = {}>{}", self::ty(item), bounds(" + ", auto_traits))
+            }
+        },
+        TypeKind::Error { repr } => match repr {
+            ErrorRepr::AnyhowError => "anyhow::Error".to_string(),
+            ErrorRepr::IoError => "std::io::Error".to_string(),
+            ErrorRepr::BoxDynError(auto_traits) => format!("Box<dyn Error{}>", bounds(" + ", auto_traits)),
+        },
+        TypeKind::UserType { qname, repr } => match repr {
+            UserTypeRepr::Direct => qname.colon_colon(),
+            UserTypeRepr::Boxed => format!("Box<{}>", qname.colon_colon()),
+        },
+        TypeKind::TraitObject { qname, repr } => match repr {
+            TraitObjectRepr::Dyn(auto_traits) => format!("dyn {}{}", qname.colon_colon(), bounds(" + ", auto_traits)),
+            TraitObjectRepr::BoxDyn(auto_traits) => {
+                format!("Box<dyn {}{}>", qname.colon_colon(), bounds(" + ", auto_traits))
+            }
+        },
+        TypeKind::Custom { path } => format!("custom {}", string(path)),
+    }
+}
+
+/// The auto traits, e.g. `Send + Sync`, preceded by `prefix`; empty if there are none.
+fn bounds(prefix: &str, auto_traits: &AutoTraits) -> String {
+    let traits: Vec<&str> = [(auto_traits.send(), "Send"), (auto_traits.sync(), "Sync"), (auto_traits.unpin(), "Unpin")]
+        .into_iter()
+        .filter_map(|(present, name)| present.then_some(name))
+        .collect();
+    if traits.is_empty() {
+        String::new()
+    } else {
+        format!("{prefix}{}", traits.join(" + "))
+    }
+}
+
+/// A string literal, with the escapes of JSON.
+fn string(text: &str) -> String {
+    serde_json::to_string(text).expect("strings can be serialized")
+}
+
+/// ` "a", "b"` for the strings `a` and `b`.
+fn strings(values: &[String]) -> String {
+    values.iter().map(|value| format!(" {}", string(value))).collect::<Vec<_>>().join(",")
+}
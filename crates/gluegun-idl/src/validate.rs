@@ -0,0 +1,230 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{FunctionOutput, Idl, Item, Method, QualifiedName, Signature, Span, Ty, TypeKind};
+
+/// A problem found by [`Idl::validate`][].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Diagnostic {
+    /// The item `item` references the user type `ty` (at `span`), but `ty` is not
+    /// defined in the IDL, e.g., because it was excluded by a filter or marked `#[gluegun::ignore]`.
+    UnexportedType {
+        span: Span,
+        item: QualifiedName,
+        ty: QualifiedName,
+    },
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Diagnostic::UnexportedType { span, item, ty } => write!(
+                f,
+                "{span}: `{item}` references `{ty}`, which is not exported",
+                item = item.colon_colon(),
+                ty = ty.colon_colon(),
+            ),
+        }
+    }
+}
+
+impl Idl {
+    /// Check that the IDL is closed, i.e., that every user type referenced from an item is itself
+    /// defined in the IDL. This always holds for a freshly parsed IDL but may not after definitions
+    /// have been removed (see [`Idl::retain_definitions`][]).
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        for (qname, item) in &self.definitions {
+            for (ty_qname, span) in item_user_types(item) {
+                if !self.definitions.contains_key(ty_qname) {
+                    diagnostics.push(Diagnostic::UnexportedType {
+                        span: span.clone(),
+                        item: qname.clone(),
+                        ty: ty_qname.clone(),
+                    });
+                }
+            }
+        }
+        diagnostics.sort();
+        diagnostics.dedup();
+        diagnostics
+    }
+
+    /// Return the user types that contain themselves, directly or through other types
+    /// (e.g., `struct Tree { children: Vec<Tree> }`). Only the fields of records, newtypes, and variants
+    /// are considered, as resources are opaque. Backends that generate value types may need
+    /// to introduce indirection (or refuse) when a type is recursive.
+    pub fn recursive_types(&self) -> BTreeSet<QualifiedName> {
+        let edges = self.field_edges();
+        edges
+            .keys()
+            .filter(|&&start| {
+                // Search for a path from `start` back to itself. The visited set
+                // ensures we terminate even if there are other cycles along the way.
+                let mut visited = BTreeSet::new();
+                let mut stack: Vec<&QualifiedName> = edges[start].iter().copied().collect();
+                while let Some(qname) = stack.pop() {
+                    if qname == start {
+                        return true;
+                    }
+                    if visited.insert(qname) {
+                        stack.extend(edges.get(qname).into_iter().flatten().copied());
+                    }
+                }
+                false
+            })
+            .map(|&qname| qname.clone())
+            .collect()
+    }
+
+    /// Return the definitions ordered so that each comes after the user types that its fields reference,
+    /// e.g. for languages in which types must be declared before they are used. As for [`Idl::recursive_types`][],
+    /// only the fields of records, newtypes, and variants are considered. The cycle of a recursive type
+    /// is broken at the type first reached; otherwise, definitions are ordered by name.
+    pub fn dependency_order(&self) -> Vec<(&QualifiedName, &Item)> {
+        fn visit<'i>(
+            qname: &'i QualifiedName,
+            edges: &BTreeMap<&'i QualifiedName, BTreeSet<&'i QualifiedName>>,
+            visited: &mut BTreeSet<&'i QualifiedName>,
+            order: &mut Vec<&'i QualifiedName>,
+        ) {
+            // Marking the type as visited before its dependencies is what breaks cycles.
+            if !visited.insert(qname) {
+                return;
+            }
+            for &dependency in edges.get(qname).into_iter().flatten() {
+                visit(dependency, edges, visited, order);
+            }
+            order.push(qname);
+        }
+
+        let edges = self.field_edges();
+        let mut visited = BTreeSet::new();
+        let mut order = vec![];
+        for &qname in edges.keys() {
+            visit(qname, &edges, &mut visited, &mut order);
+        }
+        order
+            .into_iter()
+            .filter_map(|qname| Some((qname, self.definitions.get(qname)?)))
+            .collect()
+    }
+
+    /// Return `qname` along with the definitions it references, directly or through other definitions
+    /// (in fields, signatures, or as the interfaces a resource implements). Keeping only these
+    /// (see [`Idl::retain_definitions`][]) leaves an IDL in which the item can be generated on its own.
+    pub fn dependencies(&self, qname: &QualifiedName) -> BTreeSet<QualifiedName> {
+        let mut dependencies = BTreeSet::new();
+        let mut stack = vec![qname];
+        while let Some(qname) = stack.pop() {
+            let Some(item) = self.definitions.get(qname) else {
+                continue;
+            };
+            if !dependencies.insert(qname.clone()) {
+                continue;
+            }
+            stack.extend(item_user_types(item).into_iter().map(|(ty_qname, _)| ty_qname));
+            if let Item::Resource(resource) = item {
+                stack.extend(&resource.implements);
+            }
+        }
+        dependencies
+    }
+
+    /// The user types referenced from the fields of each definition (see [`Idl::recursive_types`][]).
+    fn field_edges(&self) -> BTreeMap<&QualifiedName, BTreeSet<&QualifiedName>> {
+        self.definitions
+            .iter()
+            .map(|(qname, item)| {
+                let field_tys: Vec<&Ty> = match item {
+                    Item::Record(record) => record.fields.iter().map(|field| &field.ty).collect(),
+                    Item::Newtype(newtype) => vec![&newtype.ty],
+                    Item::Variant(variant) => variant.arms.iter().flat_map(|arm| &arm.fields).map(|field| &field.ty).collect(),
+                    _ => vec![],
+                };
+                let mut referenced = vec![];
+                for ty in field_tys {
+                    ty_user_types(ty, &mut referenced);
+                }
+                (qname, referenced.into_iter().map(|(qname, _)| qname).collect())
+            })
+            .collect()
+    }
+}
+
+/// The user types referenced from `item` (in fields or signatures), with their spans.
+fn item_user_types(item: &Item) -> Vec<(&QualifiedName, &Span)> {
+    let mut result = vec![];
+    let methods: &[Method] = match item {
+        Item::Resource(resource) => &resource.methods,
+        Item::Record(record) => {
+            for field in &record.fields {
+                ty_user_types(&field.ty, &mut result);
+            }
+            &record.methods
+        }
+        Item::Newtype(newtype) => {
+            ty_user_types(&newtype.ty, &mut result);
+            &newtype.methods
+        }
+        Item::Variant(variant) => {
+            for field in variant.arms.iter().flat_map(|arm| &arm.fields) {
+                ty_user_types(&field.ty, &mut result);
+            }
+            &variant.methods
+        }
+        Item::Enum(an_enum) => &an_enum.methods,
+        Item::Function(function) => {
+            signature_user_types(&function.signature, &mut result);
+            &[]
+        }
+        Item::Interface(interface) => &interface.methods,
+    };
+    for method in methods {
+        signature_user_types(&method.signature, &mut result);
+    }
+    result
+}
+
+fn signature_user_types<'i>(signature: &'i Signature, result: &mut Vec<(&'i QualifiedName, &'i Span)>) {
+    for input in &signature.inputs {
+        ty_user_types(input.refd_ty.ty(), result);
+    }
+    let FunctionOutput { main_ty, error_ty } = &signature.output_ty;
+    ty_user_types(main_ty.ty(), result);
+    if let Some(error_ty) = error_ty {
+        ty_user_types(error_ty, result);
+    }
+}
+
+fn ty_user_types<'i>(ty: &'i Ty, result: &mut Vec<(&'i QualifiedName, &'i Span)>) {
+    match ty.kind() {
+        TypeKind::Map { key, value, repr: _ } => {
+            ty_user_types(key, result);
+            ty_user_types(value, result);
+        }
+        TypeKind::Vec { element, repr: _ }
+        | TypeKind::Set { element, repr: _ }
+        | TypeKind::Option { element, repr: _ } => ty_user_types(element, result),
+        TypeKind::Result { ok, err, repr: _ } => {
+            ty_user_types(ok, result);
+            ty_user_types(err, result);
+        }
+        TypeKind::Tuple { elements, repr: _ } => {
+            for element in elements {
+                ty_user_types(element, result);
+            }
+        }
+        TypeKind::Future { output, repr: _ } => ty_user_types(output, result),
+        TypeKind::Stream { item, repr: _ } => ty_user_types(item, result),
+        TypeKind::UserType { qname, repr: _ } | TypeKind::TraitObject { qname, repr: _ } => result.push((qname, ty.span())),
+        TypeKind::Path { .. }
+        | TypeKind::NetAddr { .. }
+        | TypeKind::Uuid { .. }
+        | TypeKind::Timestamp { .. }
+        | TypeKind::String { .. }
+        | TypeKind::Scalar(_)
+        | TypeKind::Error { .. }
+        | TypeKind::Custom { .. } => {}
+    }
+}
@@ -9,6 +9,7 @@ use crate::util::make_java_class_files_directory;
 
 /// build-rs helper: compile all `java` files in `java_src` and
 /// store into `$OUT_DIR/java_class_files`.
+/// The classes are loaded from the class path, which ignores modules, so a `module-info.java` is skipped.
 ///
 /// Adjust `CLASSPATH` and set the variable for rustc.
 ///
@@ -16,10 +17,11 @@ use crate::util::make_java_class_files_directory;
 pub fn build_rs_main() -> anyhow::Result<()> {
     let java_class_files = make_java_class_files_directory()?;
     let new_classpath = init_classpath(&java_class_files);
-    for java_path in java_files("java_src".as_ref()) {
-        compile_java(&java_path, &java_class_files, &new_classpath)?;
+    let java_paths: Vec<PathBuf> = java_files("java_src".as_ref()).collect();
+    for java_path in &java_paths {
+        println!("cargo:rerun-if-changed={}", java_path.display());
     }
-    Ok(())
+    compile_java(&java_paths, &java_class_files, &new_classpath)
 }
 
 fn init_classpath(java_class_files: &Path) -> String {
@@ -41,24 +43,36 @@ fn java_files(java_src: &Path) -> impl Iterator<Item = PathBuf> {
                 .map(|ext| ext == "java")
                 .unwrap_or(false)
         })
+        .filter(|e| e.file_name() != "module-info.java")
         .map(|e| e.into_path())
 }
 
+/// Compile the `java_paths` together (so that they can refer to each other regardless of their order)
+/// into `java_class_files`, failing with the output of `javac` if it reports errors.
 fn compile_java(
-    java_path: &Path,
+    java_paths: &[PathBuf],
     java_class_files: &Path,
     new_classpath: &str,
 ) -> anyhow::Result<()> {
-    println!("cargo:rerun-if-changed={}", java_path.display());
+    if java_paths.is_empty() {
+        return Ok(());
+    }
 
-    Command::new("javac")
+    let output = Command::new("javac")
         .arg("-d")
         .arg(&java_class_files)
         .arg("-cp")
         .arg(&new_classpath)
-        .arg(&java_path)
+        .args(java_paths)
         .output()
-        .with_context(|| format!("invoking `javac` on `{}`", java_path.display()))?;
+        .context("invoking `javac`")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`javac` failed with {}:\n{}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,595 @@
+use std::{any::Any, io::ErrorKind, panic::AssertUnwindSafe, sync::OnceLock};
+
+use jni::{
+    objects::{GlobalRef, JClass, JMethodID, JObject, JStaticMethodID, JString, JThrowable, JValue, JValueOwned},
+    signature::{Primitive, ReturnType},
+    sys::{jboolean, jbyte, jdouble, jfloat, jint, jlong, jobject, jshort},
+    JNIEnv, JavaVM,
+};
+
+/// A Java primitive type (e.g., `jint`) whose boxed class (e.g., `java.lang.Integer`)
+/// is converted with method IDs that are resolved on first use and cached for the lifetime of the process.
+pub trait JavaPrimitive: Copy + Sized + 'static {
+    /// The JNI name of the boxed class, e.g. `java/lang/Integer`.
+    const CLASS: &'static str;
+
+    /// The method of the boxed class returning the primitive value, e.g. `intValue`.
+    const UNBOX: &'static str;
+
+    /// The JNI type signature of the primitive type, e.g. `I`.
+    const SIGNATURE: &'static str;
+
+    const PRIMITIVE: Primitive;
+
+    /// The cache of the method IDs of the boxed class.
+    fn ids() -> &'static OnceLock<BoxedIds>;
+
+    fn from_value(value: JValueOwned<'_>) -> jni::errors::Result<Self>;
+
+    fn to_value(self) -> JValue<'static, 'static>;
+}
+
+/// Method IDs of a boxed class, see [`JavaPrimitive`].
+pub struct BoxedIds {
+    class: GlobalRef,
+    value_of: JStaticMethodID,
+    unbox: JMethodID,
+}
+
+macro_rules! java_primitive {
+    ($ty:ty, $class:literal, $unbox:literal, $signature:literal, $primitive:ident, $variant:ident, $from_value:expr) => {
+        impl JavaPrimitive for $ty {
+            const CLASS: &'static str = $class;
+            const UNBOX: &'static str = $unbox;
+            const SIGNATURE: &'static str = $signature;
+            const PRIMITIVE: Primitive = Primitive::$primitive;
+
+            fn ids() -> &'static OnceLock<BoxedIds> {
+                static IDS: OnceLock<BoxedIds> = OnceLock::new();
+                &IDS
+            }
+
+            fn from_value(value: JValueOwned<'_>) -> jni::errors::Result<Self> {
+                $from_value(value)
+            }
+
+            fn to_value(self) -> JValue<'static, 'static> {
+                JValue::$variant(self)
+            }
+        }
+    };
+}
+
+java_primitive!(jboolean, "java/lang/Boolean", "booleanValue", "Z", Boolean, Bool, |value: JValueOwned<'_>| value.z().map(jboolean::from));
+java_primitive!(jbyte, "java/lang/Byte", "byteValue", "B", Byte, Byte, JValueOwned::b);
+java_primitive!(jshort, "java/lang/Short", "shortValue", "S", Short, Short, JValueOwned::s);
+java_primitive!(jint, "java/lang/Integer", "intValue", "I", Int, Int, JValueOwned::i);
+java_primitive!(jlong, "java/lang/Long", "longValue", "J", Long, Long, JValueOwned::j);
+java_primitive!(jfloat, "java/lang/Float", "floatValue", "F", Float, Float, JValueOwned::f);
+java_primitive!(jdouble, "java/lang/Double", "doubleValue", "D", Double, Double, JValueOwned::d);
+
+/// Resolve (or fetch from the cache) the method IDs of the boxed class of `T`.
+fn boxed_ids<T: JavaPrimitive>(env: &mut JNIEnv<'_>) -> jni::errors::Result<&'static BoxedIds> {
+    if let Some(ids) = T::ids().get() {
+        return Ok(ids);
+    }
+    let class = env.find_class(T::CLASS)?;
+    let value_of = env.get_static_method_id(&class, "valueOf", format!("({})L{};", T::SIGNATURE, T::CLASS))?;
+    let unbox = env.get_method_id(&class, T::UNBOX, format!("(){}", T::SIGNATURE))?;
+    let class = env.new_global_ref(class)?;
+    Ok(T::ids().get_or_init(|| BoxedIds { class, value_of, unbox }))
+}
+
+/// Convert a (nullable) boxed Java value, e.g. an `Integer`, into its primitive value.
+pub fn unbox<T: JavaPrimitive>(env: &mut JNIEnv<'_>, value: &JObject<'_>) -> jni::errors::Result<Option<T>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let ids = boxed_ids::<T>(env)?;
+    // SAFETY: `unbox` was resolved on the boxed class of `T` with the signature `()T`.
+    let result = unsafe { env.call_method_unchecked(value, ids.unbox, ReturnType::Primitive(T::PRIMITIVE), &[]) }?;
+    T::from_value(result).map(Some)
+}
+
+/// Convert a primitive value into a boxed Java value, e.g. an `Integer`, with `None` becoming `null`.
+pub fn box_value<T: JavaPrimitive>(env: &mut JNIEnv<'_>, value: Option<T>) -> jni::errors::Result<jobject> {
+    box_object(env, value).map(JObject::into_raw)
+}
+
+/// Like [`box_value`], but returning a local reference, e.g. to pass it as the argument of a Java method.
+pub fn box_object<'local, T: JavaPrimitive>(
+    env: &mut JNIEnv<'local>,
+    value: Option<T>,
+) -> jni::errors::Result<JObject<'local>> {
+    let Some(value) = value else {
+        return Ok(JObject::null());
+    };
+    let ids = boxed_ids::<T>(env)?;
+    let class = <&JClass<'_>>::from(ids.class.as_obj());
+    // SAFETY: `value_of` was resolved on `class` with the signature `(T)L{class};`.
+    let result = unsafe {
+        env.call_static_method_unchecked(class, ids.value_of, ReturnType::Object, &[value.to_value().as_jni()])
+    }?;
+    result.l()
+}
+
+/// A Rust value (e.g., a `u32`) received from Java as a JNI value (e.g., a `jint`): the argument of a native method
+/// or the result of a Java method (see [`from_java_value`]).
+pub trait FromJava<'local>: Sized {
+    /// The JNI type, e.g. `jint`.
+    type Java: TryFrom<JValueOwned<'local>, Error = jni::errors::Error>;
+
+    fn from_java(env: &mut JNIEnv<'local>, value: Self::Java) -> jni::errors::Result<Self>;
+}
+
+/// A Rust value (e.g., a `u32`) passed to Java as a JNI value (e.g., a `jint`): the result of a native method
+/// or the argument of a Java method (as a [`JValueOwned`]).
+pub trait ToJava<'local> {
+    /// The JNI type, e.g. `jint`.
+    type Java: Into<JValueOwned<'local>>;
+
+    fn to_java(self, env: &mut JNIEnv<'local>) -> jni::errors::Result<Self::Java>;
+}
+
+/// Convert the result of a Java method into a Rust value.
+pub fn from_java_value<'local, T: FromJava<'local>>(
+    env: &mut JNIEnv<'local>,
+    value: JValueOwned<'local>,
+) -> jni::errors::Result<T> {
+    T::from_java(env, T::Java::try_from(value)?)
+}
+
+macro_rules! java_scalar {
+    ($($ty:ty => $java:ty),*) => {
+        $(
+            impl<'local> FromJava<'local> for $ty {
+                type Java = $java;
+
+                fn from_java(_env: &mut JNIEnv<'local>, value: $java) -> jni::errors::Result<Self> {
+                    Ok(value as $ty)
+                }
+            }
+
+            impl<'local> ToJava<'local> for $ty {
+                type Java = $java;
+
+                fn to_java(self, _env: &mut JNIEnv<'local>) -> jni::errors::Result<$java> {
+                    Ok(self as $java)
+                }
+            }
+        )*
+    };
+}
+
+// Java has no unsigned types: unsigned values are passed as the signed type of the same size.
+java_scalar!(
+    i8 => jbyte, u8 => jbyte, i16 => jshort, u16 => jshort, i32 => jint, u32 => jint,
+    i64 => jlong, u64 => jlong, f32 => jfloat, f64 => jdouble
+);
+
+impl<'local> FromJava<'local> for bool {
+    type Java = jboolean;
+
+    fn from_java(_env: &mut JNIEnv<'local>, value: jboolean) -> jni::errors::Result<Self> {
+        Ok(value != jni::sys::JNI_FALSE)
+    }
+}
+
+impl<'local> ToJava<'local> for bool {
+    type Java = jboolean;
+
+    fn to_java(self, _env: &mut JNIEnv<'local>) -> jni::errors::Result<jboolean> {
+        Ok(jboolean::from(self))
+    }
+}
+
+/// Strings are received as a `java.lang.String`.
+impl<'local> FromJava<'local> for String {
+    type Java = JObject<'local>;
+
+    fn from_java(env: &mut JNIEnv<'local>, value: JObject<'local>) -> jni::errors::Result<Self> {
+        Ok(env.get_string(&JString::from(value))?.into())
+    }
+}
+
+impl<'local> ToJava<'local> for String {
+    type Java = JObject<'local>;
+
+    fn to_java(self, env: &mut JNIEnv<'local>) -> jni::errors::Result<JObject<'local>> {
+        self.as_str().to_java(env)
+    }
+}
+
+impl<'local> ToJava<'local> for &str {
+    type Java = JObject<'local>;
+
+    fn to_java(self, env: &mut JNIEnv<'local>) -> jni::errors::Result<JObject<'local>> {
+        env.new_string(self).map(JObject::from)
+    }
+}
+
+/// Optional primitives are exchanged as (nullable) boxed values, e.g. an `Integer` (see [`unbox`] and [`box_object`]).
+impl<'local, T> FromJava<'local> for Option<T>
+where
+    T: FromJava<'local>,
+    T::Java: JavaPrimitive,
+{
+    type Java = JObject<'local>;
+
+    fn from_java(env: &mut JNIEnv<'local>, value: JObject<'local>) -> jni::errors::Result<Self> {
+        unbox::<T::Java>(env, &value)?.map(|value| T::from_java(env, value)).transpose()
+    }
+}
+
+impl<'local, T> ToJava<'local> for Option<T>
+where
+    T: ToJava<'local>,
+    T::Java: JavaPrimitive,
+{
+    type Java = JObject<'local>;
+
+    fn to_java(self, env: &mut JNIEnv<'local>) -> jni::errors::Result<JObject<'local>> {
+        let value = self.map(|value| value.to_java(env)).transpose()?;
+        box_object(env, value)
+    }
+}
+
+// Collections, optionals, and tuples hold their elements as objects (e.g., a `u32` as an `Integer`).
+// The glue converts each element with a closure generated for its type; each call gets its own local frame,
+// so that converting a large collection does not exhaust the local references.
+
+/// Capacity of the local frame in which each element is converted.
+const ELEMENT_FRAME: i32 = 16;
+
+/// Convert a boxed Java value, the element of a collection, optional, or tuple, into a Rust value (e.g., an `Integer` into a `u32`).
+pub fn unbox_element<'local, T>(env: &mut JNIEnv<'local>, value: &JObject<'_>) -> jni::errors::Result<T>
+where
+    T: FromJava<'local>,
+    T::Java: JavaPrimitive,
+{
+    let value = unbox::<T::Java>(env, value)?.ok_or(jni::errors::Error::NullPtr("boxed element"))?;
+    T::from_java(env, value)
+}
+
+/// Convert a Rust value into a boxed Java value, the element of a collection, optional, or tuple (e.g., a `u32` into an `Integer`).
+pub fn box_element<'local, T>(env: &mut JNIEnv<'local>, value: T) -> jni::errors::Result<JObject<'local>>
+where
+    T: ToJava<'local>,
+    T::Java: JavaPrimitive,
+{
+    let value = value.to_java(env)?;
+    box_object(env, Some(value))
+}
+
+/// Convert a `java.lang.String`, the element of a collection, optional, or tuple, into a Rust string.
+pub fn string_element(env: &mut JNIEnv<'_>, value: &JObject<'_>) -> jni::errors::Result<String> {
+    if value.is_null() {
+        return Err(jni::errors::Error::NullPtr("string element"));
+    }
+    Ok(env.get_string(<&JString<'_>>::from(value))?.into())
+}
+
+/// Convert the elements of a `java.util.Collection` (e.g., a `List` or a `Set`), in the order of its iterator.
+pub fn collection_from_java<T>(
+    env: &mut JNIEnv<'_>,
+    collection: &JObject<'_>,
+    mut element: impl FnMut(&mut JNIEnv<'_>, &JObject<'_>) -> jni::errors::Result<T>,
+) -> jni::errors::Result<Vec<T>> {
+    if collection.is_null() {
+        return Err(jni::errors::Error::NullPtr("collection"));
+    }
+    let size = env.call_method(collection, "size", "()I", &[])?.i()?;
+    let mut values = Vec::with_capacity(usize::try_from(size).unwrap_or_default());
+    let iterator = env.call_method(collection, "iterator", "()Ljava/util/Iterator;", &[])?.l()?;
+    let iterator = env.auto_local(iterator);
+    while env.call_method(&iterator, "hasNext", "()Z", &[])?.z()? {
+        let value = env.call_method(&iterator, "next", "()Ljava/lang/Object;", &[])?.l()?;
+        let value = env.auto_local(value);
+        values.push(env.with_local_frame(ELEMENT_FRAME, |env| element(env, &value))?);
+    }
+    Ok(values)
+}
+
+/// Create a collection of the class `class` (e.g., `java/util/ArrayList`), which must have a constructor
+/// taking no argument, and add the converted `values` to it in order.
+pub fn collection_to_java<'local, T>(
+    env: &mut JNIEnv<'local>,
+    class: &str,
+    values: impl IntoIterator<Item = T>,
+    mut element: impl for<'a> FnMut(&mut JNIEnv<'a>, T) -> jni::errors::Result<JObject<'a>>,
+) -> jni::errors::Result<JObject<'local>> {
+    let collection = env.new_object(class, "()V", &[])?;
+    for value in values {
+        env.with_local_frame(ELEMENT_FRAME, |env| -> jni::errors::Result<()> {
+            let value = element(env, value)?;
+            env.call_method(&collection, "add", "(Ljava/lang/Object;)Z", &[JValue::Object(&value)])?;
+            Ok(())
+        })?;
+    }
+    Ok(collection)
+}
+
+/// Convert the entries of a `java.util.Map`, in the order of its entry set.
+pub fn map_from_java<K, V>(
+    env: &mut JNIEnv<'_>,
+    map: &JObject<'_>,
+    mut key: impl FnMut(&mut JNIEnv<'_>, &JObject<'_>) -> jni::errors::Result<K>,
+    mut value: impl FnMut(&mut JNIEnv<'_>, &JObject<'_>) -> jni::errors::Result<V>,
+) -> jni::errors::Result<Vec<(K, V)>> {
+    if map.is_null() {
+        return Err(jni::errors::Error::NullPtr("map"));
+    }
+    let entries = env.call_method(map, "entrySet", "()Ljava/util/Set;", &[])?.l()?;
+    let entries = env.auto_local(entries);
+    collection_from_java(env, &entries, |env, entry| {
+        let entry_key = env.call_method(entry, "getKey", "()Ljava/lang/Object;", &[])?.l()?;
+        let entry_value = env.call_method(entry, "getValue", "()Ljava/lang/Object;", &[])?.l()?;
+        Ok((key(env, &entry_key)?, value(env, &entry_value)?))
+    })
+}
+
+/// Create a map of the class `class` (e.g., `java/util/HashMap`), which must have a constructor
+/// taking no argument, and put the converted `entries` in it in order.
+pub fn map_to_java<'local, K, V>(
+    env: &mut JNIEnv<'local>,
+    class: &str,
+    entries: impl IntoIterator<Item = (K, V)>,
+    mut key: impl for<'a> FnMut(&mut JNIEnv<'a>, K) -> jni::errors::Result<JObject<'a>>,
+    mut value: impl for<'a> FnMut(&mut JNIEnv<'a>, V) -> jni::errors::Result<JObject<'a>>,
+) -> jni::errors::Result<JObject<'local>> {
+    let map = env.new_object(class, "()V", &[])?;
+    for (entry_key, entry_value) in entries {
+        env.with_local_frame(ELEMENT_FRAME, |env| -> jni::errors::Result<()> {
+            let entry_key = key(env, entry_key)?;
+            let entry_value = value(env, entry_value)?;
+            env.call_method(
+                &map,
+                "put",
+                "(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;",
+                &[JValue::Object(&entry_key), JValue::Object(&entry_value)],
+            )?;
+            Ok(())
+        })?;
+    }
+    Ok(map)
+}
+
+/// Convert a nullable object, with `null` becoming `None`.
+pub fn nullable_from_java<T>(
+    env: &mut JNIEnv<'_>,
+    value: &JObject<'_>,
+    element: impl FnOnce(&mut JNIEnv<'_>, &JObject<'_>) -> jni::errors::Result<T>,
+) -> jni::errors::Result<Option<T>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    element(env, value).map(Some)
+}
+
+/// Convert an `Option` into a nullable object, with `None` becoming `null`.
+pub fn nullable_to_java<'local, T>(
+    env: &mut JNIEnv<'local>,
+    value: Option<T>,
+    element: impl FnOnce(&mut JNIEnv<'local>, T) -> jni::errors::Result<JObject<'local>>,
+) -> jni::errors::Result<JObject<'local>> {
+    match value {
+        Some(value) => element(env, value),
+        None => Ok(JObject::null()),
+    }
+}
+
+/// Convert a `java.util.Optional`, with an empty one becoming `None`.
+pub fn optional_from_java<T>(
+    env: &mut JNIEnv<'_>,
+    optional: &JObject<'_>,
+    element: impl FnOnce(&mut JNIEnv<'_>, &JObject<'_>) -> jni::errors::Result<T>,
+) -> jni::errors::Result<Option<T>> {
+    if optional.is_null() {
+        return Err(jni::errors::Error::NullPtr("optional"));
+    }
+    if !env.call_method(optional, "isPresent", "()Z", &[])?.z()? {
+        return Ok(None);
+    }
+    let value = env.call_method(optional, "get", "()Ljava/lang/Object;", &[])?.l()?;
+    let value = env.auto_local(value);
+    element(env, &value).map(Some)
+}
+
+/// Convert an `Option` into a `java.util.Optional`, with `None` becoming an empty one.
+pub fn optional_to_java<'local, T>(
+    env: &mut JNIEnv<'local>,
+    value: Option<T>,
+    element: impl FnOnce(&mut JNIEnv<'local>, T) -> jni::errors::Result<JObject<'local>>,
+) -> jni::errors::Result<JObject<'local>> {
+    let Some(value) = value else {
+        return env.call_static_method("java/util/Optional", "empty", "()Ljava/util/Optional;", &[])?.l();
+    };
+    let value = element(env, value)?;
+    let value = env.auto_local(value);
+    env.call_static_method("java/util/Optional", "of", "(Ljava/lang/Object;)Ljava/util/Optional;", &[JValue::Object(&value)])?
+        .l()
+}
+
+/// The component `index` of a tuple, an instance of one of the generated `TupleN` record classes,
+/// whose accessors (`f0`, `f1`, ...) return an `Object`.
+pub fn tuple_component<'local>(
+    env: &mut JNIEnv<'local>,
+    tuple: &JObject<'_>,
+    index: usize,
+) -> jni::errors::Result<JObject<'local>> {
+    if tuple.is_null() {
+        return Err(jni::errors::Error::NullPtr("tuple"));
+    }
+    env.call_method(tuple, format!("f{index}"), "()Ljava/lang/Object;", &[])?.l()
+}
+
+/// Create a tuple of the generated `TupleN` record class `class` (e.g., `myCrate/gluegun/Tuple2`) from its components.
+pub fn tuple_to_java<'local>(
+    env: &mut JNIEnv<'local>,
+    class: &str,
+    components: &[&JObject<'_>],
+) -> jni::errors::Result<JObject<'local>> {
+    let descriptor = format!("({})V", "Ljava/lang/Object;".repeat(components.len()));
+    let arguments: Vec<JValue<'_, '_>> = components.iter().map(|component| JValue::Object(component)).collect();
+    env.new_object(class, descriptor, &arguments)
+}
+
+/// Throw an `IllegalArgumentException` for a value that Rust rejected (e.g., by the `TryFrom` impl of a validated newtype),
+/// returning the error telling [`return_or_throw`] that an exception is pending.
+pub fn illegal_argument(env: &mut JNIEnv<'_>, message: impl std::fmt::Display) -> jni::errors::Error {
+    match env.throw_new("java/lang/IllegalArgumentException", message.to_string()) {
+        Ok(()) => jni::errors::Error::JavaException,
+        Err(err) => err,
+    }
+}
+
+/// Throw the exception that `exception` creates (e.g., with the `of` method of a generated exception class)
+/// for the error of a Rust function, returning the error telling [`return_or_throw`] that it is pending.
+/// If creating or throwing the exception fails, that error is returned instead.
+pub fn throw_created<'local>(
+    env: &mut JNIEnv<'local>,
+    exception: impl FnOnce(&mut JNIEnv<'local>) -> jni::errors::Result<JObject<'local>>,
+) -> Box<dyn std::error::Error> {
+    match exception(env).and_then(|exception| env.throw(JThrowable::from(exception))) {
+        Ok(()) => jni::errors::Error::JavaException.into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// A value that a native method returns to Java.
+pub trait JavaReturn {
+    /// The value returned when the method throws an exception (which Java ignores).
+    fn exceptional() -> Self;
+}
+
+impl JavaReturn for () {
+    fn exceptional() -> Self {}
+}
+
+impl JavaReturn for jobject {
+    fn exceptional() -> Self {
+        std::ptr::null_mut()
+    }
+}
+
+macro_rules! java_return_zero {
+    ($($ty:ty),*) => {
+        $(
+            impl JavaReturn for $ty {
+                fn exceptional() -> Self {
+                    <$ty>::default()
+                }
+            }
+        )*
+    };
+}
+
+java_return_zero!(jboolean, jbyte, jshort, jint, jlong, jfloat, jdouble);
+
+/// Return the value of a native method, or throw a `RuntimeException` carrying the message of the error
+/// (unless a Java exception is already pending, e.g. because a JNI call failed).
+/// An `std::io::Error` is thrown as an `UncheckedIOException` instead (see [`throw_io_error`]).
+pub fn return_or_throw<T: JavaReturn>(env: &mut JNIEnv<'_>, result: Result<T, Box<dyn std::error::Error>>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(err) => {
+            if !env.exception_check().unwrap_or(true) {
+                // If throwing fails, there is nothing more we can do.
+                let _ = match err.downcast_ref::<std::io::Error>() {
+                    Some(err) => throw_io_error(env, err),
+                    None => env.throw_new("java/lang/RuntimeException", err.to_string()),
+                };
+            }
+            T::exceptional()
+        }
+    }
+}
+
+/// Throw `err` as a `java.io.UncheckedIOException` whose cause is the `IOException` subclass matching its kind
+/// (e.g., `java.nio.file.NoSuchFileException` for `ErrorKind::NotFound`). Both carry the message of the Rust error,
+/// which ends with the OS error code, if there is one (e.g., `No such file or directory (os error 2)`).
+fn throw_io_error(env: &mut JNIEnv<'_>, err: &std::io::Error) -> jni::errors::Result<()> {
+    let class = match err.kind() {
+        ErrorKind::NotFound => "java/nio/file/NoSuchFileException",
+        ErrorKind::PermissionDenied => "java/nio/file/AccessDeniedException",
+        ErrorKind::AlreadyExists => "java/nio/file/FileAlreadyExistsException",
+        ErrorKind::UnexpectedEof => "java/io/EOFException",
+        ErrorKind::TimedOut => "java/net/SocketTimeoutException",
+        ErrorKind::ConnectionRefused => "java/net/ConnectException",
+        _ => "java/io/IOException",
+    };
+    let message = env.new_string(err.to_string())?;
+    let cause = env.new_object(class, "(Ljava/lang/String;)V", &[JValue::Object(&message)])?;
+    let exception = env.new_object(
+        "java/io/UncheckedIOException",
+        "(Ljava/lang/String;Ljava/io/IOException;)V",
+        &[JValue::Object(&message), JValue::Object(&cause)],
+    )?;
+    env.throw(JThrowable::from(exception))
+}
+
+/// Call `f`, which calls a Rust function, turning a panic into an error carrying the panic's message,
+/// which [`return_or_throw`] then throws as a `RuntimeException`, rather than letting it unwind into the JVM.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, Box<dyn std::error::Error>> {
+    std::panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(&*payload).into())
+}
+
+/// The message of the exception thrown for a panic with the payload `payload`, e.g. `Rust panicked: index out of bounds`.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message,
+        (None, Some(message)) => message.as_str(),
+        (None, None) => "(no message)",
+    };
+    format!("Rust panicked: {message}")
+}
+
+/// A Java object implementing an interface that was passed to Rust as a trait object.
+/// It is held through a global reference, so that Rust can keep it beyond the native call
+/// (e.g., in a `Box<dyn Trait>`) and call it from any thread.
+pub struct ForeignObject {
+    vm: JavaVM,
+    object: GlobalRef,
+}
+
+impl ForeignObject {
+    /// Hold on to `object`, an argument of a native method.
+    pub fn new(env: &mut JNIEnv<'_>, object: &JObject<'_>) -> jni::errors::Result<Self> {
+        if object.is_null() {
+            return Err(jni::errors::Error::NullPtr("interface implementation"));
+        }
+        Ok(Self { vm: env.get_java_vm()?, object: env.new_global_ref(object)? })
+    }
+
+    /// Call `f` with the environment of the current thread (attaching it to the JVM if needed) and the object.
+    /// Local references created by `f` are freed when it returns. If a Java exception was thrown,
+    /// it is cleared and described by the error.
+    pub fn call<T>(
+        &self,
+        f: impl FnOnce(&mut JNIEnv<'_>, &JObject<'_>) -> jni::errors::Result<T>,
+    ) -> Result<T, String> {
+        let mut env = self.vm.attach_current_thread().map_err(|err| err.to_string())?;
+        let result = env.with_local_frame(16, |env| f(env, self.object.as_obj()));
+        result.map_err(|err| match err {
+            jni::errors::Error::JavaException => take_exception(&mut env),
+            err => err.to_string(),
+        })
+    }
+}
+
+/// Clear the pending Java exception and return its description (its `toString()`).
+fn take_exception(env: &mut JNIEnv<'_>) -> String {
+    let exception = match env.exception_occurred() {
+        Ok(exception) => exception,
+        Err(err) => return err.to_string(),
+    };
+    // Nothing but a few functions can be called while an exception is pending.
+    let _ = env.exception_clear();
+    let description = env.with_local_frame(4, |env| -> jni::errors::Result<String> {
+        let description = env.call_method(&exception, "toString", "()Ljava/lang/String;", &[])?.l()?;
+        Ok(env.get_string(&JString::from(description))?.into())
+    });
+    let _ = env.exception_clear();
+    let _ = env.delete_local_ref(exception);
+    description.unwrap_or_else(|err| format!("a Java exception was thrown, but it could not be described: {err}"))
+}
@@ -1,4 +1,5 @@
 mod build_rs;
+pub mod jni_support;
 mod main_rs;
 mod util;
 
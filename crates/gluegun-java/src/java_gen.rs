@@ -1,35 +1,197 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use gluegun_core::{
-    codegen::{CodeWriter, DirBuilder, Separator},
+    codegen::{self, CodeWriter, DirBuilder, Separator},
     idl::{
-        Enum, Field, Function, FunctionInput, Idl, Item, Method, MethodCategory, Name,
-        QualifiedName, Record, Resource, Scalar, SelfKind, Signature, Ty, TypeKind, Variant,
+        self, Enum, Field, Function, FunctionInput, FunctionOutput, FutureRepr, Idl, Interface, Item, MapSetRepr,
+        Method, MethodCategory, ModulePath, Name, NetAddrRepr, Newtype, PathRepr, QualifiedName, Record, Resource, Scalar,
+        SelfKind, Signature, StringRepr, TraitObjectRepr, TupleRepr, Ty, TypeKind, UuidRepr, Variant, VariantArm,
+        VecRepr,
     },
+    type_mapper::{self, TypeMapper},
 };
 
-use crate::util;
+use crate::{
+    metadata::{JavaMetadata, OptionRepr},
+    util::{self, JavaNames, JavaTextual},
+};
+
+/// Records with at least this many fields get a builder (see `generate_record_builder`).
+const BUILDER_MIN_FIELDS: usize = 4;
 
 pub(crate) struct JavaCodeGenerator<'idl> {
     idl: &'idl Idl,
+    metadata: &'idl JavaMetadata,
+    names: JavaNames<'idl>,
+
+    /// The packages of the classes generated so far, which the `module-info.java` exports.
+    packages: BTreeSet<String>,
 }
 
 impl<'idl> JavaCodeGenerator<'idl> {
-    pub(crate) fn new(idl: &'idl Idl) -> Self {
-        Self { idl }
+    pub(crate) fn new(idl: &'idl Idl, metadata: &'idl JavaMetadata) -> Self {
+        Self { idl, metadata, names: JavaNames::new(idl, metadata), packages: BTreeSet::new() }
     }
 
     pub(crate) fn generate(mut self, mut dir: DirBuilder<'_>) -> anyhow::Result<()> {
-        let mut functions: BTreeMap<QualifiedName, Vec<&'idl Function>> = Default::default();
+        let mut functions: BTreeMap<ModulePath, Vec<(&'idl QualifiedName, &'idl Function)>> = Default::default();
 
         for (qname, item) in self.idl.definitions() {
             self.generate_item(&mut dir, qname, item, &mut functions)?;
         }
 
-        for (module_qname, functions) in &functions {
-            self.generate_functions(&mut dir, module_qname, functions)?;
+        for (module_path, functions) in &functions {
+            self.generate_functions(&mut dir, module_path, functions)?;
         }
 
+        for arity in util::tuple_arities(self.idl)? {
+            self.generate_tuple_class(&mut dir, arity)?;
+        }
+
+        self.generate_interface_hash_class(&mut dir)?;
+
+        if util::uses_io_errors(self.idl)? {
+            self.generate_io_errors_class(&mut dir)?;
+        }
+
+        if let Some(module_name) = &self.metadata.module_name {
+            self.generate_module_info(&mut dir, module_name)?;
+        }
+
+        Ok(())
+    }
+
+    /// Generate the `module-info.java` of the Java module `module_name`, which exports the packages
+    /// of all the generated classes and requires the `module-requires` of the metadata, e.g.
+    ///
+    /// ```java
+    /// module com.example.mylib {
+    ///     requires java.logging;
+    ///     exports com.example.mylib;
+    ///     exports com.example.mylib.gluegun;
+    ///     exports com.example.mylib.shapes;
+    /// }
+    /// ```
+    ///
+    /// The support package (`gluegun`) is exported too, as its tuple classes appear in signatures.
+    /// The generated classes only use `java.base`, which every module requires implicitly.
+    fn generate_module_info(&self, dir: &mut DirBuilder<'_>, module_name: &str) -> anyhow::Result<()> {
+        let mut file = dir.add_file("module-info.java")?;
+        write!(file, "module {module_name} {{")?;
+        for module in &self.metadata.module_requires {
+            write!(file, "requires {module};")?;
+        }
+        for package in &self.packages {
+            write!(file, "exports {package};")?;
+        }
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// Add the file of the class `qname` to `dir`, recording its package for the `module-info.java`.
+    fn add_class_file<'dir>(&mut self, dir: &'dir mut DirBuilder<'_>, qname: &QualifiedName) -> anyhow::Result<CodeWriter<'dir>> {
+        self.packages.insert(self.names.class_package_and_name(qname).package.dotted());
+        dir.add_file(self.names.class_file_name(qname))
+    }
+
+    /// Generate the class that constructs the exception thrown for an `std::io::Error` (see [`util::io_errors_qname`]):
+    /// an `UncheckedIOException` whose cause is the `IOException` subclass matching the kind of the error, e.g.
+    ///
+    /// ```java
+    /// public static RuntimeException of(String message, String kind) {
+    ///     java.io.IOException cause = switch (kind) {
+    ///         case "NotFound" -> new java.nio.file.NoSuchFileException(message);
+    ///         default -> new java.io.IOException(message);
+    ///     };
+    ///     return new java.io.UncheckedIOException(message, cause);
+    /// }
+    /// ```
+    ///
+    /// It is only called by the native methods that use duchess; the JNI glue throws the exception itself.
+    fn generate_io_errors_class(&mut self, dir: &mut DirBuilder<'_>) -> anyhow::Result<()> {
+        let qname = util::io_errors_qname(self.idl);
+        let class_name = self.names.class_package_and_name(&qname).class_name;
+        self.generate_java_file(dir, "final class", &qname, |_this, file| {
+            write!(file, "private {class_name}() {{}}")?;
+            write!(file, "")?;
+            write!(file, "/** The exception thrown for a Rust `std::io::Error` of kind `kind` (e.g., `NotFound`). */")?;
+            write!(file, "public static RuntimeException of(String message, String kind) {{")?;
+            write!(file, "java.io.IOException cause = switch (kind) {{")?;
+            for (kind, class) in util::IO_EXCEPTIONS {
+                write!(file, "case {kind:?} -> new {class}(message);")?;
+            }
+            write!(file, "default -> new java.io.IOException(message);")?;
+            write!(file, "}};")?;
+            write!(file, "return new java.io.UncheckedIOException(message, cause);")?;
+            write!(file, "}}")?;
+            Ok(())
+        })
+    }
+
+    /// Generate the class that checks that the native library was generated from the same interface
+    /// as the Java classes (see [`util::interface_hash_qname`]). Classes with native methods call `check`
+    /// when they are initialized (see `generate_interface_hash_check`), so that a mismatched library
+    /// is reported with a clear message rather than as a missing method or a crash.
+    fn generate_interface_hash_class(&mut self, dir: &mut DirBuilder<'_>) -> anyhow::Result<()> {
+        let qname = util::interface_hash_qname(self.idl);
+        let class_name = self.names.class_package_and_name(&qname).class_name;
+        let crate_name = self.idl.crate_name();
+        self.generate_java_file(dir, "final class", &qname, |this, file| {
+            write!(file, "private {class_name}() {{}}")?;
+            write!(file, "")?;
+            write!(file, "/** Hash of the interface these classes were generated from. */")?;
+            write!(file, "public static final long EXPECTED = {:#018x}L;", this.idl.interface_hash())?;
+            write!(file, "")?;
+            write!(file, "private static boolean checked;")?;
+            write!(file, "")?;
+            write!(file, "private static native long nativeInterfaceHash();")?;
+            write!(file, "")?;
+            write!(file, "/** Throw an `IllegalStateException` if the native library was generated from another interface. */")?;
+            write!(file, "public static synchronized void check() {{")?;
+            write!(file, "if (checked) {{")?;
+            write!(file, "return;")?;
+            write!(file, "}}")?;
+            write!(file, "long actual = nativeInterfaceHash();")?;
+            write!(file, "if (actual != EXPECTED) {{")?;
+            write!(
+                file,
+                "throw new IllegalStateException(\"the native library of `{crate_name}` was generated from a different interface than its Java classes \
+                 (expected interface hash \" + Long.toHexString(EXPECTED) + \", found \" + Long.toHexString(actual) + \"); \
+                 regenerate both from the same version of the crate\");",
+            )?;
+            write!(file, "}}")?;
+            write!(file, "checked = true;")?;
+            write!(file, "}}")?;
+            Ok(())
+        })
+    }
+
+    /// Generate a static initializer checking the interface hash (see `generate_interface_hash_class`)
+    /// in a class that has native methods.
+    fn generate_interface_hash_check(&self, file: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(file, "")?;
+        write!(file, "static {{")?;
+        write!(file, "{}.check();", self.names.class_dot_name(&util::interface_hash_qname(self.idl)))?;
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// Generate a `TupleN` record class used to represent Rust tuples of arity `arity`, e.g.
+    ///
+    /// ```java
+    /// public record Tuple2<T0, T1>(T0 f0, T1 f1) { }
+    /// ```
+    fn generate_tuple_class(&mut self, dir: &mut DirBuilder<'_>, arity: usize) -> anyhow::Result<()> {
+        let qname = util::tuple_class_qname(self.idl, arity);
+        let mut file = self.add_class_file(dir, &qname)?;
+        let (package, name) = qname.split_module_path();
+        let package = self.names.package(&package).dotted();
+        let type_parameters = (0..arity).map(|i| format!("T{i}")).collect::<Vec<_>>().join(", ");
+        let components = (0..arity).map(|i| format!("T{i} f{i}")).collect::<Vec<_>>().join(", ");
+        write!(file, "package {package};")?;
+        write!(file, "")?;
+        write!(file, "public record {name}<{type_parameters}>({components}) {{")?;
+        write!(file, "}}")?;
         Ok(())
     }
 
@@ -40,12 +202,29 @@ impl<'idl> JavaCodeGenerator<'idl> {
         qname: &QualifiedName,
         body: impl FnOnce(&mut Self, &mut CodeWriter<'_>) -> anyhow::Result<()>,
     ) -> anyhow::Result<()> {
-        let mut file = dir.add_file(util::class_file_name(qname))?;
-        let (package, name) = qname.split_module_name();
-        let package = package.camel_case().dotted();
+        self.generate_java_file_implementing(dir, java_type, qname, &[], body)
+    }
+
+    /// Like `generate_java_file`, for a type implementing the Java interfaces `interfaces`.
+    fn generate_java_file_implementing(
+        &mut self,
+        dir: &mut DirBuilder<'_>,
+        java_type: &str,
+        qname: &QualifiedName,
+        interfaces: &[QualifiedName],
+        body: impl FnOnce(&mut Self, &mut CodeWriter<'_>) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let mut file = self.add_class_file(dir, qname)?;
+        let (package, name) = qname.split_module_path();
+        let package = self.names.package(&package).dotted();
         write!(file, "package {package};")?;
         write!(file, "")?;
-        write!(file, "public {java_type} {name} {{",)?;
+        if interfaces.is_empty() {
+            write!(file, "public {java_type} {name} {{",)?;
+        } else {
+            let interfaces: Vec<String> = interfaces.iter().map(|qname| self.names.class_dot_name(qname)).collect();
+            write!(file, "public {java_type} {name} implements {} {{", interfaces.join(", "))?;
+        }
 
         body(self, &mut file)?;
 
@@ -57,23 +236,25 @@ impl<'idl> JavaCodeGenerator<'idl> {
     fn generate_item(
         &mut self,
         dir: &mut DirBuilder<'_>,
-        qname: &QualifiedName,
+        qname: &'idl QualifiedName,
         item: &'idl Item,
-        functions: &mut BTreeMap<QualifiedName, Vec<&'idl Function>>,
+        functions: &mut BTreeMap<ModulePath, Vec<(&'idl QualifiedName, &'idl Function)>>,
     ) -> anyhow::Result<()> {
         match item {
             Item::Resource(resource) => self.generate_resource(dir, qname, resource),
             Item::Record(record) => self.generate_record(dir, qname, record),
+            Item::Newtype(newtype) => self.generate_newtype(dir, qname, newtype),
             Item::Variant(variant) => self.generate_variant(dir, qname, variant),
             Item::Enum(an_enum) => self.generate_enum(dir, qname, an_enum),
             Item::Function(function) => {
                 // Collect functons, grouped by module. We will generate them later.
                 functions
-                    .entry(qname.module_name())
+                    .entry(qname.module_path())
                     .or_insert(Default::default())
-                    .push(function);
+                    .push((qname, function));
                 Ok(())
             }
+            Item::Interface(interface) => self.generate_interface(dir, qname, interface),
             _ => anyhow::bail!("unsupported item: "),
         }
     }
@@ -81,69 +262,658 @@ impl<'idl> JavaCodeGenerator<'idl> {
     fn generate_functions(
         &mut self,
         dir: &mut DirBuilder<'_>,
-        module_qname: &QualifiedName,
-        functions: &[&Function],
+        module_path: &ModulePath,
+        functions: &[(&QualifiedName, &Function)],
     ) -> anyhow::Result<()> {
-        let functions_class = module_qname.join("Functions");
+        let functions_class = module_path.join("Functions");
         self.generate_java_file(dir, "class", &functions_class, |this, file| {
-            for function in functions {
-                this.generate_regular_method(file, None, function.name(), function.signature())?;
+            this.generate_interface_hash_check(file)?;
+            for (qname, function) in functions {
+                let java_name = this.metadata.member_name(function.name());
+                this.generate_regular_method(file, None, function.name(), &java_name, function.signature(), Guards::default())?;
+                if let Some((params_qname, params)) = this.idl.params_record(qname) {
+                    this.generate_packed_function(file, &java_name, function, params_qname, params)?;
+                }
             }
             Ok(())
         })
     }
 
+    /// Generate an overload of the function `java_name` taking its parameters packed in the record `params_qname`
+    /// (see `Idl::pack_params`), which passes the fields of the record to the function, e.g.
+    ///
+    /// ```java
+    /// public static User createUser(CreateUserParams params) {
+    ///     return createUser(params.name, params.age, params.email);
+    /// }
+    /// ```
+    fn generate_packed_function(
+        &self,
+        file: &mut CodeWriter<'_>,
+        java_name: &Name,
+        function: &Function,
+        params_qname: &QualifiedName,
+        params: &Record,
+    ) -> anyhow::Result<()> {
+        let output = function.signature().output_ty();
+        write!(file, "")?;
+        write!(
+            file,
+            "public static {ret} {java_name}({class} params) {{",
+            ret = self.write_return_ty(output, false)?,
+            class = self.names.class_dot_name(params_qname),
+        )?;
+        let arguments: Vec<String> = params
+            .fields()
+            .iter()
+            .map(|field| format!("params.{}", self.metadata.member_name(field.name())))
+            .collect();
+        let return_kw = if output.is_unit() { "" } else { "return " };
+        write!(file, "{return_kw}{java_name}({});", arguments.join(", "))?;
+        write!(file, "}}")?;
+        Ok(())
+    }
+
     fn generate_resource(
         &mut self,
         dir: &mut DirBuilder<'_>,
         qname: &QualifiedName,
         resource: &Resource,
     ) -> anyhow::Result<()> {
-        self.generate_java_file(dir, "class", qname, |this, file| {
+        // Methods taking `&mut self` or `self` need the only reference to the Rust value,
+        // so they must not run concurrently with other methods.
+        let exclusive = resource.methods().iter().any(|method| {
+            matches!(method.category().self_kind(), Some(SelfKind::ByRefMut | SelfKind::ByValue))
+        });
+        let guards = Guards {
+            consumable: resource.has_consuming_methods(),
+            thread_confined: !resource.auto_traits().send(),
+            synchronized: resource.auto_traits().send() && (!resource.auto_traits().sync() || exclusive),
+            ordinal: false,
+        };
+        let (constructors, methods): (Vec<Method>, Vec<Method>) = resource
+            .methods()
+            .iter()
+            .cloned()
+            .partition(|method| matches!(method.category(), MethodCategory::Constructor));
+        // Interfaces filtered out of the IDL have no Java interface to implement.
+        let interfaces: Vec<QualifiedName> = resource
+            .implements()
+            .iter()
+            .filter(|interface| matches!(self.idl.item(interface), Some(Item::Interface(_))))
+            .cloned()
+            .collect();
+        let inherited_methods = self.idl.inherited_methods(resource);
+        self.generate_java_file_implementing(dir, "class", qname, &interfaces, |this, file| {
             write!(file, "private long pointer;")?;
-            this.generate_methods(file, resource.methods())?;
+            this.generate_wrapping_constructor(file, qname)?;
+            for constructor in &constructors {
+                this.generate_constructor(file, qname, constructor)?;
+            }
+            if guards.thread_confined {
+                this.generate_thread_check(file, qname)?;
+            }
+            if guards.consumable {
+                this.generate_consumed_check(file, qname)?;
+            }
+            this.generate_interface_hash_check(file)?;
+            this.generate_methods(file, &methods, guards)?;
+            // The methods of its interfaces that the Rust type does not define itself.
+            for (_, method) in inherited_methods {
+                this.generate_method(file, method, guards)?;
+            }
             Ok(())
         })
     }
 
+    /// Generate the constructor with which native methods returning the resource `qname` wrap the pointer
+    /// of the Rust value (see `RustCodeGenerator::generate_resource_to_java`). Its `Void` parameter keeps it apart
+    /// from the constructors of the Rust type, as no Rust type maps to `Void`, e.g.
+    ///
+    /// ```java
+    /// private Counter(long pointer, Void returned) { this.pointer = pointer; }
+    /// ```
+    fn generate_wrapping_constructor(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName) -> anyhow::Result<()> {
+        write!(file, "")?;
+        write!(file, "/** Wraps the Rust value that a native method returned. */")?;
+        write!(file, "private {}(long pointer, Void returned) {{", qname.tail_name())?;
+        write!(file, "this.pointer = pointer;")?;
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// Generate a constructor calling the native counterpart of the Rust constructor `method` (`fn new(...) -> Self`),
+    /// which returns the pointer of the Rust value, e.g.
+    ///
+    /// ```java
+    /// private static native long native_new(int start);
+    ///
+    /// public Counter(int start) { this.pointer = native_new(start); }
+    /// ```
+    fn generate_constructor(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName, method: &Method) -> anyhow::Result<()> {
+        let native_name = format!("native_{}", method.name());
+        write!(file, "")?;
+        write!(file, "private static native long {native_name}(")?;
+        self.generate_function_inputs(file, method.signature().inputs(), true)?;
+        write!(file, ");")?;
+        write!(file, "")?;
+        write!(file, "public {}(", qname.tail_name())?;
+        self.generate_function_inputs(file, method.signature().inputs(), false)?;
+        write!(file, ") {{")?;
+        write!(file, "this.pointer = {native_name}(")?;
+        for (input, sep) in method.signature().inputs().iter().comma_separated() {
+            let name = input.name().to_string();
+            let argument = match self.textual_ty(input.refd_ty().ty()) {
+                Some(textual) => textual.to_string_expr(&name),
+                None => name,
+            };
+            write!(file, "{argument}{sep}")?;
+        }
+        write!(file, ");")?;
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// Generate the check confining an object whose Rust value is not `Send` to the thread that created it;
+    /// using it from another thread throws an `IllegalStateException`, e.g.
+    ///
+    /// ```java
+    /// private final Thread owner = Thread.currentThread();
+    ///
+    /// private void checkThread() { ... }
+    /// ```
+    fn generate_thread_check(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName) -> anyhow::Result<()> {
+        write!(file, "")?;
+        write!(file, "/** The Rust value is not `Send`, so the object can only be used from the thread that created it. */")?;
+        write!(file, "private final Thread owner = Thread.currentThread();")?;
+        write!(file, "")?;
+        write!(file, "private void checkThread() {{")?;
+        write!(file, "if (Thread.currentThread() != owner) {{")?;
+        write!(
+            file,
+            "throw new IllegalStateException(\"`{name}` is not thread-safe and can only be used from the thread that created it\");",
+            name = qname.tail_name(),
+        )?;
+        write!(file, "}}")?;
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// Generate the state used to invalidate the object once a method taking `self` by value
+    /// has consumed the Rust value; using it again throws an `IllegalStateException`, e.g.
+    ///
+    /// ```java
+    /// private boolean consumed;
+    ///
+    /// private void checkNotConsumed() { ... }
+    /// ```
+    fn generate_consumed_check(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName) -> anyhow::Result<()> {
+        write!(file, "")?;
+        write!(file, "/** Set once a method has taken ownership of the Rust value; the object can no longer be used. */")?;
+        write!(file, "private boolean consumed;")?;
+        write!(file, "")?;
+        write!(file, "private void checkNotConsumed() {{")?;
+        write!(file, "if (consumed) {{")?;
+        write!(
+            file,
+            "throw new IllegalStateException(\"`{name}` was consumed by a method that takes ownership of it\");",
+            name = qname.tail_name(),
+        )?;
+        write!(file, "}}")?;
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// Records become a class with a public field per field of the struct and a constructor taking all of them,
+    /// along with a builder if they have many fields, e.g.
+    ///
+    /// ```java
+    /// public class Point {
+    ///     public double x;
+    ///     public double y;
+    ///
+    ///     public Point(double x, double y) { ... }
+    /// }
+    /// ```
+    ///
+    /// Records without fields (e.g., unit structs) are marker classes with a single instance, `INSTANCE`.
+    /// The fields of tuple records are positional: they are `private final`, read with accessors like `point.f0()`,
+    /// and the record has no builder.
+    ///
+    /// We also generate a support class that the native code uses to construct them and read their fields.
     fn generate_record(
         &mut self,
         dir: &mut DirBuilder<'_>,
         qname: &QualifiedName,
         record: &Record,
     ) -> anyhow::Result<()> {
+        let class_name = self.names.class_package_and_name(qname).class_name;
         self.generate_java_file(dir, "class", qname, |this, file| {
-            this.generate_fields(file, record.fields())?;
+            let fields = record
+                .fields()
+                .iter()
+                .map(|field| Ok((this.write_ty(field.ty())?, this.metadata.member_name(field.name()))))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            if *record.tuple() {
+                for (ty, name) in &fields {
+                    write!(file, "private final {ty} {name};")?;
+                }
+            } else {
+                this.generate_fields(file, record.fields())?;
+            }
+
+            write!(file, "")?;
+            write!(file, "public {class_name}(")?;
+            for ((ty, name), sep) in fields.iter().comma_separated() {
+                write!(file, "{ty} {name}{sep}")?;
+            }
+            write!(file, ") {{")?;
+            for (_, name) in &fields {
+                write!(file, "this.{name} = {name};")?;
+            }
+            write!(file, "}}")?;
+
+            if *record.tuple() {
+                for (ty, name) in &fields {
+                    write!(file, "")?;
+                    write!(file, "public {ty} {name}() {{")?;
+                    write!(file, "return {name};")?;
+                    write!(file, "}}")?;
+                }
+            }
 
-            // FIXME: make a constructor?
+            if fields.is_empty() {
+                write!(file, "")?;
+                write!(file, "public static final {class_name} INSTANCE = new {class_name}();")?;
+            }
 
-            this.generate_methods(file, record.methods())?;
+            if fields.len() >= BUILDER_MIN_FIELDS && !*record.tuple() {
+                this.generate_record_builder(file, &class_name, &fields)?;
+            }
+
+            if !record.methods().is_empty() {
+                this.generate_interface_hash_check(file)?;
+            }
+            this.generate_methods(file, record.methods(), Guards::default())?;
+            Ok(())
+        })?;
+
+        // Records packing the parameters of a function never cross the boundary (see `generate_packed_function`).
+        if record.params_of().is_some() {
+            return Ok(());
+        }
+        self.generate_record_support_class(dir, qname, record)
+    }
+
+    /// Generate a builder for a record with many `fields` (given as their Java type and name),
+    /// so that Java code can name the fields it sets; those it does not set are zero, false, or null, e.g.
+    ///
+    /// ```java
+    /// var config = Config.builder().host("localhost").port(8080).build();
+    /// ```
+    fn generate_record_builder(
+        &self,
+        file: &mut CodeWriter<'_>,
+        class_name: &Name,
+        fields: &[(String, Name)],
+    ) -> anyhow::Result<()> {
+        write!(file, "")?;
+        write!(file, "public static Builder builder() {{")?;
+        write!(file, "return new Builder();")?;
+        write!(file, "}}")?;
+
+        write!(file, "")?;
+        write!(file, "public static final class Builder {{")?;
+        for (ty, name) in fields {
+            write!(file, "private {ty} {name};")?;
+        }
+        write!(file, "")?;
+        write!(file, "private Builder() {{}}")?;
+        for (ty, name) in fields {
+            write!(file, "")?;
+            write!(file, "public Builder {name}({ty} {name}) {{")?;
+            write!(file, "this.{name} = {name};")?;
+            write!(file, "return this;")?;
+            write!(file, "}}")?;
+        }
+        write!(file, "")?;
+        write!(file, "public {class_name} build() {{")?;
+        let names = fields.iter().map(|(_, name)| name.to_string()).collect::<Vec<_>>();
+        write!(file, "return new {class_name}({});", names.join(", "))?;
+        write!(file, "}}")?;
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// Generate the support class for a record (see [`util::support_qname`]), whose static methods
+    /// the native code uses to construct the record and to read each of its fields.
+    fn generate_record_support_class(
+        &mut self,
+        dir: &mut DirBuilder<'_>,
+        qname: &QualifiedName,
+        record: &Record,
+    ) -> anyhow::Result<()> {
+        let record_class = self.names.class_dot_name(qname);
+        let support_qname = util::support_qname(self.idl, qname);
+        let support_class_name = self.names.class_package_and_name(&support_qname).class_name;
+        self.generate_java_file(dir, "final class", &support_qname, |this, file| {
+            write!(file, "private {support_class_name}() {{}}")?;
+
+            write!(file, "")?;
+            write!(file, "public static {record_class} create(")?;
+            for (field, sep) in record.fields().iter().comma_separated() {
+                write!(
+                    file,
+                    "{ty} {name}{sep}",
+                    ty = this.write_objectified_ty(field.ty())?,
+                    name = this.metadata.member_name(field.name()),
+                )?;
+            }
+            write!(file, ") {{")?;
+            if record.fields().is_empty() {
+                write!(file, "return {record_class}.INSTANCE;")?;
+            } else {
+                write!(file, "return new {record_class}(")?;
+                for (field, sep) in record.fields().iter().comma_separated() {
+                    write!(file, "{name}{sep}", name = this.metadata.member_name(field.name()))?;
+                }
+                write!(file, ");")?;
+            }
+            write!(file, "}}")?;
+
+            for field in record.fields() {
+                write!(file, "")?;
+                write!(
+                    file,
+                    "public static {ty} {getter}({record_class} value) {{",
+                    ty = this.write_objectified_ty(field.ty())?,
+                    getter = util::record_getter(field),
+                )?;
+                // The fields of tuple records are read through their accessor.
+                let parens = if *record.tuple() { "()" } else { "" };
+                write!(file, "return value.{name}{parens};", name = this.metadata.member_name(field.name()))?;
+                write!(file, "}}")?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Interfaces become a Java interface that Java code implements to pass an object to Rust, e.g.
+    ///
+    /// ```java
+    /// public interface Listener {
+    ///     int onEvent(String name);
+    /// }
+    /// ```
+    fn generate_interface(
+        &mut self,
+        dir: &mut DirBuilder<'_>,
+        qname: &QualifiedName,
+        interface: &Interface,
+    ) -> anyhow::Result<()> {
+        self.generate_java_file(dir, "interface", qname, |this, file| {
+            for method in interface.methods() {
+                write!(file, "")?;
+                write!(
+                    file,
+                    "{ret} {name}(",
+                    ret = this.write_return_ty(method.signature().output_ty(), false)?,
+                    name = this.metadata.member_name(method.name()),
+                )?;
+                this.generate_function_inputs(file, method.signature().inputs(), false)?;
+                write!(file, ");")?;
+            }
             Ok(())
         })
     }
 
+    /// Newtypes become a record with a single component, e.g.
+    ///
+    /// ```java
+    /// public record UserId(Long value) { }
+    /// ```
+    fn generate_newtype(
+        &mut self,
+        dir: &mut DirBuilder<'_>,
+        qname: &QualifiedName,
+        newtype: &Newtype,
+    ) -> anyhow::Result<()> {
+        let mut file = self.add_class_file(dir, qname)?;
+        let (package, name) = qname.split_module_path();
+        let package = self.names.package(&package).dotted();
+        write!(file, "package {package};")?;
+        write!(file, "")?;
+        write!(file, "public record {name}({ty} value) {{", ty = self.write_objectified_ty(newtype.ty())?)?;
+        self.generate_methods(&mut file, newtype.methods(), Guards::default())?;
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// Variants become a sealed abstract class with a nested `final` subclass per arm, e.g.
+    ///
+    /// ```java
+    /// public abstract sealed class Shape {
+    ///     public interface Visitor<R> { R visitCircle(Circle arm); ... }
+    ///     public abstract <R> R accept(Visitor<R> visitor);
+    ///     public static final class Circle extends Shape { ... }
+    /// }
+    /// ```
+    ///
+    /// Arms can be matched with `instanceof`/`switch` patterns or with a `Visitor`.
+    /// We also generate a support class that the native code uses to construct and inspect the arms.
     fn generate_variant(
         &mut self,
         dir: &mut DirBuilder<'_>,
         qname: &QualifiedName,
         variant: &Variant,
     ) -> anyhow::Result<()> {
-        self.generate_java_file(dir, "abstract class", qname, |this, file| {
-            this.generate_methods(file, variant.methods())?;
+        let class_name = self.names.class_package_and_name(qname).class_name;
+        self.generate_java_file(dir, "abstract sealed class", qname, |this, file| {
+            write!(file, "private {class_name}() {{}}")?;
+
+            write!(file, "")?;
+            write!(file, "public interface Visitor<R> {{")?;
+            for arm in variant.arms() {
+                let arm_name = arm.name().upper_camel_case();
+                write!(file, "R visit{arm_name}({arm_name} arm);")?;
+            }
+            write!(file, "}}")?;
+
+            write!(file, "")?;
+            write!(file, "public abstract <R> R accept(Visitor<R> visitor);")?;
+
+            for arm in variant.arms() {
+                write!(file, "")?;
+                this.generate_variant_arm(file, &class_name, arm)?;
+            }
+
+            this.generate_methods(file, variant.methods(), Guards::default())?;
             Ok(())
         })?;
 
-        for variant_arm in variant.arms() {
-            let variant_qname = qname.module_name().join(variant_arm.name());
-            self.generate_java_file(dir, "abstract class", &variant_qname, |this, file| {
-                this.generate_fields(file, variant_arm.fields())?;
-                Ok(())
-            })?;
+        if *variant.implements_error() {
+            let arms: Vec<Name> = variant.arms().iter().map(|arm| arm.name().upper_camel_case()).collect();
+            self.generate_exception(dir, qname, &arms, false)?;
         }
 
+        self.generate_variant_support_class(dir, qname, variant)
+    }
+
+    /// Generate the nested class for a variant arm. Like a Java record, it has a constructor
+    /// taking all fields, an accessor per field, and value-based `equals`/`hashCode`/`toString`.
+    fn generate_variant_arm(
+        &self,
+        file: &mut CodeWriter<'_>,
+        class_name: &Name,
+        arm: &VariantArm,
+    ) -> anyhow::Result<()> {
+        let arm_name = arm.name().upper_camel_case();
+        let fields = arm
+            .fields()
+            .iter()
+            .map(|field| Ok((self.write_ty(field.ty())?, self.metadata.member_name(field.name()))))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        write!(file, "public static final class {arm_name} extends {class_name} {{")?;
+        for (ty, name) in &fields {
+            write!(file, "private final {ty} {name};")?;
+        }
+
+        write!(file, "")?;
+        write!(file, "public {arm_name}(")?;
+        for ((ty, name), sep) in fields.iter().comma_separated() {
+            write!(file, "{ty} {name}{sep}")?;
+        }
+        write!(file, ") {{")?;
+        for (_, name) in &fields {
+            write!(file, "this.{name} = {name};")?;
+        }
+        write!(file, "}}")?;
+
+        for (ty, name) in &fields {
+            write!(file, "")?;
+            write!(file, "public {ty} {name}() {{")?;
+            write!(file, "return {name};")?;
+            write!(file, "}}")?;
+        }
+
+        write!(file, "")?;
+        write!(file, "@Override")?;
+        write!(file, "public <R> R accept(Visitor<R> visitor) {{")?;
+        write!(file, "return visitor.visit{arm_name}(this);")?;
+        write!(file, "}}")?;
+
+        write!(file, "")?;
+        write!(file, "@Override")?;
+        write!(file, "public boolean equals(Object other) {{")?;
+        if fields.is_empty() {
+            write!(file, "return other instanceof {arm_name};")?;
+        } else {
+            let comparisons = fields
+                .iter()
+                .map(|(_, name)| format!("java.util.Objects.equals({name}, that.{name})"))
+                .collect::<Vec<_>>()
+                .join(" && ");
+            write!(file, "return other instanceof {arm_name} that && {comparisons};")?;
+        }
+        write!(file, "}}")?;
+
+        write!(file, "")?;
+        write!(file, "@Override")?;
+        write!(file, "public int hashCode() {{")?;
+        let hashed = std::iter::once(format!("{arm_name}.class"))
+            .chain(fields.iter().map(|(_, name)| name.to_string()))
+            .collect::<Vec<_>>();
+        write!(file, "return java.util.Objects.hash({hashed});", hashed = hashed.join(", "))?;
+        write!(file, "}}")?;
+
+        write!(file, "")?;
+        write!(file, "@Override")?;
+        write!(file, "public String toString() {{")?;
+        let components = fields
+            .iter()
+            .map(|(_, name)| format!("\"{name}=\" + {name}"))
+            .collect::<Vec<_>>()
+            .join(" + \", \" + ");
+        if components.is_empty() {
+            write!(file, "return \"{class_name}.{arm_name}[]\";")?;
+        } else {
+            write!(file, "return \"{class_name}.{arm_name}[\" + {components} + \"]\";")?;
+        }
+        write!(file, "}}")?;
+
+        write!(file, "}}")?;
         Ok(())
     }
 
+    /// Generate the support class for a variant (see [`util::support_qname`]).
+    /// The native code cannot easily `instanceof` or name the nested arm classes,
+    /// so this class exposes static methods to find out which arm a value is,
+    /// read the fields of each arm, and construct each arm.
+    fn generate_variant_support_class(
+        &mut self,
+        dir: &mut DirBuilder<'_>,
+        qname: &QualifiedName,
+        variant: &Variant,
+    ) -> anyhow::Result<()> {
+        let variant_class = self.names.class_dot_name(qname);
+        let support_qname = util::support_qname(self.idl, qname);
+        let support_class_name = self.names.class_package_and_name(&support_qname).class_name;
+        self.generate_java_file(dir, "final class", &support_qname, |this, file| {
+            write!(file, "private {support_class_name}() {{}}")?;
+
+            write!(file, "")?;
+            write!(file, "public static int arm({variant_class} value) {{")?;
+            for (index, arm) in variant.arms().iter().enumerate() {
+                let arm_name = arm.name().upper_camel_case();
+                write!(file, "if (value instanceof {variant_class}.{arm_name}) {{")?;
+                write!(file, "return {index};")?;
+                write!(file, "}}")?;
+            }
+            write!(file, "throw new IllegalStateException(\"unknown arm of {variant_class}: \" + value);")?;
+            write!(file, "}}")?;
+
+            for arm in variant.arms() {
+                let arm_name = arm.name().upper_camel_case();
+
+                write!(file, "")?;
+                write!(
+                    file,
+                    "public static {variant_class} {constructor}(",
+                    constructor = util::variant_arm_constructor(arm),
+                )?;
+                for (field, sep) in arm.fields().iter().comma_separated() {
+                    write!(
+                        file,
+                        "{ty} {name}{sep}",
+                        ty = this.write_objectified_ty(field.ty())?,
+                        name = this.metadata.member_name(field.name()),
+                    )?;
+                }
+                write!(file, ") {{")?;
+                write!(file, "return new {variant_class}.{arm_name}(")?;
+                for (field, sep) in arm.fields().iter().comma_separated() {
+                    write!(file, "{name}{sep}", name = this.metadata.member_name(field.name()))?;
+                }
+                write!(file, ");")?;
+                write!(file, "}}")?;
+
+                for field in arm.fields() {
+                    write!(file, "")?;
+                    write!(
+                        file,
+                        "public static {ty} {getter}({variant_class} value) {{",
+                        ty = this.write_objectified_ty(field.ty())?,
+                        getter = util::variant_arm_getter(arm, field),
+                    )?;
+                    write!(
+                        file,
+                        "return (({variant_class}.{arm_name}) value).{name}();",
+                        name = this.metadata.member_name(field.name()),
+                    )?;
+                    write!(file, "}}")?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Enums without associated data become a Java enum with a constant per arm, e.g.
+    ///
+    /// ```java
+    /// public enum Color {
+    ///     Red,
+    ///     Green;
+    ///
+    ///     public static native String native_hex(int ordinal);
+    ///
+    ///     public String hex() {
+    ///         return native_hex(ordinal());
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Their methods are dispatched on the ordinal of the constant (see [`Guards::ordinal`]).
     fn generate_enum(
         &mut self,
         dir: &mut DirBuilder<'_>,
@@ -151,12 +921,94 @@ impl<'idl> JavaCodeGenerator<'idl> {
         an_enum: &Enum,
     ) -> anyhow::Result<()> {
         self.generate_java_file(dir, "enum", qname, |this, file| {
-            for (arm, sep) in an_enum.arms().iter().comma_separated() {
+            // The constants must be terminated by `;` if the enum has other members.
+            let last_sep = if an_enum.methods().is_empty() { "" } else { ";" };
+            for (arm, sep) in codegen::with_separator(",", last_sep, an_enum.arms().iter()) {
                 write!(file, "{}{sep}", arm.name().upper_camel_case())?;
             }
-            this.generate_methods(file, an_enum.methods())?;
+            if !an_enum.methods().is_empty() {
+                this.generate_interface_hash_check(file)?;
+            }
+            let guards = Guards { ordinal: true, ..Guards::default() };
+            this.generate_methods(file, an_enum.methods(), guards)?;
             Ok(())
-        })
+        })?;
+
+        if *an_enum.implements_error() {
+            let arms: Vec<Name> = an_enum.arms().iter().map(|arm| arm.name().upper_camel_case()).collect();
+            self.generate_exception(dir, qname, &arms, true)?;
+        }
+        Ok(())
+    }
+
+    /// Enums implementing `std::error::Error` (see [`Idl::error_item`]) also get an exception class,
+    /// which the native methods failing with them throw, with a subclass per arm, e.g.
+    ///
+    /// ```java
+    /// public abstract class ParseErrorException extends RuntimeException {
+    ///     public ParseError error() { ... }
+    ///     public static final class Empty extends ParseErrorException { ... }
+    /// }
+    /// ```
+    ///
+    /// The message of the exception is that of the Rust error. The native code constructs it with `of`,
+    /// passing the value of the variant or, for a C-like enum (`by_ordinal`), the ordinal of the constant.
+    fn generate_exception(
+        &mut self,
+        dir: &mut DirBuilder<'_>,
+        qname: &QualifiedName,
+        arms: &[Name],
+        by_ordinal: bool,
+    ) -> anyhow::Result<()> {
+        let error_class = self.names.class_dot_name(qname);
+        let exception_qname = util::exception_qname(qname);
+        let exception_class = self.names.class_package_and_name(&exception_qname).class_name;
+        let mut file = self.add_class_file(dir, &exception_qname)?;
+        write!(file, "package {};", self.names.package(&exception_qname.module_path()).dotted())?;
+        write!(file, "")?;
+        write!(file, "public abstract class {exception_class} extends RuntimeException {{")?;
+        write!(file, "private final {error_class} error;")?;
+
+        write!(file, "")?;
+        write!(file, "private {exception_class}(String message, {error_class} error) {{")?;
+        write!(file, "super(message);")?;
+        write!(file, "this.error = error;")?;
+        write!(file, "}}")?;
+
+        write!(file, "")?;
+        write!(file, "public {error_class} error() {{")?;
+        write!(file, "return error;")?;
+        write!(file, "}}")?;
+
+        for arm in arms {
+            write!(file, "")?;
+            write!(file, "public static final class {arm} extends {exception_class} {{")?;
+            write!(file, "private {arm}(String message, {error_class} error) {{")?;
+            write!(file, "super(message, error);")?;
+            write!(file, "}}")?;
+            write!(file, "}}")?;
+        }
+
+        write!(file, "")?;
+        if by_ordinal {
+            write!(file, "public static {exception_class} of(String message, int ordinal) {{")?;
+            write!(file, "{error_class} error = {error_class}.values()[ordinal];")?;
+        } else {
+            write!(file, "public static {exception_class} of(String message, {error_class} error) {{")?;
+        }
+        for arm in arms {
+            if by_ordinal {
+                write!(file, "if (error == {error_class}.{arm}) {{")?;
+            } else {
+                write!(file, "if (error instanceof {error_class}.{arm}) {{")?;
+            }
+            write!(file, "return new {arm}(message, error);")?;
+            write!(file, "}}")?;
+        }
+        write!(file, "throw new IllegalStateException(\"unknown arm of {error_class}: \" + error);")?;
+        write!(file, "}}")?;
+        write!(file, "}}")?;
+        Ok(())
     }
 
     fn generate_fields(&self, file: &mut CodeWriter<'_>, fields: &[Field]) -> anyhow::Result<()> {
@@ -165,118 +1017,256 @@ impl<'idl> JavaCodeGenerator<'idl> {
                 file,
                 "public {ty} {name};",
                 ty = self.write_ty(field.ty())?,
-                name = field.name().camel_case()
+                name = self.metadata.member_name(field.name())
             )?;
         }
         Ok(())
     }
 
+    /// Generate `methods`, with the checks in `guards` at the start of each instance method.
     fn generate_methods(
         &self,
         file: &mut CodeWriter<'_>,
         methods: &[Method],
+        guards: Guards,
     ) -> anyhow::Result<()> {
+        self.check_overloads(methods)?;
+        for method in methods {
+            self.generate_method(file, method, guards)?;
+        }
+        Ok(())
+    }
+
+    /// Check that the methods with the same Java name (the overloads of a group, see `JavaMetadata::method_name`)
+    /// can be told apart by the types of their parameters, which Java compares after erasing type arguments.
+    fn check_overloads(&self, methods: &[Method]) -> anyhow::Result<()> {
+        let mut overloads: BTreeMap<(Name, Vec<String>), &Method> = BTreeMap::new();
         for method in methods {
-            self.generate_method(file, method)?;
+            let mut parameter_tys = vec![];
+            for input in method.signature().inputs() {
+                let ty = self.write_signature_ty(input.refd_ty().ty(), false)?;
+                parameter_tys.push(ty.split('<').next().unwrap_or_default().to_string());
+            }
+            let java_name = self.metadata.method_name(method);
+            if let Some(other) = overloads.insert((java_name.clone(), parameter_tys), method) {
+                anyhow::bail!(
+                    "{}: `{}` and `{}` cannot both be `{java_name}` in Java, as their parameters have the same types",
+                    method.span(),
+                    other.name(),
+                    method.name(),
+                );
+            }
         }
         Ok(())
     }
 
-    fn generate_method(&self, file: &mut CodeWriter<'_>, method: &Method) -> anyhow::Result<()> {
+    fn generate_method(&self, file: &mut CodeWriter<'_>, method: &Method, guards: Guards) -> anyhow::Result<()> {
         write!(file, "")?;
 
         match method.category() {
-            MethodCategory::Constructor => todo!(),
+            // Only resources have constructors, which `generate_resource` generates.
+            MethodCategory::Constructor => {
+                anyhow::bail!("{}: the Java backend only supports the constructors of resources", method.span())
+            }
 
-            MethodCategory::InstanceMethod(self_kind)
-            | MethodCategory::BuilderMethod(self_kind) => self.generate_regular_method(
+            // Named constructors are static factories returning the class, e.g. `static Config fromPath(...)`,
+            // and getters read the field through the native counterpart, like a `&self` method.
+            MethodCategory::InstanceMethod(_)
+            | MethodCategory::BuilderMethod(_)
+            | MethodCategory::NamedConstructor
+            | MethodCategory::StaticMethod
+            | MethodCategory::Getter(_) => self.generate_regular_method(
                 file,
-                Some(self_kind),
+                Some(method.category()),
                 method.name(),
+                &self.metadata.method_name(method),
                 method.signature(),
+                guards,
             ),
 
-            MethodCategory::StaticMethod => {
-                self.generate_regular_method(file, None, method.name(), method.signature())
-            }
-
             _ => anyhow::bail!("unsupported method category: `{:?}`", method.category()),
         }
     }
 
+    /// Generate a method `java_name` calling the native counterpart of the Rust method or function `name`
+    /// (see `generate_native_counterpart`); `category` is `None` for functions.
+    ///
+    /// If the method is a builder (`fn with_x(self, ...) -> Self`) and the object holds a Rust value
+    /// that the method consumes, the method is fluent: the object takes over the value the native method
+    /// returns and returns itself, so that calls can be chained and the object stays usable, e.g.
+    ///
+    /// ```java
+    /// public Request retries(int retries) {
+    ///     checkNotConsumed();
+    ///     consumed = true;
+    ///     var next = native_retries(retries);
+    ///     pointer = next.pointer;
+    ///     next.pointer = 0;
+    ///     consumed = false;
+    ///     return this;
+    /// }
+    /// ```
     fn generate_regular_method(
         &self,
         file: &mut CodeWriter<'_>,
-        self_kind: Option<&SelfKind>,
+        category: Option<&MethodCategory>,
         name: &Name,
+        java_name: &Name,
         signature: &Signature,
+        guards: Guards,
     ) -> anyhow::Result<()> {
-        let native_name = self.generate_native_counterpart(file, self_kind, name, signature)?;
+        let self_kind = category.and_then(MethodCategory::self_kind);
+        let builder = matches!(category, Some(MethodCategory::BuilderMethod(_)));
+        let ordinal = guards.ordinal && self_kind.is_some();
+        let native_name = self.generate_native_counterpart(file, self_kind, name, signature, ordinal)?;
 
         write!(file, "")?;
 
-        let static_kw = if self_kind.is_none() { "static" } else { "" };
+        let modifier = match self_kind {
+            None => "static",
+            Some(_) if guards.synchronized => "synchronized",
+            Some(_) => "",
+        };
 
-        let return_ty = signature.output_ty().main_ty();
         write!(
             file,
-            "public {static_kw} {ret} {name}(",
-            ret = self.write_ty(return_ty.ty())?,
-            name = name
+            "public {modifier} {ret} {java_name}(",
+            ret = self.write_return_ty(signature.output_ty(), false)?,
         )?;
-        self.generate_function_inputs(file, signature.inputs())?;
+        self.generate_function_inputs(file, signature.inputs(), false)?;
         write!(file, ") {{")?;
-        write!(file, "return {native_name}(")?;
+        if guards.thread_confined && self_kind.is_some() {
+            write!(file, "checkThread();")?;
+        }
+        if guards.consumable && self_kind.is_some() {
+            write!(file, "checkNotConsumed();")?;
+            // The Rust method takes ownership even if it fails, so invalidate the object first.
+            if self_kind.is_some_and(SelfKind::consumes) {
+                write!(file, "consumed = true;")?;
+            }
+        }
+
+        let fluent = builder && guards.consumable && self_kind.is_some_and(SelfKind::consumes);
+
+        // Values exchanged through their string form are converted (see `util::textual_ty`).
+        let output = signature.output_ty();
+        let textual_output = if output.is_unit() { None } else { self.textual_ty(output.main_ty().ty()) };
+        let return_kw = if output.is_unit() { "" } else { "return " };
+        let (prefix, suffix) = match textual_output {
+            Some(JavaTextual::Uuid) => (format!("{return_kw}java.util.UUID.fromString("), "));"),
+            Some(JavaTextual::InetAddress(class)) => {
+                // `getByName` does not look up address literals, but still declares `UnknownHostException`.
+                write!(file, "try {{")?;
+                let cast = if class == "java.net.InetAddress" { String::new() } else { format!("({class}) ") };
+                (format!("{return_kw}{cast}java.net.InetAddress.getByName("), "));")
+            }
+            Some(JavaTextual::String) | None if fluent => ("var next = ".to_string(), ");"),
+            Some(JavaTextual::String) | None => (return_kw.to_string(), ");"),
+        };
+        write!(file, "{prefix}{native_name}(")?;
+        if ordinal {
+            let sep = if signature.inputs().is_empty() { "" } else { "," };
+            write!(file, "ordinal(){sep}")?;
+        }
         for (input, sep) in signature.inputs().iter().comma_separated() {
-            write!(file, "{input_name}{sep}", input_name = input.name())?;
+            let name = input.name().to_string();
+            let argument = match self.textual_ty(input.refd_ty().ty()) {
+                Some(textual) => textual.to_string_expr(&name),
+                None => name,
+            };
+            write!(file, "{argument}{sep}")?;
+        }
+        write!(file, "{suffix}")?;
+        if let Some(JavaTextual::InetAddress(_)) = textual_output {
+            write!(file, "}} catch (java.net.UnknownHostException e) {{")?;
+            write!(file, "throw new IllegalStateException(e);")?;
+            write!(file, "}}")?;
+        }
+        if fluent {
+            // The object now holds the value the method returned, which `next` must not release.
+            write!(file, "pointer = next.pointer;")?;
+            write!(file, "next.pointer = 0;")?;
+            write!(file, "consumed = false;")?;
+            write!(file, "return this;")?;
         }
-        write!(file, ");")?;
         write!(file, "}}")?;
 
         Ok(())
     }
 
+    /// Generate the parameters of a method; `native` is true for the native counterpart,
+    /// which takes values exchanged through their string form as `String`.
     fn generate_function_inputs(
         &self,
         file: &mut CodeWriter<'_>,
         inputs: &[FunctionInput],
+        native: bool,
     ) -> anyhow::Result<()> {
         for (input, sep) in inputs.iter().comma_separated() {
             write!(
                 file,
                 "{ty} {name}{sep}",
-                ty = self.write_ty(input.refd_ty().ty())?,
+                ty = self.write_signature_ty(input.refd_ty().ty(), native)?,
                 name = input.name()
             )?;
         }
         Ok(())
     }
 
+    /// Generate the native method that Rust implements for the method `name`.
+    /// If `ordinal` is true, it is static and takes the ordinal of the enum constant first (see [`Guards::ordinal`]).
     fn generate_native_counterpart(
         &self,
         file: &mut CodeWriter<'_>,
         self_kind: Option<&SelfKind>,
         name: &Name,
         signature: &Signature,
+        ordinal: bool,
     ) -> anyhow::Result<String> {
-        let native_name = format!("native${name}");
+        let native_name = format!("native_{name}");
 
         write!(file, "")?;
 
-        let static_kw = if self_kind.is_none() { "static" } else { "" };
+        let static_kw = if self_kind.is_none() || ordinal { "static" } else { "" };
 
-        let return_ty = signature.output_ty().main_ty();
         write!(
             file,
             "public {static_kw} native {ret} {native_name}(",
-            ret = self.write_ty(return_ty.ty())?,
+            ret = self.write_return_ty(signature.output_ty(), true)?,
         )?;
-        self.generate_function_inputs(file, signature.inputs())?;
+        if ordinal {
+            let sep = if signature.inputs().is_empty() { "" } else { "," };
+            write!(file, "int ordinal{sep}")?;
+        }
+        self.generate_function_inputs(file, signature.inputs(), true)?;
         write!(file, ");")?;
 
         Ok(native_name)
     }
 
+    /// The Java return type for `output`: `void` for functions returning `()` or `Result<(), E>`
+    /// (errors are thrown as exceptions either way). `native` is as for `generate_function_inputs`.
+    fn write_return_ty(&self, output: &FunctionOutput, native: bool) -> anyhow::Result<String> {
+        if output.is_unit() {
+            return Ok("void".to_string());
+        }
+        self.write_signature_ty(output.main_ty().ty(), native)
+    }
+
+    /// The Java type of a parameter or result of type `ty`: like `write_ty`, but accepting the types
+    /// exchanged through their string form (see `util::textual_ty`), which are `String` if `native` is true.
+    fn write_signature_ty(&self, ty: &Ty, native: bool) -> anyhow::Result<String> {
+        match self.textual_ty(ty) {
+            Some(_) if native => Ok("String".to_string()),
+            Some(textual) => Ok(textual.java_ty().to_string()),
+            None => self.write_ty(ty),
+        }
+    }
+
+    fn textual_ty(&self, ty: &Ty) -> Option<JavaTextual> {
+        util::textual_ty(ty, self.metadata.textual_types)
+    }
+
     fn write_ty(&self, ty: &Ty) -> anyhow::Result<String> {
         match ty.kind() {
             TypeKind::Scalar(scalar) => match scalar {
@@ -295,51 +1285,139 @@ impl<'idl> JavaCodeGenerator<'idl> {
     }
 
     fn write_objectified_ty(&self, ty: &Ty) -> anyhow::Result<String> {
-        match ty.kind() {
-            TypeKind::Map { key, value, repr: _ } => Ok(format!(
-                "java.util.Map<{K}, {V}>",
-                K = self.write_objectified_ty(key)?,
-                V = self.write_objectified_ty(value)?,
-            )),
-            TypeKind::Vec { element, repr: _ } => Ok(format!(
-                "java.util.List<{E}>",
-                E = self.write_objectified_ty(element)?,
-            )),
-            TypeKind::Set { element, repr: _ } => Ok(format!(
-                "java.util.Set<{E}>",
-                E = self.write_objectified_ty(element)?,
-            )),
-            TypeKind::Path { repr: _ }=> Ok("String".to_string()),
-            TypeKind::String { repr: _ }=> Ok("String".to_string()),
-            TypeKind::Option { element, repr: _ } => self.write_objectified_ty(element),
+        ObjectTypeMapper {
+            idl: self.idl,
+            metadata: self.metadata,
+            names: self.names,
+        }
+        .map_ty(ty)
+    }
+}
+
+/// Maps IDL types to Java object types (e.g., `Integer` rather than `int`),
+/// which is what is needed for type arguments.
+struct ObjectTypeMapper<'idl> {
+    idl: &'idl Idl,
+    metadata: &'idl JavaMetadata,
+    names: JavaNames<'idl>,
+}
+
+impl TypeMapper for ObjectTypeMapper<'_> {
+    type Output = String;
 
+    fn map_ty(&mut self, ty: &Ty) -> anyhow::Result<String> {
+        match ty.kind() {
             // This is pretty bad, but the expectation is that people don't pass `Result`
             // around most of the time, they should up in return types where they
             // are specially handled.
             TypeKind::Result { ok: _, err: _, repr: _ } => Ok("Object".to_string()),
+            _ => type_mapper::walk_ty(self, ty),
+        }
+    }
 
-            // FIXME: I think tuple arguments should be flattened,
-            // since Java has no native concept of them.
-            TypeKind::Tuple { elements: _, repr: _ } => Ok("Object[]".to_string()),
+    fn map_map(&mut self, _ty: &Ty, key: String, value: String, _repr: &MapSetRepr) -> anyhow::Result<String> {
+        Ok(format!("java.util.Map<{key}, {value}>"))
+    }
 
-            TypeKind::Scalar(scalar) => match scalar {
-                Scalar::Char => Ok("Integer".to_string()),
-                Scalar::Boolean => Ok("Boolean".to_string()),
-                Scalar::I8 | Scalar::U8 => Ok("Byte".to_string()),
-                Scalar::I16 | Scalar::U16 => Ok("Short".to_string()),
-                Scalar::I32 | Scalar::U32 => Ok("Integer".to_string()),
-                Scalar::I64 | Scalar::U64 => Ok("Long".to_string()),
-                Scalar::F32 => Ok("Float".to_string()),
-                Scalar::F64 => Ok("Double".to_string()),
-                _ => anyhow::bail!("unsupported scalar type: `{scalar}`"),
-            },
-            TypeKind::Future { output, repr: _ } => Ok(format!(
-                "java.util.concurrent.Future<{V}>",
-                V = self.write_objectified_ty(output)?
-            )),
-            TypeKind::Error { repr: _} => todo!(),
-            TypeKind::UserType { qname } => Ok(util::class_dot_name(qname)),
-            _ => anyhow::bail!("unsupported type: `{ty}`"),
+    fn map_vec(&mut self, _ty: &Ty, element: String, _repr: &VecRepr) -> anyhow::Result<String> {
+        Ok(format!("java.util.List<{element}>"))
+    }
+
+    fn map_set(&mut self, _ty: &Ty, element: String, _repr: &MapSetRepr) -> anyhow::Result<String> {
+        Ok(format!("java.util.Set<{element}>"))
+    }
+
+    fn map_path(&mut self, _ty: &Ty, _repr: &PathRepr) -> anyhow::Result<String> {
+        Ok("String".to_string())
+    }
+
+    fn map_string(&mut self, _ty: &Ty, _repr: &StringRepr) -> anyhow::Result<String> {
+        Ok("String".to_string())
+    }
+
+    fn map_net_addr(&mut self, ty: &Ty, _repr: &NetAddrRepr) -> anyhow::Result<String> {
+        textual_unsupported(ty)
+    }
+
+    fn map_uuid(&mut self, ty: &Ty, _repr: &UuidRepr) -> anyhow::Result<String> {
+        textual_unsupported(ty)
+    }
+
+    fn map_option(&mut self, _ty: &Ty, element: String, _repr: &idl::OptionRepr) -> anyhow::Result<String> {
+        match self.metadata.option {
+            OptionRepr::Nullable => Ok(element),
+            OptionRepr::Optional => Ok(format!("java.util.Optional<{element}>")),
+        }
+    }
+
+    // Java has no native concept of tuples, so we generate a `TupleN` record class
+    // for each arity that is used (see `generate_tuple_class`).
+    fn map_tuple(&mut self, _ty: &Ty, elements: Vec<String>, _repr: &TupleRepr) -> anyhow::Result<String> {
+        if elements.is_empty() {
+            return Ok("Object[]".to_string());
+        }
+        Ok(format!(
+            "{T}<{E}>",
+            T = self.names.class_dot_name(&util::tuple_class_qname(self.idl, elements.len())),
+            E = elements.join(", "),
+        ))
+    }
+
+    fn map_scalar(&mut self, ty: &Ty, scalar: &Scalar) -> anyhow::Result<String> {
+        match scalar {
+            Scalar::Char => Ok("Integer".to_string()),
+            Scalar::Boolean => Ok("Boolean".to_string()),
+            Scalar::I8 | Scalar::U8 => Ok("Byte".to_string()),
+            Scalar::I16 | Scalar::U16 => Ok("Short".to_string()),
+            Scalar::I32 | Scalar::U32 => Ok("Integer".to_string()),
+            Scalar::I64 | Scalar::U64 => Ok("Long".to_string()),
+            Scalar::F32 => Ok("Float".to_string()),
+            Scalar::F64 => Ok("Double".to_string()),
+            _ => type_mapper::unsupported(ty),
         }
     }
+
+    fn map_future(&mut self, _ty: &Ty, output: String, _repr: &FutureRepr) -> anyhow::Result<String> {
+        Ok(format!("java.util.concurrent.Future<{output}>"))
+    }
+
+    fn map_stream(&mut self, ty: &Ty, _item: String, _repr: &idl::StreamRepr) -> anyhow::Result<String> {
+        anyhow::bail!("{span}: the Java backend does not support streams yet (`{ty}`)", span = ty.span())
+    }
+
+    fn map_user_type(&mut self, _ty: &Ty, qname: &QualifiedName) -> anyhow::Result<String> {
+        Ok(self.names.class_dot_name(qname))
+    }
+
+    fn map_trait_object(&mut self, _ty: &Ty, qname: &QualifiedName, _repr: &TraitObjectRepr) -> anyhow::Result<String> {
+        Ok(self.names.class_dot_name(qname))
+    }
+}
+
+/// Report that `ty`, which is exchanged through its string form, appears somewhere other than
+/// as a function argument or return type (e.g., as the element of a `List` or the field of a record).
+fn textual_unsupported<T>(ty: &Ty) -> anyhow::Result<T> {
+    anyhow::bail!(
+        "{span}: `{ty}` is only supported by the Java backend as a function argument or return type",
+        span = ty.span(),
+    )
+}
+
+/// The checks that the instance methods of a class perform before calling into Rust, and how they call it.
+#[derive(Copy, Clone, Default)]
+struct Guards {
+    /// Some method takes `self` by value, so the class has a `checkNotConsumed` method
+    /// (see `generate_consumed_check`).
+    consumable: bool,
+
+    /// The Rust value is not `Send`, so the class has a `checkThread` method (see `generate_thread_check`).
+    thread_confined: bool,
+
+    /// The Rust value is `Send` but not `Sync`, or some method takes `&mut self` or `self`,
+    /// so instance methods are `synchronized`.
+    synchronized: bool,
+
+    /// The class is a Java enum, whose constants do not hold a Rust value: instance methods pass their
+    /// `ordinal()` to a static native method, which calls the Rust method on the arm of the same index.
+    ordinal: bool,
 }
@@ -1,65 +1,150 @@
+use std::path::PathBuf;
+
 use anyhow::Context;
-use camino::Utf8PathBuf;
 use gluegun_core::{
-    cli::{GenerateCx, GlueGunHelper},
-    codegen::{AddDependency, LibraryCrate},
+    cli::{GenerateCx, GlueGunHelper, WorkspaceCx},
+    codegen::{AddDependency, Feature, LibraryCrate, RequiredTools, WorkspaceFiles},
+    naming::{Case, Naming},
 };
+use metadata::JavaMetadata;
 
 mod java_gen;
+mod maven_gen;
+mod metadata;
 mod rs_gen;
+mod test_gen;
+mod usage_gen;
 mod util;
 
 pub fn main() -> anyhow::Result<()> {
     gluegun_core::cli::run(GlueGunJava)
 }
 
+#[derive(Clone)]
 struct GlueGunJava;
 
 impl GlueGunHelper for GlueGunJava {
-    type Metadata = ();
+    type Metadata = JavaMetadata;
+
+    const SUPPORTS_PACK_PARAMS: bool = true;
 
     fn name(&self) -> String {
         "java".to_string()
     }
 
-    fn generate(self, cx: &mut GenerateCx, &(): &(), output: &mut LibraryCrate) -> anyhow::Result<()> {
+    /// Packages and record components are camelCase and classes UpperCamelCase (see `util::JavaNames`);
+    /// methods keep their Rust names, except for overloads (see `JavaMetadata::method_name`).
+    fn naming(&self, metadata: &JavaMetadata) -> Naming {
+        Naming::new()
+            .modules(Case::Camel)
+            .types(Case::UpperCamel)
+            .functions(metadata.rename_all)
+            .fields(metadata.rename_all)
+            .overloads(metadata.overloads)
+    }
+
+    fn generate(self, cx: &mut GenerateCx, metadata: &JavaMetadata, output: &mut LibraryCrate) -> anyhow::Result<()> {
+        let is_identifier = |name: &str| {
+            name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_')
+                && name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '$')
+        };
+        if let Some(package) = &metadata.package {
+            if !package.split('.').all(is_identifier) {
+                anyhow::bail!("`package` must be a Java package name like `com.example.mylib`, not `{package}`");
+            }
+        }
+        if let Some(module_name) = &metadata.module_name {
+            if !module_name.split('.').all(is_identifier) {
+                anyhow::bail!("`module-name` must be a Java module name like `com.example.mylib`, not `{module_name}`");
+            }
+        }
+
         // libary dependencies
         output.add_dependency("duchess").version("0.3");
+        // Even with the duchess glue, interfaces, collections, tuples, and optionals go through JNI.
+        output.add_dependency("jni").version("0.21");
+        if util::uses_indexmap(cx.idl())? {
+            output.require_feature(Feature::IndexMap);
+        }
 
         // build-rs dependencies
         output.add_dependency("anyhow").version("1").build();
-        self.add_gluegun_java_util(output)?.build();
+        self.add_gluegun_java_util(output).build();
 
         // binary dependencies
         output.add_dependency("anyhow").version("1");
-        self.add_gluegun_java_util(output)?;
+        self.add_gluegun_java_util(output);
 
         let java_src_dir = output
-            .add_dir("java_src")
+            .add_dir(metadata.java_src_dir())
             .with_context(|| format!("adding `java_src` dir"))?;
-        java_gen::JavaCodeGenerator::new(cx.idl())
+        java_gen::JavaCodeGenerator::new(cx.idl(), metadata)
             .generate(java_src_dir)
-            .with_context(|| format!("generaring Java sources"))?;
+            .with_context(|| format!("generating Java sources"))?;
+
+        // Java sources among the `extra-files` are compiled along with the generated ones.
+        for extra_file in cx.extra_files().iter() {
+            if extra_file.path().extension().is_some_and(|extension| extension == "java") {
+                output.add_extra_file(extra_file, metadata.java_src_dir())?;
+            }
+        }
+
+        // Doc examples become JUnit test stubs (only extracted if `doc-examples` is enabled).
+        let test_gen = test_gen::ExampleTestGenerator::new(cx.idl(), metadata);
+        if test_gen.has_examples() {
+            let java_test_dir = output
+                .add_dir("java_test")
+                .context("adding `java_test` dir")?;
+            test_gen
+                .generate(java_test_dir)
+                .context("generating Java example tests")?;
+        }
 
-        rs_gen::RustCodeGenerator::new(cx.idl())
+        rs_gen::RustCodeGenerator::new(cx.idl(), metadata)
             .generate(output)
-            .with_context(|| format!("generaring Rust sources"))?;
+            .with_context(|| format!("generating Rust sources"))?;
+
+        usage_gen::UsageGenerator::new(cx.idl(), metadata).generate(output);
+
+        if let Some(group_id) = &metadata.maven_group_id {
+            maven_gen::MavenGenerator::new(group_id)
+                .generate_crate(cx.idl(), &metadata.java_src_dir(), output)
+                .context("generating `pom.xml`")?;
+        }
 
         Ok(())
     }
+
+    /// With `maven-group-id`, a `pom.xml` aggregating those of the generated crates.
+    fn generate_workspace(&self, cx: &WorkspaceCx, metadata: &JavaMetadata, output: &mut WorkspaceFiles) -> anyhow::Result<()> {
+        match &metadata.maven_group_id {
+            Some(group_id) => maven_gen::MavenGenerator::new(group_id).generate_workspace(cx, output),
+            None => Ok(()),
+        }
+    }
+
+    /// The build script compiles the Java sources with `javac`, and `duchess` finds the JVM through `JAVA_HOME`;
+    /// with `maven-group-id`, the Java sources are also built with `mvn`.
+    fn required_tools(&self, metadata: &JavaMetadata, tools: &mut RequiredTools) {
+        tools
+            .require_helper_command("javac")
+            .or_fail("install a JDK (17 or later), e.g. from https://adoptium.net".to_string());
+        tools.require_env_var("JAVA_HOME", "set it to the directory of the JDK");
+        if metadata.maven_group_id.is_some() {
+            tools
+                .require_helper_command("mvn")
+                .or_fail("install Maven, e.g. from https://maven.apache.org/download.cgi".to_string());
+        }
+    }
 }
 
 impl GlueGunJava {
-    fn add_gluegun_java_util<'lib>(&self, lib: &'lib mut LibraryCrate) -> anyhow::Result<AddDependency<'lib>> {
-        let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") else {
-            anyhow::bail!("no CARGO_MANIFEST_DIR variable set")
-        };
-        let mut manifest_path = Utf8PathBuf::from(manifest_dir);
-        manifest_path.pop();
-        manifest_path.push("gluegun-java-util");
-
-        // FIXME: we should eventually get this from crates.io, at least when not testing
-        Ok(lib.add_dependency("gluegun-java-util")
-            .path(manifest_path))
+    /// The generated crate uses `gluegun-java-util` from crates.io,
+    /// unless we are running from the gluegun workspace (e.g., in tests).
+    fn add_gluegun_java_util<'lib>(&self, lib: &'lib mut LibraryCrate) -> AddDependency<'lib> {
+        let mut local_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        local_path.pop();
+        local_path.push("gluegun-java-util");
+        lib.add_dependency_published_or_path("gluegun-java-util", "0.1", local_path)
     }
 }
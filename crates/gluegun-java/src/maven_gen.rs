@@ -0,0 +1,81 @@
+use std::{fmt::Write, path::Path};
+
+use gluegun_core::{
+    cli::WorkspaceCx,
+    codegen::{LibraryCrate, WorkspaceFiles},
+    idl::Idl,
+};
+
+/// Version of the aggregator project, which is never published.
+const AGGREGATOR_VERSION: &str = "1.0-SNAPSHOT";
+
+/// Generates the Maven build files enabled by `maven-group-id`: a `pom.xml` in each generated crate,
+/// which compiles its Java sources, and a `pom.xml` at the workspace root listing them as modules,
+/// so that `mvn package` at the root builds the Java sources of all the crates.
+pub(crate) struct MavenGenerator<'m> {
+    group_id: &'m str,
+}
+
+impl<'m> MavenGenerator<'m> {
+    pub(crate) fn new(group_id: &'m str) -> Self {
+        Self { group_id }
+    }
+
+    /// The `pom.xml` of the crate generated for `idl`, whose artifact is named after the crate
+    /// and whose Java sources are in `java_src_dir` (see `JavaMetadata::java_src_dir`).
+    pub(crate) fn generate_crate(&self, idl: &Idl, java_src_dir: &Path, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        let version = idl.crate_metadata().version().as_deref().unwrap_or("0.1.0");
+        let mut pom = self.project_header(lib.crate_name(), version, "jar");
+        writeln!(pom, "  <properties>")?;
+        writeln!(pom, "    <maven.compiler.release>17</maven.compiler.release>")?;
+        writeln!(pom, "    <project.build.sourceEncoding>UTF-8</project.build.sourceEncoding>")?;
+        writeln!(pom, "  </properties>")?;
+        writeln!(pom, "  <build>")?;
+        writeln!(pom, "    <sourceDirectory>{}</sourceDirectory>", java_src_dir.display())?;
+        writeln!(pom, "  </build>")?;
+        writeln!(pom, "</project>")?;
+
+        let mut file = lib.add_file("pom.xml")?;
+        for line in pom.lines() {
+            file.write_verbatim(line)?;
+        }
+        Ok(())
+    }
+
+    /// The `pom.xml` at the workspace root, aggregating those of the crates generated in it.
+    pub(crate) fn generate_workspace(&self, cx: &WorkspaceCx, output: &mut WorkspaceFiles) -> anyhow::Result<()> {
+        if cx.crates().is_empty() {
+            return Ok(());
+        }
+
+        let root_name = cx
+            .workspace_root()
+            .file_name()
+            .map_or("workspace".into(), |name| name.to_string_lossy());
+        let mut pom = self.project_header(&format!("{root_name}-java"), AGGREGATOR_VERSION, "pom");
+        writeln!(pom, "  <modules>")?;
+        for krate in cx.crates() {
+            let module = krate.path().strip_prefix(cx.workspace_root()).unwrap_or(krate.path());
+            writeln!(pom, "    <module>{}</module>", module.display())?;
+        }
+        writeln!(pom, "  </modules>")?;
+        writeln!(pom, "</project>")?;
+        output.add_file("pom.xml", pom)
+    }
+
+    /// The start of a `pom.xml`, up to the packaging of the project. There is no XML declaration,
+    /// as the `file-header` comment (if any) comes first.
+    fn project_header(&self, artifact_id: &str, version: &str, packaging: &str) -> String {
+        let group_id = self.group_id;
+        let mut pom = String::new();
+        pom.push_str("<project xmlns=\"http://maven.apache.org/POM/4.0.0\"\n");
+        pom.push_str("         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"\n");
+        pom.push_str("         xsi:schemaLocation=\"http://maven.apache.org/POM/4.0.0 https://maven.apache.org/xsd/maven-4.0.0.xsd\">\n");
+        pom.push_str("  <modelVersion>4.0.0</modelVersion>\n");
+        pom.push_str(&format!("  <groupId>{group_id}</groupId>\n"));
+        pom.push_str(&format!("  <artifactId>{artifact_id}</artifactId>\n"));
+        pom.push_str(&format!("  <version>{version}</version>\n"));
+        pom.push_str(&format!("  <packaging>{packaging}</packaging>\n"));
+        pom
+    }
+}
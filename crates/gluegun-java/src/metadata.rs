@@ -0,0 +1,179 @@
+use std::path::{Path, PathBuf};
+
+use gluegun_core::{
+    cli::{DescribeMetadata, MetadataKind, MetadataSchema},
+    idl::{Method, Name},
+    naming::{Case, OverloadPolicy},
+};
+use serde::Deserialize;
+
+/// Configuration for the Java helper, read from `[package.metadata.gluegun.java]`
+/// (or the `workspace` equivalent).
+#[derive(Deserialize, Debug)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct JavaMetadata {
+    /// How `Option<T>` is represented in Java signatures.
+    pub option: OptionRepr,
+
+    /// How native methods are bound to Rust.
+    pub glue: Glue,
+
+    /// How IP addresses and UUIDs are represented in Java signatures.
+    pub textual_types: TextualTypes,
+
+    /// The case of the names of fields and methods in Java.
+    pub rename_all: Case,
+
+    /// How the methods of a group of overloads are named in Java.
+    pub overloads: OverloadPolicy,
+
+    /// If set, the Java package of the crate (e.g., `com.example.mylib`), which otherwise is its name in camelCase.
+    /// The packages of its modules are nested in it.
+    pub package: Option<String>,
+
+    /// If set, the group ID of the Maven projects generated for the Java sources of each crate
+    /// and aggregating them at the workspace root.
+    pub maven_group_id: Option<String>,
+
+    /// If set, the name of the Java module (e.g., `com.example.mylib`) whose `module-info.java` exports
+    /// the packages of the generated classes. The sources are then in `java_src/{module}` (see [`Self::java_src_dir`]).
+    pub module_name: Option<String>,
+
+    /// The modules that the `module-info.java` requires (e.g., `java.logging` or `transitive java.sql`),
+    /// besides `java.base`.
+    pub module_requires: Vec<String>,
+}
+
+impl JavaMetadata {
+    /// The Java name of a field or method, in the case selected by `rename-all`.
+    pub fn member_name(&self, name: &Name) -> Name {
+        self.rename_all.apply(name)
+    }
+
+    /// The Java name of a method, which is that of its group of overloads (if any) with the `overload` policy.
+    pub fn method_name(&self, method: &Method) -> Name {
+        self.member_name(&self.overloads.method_name(method))
+    }
+
+    /// The directory of the generated crate with the Java sources: `java_src`, or the directory named after
+    /// the `module-name` in it, which is the layout `javac --module-source-path java_src` expects.
+    pub fn java_src_dir(&self) -> PathBuf {
+        match &self.module_name {
+            Some(module_name) => Path::new("java_src").join(module_name),
+            None => PathBuf::from("java_src"),
+        }
+    }
+}
+
+impl Default for JavaMetadata {
+    fn default() -> Self {
+        Self {
+            option: Default::default(),
+            glue: Default::default(),
+            textual_types: Default::default(),
+            rename_all: Case::Camel,
+            overloads: OverloadPolicy::Overload,
+            package: None,
+            maven_group_id: None,
+            module_name: None,
+            module_requires: vec![],
+        }
+    }
+}
+
+impl DescribeMetadata for JavaMetadata {
+    fn describe() -> MetadataSchema {
+        MetadataSchema::new()
+            .option(
+                "option",
+                MetadataKind::one_of(["nullable", "optional"]),
+                "How `Option<T>` is represented in Java signatures",
+            )
+            .option(
+                "glue",
+                MetadataKind::one_of(["duchess", "jni"]),
+                "How native methods are bound to Rust; `jni` binds functions with primitive and string signatures directly",
+            )
+            .option(
+                "textual-types",
+                MetadataKind::one_of(["native", "string"]),
+                "How IP addresses and UUIDs are represented: as `java.net.InetAddress` and `java.util.UUID`, or as strings",
+            )
+            .option(
+                "rename-all",
+                MetadataKind::one_of(Case::METADATA_NAMES),
+                "The case of field and method names, as in `#[serde(rename_all)]`; defaults to `camelCase`",
+            )
+            .option(
+                "overloads",
+                MetadataKind::one_of(OverloadPolicy::METADATA_NAMES),
+                "How the methods of a group of overloads (`#[gluegun::overload(name)]`) are named; defaults to `overload`",
+            )
+            .option(
+                "package",
+                MetadataKind::String,
+                "The Java package of the crate (e.g., `com.example.mylib`), under which its modules are nested; \
+                 defaults to the crate name in camelCase",
+            )
+            .option(
+                "maven-group-id",
+                MetadataKind::String,
+                "Generate a Maven `pom.xml` with this group ID for each crate, and one aggregating them at the workspace root",
+            )
+            .option(
+                "module-name",
+                MetadataKind::String,
+                "Generate a `module-info.java` for a Java module with this name, exporting the generated packages, \
+                 and put the Java sources in `java_src/{module-name}`",
+            )
+            .option(
+                "module-requires",
+                MetadataKind::StringList,
+                "The modules that the `module-info.java` requires besides `java.base` (e.g., `java.logging`)",
+            )
+    }
+}
+
+/// Java representation of a Rust `Option<T>`.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum OptionRepr {
+    /// `Option<T>` maps to the (boxed) Java type for `T` and `None` maps to `null`.
+    #[default]
+    Nullable,
+
+    /// `Option<T>` maps to `java.util.Optional<T>`.
+    Optional,
+}
+
+/// How the native methods of the generated Java classes are bound to Rust.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Glue {
+    /// Every native method is implemented with duchess, which converts arguments and results
+    /// through Java objects on each call.
+    #[default]
+    Duchess,
+
+    /// Native methods whose arguments and results are primitives, strings, or optional primitives
+    /// are implemented as raw JNI functions: primitives are passed as is, strings are read in place
+    /// (`GetStringUTFChars`), and boxed primitives are converted with method IDs cached on first use.
+    /// Other native methods still use duchess, except those exchanging trait objects, collections, tuples,
+    /// `java.util.Optional`s, or user types, the methods of records and resources, and those throwing error enums,
+    /// which are raw JNI functions whatever the glue.
+    Jni,
+}
+
+/// Java representation of the types exchanged as strings (IP and socket addresses, UUIDs).
+/// The native methods always take and return the string; the public methods convert it.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum TextualTypes {
+    /// IP addresses map to `java.net.InetAddress` (or `Inet4Address`, `Inet6Address`), and UUIDs to `java.util.UUID`.
+    /// Socket addresses are strings (e.g., `"127.0.0.1:8080"`), as `InetSocketAddress` cannot be parsed from one.
+    #[default]
+    Native,
+
+    /// All of them map to `String`, in their usual string form.
+    String,
+}
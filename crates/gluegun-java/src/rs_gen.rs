@@ -1,21 +1,71 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use gluegun_core::{
     codegen::{CodeWriter, LibraryCrate},
     idl::{
-        Enum, FunctionInput, FunctionOutput, Idl, Item, Method, MethodCategory, Name, QualifiedName, Record, RefdTy, Resource, Signature, TypeKind, Variant
+        Enum, ErrorRepr, FunctionInput, FunctionOutput, Idl, Interface, IsAsync, Item, MapSetRepr, Method, MethodCategory, Name, Newtype, QualifiedName, Record, RefdTy, Resource, Scalar, SelfKind, Signature, TraitObjectRepr, Ty, TypeKind, UserTypeRepr, Variant
     },
 };
 
-use crate::util::{self, AsTy, JavaQName};
+use crate::{
+    metadata::{Glue, JavaMetadata, OptionRepr},
+    util::{self, AsTy, JavaNames, JavaQName},
+};
 
 pub(crate) struct RustCodeGenerator<'idl> {
     idl: &'idl Idl,
+    metadata: &'idl JavaMetadata,
+    names: JavaNames<'idl>,
+
+    /// The records, variants, newtypes, and enums converted to and from Java through JNI (see [`Self::jni_user_types`]).
+    jni_types: BTreeSet<QualifiedName>,
 }
 
 impl<'idl> RustCodeGenerator<'idl> {
-    pub(crate) fn new(idl: &'idl Idl) -> Self {
-        Self { idl }
+    pub(crate) fn new(idl: &'idl Idl, metadata: &'idl JavaMetadata) -> Self {
+        let mut generator = Self { idl, metadata, names: JavaNames::new(idl, metadata), jni_types: BTreeSet::default() };
+        generator.jni_types = generator.jni_user_types();
+        generator
+    }
+
+    /// The records, variants, newtypes, and enums whose fields are all values of [`Self::is_jni_element`],
+    /// which get the conversion functions of `generate_from_java` and `generate_to_java`.
+    /// Starting from all of them, we drop those with a field we cannot convert until none is left,
+    /// so that recursive types (e.g., `next: Option<Box<Node>>`) are kept.
+    fn jni_user_types(&mut self) -> BTreeSet<QualifiedName> {
+        self.jni_types = self
+            .idl
+            .definitions()
+            .iter()
+            .filter(|(_, item)| {
+                matches!(item, Item::Record(_) | Item::Variant(_) | Item::Newtype(_) | Item::Enum(_)) && !is_params_record(item)
+            })
+            .map(|(qname, _)| qname.clone())
+            .collect();
+        loop {
+            let rejected: Vec<QualifiedName> = self
+                .jni_types
+                .iter()
+                .filter(|qname| {
+                    let field_tys: Vec<&Ty> = match self.idl.item(qname) {
+                        Some(Item::Record(record)) => record.fields().iter().map(|field| field.ty()).collect(),
+                        Some(Item::Variant(variant)) => {
+                            variant.arms().iter().flat_map(|arm| arm.fields()).map(|field| field.ty()).collect()
+                        }
+                        Some(Item::Newtype(newtype)) => vec![newtype.ty()],
+                        _ => vec![],
+                    };
+                    !field_tys.into_iter().all(|ty| self.is_jni_element(ty))
+                })
+                .cloned()
+                .collect();
+            if rejected.is_empty() {
+                return std::mem::take(&mut self.jni_types);
+            }
+            for qname in rejected {
+                self.jni_types.remove(&qname);
+            }
+        }
     }
 
     pub(crate) fn generate(mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
@@ -30,15 +80,45 @@ impl<'idl> RustCodeGenerator<'idl> {
 
         write!(lib_rs, "#![allow(non_snake_case)]")?; // FIXME: bug in duchess
 
+        self.generate_interface_hash(&mut lib_rs)?;
         self.generate_java_classes(&mut lib_rs)?;
+        if util::uses_io_errors(self.idl)? {
+            self.generate_io_error_to_java(&mut lib_rs)?;
+        }
 
         for (qname, item) in self.idl.definitions() {
-            self.generate_item(&mut lib_rs, qname, item)?;
+            lib_rs.with_span(item.span(), |lib_rs| self.generate_item(lib_rs, qname, item))?;
         }
 
         Ok(())
     }
 
+    /// Generate the hash of the interface (see [`Idl::interface_hash`]) and the native method
+    /// of the `InterfaceHash` class (see [`util::interface_hash_qname`]) that returns it, e.g.
+    ///
+    /// ```rust,ignore
+    /// pub const GLUEGUN_INTERFACE_HASH: u64 = 0x0123456789abcdef;
+    ///
+    /// #[no_mangle]
+    /// pub extern "system" fn Java_myCrate_gluegun_InterfaceHash_nativeInterfaceHash(...) -> i64 { ... }
+    /// ```
+    ///
+    /// The function ignores its arguments, so it is declared with raw pointers and does not need the `jni` crate.
+    fn generate_interface_hash(&self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "/// Hash of the interface these bindings were generated from.")?;
+        write!(lib_rs, "pub const GLUEGUN_INTERFACE_HASH: u64 = {:#018x};", self.idl.interface_hash())?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[no_mangle]")?;
+        write!(
+            lib_rs,
+            "pub extern \"system\" fn {symbol}(_env: *mut std::ffi::c_void, _class: *mut std::ffi::c_void) -> i64 {{",
+            symbol = self.names.jni_symbol(&util::interface_hash_qname(self.idl), "nativeInterfaceHash"),
+        )?;
+        write!(lib_rs, "GLUEGUN_INTERFACE_HASH as i64")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
     fn generate_build_rs(&mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
         let mut build_rs = lib.add_file("build.rs")?;
         write!(
@@ -58,33 +138,59 @@ impl<'idl> RustCodeGenerator<'idl> {
     }
 
 
+    /// Declare the Java classes that the native code uses with `duchess::java_package!`. All packages are declared
+    /// in a single invocation, since each invocation defines the modules of the packages it declares
+    /// (so declaring `myCrate` and `myCrate.gluegun` separately would define `myCrate` twice).
     fn generate_java_classes(&self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
-        let mut map = BTreeMap::default();
+        let mut classes = BTreeSet::default();
 
         for (qname, item) in self.idl.definitions() {
-            let java_qname = self.java_class(qname, item)?;
-            map.entry(java_qname).or_insert(vec![]).push(item);
+            // Interfaces are called through JNI (see `generate_interface_adapter`).
+            if let Item::Interface(_) = item {
+                continue;
+            }
+            if is_params_record(item) {
+                continue;
+            }
+            classes.insert(self.java_class(qname, item)?);
         }
 
-        for (java_qname, _items) in map {
+        // The classes to declare, by package, with the members to bind.
+        let mut packages: BTreeMap<String, BTreeMap<Name, &str>> = BTreeMap::default();
+        for java_qname in classes {
             // FIXME: Do we want to generate items or Java-based members in any of these classes?
-            
-            write!(lib_rs, "duchess::java_package! {{")?;
-            write!(lib_rs, "package {};", java_qname.package.dotted())?;
-            write!(lib_rs, "class {} {{ }}", java_qname.class_name)?;
-            write!(lib_rs, "}}")?;
+            packages.entry(java_qname.package.dotted()).or_default().insert(java_qname.class_name, "");
         }
 
+        // `std::io::Error`s are thrown as the exception constructed by the `of` method of the generated `IoErrors` class
+        // (see `generate_io_error_to_java`). Other Java classes are only used by the JNI glue.
+        if util::uses_io_errors(self.idl)? {
+            let java_qname = self.names.class_package_and_name(&util::io_errors_qname(self.idl));
+            packages.entry(java_qname.package.dotted()).or_default().insert(java_qname.class_name, "*");
+        }
+
+        if packages.is_empty() {
+            return Ok(());
+        }
+        write!(lib_rs, "duchess::java_package! {{")?;
+        for (package, classes) in &packages {
+            write!(lib_rs, "package {package};")?;
+            for (class_name, members) in classes {
+                write!(lib_rs, "class {class_name} {{ {members} }}")?;
+            }
+        }
+        write!(lib_rs, "}}")?;
+
         Ok(())
     }
 
     fn java_class(&self, qname: &QualifiedName, item: &Item) -> anyhow::Result<JavaQName> {
         match item {
-            Item::Resource(_) | Item::Record(_) | Item::Variant(_) | Item::Enum(_) => {
-                Ok(util::class_package_and_name(qname))
+            Item::Resource(_) | Item::Record(_) | Item::Newtype(_) | Item::Variant(_) | Item::Enum(_) => {
+                Ok(self.names.class_package_and_name(qname))
             }
             Item::Function(_) => {
-                let package = qname.module_name().camel_case();
+                let package = self.names.package(&qname.module_path());
                 Ok(JavaQName {
                     package,
                     class_name: Name::from("Functions"),
@@ -104,15 +210,17 @@ impl<'idl> RustCodeGenerator<'idl> {
     ) -> anyhow::Result<()> {
         match item {
             Item::Resource(resource) => self.generate_resource(lib_rs, qname, resource),
+            Item::Record(_) if is_params_record(item) => Ok(()),
             Item::Record(record) => self.generate_record(lib_rs, qname, record),
+            Item::Newtype(newtype) => self.generate_newtype(lib_rs, qname, newtype),
             Item::Variant(variant) => self.generate_variant(lib_rs, qname, variant),
             Item::Enum(an_enum) => self.generate_enum(lib_rs, qname, an_enum),
             Item::Function(f) => {
-                let module_name = qname.module_name();
-                let java_qname = module_name.join("Functions");
+                let module_path = qname.module_path();
+                let java_qname = module_path.join("Functions");
                 self.generate_native_function(
                     lib_rs,
-                    &module_name,
+                    module_path.qname(),
                     &java_qname,
                     f.name(),
                     &MethodCategory::StaticMethod,
@@ -120,19 +228,213 @@ impl<'idl> RustCodeGenerator<'idl> {
                 )?;
                 Ok(())
             }
+            Item::Interface(interface) => self.generate_interface_adapter(lib_rs, qname, interface),
             _ => anyhow::bail!("unsupported item: {item:?}"),
         }
     }
 
+    /// Generate an adapter implementing the trait of an interface by calling the methods
+    /// of a Java object through JNI, e.g.
+    ///
+    /// ```rust,ignore
+    /// pub struct my_crate_Listener(gluegun_java_util::jni_support::ForeignObject);
+    ///
+    /// impl my_crate::Listener for my_crate_Listener {
+    ///     fn on_event(&self, count: u32) -> u32 {
+    ///         self.0
+    ///             .call(|env, this| {
+    ///                 let count = jni::objects::JValueOwned::from(gluegun_java_util::jni_support::ToJava::to_java(count, env)?);
+    ///                 let result = env.call_method(this, "onEvent", "(I)I", &[jni::objects::JValue::from(&count)])?;
+    ///                 gluegun_java_util::jni_support::from_java_value::<u32>(env, result)
+    ///             })
+    ///             .unwrap_or_else(|err| panic!("`Listener.on_event` failed: {err}"))
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Functions taking a trait object are always raw JNI functions, which wrap the Java object
+    /// they are given in the adapter (see `generate_jni_function`). Like those, the adapter only
+    /// exchanges primitives, strings, and optional primitives. The methods of the trait cannot
+    /// report a Java exception, so the adapter panics with its description.
+    fn generate_interface_adapter(
+        &self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        interface: &Interface,
+    ) -> anyhow::Result<()> {
+        let adapter = interface_adapter(qname);
+        write!(lib_rs, "#[allow(non_camel_case_types)]")?;
+        write!(lib_rs, "pub struct {adapter}(gluegun_java_util::jni_support::ForeignObject);")?;
+        write!(lib_rs, "impl {} for {adapter} {{", rust_item_path(qname))?;
+        for method in interface.methods() {
+            lib_rs.with_span(method.span(), |lib_rs| self.generate_interface_method(lib_rs, qname, method))?;
+        }
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    fn generate_interface_method(
+        &self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        method: &Method,
+    ) -> anyhow::Result<()> {
+        let name = method.name();
+        let signature = method.signature();
+        let output = signature.output_ty();
+
+        // The JNI descriptor of the Java method, e.g. `(Ljava/lang/String;I)I`.
+        let mut descriptor = String::from("(");
+        for input in signature.inputs() {
+            descriptor.push_str(&self.jni_descriptor(qname, method, input.refd_ty().ty())?);
+        }
+        descriptor.push(')');
+        if output.is_unit() {
+            descriptor.push('V');
+        } else if output.requires_copy_out() || output.error_ty().is_some() {
+            anyhow::bail!(
+                "{span}: `{qname}::{name}` must return an owned value, not a reference or a `Result`, to be implemented in Java",
+                span = method.span(),
+                qname = qname.colon_colon(),
+            );
+        } else {
+            descriptor.push_str(&self.jni_descriptor(qname, method, output.main_ty().ty())?);
+        }
+
+        let self_param = match method.category().self_kind() {
+            Some(SelfKind::ByRefMut) => "&mut self",
+            _ => "&self",
+        };
+        let inputs: Vec<String> = std::iter::once(self_param.to_string())
+            .chain(
+                signature
+                    .inputs()
+                    .iter()
+                    .map(|input| format!("{}: {}", input.name(), input.refd_ty().rust_absolute_spelling())),
+            )
+            .collect();
+        if output.is_unit() {
+            write!(lib_rs, "fn {name}({}) {{", inputs.join(", "))?;
+        } else {
+            write!(lib_rs, "fn {name}({}) -> {} {{", inputs.join(", "), output.main_ty().rust_absolute_spelling())?;
+        }
+        write!(lib_rs, "self.0.call(|env, this| {{")?;
+
+        let mut arguments = vec![];
+        for input in signature.inputs() {
+            let name = input.name();
+            write!(
+                lib_rs,
+                "let {name} = jni::objects::JValueOwned::from(gluegun_java_util::jni_support::ToJava::to_java({name}, env)?);",
+            )?;
+            arguments.push(format!("jni::objects::JValue::from(&{name})"));
+        }
+        let prefix = if output.is_unit() { "" } else { "let result = " };
+        write!(
+            lib_rs,
+            "{prefix}env.call_method(this, {:?}, {descriptor:?}, &[{}])?;",
+            self.metadata.member_name(name).to_string(),
+            arguments.join(", "),
+        )?;
+
+        if output.is_unit() {
+            write!(lib_rs, "Ok(())")?;
+        } else {
+            write!(
+                lib_rs,
+                "gluegun_java_util::jni_support::from_java_value::<{}>(env, result)",
+                output.main_ty().rust_absolute_spelling(),
+            )?;
+        }
+
+        write!(lib_rs, "}})")?;
+        write!(
+            lib_rs,
+            ".unwrap_or_else(|err| panic!(\"`{}.{name}` failed: {{err}}\"))",
+            self.names.class_package_and_name(qname).class_name,
+        )?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// The JNI type descriptor (e.g., `I` or `Ljava/lang/String;`) of `ty`, a parameter or result
+    /// of the method of the interface `qname`, if it is one of the types the adapter can exchange
+    /// (see `generate_interface_adapter`).
+    fn jni_descriptor(&self, qname: &QualifiedName, method: &Method, ty: &Ty) -> anyhow::Result<String> {
+        let descriptor = match ty.kind() {
+            TypeKind::Scalar(scalar) => jni_primitive_descriptor(scalar).map(|(descriptor, _)| descriptor.to_string()),
+            TypeKind::String { repr: _ } => Some("Ljava/lang/String;".to_string()),
+            TypeKind::Option { element, repr: _ } if self.metadata.option == OptionRepr::Nullable => {
+                match element.kind() {
+                    TypeKind::Scalar(scalar) => {
+                        jni_primitive_descriptor(scalar).map(|(_, class)| format!("Ljava/lang/{class};"))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        match descriptor {
+            Some(descriptor) => Ok(descriptor),
+            None => anyhow::bail!(
+                "{span}: `{qname}::{name}` exchanges `{ty}`, which Java implementations of an interface cannot handle yet",
+                span = ty.span(),
+                qname = qname.colon_colon(),
+                name = method.name(),
+            ),
+        }
+    }
+
     fn generate_resource(
         &self,
         lib_rs: &mut CodeWriter<'_>,
         qname: &QualifiedName,
         resource: &Resource,
     ) -> Result<(), anyhow::Error> {
+        self.generate_resource_to_java(lib_rs, qname)?;
         for method in resource.methods() {
             self.generate_method(lib_rs, qname, method)?;
         }
+
+        // The methods of its interfaces are called like inherent methods, which requires their trait in scope.
+        let inherited_methods = self.idl.inherited_methods(resource);
+        let mut interfaces: Vec<&QualifiedName> = inherited_methods.iter().map(|&(interface, _)| interface).collect();
+        interfaces.dedup();
+        for interface in interfaces {
+            write!(lib_rs, "use {} as _;", rust_item_path(interface))?;
+        }
+        for (_, method) in inherited_methods {
+            self.generate_method(lib_rs, qname, method)?;
+        }
+        Ok(())
+    }
+
+    /// Generate a function `{qname}_to_java` that moves a value of the resource `qname` into a new object of its class,
+    /// which holds the pointer of an `Arc` owning it (see `generate_jni_function`), e.g.
+    ///
+    /// ```rust,ignore
+    /// fn my_crate_Counter_to_java<'local>(env: &mut jni::JNIEnv<'local>, value: ::my_crate::Counter) -> jni::errors::Result<jni::objects::JObject<'local>> {
+    ///     let pointer = std::sync::Arc::into_raw(std::sync::Arc::new(value)) as jni::sys::jlong;
+    ///     env.new_object("myCrate/Counter", "(JLjava/lang/Void;)V", &[jni::objects::JValue::Long(pointer), jni::objects::JValue::Object(&jni::objects::JObject::null())])
+    /// }
+    /// ```
+    ///
+    /// Resources are never converted from Java: methods are given the value behind the pointer of the object.
+    fn generate_resource_to_java(&self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName) -> anyhow::Result<()> {
+        write!(lib_rs, "#[allow(dead_code)]")?;
+        write!(
+            lib_rs,
+            "fn {to_java}<'local>(env: &mut jni::JNIEnv<'local>, value: {rust_path}) -> jni::errors::Result<jni::objects::JObject<'local>> {{",
+            to_java = to_java_fn(qname),
+            rust_path = rust_item_path(qname),
+        )?;
+        write!(lib_rs, "let pointer = std::sync::Arc::into_raw(std::sync::Arc::new(value)) as jni::sys::jlong;")?;
+        write!(
+            lib_rs,
+            "env.new_object({class:?}, \"(JLjava/lang/Void;)V\", &[jni::objects::JValue::Long(pointer), jni::objects::JValue::Object(&jni::objects::JObject::null())])",
+            class = self.names.class_jni_name(qname),
+        )?;
+        write!(lib_rs, "}}")?;
         Ok(())
     }
 
@@ -142,36 +444,403 @@ impl<'idl> RustCodeGenerator<'idl> {
         qname: &QualifiedName,
         record: &Record,
     ) -> Result<(), anyhow::Error> {
+        self.generate_conversions(lib_rs, qname)?;
         for method in record.methods() {
             self.generate_method(lib_rs, qname, method)?;
         }
         Ok(())
     }
 
+    /// If the record, variant, newtype, or enum `qname` can be converted through JNI (see [`Self::jni_user_types`]),
+    /// generate its conversion functions. Functions exchanging the others report the error instead (see `check_jni_ty`).
+    fn generate_conversions(&self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName) -> anyhow::Result<()> {
+        if self.jni_types.contains(qname) {
+            // Types that are only passed in one direction do not use the other function.
+            write!(lib_rs, "#[allow(dead_code)]")?;
+            self.generate_from_java(lib_rs, qname)?;
+            write!(lib_rs, "#[allow(dead_code)]")?;
+            self.generate_to_java(lib_rs, qname)?;
+        }
+        Ok(())
+    }
+
+    /// Generate a function `{qname}_from_java` that converts a Java value of the record, variant, newtype, or enum `qname`
+    /// into Rust, e.g. for a record:
+    ///
+    /// ```rust,ignore
+    /// fn my_crate_Point_from_java(env: &mut jni::JNIEnv<'_>, value: &jni::objects::JObject<'_>) -> jni::errors::Result<::my_crate::Point> {
+    ///     Ok(::my_crate::Point {
+    ///         x: {
+    ///             let value = env.call_static_method("myCrate/gluegun/MyCratePointSupport", "get_x", "(LmyCrate/Point;)Ljava/lang/Double;", &[jni::objects::JValue::Object(value)])?.l()?;
+    ///             let value = env.auto_local(value);
+    ///             gluegun_java_util::jni_support::unbox_element::<f64>(env, &value)?
+    ///         },
+    ///         ...
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// Records and variants are read with the static methods of their support class (see [`util::support_qname`]),
+    /// newtypes with the accessor of their record class, and enums by the ordinal of the constant.
+    fn generate_from_java(&self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName) -> anyhow::Result<()> {
+        let rust_path = rust_item_path(qname);
+        let class = self.names.class_jni_name(qname);
+        let support = self.names.class_jni_name(&util::support_qname(self.idl, qname));
+        write!(
+            lib_rs,
+            "fn {from_java}(env: &mut jni::JNIEnv<'_>, value: &jni::objects::JObject<'_>) -> jni::errors::Result<{rust_path}> {{",
+            from_java = from_java_fn(qname),
+        )?;
+        // Reads the field of type `ty` with the static method `getter` of the support class.
+        let support_getter = |getter: &Name, ty: &Ty| {
+            format!(
+                "{{ let value = env.call_static_method({support:?}, {getter:?}, {descriptor:?}, &[jni::objects::JValue::Object(value)])?.l()?; \
+                 let value = env.auto_local(value); {converted} }}",
+                getter = getter.to_string(),
+                descriptor = format!("(L{class};){}", self.jni_object_descriptor(ty)),
+                converted = self.jni_from_java(ty, "&value"),
+            )
+        };
+        match self.idl.item(qname) {
+            Some(Item::Record(record)) => {
+                write!(lib_rs, "Ok({rust_path} {{")?;
+                for (index, field) in record.fields().iter().enumerate() {
+                    write!(
+                        lib_rs,
+                        "{member}: {value},",
+                        member = record.member(index),
+                        value = support_getter(&util::record_getter(field), field.ty()),
+                    )?;
+                }
+                write!(lib_rs, "}})")?;
+            }
+            Some(Item::Variant(variant)) => {
+                write!(
+                    lib_rs,
+                    "let arm = env.call_static_method({support:?}, \"arm\", \"(L{class};)I\", &[jni::objects::JValue::Object(value)])?.i()?;",
+                )?;
+                write!(lib_rs, "Ok(match arm {{")?;
+                for (index, arm) in variant.arms().iter().enumerate() {
+                    write!(lib_rs, "{index} => {rust_path}::{} {{", arm.name())?;
+                    let tuple_like = util::is_tuple_like(arm.fields());
+                    for (field_index, field) in arm.fields().iter().enumerate() {
+                        let field_name = if tuple_like { field_index.to_string() } else { field.name().to_string() };
+                        write!(
+                            lib_rs,
+                            "{field_name}: {value},",
+                            value = support_getter(&util::variant_arm_getter(arm, field), field.ty()),
+                        )?;
+                    }
+                    write!(lib_rs, "}},")?;
+                }
+                write!(lib_rs, "arm => unreachable!(\"unknown arm {{arm}} of `{}`\"),", qname.colon_colon())?;
+                write!(lib_rs, "}})")?;
+            }
+            // Values rejected by the `TryFrom` impl of a validated newtype are thrown as an `IllegalArgumentException`.
+            Some(Item::Newtype(newtype)) => {
+                write!(
+                    lib_rs,
+                    "let field = env.call_method(value, \"value\", {descriptor:?}, &[])?.l()?;",
+                    descriptor = format!("(){}", self.jni_object_descriptor(newtype.ty())),
+                )?;
+                write!(lib_rs, "let field = env.auto_local(field);")?;
+                write!(lib_rs, "let field = {};", self.jni_from_java(newtype.ty(), "&field"))?;
+                let wrapped = newtype.wrap_expr(&rust_path, "field");
+                if *newtype.validated() {
+                    write!(
+                        lib_rs,
+                        "{wrapped}.map_err(|err| gluegun_java_util::jni_support::illegal_argument(env, format!(\"invalid `{}`: {{err}}\")))",
+                        qname.tail_name(),
+                    )?;
+                } else {
+                    write!(lib_rs, "Ok({wrapped})")?;
+                }
+            }
+            Some(Item::Enum(an_enum)) => {
+                write!(lib_rs, "let ordinal = env.call_method(value, \"ordinal\", \"()I\", &[])?.i()?;")?;
+                write!(lib_rs, "Ok(match ordinal {{")?;
+                for (index, arm) in an_enum.arms().iter().enumerate() {
+                    write!(lib_rs, "{index} => {rust_path}::{},", arm.name())?;
+                }
+                write!(lib_rs, "ordinal => unreachable!(\"unknown ordinal {{ordinal}} of `{}`\"),", qname.colon_colon())?;
+                write!(lib_rs, "}})")?;
+            }
+            item => unreachable!("no conversions for {item:?}"),
+        }
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Generate a function `{qname}_to_java` that converts a Rust value of the record, variant, newtype, or enum `qname`
+    /// into Java (see `generate_from_java`): records and variants are constructed with the static methods
+    /// of their support class, newtypes with the constructor of their record class, and enums are the constant of the arm.
+    fn generate_to_java(&self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName) -> anyhow::Result<()> {
+        let rust_path = rust_item_path(qname);
+        let class = self.names.class_jni_name(qname);
+        let support = self.names.class_jni_name(&util::support_qname(self.idl, qname));
+        write!(
+            lib_rs,
+            "fn {to_java}<'local>(env: &mut jni::JNIEnv<'local>, value: {rust_path}) -> jni::errors::Result<jni::objects::JObject<'local>> {{",
+            to_java = to_java_fn(qname),
+        )?;
+        // Converts the `fields`, given as the Rust expression of their value, and passes them to the static method
+        // `constructor` of the support class, returning the value of the class `class`.
+        let support_constructor = |lib_rs: &mut CodeWriter<'_>, constructor: &str, fields: Vec<(String, &Ty)>| -> anyhow::Result<()> {
+            let mut descriptor = String::from("(");
+            let mut arguments = vec![];
+            for (index, (value, ty)) in fields.into_iter().enumerate() {
+                write!(lib_rs, "let f{index} = {{ let value = {value}; {} }};", self.jni_to_java(ty, "value"))?;
+                write!(lib_rs, "let f{index} = env.auto_local(f{index});")?;
+                descriptor.push_str(&self.jni_object_descriptor(ty));
+                arguments.push(format!("jni::objects::JValue::Object(&f{index})"));
+            }
+            descriptor.push_str(&format!(")L{class};"));
+            write!(
+                lib_rs,
+                "env.call_static_method({support:?}, {constructor:?}, {descriptor:?}, &[{}])?.l()",
+                arguments.join(", "),
+            )?;
+            Ok(())
+        };
+        match self.idl.item(qname) {
+            Some(Item::Record(record)) => {
+                if record.fields().is_empty() {
+                    // Records without fields convert to the single instance of their class.
+                    write!(lib_rs, "let _ = value;")?;
+                }
+                let fields = record
+                    .fields()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| (format!("value.{}", record.member(index)), field.ty()))
+                    .collect();
+                support_constructor(lib_rs, "create", fields)?;
+            }
+            Some(Item::Variant(variant)) => {
+                write!(lib_rs, "match value {{")?;
+                for arm in variant.arms() {
+                    let tuple_like = util::is_tuple_like(arm.fields());
+                    let fields = arm
+                        .fields()
+                        .iter()
+                        .enumerate()
+                        .map(|(field_index, field)| {
+                            if tuple_like {
+                                format!("{field_index}: {}", field.name())
+                            } else {
+                                field.name().to_string()
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    let pattern = if fields.is_empty() { "{}".to_string() } else { format!("{{ {} }}", fields.join(", ")) };
+                    write!(lib_rs, "{rust_path}::{} {pattern} => {{", arm.name())?;
+                    let fields = arm.fields().iter().map(|field| (field.name().to_string(), field.ty())).collect();
+                    support_constructor(lib_rs, &util::variant_arm_constructor(arm).to_string(), fields)?;
+                    write!(lib_rs, "}}")?;
+                }
+                write!(lib_rs, "}}")?;
+            }
+            Some(Item::Newtype(newtype)) => {
+                write!(
+                    lib_rs,
+                    "let field = {{ let value = {}; {} }};",
+                    newtype.unwrap_expr("value"),
+                    self.jni_to_java(newtype.ty(), "value"),
+                )?;
+                write!(lib_rs, "let field = env.auto_local(field);")?;
+                write!(
+                    lib_rs,
+                    "env.new_object({class:?}, {descriptor:?}, &[jni::objects::JValue::Object(&field)])",
+                    descriptor = format!("({})V", self.jni_object_descriptor(newtype.ty())),
+                )?;
+            }
+            Some(Item::Enum(an_enum)) => {
+                write!(lib_rs, "let constant = match value {{")?;
+                for arm in an_enum.arms() {
+                    write!(lib_rs, "{rust_path}::{} => {:?},", arm.name(), arm.name().upper_camel_case().to_string())?;
+                }
+                write!(lib_rs, "}};")?;
+                write!(lib_rs, "env.get_static_field({class:?}, constant, \"L{class};\")?.l()")?;
+            }
+            item => unreachable!("no conversions for {item:?}"),
+        }
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    fn generate_newtype(
+        &self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        newtype: &Newtype,
+    ) -> Result<(), anyhow::Error> {
+        self.generate_conversions(lib_rs, qname)?;
+        for method in newtype.methods() {
+            self.generate_method(lib_rs, qname, method)?;
+        }
+        Ok(())
+    }
+
     fn generate_variant(
         &self,
         lib_rs: &mut CodeWriter<'_>,
         qname: &QualifiedName,
         variant: &Variant,
     ) -> Result<(), anyhow::Error> {
+        self.generate_conversions(lib_rs, qname)?;
+        if self.is_thrown(qname, self.idl.item(qname).expect("defined")) {
+            self.generate_error_to_java(lib_rs, qname, ErrorArm::Value)?;
+        }
         for method in variant.methods() {
             self.generate_method(lib_rs, qname, method)?;
         }
         Ok(())
     }
 
+    /// If `ty` is, or contains, a record, variant, newtype, or enum, check that it can be converted through JNI
+    /// (see [`Self::jni_user_types`]), reporting the first field that cannot otherwise
+    /// (which, by construction of `jni_user_types`, there is).
+    fn check_jni_ty(&self, ty: &Ty) -> anyhow::Result<()> {
+        let qname = match ty.kind() {
+            TypeKind::Vec { element, repr: _ } | TypeKind::Set { element, repr: _ } | TypeKind::Option { element, repr: _ } => {
+                return self.check_jni_ty(element);
+            }
+            TypeKind::Map { key, value, repr: _ } => {
+                self.check_jni_ty(key)?;
+                return self.check_jni_ty(value);
+            }
+            TypeKind::Tuple { elements, repr: _ } => return elements.iter().try_for_each(|element| self.check_jni_ty(element)),
+            TypeKind::UserType { qname, repr: _ } if !self.jni_types.contains(qname) => qname,
+            _ => return Ok(()),
+        };
+        let fields: Vec<(String, &Ty)> = match self.idl.item(qname) {
+            Some(Item::Record(record)) => {
+                record.fields().iter().map(|field| (format!("field `{}`", field.name()), field.ty())).collect()
+            }
+            Some(Item::Variant(variant)) => variant
+                .arms()
+                .iter()
+                .flat_map(|arm| arm.fields().iter().map(move |field| (format!("field `{}` of `{}`", field.name(), arm.name()), field.ty())))
+                .collect(),
+            Some(Item::Newtype(newtype)) => vec![("the field".to_string(), newtype.ty())],
+            _ => return Ok(()),
+        };
+        for (field, field_ty) in fields {
+            if !self.is_jni_element(field_ty) {
+                anyhow::bail!(
+                    "{field} of `{qname}` has type `{ty}`, which the java backend cannot convert yet",
+                    qname = qname.colon_colon(),
+                    ty = field_ty.rust_owned_spelling(),
+                );
+            }
+        }
+        unreachable!("`{}` has a field that cannot be converted", qname.colon_colon())
+    }
+
     fn generate_enum(
         &self,
         lib_rs: &mut CodeWriter<'_>,
         qname: &QualifiedName,
         an_enum: &Enum,
     ) -> Result<(), anyhow::Error> {
+        self.generate_conversions(lib_rs, qname)?;
+        if *an_enum.implements_error() {
+            self.generate_error_to_java(lib_rs, qname, ErrorArm::Ordinal(an_enum))?;
+        }
         for method in an_enum.methods() {
             self.generate_method(lib_rs, qname, method)?;
         }
         Ok(())
     }
 
+    /// Generate a function `{qname}_to_java_exception` that throws an error enum as the exception of its arm
+    /// (see `java_gen`), carrying the message of the Rust error, for the native method that returned it
+    /// (see `gluegun_java_util::jni_support::throw_created`). If constructing the exception fails, that error is thrown instead.
+    fn generate_error_to_java(&self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, arm: ErrorArm<'_>) -> anyhow::Result<()> {
+        let exception_qname = util::exception_qname(qname);
+        let exception_class = self.names.class_jni_name(&exception_qname);
+        let rust_path = rust_item_path(qname);
+        write!(
+            lib_rs,
+            "fn {to_java_exception}(env: &mut jni::JNIEnv<'_>, error: {rust_path}) -> Box<dyn std::error::Error> {{",
+            to_java_exception = to_java_exception_fn(qname),
+        )?;
+        write!(lib_rs, "gluegun_java_util::jni_support::throw_created(env, |env| {{")?;
+        write!(lib_rs, "let message = gluegun_java_util::jni_support::ToJava::to_java(error.to_string(), env)?;")?;
+        write!(lib_rs, "let message = env.auto_local(message);")?;
+        match arm {
+            ErrorArm::Value => {
+                write!(lib_rs, "let error = {}(env, error)?;", to_java_fn(qname))?;
+                write!(lib_rs, "let error = env.auto_local(error);")?;
+                write!(
+                    lib_rs,
+                    "env.call_static_method({exception_class:?}, \"of\", {descriptor:?}, &[jni::objects::JValue::Object(&message), jni::objects::JValue::Object(&error)])?.l()",
+                    descriptor = format!("(Ljava/lang/String;L{};)L{exception_class};", self.names.class_jni_name(qname)),
+                )?;
+            }
+            ErrorArm::Ordinal(an_enum) => {
+                write!(lib_rs, "let ordinal: jni::sys::jint = match error {{")?;
+                for (index, arm) in an_enum.arms().iter().enumerate() {
+                    write!(lib_rs, "{rust_path}::{} => {index},", arm.name())?;
+                }
+                write!(lib_rs, "}};")?;
+                write!(
+                    lib_rs,
+                    "env.call_static_method({exception_class:?}, \"of\", {descriptor:?}, &[jni::objects::JValue::Object(&message), jni::objects::JValue::Int(ordinal)])?.l()",
+                    descriptor = format!("(Ljava/lang/String;I)L{exception_class};"),
+                )?;
+            }
+        }
+        write!(lib_rs, "}})")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Generate the function `gluegun_io_error_to_java` that converts an `std::io::Error` into the exception
+    /// constructed by the `IoErrors` class (see `java_gen`), to be thrown by the native method that returned it.
+    /// If constructing the exception fails, that error is thrown instead.
+    fn generate_io_error_to_java(&self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        let io_errors_path = self.names.class_rust_path(&util::io_errors_qname(self.idl));
+        write!(
+            lib_rs,
+            "fn gluegun_io_error_to_java(error: std::io::Error) -> duchess::Error<duchess::Java<duchess::java::lang::Throwable>> {{",
+        )?;
+        write!(lib_rs, "use duchess::prelude::*;")?;
+        write!(lib_rs, "let message = error.to_string();")?;
+        write!(lib_rs, "let kind = format!(\"{{:?}}\", error.kind());")?;
+        write!(
+            lib_rs,
+            "let exception: duchess::Result<duchess::Java<duchess::java::lang::Throwable>> = \
+             duchess::JvmOp::execute(crate::{io_errors_path}::of(message.to_java::<duchess::java::lang::String>(), kind.to_java::<duchess::java::lang::String>()).assert_not_null().upcast::<duchess::java::lang::Throwable>());",
+        )?;
+        write!(lib_rs, "match exception {{")?;
+        write!(lib_rs, "Ok(exception) => duchess::Error::Thrown(exception),")?;
+        write!(lib_rs, "Err(err) => err,")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// True if `item`, named `qname`, is an enum implementing `std::error::Error` (see [`Idl::error_item`])
+    /// that native methods throw as its exception class (see `generate_error_to_java`).
+    /// Error variants with fields we cannot convert are thrown as a plain error instead.
+    fn is_thrown(&self, qname: &QualifiedName, item: &Item) -> bool {
+        match item {
+            Item::Variant(variant) => *variant.implements_error() && self.jni_types.contains(qname),
+            Item::Enum(an_enum) => *an_enum.implements_error(),
+            _ => false,
+        }
+    }
+
+    /// If `ty` is an enum that native methods throw as its exception class (see `is_thrown`), return its name.
+    fn error_ty<'ty>(&self, ty: &'ty Ty) -> Option<&'ty QualifiedName> {
+        let TypeKind::UserType { qname, .. } = ty.kind() else {
+            return None;
+        };
+        let (_, item) = self.idl.error_item(ty)?;
+        self.is_thrown(qname, item).then_some(qname)
+    }
+
     fn generate_method(
         &self,
         lib_rs: &mut CodeWriter<'_>,
@@ -207,85 +876,665 @@ impl<'idl> RustCodeGenerator<'idl> {
         method_category: &MethodCategory,
         signature: &Signature,
     ) -> anyhow::Result<()> {
+        // The methods of resources are given the value behind the pointer of the Java object, those of records a copy
+        // of the Java object, and those of enums the ordinal of the constant (see `receiver`).
+        let receiver = self.receiver(rust_qname, fn_name, method_category)?;
+
+        // Only raw JNI functions pass the values of `needs_jni`, resources, and the copies of records,
+        // and throw error enums as their exception class, whatever the glue.
+        let output = signature.output_ty();
+        let needs_jni = matches!(self.idl.item(rust_qname), Some(Item::Resource(_) | Item::Record(_)))
+            || signature.inputs().iter().any(|input| self.needs_jni(input.refd_ty().ty()))
+            || self.needs_jni(output.main_ty().ty())
+            || output.error_ty().as_ref().is_some_and(|error_ty| self.error_ty(error_ty).is_some());
+        if needs_jni {
+            self.check_jni_signature(rust_qname, fn_name, method_category, signature)?;
+            return self.generate_jni_function(lib_rs, rust_qname, java_qname, fn_name, method_category, receiver, signature);
+        }
+        if self.metadata.glue == Glue::Jni && self.check_jni_signature(rust_qname, fn_name, method_category, signature).is_ok() {
+            return self.generate_jni_function(lib_rs, rust_qname, java_qname, fn_name, method_category, receiver, signature);
+        }
+
+        // `duchess::java_function` catches panics in the body and throws them as a `RuntimeException`.
         write!(lib_rs, "const _: () = {{")?;
 
         write!(lib_rs, "use duchess::java;")?; // FIXME: duchess bug, this should not be needed
 
         write!(
             lib_rs,
-            "#[duchess::java_function({class_dot_name}::{fn_name})]",
-            class_dot_name = util::class_dot_name(java_qname)
+            "#[duchess::java_function({class_dot_name}::native_{fn_name})]",
+            class_dot_name = self.names.class_dot_name(java_qname)
         )?;
         write!(lib_rs, "fn {fn_name}(")?;
 
         match method_category {
             MethodCategory::Constructor => {}
-            MethodCategory::BuilderMethod(_self_kind)
-            | MethodCategory::InstanceMethod(_self_kind) => {
-                write!(lib_rs, "_self: &duchess::JavaObject")?; // FIXME
-            }
-            MethodCategory::StaticMethod => {}
+            MethodCategory::BuilderMethod(_)
+            | MethodCategory::InstanceMethod(_)
+            | MethodCategory::Getter(_) => match receiver {
+                Some(Receiver::Enum(..)) => write!(lib_rs, "ordinal: i32,")?,
+                Some(Receiver::Resource(..) | Receiver::Record(_)) => {
+                    unreachable!("resources and records are passed by JNI functions")
+                }
+                None => write!(lib_rs, "_self: &duchess::JavaObject,")?, // FIXME
+            },
+            MethodCategory::NamedConstructor | MethodCategory::StaticMethod => {}
             _ => anyhow::bail!("unsupported method category: {method_category:?}"),
         }
 
+        for input in signature.inputs() {
+            write!(lib_rs, "{name}: {ty},", name = input.name(), ty = self.java_parameter_ty(input.refd_ty().ty())?)?;
+        }
+
+        write!(lib_rs, ") -> {} {{", self.rust_return_ty(output)?)?;
+
+        self.generate_fn_body(lib_rs, fn_name, rust_qname, receiver, signature, output)?;
+
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}};")?;
+        Ok(())
+    }
+
+    /// If `rust_qname` is a resource, a record, or an enum and `method_category` takes `self`, return how the method
+    /// is given it (see [`Receiver`]). Records must be convertible from Java. Records and enums are not passed by handle,
+    /// so their methods taking `&mut self` could not modify the Java object and are rejected.
+    fn receiver<'r>(
+        &'r self,
+        rust_qname: &QualifiedName,
+        fn_name: &Name,
+        method_category: &'r MethodCategory,
+    ) -> anyhow::Result<Option<Receiver<'r>>> {
+        let Some(self_kind) = method_category.self_kind() else {
+            return Ok(None);
+        };
+        let (receiver, passed_as) = match self.idl.item(rust_qname) {
+            Some(Item::Resource(_)) => {
+                if let SelfKind::ByRc = self_kind {
+                    anyhow::bail!(
+                        "`{}::{fn_name}`: the java backend does not support methods taking `self: Rc<Self>`, \
+                         as Java objects can be used from any thread",
+                        rust_qname.colon_colon(),
+                    );
+                }
+                let field = match method_category {
+                    MethodCategory::Getter(field) => Some(field),
+                    _ => None,
+                };
+                return Ok(Some(Receiver::Resource(self_kind, field)));
+            }
+            Some(Item::Record(_)) => {
+                if !self.jni_types.contains(rust_qname) {
+                    anyhow::bail!(
+                        "`{}::{fn_name}`: the java backend passes records to Rust by copy, \
+                         but the record has fields it cannot convert yet",
+                        rust_qname.colon_colon(),
+                    );
+                }
+                (Receiver::Record(self_kind), "records to Rust by copy")
+            }
+            Some(Item::Enum(an_enum)) => (Receiver::Enum(an_enum, self_kind), "enums to Rust by ordinal"),
+            _ => return Ok(None),
+        };
+        match self_kind {
+            SelfKind::ByValue | SelfKind::ByRef => Ok(Some(receiver)),
+            _ => anyhow::bail!(
+                "`{}::{fn_name}`: the java backend passes {passed_as}, \
+                 so it only supports their methods taking `self` or `&self`",
+                rust_qname.colon_colon(),
+            ),
+        }
+    }
+
+    /// Check that a raw JNI function (see `generate_jni_function`) can implement the method `fn_name`,
+    /// i.e. that it is not async and only has arguments and results of [`Self::jni_ty`].
+    fn check_jni_signature(
+        &self,
+        rust_qname: &QualifiedName,
+        fn_name: &Name,
+        method_category: &MethodCategory,
+        signature: &Signature,
+    ) -> anyhow::Result<()> {
+        let qname = rust_qname.colon_colon();
+        if let IsAsync::Yes = signature.is_async() {
+            anyhow::bail!("`{qname}::{fn_name}`: the Java backend does not support async functions yet");
+        }
+        if let MethodCategory::Constructor = method_category {
+            if !matches!(self.idl.item(rust_qname), Some(Item::Resource(_))) {
+                anyhow::bail!("`{qname}::{fn_name}`: the Java backend only supports the constructors of resources");
+            }
+        }
+
+        // Resources are returned as a new object of their class (see `generate_resource_to_java`),
+        // but cannot be passed to Rust yet.
+        let output = signature.output_ty();
+        for input in signature.inputs() {
+            if let Some(resource_qname) = self.resource_ty(input.refd_ty().ty()) {
+                anyhow::bail!(
+                    "{span}: `{qname}::{fn_name}` takes the resource `{}`, which the Java backend cannot pass to Rust yet",
+                    resource_qname.colon_colon(),
+                    span = input.refd_ty().ty().span(),
+                );
+            }
+        }
+        let tys = signature
+            .inputs()
+            .iter()
+            .map(|input| input.refd_ty().ty())
+            .chain((!output.is_unit() && self.resource_ty(output.main_ty().ty()).is_none()).then(|| output.main_ty().ty()));
+        for ty in tys {
+            self.check_jni_ty(ty)?;
+            if self.jni_ty(ty).is_none() {
+                anyhow::bail!(
+                    "{span}: `{qname}::{fn_name}` exchanges `{ty}`, which the Java backend only passes \
+                     to native methods whose other arguments and results are primitives, strings, \
+                     or collections, optionals, tuples, records, variants, newtypes, or enums of them",
+                    span = ty.span(),
+                    ty = ty.rust_owned_spelling(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// If `ty` is a resource (rather than, e.g., a `Box` of one), return its name.
+    fn resource_ty<'ty>(&self, ty: &'ty Ty) -> Option<&'ty QualifiedName> {
+        let TypeKind::UserType { qname, repr: UserTypeRepr::Direct } = ty.kind() else {
+            return None;
+        };
+        match self.idl.item(qname) {
+            Some(Item::Resource(_)) => Some(qname),
+            _ => None,
+        }
+    }
+
+    /// The JNI type (e.g., `jni::sys::jint`) of a native method parameter of type `ty`,
+    /// if `ty` is a primitive, string, optional primitive, trait object, or one of the objects of [`Self::is_jni_object`].
+    fn jni_ty(&self, ty: &Ty) -> Option<&'static str> {
+        match ty.kind() {
+            TypeKind::Scalar(scalar) => jni_primitive(scalar),
+            TypeKind::String { repr: _ } => Some("jni::objects::JString<'local>"),
+            TypeKind::TraitObject { .. } => Some("jni::objects::JObject<'local>"),
+            _ if self.is_jni_object(ty) => Some("jni::objects::JObject<'local>"),
+            _ => None,
+        }
+    }
+
+    /// True if `ty` can be held by the objects of [`Self::is_jni_object`]: a primitive (boxed, e.g. a `u32` as an `Integer`,
+    /// except for characters, which are not supported), a string, or such an object.
+    fn is_jni_element(&self, ty: &Ty) -> bool {
+        match ty.kind() {
+            TypeKind::Scalar(scalar) => jni_primitive(scalar).is_some(),
+            TypeKind::String { repr: _ } => true,
+            _ => self.is_jni_object(ty),
+        }
+    }
+
+    /// True if `ty` is exchanged through JNI as an object converted by the helpers of `gluegun_java_util::jni_support`
+    /// (see [`Self::jni_from_java`]): a collection, an option, or a tuple of the elements of [`Self::is_jni_element`],
+    /// or a record, variant, newtype, or enum of [`Self::jni_user_types`].
+    fn is_jni_object(&self, ty: &Ty) -> bool {
+        match ty.kind() {
+            TypeKind::Vec { element, repr: _ } | TypeKind::Set { element, repr: _ } => self.is_jni_element(element),
+            TypeKind::Map { key, value, repr: _ } => self.is_jni_element(key) && self.is_jni_element(value),
+            TypeKind::Option { element, repr: _ } => self.is_jni_element(element),
+            TypeKind::Tuple { elements, repr: _ } => {
+                !elements.is_empty() && elements.iter().all(|element| self.is_jni_element(element))
+            }
+            TypeKind::UserType { qname, repr: _ } => self.jni_types.contains(qname),
+            _ => false,
+        }
+    }
+
+    /// True if `ty` is a trait object, a collection, a tuple, a user type, or (if so configured) a `java.util.Optional`,
+    /// which duchess cannot convert, so that only the JNI glue passes them (see [`Self::is_jni_object`]).
+    fn needs_jni(&self, ty: &Ty) -> bool {
+        match ty.kind() {
+            TypeKind::TraitObject { .. } | TypeKind::UserType { .. } => true,
+            TypeKind::Vec { .. } | TypeKind::Set { .. } | TypeKind::Map { .. } => true,
+            TypeKind::Tuple { elements, repr: _ } => !elements.is_empty(),
+            TypeKind::Option { element, repr: _ } => {
+                self.metadata.option == OptionRepr::Optional || self.needs_jni(element)
+            }
+            _ => false,
+        }
+    }
+
+    /// The JNI type descriptor of the Java class holding a value of type `ty` in an object of [`Self::is_jni_object`]
+    /// (e.g., `Ljava/lang/Integer;` for a `u32`), which is also that of the fields of the support classes.
+    fn jni_object_descriptor(&self, ty: &Ty) -> String {
+        let class = match ty.kind() {
+            TypeKind::Scalar(scalar) => match jni_primitive_descriptor(scalar) {
+                Some((_, class)) => format!("java/lang/{class}"),
+                None => "java/lang/Object".to_string(),
+            },
+            TypeKind::String { repr: _ } => "java/lang/String".to_string(),
+            TypeKind::Vec { .. } => "java/util/List".to_string(),
+            TypeKind::Set { .. } => "java/util/Set".to_string(),
+            TypeKind::Map { .. } => "java/util/Map".to_string(),
+            TypeKind::Option { element, repr: _ } => match self.metadata.option {
+                OptionRepr::Nullable => return self.jni_object_descriptor(element),
+                OptionRepr::Optional => "java/util/Optional".to_string(),
+            },
+            TypeKind::Tuple { elements, repr: _ } => {
+                self.names.class_jni_name(&util::tuple_class_qname(self.idl, elements.len()))
+            }
+            TypeKind::UserType { qname, repr: _ } => self.names.class_jni_name(qname),
+            _ => "java/lang/Object".to_string(),
+        };
+        format!("L{class};")
+    }
+
+    /// An expression converting `value`, a `&JObject` of the type of [`Self::is_jni_object`] or an element of one,
+    /// into a Rust value of type `ty`, given `env`, a `&mut JNIEnv`. For example, for a `Vec<u32>`:
+    ///
+    /// ```rust,ignore
+    /// gluegun_java_util::jni_support::collection_from_java(env, value, |env, value| {
+    ///     Ok(gluegun_java_util::jni_support::unbox_element::<u32>(env, value)?)
+    /// })?
+    /// ```
+    fn jni_from_java(&self, ty: &Ty, value: &str) -> String {
+        let support = "gluegun_java_util::jni_support";
+        match ty.kind() {
+            TypeKind::Scalar(scalar) => format!("{support}::unbox_element::<{scalar}>(env, {value})?"),
+            TypeKind::String { repr: _ } => format!("{support}::string_element(env, {value})?"),
+            TypeKind::Vec { element, repr: _ } => format!(
+                "{support}::collection_from_java(env, {value}, |env, value| Ok({}))?",
+                self.jni_from_java(element, "value"),
+            ),
+            TypeKind::Set { element, repr: _ } => format!(
+                "{support}::collection_from_java(env, {value}, |env, value| Ok({}))?.into_iter().collect::<{}>()",
+                self.jni_from_java(element, "value"),
+                ty.rust_owned_absolute_spelling(),
+            ),
+            TypeKind::Map { key, value: value_ty, repr: _ } => format!(
+                "{support}::map_from_java(env, {value}, |env, key| Ok({}), |env, value| Ok({}))?.into_iter().collect::<{}>()",
+                self.jni_from_java(key, "key"),
+                self.jni_from_java(value_ty, "value"),
+                ty.rust_owned_absolute_spelling(),
+            ),
+            TypeKind::Option { element, repr: _ } => format!(
+                "{support}::{}_from_java(env, {value}, |env, value| Ok({}))?",
+                self.option_helper(),
+                self.jni_from_java(element, "value"),
+            ),
+            // Each component is converted in its own block, so that those of nested tuples do not shadow `value`.
+            TypeKind::Tuple { elements, repr: _ } => {
+                let converted: Vec<String> = elements
+                    .iter()
+                    .enumerate()
+                    .map(|(index, element)| {
+                        format!(
+                            "{{ let value = {support}::tuple_component(env, {value}, {index})?; {} }}",
+                            self.jni_from_java(element, "&value"),
+                        )
+                    })
+                    .collect();
+                format!("({},)", converted.join(", "))
+            }
+            // Records, variants, newtypes, and enums are converted by the functions of `generate_conversions`.
+            TypeKind::UserType { qname, repr } => {
+                let converted = format!("{}(env, {value})?", from_java_fn(qname));
+                if repr.is_direct() { converted } else { format!("Box::new({converted})") }
+            }
+            _ => unreachable!("checked by `is_jni_object`"),
+        }
+    }
+
+    /// An expression converting `value`, an owned Rust value of type `ty` (see [`Self::jni_from_java`]),
+    /// into a `JObject`, given `env`, a `&mut JNIEnv`. Maps and sets keep the order of their iteration
+    /// in a `LinkedHashMap` or a `LinkedHashSet`, unless they are hashed.
+    fn jni_to_java(&self, ty: &Ty, value: &str) -> String {
+        let support = "gluegun_java_util::jni_support";
+        let linked = |repr: &MapSetRepr| if let MapSetRepr::Hash = repr { "" } else { "Linked" };
+        match ty.kind() {
+            TypeKind::Scalar(_) => format!("{support}::box_element(env, {value})?"),
+            TypeKind::String { repr: _ } => format!("{support}::ToJava::to_java({value}, env)?"),
+            TypeKind::Vec { element, repr: _ } => format!(
+                "{support}::collection_to_java(env, \"java/util/ArrayList\", {value}, |env, value| Ok({}))?",
+                self.jni_to_java(element, "value"),
+            ),
+            TypeKind::Set { element, repr } => format!(
+                "{support}::collection_to_java(env, \"java/util/{}HashSet\", {value}, |env, value| Ok({}))?",
+                linked(repr),
+                self.jni_to_java(element, "value"),
+            ),
+            TypeKind::Map { key, value: value_ty, repr } => format!(
+                "{support}::map_to_java(env, \"java/util/{}HashMap\", {value}, |env, key| Ok({}), |env, value| Ok({}))?",
+                linked(repr),
+                self.jni_to_java(key, "key"),
+                self.jni_to_java(value_ty, "value"),
+            ),
+            TypeKind::Option { element, repr: _ } => format!(
+                "{support}::{}_to_java(env, {value}, |env, value| Ok({}))?",
+                self.option_helper(),
+                self.jni_to_java(element, "value"),
+            ),
+            TypeKind::Tuple { elements, repr: _ } => {
+                let names: Vec<String> = (0..elements.len()).map(|index| format!("c{index}")).collect();
+                let converted: String = elements
+                    .iter()
+                    .zip(&names)
+                    .map(|(element, name)| format!("let {name} = {};", self.jni_to_java(element, name)))
+                    .collect();
+                format!(
+                    "{{ let ({},) = {value}; {converted} {support}::tuple_to_java(env, {:?}, &[{}])? }}",
+                    names.join(", "),
+                    self.names.class_jni_name(&util::tuple_class_qname(self.idl, elements.len())),
+                    names.iter().map(|name| format!("&{name}")).collect::<Vec<_>>().join(", "),
+                )
+            }
+            TypeKind::UserType { qname, repr } => {
+                let value = if repr.is_direct() { value.to_string() } else { format!("*{value}") };
+                format!("{}(env, {value})?", to_java_fn(qname))
+            }
+            _ => unreachable!("checked by `is_jni_object`"),
+        }
+    }
+
+    /// The prefix of the helpers of `gluegun_java_util::jni_support` converting options (see the `option` setting).
+    fn option_helper(&self) -> &'static str {
+        match self.metadata.option {
+            OptionRepr::Nullable => "nullable",
+            OptionRepr::Optional => "optional",
+        }
+    }
+
+    /// Generate a raw JNI function implementing the native method (see [`Glue::Jni`]), e.g.
+    ///
+    /// ```rust,ignore
+    /// #[no_mangle]
+    /// pub extern "system" fn Java_myCrate_Functions_native_1add<'local>(
+    ///     mut env: jni::JNIEnv<'local>,
+    ///     _class: jni::objects::JClass<'local>,
+    ///     a: jni::sys::jint,
+    /// ) -> jni::sys::jint {
+    ///     let result = (|| -> Result<jni::sys::jint, Box<dyn std::error::Error>> {
+    ///         let a = <u32 as gluegun_java_util::jni_support::FromJava>::from_java(&mut env, a)?;
+    ///         let result = gluegun_java_util::jni_support::catch_panic(|| my_crate::add(a))?;
+    ///         Ok(gluegun_java_util::jni_support::ToJava::to_java(result, &mut env)?)
+    ///     })();
+    ///     gluegun_java_util::jni_support::return_or_throw(&mut env, result)
+    /// }
+    /// ```
+    ///
+    /// Values are converted by the `FromJava` and `ToJava` traits of `gluegun_java_util::jni_support`,
+    /// or by the functions of `jni_from_java` and `jni_to_java`. The methods of resources and records are instance methods,
+    /// given the Java object as `this`, and those of enums are static methods given the ordinal of the constant.
+    /// The constructors of resources return the pointer that the Java object holds (see `generate_resource_receiver`).
+    /// Errors (of the Rust function or of JNI) and panics are thrown as a `RuntimeException`,
+    /// except for `std::io::Error`s, which are thrown as an `UncheckedIOException`,
+    /// and error enums, which are thrown as their exception class (see `generate_error_to_java`).
+    #[allow(clippy::too_many_arguments)]
+    fn generate_jni_function(
+        &self,
+        lib_rs: &mut CodeWriter<'_>,
+        rust_qname: &QualifiedName,
+        java_qname: &QualifiedName,
+        fn_name: &Name,
+        method_category: &MethodCategory,
+        receiver: Option<Receiver<'_>>,
+        signature: &Signature,
+    ) -> anyhow::Result<()> {
+        let output = signature.output_ty();
+        let main_ty = output.main_ty().ty();
+        let constructor = matches!(method_category, MethodCategory::Constructor);
+        let return_ty = match main_ty.kind() {
+            _ if constructor => "jni::sys::jlong",
+            _ if output.is_unit() => "()",
+            TypeKind::Scalar(scalar) => jni_primitive(scalar).expect("checked by `check_jni_signature`"),
+            _ => "jni::sys::jobject",
+        };
+
+        write!(lib_rs, "#[no_mangle]")?;
+        write!(
+            lib_rs,
+            "pub extern \"system\" fn {symbol}<'local>(",
+            symbol = self.names.jni_symbol(java_qname, &format!("native_{fn_name}")),
+        )?;
+        write!(lib_rs, "mut env: jni::JNIEnv<'local>,")?;
+        match receiver {
+            Some(Receiver::Resource(..) | Receiver::Record(_)) => write!(lib_rs, "this: jni::objects::JObject<'local>,")?,
+            Some(Receiver::Enum(..)) => {
+                write!(lib_rs, "_class: jni::objects::JClass<'local>,")?;
+                write!(lib_rs, "ordinal: jni::sys::jint,")?;
+            }
+            None => write!(lib_rs, "_class: jni::objects::JClass<'local>,")?,
+        }
+        for input in signature.inputs() {
+            let ty = self.jni_ty(input.refd_ty().ty()).expect("checked by `check_jni_signature`");
+            write!(lib_rs, "{name}: {ty},", name = input.name())?;
+        }
+        if output.is_unit() {
+            write!(lib_rs, ") {{")?;
+        } else {
+            write!(lib_rs, ") -> {return_ty} {{")?;
+        }
+        write!(lib_rs, "let result = (|| -> Result<{return_ty}, Box<dyn std::error::Error>> {{")?;
+
+        let field = match receiver {
+            Some(Receiver::Resource(_, field)) => field,
+            _ => None,
+        };
+        let receiver = match receiver {
+            Some(Receiver::Resource(self_kind, _)) => self.generate_resource_receiver(lib_rs, rust_qname, fn_name, self_kind)?,
+            Some(Receiver::Record(self_kind)) => {
+                write!(lib_rs, "let _self = {}(&mut env, &this)?;", from_java_fn(rust_qname))?;
+                if let SelfKind::ByRef = self_kind { "&_self," } else { "_self," }
+            }
+            Some(Receiver::Enum(an_enum, self_kind)) => {
+                let rust_path = rust_item_path(rust_qname);
+                write!(lib_rs, "let _self = match ordinal {{")?;
+                for (index, arm) in an_enum.arms().iter().enumerate() {
+                    write!(lib_rs, "{index} => {rust_path}::{},", arm.name())?;
+                }
+                write!(lib_rs, "_ => return Err(format!(\"invalid ordinal of `{rust_path}`: {{ordinal}}\").into()),")?;
+                write!(lib_rs, "}};")?;
+                if let SelfKind::ByRef = self_kind { "&_self," } else { "_self," }
+            }
+            None => "",
+        };
+
         for input in signature.inputs() {
             let name = input.name();
             let ty = input.refd_ty().ty();
-            write!(lib_rs, "{name}: {ty},", ty = self.java_parameter_ty(ty)?)?;
+            match ty.kind() {
+                TypeKind::Scalar(_) => write!(
+                    lib_rs,
+                    "let {name} = <{ty} as gluegun_java_util::jni_support::FromJava>::from_java(&mut env, {name})?;",
+                )?,
+                // Optional primitives are unboxed directly (see `gluegun_java_util::jni_support::unbox`).
+                TypeKind::Option { element, repr: _ }
+                    if self.metadata.option == OptionRepr::Nullable && matches!(element.kind(), TypeKind::Scalar(_)) =>
+                {
+                    write!(
+                        lib_rs,
+                        "let {name} = <{ty} as gluegun_java_util::jni_support::FromJava>::from_java(&mut env, {name})?;",
+                    )?
+                }
+                // The Java signature guarantees that the argument is a `java.lang.String`.
+                // Borrowed strings are borrowed in place, unless they need to be re-encoded from modified UTF-8.
+                TypeKind::String { repr: _ } => match input.refd_ty() {
+                    RefdTy::Owned(..) => write!(
+                        lib_rs,
+                        "let {name} = <String as gluegun_java_util::jni_support::FromJava>::from_java(&mut env, {name}.into())?;",
+                    )?,
+                    RefdTy::Ref(..) => {
+                        write!(lib_rs, "let {name} = unsafe {{ env.get_string_unchecked(&{name}) }}?;")?;
+                        write!(lib_rs, "let {name}: std::borrow::Cow<'_, str> = (&{name}).into();")?
+                    }
+                },
+                // The Java object implements the interface; wrap it in the adapter (see `generate_interface_adapter`).
+                TypeKind::TraitObject { qname, repr } => {
+                    write!(
+                        lib_rs,
+                        "let {name} = {}(gluegun_java_util::jni_support::ForeignObject::new(&mut env, &{name})?);",
+                        interface_adapter(qname),
+                    )?;
+                    if let TraitObjectRepr::BoxDyn(_) = repr {
+                        write!(lib_rs, "let {name} = Box::new({name});")?;
+                    }
+                }
+                _ => write!(
+                    lib_rs,
+                    "let {name}: {} = {{ let env = &mut env; {} }};",
+                    ty.rust_owned_absolute_spelling(),
+                    self.jni_from_java(ty, &format!("&{name}")),
+                )?,
+            }
         }
 
-        let output = signature.output_ty();
-        write!(lib_rs, ") -> {} {{", self.rust_return_ty(output))?;
+        // Panics are caught too, rather than unwinding into the JVM.
+        // `std::io::Error`s are kept, so that `return_or_throw` throws them as an `UncheckedIOException`.
+        let suffix = match output.error_ty().as_ref().map(|error_ty| (error_ty.kind(), self.error_ty(error_ty))) {
+            Some((_, Some(error_qname))) => {
+                format!(")?.map_err(|error| {}(&mut env, error))?;", to_java_exception_fn(error_qname))
+            }
+            Some((TypeKind::Error { repr: ErrorRepr::IoError }, None)) => ")??;".to_string(),
+            Some(_) => ")?.map_err(|err| err.to_string())?;".to_string(),
+            None => ")?;".to_string(),
+        };
+        let prefix = if output.is_unit() { "" } else { "let result = " };
+        let prefix = format!("{prefix}gluegun_java_util::jni_support::catch_panic(|| ");
+        if let Some(field) = field {
+            // Getters read the field of the resource (see `MethodCategory::Getter`).
+            write!(lib_rs, "{prefix}&_self.{field}{suffix}")?;
+        } else {
+            let callee = format!("{}::{fn_name}", rust_item_path(rust_qname));
+            self.generate_rust_call(lib_rs, &prefix, &suffix, &callee, receiver, signature)?;
+        }
+        // Borrowed data (other than strings, which are converted in place) is copied out into an owned value.
+        if output.requires_copy_out() && !matches!(main_ty.kind(), TypeKind::String { .. }) {
+            write!(lib_rs, "let result = std::borrow::ToOwned::to_owned(result);")?;
+        }
 
-        self.generate_fn_body(lib_rs, fn_name, rust_qname, signature, output)?;
+        // Strings, optional primitives, and the objects of `is_jni_object` are returned as objects.
+        match main_ty.kind() {
+            // Constructors return the pointer that the Java constructor stores.
+            _ if constructor => {
+                write!(lib_rs, "Ok(std::sync::Arc::into_raw(std::sync::Arc::new(result)) as jni::sys::jlong)")?
+            }
+            _ if output.is_unit() => write!(lib_rs, "Ok(())")?,
+            TypeKind::Scalar(_) => write!(lib_rs, "Ok(gluegun_java_util::jni_support::ToJava::to_java(result, &mut env)?)")?,
+            TypeKind::Option { element, repr: _ }
+                if self.metadata.option == OptionRepr::Nullable && matches!(element.kind(), TypeKind::Scalar(_)) =>
+            {
+                write!(lib_rs, "Ok(gluegun_java_util::jni_support::ToJava::to_java(result, &mut env)?.into_raw())")?
+            }
+            TypeKind::String { repr: _ } => {
+                write!(lib_rs, "Ok(gluegun_java_util::jni_support::ToJava::to_java(result, &mut env)?.into_raw())")?
+            }
+            _ => write!(lib_rs, "Ok({{ let env = &mut env; {} }}.into_raw())", self.jni_to_java(main_ty, "result"))?,
+        }
 
+        write!(lib_rs, "}})();")?;
+        write!(lib_rs, "gluegun_java_util::jni_support::return_or_throw(&mut env, result)")?;
         write!(lib_rs, "}}")?;
-        write!(lib_rs, "}};")?;
         Ok(())
     }
 
-    fn rust_return_ty(&self, output: &FunctionOutput) -> String {
+    /// Generate the code giving the method `fn_name` of the resource `rust_qname` its `self` as `_self`, and return
+    /// the receiver argument of the call (see `generate_rust_call`). The Java object holds the pointer of an `Arc`
+    /// owning the value (see `generate_resource_to_java`), e.g. for `&self`:
+    ///
+    /// ```rust,ignore
+    /// let pointer = env.get_field(&this, "pointer", "J")?.j()? as *const ::my_crate::Counter;
+    /// let _self = unsafe { &*pointer };
+    /// ```
+    ///
+    /// Methods taking `self: Arc<Self>` are given a clone of the `Arc`. Those taking `&mut self` or `self` fail
+    /// if Rust code still holds a clone; the latter move the value out of the `Arc` and clear the pointer.
+    /// The Java methods are `synchronized` when there are such methods (see `JavaCodeGenerator::generate_resource`),
+    /// so that no other method uses the value in the meantime.
+    fn generate_resource_receiver(
+        &self,
+        lib_rs: &mut CodeWriter<'_>,
+        rust_qname: &QualifiedName,
+        fn_name: &Name,
+        self_kind: &SelfKind,
+    ) -> anyhow::Result<&'static str> {
+        let rust_path = rust_item_path(rust_qname);
+        let name = rust_qname.tail_name();
+        write!(lib_rs, "let pointer = env.get_field(&this, \"pointer\", \"J\")?.j()? as *const {rust_path};")?;
+        write!(lib_rs, "if pointer.is_null() {{")?;
+        write!(lib_rs, "return Err(\"`{name}` was consumed by a method that takes ownership of it\".into());")?;
+        write!(lib_rs, "}}")?;
+        let shared_error = format!(
+            "return Err(\"`{name}::{fn_name}` needs the only reference to `{name}`, but Rust code still shares it\".into());"
+        );
+        match self_kind {
+            SelfKind::ByRef => {
+                write!(lib_rs, "let _self = unsafe {{ &*pointer }};")?;
+                Ok("_self,")
+            }
+            SelfKind::ByArc => {
+                write!(lib_rs, "let _self = unsafe {{")?;
+                write!(lib_rs, "std::sync::Arc::increment_strong_count(pointer);")?;
+                write!(lib_rs, "std::sync::Arc::from_raw(pointer)")?;
+                write!(lib_rs, "}};")?;
+                Ok("_self,")
+            }
+            SelfKind::ByRefMut => {
+                // The object keeps owning the `Arc`.
+                write!(lib_rs, "let mut shared = std::mem::ManuallyDrop::new(unsafe {{ std::sync::Arc::from_raw(pointer) }});")?;
+                write!(lib_rs, "let Some(_self) = std::sync::Arc::get_mut(&mut shared) else {{")?;
+                write!(lib_rs, "{shared_error}")?;
+                write!(lib_rs, "}};")?;
+                Ok("_self,")
+            }
+            SelfKind::ByValue => {
+                write!(lib_rs, "let _self = match std::sync::Arc::try_unwrap(unsafe {{ std::sync::Arc::from_raw(pointer) }}) {{")?;
+                write!(lib_rs, "Ok(value) => value,")?;
+                write!(lib_rs, "Err(shared) => {{")?;
+                // The object keeps owning the `Arc`.
+                write!(lib_rs, "let _ = std::sync::Arc::into_raw(shared);")?;
+                write!(lib_rs, "{shared_error}")?;
+                write!(lib_rs, "}}")?;
+                write!(lib_rs, "}};")?;
+                write!(lib_rs, "env.set_field(&this, \"pointer\", \"J\", jni::objects::JValue::Long(0))?;")?;
+                Ok("_self,")
+            }
+            self_kind => anyhow::bail!("`{rust_path}::{fn_name}`: unsupported receiver: `{self_kind:?}`"),
+        }
+    }
+
+    fn rust_return_ty(&self, output: &FunctionOutput) -> anyhow::Result<String> {
         let main_ty = output.main_ty();
-        let main_str = self.rust_owned_ty(main_ty);
+
+        let main_str = match main_ty.ty().kind() {
+            // Addresses and UUIDs are returned in their string form.
+            TypeKind::NetAddr { repr: _ } | TypeKind::Uuid { repr: _ } => "String".to_string(),
+            // Scalars are returned as the Java primitive type (e.g., `u32` as `i32`).
+            TypeKind::Scalar(scalar) => match duchess_scalar(scalar) {
+                Some(java_scalar) => java_scalar.to_string(),
+                None => anyhow::bail!("unsupported scalar type: `{scalar}`"),
+            },
+            _ => self.rust_owned_ty(main_ty),
+        };
 
         let Some(_err_ty) = output.error_ty() else {
-            return format!("duchess::Result<{main_str}>");
+            return Ok(format!("duchess::Result<{main_str}>"));
         };
 
         // FIXME: fix the `err_ty` handling
 
-        format!("duchess::Result<{main_str}>")
+        Ok(format!("duchess::Result<{main_str}>"))
     }
 
     /// Return the type we should expect to receive from Java.
+    /// Collections, tuples, user types, and `java.util.Optional`s are only passed by the JNI glue (see `needs_jni`).
     fn java_parameter_ty(&self, ty: impl AsTy) -> anyhow::Result<String> {
         let ty = ty.as_ty();
 
-        // FIXME: Duchess's macro has bugs but these work more-or-less for now.
         match ty.kind() {
-            TypeKind::Map { key, value, repr: _ } => {
-                Ok(format!(
-                    "&duchess::java::util::Map<{}, {}>",
-                    self.java_parameter_ty(key)?,
-                    self.java_parameter_ty(value)?,
-                ))
-            }
-            TypeKind::Vec { element, repr: _ } => {
-                Ok(format!("&duchess::java::util::List<{}>", self.java_parameter_ty(element)?))
-            }
-            TypeKind::Set { element, repr: _ } => {
-                Ok(format!("&duchess::java::util::Set<{}>", self.java_parameter_ty(element)?))
-            }
-            TypeKind::Path { repr: _ } => {
+            // Addresses and UUIDs are exchanged through their string form (see `util::textual_ty`).
+            TypeKind::Path { repr: _ } | TypeKind::NetAddr { repr: _ } | TypeKind::Uuid { repr: _ } => {
                 Ok(format!("&duchess::java::lang::String"))
             }
             TypeKind::String { repr: _ } => {
                 Ok(format!("&duchess::java::lang::String"))
             }
-            TypeKind::Option { element, repr: _ } => {
-                // in practice everything in Java is nullable...
-                self.java_parameter_ty(element)
-            }
+            // in practice everything in Java is nullable...
+            TypeKind::Option { element, repr: _ } => self.java_parameter_ty(element),
             TypeKind::Result { ok: _, err: _, repr: _ } => {
                 Ok(format!("&duchess::java::lang::Object"))
             }
@@ -294,69 +1543,23 @@ impl<'idl> RustCodeGenerator<'idl> {
                     "&[&duchess::lang::Object]",
                 ))
             }
-            TypeKind::Scalar(scalar) => Ok(scalar.to_string()),
-            TypeKind::Future { output: _, repr: _ } => todo!(),
+            TypeKind::Scalar(scalar) => match duchess_scalar(scalar) {
+                Some(java_scalar) => Ok(java_scalar.to_string()),
+                None => anyhow::bail!("unsupported scalar type: `{scalar}`"),
+            },
+            TypeKind::Future { output: _, repr: _ } => {
+                anyhow::bail!("the Java backend does not support passing futures to Rust yet: `{ty}`", ty = ty.as_ty())
+            }
             TypeKind::Error { repr: _ } => {
                 Ok(format!("&duchess::java::lang::Exception"))
             }
-            TypeKind::UserType { qname: _ } => {
-                anyhow::bail!("user types not supported currently")
-            }
-            _ => todo!(),
+            _ => anyhow::bail!("unsupported parameter type: `{ty}`", ty = ty.as_ty()),
         }
     }
 
     /// Return the owned version of Rust type
     fn rust_owned_ty(&self, ty: impl AsTy) -> String {
-        let ty = ty.as_ty();
-
-        // FIXME: We really ought to be taking the Rust representation into account.
-        match ty.kind() {
-            TypeKind::Map { key, value, repr: _ } => {
-                format!(
-                    "HashMap<{}, {}>",
-                    self.rust_owned_ty(key),
-                    self.rust_owned_ty(value),
-                )
-            }
-            TypeKind::Vec { element, repr: _ } => {
-                format!("Vec<{}>", self.rust_owned_ty(element))
-            }
-            TypeKind::Set { element, repr: _ } => {
-                format!("HashSet<{}>", self.rust_owned_ty(element),)
-            }
-            TypeKind::Path { repr: _ } => {
-                format!("PathBuf")
-            }
-            TypeKind::String { repr: _ } => {
-                format!("String")
-            }
-            TypeKind::Option { element, repr: _ } => {
-                format!("Option<{}>", self.rust_owned_ty(element))
-            }
-            TypeKind::Result { ok, err, repr: _ } => {
-                format!(
-                    "Result<{}, {}>",
-                    self.rust_owned_ty(ok),
-                    self.rust_owned_ty(err)
-                )
-            }
-            TypeKind::Tuple { elements, repr: _ } => {
-                format!(
-                    "({})",
-                    elements
-                        .iter()
-                        .map(|ty| self.rust_owned_ty(ty))
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                )
-            }
-            TypeKind::Scalar(scalar) => scalar.to_string(),
-            TypeKind::Future { output: _, repr: _ } => todo!(),
-            TypeKind::Error { repr: _ } => format!("anyhow::Error"),
-            TypeKind::UserType { qname } => qname.colon_colon(),
-            _ => todo!(),
-        }
+        ty.as_ty().rust_owned_absolute_spelling()
     }
 
     /// Generate a call to the underlying Rust function.
@@ -369,31 +1572,121 @@ impl<'idl> RustCodeGenerator<'idl> {
         lib_rs: &mut CodeWriter<'_>,
         fn_name: &Name,
         rust_qname: &QualifiedName,
+        receiver: Option<Receiver<'_>>,
         signature: &Signature,
         output: &FunctionOutput,
     ) -> anyhow::Result<()> {
+        // The ordinal of an enum constant selects the arm passed as `self` (see `receiver`).
+        let receiver = match receiver {
+            Some(Receiver::Enum(an_enum, self_kind)) => {
+                let rust_path = rust_item_path(rust_qname);
+                write!(lib_rs, "let _self: {rust_path} = match ordinal {{")?;
+                for (index, arm) in an_enum.arms().iter().enumerate() {
+                    write!(lib_rs, "{index} => {rust_path}::{},", arm.name())?;
+                }
+                write!(
+                    lib_rs,
+                    "_ => return Err(duchess::Error::JvmInternal(format!(\"invalid ordinal of `{rust_path}`: {{ordinal}}\"))),",
+                )?;
+                write!(lib_rs, "}};")?;
+                if let SelfKind::ByRef = self_kind { "&_self," } else { "_self," }
+            }
+            Some(Receiver::Resource(..) | Receiver::Record(_)) => {
+                unreachable!("resources and records are passed by JNI functions")
+            }
+            None => "",
+        };
+        let callee = format!("{}::{fn_name}", rust_item_path(rust_qname));
+
         for input in signature.inputs() {
             let name = input.name();
-            write!(
-                lib_rs, 
-                "let {name}: {ty} = duchess::JvmOp::execute({name})?;",
-                ty = self.rust_owned_ty(input.refd_ty().ty()),
-            )?;
+            let ty = self.rust_owned_ty(input.refd_ty().ty());
+            match input.refd_ty().ty().kind() {
+                // Scalars arrive as the Java primitive type; characters are checked to be valid.
+                TypeKind::Scalar(Scalar::Char) => {
+                    write!(
+                        lib_rs,
+                        "let {name}: char = char::from_u32({name} as u32).ok_or_else(|| duchess::Error::JvmInternal(format!(\"invalid `{name}`: {{{name}}}\")))?;",
+                    )?;
+                }
+                TypeKind::Scalar(_) => write!(lib_rs, "let {name}: {ty} = {name} as {ty};")?,
+                // Addresses and UUIDs arrive in their string form; parse them.
+                TypeKind::NetAddr { repr: _ } | TypeKind::Uuid { repr: _ } => {
+                    write!(lib_rs, "let {name}: String = duchess::JvmOp::execute({name})?;")?;
+                    write!(
+                        lib_rs,
+                        "let {name} = {name}.parse().map_err(|err| duchess::Error::JvmInternal(format!(\"invalid `{name}`: {{err}}\")))?;",
+                    )?;
+                }
+                _ => write!(
+                    lib_rs, 
+                    "let {name}: {ty} = duchess::JvmOp::execute({name})?;",
+                )?,
+            }
         }
 
-        write!(lib_rs, "Ok({m}::{fn_name}(", m = rust_qname.colon_colon())?;
+        // `std::io::Error`s are thrown as an `UncheckedIOException` (see `generate_io_error_to_java`);
+        // error enums are thrown by JNI functions (see `needs_jni`).
+        let qmark = match output.error_ty().as_ref().map(|error_ty| error_ty.kind()) {
+            Some(TypeKind::Error { repr: ErrorRepr::IoError }) => ".map_err(gluegun_io_error_to_java)?".to_string(),
+            Some(_) => "?".to_string(),
+            None => String::new(),
+        };
 
-        for input in signature.inputs() {
-            self.generate_rust_argument(lib_rs, input)?;
+        let main_ty = output.main_ty().ty();
+        match main_ty.kind() {
+            // Addresses and UUIDs are returned in their string form.
+            TypeKind::NetAddr { repr: _ } | TypeKind::Uuid { repr: _ } => {
+                self.generate_rust_call(lib_rs, "Ok(", &format!("{qmark}.to_string())"), &callee, receiver, signature)?;
+            }
+            // Scalars are returned as the Java primitive type.
+            TypeKind::Scalar(scalar) => {
+                let java_scalar = duchess_scalar(scalar).unwrap_or_default();
+                self.generate_rust_call(lib_rs, "Ok(", &format!("{qmark} as {java_scalar})"), &callee, receiver, signature)?;
+            }
+            // Unit-returning functions (including `Result<(), E>`) map to `void` Java methods.
+            _ if output.is_unit() => {
+                self.generate_rust_call(lib_rs, "", &format!("{qmark};"), &callee, receiver, signature)?;
+                write!(lib_rs, "Ok(())")?;
+            }
+            // Borrowed data is copied out into an owned value before it is returned to Java.
+            _ if output.requires_copy_out() => {
+                self.generate_rust_call(
+                    lib_rs,
+                    "Ok(std::borrow::ToOwned::to_owned(",
+                    &format!("{qmark}))"),
+                    &callee,
+                    receiver,
+                    signature,
+                )?;
+            }
+            _ => {
+                self.generate_rust_call(lib_rs, "Ok(", &format!("{qmark})"), &callee, receiver, signature)?;
+            }
         }
 
-        let qmark = if output.error_ty().is_some() {
-            "?"
-        } else {
-            ""
-        };
+        Ok(())
+    }
 
-        write!(lib_rs, "){qmark})")?;
+    /// Generate a call `{prefix}{callee}({receiver}args){suffix}` to the underlying Rust function `callee` (e.g., `path::to::fn_name`),
+    /// where `receiver` is the `self` argument of a method, if any (e.g., `&_self,`).
+    fn generate_rust_call(
+        &self,
+        lib_rs: &mut CodeWriter<'_>,
+        prefix: &str,
+        suffix: &str,
+        callee: &str,
+        receiver: &str,
+        signature: &Signature,
+    ) -> anyhow::Result<()> {
+        write!(lib_rs, "{prefix}{callee}(")?;
+        if !receiver.is_empty() {
+            write!(lib_rs, "{receiver}")?;
+        }
+        for input in signature.inputs() {
+            self.generate_rust_argument(lib_rs, input)?;
+        }
+        write!(lib_rs, "){suffix}")?;
         Ok(())
     }
 
@@ -409,3 +1702,103 @@ impl<'idl> RustCodeGenerator<'idl> {
         Ok(())
     }
 }
+
+/// How a method is given its `self` (see `RustCodeGenerator::receiver`).
+#[derive(Copy, Clone)]
+enum Receiver<'r> {
+    /// A resource, whose Java object holds the pointer of an `Arc` owning the value (see `generate_resource_receiver`).
+    /// Getters (see [`MethodCategory::Getter`]) are given the field to read.
+    Resource(&'r SelfKind, Option<&'r Name>),
+
+    /// A record, of which the method is given a copy converted from the Java object.
+    Record(&'r SelfKind),
+
+    /// An enum without associated data, of which the method is given the arm whose index is the ordinal
+    /// of the Java constant.
+    Enum(&'r Enum, &'r SelfKind),
+}
+
+/// Name of the function generated by `generate_variant_from_java` or `generate_record_from_java` for `qname`.
+fn from_java_fn(qname: &QualifiedName) -> String {
+    format!("{}_from_java", qname.to_string("_"))
+}
+
+/// Name of the function generated by `generate_variant_to_java` or `generate_record_to_java` for `qname`.
+fn to_java_fn(qname: &QualifiedName) -> String {
+    format!("{}_to_java", qname.to_string("_"))
+}
+
+/// Name of the function generated by `generate_error_to_java` for `qname`.
+fn to_java_exception_fn(qname: &QualifiedName) -> String {
+    format!("{}_to_java_exception", qname.to_string("_"))
+}
+
+/// How `generate_error_to_java` tells the exception class which arm the error is.
+enum ErrorArm<'idl> {
+    /// The Java value of the variant, converted by `generate_variant_to_java`.
+    Value,
+
+    /// The ordinal of the constant of the enum.
+    Ordinal(&'idl Enum),
+}
+
+/// Name of the adapter generated by `generate_interface_adapter` for the interface `qname`.
+fn interface_adapter(qname: &QualifiedName) -> String {
+    qname.to_string("_")
+}
+
+/// True for the records packing the parameters of a function (see `Idl::pack_params`),
+/// which only Java code uses (see `JavaCodeGenerator::generate_packed_function`), so they have no Rust conversions.
+fn is_params_record(item: &Item) -> bool {
+    matches!(item, Item::Record(record) if record.params_of().is_some())
+}
+
+/// The JNI type descriptor of the Java primitive type that `scalar` maps to (e.g., `I` for `u32`)
+/// and the name of its boxed class in `java.lang` (e.g., `Integer`).
+fn jni_primitive_descriptor(scalar: &Scalar) -> Option<(&'static str, &'static str)> {
+    match scalar {
+        Scalar::Boolean => Some(("Z", "Boolean")),
+        Scalar::I8 | Scalar::U8 => Some(("B", "Byte")),
+        Scalar::I16 | Scalar::U16 => Some(("S", "Short")),
+        Scalar::I32 | Scalar::U32 => Some(("I", "Integer")),
+        Scalar::I64 | Scalar::U64 => Some(("J", "Long")),
+        Scalar::F32 => Some(("F", "Float")),
+        Scalar::F64 => Some(("D", "Double")),
+        _ => None,
+    }
+}
+
+/// The JNI type of the Java primitive type that `scalar` maps to (e.g., `jni::sys::jint` for `u32`).
+fn jni_primitive(scalar: &Scalar) -> Option<&'static str> {
+    match scalar {
+        Scalar::Boolean => Some("jni::sys::jboolean"),
+        Scalar::I8 | Scalar::U8 => Some("jni::sys::jbyte"),
+        Scalar::I16 | Scalar::U16 => Some("jni::sys::jshort"),
+        Scalar::I32 | Scalar::U32 => Some("jni::sys::jint"),
+        Scalar::I64 | Scalar::U64 => Some("jni::sys::jlong"),
+        Scalar::F32 => Some("jni::sys::jfloat"),
+        Scalar::F64 => Some("jni::sys::jdouble"),
+        _ => None,
+    }
+}
+
+/// The Rust type that duchess uses for the Java primitive type that `scalar` maps to (e.g., `i32` for `u32`),
+/// which native functions take and return in place of `scalar` (converting with `as`).
+fn duchess_scalar(scalar: &Scalar) -> Option<&'static str> {
+    match scalar {
+        Scalar::Boolean => Some("bool"),
+        Scalar::I8 | Scalar::U8 => Some("i8"),
+        Scalar::I16 | Scalar::U16 => Some("i16"),
+        Scalar::Char | Scalar::I32 | Scalar::U32 => Some("i32"),
+        Scalar::I64 | Scalar::U64 => Some("i64"),
+        Scalar::F32 => Some("f32"),
+        Scalar::F64 => Some("f64"),
+        _ => None,
+    }
+}
+
+/// Path of the Rust item `qname` starting with `::`, so that it names the user's crate even where
+/// the module that `duchess::java_package!` generates for the Java package of the same name is in scope.
+fn rust_item_path(qname: &QualifiedName) -> String {
+    format!("::{}", qname.colon_colon())
+}
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use gluegun_core::{
+    codegen::{CodeWriter, DirBuilder},
+    idl::{DocExample, Function, Idl, ModulePath, QualifiedName},
+};
+
+use crate::{metadata::JavaMetadata, util::{self, JavaNames}};
+
+/// Generates a JUnit test class (e.g., `my_crate/FunctionsExamplesTest.java`) per module,
+/// with a test stub for each code example in the doc comments of the module's functions.
+/// The stubs contain the Rust code of the example and are disabled until it is ported to Java.
+pub(crate) struct ExampleTestGenerator<'idl> {
+    idl: &'idl Idl,
+    names: JavaNames<'idl>,
+}
+
+impl<'idl> ExampleTestGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl, metadata: &'idl JavaMetadata) -> Self {
+        Self { idl, names: JavaNames::new(idl, metadata) }
+    }
+
+    /// True if any function has examples, in which case there are tests to generate.
+    pub(crate) fn has_examples(&self) -> bool {
+        !self.functions_with_examples().is_empty()
+    }
+
+    pub(crate) fn generate(self, mut dir: DirBuilder<'_>) -> anyhow::Result<()> {
+        for (module_path, functions) in self.functions_with_examples() {
+            let test_class = module_path.join("FunctionsExamplesTest");
+            let mut file = dir.add_file(self.names.class_file_name(&test_class))?;
+            let util::JavaQName { package, class_name } = self.names.class_package_and_name(&test_class);
+            write!(file, "package {};", package.dotted())?;
+            write!(file, "")?;
+            write!(file, "import org.junit.jupiter.api.Disabled;")?;
+            write!(file, "import org.junit.jupiter.api.Test;")?;
+            write!(file, "")?;
+            write!(file, "class {class_name} {{")?;
+            for (qname, function) in functions {
+                for (index, example) in function.examples().iter().enumerate() {
+                    file.with_span(example.span(), |file| {
+                        self.generate_example_test(file, qname, function, index + 1, example)
+                    })?;
+                }
+            }
+            write!(file, "}}")?;
+        }
+        Ok(())
+    }
+
+    fn generate_example_test(
+        &self,
+        file: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        function: &Function,
+        number: usize,
+        example: &DocExample,
+    ) -> anyhow::Result<()> {
+        write!(file, "")?;
+        write!(
+            file,
+            "/** Example {number} from the documentation of `{qname}` ({path}:{line}). */",
+            qname = qname.colon_colon(),
+            path = example.span().path().display(),
+            line = example.span().start().line(),
+        )?;
+        write!(file, "@Test")?;
+        write!(file, "@Disabled(\"example not yet ported from Rust\")")?;
+        write!(file, "void {}Example{number}() {{", function.name().camel_case())?;
+        for line in example.code().lines() {
+            file.write_verbatim(format_args!("// {line}"))?;
+        }
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// The functions with examples, grouped by module.
+    fn functions_with_examples(&self) -> BTreeMap<ModulePath, Vec<(&'idl QualifiedName, &'idl Function)>> {
+        let mut modules: BTreeMap<ModulePath, Vec<_>> = BTreeMap::new();
+        for (qname, function) in self.idl.functions() {
+            if !function.examples().is_empty() {
+                modules.entry(qname.module_path()).or_default().push((qname, function));
+            }
+        }
+        modules
+    }
+}
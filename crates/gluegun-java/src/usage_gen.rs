@@ -0,0 +1,100 @@
+use gluegun_core::{
+    codegen::LibraryCrate,
+    idl::{Function, Idl, Item, QualifiedName, Variant},
+};
+
+use crate::{metadata::JavaMetadata, util::JavaNames};
+
+/// Registers the build steps and a usage snippet for each function and enum with associated data,
+/// which gluegun assembles into the `README.md` of the generated crate, e.g.
+///
+/// ```java
+/// import myCrate.Functions;
+///
+/// var result = Functions.greet(name);
+/// ```
+///
+/// It also registers the Java class (or method of the `Functions` class) generated for each item,
+/// along with its methods, for the `bindings.json` manifest.
+pub(crate) struct UsageGenerator<'idl> {
+    idl: &'idl Idl,
+    metadata: &'idl JavaMetadata,
+    names: JavaNames<'idl>,
+}
+
+impl<'idl> UsageGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl, metadata: &'idl JavaMetadata) -> Self {
+        Self { idl, metadata, names: JavaNames::new(idl, metadata) }
+    }
+
+    pub(crate) fn generate(self, lib: &mut LibraryCrate) {
+        lib.add_build_step(
+            "Build the native library; its build script also compiles the Java sources in `java_src`",
+            "cargo build --release",
+        );
+
+        for (qname, item) in self.idl.definitions() {
+            match item {
+                Item::Function(function) => lib.add_usage(qname, "java", function_usage(self.metadata, self.names, qname, function)),
+                Item::Variant(variant) => lib.add_usage(qname, "java", variant_usage(self.names, qname, variant)),
+                _ => {}
+            }
+            register_bindings(lib, self.metadata, self.names, qname, item);
+        }
+    }
+}
+
+/// Register the class generated for `item`, and its methods, or, for a function, the method of the
+/// `Functions` class of its module (e.g., `myCrate.Functions.greet`).
+fn register_bindings(lib: &mut LibraryCrate, metadata: &JavaMetadata, names: JavaNames<'_>, qname: &QualifiedName, item: &Item) {
+    let methods = match item {
+        Item::Function(function) => {
+            let functions_class = names.class_dot_name(&qname.module_path().join("Functions"));
+            lib.add_binding(qname, format!("{functions_class}.{}", metadata.member_name(function.name())));
+            return;
+        }
+        Item::Resource(resource) => resource.methods(),
+        Item::Record(record) => record.methods(),
+        Item::Newtype(newtype) => newtype.methods(),
+        Item::Variant(variant) => variant.methods(),
+        Item::Enum(an_enum) => an_enum.methods(),
+        Item::Interface(interface) => interface.methods(),
+        _ => return,
+    };
+
+    let class = names.class_dot_name(qname);
+    lib.add_binding(qname, &class);
+    for method in methods {
+        lib.add_member_binding(qname, method.name(), format!("{class}.{}", metadata.method_name(method)));
+    }
+}
+
+fn function_usage(metadata: &JavaMetadata, names: JavaNames<'_>, qname: &QualifiedName, function: &Function) -> String {
+    let functions_class = qname.module_path().join("Functions");
+    let arguments: Vec<String> = function
+        .signature()
+        .inputs()
+        .iter()
+        .map(|input| input.name().to_string())
+        .collect();
+    let result = if function.signature().output_ty().is_unit() { "" } else { "var result = " };
+    format!(
+        "import {class};\n\n{result}Functions.{name}({arguments});",
+        class = names.class_dot_name(&functions_class),
+        name = metadata.member_name(function.name()),
+        arguments = arguments.join(", "),
+    )
+}
+
+fn variant_usage(names: JavaNames<'_>, qname: &QualifiedName, variant: &Variant) -> String {
+    let class_name = names.class_package_and_name(qname).class_name;
+    let mut usage = format!("import {};\n\nswitch (value) {{", names.class_dot_name(qname));
+    for arm in variant.arms() {
+        usage.push_str(&format!(
+            "\n    case {class_name}.{arm} arm -> {{ /* ... */ }}",
+            arm = arm.name().upper_camel_case()
+        ));
+    }
+    usage.push_str("\n}");
+    usage
+}
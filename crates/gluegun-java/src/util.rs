@@ -1,42 +1,298 @@
-use std::path::PathBuf;
+use std::{collections::BTreeSet, path::PathBuf};
 
-use gluegun_core::idl::{Name, QualifiedName, RefdTy, Ty};
+use gluegun_core::{
+    idl::{ErrorRepr, Field, Idl, MapSetRepr, ModulePath, Name, NetAddrRepr, QualifiedName, RefdTy, Ty, TypeKind, VariantArm},
+    visit::{self, Visitor},
+};
+
+use crate::metadata::{JavaMetadata, TextualTypes};
 
 /// A qualified name following Java conventions.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub(crate) struct JavaQName {
     /// like `java.lang`
-    pub(crate) package: QualifiedName,
+    pub(crate) package: ModulePath,
 
     /// like `String`
     pub(crate) class_name: Name,
 }
 
-/// Convert a qualified name from Rust to Java conventions and break apart the module/class name
-pub(crate) fn class_package_and_name(qname: &QualifiedName) -> JavaQName  {
-    let (module_name, type_name) = qname.camel_case().split_module_name();
-    JavaQName {
-        package: module_name,
-        class_name: type_name.upper_camel_case(),
+/// Converts qualified names from Rust to Java conventions. The modules of the crate become packages
+/// under the root package, which is the crate name in camelCase unless the `package` option sets it
+/// (e.g., `com.example.mylib`); the packages of other crates are derived from their names.
+#[derive(Copy, Clone)]
+pub(crate) struct JavaNames<'a> {
+    crate_name: &'a Name,
+    package: Option<&'a str>,
+}
+
+impl<'a> JavaNames<'a> {
+    pub(crate) fn new(idl: &'a Idl, metadata: &'a JavaMetadata) -> Self {
+        Self {
+            crate_name: idl.crate_name(),
+            package: metadata.package.as_deref(),
+        }
+    }
+
+    /// The Java package of the Rust module `module_path` (e.g., `myCrate.shapes` for `my_crate::shapes`)
+    pub(crate) fn package(&self, module_path: &ModulePath) -> ModulePath {
+        let package = module_path.camel_case();
+        match self.package {
+            Some(root) => package.with_crate_root(&self.crate_name.camel_case(), root),
+            None => package,
+        }
+    }
+
+    /// Convert a qualified name from Rust to Java conventions and break apart the package/class name
+    pub(crate) fn class_package_and_name(&self, qname: &QualifiedName) -> JavaQName {
+        let (package, type_name) = qname.camel_case().split_module_path();
+        JavaQName {
+            package: self.package(&package),
+            class_name: type_name.upper_camel_case(),
+        }
+    }
+
+    /// Return a path like `java/lang/String.java`
+    pub(crate) fn class_file_name(&self, qname: &QualifiedName) -> PathBuf {
+        let JavaQName { package, class_name } = self.class_package_and_name(qname);
+        let mut path = PathBuf::new();
+        for name in package.names() {
+            path.push(name.text());
+        }
+        path.push(class_name.text());
+        path.set_extension("java");
+        path
+    }
+
+    /// Return a string like `java.lang.String`
+    pub(crate) fn class_dot_name(&self, qname: &QualifiedName) -> String {
+        let JavaQName { package, class_name } = self.class_package_and_name(qname);
+        format!("{}.{}", package.dotted(), class_name)
+    }
+
+    /// Return a string like `java/lang/String`, the name JNI uses for a class
+    pub(crate) fn class_jni_name(&self, qname: &QualifiedName) -> String {
+        self.class_dot_name(qname).replace('.', "/")
+    }
+
+    /// Return the path of the Rust type that duchess generates for a Java class (e.g., `java::lang::String`).
+    pub(crate) fn class_rust_path(&self, qname: &QualifiedName) -> String {
+        let JavaQName { package, class_name } = self.class_package_and_name(qname);
+        format!("{}::{}", package.colon_colon(), class_name)
+    }
+
+    /// Return the symbol of the native function implementing `method` of the class `qname`
+    /// (e.g., `Java_myCrate_Functions_native_1greet` for `myCrate.Functions.native_greet`),
+    /// following the JNI name mangling rules.
+    pub(crate) fn jni_symbol(&self, qname: &QualifiedName, method: &str) -> String {
+        format!("Java_{}_{}", jni_mangle(&self.class_dot_name(qname)), jni_mangle(method))
+    }
+}
+
+/// Return the qname of the generated `TupleN` class for tuples of the given arity
+/// (e.g., `my_crate.gluegun.Tuple2`). These live in a support package of the crate
+/// so that they cannot collide with user-defined types.
+pub(crate) fn tuple_class_qname(idl: &Idl, arity: usize) -> QualifiedName {
+    QualifiedName::from(idl.crate_name())
+        .join("gluegun")
+        .join(format!("Tuple{arity}"))
+}
+
+/// Return the qname of the generated support class used by the native code to convert
+/// values of the variant or record `qname` to and from Java (e.g., `my_crate.gluegun.MyCrateShapeSupport`).
+/// Like the `TupleN` classes, these live in the support package of the crate.
+pub(crate) fn support_qname(idl: &Idl, qname: &QualifiedName) -> QualifiedName {
+    QualifiedName::from(idl.crate_name())
+        .join("gluegun")
+        .join(format!("{}Support", qname.upper_camel_case().to_string("")))
+}
+
+/// Return the qname of the exception class generated for the error enum `qname` (see [`Idl::error_item`]),
+/// which lives next to the class of the enum (e.g., `my_crate.ParseErrorException` for `my_crate::ParseError`).
+pub(crate) fn exception_qname(qname: &QualifiedName) -> QualifiedName {
+    qname.module_path().join(format!("{}Exception", qname.tail_name()))
+}
+
+/// Return the qname of the generated class that checks, when the native library is first used,
+/// that it was generated from the same interface as the Java classes (e.g., `my_crate.gluegun.InterfaceHash`).
+pub(crate) fn interface_hash_qname(idl: &Idl) -> QualifiedName {
+    QualifiedName::from(idl.crate_name()).join("gluegun").join("InterfaceHash")
+}
+
+/// Name of the static method of the variant support class that constructs `arm` (e.g., `new_circle`).
+///
+/// The support methods are only called from the generated Rust code, so they are named in snake case:
+/// duchess maps Java method names to snake case, and leaving them unchanged avoids any ambiguity.
+pub(crate) fn variant_arm_constructor(arm: &VariantArm) -> Name {
+    Name::from(format!("new_{}", arm.name().snake_case()))
+}
+
+/// Name of the static method of the variant support class that reads `field` of `arm` (e.g., `circle_radius`).
+pub(crate) fn variant_arm_getter(arm: &VariantArm, field: &Field) -> Name {
+    Name::from(format!("{}_{}", arm.name().snake_case(), field.name()))
+}
+
+/// Name of the static method of the record support class that reads `field` (e.g., `get_radius`).
+pub(crate) fn record_getter(field: &Field) -> Name {
+    Name::from(format!("get_{}", field.name()))
+}
+
+/// True if these are the fields of a tuple struct or tuple-like variant arm,
+/// which the IDL names `f0`, `f1`, etc.
+pub(crate) fn is_tuple_like(fields: &[Field]) -> bool {
+    !fields.is_empty()
+        && fields
+            .iter()
+            .enumerate()
+            .all(|(index, field)| *field.name().text() == format!("f{index}"))
+}
+
+/// Return the set of (non-zero) tuple arities that appear anywhere in the IDL,
+/// so that we only generate the `TupleN` classes that are actually needed.
+pub(crate) fn tuple_arities(idl: &Idl) -> anyhow::Result<BTreeSet<usize>> {
+    struct TupleArities(BTreeSet<usize>);
+
+    impl<'idl> Visitor<'idl> for TupleArities {
+        fn visit_ty(&mut self, ty: &'idl Ty) -> anyhow::Result<()> {
+            if let TypeKind::Tuple { elements, repr: _ } = ty.kind() {
+                if !elements.is_empty() {
+                    self.0.insert(elements.len());
+                }
+            }
+            visit::walk_ty(self, ty)
+        }
+    }
+
+    let mut visitor = TupleArities(BTreeSet::new());
+    visitor.visit_idl(idl)?;
+    Ok(visitor.0)
+}
+
+/// True if an `IndexMap` or `IndexSet` appears anywhere in the IDL,
+/// in which case the generated crate needs to depend on `indexmap`.
+pub(crate) fn uses_indexmap(idl: &Idl) -> anyhow::Result<bool> {
+    struct UsesIndexMap(bool);
+
+    impl<'idl> Visitor<'idl> for UsesIndexMap {
+        fn visit_ty(&mut self, ty: &'idl Ty) -> anyhow::Result<()> {
+            if let TypeKind::Map { repr: MapSetRepr::Index, .. } | TypeKind::Set { repr: MapSetRepr::Index, .. } =
+                ty.kind()
+            {
+                self.0 = true;
+            }
+            visit::walk_ty(self, ty)
+        }
+    }
+
+    let mut visitor = UsesIndexMap(false);
+    visitor.visit_idl(idl)?;
+    Ok(visitor.0)
+}
+
+/// Return the qname of the generated class that constructs the exception thrown for an `std::io::Error`
+/// (e.g., `my_crate.gluegun.IoErrors`), which is only generated if the IDL uses them (see [`uses_io_errors`]).
+pub(crate) fn io_errors_qname(idl: &Idl) -> QualifiedName {
+    QualifiedName::from(idl.crate_name()).join("gluegun").join("IoErrors")
+}
+
+/// True if an `std::io::Error` appears anywhere in the IDL (typically as the error of a function).
+pub(crate) fn uses_io_errors(idl: &Idl) -> anyhow::Result<bool> {
+    struct UsesIoErrors(bool);
+
+    impl<'idl> Visitor<'idl> for UsesIoErrors {
+        fn visit_ty(&mut self, ty: &'idl Ty) -> anyhow::Result<()> {
+            if let TypeKind::Error { repr: ErrorRepr::IoError } = ty.kind() {
+                self.0 = true;
+            }
+            visit::walk_ty(self, ty)
+        }
     }
+
+    let mut visitor = UsesIoErrors(false);
+    visitor.visit_idl(idl)?;
+    Ok(visitor.0)
 }
 
-/// Return a path like `java/lang/String.java`
-pub(crate) fn class_file_name(qname: &QualifiedName) -> PathBuf {
-    let JavaQName { package, class_name } = class_package_and_name(qname);
-    let mut path = PathBuf::new();
-    for name in package.names() {
-        path.push(name.text());
+/// The `IOException` subclass thrown (as the cause of an `UncheckedIOException`) for each kind of `std::io::Error`;
+/// other kinds are thrown as a plain `java.io.IOException`. This mirrors `gluegun_java_util::jni_support`,
+/// which throws them from the JNI glue.
+pub(crate) const IO_EXCEPTIONS: &[(&str, &str)] = &[
+    ("NotFound", "java.nio.file.NoSuchFileException"),
+    ("PermissionDenied", "java.nio.file.AccessDeniedException"),
+    ("AlreadyExists", "java.nio.file.FileAlreadyExistsException"),
+    ("UnexpectedEof", "java.io.EOFException"),
+    ("TimedOut", "java.net.SocketTimeoutException"),
+    ("ConnectionRefused", "java.net.ConnectException"),
+];
+
+/// How a type exchanged through its string form appears in the public Java API (see [`TextualTypes`]).
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) enum JavaTextual {
+    /// `String`
+    String,
+
+    /// `java.util.UUID`
+    Uuid,
+
+    /// `java.net.InetAddress`, or the subclass named here (e.g., `java.net.Inet4Address`)
+    InetAddress(&'static str),
+}
+
+impl JavaTextual {
+    /// The Java type.
+    pub(crate) fn java_ty(self) -> &'static str {
+        match self {
+            JavaTextual::String => "String",
+            JavaTextual::Uuid => "java.util.UUID",
+            JavaTextual::InetAddress(class) => class,
+        }
+    }
+
+    /// A Java expression giving the string form of `expr`, to pass it to the native method.
+    pub(crate) fn to_string_expr(self, expr: &str) -> String {
+        match self {
+            JavaTextual::String => expr.to_string(),
+            JavaTextual::Uuid => format!("{expr}.toString()"),
+            JavaTextual::InetAddress(_) => format!("{expr}.getHostAddress()"),
+        }
     }
-    path.push(class_name.text());
-    path.set_extension("java");
-    path
 }
 
-/// Return a string like `java.lang.String`
-pub(crate) fn class_dot_name(qname: &QualifiedName) -> String {
-    let JavaQName { package, class_name } = class_package_and_name(qname);
-    format!("{}.{}", package.dotted(), class_name)
+/// If `ty` is exchanged with Java through its string form (an IP or socket address, or a UUID),
+/// return how it appears in the public Java API. Only function arguments and return types
+/// are converted: the Java backend rejects these types anywhere else.
+pub(crate) fn textual_ty(ty: &Ty, textual_types: TextualTypes) -> Option<JavaTextual> {
+    let textual = match (ty.kind(), textual_types) {
+        (TypeKind::NetAddr { .. } | TypeKind::Uuid { .. }, TextualTypes::String) => JavaTextual::String,
+        (TypeKind::NetAddr { repr }, TextualTypes::Native) => match repr {
+            NetAddrRepr::IpAddr => JavaTextual::InetAddress("java.net.InetAddress"),
+            NetAddrRepr::Ipv4Addr => JavaTextual::InetAddress("java.net.Inet4Address"),
+            NetAddrRepr::Ipv6Addr => JavaTextual::InetAddress("java.net.Inet6Address"),
+            _ => JavaTextual::String,
+        },
+        (TypeKind::Uuid { .. }, TextualTypes::Native) => JavaTextual::Uuid,
+        _ => return None,
+    };
+    Some(textual)
+}
+
+fn jni_mangle(name: &str) -> String {
+    let mut mangled = String::new();
+    for ch in name.chars() {
+        match ch {
+            '.' | '/' => mangled.push('_'),
+            '_' => mangled.push_str("_1"),
+            ';' => mangled.push_str("_2"),
+            '[' => mangled.push_str("_3"),
+            _ if ch.is_ascii_alphanumeric() => mangled.push(ch),
+            _ => {
+                for unit in ch.encode_utf16(&mut [0; 2]) {
+                    mangled.push_str(&format!("_0{unit:04x}"));
+                }
+            }
+        }
+    }
+    mangled
 }
 
 pub trait AsTy {
@@ -0,0 +1,40 @@
+use anyhow::Context;
+use gluegun_core::{
+    cli::{GenerateCx, GlueGunHelper},
+    codegen::LibraryCrate,
+};
+
+mod rs_gen;
+
+pub fn main() -> anyhow::Result<()> {
+    gluegun_core::cli::run(GlueGunLua)
+}
+
+/// Generates a Lua module using [mlua](https://github.com/mlua-rs/mlua): functions map to module functions
+/// and resources to userdata with methods, so that the library can be `require`d from Lua scripts.
+#[derive(Clone)]
+struct GlueGunLua;
+
+impl GlueGunHelper for GlueGunLua {
+    type Metadata = ();
+
+    const SUPPORTS_DEBUG_BINDINGS: bool = true;
+
+    fn name(&self) -> String {
+        "lua".to_string()
+    }
+
+    fn generate(self, cx: &mut GenerateCx, _metadata: &(), output: &mut LibraryCrate) -> anyhow::Result<()> {
+        // The generated crate is a `cdylib` (the default); Lua's `require` looks for a `luaopen_<name>`
+        // function matching the file name of the library.
+        let module_name = output.crate_name().replace('-', "_");
+
+        output.add_dependency("mlua").version("0.9").feature("lua54").feature("module");
+
+        rs_gen::RustCodeGenerator::new(cx.idl(), module_name, output.debug_bindings_enabled())
+            .generate(output)
+            .context("generating Rust sources")?;
+
+        Ok(())
+    }
+}
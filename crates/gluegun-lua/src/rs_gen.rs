@@ -0,0 +1,407 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use gluegun_core::{
+    codegen::{copy_out, CodeWriter, LibraryCrate},
+    idl::{
+        Function, Idl, Item, MapSetRepr, Method, MethodCategory, Name, OptionRepr, QualifiedName, RefdTy, Resource,
+        Scalar, SelfKind, Signature, StringRepr, TupleRepr, Ty, TypeKind, UserTypeRepr, VecRepr,
+    },
+    type_mapper::{self, TypeMapper},
+};
+
+/// Generates the Lua module. Each resource gets a wrapper type (e.g., `Counter` wrapping
+/// `my_crate::Counter`) implementing `mlua::UserData` with its instance methods; its constructor
+/// (`new`) and static methods are placed in a table named after the type. Functions are placed in
+/// the module table, with nested tables for Rust modules (e.g., `geometry.area` for `my_crate::geometry::area`).
+pub(crate) struct RustCodeGenerator<'idl> {
+    idl: &'idl Idl,
+
+    /// Name of the Lua module (and of the `luaopen_` function that Lua looks for).
+    module_name: String,
+
+    /// Whether wrapper types count their live instances (see `LibraryCrate::debug_bindings_enabled`).
+    debug_bindings: bool,
+}
+
+/// The receiver of an instance method, as given to the closure registered with `mlua`.
+enum Receiver {
+    None,
+    Ref,
+    RefMut,
+}
+
+impl<'idl> RustCodeGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl, module_name: String, debug_bindings: bool) -> Self {
+        Self { idl, module_name, debug_bindings }
+    }
+
+    pub(crate) fn generate(mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        self.check_wrapper_names()?;
+
+        let mut lib_rs = lib.add_file("src/lib.rs")?;
+        self.generate_support(&mut lib_rs)?;
+
+        let mut functions = vec![];
+        let mut resources = vec![];
+        for (qname, item) in self.idl.definitions() {
+            match item {
+                Item::Function(function) => functions.push((qname, function)),
+                Item::Resource(resource) => {
+                    lib_rs.with_span(resource.span(), |lib_rs| self.generate_user_data(lib_rs, qname, resource))?;
+                    resources.push((qname, resource));
+                }
+                _ => anyhow::bail!(
+                    "`{}`: only functions and resources are currently supported by the lua backend",
+                    qname.colon_colon()
+                ),
+            }
+        }
+
+        write!(lib_rs, "#[mlua::lua_module]")?;
+        write!(lib_rs, "fn {}(lua: &mlua::Lua) -> mlua::Result<mlua::Table> {{", self.module_name)?;
+        write!(lib_rs, "let exports = lua.create_table()?;")?;
+
+        // Create a table for each module containing a function or resource (and its parents).
+        let mut modules = BTreeSet::new();
+        let qnames = functions.iter().map(|&(qname, _)| qname).chain(resources.iter().map(|&(qname, _)| qname));
+        for qname in qnames {
+            let names = qname.names();
+            for len in 2..names.len() {
+                modules.insert(names[1..len].to_vec());
+            }
+        }
+        for module in &modules {
+            let (name, parent) = module.split_last().unwrap();
+            write!(lib_rs, "let {} = lua.create_table()?;", table_variable(module))?;
+            write!(lib_rs, "{}.set({:?}, &{})?;", table_variable(parent), name.text(), table_variable(module))?;
+        }
+
+        for (qname, function) in functions {
+            lib_rs.with_span(function.span(), |lib_rs| self.generate_function(lib_rs, qname, function))?;
+        }
+        for (qname, resource) in resources {
+            lib_rs.with_span(resource.span(), |lib_rs| self.generate_class_table(lib_rs, qname, resource))?;
+        }
+        if self.debug_bindings {
+            self.generate_debug_table(&mut lib_rs)?;
+        }
+
+        write!(lib_rs, "Ok(exports)")?;
+        write!(lib_rs, "}}")?;
+
+        Ok(())
+    }
+
+    /// Generate the helper that raises a panic as a Lua error carrying the panic's message,
+    /// so that Lua code can recover from it with `pcall`.
+    fn generate_support(&mut self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "fn gluegun_catch_panic<T>(f: impl FnOnce() -> T) -> mlua::Result<T> {{")?;
+        write!(lib_rs, "std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).map_err(|payload| {{")?;
+        write!(lib_rs, "let message = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {{")?;
+        write!(lib_rs, "(Some(message), _) => message,")?;
+        write!(lib_rs, "(None, Some(message)) => message.as_str(),")?;
+        write!(lib_rs, "(None, None) => \"(no message)\",")?;
+        write!(lib_rs, "}};")?;
+        write!(lib_rs, "mlua::Error::runtime(format!(\"Rust panicked: {{message}}\"))")?;
+        write!(lib_rs, "}})")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "")?;
+        Ok(())
+    }
+
+    /// Wrapper types are all defined at the root of the generated crate, so their names must be unique.
+    fn check_wrapper_names(&self) -> anyhow::Result<()> {
+        let mut names: BTreeMap<Name, &QualifiedName> = BTreeMap::new();
+        for (qname, item) in self.idl.definitions() {
+            if let Item::Resource(_) = item {
+                if let Some(previous) = names.insert(qname.tail_name(), qname) {
+                    anyhow::bail!(
+                        "`{}` and `{}` have the same name, which is not supported by the lua backend",
+                        previous.colon_colon(),
+                        qname.colon_colon()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Generate the `gluegun_debug` table of the module, whose `live_objects` function returns the number of
+    /// wrappers that Lua has not garbage collected yet (see `generate_user_data`).
+    fn generate_debug_table(&mut self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "let gluegun_debug = lua.create_table()?;")?;
+        write!(
+            lib_rs,
+            "gluegun_debug.set(\"live_objects\", lua.create_function(|_, ()| Ok(crate::gluegun_debug::live_objects()))?)?;"
+        )?;
+        write!(lib_rs, "exports.set(\"gluegun_debug\", gluegun_debug)?;")?;
+        Ok(())
+    }
+
+    /// Generate the wrapper type for the resource `qname`, exposing its instance methods to Lua.
+    /// With debug bindings, it also holds a `gluegun_debug::LiveObject`, so that it counts as live until Lua collects it.
+    fn generate_user_data(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, resource: &Resource) -> anyhow::Result<()> {
+        let wrapper = qname.tail_name();
+        let live_object = if self.debug_bindings { ", crate::gluegun_debug::LiveObject" } else { "" };
+        write!(lib_rs, "pub struct {wrapper}(pub {}{live_object});", qname.colon_colon())?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "impl mlua::UserData for {wrapper} {{")?;
+        write!(
+            lib_rs,
+            "fn add_methods<'lua, M: mlua::UserDataMethods<'lua, Self>>(methods: &mut M) {{"
+        )?;
+        for method in resource.methods() {
+            let receiver = match method.category() {
+                MethodCategory::Constructor | MethodCategory::NamedConstructor | MethodCategory::StaticMethod => continue,
+                MethodCategory::InstanceMethod(SelfKind::ByRef) => Receiver::Ref,
+                MethodCategory::InstanceMethod(SelfKind::ByRefMut) => Receiver::RefMut,
+                _ => anyhow::bail!(
+                    "{}: only `&self` and `&mut self` methods are supported by the lua backend",
+                    method.span()
+                ),
+            };
+            let add_method = match receiver {
+                Receiver::RefMut => "add_method_mut",
+                _ => "add_method",
+            };
+            self.generate_closure(
+                lib_rs,
+                &format!("methods.{add_method}({:?}, ", method.name().text()),
+                ");",
+                &method_path(qname, method),
+                receiver,
+                method.signature(),
+            )?;
+        }
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "")?;
+        Ok(())
+    }
+
+    /// Generate the table for the resource `qname` holding its constructor (`new`) and static methods.
+    fn generate_class_table(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, resource: &Resource) -> anyhow::Result<()> {
+        write!(lib_rs, "{{")?;
+        write!(lib_rs, "let class = lua.create_table()?;")?;
+        for method in resource.methods() {
+            let name = match method.category() {
+                MethodCategory::Constructor => "new".to_string(),
+                MethodCategory::NamedConstructor | MethodCategory::StaticMethod => method.name().to_string(),
+                _ => continue,
+            };
+            self.generate_closure(
+                lib_rs,
+                &format!("class.set({name:?}, lua.create_function("),
+                ")?)?;",
+                &method_path(qname, method),
+                Receiver::None,
+                method.signature(),
+            )?;
+        }
+        write!(
+            lib_rs,
+            "{}.set({:?}, class)?;",
+            parent_table_variable(qname),
+            qname.tail_name().text()
+        )?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    fn generate_function(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, function: &Function) -> anyhow::Result<()> {
+        self.generate_closure(
+            lib_rs,
+            &format!(
+                "{}.set({:?}, lua.create_function(",
+                parent_table_variable(qname),
+                function.name().text()
+            ),
+            ")?)?;",
+            &qname.colon_colon(),
+            Receiver::None,
+            function.signature(),
+        )
+    }
+
+    /// Generate the closure registered with `mlua` that invokes `rust_path`, preceded by `prefix`
+    /// and followed by `suffix` (e.g., the call registering it).
+    /// Arguments are received as a tuple of owned values (or `UserDataRef`s for resources)
+    /// and borrowed if the wrapped function expects a reference.
+    fn generate_closure(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        prefix: &str,
+        suffix: &str,
+        rust_path: &str,
+        receiver: Receiver,
+        signature: &Signature,
+    ) -> anyhow::Result<()> {
+        if let gluegun_core::idl::IsAsync::Yes = signature.is_async() {
+            anyhow::bail!("`{rust_path}`: async functions are not yet supported by the lua backend");
+        }
+
+        let mut names = vec![];
+        let mut tys = vec![];
+        let mut arguments = vec![];
+        match receiver {
+            Receiver::None => {}
+            Receiver::Ref => arguments.push("&this.0".to_string()),
+            Receiver::RefMut => arguments.push("&mut this.0".to_string()),
+        }
+        for input in signature.inputs() {
+            let name = input.name();
+            let ty = input.refd_ty().ty();
+            names.push(name.to_string());
+            match (self.resource_wrapper(ty), input.refd_ty()) {
+                (Some(wrapper), RefdTy::Ref(..)) => {
+                    tys.push(format!("mlua::UserDataRef<{wrapper}>"));
+                    arguments.push(format!("&{name}.0"));
+                }
+                (Some(_), RefdTy::Owned(..)) => {
+                    anyhow::bail!("{}: resources can only be passed by reference in the lua backend", ty.span())
+                }
+                (None, refd_ty) => {
+                    tys.push(self.map_ty(ty)?);
+                    match refd_ty {
+                        RefdTy::Owned(..) => arguments.push(name.to_string()),
+                        RefdTy::Ref(..) => arguments.push(format!("&{name}")),
+                    }
+                }
+            }
+        }
+
+        let this = match receiver {
+            Receiver::None => "",
+            Receiver::Ref | Receiver::RefMut => "this, ",
+        };
+        let trailing_comma = if names.len() == 1 { "," } else { "" };
+        write!(
+            lib_rs,
+            "{prefix}|_, {this}({}{trailing_comma}): ({}{trailing_comma})| {{",
+            names.join(", "),
+            tys.join(", ")
+        )?;
+
+        // Errors and panics are raised as Lua errors carrying the error's message.
+        let output_ty = signature.output_ty();
+        let mut call = format!("gluegun_catch_panic(|| {rust_path}({}))?", arguments.join(", "));
+        if output_ty.error_ty().is_some() {
+            call = format!("{call}.map_err(|err| mlua::Error::runtime(err.to_string()))?");
+        }
+        // Unit-returning functions (including `Result<(), E>`) return nothing to Lua.
+        if output_ty.is_unit() {
+            write!(lib_rs, "{call};")?;
+            write!(lib_rs, "Ok(())")?;
+            write!(lib_rs, "}}{suffix}")?;
+            return Ok(());
+        }
+        write!(lib_rs, "let result = {call};")?;
+
+        let main_ty = output_ty.main_ty().ty();
+        match self.resource_wrapper(main_ty) {
+            Some(_) if output_ty.requires_copy_out() => {
+                anyhow::bail!("{}: resources can only be returned by value in the lua backend", main_ty.span())
+            }
+            Some(wrapper) if self.debug_bindings => {
+                write!(lib_rs, "Ok({wrapper}(result, crate::gluegun_debug::LiveObject::new()))")?
+            }
+            Some(wrapper) => write!(lib_rs, "Ok({wrapper}(result))")?,
+            None => {
+                self.map_ty(main_ty)?;
+                write!(lib_rs, "Ok({})", copy_out(output_ty, "result"))?;
+            }
+        }
+        write!(lib_rs, "}}{suffix}")?;
+        Ok(())
+    }
+
+    /// If `ty` is a resource, the name of its wrapper type.
+    fn resource_wrapper(&self, ty: &Ty) -> Option<Name> {
+        match ty.kind() {
+            TypeKind::UserType { qname, repr: UserTypeRepr::Direct } => match self.idl.item(qname) {
+                Some(Item::Resource(_)) => Some(qname.tail_name()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The variable holding the Lua table for the module `names` (relative to the crate).
+fn table_variable(names: &[Name]) -> String {
+    if names.is_empty() {
+        "exports".to_string()
+    } else {
+        let names: Vec<&str> = names.iter().map(|name| name.text().as_str()).collect();
+        format!("module_{}", names.join("_"))
+    }
+}
+
+/// The variable holding the Lua table in which the item `qname` is defined.
+fn parent_table_variable(qname: &QualifiedName) -> String {
+    let names = qname.names();
+    table_variable(&names[1..names.len() - 1])
+}
+
+fn method_path(qname: &QualifiedName, method: &Method) -> String {
+    format!("{}::{}", qname.colon_colon(), method.name())
+}
+
+/// Maps types to the owned Rust types that `mlua` converts from and to Lua values.
+/// Resources are handled separately and only at the top level.
+impl TypeMapper for RustCodeGenerator<'_> {
+    type Output = String;
+
+    fn map_map(&mut self, ty: &Ty, key: String, value: String, repr: &MapSetRepr) -> anyhow::Result<String> {
+        match repr {
+            MapSetRepr::Hash => Ok(format!("std::collections::HashMap<{key}, {value}>")),
+            MapSetRepr::BTree => Ok(format!("std::collections::BTreeMap<{key}, {value}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_set(&mut self, ty: &Ty, element: String, repr: &MapSetRepr) -> anyhow::Result<String> {
+        match repr {
+            MapSetRepr::Hash => Ok(format!("std::collections::HashSet<{element}>")),
+            MapSetRepr::BTree => Ok(format!("std::collections::BTreeSet<{element}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_vec(&mut self, ty: &Ty, element: String, repr: &VecRepr) -> anyhow::Result<String> {
+        match repr {
+            VecRepr::Vec | VecRepr::SliceRef | VecRepr::ImplIntoIterator => Ok(format!("Vec<{element}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_string(&mut self, ty: &Ty, repr: &StringRepr) -> anyhow::Result<String> {
+        match repr {
+            StringRepr::String | StringRepr::StrRef => Ok("String".to_string()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_option(&mut self, ty: &Ty, element: String, repr: &OptionRepr) -> anyhow::Result<String> {
+        match repr {
+            OptionRepr::Option => Ok(format!("Option<{element}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_tuple(&mut self, ty: &Ty, elements: Vec<String>, _repr: &TupleRepr) -> anyhow::Result<String> {
+        // Lua has no tuples, but `()` is fine as a return type.
+        if elements.is_empty() {
+            Ok("()".to_string())
+        } else {
+            type_mapper::unsupported(ty)
+        }
+    }
+
+    fn map_scalar(&mut self, ty: &Ty, scalar: &Scalar) -> anyhow::Result<String> {
+        match scalar {
+            Scalar::Char => type_mapper::unsupported(ty),
+            _ => Ok(scalar.to_string()),
+        }
+    }
+}
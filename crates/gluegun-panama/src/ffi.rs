@@ -0,0 +1,106 @@
+use gluegun_core::idl::{FunctionInput, FunctionOutput, Idl, QualifiedName, Scalar, StringRepr, Ty, TypeKind};
+
+/// How a value crosses the C ABI boundary between Java (via the FFM API) and Rust.
+pub(crate) enum FfiTy {
+    /// A primitive, passed by value.
+    Scalar(Scalar),
+
+    /// A UTF-8 string. Inputs are passed as a pointer and a length;
+    /// outputs are returned as a pointer to a Rust-allocated buffer whose length is written
+    /// to an out-parameter (the buffer must be released with the free function, see [`free_bytes_symbol`][]).
+    String,
+
+    /// No value (return types only).
+    Unit,
+}
+
+impl FfiTy {
+    pub(crate) fn for_input(input: &FunctionInput) -> anyhow::Result<Self> {
+        // References are fine: the Rust side borrows from the argument for the duration of the call.
+        Self::for_ty(input.refd_ty().ty())
+    }
+
+    /// The type of the value returned on success. Every function takes two extra out-parameters,
+    /// `err_ptr: *mut *mut u8` and `err_len: *mut usize`: on error (or panic), the Rust side writes the error's
+    /// message to them (as a buffer to be released like returned strings) and returns a placeholder.
+    /// `err_ptr` is left null on success.
+    pub(crate) fn for_output(output: &FunctionOutput) -> anyhow::Result<Self> {
+        if output.is_unit() {
+            return Ok(FfiTy::Unit);
+        }
+        Self::for_ty(output.main_ty().ty())
+    }
+
+    fn for_ty(ty: &Ty) -> anyhow::Result<Self> {
+        match ty.kind() {
+            TypeKind::Scalar(Scalar::Char) => unsupported(ty),
+            TypeKind::Scalar(scalar) => Ok(FfiTy::Scalar(*scalar)),
+            TypeKind::String {
+                repr: StringRepr::String | StringRepr::StrRef,
+            } => Ok(FfiTy::String),
+            _ => unsupported(ty),
+        }
+    }
+}
+
+fn unsupported<T>(ty: &Ty) -> anyhow::Result<T> {
+    anyhow::bail!("{}: type `{ty}` is not yet supported by the panama backend", ty.span())
+}
+
+/// Rust type used for a scalar in the `extern "C"` signature.
+pub(crate) fn scalar_rust_ty(scalar: &Scalar) -> anyhow::Result<&'static str> {
+    Ok(match scalar {
+        Scalar::Boolean => "bool",
+        Scalar::I8 => "i8",
+        Scalar::I16 => "i16",
+        Scalar::I32 => "i32",
+        Scalar::I64 => "i64",
+        Scalar::U8 => "u8",
+        Scalar::U16 => "u16",
+        Scalar::U32 => "u32",
+        Scalar::U64 => "u64",
+        Scalar::F32 => "f32",
+        Scalar::F64 => "f64",
+        _ => anyhow::bail!("unsupported scalar type: `{scalar}`"),
+    })
+}
+
+/// Java type used for a scalar. Java has no unsigned types, so unsigned values
+/// are reinterpreted as the signed type of the same width.
+pub(crate) fn scalar_java_ty(scalar: &Scalar) -> anyhow::Result<&'static str> {
+    Ok(match scalar {
+        Scalar::Boolean => "boolean",
+        Scalar::I8 | Scalar::U8 => "byte",
+        Scalar::I16 | Scalar::U16 => "short",
+        Scalar::I32 | Scalar::U32 => "int",
+        Scalar::I64 | Scalar::U64 => "long",
+        Scalar::F32 => "float",
+        Scalar::F64 => "double",
+        _ => anyhow::bail!("unsupported scalar type: `{scalar}`"),
+    })
+}
+
+/// `java.lang.foreign.ValueLayout` constant describing a scalar.
+pub(crate) fn scalar_layout(scalar: &Scalar) -> anyhow::Result<&'static str> {
+    Ok(match scalar {
+        Scalar::Boolean => "JAVA_BOOLEAN",
+        Scalar::I8 | Scalar::U8 => "JAVA_BYTE",
+        Scalar::I16 | Scalar::U16 => "JAVA_SHORT",
+        Scalar::I32 | Scalar::U32 => "JAVA_INT",
+        Scalar::I64 | Scalar::U64 => "JAVA_LONG",
+        Scalar::F32 => "JAVA_FLOAT",
+        Scalar::F64 => "JAVA_DOUBLE",
+        _ => anyhow::bail!("unsupported scalar type: `{scalar}`"),
+    })
+}
+
+/// Name of the exported C symbol for the function `qname`, e.g. `gluegun__my_crate__module__function`.
+pub(crate) fn function_symbol(qname: &QualifiedName) -> String {
+    format!("gluegun__{}", qname.to_string("__"))
+}
+
+/// Name of the exported C symbol that releases a buffer returned from Rust.
+/// It includes the crate name so that several generated libraries can be loaded into the same JVM.
+pub(crate) fn free_bytes_symbol(idl: &Idl) -> String {
+    format!("gluegun__{}__free_bytes", idl.crate_name())
+}
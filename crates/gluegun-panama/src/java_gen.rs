@@ -0,0 +1,265 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use gluegun_core::{
+    codegen::{CodeWriter, DirBuilder},
+    idl::{Function, FunctionInput, Idl, Item, ModulePath, QualifiedName},
+};
+
+use crate::ffi::{self, FfiTy};
+
+/// Generates one `Functions` class per module whose static methods
+/// invoke the exported Rust functions through the Foreign Function & Memory API (Java 22+).
+pub(crate) struct JavaCodeGenerator<'idl> {
+    idl: &'idl Idl,
+
+    /// Name of the native library to load (e.g., `my_crate_panama` for `libmy_crate_panama.so`).
+    library_name: String,
+}
+
+impl<'idl> JavaCodeGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl, library_name: String) -> Self {
+        Self { idl, library_name }
+    }
+
+    pub(crate) fn generate(mut self, mut dir: DirBuilder<'_>) -> anyhow::Result<()> {
+        let mut functions: BTreeMap<ModulePath, Vec<(&'idl QualifiedName, &'idl Function)>> = Default::default();
+
+        for (qname, item) in self.idl.definitions() {
+            match item {
+                Item::Function(function) => {
+                    functions.entry(qname.module_path()).or_default().push((qname, function));
+                }
+                _ => anyhow::bail!(
+                    "`{}`: only functions are currently supported by the panama backend",
+                    qname.colon_colon()
+                ),
+            }
+        }
+
+        for (module_path, functions) in &functions {
+            self.generate_functions(&mut dir, module_path, functions)?;
+        }
+
+        Ok(())
+    }
+
+    /// Generate the `Functions` class for `module_path`, e.g.
+    ///
+    /// ```java
+    /// package myCrate;
+    ///
+    /// public final class Functions {
+    ///     private static final MethodHandle greet = ...;
+    ///     public static String greet(String name) { ... }
+    /// }
+    /// ```
+    fn generate_functions(
+        &mut self,
+        dir: &mut DirBuilder<'_>,
+        module_path: &ModulePath,
+        functions: &[(&QualifiedName, &Function)],
+    ) -> anyhow::Result<()> {
+        let package = module_path.camel_case();
+        let mut path = PathBuf::new();
+        for name in package.names() {
+            path.push(name.text());
+        }
+        path.push("Functions.java");
+
+        let mut file = dir.add_file(path)?;
+        write!(file, "package {};", package.dotted())?;
+        write!(file, "")?;
+        write!(file, "import java.lang.foreign.*;")?;
+        write!(file, "import java.lang.invoke.MethodHandle;")?;
+        write!(file, "import java.nio.charset.StandardCharsets;")?;
+        write!(file, "")?;
+        write!(file, "public final class Functions {{")?;
+        write!(file, "private Functions() {{}}")?;
+        write!(file, "")?;
+        write!(file, "private static final Linker LINKER = Linker.nativeLinker();")?;
+        write!(file, "private static final SymbolLookup SYMBOLS;")?;
+        write!(file, "static {{")?;
+        write!(file, "System.loadLibrary(\"{}\");", self.library_name)?;
+        write!(file, "SYMBOLS = SymbolLookup.loaderLookup();")?;
+        write!(file, "}}")?;
+        write!(file, "")?;
+        write!(
+            file,
+            "private static final MethodHandle FREE_BYTES = {};",
+            downcall_handle(
+                &ffi::free_bytes_symbol(self.idl),
+                "FunctionDescriptor.ofVoid(ValueLayout.ADDRESS, ValueLayout.JAVA_LONG)"
+            )
+        )?;
+
+        write!(file, "")?;
+        self.generate_check_error(&mut file)?;
+
+        for &(qname, function) in functions {
+            write!(file, "")?;
+            self.generate_function(&mut file, qname, function)?;
+        }
+
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    /// Generate the helper that throws the error (or panic) reported by a function, if any,
+    /// as a `RuntimeException` carrying the error's message.
+    fn generate_check_error(&mut self, file: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(file, "private static void checkError(MemorySegment errPtr, MemorySegment errLen) throws Throwable {{")?;
+        write!(file, "MemorySegment ptr = errPtr.get(ValueLayout.ADDRESS, 0);")?;
+        write!(file, "if (ptr.address() == 0) {{")?;
+        write!(file, "return;")?;
+        write!(file, "}}")?;
+        write!(file, "long len = errLen.get(ValueLayout.JAVA_LONG, 0);")?;
+        write!(
+            file,
+            "String message = new String(ptr.reinterpret(len).toArray(ValueLayout.JAVA_BYTE), StandardCharsets.UTF_8);"
+        )?;
+        write!(file, "FREE_BYTES.invokeExact(ptr, len);")?;
+        write!(file, "throw new RuntimeException(message);")?;
+        write!(file, "}}")?;
+        Ok(())
+    }
+
+    fn generate_function(
+        &mut self,
+        file: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        function: &Function,
+    ) -> anyhow::Result<()> {
+        let signature = function.signature();
+        let inputs = signature
+            .inputs()
+            .iter()
+            .map(|input| Ok((input, FfiTy::for_input(input)?)))
+            .collect::<anyhow::Result<Vec<(&FunctionInput, FfiTy)>>>()?;
+        let output = FfiTy::for_output(signature.output_ty())?;
+        let handle = function.name().to_string().to_uppercase();
+
+        // The method handle describing the C signature of the exported function.
+        let mut layouts = vec![];
+        for (_, ffi_ty) in &inputs {
+            match ffi_ty {
+                FfiTy::Scalar(scalar) => layouts.push(format!("ValueLayout.{}", ffi::scalar_layout(scalar)?)),
+                FfiTy::String => {
+                    layouts.push("ValueLayout.ADDRESS".to_string());
+                    layouts.push("ValueLayout.JAVA_LONG".to_string());
+                }
+                FfiTy::Unit => unreachable!("unit inputs"),
+            }
+        }
+        if let FfiTy::String = output {
+            layouts.push("ValueLayout.ADDRESS".to_string());
+        }
+        layouts.push("ValueLayout.ADDRESS".to_string());
+        layouts.push("ValueLayout.ADDRESS".to_string());
+        let descriptor = match &output {
+            FfiTy::Scalar(scalar) => {
+                layouts.insert(0, format!("ValueLayout.{}", ffi::scalar_layout(scalar)?));
+                format!("FunctionDescriptor.of({})", layouts.join(", "))
+            }
+            FfiTy::String => {
+                layouts.insert(0, "ValueLayout.ADDRESS".to_string());
+                format!("FunctionDescriptor.of({})", layouts.join(", "))
+            }
+            FfiTy::Unit => format!("FunctionDescriptor.ofVoid({})", layouts.join(", ")),
+        };
+        write!(
+            file,
+            "private static final MethodHandle {handle} = {};",
+            downcall_handle(&ffi::function_symbol(qname), &descriptor)
+        )?;
+        write!(file, "")?;
+
+        // The public wrapper method.
+        let java_output = match &output {
+            FfiTy::Scalar(scalar) => ffi::scalar_java_ty(scalar)?,
+            FfiTy::String => "String",
+            FfiTy::Unit => "void",
+        };
+        write!(file, "public static {java_output} {}(", function.name().camel_case())?;
+        for (index, (input, ffi_ty)) in inputs.iter().enumerate() {
+            let comma = if index + 1 < inputs.len() { "," } else { "" };
+            let java_ty = match ffi_ty {
+                FfiTy::Scalar(scalar) => ffi::scalar_java_ty(scalar)?,
+                FfiTy::String => "String",
+                FfiTy::Unit => unreachable!("unit inputs"),
+            };
+            write!(file, "{java_ty} {}{comma}", input.name().camel_case())?;
+        }
+        write!(file, ") {{")?;
+
+        write!(file, "try (var arena = Arena.ofConfined()) {{")?;
+
+        // Strings are copied into native memory as (NUL-terminated) UTF-8;
+        // the length passed to Rust excludes the terminator.
+        let mut arguments = vec![];
+        for (input, ffi_ty) in &inputs {
+            let name = input.name().camel_case();
+            match ffi_ty {
+                FfiTy::Scalar(_) => arguments.push(name.to_string()),
+                FfiTy::String => {
+                    write!(file, "MemorySegment {name}Segment = arena.allocateFrom({name});")?;
+                    arguments.push(format!("{name}Segment"));
+                    arguments.push(format!("{name}Segment.byteSize() - 1"));
+                }
+                FfiTy::Unit => unreachable!("unit inputs"),
+            }
+        }
+        if let FfiTy::String = output {
+            write!(file, "MemorySegment outLen = arena.allocate(ValueLayout.JAVA_LONG);")?;
+            arguments.push("outLen".to_string());
+        }
+        // Memory allocated by the arena is zeroed, so `errPtr` starts out null.
+        // Every function can report an error, as panics are reported like errors.
+        write!(file, "MemorySegment errPtr = arena.allocate(ValueLayout.ADDRESS);")?;
+        write!(file, "MemorySegment errLen = arena.allocate(ValueLayout.JAVA_LONG);")?;
+        arguments.push("errPtr".to_string());
+        arguments.push("errLen".to_string());
+        let check_error = |file: &mut CodeWriter<'_>| -> anyhow::Result<()> {
+            write!(file, "checkError(errPtr, errLen);")?;
+            Ok(())
+        };
+
+        let call = format!("{handle}.invokeExact({})", arguments.join(", "));
+        match &output {
+            FfiTy::Scalar(_) => {
+                write!(file, "{java_output} result = ({java_output}) {call};")?;
+                check_error(file)?;
+                write!(file, "return result;")?;
+            }
+            FfiTy::Unit => {
+                write!(file, "{call};")?;
+                check_error(file)?;
+            }
+            FfiTy::String => {
+                write!(file, "MemorySegment ptr = (MemorySegment) {call};")?;
+                check_error(file)?;
+                write!(file, "long len = outLen.get(ValueLayout.JAVA_LONG, 0);")?;
+                write!(file, "try {{")?;
+                write!(
+                    file,
+                    "return new String(ptr.reinterpret(len).toArray(ValueLayout.JAVA_BYTE), StandardCharsets.UTF_8);"
+                )?;
+                write!(file, "}} finally {{")?;
+                write!(file, "FREE_BYTES.invokeExact(ptr, len);")?;
+                write!(file, "}}")?;
+            }
+        }
+
+        write!(file, "}} catch (RuntimeException | Error e) {{")?;
+        write!(file, "throw e;")?;
+        write!(file, "}} catch (Throwable e) {{")?;
+        write!(file, "throw new RuntimeException(e);")?;
+        write!(file, "}}")?;
+        write!(file, "}}")?;
+
+        Ok(())
+    }
+}
+
+fn downcall_handle(symbol: &str, descriptor: &str) -> String {
+    format!("LINKER.downcallHandle(SYMBOLS.find(\"{symbol}\").orElseThrow(), {descriptor})")
+}
@@ -0,0 +1,48 @@
+use anyhow::Context;
+use gluegun_core::{
+    cli::{GenerateCx, GlueGunHelper},
+    codegen::LibraryCrate,
+    naming::{Case, Naming},
+};
+
+mod ffi;
+mod java_gen;
+mod rs_gen;
+
+pub fn main() -> anyhow::Result<()> {
+    gluegun_core::cli::run(GlueGunPanama)
+}
+
+/// Generates Java bindings that use the Foreign Function & Memory API ("Project Panama", Java 22+)
+/// rather than JNI: the Rust crate exports plain `extern "C"` functions which are invoked
+/// through `MethodHandle`s.
+#[derive(Clone)]
+struct GlueGunPanama;
+
+impl GlueGunHelper for GlueGunPanama {
+    type Metadata = ();
+
+    fn name(&self) -> String {
+        "panama".to_string()
+    }
+
+    fn naming(&self, _metadata: &()) -> Naming {
+        Naming::new().modules(Case::Camel).functions(Case::Camel).parameters(Case::Camel)
+    }
+
+    fn generate(self, cx: &mut GenerateCx, _metadata: &(), output: &mut LibraryCrate) -> anyhow::Result<()> {
+        // The generated crate is a `cdylib` (the default) whose file name is derived from the crate name.
+        let library_name = output.crate_name().replace('-', "_");
+
+        let java_src_dir = output.add_dir("java_src").context("adding `java_src` dir")?;
+        java_gen::JavaCodeGenerator::new(cx.idl(), library_name)
+            .generate(java_src_dir)
+            .context("generating Java sources")?;
+
+        rs_gen::RustCodeGenerator::new(cx.idl())
+            .generate(output)
+            .context("generating Rust sources")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use gluegun_core::{
+    codegen::{CodeWriter, LibraryCrate},
+    idl::{Function, Idl, Item, QualifiedName, RefdTy},
+};
+
+use crate::ffi::{self, FfiTy};
+
+/// Generates `extern "C"` wrappers around each function, to be invoked
+/// from Java through downcall handles.
+pub(crate) struct RustCodeGenerator<'idl> {
+    idl: &'idl Idl,
+}
+
+impl<'idl> RustCodeGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl) -> Self {
+        Self { idl }
+    }
+
+    pub(crate) fn generate(mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        self.generate_lib_rs(lib)
+    }
+
+    fn generate_lib_rs(&mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        let mut lib_rs = lib.add_file("src/lib.rs")?;
+
+        self.generate_support(&mut lib_rs)?;
+
+        for (qname, item) in self.idl.definitions() {
+            match item {
+                Item::Function(function) => lib_rs.with_span(function.span(), |lib_rs| self.generate_function(lib_rs, qname, function))?,
+                _ => anyhow::bail!(
+                    "`{}`: only functions are currently supported by the panama backend",
+                    qname.colon_colon()
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generate the helpers for returning strings (including error messages): they are returned
+    /// as a leaked `Box<[u8]>` whose length is written to an out-parameter, and Java frees them once copied.
+    /// `gluegun_catch_panic` reports a panic like an error, as unwinding out of an `extern "C"` function aborts the process.
+    fn generate_support(&mut self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "fn gluegun_string_out(value: String, out_len: *mut usize) -> *mut u8 {{")?;
+        write!(lib_rs, "let bytes = value.into_bytes().into_boxed_slice();")?;
+        write!(lib_rs, "unsafe {{ *out_len = bytes.len(); }}")?;
+        write!(lib_rs, "Box::into_raw(bytes) as *mut u8")?;
+        write!(lib_rs, "}}")?;
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "/// # Safety")?;
+        write!(lib_rs, "///")?;
+        write!(lib_rs, "/// `ptr` and `len` must have been returned by one of the other functions in this library.")?;
+        write!(lib_rs, "#[unsafe(no_mangle)]")?;
+        write!(
+            lib_rs,
+            "pub unsafe extern \"C\" fn {free}(ptr: *mut u8, len: usize) {{",
+            free = ffi::free_bytes_symbol(self.idl),
+        )?;
+        write!(lib_rs, "drop(unsafe {{ Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)) }});")?;
+        write!(lib_rs, "}}")?;
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "fn gluegun_catch_panic<T>(err_ptr: *mut *mut u8, err_len: *mut usize, f: impl FnOnce() -> T) -> Option<T> {{")?;
+        write!(lib_rs, "let payload = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {{")?;
+        write!(lib_rs, "Ok(value) => return Some(value),")?;
+        write!(lib_rs, "Err(payload) => payload,")?;
+        write!(lib_rs, "}};")?;
+        write!(lib_rs, "let message = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {{")?;
+        write!(lib_rs, "(Some(message), _) => message,")?;
+        write!(lib_rs, "(None, Some(message)) => message.as_str(),")?;
+        write!(lib_rs, "(None, None) => \"(no message)\",")?;
+        write!(lib_rs, "}};")?;
+        write!(lib_rs, "unsafe {{ *err_ptr = gluegun_string_out(format!(\"Rust panicked: {{message}}\"), err_len); }}")?;
+        write!(lib_rs, "None")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    fn generate_function(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        function: &Function,
+    ) -> anyhow::Result<()> {
+        let signature = function.signature();
+        let output = FfiTy::for_output(signature.output_ty())?;
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "/// # Safety")?;
+        write!(lib_rs, "///")?;
+        write!(lib_rs, "/// String arguments must be valid UTF-8 (`ptr`, `len`) pairs.")?;
+        write!(lib_rs, "#[unsafe(no_mangle)]")?;
+        write!(lib_rs, "pub unsafe extern \"C\" fn {}(", ffi::function_symbol(qname))?;
+        for input in signature.inputs() {
+            let name = input.name();
+            match FfiTy::for_input(input)? {
+                FfiTy::Scalar(scalar) => write!(lib_rs, "{name}: {},", ffi::scalar_rust_ty(&scalar)?)?,
+                FfiTy::String => write!(lib_rs, "{name}_ptr: *const u8, {name}_len: usize,")?,
+                FfiTy::Unit => unreachable!("unit inputs"),
+            }
+        }
+        // Errors and panics are reported through the `err_ptr` out-parameter.
+        let fallible = signature.output_ty().error_ty().is_some();
+        let mut out_params = vec![];
+        if let FfiTy::String = output {
+            out_params.push("out_len: *mut usize");
+        }
+        out_params.push("err_ptr: *mut *mut u8");
+        out_params.push("err_len: *mut usize");
+        let out_params = out_params.join(", ");
+        match &output {
+            FfiTy::Scalar(scalar) => write!(lib_rs, "{out_params}) -> {} {{", ffi::scalar_rust_ty(scalar)?)?,
+            FfiTy::String => write!(lib_rs, "{out_params}) -> *mut u8 {{")?,
+            FfiTy::Unit => write!(lib_rs, "{out_params}) {{")?,
+        }
+
+        // Convert string arguments back into `&str`.
+        for input in signature.inputs() {
+            let name = input.name();
+            if let FfiTy::String = FfiTy::for_input(input)? {
+                write!(
+                    lib_rs,
+                    "let {name} = unsafe {{ std::str::from_utf8_unchecked(std::slice::from_raw_parts({name}_ptr, {name}_len)) }};"
+                )?;
+            }
+        }
+
+        let mut arguments = vec![];
+        for input in signature.inputs() {
+            let name = input.name();
+            arguments.push(match (FfiTy::for_input(input)?, input.refd_ty()) {
+                (FfiTy::String, RefdTy::Owned(..)) => format!("{name}.to_string()"),
+                _ => name.to_string(),
+            });
+        }
+        let call = format!(
+            "gluegun_catch_panic(err_ptr, err_len, || {}({}))",
+            qname.colon_colon(),
+            arguments.join(", ")
+        );
+
+        // Unit-returning functions (including `Result<(), E>`) have nothing to return.
+        if let FfiTy::Unit = output {
+            if fallible {
+                write!(lib_rs, "if let Some(Err(err)) = {call} {{")?;
+                write!(lib_rs, "unsafe {{ *err_ptr = gluegun_string_out(err.to_string(), err_len); }}")?;
+                write!(lib_rs, "}}")?;
+            } else {
+                write!(lib_rs, "{call};")?;
+            }
+            write!(lib_rs, "}}")?;
+            return Ok(());
+        }
+
+        // Errors are reported through the `err_ptr` out-parameter, with a placeholder return value;
+        // `gluegun_catch_panic` already reported a panic.
+        let placeholder = match &output {
+            FfiTy::Scalar(_) => "Default::default()",
+            FfiTy::String => "std::ptr::null_mut()",
+            FfiTy::Unit => unreachable!("handled above"),
+        };
+        if fallible {
+            write!(lib_rs, "let result = match {call} {{")?;
+            write!(lib_rs, "Some(Ok(value)) => value,")?;
+            write!(lib_rs, "Some(Err(err)) => {{")?;
+            write!(lib_rs, "unsafe {{ *err_ptr = gluegun_string_out(err.to_string(), err_len); }}")?;
+            write!(lib_rs, "return {placeholder};")?;
+            write!(lib_rs, "}}")?;
+            write!(lib_rs, "None => return {placeholder},")?;
+            write!(lib_rs, "}};")?;
+        } else {
+            write!(lib_rs, "let Some(result) = {call} else {{")?;
+            write!(lib_rs, "return {placeholder};")?;
+            write!(lib_rs, "}};")?;
+        }
+
+        // Borrowed data (e.g., `-> &str`) is copied out into an owned value.
+        if signature.output_ty().requires_copy_out() {
+            write!(lib_rs, "let result = std::borrow::ToOwned::to_owned(result);")?;
+        }
+
+        match output {
+            FfiTy::Scalar(_) => write!(lib_rs, "result")?,
+            FfiTy::String => write!(lib_rs, "gluegun_string_out(result, out_len)")?,
+            FfiTy::Unit => unreachable!("handled above"),
+        }
+        write!(lib_rs, "}}")?;
+
+        Ok(())
+    }
+}
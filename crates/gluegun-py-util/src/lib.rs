@@ -0,0 +1,114 @@
+//! Runtime support for the Python bindings generated by gluegun: conversions and errors
+//! shared by the generated functions, so that they are not repeated at each call site.
+
+use std::{any::Any, fmt::Display, panic::AssertUnwindSafe, str::FromStr};
+
+use pyo3::{
+    exceptions::{PyOSError, PyRuntimeError, PyValueError},
+    types::{PyAnyMethods, PyString, PyStringMethods},
+    Bound, PyAny, PyErr, PyObject, PyResult, Python,
+};
+
+/// A Rust value converted from the Python object passed as an argument, for types that pyo3 does not convert.
+pub trait FromPy: Sized {
+    /// Convert `value`, the argument named `name` (mentioned in errors).
+    fn from_py(name: &str, value: &Bound<'_, PyAny>) -> PyResult<Self>;
+}
+
+/// A Rust value converted into a Python object, for types that pyo3 does not convert.
+pub trait ToPy {
+    fn to_py(self, py: Python<'_>) -> PyResult<PyObject>;
+}
+
+/// A value exchanged through its string form, as a Python `str` (e.g., a socket address).
+pub struct Text<T>(pub T);
+
+impl<T: FromStr> FromPy for Text<T>
+where
+    T::Err: Display,
+{
+    fn from_py(name: &str, value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let text: String = value.extract()?;
+        parse(name, &text).map(Text)
+    }
+}
+
+impl<T: Display> ToPy for Text<T> {
+    fn to_py(self, py: Python<'_>) -> PyResult<PyObject> {
+        Ok(PyString::new(py, &self.0.to_string()).into_any().unbind())
+    }
+}
+
+/// A UUID, exchanged as a Python `uuid.UUID`. Strings are accepted as arguments too.
+pub struct Uuid<T>(pub T);
+
+impl<T: FromStr> FromPy for Uuid<T>
+where
+    T::Err: Display,
+{
+    fn from_py(name: &str, value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        parse(name, value.str()?.to_str()?).map(Uuid)
+    }
+}
+
+impl<T: Display> ToPy for Uuid<T> {
+    fn to_py(self, py: Python<'_>) -> PyResult<PyObject> {
+        let uuid = py.import("uuid")?.getattr("UUID")?.call1((self.0.to_string(),))?;
+        Ok(uuid.unbind())
+    }
+}
+
+fn parse<T: FromStr>(name: &str, text: &str) -> PyResult<T>
+where
+    T::Err: Display,
+{
+    text.parse().map_err(|err| PyValueError::new_err(format!("invalid `{name}`: {err}")))
+}
+
+/// The exception raised for an error returned by a Rust function: a `RuntimeError` carrying its message.
+pub fn runtime_error(err: impl Display) -> PyErr {
+    PyRuntimeError::new_err(err.to_string())
+}
+
+/// The exception raised when the argument `name` is rejected by the `TryFrom` impl of its newtype
+/// (see `#[gluegun::marshal_as]`): a `ValueError` carrying the error's message.
+pub fn invalid_value(name: &str, err: impl Display) -> PyErr {
+    PyValueError::new_err(format!("invalid `{name}`: {err}"))
+}
+
+/// The exception raised for an `std::io::Error` returned by a Rust function: an `OSError`, with `errno` set
+/// if the error has an OS error code, so that Python picks the subclass (e.g., `FileNotFoundError`) matching it.
+/// Other errors are raised as the subclass matching their kind.
+pub fn io_error(err: std::io::Error) -> PyErr {
+    match err.raw_os_error() {
+        Some(errno) => PyOSError::new_err((errno, err.to_string())),
+        None => err.into(),
+    }
+}
+
+/// The exception raised when an object of the class `class` is used after a method took ownership of its value.
+pub fn consumed_error(class: &str) -> PyErr {
+    PyValueError::new_err(format!("`{class}` was consumed by a method that takes ownership of it"))
+}
+
+/// The exception raised when a method needs the only reference to the value of an object of the class `class`
+/// (taking `&mut self` or `self`), but Rust code still holds a clone of it from a method taking `self: Arc<Self>`.
+pub fn shared_error(class: &str) -> PyErr {
+    PyValueError::new_err(format!("`{class}` is still shared with Rust code, so it cannot be modified or consumed"))
+}
+
+/// Call `f`, which calls a Rust function, raising a panic as a `RuntimeError` carrying the panic's message.
+/// pyo3 would otherwise raise its `PanicException`, which derives from `BaseException`, so that `except Exception` misses it.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> PyResult<T> {
+    std::panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| PyRuntimeError::new_err(panic_message(&*payload)))
+}
+
+/// The message of the exception raised for a panic with the payload `payload`, e.g. `Rust panicked: index out of bounds`.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message,
+        (None, Some(message)) => message.as_str(),
+        (None, None) => "(no message)",
+    };
+    format!("Rust panicked: {message}")
+}
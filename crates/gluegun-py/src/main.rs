@@ -1,37 +1,107 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
 use gluegun_core::{
     cli::{GenerateCx, GlueGunHelper},
-    codegen::LibraryCrate,
+    codegen::{AddDependency, Feature, LibraryCrate, RequiredTools},
+    naming::{Naming, OverloadPolicy},
 };
+use metadata::PythonMetadata;
 use rs_gen::RustCodeGenerator;
 
 pub fn main() -> anyhow::Result<()> {
     gluegun_core::cli::run(GlueGunPython)
 }
 
+mod metadata;
+mod py_gen;
+mod pyproject_gen;
 mod rs_gen;
+mod usage_gen;
 
+#[derive(Clone)]
 struct GlueGunPython;
 
 impl GlueGunHelper for GlueGunPython {
-    type Metadata = ();
+    type Metadata = PythonMetadata;
+
+    const SUPPORTS_TARGET_GATES: bool = true;
+
+    const SUPPORTS_DEBUG_BINDINGS: bool = true;
+
+    const SUPPORTS_PACK_PARAMS: bool = true;
 
     fn name(&self) -> String {
         format!("py")
     }
 
+    fn naming(&self, metadata: &PythonMetadata) -> Naming {
+        Naming::new()
+            .functions(metadata.rename_all)
+            .fields(metadata.rename_all)
+            .overloads(metadata.overloads)
+    }
+
     fn generate(
         self,
         cx: &mut GenerateCx,
-        _metadata: &Self::Metadata,
+        metadata: &Self::Metadata,
         output: &mut LibraryCrate,
     ) -> anyhow::Result<()> {
-        let features = RustCodeGenerator::new(cx.idl()).generate(output)?;
+        if metadata.overloads == OverloadPolicy::Overload {
+            anyhow::bail!("Python has no overloading: `overloads` must be `suffix` or `separate`");
+        }
+        if let Some(module) = &metadata.module {
+            let is_identifier = |name: &str| {
+                name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_')
+                    && name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+            };
+            if !module.split('.').all(is_identifier) {
+                anyhow::bail!("`module` must be a Python module name like `mylib`, not `{module}`");
+            }
+        }
+
+        let debug_bindings = output.debug_bindings_enabled();
+        let features = RustCodeGenerator::new(cx.idl(), cx.type_overrides(), cx.target_gates(), metadata, debug_bindings)
+            .generate(output)?;
 
-        let mut dep = output.add_dependency("pyo3").version("0.23");
-        for feature in features {
-            dep = dep.feature(feature);
+        let python_dir = output.add_dir("python").context("adding `python` dir")?;
+        py_gen::PythonCodeGenerator::new(cx.idl(), cx.type_overrides(), cx.target_gates(), metadata, debug_bindings).generate(python_dir)?;
+        pyproject_gen::PyprojectGenerator::new(cx.idl(), metadata).generate(output)?;
+
+        output
+            .add_dependency("pyo3")
+            .version("0.23")
+            .feature_with(Feature::IndexMap, "indexmap")
+            .feature_with(Feature::AsyncRuntime, "experimental-async");
+        self.add_gluegun_py_util(output);
+        for feature in &features {
+            output.require_feature(feature);
         }
 
+        usage_gen::UsageGenerator::new(cx.idl(), metadata).generate(output);
+
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// The Python package is built with `maturin`, which needs a Python interpreter.
+    fn required_tools(&self, _metadata: &PythonMetadata, tools: &mut RequiredTools) {
+        tools
+            .require_helper_command("python3")
+            .or_fail("install Python 3, e.g. from https://www.python.org/downloads".to_string());
+        tools
+            .require_helper_command("maturin")
+            .or_fail("install it with `pip install maturin`".to_string());
+    }
+}
+
+impl GlueGunPython {
+    /// The generated crate uses `gluegun-py-util` from crates.io,
+    /// unless we are running from the gluegun workspace (e.g., in tests).
+    fn add_gluegun_py_util<'lib>(&self, lib: &'lib mut LibraryCrate) -> AddDependency<'lib> {
+        let mut local_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        local_path.pop();
+        local_path.push("gluegun-py-util");
+        lib.add_dependency_published_or_path("gluegun-py-util", "0.1", local_path)
+    }
+}
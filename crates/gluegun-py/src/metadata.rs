@@ -0,0 +1,102 @@
+use gluegun_core::{
+    cli::{DescribeMetadata, MetadataKind, MetadataSchema},
+    idl::{Idl, Method, ModulePath, Name, QualifiedName},
+    naming::{Case, OverloadPolicy},
+};
+use serde::Deserialize;
+
+/// Configuration for the Python helper, read from `[package.metadata.gluegun.py]`
+/// (or the `workspace` equivalent).
+#[derive(Deserialize, Debug)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct PythonMetadata {
+    /// How IP addresses and UUIDs are represented in Python.
+    pub textual_types: TextualTypes,
+
+    /// The case of the names of fields and methods in Python.
+    pub rename_all: Case,
+
+    /// How the methods of a group of overloads are named in Python, which has no overloading.
+    pub overloads: OverloadPolicy,
+
+    /// If set, the name of the Python module of the crate (e.g., `mylib`), which otherwise is the crate name.
+    /// The modules of its submodules are nested in it.
+    pub module: Option<String>,
+}
+
+impl PythonMetadata {
+    /// The Python name of a field, method, or function, in the case selected by `rename-all`.
+    pub fn member_name(&self, name: &Name) -> Name {
+        self.rename_all.apply(name)
+    }
+
+    /// The Python name of a method, which starts with the name of its group of overloads (if any)
+    /// with the `suffix` policy.
+    pub fn method_name(&self, method: &Method) -> Name {
+        self.member_name(&self.overloads.method_name(method))
+    }
+
+    /// The Python module of the Rust module `module_path` (e.g., `my_crate.shapes` for `my_crate::shapes`),
+    /// which is nested in the one named by `module`, if set, rather than in the crate name.
+    pub fn python_module(&self, idl: &Idl, module_path: &ModulePath) -> ModulePath {
+        match &self.module {
+            Some(module) => module_path.with_crate_root(idl.crate_name(), module),
+            None => module_path.clone(),
+        }
+    }
+
+    /// The dotted Python name of the class or function `qname` (e.g., `my_crate.shapes.Circle`).
+    pub fn python_name(&self, idl: &Idl, qname: &QualifiedName) -> String {
+        self.python_module(idl, &qname.module_path()).join(qname.tail_name()).dotted()
+    }
+}
+
+impl Default for PythonMetadata {
+    fn default() -> Self {
+        Self {
+            textual_types: Default::default(),
+            rename_all: Case::Snake,
+            overloads: OverloadPolicy::Suffix,
+            module: None,
+        }
+    }
+}
+
+impl DescribeMetadata for PythonMetadata {
+    fn describe() -> MetadataSchema {
+        MetadataSchema::new()
+            .option(
+                "textual-types",
+                MetadataKind::one_of(["native", "string"]),
+                "How IP addresses and UUIDs are represented: as `ipaddress` and `uuid.UUID` objects, or as strings",
+            )
+            .option(
+                "rename-all",
+                MetadataKind::one_of(Case::METADATA_NAMES),
+                "The case of field, method, and function names, as in `#[serde(rename_all)]`; defaults to `snake_case`",
+            )
+            .option(
+                "overloads",
+                MetadataKind::one_of(["suffix", "separate"]),
+                "How the methods of a group of overloads (`#[gluegun::overload(name)]`) are named; defaults to `suffix`",
+            )
+            .option(
+                "module",
+                MetadataKind::String,
+                "The Python module of the crate (e.g., `mylib`), under which its modules are nested; defaults to the crate name",
+            )
+    }
+}
+
+/// Python representation of the types exchanged as strings (IP and socket addresses, UUIDs).
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum TextualTypes {
+    /// IP addresses map to `ipaddress.IPv4Address` and `ipaddress.IPv6Address`, and UUIDs to `uuid.UUID`.
+    /// Socket addresses are strings (e.g., `"127.0.0.1:8080"`), as Python has no class for them.
+    #[default]
+    Native,
+
+    /// All of them map to `str`, in their usual string form.
+    String,
+}
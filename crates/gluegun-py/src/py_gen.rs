@@ -0,0 +1,735 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
+
+use gluegun_core::{
+    cli::{TargetGates, TypeOverrides},
+    codegen::{CodeWriter, DirBuilder},
+    idl::{
+        DocExample, Enum, Function, FutureRepr, Idl, Interface, IsAsync, Item, MapSetRepr, ModulePath, Name, NetAddrRepr, Newtype, OptionRepr, PathRepr, QualifiedName, Record, ResultRepr, Scalar, StreamRepr, StringRepr,
+        TraitObjectRepr, TupleRepr, Ty, TypeKind, UuidRepr, Variant, VariantArm, VecRepr,
+    },
+    type_mapper::{self, TypeMapper},
+};
+
+use crate::metadata::{PythonMetadata, TextualTypes};
+
+/// Generates the Python package (under `python/`), which wraps the native module (`_native`, see `rs_gen`) in pure Python:
+/// it re-exports the class of each resource, defines an `enum.Enum` for each C-like enum, and a function with annotations
+/// and a docstring calling the native one for each function. It also defines a class for each Rust enum with associated data,
+/// a frozen dataclass for each record (a unit struct becomes one without fields, whose instances are all equal),
+/// a [`typing.NewType`](https://docs.python.org/3/library/typing.html#newtype) for each newtype,
+/// and an abstract base class for each interface (trait), along with pytest stubs (`tests/test_examples.py`) for the code examples in doc comments.
+/// Enums implementing `std::error::Error` (see [`Idl::error_item`]) become exception classes instead, raised by the functions failing with them.
+///
+/// Each enum becomes a base class and each of its arms a frozen dataclass deriving from it,
+/// reachable as an attribute of the base class (e.g., `Shape.Circle`). Dataclasses define
+/// `__match_args__`, so Python code can `match` on values returned from Rust:
+///
+/// ```python
+/// match shape:
+///     case Shape.Circle(radius):
+///         ...
+///     case Shape.Rect(width, height):
+///         ...
+/// ```
+pub(crate) struct PythonCodeGenerator<'idl> {
+    idl: &'idl Idl,
+    type_overrides: &'idl TypeOverrides,
+    target_gates: &'idl TargetGates,
+    metadata: &'idl PythonMetadata,
+    /// Whether to generate `_gluegun_debug.py` (see `generate_debug_bindings`).
+    debug_bindings: bool,
+}
+
+impl<'idl> PythonCodeGenerator<'idl> {
+    pub(crate) fn new(
+        idl: &'idl Idl,
+        type_overrides: &'idl TypeOverrides,
+        target_gates: &'idl TargetGates,
+        metadata: &'idl PythonMetadata,
+        debug_bindings: bool,
+    ) -> Self {
+        Self { idl, type_overrides, target_gates, metadata, debug_bindings }
+    }
+
+    fn type_mapper(&self) -> PythonTypeMapper<'idl> {
+        PythonTypeMapper { idl: self.idl, type_overrides: self.type_overrides, metadata: self.metadata }
+    }
+
+    pub(crate) fn generate(self, mut dir: DirBuilder<'_>) -> anyhow::Result<()> {
+        // Group the items by module; each module becomes a Python package. The package of the crate
+        // is generated even if empty, as it loads the native module.
+        let root = ModulePath::of_crate(self.idl.crate_name());
+        let mut modules: BTreeMap<ModulePath, Vec<(&QualifiedName, &Item)>> = BTreeMap::new();
+        modules.entry(root.clone()).or_default();
+        for (qname, item) in self.idl.definitions() {
+            modules.entry(qname.module_path()).or_default().push((qname, item));
+        }
+
+        for (module_path, items) in &modules {
+            let mut file = dir.add_file(package_file_name(&self.metadata.python_module(self.idl, module_path)))?;
+            write!(file, "from __future__ import annotations")?;
+            write!(file, "")?;
+            if items.iter().any(|(_, item)| matches!(item, Item::Interface(_))) {
+                write!(file, "import abc")?;
+            }
+            write!(file, "import dataclasses")?;
+            if items.iter().any(|(_, item)| is_python_enum(item)) {
+                write!(file, "import enum")?;
+            }
+            write!(file, "import typing")?;
+            // The native module comes first, as the packages imported below import it from this one.
+            write!(file, "from {} import _native", self.root_package().dotted())?;
+            if *module_path == root {
+                write!(file, "from {} import _interface_hash", self.root_package().dotted())?;
+            }
+            // `NewType`s are evaluated, so the classes they refer to have to be imported;
+            // so are the modules of the classes registered with the interfaces, and of the enums functions convert.
+            for module in self.newtype_imports(items) {
+                write!(file, "import {module}")?;
+            }
+            for module in self.implementation_imports(module_path, items) {
+                write!(file, "import {module}")?;
+            }
+            for module in self.enum_imports(module_path, items) {
+                write!(file, "import {module}")?;
+            }
+            if *module_path == root {
+                write!(file, "")?;
+                write!(file, "_interface_hash.check(_native)")?;
+            }
+
+            // The classes of the native module come first, so that the interfaces can register them.
+            let (native, defined): (Vec<_>, Vec<_>) =
+                items.iter().partition(|(_, item)| matches!(item, Item::Resource(_)) || is_python_enum(item));
+            for (qname, item) in native.into_iter().chain(defined) {
+                file.with_span(item.span(), |file| match item {
+                    Item::Resource(_) => self.generate_resource(file, qname),
+                    Item::Enum(an_enum) if *an_enum.implements_error() => self.generate_error_enum(file, qname, an_enum),
+                    Item::Enum(an_enum) => self.generate_enum(file, qname, an_enum),
+                    Item::Record(record) => self.generate_record(file, qname, record),
+                    Item::Variant(variant) => self.generate_variant(file, qname, variant),
+                    Item::Newtype(newtype) => self.generate_newtype(file, qname, newtype),
+                    Item::Interface(interface) => self.generate_interface(file, qname, interface),
+                    Item::Function(function) => self.generate_function(file, qname, function),
+                    _ => Ok(()),
+                })?;
+            }
+        }
+
+        self.generate_interface_hash(&mut dir)?;
+        if self.debug_bindings {
+            self.generate_debug_bindings(&mut dir)?;
+        }
+        self.generate_example_tests(&mut dir)?;
+
+        Ok(())
+    }
+
+    /// Generate the module `my_crate/_gluegun_debug.py`, which reports how many Python objects wrapping
+    /// Rust resources have not been freed yet, so that tests can check that none are leaked, e.g.
+    ///
+    /// ```python
+    /// import gc
+    /// import my_crate._gluegun_debug
+    ///
+    /// counter = None
+    /// gc.collect()
+    /// assert my_crate._gluegun_debug.live_objects(native_module) == 0
+    /// ```
+    fn generate_debug_bindings(&self, dir: &mut DirBuilder<'_>) -> anyhow::Result<()> {
+        let crate_name = self.idl.crate_name();
+        let module = self.metadata.python_module(self.idl, &ModulePath::of_crate(crate_name));
+        let mut file = dir.add_file(package_file_name(&module).with_file_name("_gluegun_debug.py"))?;
+        write!(file, "\"\"\"Counts the Python objects wrapping Rust values of `{crate_name}` (generated with `debug-bindings`).\"\"\"")?;
+        write!(file, "")?;
+        write!(file, "")?;
+        write!(file, "def live_objects(native) -> int:")?;
+        write!(file, "    \"\"\"The number of objects created by `native`, the native module, that Python has not freed yet.\"\"\"")?;
+        write!(file, "    return native.gluegun_live_objects()")?;
+        Ok(())
+    }
+
+    /// Generate the module `my_crate/_interface_hash.py`, which checks that the native module
+    /// was generated from the same interface as this package (see [`Idl::interface_hash`]), e.g.
+    ///
+    /// ```python
+    /// import my_crate._interface_hash
+    ///
+    /// my_crate._interface_hash.check(native_module)
+    /// ```
+    ///
+    /// The native module exposes its hash as `gluegun_interface_hash()`; a mismatch raises an `ImportError`.
+    fn generate_interface_hash(&self, dir: &mut DirBuilder<'_>) -> anyhow::Result<()> {
+        let crate_name = self.idl.crate_name();
+        let module = self.metadata.python_module(self.idl, &ModulePath::of_crate(crate_name));
+        let mut file = dir.add_file(package_file_name(&module).with_file_name("_interface_hash.py"))?;
+        write!(file, "\"\"\"Checks that the native module of `{crate_name}` was generated from the same interface as this package.\"\"\"")?;
+        write!(file, "")?;
+        write!(file, "# Hash of the interface this package was generated from.")?;
+        write!(file, "EXPECTED = {:#018x}", self.idl.interface_hash())?;
+        write!(file, "")?;
+        write!(file, "")?;
+        write!(file, "def check(native) -> None:")?;
+        write!(file, "    \"\"\"Raise `ImportError` if `native`, the native module, was generated from another interface.\"\"\"")?;
+        write!(file, "    actual = native.gluegun_interface_hash()")?;
+        write!(file, "    if actual != EXPECTED:")?;
+        write!(
+            file,
+            "        raise ImportError(f\"the native module of `{crate_name}` was generated from a different interface than its Python package \
+             (expected interface hash {{EXPECTED:#x}}, found {{actual:#x}}); regenerate both from the same version of the crate\")",
+        )?;
+        Ok(())
+    }
+
+    /// The modules of the classes the newtypes among `items` wrap, if not built in.
+    fn newtype_imports(&self, items: &[(&QualifiedName, &Item)]) -> BTreeSet<&'static str> {
+        let mut modules = BTreeSet::new();
+        for (_, item) in items {
+            let Item::Newtype(newtype) = item else {
+                continue;
+            };
+            match (newtype.ty().kind(), self.metadata.textual_types) {
+                (TypeKind::NetAddr { repr }, TextualTypes::Native) if !repr.is_socket() => {
+                    modules.insert("ipaddress");
+                }
+                (TypeKind::Uuid { .. }, TextualTypes::Native) => {
+                    modules.insert("uuid");
+                }
+                _ => {}
+            }
+        }
+        modules
+    }
+
+    /// The modules other than `module_path` defining the enums that the functions among `items` convert
+    /// (see `generate_function`).
+    fn enum_imports(&self, module_path: &ModulePath, items: &[(&QualifiedName, &Item)]) -> BTreeSet<String> {
+        items
+            .iter()
+            .filter_map(|(_, item)| match item {
+                Item::Function(function) => Some(function.signature()),
+                _ => None,
+            })
+            .flat_map(|signature| {
+                let inputs = signature.inputs().iter().map(|input| input.refd_ty().ty());
+                inputs.chain([signature.output_ty().main_ty().ty()])
+            })
+            .filter_map(|ty| self.python_enum(ty))
+            .map(|qname| qname.module_path())
+            .filter(|module| module != module_path)
+            .map(|module| self.metadata.python_module(self.idl, &module).dotted())
+            .collect()
+    }
+
+    /// The modules other than `module_path` defining classes that implement the interfaces among `items`.
+    fn implementation_imports(&self, module_path: &ModulePath, items: &[(&QualifiedName, &Item)]) -> BTreeSet<String> {
+        items
+            .iter()
+            .filter(|(_, item)| matches!(item, Item::Interface(_)))
+            .flat_map(|(qname, _)| self.implementations(qname))
+            .map(|qname| qname.module_path())
+            .filter(|module| module != module_path)
+            .map(|module| self.metadata.python_module(self.idl, &module).dotted())
+            .collect()
+    }
+
+    /// The resources implementing the interface `interface`.
+    fn implementations(&self, interface: &QualifiedName) -> Vec<&'idl QualifiedName> {
+        self.idl
+            .resources()
+            .filter(|(_, resource)| resource.implements().contains(interface))
+            .map(|(qname, _)| qname)
+            .collect()
+    }
+
+    /// Generate a pytest stub for each code example in the doc comments of functions
+    /// (only extracted if `doc-examples` is enabled). The stubs contain the Rust code
+    /// of the example and are skipped until it is ported to Python.
+    fn generate_example_tests(&self, dir: &mut DirBuilder<'_>) -> anyhow::Result<()> {
+        let functions: Vec<(&QualifiedName, &Function)> = self
+            .idl
+            .functions()
+            .filter(|(_, function)| !function.examples().is_empty())
+            .collect();
+        if functions.is_empty() {
+            return Ok(());
+        }
+
+        let mut file = dir.add_file("tests/test_examples.py")?;
+        write!(file, "import pytest")?;
+        for (qname, function) in functions {
+            for (index, example) in function.examples().iter().enumerate() {
+                file.with_span(example.span(), |file| self.generate_example_test(file, qname, index + 1, example))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_example_test(
+        &self,
+        file: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        number: usize,
+        example: &DocExample,
+    ) -> anyhow::Result<()> {
+        write!(file, "")?;
+        write!(file, "")?;
+        write!(file, "@pytest.mark.skip(reason=\"example not yet ported from Rust\")")?;
+        write!(file, "def test_{}_example_{number}():", qname.to_string("_"))?;
+        write!(
+            file,
+            "    \"\"\"Example {number} from the documentation of `{qname}` ({path}:{line}).\"\"\"",
+            qname = qname.colon_colon(),
+            path = example.span().path().display(),
+            line = example.span().start().line(),
+        )?;
+        for line in example.code().lines() {
+            file.write_verbatim(format_args!("    # {line}"))?;
+        }
+        Ok(())
+    }
+
+    /// The Python package of the crate (e.g., `my_crate`), which holds the native module.
+    fn root_package(&self) -> ModulePath {
+        self.metadata.python_module(self.idl, &ModulePath::of_crate(self.idl.crate_name()))
+    }
+
+    /// If `ty` is an enum that becomes an `enum.Enum` (see `generate_enum`), return its name.
+    fn python_enum<'ty>(&self, ty: &'ty Ty) -> Option<&'ty QualifiedName> {
+        let TypeKind::UserType { qname, .. } = ty.kind() else {
+            return None;
+        };
+        self.idl.item(qname).filter(|item| is_python_enum(item)).map(|_| qname)
+    }
+
+    /// The name of the class `qname` in the package of `module_path`: its own name if defined there,
+    /// its dotted name otherwise.
+    fn class_name(&self, module_path: &ModulePath, qname: &QualifiedName) -> String {
+        if qname.module_path() == *module_path {
+            qname.tail_name().to_string()
+        } else {
+            self.metadata.python_name(self.idl, qname)
+        }
+    }
+
+    /// Re-export the class of the native module wrapping a resource (see `rs_gen`) under its own name.
+    /// With `target-gates`, the native module only has it on the selected targets; elsewhere, the name is `None`.
+    fn generate_resource(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName) -> anyhow::Result<()> {
+        write!(file, "")?;
+        write!(file, "")?;
+        if self.target_gates.cfg_attr(qname).is_some() {
+            write!(file, "{} = getattr(_native, {:?}, None)", qname.tail_name(), qname.to_string("_"))?;
+        } else {
+            write!(file, "{} = _native.{}", qname.tail_name(), qname.to_string("_"))?;
+        }
+        Ok(())
+    }
+
+    /// Generate an `enum.Enum` for a C-like enum, whose members have the values of the class of the native module
+    /// (see `rs_gen`), e.g.
+    ///
+    /// ```python
+    /// class Color(enum.Enum):
+    ///     Red = _native.my_crate_Color.Red
+    ///     Green = _native.my_crate_Color.Green
+    /// ```
+    ///
+    /// The functions of the package convert their arguments and results of the enum (see `generate_function`);
+    /// elsewhere (e.g., in methods of resources or fields of records), the value of the native class is exchanged,
+    /// which `Color(value)` converts into the member and `member.value` back.
+    /// With `target-gates`, the enum is only defined on the targets selected for it.
+    fn generate_enum(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName, an_enum: &Enum) -> anyhow::Result<()> {
+        let native_class = qname.to_string("_");
+        let indent = if self.target_gates.cfg_attr(qname).is_some() { "    " } else { "" };
+
+        write!(file, "")?;
+        write!(file, "")?;
+        if !indent.is_empty() {
+            write!(file, "if hasattr(_native, {native_class:?}):")?;
+        }
+        write!(file, "{indent}class {}(enum.Enum):", qname.tail_name())?;
+        write!(file, "{indent}    \"\"\"The Rust enum `{}`.\"\"\"", qname.colon_colon())?;
+        write!(file, "")?;
+        for arm in an_enum.arms() {
+            write!(file, "{indent}    {arm} = _native.{native_class}.{arm}", arm = arm.name())?;
+        }
+        Ok(())
+    }
+
+    /// Generate a function calling the one of the native module (and, with `pack-params`, its packed form),
+    /// annotated with the Python types of its parameters and result, e.g.
+    ///
+    /// ```python
+    /// def paint(shape: Shape, color: Color) -> Color:
+    ///     """Calls the Rust function `my_crate::paint`."""
+    ///     color = Color(color).value
+    ///     return Color(_native.paint(shape, color))
+    /// ```
+    ///
+    /// Arguments and results that are enums (see `generate_enum`) are converted from and to their members.
+    fn generate_function(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName, function: &Function) -> anyhow::Result<()> {
+        let module_path = qname.module_path();
+        let signature = function.signature();
+        let is_async = matches!(signature.is_async(), IsAsync::Yes);
+        let name = self.metadata.member_name(function.name());
+
+        let mut parameters = vec![];
+        let mut conversions = vec![];
+        for input in signature.inputs() {
+            let ty = input.refd_ty().ty();
+            parameters.push(format!("{}: {}", input.name(), self.type_mapper().map_ty(ty)?));
+            if let Some(enum_qname) = self.python_enum(ty) {
+                conversions.push(format!("{name} = {}({name}).value", self.class_name(&module_path, enum_qname), name = input.name()));
+            }
+        }
+        let arguments: Vec<String> = signature.inputs().iter().map(|input| input.name().to_string()).collect();
+        let output_ty = signature.output_ty().main_ty().ty();
+        let result_class = self.python_enum(output_ty).map(|enum_qname| self.class_name(&module_path, enum_qname));
+        self.generate_function_calling(
+            file,
+            is_async,
+            &name,
+            &parameters,
+            &conversions,
+            &format!("_native.{name}({})", arguments.join(", ")),
+            &self.type_mapper().map_ty(output_ty)?,
+            result_class.as_deref(),
+            qname,
+        )?;
+
+        if let Some((params_qname, _)) = self.idl.params_record(qname) {
+            let packed_name = self.metadata.member_name(&Name::from(format!("{}_packed", function.name())));
+            self.generate_function_calling(
+                file,
+                is_async,
+                &packed_name,
+                &[format!("params: {}", self.class_name(&module_path, params_qname))],
+                &[],
+                &format!("_native.{packed_name}(params)"),
+                &self.type_mapper().map_ty(output_ty)?,
+                result_class.as_deref(),
+                qname,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Generate the Python function `name`, which applies `conversions` to its parameters and returns the result
+    /// of `call` (converted by `result_class`, if any), for the Rust function `qname`.
+    #[allow(clippy::too_many_arguments)]
+    fn generate_function_calling(
+        &self,
+        file: &mut CodeWriter<'_>,
+        is_async: bool,
+        name: &Name,
+        parameters: &[String],
+        conversions: &[String],
+        call: &str,
+        output: &str,
+        result_class: Option<&str>,
+        qname: &QualifiedName,
+    ) -> anyhow::Result<()> {
+        let (def, await_kw) = if is_async { ("async def", "await ") } else { ("def", "") };
+        let result = match result_class {
+            Some(class) => format!("{class}({await_kw}{call})"),
+            None => format!("{await_kw}{call}"),
+        };
+
+        write!(file, "")?;
+        write!(file, "")?;
+        write!(file, "{def} {name}({}) -> {output}:", parameters.join(", "))?;
+        write!(file, "    \"\"\"Calls the Rust function `{}`.\"\"\"", qname.colon_colon())?;
+        for conversion in conversions {
+            write!(file, "    {conversion}")?;
+        }
+        write!(file, "    return {result}")?;
+        Ok(())
+    }
+
+    /// Generate the dataclass for a record, which is converted to and from the Rust struct field by field.
+    /// Tuple records are named tuples instead, so they can also be indexed and unpacked like Python tuples.
+    fn generate_record(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName, record: &Record) -> anyhow::Result<()> {
+        write!(file, "")?;
+        write!(file, "")?;
+        if *record.tuple() {
+            write!(file, "class {}(typing.NamedTuple):", qname.tail_name())?;
+        } else {
+            write!(file, "@dataclasses.dataclass(frozen=True)")?;
+            write!(file, "class {}:", qname.tail_name())?;
+        }
+        if record.fields().is_empty() {
+            write!(file, "    pass")?;
+        }
+        for field in record.fields() {
+            write!(
+                file,
+                "    {}: {}",
+                self.metadata.member_name(field.name()),
+                self.type_mapper().map_ty(field.ty())?,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// An error variant derives from `Exception`, so its arms can be raised (see `rs_gen`)
+    /// and caught as the base class or one arm at a time.
+    fn generate_variant(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName, variant: &Variant) -> anyhow::Result<()> {
+        let name = qname.tail_name();
+
+        write!(file, "")?;
+        write!(file, "")?;
+        if *variant.implements_error() {
+            write!(file, "class {name}(Exception):")?;
+        } else {
+            write!(file, "class {name}:")?;
+        }
+        write!(file, "    __slots__ = ()")?;
+
+        for arm in variant.arms() {
+            write!(file, "")?;
+            write!(file, "")?;
+            self.generate_variant_arm(file, qname, variant, arm)?;
+        }
+
+        write!(file, "")?;
+        write!(file, "")?;
+        for arm in variant.arms() {
+            write!(file, "{name}.{arm} = {class}", arm = arm.name(), class = arm_class_name(qname, arm.name()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Generate the exception classes for a C-like enum implementing `std::error::Error`: a base class
+    /// and a subclass for each arm, whose only argument is the message of the error. The enum itself
+    /// is a class of the native module (see `rs_gen`).
+    fn generate_error_enum(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName, an_enum: &Enum) -> anyhow::Result<()> {
+        let name = qname.tail_name();
+
+        write!(file, "")?;
+        write!(file, "")?;
+        write!(file, "class {name}(Exception):")?;
+        write!(file, "    pass")?;
+
+        for arm in an_enum.arms() {
+            write!(file, "")?;
+            write!(file, "")?;
+            write!(file, "class {class}({name}):", class = arm_class_name(qname, arm.name()))?;
+            write!(file, "    __qualname__ = \"{name}.{}\"", arm.name())?;
+        }
+
+        write!(file, "")?;
+        write!(file, "")?;
+        for arm in an_enum.arms() {
+            write!(file, "{name}.{arm} = {class}", arm = arm.name(), class = arm_class_name(qname, arm.name()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Generate the `NewType` for a newtype. Python code passes and receives values of the field type;
+    /// the `NewType` only exists for the benefit of type checkers.
+    fn generate_newtype(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName, newtype: &Newtype) -> anyhow::Result<()> {
+        let name = qname.tail_name();
+
+        write!(file, "")?;
+        write!(file, "")?;
+        write!(file, "{name} = typing.NewType({:?}, {})", name.to_string(), self.type_mapper().map_ty(newtype.ty())?)?;
+
+        Ok(())
+    }
+
+    /// Generate the abstract base class for an interface. Rust calls the methods of the Python object
+    /// it is given by name, so deriving from the class is not required, but it documents the methods
+    /// to implement and lets type checkers verify them.
+    fn generate_interface(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName, interface: &Interface) -> anyhow::Result<()> {
+        write!(file, "")?;
+        write!(file, "")?;
+        write!(file, "class {}(abc.ABC):", qname.tail_name())?;
+        write!(file, "    __slots__ = ()")?;
+        for method in interface.methods() {
+            let signature = method.signature();
+            let mut parameters = vec!["self".to_string()];
+            for input in signature.inputs() {
+                parameters.push(format!("{}: {}", input.name(), self.type_mapper().map_ty(input.refd_ty().ty())?));
+            }
+            write!(file, "")?;
+            write!(file, "    @abc.abstractmethod")?;
+            write!(
+                file,
+                "    def {}({}) -> {}: ...",
+                self.metadata.member_name(method.name()),
+                parameters.join(", "),
+                self.type_mapper().map_ty(signature.output_ty().main_ty().ty())?,
+            )?;
+        }
+
+        // Classes of resources implementing the interface are virtual subclasses of it,
+        // so `isinstance` checks succeed and they can be passed where it is expected.
+        let implementations = self.implementations(qname);
+        if !implementations.is_empty() {
+            write!(file, "")?;
+            write!(file, "")?;
+        }
+        for implementation in implementations {
+            let class = if implementation.module_path() == qname.module_path() {
+                implementation.tail_name().to_string()
+            } else {
+                self.metadata.python_name(self.idl, implementation)
+            };
+            write!(file, "{}.register({class})", qname.tail_name())?;
+        }
+        Ok(())
+    }
+
+    /// Generate the dataclass for an arm. It is defined at the top level under a private name
+    /// (a class cannot derive from the class that encloses it) but named after the attribute
+    /// of the base class it is reachable as, so that it also prints that way.
+    /// Exceptions are not frozen, as raising them sets their attributes (e.g., `__traceback__`).
+    fn generate_variant_arm(&self, file: &mut CodeWriter<'_>, qname: &QualifiedName, variant: &Variant, arm: &VariantArm) -> anyhow::Result<()> {
+        if *variant.implements_error() {
+            write!(file, "@dataclasses.dataclass(eq=False)")?;
+        } else {
+            write!(file, "@dataclasses.dataclass(frozen=True)")?;
+        }
+        write!(file, "class {class}({name}):", class = arm_class_name(qname, arm.name()), name = qname.tail_name())?;
+        write!(file, "    __qualname__ = \"{}.{}\"", qname.tail_name(), arm.name())?;
+        for field in arm.fields() {
+            write!(
+                file,
+                "    {}: {}",
+                self.metadata.member_name(field.name()),
+                self.type_mapper().map_ty(field.ty())?,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `item` is a C-like enum that becomes an `enum.Enum` (error enums become exception classes instead).
+fn is_python_enum(item: &Item) -> bool {
+    matches!(item, Item::Enum(an_enum) if !*an_enum.implements_error())
+}
+
+/// Name of the module-level class generated for `arm` of the variant (or error enum) `qname` (e.g., `_Shape_Circle`).
+fn arm_class_name(qname: &QualifiedName, arm: &Name) -> String {
+    format!("_{}_{}", qname.tail_name(), arm)
+}
+
+/// Path of the `__init__.py` file of the Python package for the Rust module `module_path`
+/// (e.g., `my_crate/shapes/__init__.py` for `my_crate::shapes`).
+fn package_file_name(module_path: &ModulePath) -> PathBuf {
+    let mut path = PathBuf::new();
+    for name in module_path.names() {
+        path.push(name.text());
+    }
+    path.push("__init__.py");
+    path
+}
+
+/// Maps IDL types to the Python types used in annotations.
+/// Annotations are not evaluated (see `from __future__ import annotations`),
+/// so user types can be referenced by their qualified name without importing them.
+/// Third-party types are annotated with the Python type of their override.
+struct PythonTypeMapper<'o> {
+    idl: &'o Idl,
+    type_overrides: &'o TypeOverrides,
+    metadata: &'o PythonMetadata,
+}
+
+impl TypeMapper for PythonTypeMapper<'_> {
+    type Output = String;
+
+    fn map_map(&mut self, _ty: &Ty, key: String, value: String, _repr: &MapSetRepr) -> anyhow::Result<String> {
+        Ok(format!("dict[{key}, {value}]"))
+    }
+
+    fn map_vec(&mut self, _ty: &Ty, element: String, _repr: &VecRepr) -> anyhow::Result<String> {
+        Ok(format!("list[{element}]"))
+    }
+
+    fn map_set(&mut self, _ty: &Ty, element: String, _repr: &MapSetRepr) -> anyhow::Result<String> {
+        Ok(format!("set[{element}]"))
+    }
+
+    fn map_path(&mut self, _ty: &Ty, _repr: &PathRepr) -> anyhow::Result<String> {
+        Ok("pathlib.Path".to_string())
+    }
+
+    fn map_net_addr(&mut self, _ty: &Ty, repr: &NetAddrRepr) -> anyhow::Result<String> {
+        let annotation = match (self.metadata.textual_types, repr) {
+            (TextualTypes::Native, NetAddrRepr::Ipv4Addr) => "ipaddress.IPv4Address",
+            (TextualTypes::Native, NetAddrRepr::Ipv6Addr) => "ipaddress.IPv6Address",
+            (TextualTypes::Native, NetAddrRepr::IpAddr) => "ipaddress.IPv4Address | ipaddress.IPv6Address",
+            _ => "str",
+        };
+        Ok(annotation.to_string())
+    }
+
+    fn map_uuid(&mut self, _ty: &Ty, _repr: &UuidRepr) -> anyhow::Result<String> {
+        match self.metadata.textual_types {
+            TextualTypes::Native => Ok("uuid.UUID".to_string()),
+            TextualTypes::String => Ok("str".to_string()),
+        }
+    }
+
+    fn map_string(&mut self, _ty: &Ty, _repr: &StringRepr) -> anyhow::Result<String> {
+        Ok("str".to_string())
+    }
+
+    fn map_option(&mut self, _ty: &Ty, element: String, _repr: &OptionRepr) -> anyhow::Result<String> {
+        Ok(format!("{element} | None"))
+    }
+
+    fn map_result(&mut self, _ty: &Ty, ok: String, _err: String, _repr: &ResultRepr) -> anyhow::Result<String> {
+        // Errors are raised as exceptions.
+        Ok(ok)
+    }
+
+    fn map_tuple(&mut self, _ty: &Ty, elements: Vec<String>, _repr: &TupleRepr) -> anyhow::Result<String> {
+        if elements.is_empty() {
+            return Ok("None".to_string());
+        }
+        Ok(format!("tuple[{}]", elements.join(", ")))
+    }
+
+    fn map_scalar(&mut self, ty: &Ty, scalar: &Scalar) -> anyhow::Result<String> {
+        match scalar {
+            Scalar::Boolean => Ok("bool".to_string()),
+            Scalar::Char => Ok("str".to_string()),
+            Scalar::I8
+            | Scalar::I16
+            | Scalar::I32
+            | Scalar::I64
+            | Scalar::U8
+            | Scalar::U16
+            | Scalar::U32
+            | Scalar::U64 => Ok("int".to_string()),
+            Scalar::F32 | Scalar::F64 => Ok("float".to_string()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_future(&mut self, _ty: &Ty, output: String, _repr: &FutureRepr) -> anyhow::Result<String> {
+        Ok(format!("collections.abc.Awaitable[{output}]"))
+    }
+
+    fn map_stream(&mut self, _ty: &Ty, item: String, _repr: &StreamRepr) -> anyhow::Result<String> {
+        Ok(format!("collections.abc.AsyncIterator[{item}]"))
+    }
+
+    fn map_user_type(&mut self, _ty: &Ty, qname: &QualifiedName) -> anyhow::Result<String> {
+        Ok(self.metadata.python_name(self.idl, qname))
+    }
+
+    fn map_trait_object(&mut self, _ty: &Ty, qname: &QualifiedName, _repr: &TraitObjectRepr) -> anyhow::Result<String> {
+        Ok(self.metadata.python_name(self.idl, qname))
+    }
+
+    fn map_custom(&mut self, ty: &Ty, path: &str) -> anyhow::Result<String> {
+        Ok(self.type_overrides.expect(ty, path)?.ty().clone())
+    }
+}
@@ -0,0 +1,50 @@
+use gluegun_core::{
+    codegen::LibraryCrate,
+    idl::{Idl, ModulePath},
+};
+
+use crate::metadata::PythonMetadata;
+
+/// Generates the `pyproject.toml` with which `maturin` builds the generated crate into a Python distribution:
+/// the Python package under `python/` (see `py_gen`), with the native module built from the crate (see `rs_gen`)
+/// installed in it as `_native`, e.g.
+///
+/// ```toml
+/// [tool.maturin]
+/// python-source = "python"
+/// module-name = "my_crate._native"
+/// ```
+pub(crate) struct PyprojectGenerator<'idl> {
+    idl: &'idl Idl,
+    metadata: &'idl PythonMetadata,
+}
+
+impl<'idl> PyprojectGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl, metadata: &'idl PythonMetadata) -> Self {
+        Self { idl, metadata }
+    }
+
+    pub(crate) fn generate(self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        let package = self.metadata.python_module(self.idl, &ModulePath::of_crate(self.idl.crate_name()));
+        let crate_metadata = self.idl.crate_metadata();
+
+        let mut file = lib.add_file("pyproject.toml")?;
+        write!(file, "[build-system]")?;
+        write!(file, "requires = [\"maturin>=1.0,<2.0\"]")?;
+        write!(file, "build-backend = \"maturin\"")?;
+        write!(file, "")?;
+        write!(file, "[project]")?;
+        write!(file, "name = {:?}", package.dotted())?;
+        write!(file, "version = {:?}", crate_metadata.version().as_deref().unwrap_or("0.1.0"))?;
+        if let Some(description) = crate_metadata.description() {
+            write!(file, "description = \"{}\"", description.replace('\\', "\\\\").replace('"', "\\\""))?;
+        }
+        // The package uses `match` on the classes of enums with associated data.
+        write!(file, "requires-python = \">=3.10\"")?;
+        write!(file, "")?;
+        write!(file, "[tool.maturin]")?;
+        write!(file, "python-source = \"python\"")?;
+        write!(file, "module-name = \"{}._native\"", package.dotted())?;
+        Ok(())
+    }
+}
@@ -1,26 +1,51 @@
 use gluegun_core::{
-    codegen::{CodeWriter, LibraryCrate},
+    cli::{TargetGates, TypeOverride, TypeOverrides},
+    codegen::{CodeWriter, Feature, FeatureSet, LibraryCrate},
     idl::{
-        Function, FunctionInput, FutureRepr, Idl, Item, MapSetRepr, OptionRepr,
-        PathRepr, QualifiedName, RefdTy, ResultRepr, StringRepr, TupleRepr, Ty, TypeKind,
-        VecRepr,
+        Enum, ErrorRepr, Field, Function, FunctionInput, FutureRepr, Idl, Interface, Item, MapSetRepr, Method, MethodCategory, Name,
+        NetAddrRepr, OptionRepr, PathRepr, QualifiedName, Record, RefdTy, Resource, ResultRepr, Scalar, SelfKind, Signature,
+        StreamRepr, StringRepr, TraitObjectRepr, TupleRepr, Ty, TypeKind, UserTypeRepr, UuidRepr, Variant, VecRepr,
     },
+    visit::{self, Visitor},
+    type_mapper::{self, TypeMapper},
 };
 
+use crate::metadata::{PythonMetadata, TextualTypes};
+
 pub(crate) struct RustCodeGenerator<'idl> {
     idl: &'idl Idl,
-    features: Vec<&'static str>,
+    type_overrides: &'idl TypeOverrides,
+    target_gates: &'idl TargetGates,
+    metadata: &'idl PythonMetadata,
+    /// Whether resource classes count their live instances (see `LibraryCrate::debug_bindings_enabled`).
+    debug_bindings: bool,
+    /// Features required by the generated code, discovered while generating it.
+    features: FeatureSet,
+    /// Statements adding the functions and classes to the native module, collected while generating them
+    /// (see `generate_native_module`).
+    registrations: Vec<String>,
 }
 
 impl<'idl> RustCodeGenerator<'idl> {
-    pub(crate) fn new(idl: &'idl Idl) -> Self {
+    pub(crate) fn new(
+        idl: &'idl Idl,
+        type_overrides: &'idl TypeOverrides,
+        target_gates: &'idl TargetGates,
+        metadata: &'idl PythonMetadata,
+        debug_bindings: bool,
+    ) -> Self {
         Self {
             idl,
+            type_overrides,
+            target_gates,
+            metadata,
+            debug_bindings,
             features: Default::default(),
+            registrations: Default::default(),
         }
     }
 
-    pub(crate) fn generate(mut self, lib: &mut LibraryCrate) -> anyhow::Result<Vec<&'static str>> {
+    pub(crate) fn generate(mut self, lib: &mut LibraryCrate) -> anyhow::Result<FeatureSet> {
         self.generate_lib_rs(lib)?;
         Ok(self.features)
     }
@@ -30,14 +55,165 @@ impl<'idl> RustCodeGenerator<'idl> {
 
         write!(lib_rs, "#![allow(non_snake_case)]")?; // FIXME: bug in duchess
 
+        self.generate_interface_hash(&mut lib_rs)?;
+        if self.debug_bindings {
+            self.generate_live_objects(&mut lib_rs)?;
+        }
         self.generate_python_items(&mut lib_rs)?;
+        if self.features.contains(Feature::Streams) {
+            self.generate_stream_class(&mut lib_rs)?;
+        }
+        self.generate_native_module(&mut lib_rs)?;
+
+        Ok(())
+    }
+
+    /// Generate the native module, `_native`, which the Python package imports as `{package}._native` (see `py_gen`):
+    ///
+    /// ```rust,ignore
+    /// #[pyo3::pymodule]
+    /// #[pyo3(name = "_native")]
+    /// fn gluegun_native(module: &pyo3::Bound<'_, pyo3::types::PyModule>) -> pyo3::PyResult<()> {
+    ///     module.add_function(pyo3::wrap_pyfunction!(greet, module)?)?;
+    ///     module.add("my_crate_Counter", module.py().get_type::<my_crate_Counter>())?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Classes are added under the name of their Rust type (e.g., `my_crate_Counter`), which is unique across modules;
+    /// the Python package re-exports them under their own name.
+    fn generate_native_module(&self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[pyo3::pymodule]")?;
+        write!(lib_rs, "#[pyo3(name = \"_native\")]")?;
+        write!(lib_rs, "fn gluegun_native(module: &pyo3::Bound<'_, pyo3::types::PyModule>) -> pyo3::PyResult<()> {{")?;
+        write!(lib_rs, "use pyo3::types::PyModuleMethods;")?;
+        write!(lib_rs, "module.add_function(pyo3::wrap_pyfunction!(gluegun_interface_hash, module)?)?;")?;
+        if self.debug_bindings {
+            write!(lib_rs, "module.add_function(pyo3::wrap_pyfunction!(gluegun_live_objects, module)?)?;")?;
+        }
+        if self.features.contains(Feature::Streams) {
+            write!(lib_rs, "module.add_class::<gluegun_Stream>()?;")?;
+        }
+        for registration in &self.registrations {
+            write!(lib_rs, "{registration}")?;
+        }
+        write!(lib_rs, "Ok(())")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Add the function `name` (the Rust function generated for it) to the native module.
+    fn register_function(&mut self, qname: &QualifiedName, name: &Name) {
+        self.register(qname, format!("module.add_function(pyo3::wrap_pyfunction!({name}, module)?)?;"));
+    }
+
+    /// Add the class `class` (the Rust type generated for `qname`) to the native module, under the name of that type.
+    fn register_class(&mut self, qname: &QualifiedName, class: &str) {
+        self.register(qname, format!("module.add({class:?}, module.py().get_type::<{class}>())?;"));
+    }
+
+    /// Add `statement` to the body of the native module, under the `#[cfg]` of the `target-gates` matching `qname`, if any.
+    fn register(&mut self, qname: &QualifiedName, statement: String) {
+        if let Some(cfg_attr) = self.target_gates.cfg_attr(qname) {
+            self.registrations.push(cfg_attr);
+        }
+        self.registrations.push(statement);
+    }
+
+    /// Generate the hash of the interface (see [`Idl::interface_hash`]) and the function returning it,
+    /// which the Python package calls to check that it was generated from the same interface.
+    fn generate_interface_hash(&self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "/// Hash of the interface these bindings were generated from.")?;
+        write!(lib_rs, "pub const GLUEGUN_INTERFACE_HASH: u64 = {:#018x};", self.idl.interface_hash())?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[pyo3::pyfunction]")?;
+        write!(lib_rs, "fn gluegun_interface_hash() -> u64 {{")?;
+        write!(lib_rs, "GLUEGUN_INTERFACE_HASH")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
 
+    /// Generate the function returning the number of live resource objects (see `generate_resource`),
+    /// which the Python package exposes as `_gluegun_debug.live_objects`.
+    fn generate_live_objects(&self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[pyo3::pyfunction]")?;
+        write!(lib_rs, "fn gluegun_live_objects() -> usize {{")?;
+        write!(lib_rs, "crate::gluegun_debug::live_objects()")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Generate the Python class wrapping the streams returned by functions and methods, an async iterator,
+    /// and the `tokio` runtime that drives them. The Rust code of a stream may need a runtime of its own
+    /// (e.g., for timers or sockets), so each item is produced on the runtime rather than on Python's event loop.
+    fn generate_stream_class(&self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "fn gluegun_runtime() -> &'static tokio::runtime::Runtime {{")?;
+        write!(lib_rs, "static RUNTIME: std::sync::OnceLock<tokio::runtime::Runtime> = std::sync::OnceLock::new();")?;
+        write!(lib_rs, "RUNTIME.get_or_init(|| {{")?;
+        write!(lib_rs, "tokio::runtime::Builder::new_multi_thread()")?;
+        write!(lib_rs, ".enable_all()")?;
+        write!(lib_rs, ".build()")?;
+        write!(lib_rs, ".expect(\"failed to start the tokio runtime\")")?;
+        write!(lib_rs, "}})")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[allow(non_camel_case_types)]")?;
+        write!(
+            lib_rs,
+            "type gluegun_PyStream = std::pin::Pin<Box<dyn futures::Stream<Item = pyo3::PyResult<pyo3::PyObject>> + Send>>;"
+        )?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[pyo3::pyclass(name = \"Stream\")]")?;
+        write!(lib_rs, "#[allow(non_camel_case_types)]")?;
+        write!(lib_rs, "pub struct gluegun_Stream(std::sync::Arc<futures::lock::Mutex<gluegun_PyStream>>);")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "impl gluegun_Stream {{")?;
+        write!(lib_rs, "fn new<T: Send + 'static>(")?;
+        write!(lib_rs, "stream: impl futures::Stream<Item = T> + Send + 'static,")?;
+        write!(lib_rs, "to_py: fn(pyo3::Python<'_>, T) -> pyo3::PyResult<pyo3::PyObject>,")?;
+        write!(lib_rs, ") -> Self {{")?;
+        write!(
+            lib_rs,
+            "let stream = futures::StreamExt::map(stream, move |item| pyo3::Python::with_gil(|py| to_py(py, item)));"
+        )?;
+        write!(lib_rs, "Self(std::sync::Arc::new(futures::lock::Mutex::new(Box::pin(stream))))")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[pyo3::pymethods]")?;
+        write!(lib_rs, "impl gluegun_Stream {{")?;
+        write!(lib_rs, "fn __aiter__(slf: pyo3::PyRef<'_, Self>) -> pyo3::PyRef<'_, Self> {{")?;
+        write!(lib_rs, "slf")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "")?;
+        // pyo3 does not support `async` slots, so `__anext__` returns the coroutine of an `async` method.
+        write!(lib_rs, "fn __anext__(slf: &pyo3::Bound<'_, Self>) -> pyo3::PyResult<pyo3::PyObject> {{")?;
+        write!(lib_rs, "use pyo3::types::PyAnyMethods;")?;
+        write!(lib_rs, "Ok(slf.call_method0(\"_next\")?.unbind())")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[pyo3(name = \"_next\")]")?;
+        write!(lib_rs, "async fn next(&self) -> pyo3::PyResult<pyo3::PyObject> {{")?;
+        write!(lib_rs, "let stream = std::sync::Arc::clone(&self.0);")?;
+        write!(
+            lib_rs,
+            "let next = gluegun_runtime().spawn(async move {{ futures::StreamExt::next(&mut *stream.lock().await).await }});"
+        )?;
+        write!(lib_rs, "match next.await {{")?;
+        write!(lib_rs, "Ok(Some(item)) => item,")?;
+        write!(lib_rs, "Ok(None) => Err(pyo3::exceptions::PyStopAsyncIteration::new_err(())),")?;
+        write!(lib_rs, "Err(err) => Err(gluegun_py_util::runtime_error(err)),")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
         Ok(())
     }
 
     fn generate_python_items(&mut self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
         for (qname, item) in self.idl.definitions() {
-            self.generate_python_item(lib_rs, qname, item)?;
+            lib_rs.with_span(item.span(), |lib_rs| self.generate_python_item(lib_rs, qname, item))?;
         }
 
         Ok(())
@@ -50,27 +226,723 @@ impl<'idl> RustCodeGenerator<'idl> {
         item: &Item,
     ) -> anyhow::Result<()> {
         match item {
-            Item::Resource(_resource) => {
-                todo!()
+            Item::Resource(resource) => {
+                self.generate_resource(lib_rs, qname, resource)?;
             }
-            Item::Enum(_enum_) => {
-                todo!()
+            Item::Enum(an_enum) => {
+                self.generate_enum(lib_rs, qname, an_enum)?;
+                if *an_enum.implements_error() {
+                    self.generate_error_enum_to_py_err(lib_rs, qname, an_enum)?;
+                }
             }
-            Item::Record(_record) => {
-                todo!()
+            Item::Record(record) => {
+                // As for variants, functions that take or return the record report fields we cannot convert.
+                // Records packing the parameters of a function are only read by its packed form (see `generate_callable`).
+                if record.params_of().is_none() && check_record_fields(qname, record).is_ok() {
+                    self.generate_record_from_py(lib_rs, qname, record)?;
+                    self.generate_record_to_py(lib_rs, qname, record)?;
+                }
             }
-            Item::Variant(_variant) => {
-                todo!()
+            Item::Variant(variant) => {
+                // If the variant has fields we cannot convert, we skip the conversion functions;
+                // functions that take or return the variant report the error instead (see `dataclass_ty`).
+                if check_variant_fields(qname, variant).is_ok() {
+                    self.generate_variant_from_py(lib_rs, qname, variant)?;
+                    self.generate_variant_to_py(lib_rs, qname, variant)?;
+                    if *variant.implements_error() {
+                        self.generate_error_variant_to_py_err(lib_rs, qname)?;
+                    }
+                }
             }
             Item::Function(function) => {
                 self.generate_python_function(lib_rs, qname, function)?;
             }
+            Item::Newtype(_) => {
+                // Newtypes are passed to and from Python as the type of their field,
+                // see `generate_python_function`.
+            }
+            Item::Interface(interface) => {
+                self.generate_interface_adapter(lib_rs, qname, interface)?;
+            }
             _ => todo!(),
         }
 
         Ok(())
     }
 
+    /// Put the `#[cfg]` of the `target-gates` matching `qname`, if any, on the next Rust item generated for it.
+    /// Each of the items generated for a definition needs its own.
+    fn generate_cfg(&self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName) -> anyhow::Result<()> {
+        if let Some(cfg_attr) = self.target_gates.cfg_attr(qname) {
+            write!(lib_rs, "{cfg_attr}")?;
+        }
+        Ok(())
+    }
+
+    /// Generate a function `{qname}_from_py` that converts an instance of one of the Python classes
+    /// generated for the variant's arms (see `py_gen`) into the Rust enum.
+    fn generate_variant_from_py(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        variant: &Variant,
+    ) -> anyhow::Result<()> {
+        self.generate_cfg(lib_rs, qname)?;
+        write!(
+            lib_rs,
+            "fn {from_py}(value: &pyo3::Bound<'_, pyo3::PyAny>) -> pyo3::PyResult<{rust_path}> {{",
+            from_py = from_py_fn(qname),
+            rust_path = qname.colon_colon(),
+        )?;
+        write!(lib_rs, "use pyo3::types::PyAnyMethods;")?;
+        write!(
+            lib_rs,
+            "let class = value.py().import({module:?})?.getattr({name:?})?;",
+            module = self.metadata.python_module(self.idl, &qname.module_path()).dotted(),
+            name = qname.tail_name().to_string(),
+        )?;
+        for arm in variant.arms() {
+            write!(lib_rs, "if value.is_instance(&class.getattr({:?})?)? {{", arm.name().to_string())?;
+            if arm.fields().is_empty() {
+                write!(lib_rs, "return Ok({}::{} {{}});", qname.colon_colon(), arm.name())?;
+                write!(lib_rs, "}}")?;
+                continue;
+            }
+
+            let tuple_like = is_tuple_like(arm.fields());
+            write!(lib_rs, "return Ok({}::{} {{", qname.colon_colon(), arm.name())?;
+            for (index, field) in arm.fields().iter().enumerate() {
+                let field_name = if tuple_like { index.to_string() } else { field.name().to_string() };
+                write!(
+                    lib_rs,
+                    "{field_name}: value.getattr({:?})?.extract::<{}>()?,",
+                    self.metadata.member_name(field.name()).to_string(),
+                    self.generic_ty(field.ty())?,
+                )?;
+            }
+            write!(lib_rs, "}});")?;
+            write!(lib_rs, "}}")?;
+        }
+        write!(
+            lib_rs,
+            "Err(pyo3::exceptions::PyTypeError::new_err(\"expected an instance of `{}`\"))",
+            self.metadata.python_name(self.idl, qname),
+        )?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Generate a function `{qname}_to_py` that converts the Rust enum into an instance
+    /// of the Python class generated for its arm (see `py_gen`).
+    fn generate_variant_to_py(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        variant: &Variant,
+    ) -> anyhow::Result<()> {
+        self.generate_cfg(lib_rs, qname)?;
+        write!(
+            lib_rs,
+            "fn {to_py}(py: pyo3::Python<'_>, value: {rust_path}) -> pyo3::PyResult<pyo3::PyObject> {{",
+            to_py = to_py_fn(qname),
+            rust_path = qname.colon_colon(),
+        )?;
+        write!(lib_rs, "use pyo3::types::PyAnyMethods;")?;
+        write!(
+            lib_rs,
+            "let class = py.import({module:?})?.getattr({name:?})?;",
+            module = self.metadata.python_module(self.idl, &qname.module_path()).dotted(),
+            name = qname.tail_name().to_string(),
+        )?;
+        write!(lib_rs, "let value = match value {{")?;
+        for arm in variant.arms() {
+            let tuple_like = is_tuple_like(arm.fields());
+            let fields = arm
+                .fields()
+                .iter()
+                .enumerate()
+                .map(|(index, field)| {
+                    if tuple_like {
+                        format!("{index}: {}", field.name())
+                    } else {
+                        field.name().to_string()
+                    }
+                })
+                .collect::<Vec<_>>();
+            let pattern = if fields.is_empty() { "{}".to_string() } else { format!("{{ {} }}", fields.join(", ")) };
+            // Tuples of one element need a trailing comma.
+            let arguments = match &arm.fields()[..] {
+                [field] => format!("{},", field.name()),
+                fields => fields.iter().map(|field| field.name().to_string()).collect::<Vec<_>>().join(", "),
+            };
+            write!(
+                lib_rs,
+                "{}::{} {pattern} => class.getattr({:?})?.call1(({arguments}))?,",
+                qname.colon_colon(),
+                arm.name(),
+                arm.name().to_string(),
+            )?;
+        }
+        write!(lib_rs, "}};")?;
+        write!(lib_rs, "Ok(value.unbind())")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Generate a function `{qname}_to_py_err` that converts an error variant into the exception
+    /// of its arm (see `py_gen`), whose message (`args`) is that of the Rust error.
+    fn generate_error_variant_to_py_err(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName) -> anyhow::Result<()> {
+        self.generate_cfg(lib_rs, qname)?;
+        write!(
+            lib_rs,
+            "fn {to_py_err}(error: {rust_path}) -> pyo3::PyErr {{",
+            to_py_err = to_py_err_fn(qname),
+            rust_path = qname.colon_colon(),
+        )?;
+        write!(lib_rs, "use pyo3::types::PyAnyMethods;")?;
+        write!(lib_rs, "let message = error.to_string();")?;
+        write!(lib_rs, "pyo3::Python::with_gil(|py| {{")?;
+        write!(lib_rs, "let exception = {}(py, error)?.into_bound(py);", to_py_fn(qname))?;
+        write!(lib_rs, "exception.setattr(\"args\", (message,))?;")?;
+        write!(lib_rs, "Ok::<_, pyo3::PyErr>(pyo3::PyErr::from_value(exception))")?;
+        write!(lib_rs, "}})")?;
+        write!(lib_rs, ".unwrap_or_else(|err| err)")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Generate a function `{qname}_to_py_err` that converts an error enum into the exception
+    /// of its arm (see `py_gen`), whose message is that of the Rust error.
+    fn generate_error_enum_to_py_err(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        an_enum: &Enum,
+    ) -> anyhow::Result<()> {
+        self.generate_cfg(lib_rs, qname)?;
+        write!(
+            lib_rs,
+            "fn {to_py_err}(error: {rust_path}) -> pyo3::PyErr {{",
+            to_py_err = to_py_err_fn(qname),
+            rust_path = qname.colon_colon(),
+        )?;
+        write!(lib_rs, "use pyo3::types::PyAnyMethods;")?;
+        write!(lib_rs, "let arm = match &error {{")?;
+        for arm in an_enum.arms() {
+            write!(lib_rs, "{}::{} => {:?},", qname.colon_colon(), arm.name(), arm.name().to_string())?;
+        }
+        write!(lib_rs, "}};")?;
+        write!(lib_rs, "let message = error.to_string();")?;
+        write!(lib_rs, "pyo3::Python::with_gil(|py| {{")?;
+        write!(
+            lib_rs,
+            "let class = py.import({module:?})?.getattr({name:?})?;",
+            module = self.metadata.python_module(self.idl, &qname.module_path()).dotted(),
+            name = qname.tail_name().to_string(),
+        )?;
+        write!(lib_rs, "let exception = class.getattr(arm)?.call1((message,))?;")?;
+        write!(lib_rs, "Ok::<_, pyo3::PyErr>(pyo3::PyErr::from_value(exception))")?;
+        write!(lib_rs, "}})")?;
+        write!(lib_rs, ".unwrap_or_else(|err| err)")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Generate a function `{qname}_from_py` that converts an instance of the dataclass generated
+    /// for the record (see `py_gen`) into the Rust struct. Records without fields (e.g., unit structs)
+    /// only need the instance to be of the right class.
+    fn generate_record_from_py(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        record: &Record,
+    ) -> anyhow::Result<()> {
+        self.generate_cfg(lib_rs, qname)?;
+        write!(
+            lib_rs,
+            "fn {from_py}(value: &pyo3::Bound<'_, pyo3::PyAny>) -> pyo3::PyResult<{rust_path}> {{",
+            from_py = from_py_fn(qname),
+            rust_path = qname.colon_colon(),
+        )?;
+        write!(lib_rs, "use pyo3::types::PyAnyMethods;")?;
+        write!(
+            lib_rs,
+            "let class = value.py().import({module:?})?.getattr({name:?})?;",
+            module = self.metadata.python_module(self.idl, &qname.module_path()).dotted(),
+            name = qname.tail_name().to_string(),
+        )?;
+        write!(lib_rs, "if !value.is_instance(&class)? {{")?;
+        write!(
+            lib_rs,
+            "return Err(pyo3::exceptions::PyTypeError::new_err(\"expected an instance of `{}`\"));",
+            self.metadata.python_name(self.idl, qname),
+        )?;
+        write!(lib_rs, "}}")?;
+
+        write!(lib_rs, "Ok({} {{", qname.colon_colon())?;
+        for (index, field) in record.fields().iter().enumerate() {
+            write!(
+                lib_rs,
+                "{}: value.getattr({:?})?.extract::<{}>()?,",
+                record.member(index),
+                self.metadata.member_name(field.name()).to_string(),
+                self.generic_ty(field.ty())?,
+            )?;
+        }
+        write!(lib_rs, "}})")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Generate a function `{qname}_to_py` that converts the Rust struct into an instance
+    /// of the dataclass generated for the record (see `py_gen`).
+    fn generate_record_to_py(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        record: &Record,
+    ) -> anyhow::Result<()> {
+        self.generate_cfg(lib_rs, qname)?;
+        write!(
+            lib_rs,
+            "fn {to_py}(py: pyo3::Python<'_>, value: {rust_path}) -> pyo3::PyResult<pyo3::PyObject> {{",
+            to_py = to_py_fn(qname),
+            rust_path = qname.colon_colon(),
+        )?;
+        write!(lib_rs, "use pyo3::types::PyAnyMethods;")?;
+        write!(
+            lib_rs,
+            "let class = py.import({module:?})?.getattr({name:?})?;",
+            module = self.metadata.python_module(self.idl, &qname.module_path()).dotted(),
+            name = qname.tail_name().to_string(),
+        )?;
+        let arguments = (0..record.fields().len())
+            .map(|index| format!("value.{},", record.member(index)))
+            .collect::<String>();
+        if record.fields().is_empty() {
+            write!(lib_rs, "let _ = value;")?;
+        }
+        write!(lib_rs, "Ok(class.call1(({arguments}))?.unbind())")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// If `ty` is a third-party type, return its path and the override the user configured for it.
+    fn custom_ty<'ty>(&self, ty: &'ty Ty) -> anyhow::Result<Option<(&'ty str, &'idl TypeOverride)>> {
+        let TypeKind::Custom { path } = ty.kind() else {
+            return Ok(None);
+        };
+        Ok(Some((path, self.type_overrides.expect(ty, path)?)))
+    }
+
+    /// If `ty` is a trait object, return the name of its interface and how it is passed.
+    fn trait_object_ty<'ty>(&self, ty: &'ty Ty) -> Option<(&'ty QualifiedName, &'ty TraitObjectRepr)> {
+        match ty.kind() {
+            TypeKind::TraitObject { qname, repr } => Some((qname, repr)),
+            _ => None,
+        }
+    }
+
+    /// If `ty` is a resource, return its name and how it is shared between threads.
+    fn resource_ty<'ty>(&self, ty: &'ty Ty) -> Option<(&'ty QualifiedName, ThreadSafety)> {
+        let TypeKind::UserType { qname, repr: UserTypeRepr::Direct } = ty.kind() else {
+            return None;
+        };
+        match self.idl.item(qname) {
+            Some(Item::Resource(resource)) => Some((qname, ThreadSafety::of(resource))),
+            _ => None,
+        }
+    }
+
+    /// An expression creating the Python class of the resource `qname` holding `value`, see `generate_resource`.
+    fn resource_object(&self, qname: &QualifiedName, thread_safety: ThreadSafety, value: &str) -> String {
+        let live_object = if self.debug_bindings { ", crate::gluegun_debug::LiveObject::new()" } else { "" };
+        let value = match self.idl.item(qname) {
+            Some(Item::Resource(resource)) if shares_ownership(resource) => format!("std::sync::Arc::new({value})"),
+            _ => value.to_string(),
+        };
+        match thread_safety {
+            ThreadSafety::Locked => {
+                format!("{}(std::sync::Mutex::new(Some({value})){live_object})", resource_class(qname))
+            }
+            ThreadSafety::Shared | ThreadSafety::Unsendable => {
+                format!("{}(Some({value}){live_object})", resource_class(qname))
+            }
+        }
+    }
+
+    /// If `ty` is an enum without associated data, return its name.
+    fn enum_ty<'ty>(&self, ty: &'ty Ty) -> Option<&'ty QualifiedName> {
+        let TypeKind::UserType { qname, repr: UserTypeRepr::Direct } = ty.kind() else {
+            return None;
+        };
+        match self.idl.item(qname) {
+            Some(Item::Enum(_)) => Some(qname),
+            _ => None,
+        }
+    }
+
+    /// If `ty` (or, for a newtype, its field) has no pyo3 conversion and is exchanged through its string form,
+    /// return how it is represented in Python. This is the case of UUIDs and socket addresses,
+    /// and of IP addresses if `textual-types` is `string` (otherwise pyo3 converts them to `ipaddress` objects).
+    fn textual_ty(&self, ty: &Ty) -> Option<TextualPy> {
+        let ty = match self.idl.newtype(ty) {
+            Some((_, newtype)) => newtype.ty(),
+            None => ty,
+        };
+        match (ty.kind(), self.metadata.textual_types) {
+            (TypeKind::Uuid { .. }, TextualTypes::Native) => Some(TextualPy::Uuid),
+            (TypeKind::NetAddr { repr }, TextualTypes::Native) if !repr.is_socket() => None,
+            (TypeKind::Uuid { .. } | TypeKind::NetAddr { .. }, _) => Some(TextualPy::Str),
+            _ => None,
+        }
+    }
+
+    /// If `ty` is an enum implementing `std::error::Error` (see [`Idl::error_item`]) whose arms can be raised
+    /// as exceptions, return its name. Error variants with fields we cannot convert are raised as a `RuntimeError`.
+    fn error_ty<'ty>(&self, ty: &'ty Ty) -> Option<&'ty QualifiedName> {
+        let TypeKind::UserType { qname, .. } = ty.kind() else {
+            return None;
+        };
+        match self.idl.error_item(ty)? {
+            (_, Item::Variant(variant)) if check_variant_fields(qname, variant).is_err() => None,
+            _ => Some(qname),
+        }
+    }
+
+    /// If `ty` is a variant or a record, which are exchanged with Python as instances of dataclasses (see `py_gen`),
+    /// check that it can be converted to and from Python and return its name.
+    fn dataclass_ty<'ty>(&self, ty: &'ty Ty) -> anyhow::Result<Option<&'ty QualifiedName>> {
+        let TypeKind::UserType { qname, repr: UserTypeRepr::Direct } = ty.kind() else {
+            return Ok(None);
+        };
+        match self.idl.item(qname) {
+            Some(Item::Variant(variant)) => check_variant_fields(qname, variant)?,
+            Some(Item::Record(record)) => check_record_fields(qname, record)?,
+            _ => return Ok(None),
+        }
+        Ok(Some(qname))
+    }
+
+    /// If `ty` is a stream, check that it can be returned to Python and return an expression converting
+    /// one of its items, `item`, into a Python object (a `PyResult<PyObject>`, with `py` in scope).
+    fn stream_ty(&mut self, ty: &Ty) -> anyhow::Result<Option<String>> {
+        let TypeKind::Stream { item, repr } = ty.kind() else {
+            return Ok(None);
+        };
+        match repr {
+            StreamRepr::ImplStream(auto_traits) if auto_traits.send() => (),
+            _ => anyhow::bail!(
+                "{span}: the Python backend produces stream items on a separate thread, \
+                 so streams must be `Send` (e.g., `impl Stream<Item = T> + Send + 'static`)",
+                span = ty.span(),
+            ),
+        }
+        self.features.insert(Feature::AsyncRuntime);
+        self.features.insert(Feature::Streams);
+        Ok(Some(self.stream_item_expr(item)?))
+    }
+
+    /// An expression converting the stream item `item` into a Python object, see `stream_ty`.
+    fn stream_item_expr(&mut self, item: &Ty) -> anyhow::Result<String> {
+        if let Some(dataclass_qname) = self.dataclass_ty(item)? {
+            return Ok(format!("{}(py, item)", to_py_fn(dataclass_qname)));
+        }
+        if let Some(enum_qname) = self.enum_ty(item) {
+            return Ok(format!(
+                "pyo3::IntoPyObjectExt::into_py_any({}::from(item), py)",
+                enum_class(enum_qname)
+            ));
+        }
+        let value = match self.resource_ty(item) {
+            Some((resource_qname, thread_safety @ (ThreadSafety::Locked | ThreadSafety::Shared))) => {
+                self.resource_object(resource_qname, thread_safety, "item")
+            }
+            Some((resource_qname, ThreadSafety::Unsendable)) => anyhow::bail!(
+                "{span}: `{qname}` is not `Send`, so the Python backend cannot produce it as a stream item",
+                span = item.span(),
+                qname = resource_qname.colon_colon(),
+            ),
+            None => {
+                // Checks that pyo3 can convert the item.
+                self.generic_ty(item)?;
+                "item".to_string()
+            }
+        };
+        Ok(format!("pyo3::IntoPyObjectExt::into_py_any({value}, py)"))
+    }
+
+    /// Generate a Python class wrapping the resource, e.g.
+    ///
+    /// ```rust,ignore
+    /// #[pyo3::pyclass(name = "Counter", module = "my_crate")]
+    /// pub struct my_crate_Counter(Option<my_crate::Counter>);
+    /// ```
+    ///
+    /// The Rust value is `None` once a method taking `self` by value has consumed it;
+    /// calling a method on the Python object after that raises a `ValueError`.
+    /// Resources with methods taking `self: Arc<Self>` are held in an `Arc`, which those methods are given a clone of;
+    /// methods taking `&mut self` or `self` then raise a `ValueError` while Rust code still holds a clone.
+    ///
+    /// Python objects can be used from any thread, so a resource that is `Send` but not `Sync`
+    /// is wrapped in a `Mutex`, and one that is not `Send` is marked `unsendable`,
+    /// which makes pyo3 raise an error when it is used from a thread other than the one that created it.
+    ///
+    /// With debug bindings, the class also holds a `gluegun_debug::LiveObject`, so that the object counts as live
+    /// until Python frees it (see `resource_object`).
+    fn generate_resource(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        resource: &Resource,
+    ) -> anyhow::Result<()> {
+        let class = resource_class(qname);
+        let name = qname.tail_name().to_string();
+        let module = self.metadata.python_module(self.idl, &qname.module_path()).dotted();
+        let thread_safety = ThreadSafety::of(resource);
+        let shared = shares_ownership(resource);
+        if shared && matches!(thread_safety, ThreadSafety::Locked) {
+            anyhow::bail!(
+                "`{}` is not `Sync`, so the Python backend cannot share it with its methods taking `self: Arc<Self>`",
+                qname.colon_colon(),
+            );
+        }
+        self.generate_cfg(lib_rs, qname)?;
+        match thread_safety {
+            ThreadSafety::Unsendable => write!(lib_rs, "#[pyo3::pyclass(name = {name:?}, module = {module:?}, unsendable)]")?,
+            ThreadSafety::Shared | ThreadSafety::Locked => write!(lib_rs, "#[pyo3::pyclass(name = {name:?}, module = {module:?})]")?,
+        }
+        self.register_class(qname, &class);
+        write!(lib_rs, "#[allow(non_camel_case_types)]")?;
+        let live_object = if self.debug_bindings { ", crate::gluegun_debug::LiveObject" } else { "" };
+        match thread_safety {
+            ThreadSafety::Locked => write!(
+                lib_rs,
+                "pub struct {class}(std::sync::Mutex<Option<{}>>{live_object});",
+                qname.colon_colon()
+            )?,
+            ThreadSafety::Shared | ThreadSafety::Unsendable if shared => write!(
+                lib_rs,
+                "pub struct {class}(Option<std::sync::Arc<{}>>{live_object});",
+                qname.colon_colon()
+            )?,
+            ThreadSafety::Shared | ThreadSafety::Unsendable => {
+                write!(lib_rs, "pub struct {class}(Option<{}>{live_object});", qname.colon_colon())?
+            }
+        }
+
+        let inherited_methods = self.idl.inherited_methods(resource);
+        if resource.methods().is_empty() && inherited_methods.is_empty() {
+            return Ok(());
+        }
+
+        // The methods of its interfaces are called like inherent methods, which requires their trait in scope.
+        let mut interfaces: Vec<&QualifiedName> = inherited_methods.iter().map(|&(interface, _)| interface).collect();
+        interfaces.dedup();
+        for interface in interfaces {
+            self.generate_cfg(lib_rs, qname)?;
+            write!(lib_rs, "use {} as _;", interface.colon_colon())?;
+        }
+
+        self.generate_cfg(lib_rs, qname)?;
+        write!(lib_rs, "#[pyo3::pymethods]")?;
+        write!(lib_rs, "impl {class} {{")?;
+        let methods = resource.methods().iter().chain(inherited_methods.iter().map(|&(_, method)| method));
+        for method in methods {
+            lib_rs.with_span(method.span(), |lib_rs| {
+                self.generate_python_method(lib_rs, qname, Holder::Resource(thread_safety), method)
+            })?;
+        }
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Generate a Python enum class mirroring the enum, and conversions between the two, e.g.
+    ///
+    /// ```rust,ignore
+    /// #[pyo3::pyclass(name = "Color", module = "my_crate", eq, eq_int)]
+    /// #[derive(Clone, Copy, PartialEq)]
+    /// pub enum my_crate_Color { Red, Green }
+    ///
+    /// impl From<my_crate::Color> for my_crate_Color { /* match on the arm */ }
+    /// impl From<my_crate_Color> for my_crate::Color { /* match on the arm */ }
+    /// ```
+    ///
+    /// Values of the enum are passed to and from Python as the class. Its methods convert the Python value
+    /// into the Rust enum and call the Rust method on it.
+    fn generate_enum(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        an_enum: &Enum,
+    ) -> anyhow::Result<()> {
+        let class = enum_class(qname);
+        let rust_path = qname.colon_colon();
+        let name = qname.tail_name().to_string();
+        let module = self.metadata.python_module(self.idl, &qname.module_path()).dotted();
+        self.generate_cfg(lib_rs, qname)?;
+        write!(lib_rs, "#[pyo3::pyclass(name = {name:?}, module = {module:?}, eq, eq_int)]")?;
+        self.register_class(qname, &class);
+        write!(lib_rs, "#[derive(Clone, Copy, PartialEq)]")?;
+        write!(lib_rs, "#[allow(non_camel_case_types)]")?;
+        write!(lib_rs, "pub enum {class} {{")?;
+        for arm in an_enum.arms() {
+            write!(lib_rs, "{},", arm.name())?;
+        }
+        write!(lib_rs, "}}")?;
+
+        for (from, to) in [(&rust_path, &class), (&class, &rust_path)] {
+            self.generate_cfg(lib_rs, qname)?;
+            write!(lib_rs, "impl From<{from}> for {to} {{")?;
+            write!(lib_rs, "fn from(value: {from}) -> Self {{")?;
+            write!(lib_rs, "match value {{")?;
+            for arm in an_enum.arms() {
+                write!(lib_rs, "{from}::{arm} => {to}::{arm},", arm = arm.name())?;
+            }
+            write!(lib_rs, "}}")?;
+            write!(lib_rs, "}}")?;
+            write!(lib_rs, "}}")?;
+        }
+
+        if an_enum.methods().is_empty() {
+            return Ok(());
+        }
+        self.generate_cfg(lib_rs, qname)?;
+        write!(lib_rs, "#[pyo3::pymethods]")?;
+        write!(lib_rs, "impl {class} {{")?;
+        for method in an_enum.methods() {
+            lib_rs.with_span(method.span(), |lib_rs| {
+                self.generate_python_method(lib_rs, qname, Holder::Enum, method)
+            })?;
+        }
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Generate an adapter implementing the trait of an interface by calling the methods
+    /// of a Python object, e.g.
+    ///
+    /// ```rust,ignore
+    /// #[allow(non_camel_case_types)]
+    /// pub struct my_crate_Listener(pyo3::PyObject);
+    ///
+    /// impl my_crate::Listener for my_crate_Listener {
+    ///     fn on_event(&self, name: &str) -> u32 {
+    ///         pyo3::Python::with_gil(|py| { /* call `self.0.on_event(name)` */ })
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Functions taking a trait object wrap the Python object they are given in the adapter
+    /// (see `generate_callable`). The methods of the trait cannot report a Python exception,
+    /// so the adapter panics with its message, which pyo3 raises as a `PanicException`.
+    fn generate_interface_adapter(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        interface: &Interface,
+    ) -> anyhow::Result<()> {
+        let adapter = interface_adapter(qname);
+        self.generate_cfg(lib_rs, qname)?;
+        write!(lib_rs, "#[allow(non_camel_case_types)]")?;
+        write!(lib_rs, "pub struct {adapter}(pyo3::PyObject);")?;
+        self.generate_cfg(lib_rs, qname)?;
+        write!(lib_rs, "impl {} for {adapter} {{", qname.colon_colon())?;
+        for method in interface.methods() {
+            lib_rs.with_span(method.span(), |lib_rs| self.generate_interface_method(lib_rs, qname, method))?;
+        }
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    fn generate_interface_method(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        method: &Method,
+    ) -> anyhow::Result<()> {
+        let name = method.name();
+        let signature = method.signature();
+        check_interface_method(qname, method)?;
+
+        let self_param = match method.category().self_kind() {
+            Some(SelfKind::ByRefMut) => "&mut self",
+            _ => "&self",
+        };
+        let inputs: Vec<String> = std::iter::once(self_param.to_string())
+            .chain(
+                signature
+                    .inputs()
+                    .iter()
+                    .map(|input| format!("{}: {}", input.name(), input.refd_ty().rust_spelling())),
+            )
+            .collect();
+        let output_ty = signature.output_ty();
+        if output_ty.is_unit() {
+            write!(lib_rs, "fn {name}({}) {{", inputs.join(", "))?;
+        } else {
+            write!(lib_rs, "fn {name}({}) -> {} {{", inputs.join(", "), output_ty.main_ty().rust_spelling())?;
+        }
+
+        let python_name = self.metadata.member_name(name).to_string();
+        write!(lib_rs, "pyo3::Python::with_gil(|py| {{")?;
+        if signature.inputs().is_empty() {
+            write!(lib_rs, "self.0.call_method0(py, {python_name:?})")?;
+        } else {
+            let arguments: Vec<String> = signature.inputs().iter().map(|input| input.name().to_string()).collect();
+            write!(lib_rs, "self.0.call_method1(py, {python_name:?}, ({},))", arguments.join(", "))?;
+        }
+        if output_ty.is_unit() {
+            write!(lib_rs, ".map(|_| ())")?;
+        } else {
+            write!(lib_rs, ".and_then(|result| result.extract(py))")?;
+        }
+        write!(
+            lib_rs,
+            ".unwrap_or_else(|err| panic!(\"`{}.{name}` raised an exception: {{err}}\"))",
+            qname.tail_name(),
+        )?;
+        write!(lib_rs, "}})")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    fn generate_python_method(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        holder: Holder,
+        method: &Method,
+    ) -> anyhow::Result<()> {
+        match method.category() {
+            MethodCategory::Constructor => write!(lib_rs, "#[new]")?,
+            MethodCategory::NamedConstructor => write!(lib_rs, "#[classmethod]")?,
+            MethodCategory::StaticMethod => write!(lib_rs, "#[staticmethod]")?,
+            MethodCategory::Getter(_) => write!(lib_rs, "#[getter]")?,
+            MethodCategory::InstanceMethod(_) | MethodCategory::BuilderMethod(_) => {}
+            category => anyhow::bail!("{}: unsupported method category: `{category:?}`", method.span()),
+        }
+        if !matches!(method.category(), MethodCategory::Constructor) {
+            self.generate_python_name(lib_rs, method.name(), &self.metadata.method_name(method))?;
+        }
+
+        let callee = match (method.category(), method.category().self_kind()) {
+            (MethodCategory::Getter(field), _) => Callee::Field(format!("this.{field}")),
+            (_, Some(_)) => Callee::Call(format!("this.{}", method.name())),
+            (_, None) => Callee::Call(format!("{}::{}", qname.colon_colon(), method.name())),
+        };
+        // Enums are values, so their builder methods simply return the new value.
+        let builder = matches!(method.category(), MethodCategory::BuilderMethod(_))
+            && matches!(holder, Holder::Resource(_));
+        let shared = matches!(self.idl.item(qname), Some(Item::Resource(resource)) if shares_ownership(resource));
+        let receiver = method
+            .category()
+            .self_kind()
+            .map(|self_kind| Receiver { qname, holder, self_kind, builder, shared });
+        let class_method = matches!(method.category(), MethodCategory::NamedConstructor);
+        self.generate_callable(lib_rs, method.name(), receiver, class_method, false, callee, method.signature())
+    }
+
     fn generate_python_function(
         &mut self,
         lib_rs: &mut CodeWriter<'_>,
@@ -78,32 +950,398 @@ impl<'idl> RustCodeGenerator<'idl> {
         function: &Function,
     ) -> anyhow::Result<()> {
         // Write function definition with #[pyfunction] attribute
+        self.generate_cfg(lib_rs, qname)?;
         write!(lib_rs, "#[pyo3::pyfunction]")?;
-        write!(lib_rs, "fn {}(", function.name())?;
+        self.generate_python_name(lib_rs, function.name(), &self.metadata.member_name(function.name()))?;
+        self.generate_callable(lib_rs, function.name(), None, false, false, Callee::Call(qname.colon_colon()), function.signature())?;
+        self.register_function(qname, function.name());
+
+        // With `pack-params`, functions with many parameters also take them packed in a dataclass,
+        // as `{name}_packed(params)`.
+        if self.idl.params_record(qname).is_some() {
+            let packed_name = Name::from(format!("{}_packed", function.name()));
+            self.generate_cfg(lib_rs, qname)?;
+            write!(lib_rs, "#[pyo3::pyfunction]")?;
+            self.generate_python_name(lib_rs, &packed_name, &self.metadata.member_name(&packed_name))?;
+            self.generate_callable(lib_rs, &packed_name, None, false, true, Callee::Call(qname.colon_colon()), function.signature())?;
+            self.register_function(qname, &packed_name);
+        }
+        Ok(())
+    }
+
+    /// Rename the function or method `name` in Python if its `python_name` differs (e.g., due to `rename-all`
+    /// or `overloads`), e.g. `#[pyo3(name = "toUtf8")]` for `to_utf8` in camelCase.
+    fn generate_python_name(&self, lib_rs: &mut CodeWriter<'_>, name: &Name, python_name: &Name) -> anyhow::Result<()> {
+        if python_name != name {
+            write!(lib_rs, "#[pyo3(name = {:?})]", python_name.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Generate a Rust function `name` callable from Python that converts its arguments,
+    /// calls (or, for a getter, reads) `callee`, and converts the result.
+    ///
+    /// For methods, `receiver` describes the resource or enum and the kind of `self`: the Rust value is taken
+    /// from the Python object as `this` (see `generate_resource` and `generate_enum`). Class methods (`class_method`)
+    /// take the class as their first argument, which is not used. If `packed` is true, the function takes a single
+    /// argument, the dataclass packing the parameters (see `Idl::pack_params`), and reads them from its attributes.
+    #[allow(clippy::too_many_arguments)]
+    fn generate_callable(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        name: &Name,
+        receiver: Option<Receiver<'_>>,
+        class_method: bool,
+        packed: bool,
+        callee: Callee,
+        signature: &Signature,
+    ) -> anyhow::Result<()> {
+        let output_ty = signature.output_ty();
+        let dataclass_output = self.dataclass_ty(output_ty.main_ty().ty())?;
+        let enum_output = self.enum_ty(output_ty.main_ty().ty());
+        let newtype_output = self.idl.newtype(output_ty.main_ty().ty());
+        let custom_output = self.custom_ty(output_ty.main_ty().ty())?;
+        let resource_output = self.resource_ty(output_ty.main_ty().ty());
+        let textual_output = self.textual_ty(output_ty.main_ty().ty());
+        let stream_output = self.stream_ty(output_ty.main_ty().ty())?;
+
+        write!(lib_rs, "fn {name}(")?;
+
+        // Methods that consume the Rust value need to take it out of the Python object.
+        // A `Mutex` gives mutable access through `&self`. Builder methods return the object itself,
+        // so they take a reference to it (`slf`) rather than `self`.
+        // Enums are copied out of the Python object, so their methods cannot modify it.
+        if let Some(receiver) = &receiver {
+            match (receiver.self_kind, receiver.holder) {
+                (SelfKind::ByRef | SelfKind::ByValue, Holder::Enum) => write!(lib_rs, "&self,")?,
+                (SelfKind::ByRefMut, Holder::Enum) => anyhow::bail!(
+                    "`{}::{name}`: the Python backend passes enums by value, \
+                     so it only supports their methods taking `self` or `&self`",
+                    receiver.qname.colon_colon()
+                ),
+                _ if receiver.builder && matches!(receiver.holder, Holder::Resource(ThreadSafety::Locked)) => {
+                    write!(lib_rs, "slf: pyo3::PyRef<'_, Self>,")?
+                }
+                _ if receiver.builder => write!(lib_rs, "mut slf: pyo3::PyRefMut<'_, Self>,")?,
+                (SelfKind::ByRef | SelfKind::ByArc, Holder::Resource(_))
+                | (SelfKind::ByRefMut | SelfKind::ByValue, Holder::Resource(ThreadSafety::Locked)) => {
+                    write!(lib_rs, "&self,")?
+                }
+                (SelfKind::ByRefMut | SelfKind::ByValue, _) => write!(lib_rs, "&mut self,")?,
+                (SelfKind::ByRc, _) => anyhow::bail!(
+                    "`{}::{name}`: the Python backend does not support methods taking `self: Rc<Self>`, \
+                     as Python objects can be used from any thread",
+                    receiver.qname.colon_colon()
+                ),
+                (self_kind, _) => anyhow::bail!(
+                    "`{}::{name}`: methods taking `{self_kind:?}` are not supported by the Python backend",
+                    receiver.qname.colon_colon()
+                ),
+            }
+        }
+        if class_method {
+            write!(lib_rs, "_cls: &pyo3::Bound<'_, pyo3::types::PyType>,")?;
+        }
+
+        // Variants and records (and values exchanged through their string form) are converted to Python objects,
+        // which requires holding the GIL.
+        if dataclass_output.is_some() || textual_output.is_some() {
+            write!(lib_rs, "py: pyo3::Python<'_>,")?;
+        }
 
         // Write function parameters
-        for input in function.signature().inputs() {
-            let input_type = self.rust_argument_ty(input)?;
-            write!(lib_rs, "{}: {},", input.name(), input_type)?;
+        let mut parameters = vec![];
+        let mut dataclass_inputs = vec![];
+        let mut enum_inputs = vec![];
+        let mut newtype_inputs = vec![];
+        let mut custom_inputs = vec![];
+        let mut textual_inputs = vec![];
+        let mut trait_object_inputs = vec![];
+        for input in signature.inputs() {
+            if let Some((interface_qname, repr)) = self.trait_object_ty(input.refd_ty().ty()) {
+                trait_object_inputs.push((input.name(), interface_qname, repr));
+                parameters.push((input.name(), "pyo3::PyObject".to_string()));
+                continue;
+            }
+            let textual_input = self.textual_ty(input.refd_ty().ty());
+            if let Some(textual) = textual_input {
+                textual_inputs.push((input.name(), textual));
+            }
+            if let Some(enum_qname) = self.enum_ty(input.refd_ty().ty()) {
+                enum_inputs.push((input.name(), enum_qname));
+            }
+            let input_type = match self.dataclass_ty(input.refd_ty().ty())? {
+                Some(dataclass_qname) => {
+                    dataclass_inputs.push((input.name(), dataclass_qname));
+                    "pyo3::Bound<'_, pyo3::PyAny>".to_string()
+                }
+                None => match self.idl.newtype(input.refd_ty().ty()) {
+                    Some((newtype_qname, newtype)) => {
+                        newtype_inputs.push((input.name(), newtype_qname, newtype));
+                        match textual_input {
+                            Some(_) => "pyo3::Bound<'_, pyo3::PyAny>".to_string(),
+                            None => self.generic_ty(newtype.ty())?,
+                        }
+                    }
+                    None => match (textual_input, self.custom_ty(input.refd_ty().ty())?) {
+                        (Some(_), _) => "pyo3::Bound<'_, pyo3::PyAny>".to_string(),
+                        (None, Some((path, type_override))) => {
+                            custom_inputs.push((input.name(), type_override));
+                            type_override.repr_or(path).to_string()
+                        }
+                        (None, None) => self.rust_argument_ty(input)?,
+                    },
+                },
+            };
+            parameters.push((input.name(), input_type));
+        }
+        if packed {
+            write!(lib_rs, "params: pyo3::Bound<'_, pyo3::PyAny>,")?;
+        } else {
+            for (name, ty) in &parameters {
+                write!(lib_rs, "{name}: {ty},")?;
+            }
+        }
+
+        // Write return type if function has output. Borrowed data (e.g., `-> &str`)
+        // is copied out into the corresponding owned type (e.g., `String`).
+        // Errors are raised as a Python `RuntimeError` carrying the error's message,
+        // and so are panics and failures to convert variants and records; error enums are raised as the exception
+        // of their arm instead (see `error_ty`), and `std::io::Error`s as an `OSError` carrying their `errno`
+        // (see `gluegun_py_util::io_error`). Functions returning `()` (or `Result<(), E>`)
+        // return `None` to Python. Newtypes are returned as the value of their field
+        // and third-party types as the `repr` type of their override. Resources are wrapped
+        // in their Python class (see `generate_resource`). Types without a pyo3 conversion
+        // are returned through their string form (see `textual_ty`), and streams as async iterators
+        // (see `generate_stream_class`).
+        let main_ty = match (dataclass_output, textual_output, newtype_output, custom_output, resource_output) {
+            _ if receiver.as_ref().is_some_and(|receiver| receiver.builder) => "pyo3::Py<Self>".to_string(),
+            _ if stream_output.is_some() => "gluegun_Stream".to_string(),
+            (Some(_), _, _, _, _) => "pyo3::PyObject".to_string(),
+            (None, Some(_), _, _, _) => "pyo3::PyObject".to_string(),
+            (None, None, Some((_, newtype)), _, _) => self.generic_ty(newtype.ty())?,
+            (None, None, None, Some((path, type_override)), _) => type_override.repr_or(path).to_string(),
+            (None, None, None, None, Some((resource_qname, _))) => resource_class(resource_qname),
+            (None, None, None, None, None) => self.generic_ty(output_ty.main_ty().ty())?,
+        };
+        // Calls catch panics, which are raised as a `RuntimeError` (see `gluegun_py_util::catch_panic`).
+        let fallible = matches!(callee, Callee::Call(_))
+            || output_ty.error_ty().is_some()
+            || dataclass_output.is_some()
+            || !dataclass_inputs.is_empty()
+            || !textual_inputs.is_empty()
+            || textual_output.is_some()
+            || receiver.is_some()
+            || newtype_inputs.iter().any(|(_, _, newtype)| *newtype.validated())
+            || packed;
+        if fallible {
+            write!(lib_rs, ") -> pyo3::PyResult<{main_ty}> {{")?;
+        } else if output_ty.is_unit() {
+            write!(lib_rs, ") {{")?;
+        } else {
+            write!(lib_rs, ") -> {main_ty} {{")?;
+        }
+
+        // Packed parameters are read from the dataclass, as they would be passed to the unpacked form.
+        // The attribute is kept alive, as the parameter may borrow from it (e.g., `&str`).
+        if packed {
+            write!(lib_rs, "use pyo3::types::PyAnyMethods;")?;
+            for (name, ty) in &parameters {
+                write!(lib_rs, "let {name} = params.getattr({:?})?;", self.metadata.member_name(name).to_string())?;
+                write!(lib_rs, "let {name}: {ty} = {name}.extract()?;")?;
+            }
+        }
+
+        // Types without a pyo3 conversion arrive as strings (or objects whose `str` is their string form);
+        // parse them. This comes first, as the value may then be wrapped in a newtype.
+        for (name, textual) in &textual_inputs {
+            write!(
+                lib_rs,
+                "let {name} = <{}<_> as gluegun_py_util::FromPy>::from_py({:?}, &{name})?.0;",
+                textual.wrapper(),
+                name.to_string(),
+            )?;
         }
 
-        // Write return type if function has output
-        let main_ty =
-            self.generic_ty(function.signature().output_ty().main_ty().owned_or_err()?)?;
-        write!(lib_rs, ") -> {main_ty} {{")?;
+        // Variants and records arrive as Python objects; convert them into the Rust values.
+        for (name, dataclass_qname) in &dataclass_inputs {
+            write!(lib_rs, "let {name} = {}(&{name})?;", from_py_fn(dataclass_qname))?;
+        }
+
+        // Enums arrive as their Python class (see `generate_enum`); convert them into the Rust enum.
+        for (name, enum_qname) in &enum_inputs {
+            write!(lib_rs, "let {name} = {}::from({name});", enum_qname.colon_colon())?;
+        }
+
+        // Newtypes arrive as the value of their field; wrap them. Values rejected by the `TryFrom` impl
+        // of a validated newtype are raised as a `ValueError` (see `gluegun_py_util::invalid_value`).
+        for (name, newtype_qname, newtype) in &newtype_inputs {
+            let wrapped = newtype.wrap_expr(&newtype_qname.colon_colon(), &name.to_string());
+            if *newtype.validated() {
+                write!(
+                    lib_rs,
+                    "let {name} = {wrapped}.map_err(|err| gluegun_py_util::invalid_value({:?}, err))?;",
+                    name.to_string(),
+                )?;
+            } else {
+                write!(lib_rs, "let {name} = {wrapped};")?;
+            }
+        }
+
+        // Third-party types arrive as the `repr` type of their override; convert them.
+        for (name, type_override) in &custom_inputs {
+            if type_override.from_repr().is_some() {
+                write!(lib_rs, "let {name} = {};", type_override.from_repr_expr(&name.to_string()))?;
+            }
+        }
+
+        // Trait objects arrive as Python objects; wrap them in the adapter of their interface.
+        for (name, interface_qname, repr) in &trait_object_inputs {
+            let adapter = interface_adapter(interface_qname);
+            match repr {
+                TraitObjectRepr::BoxDyn(_) => write!(lib_rs, "let {name} = Box::new({adapter}({name}));")?,
+                _ => write!(lib_rs, "let {name} = {adapter}({name});")?,
+            }
+        }
+
+        // The Rust value of a resource is gone once a method has consumed it.
+        // A `Mutex` stays locked for the duration of the call. Enums are converted into the Rust enum.
+        // Methods taking `self: Arc<Self>` are given a clone of the `Arc` (see `generate_resource`).
+        if let Some(receiver) = &receiver {
+            let take = match receiver.self_kind {
+                SelfKind::ByRef => "as_ref()",
+                SelfKind::ByRefMut => "as_mut()",
+                SelfKind::ByArc => "as_ref().map(std::sync::Arc::clone)",
+                _ => "take()",
+            };
+            let object = if receiver.builder { "slf" } else { "self" };
+            let value = match receiver.holder {
+                Holder::Enum => None,
+                Holder::Resource(ThreadSafety::Locked) => {
+                    let mutability = if let SelfKind::ByRef = receiver.self_kind { "" } else { "mut " };
+                    write!(
+                        lib_rs,
+                        "let {mutability}guard = {object}.0.lock().unwrap_or_else(std::sync::PoisonError::into_inner);"
+                    )?;
+                    Some("guard".to_string())
+                }
+                Holder::Resource(ThreadSafety::Shared | ThreadSafety::Unsendable) => Some(format!("{object}.0")),
+            };
+            let class = receiver.qname.tail_name().to_string();
+            match value {
+                Some(value) => {
+                    write!(lib_rs, "let this = {value}.{take}.ok_or_else(|| gluegun_py_util::consumed_error({class:?}))?;")?;
+                    // The value is only borrowed mutably or moved out of the `Arc` if nothing else holds it.
+                    match receiver.self_kind {
+                        SelfKind::ByRefMut if receiver.shared => write!(
+                            lib_rs,
+                            "let this = std::sync::Arc::get_mut(this).ok_or_else(|| gluegun_py_util::shared_error({class:?}))?;"
+                        )?,
+                        SelfKind::ByValue if receiver.shared => {
+                            write!(lib_rs, "let this = match std::sync::Arc::try_unwrap(this) {{")?;
+                            write!(lib_rs, "Ok(this) => this,")?;
+                            write!(lib_rs, "Err(this) => {{")?;
+                            write!(lib_rs, "{value} = Some(this);")?;
+                            write!(lib_rs, "return Err(gluegun_py_util::shared_error({class:?}));")?;
+                            write!(lib_rs, "}}")?;
+                            write!(lib_rs, "}};")?;
+                        }
+                        _ => {}
+                    }
+                }
+                None => write!(lib_rs, "let this = {}::from(*self);", receiver.qname.colon_colon())?,
+            }
+        }
 
         // Write function body. Arguments will a suitable Rust owned type
         // but they may need to be borrowed or adapted to fit what the callee function
         // expects.
-        write!(lib_rs, "{}(", qname.colon_colon())?;
-        for input in function.signature().inputs() {
-            let name = input.name();
-            match input.refd_ty() {
-                RefdTy::Owned(..) => write!(lib_rs, "&{name}, ")?,
-                RefdTy::Ref(..) => write!(lib_rs, "&{name}, ")?,
+        let (mut prefix, mut suffix) = (String::new(), String::new());
+        let binds_result = (fallible
+            || custom_output.is_some()
+            || resource_output.is_some()
+            || textual_output.is_some()
+            || stream_output.is_some())
+            && !output_ty.is_unit();
+        if binds_result {
+            prefix.push_str("let result = ");
+        }
+        // Validated newtypes are unwrapped with `From`, the others by reading their field.
+        let validated_output = newtype_output.as_ref().is_some_and(|(_, newtype)| *newtype.validated());
+        if validated_output {
+            prefix.push_str(&format!("<{}>::from(", newtype_output.unwrap().1.ty().rust_owned_spelling()));
+        }
+        if output_ty.requires_copy_out() {
+            prefix.push_str("std::borrow::ToOwned::to_owned(");
+        }
+        match output_ty.error_ty().as_ref().map(|error_ty| (error_ty.kind(), self.error_ty(error_ty))) {
+            Some((_, Some(error_qname))) => suffix.push_str(&format!(".map_err({})?", to_py_err_fn(error_qname))),
+            Some((TypeKind::Error { repr: ErrorRepr::IoError }, None)) => {
+                suffix.push_str(".map_err(gluegun_py_util::io_error)?")
+            }
+            Some((_, None)) => suffix.push_str(".map_err(gluegun_py_util::runtime_error)?"),
+            None => {}
+        }
+        if output_ty.requires_copy_out() {
+            suffix.push(')');
+        }
+        if validated_output {
+            suffix.push(')');
+        } else if newtype_output.is_some() {
+            suffix.push_str(".0");
+        }
+        if enum_output.is_some() {
+            suffix.push_str(".into()");
+        }
+        if binds_result || output_ty.is_unit() {
+            suffix.push(';');
+        }
+        match callee {
+            Callee::Call(callee) => {
+                write!(lib_rs, "{prefix}gluegun_py_util::catch_panic(|| {callee}(")?;
+                for input in signature.inputs() {
+                    let name = input.name();
+                    match input.refd_ty() {
+                        RefdTy::Owned(..) => write!(lib_rs, "{name}, ")?,
+                        RefdTy::Ref(..) => write!(lib_rs, "&{name}, ")?,
+                    }
+                }
+                write!(lib_rs, "))?{suffix}")?;
+            }
+            Callee::Field(field) => write!(lib_rs, "{prefix}&{field}{suffix}")?,
+        }
+        let result = if let Some(item_expr) = &stream_output {
+            format!("gluegun_Stream::new(result, |py, item| {item_expr})")
+        } else {
+            match (textual_output, custom_output, resource_output) {
+                (Some(textual), _, _) => textual.output_expr("result"),
+                (None, Some((_, type_override)), _) => type_override.to_repr_expr("result"),
+                (None, None, Some((resource_qname, thread_safety))) => {
+                    self.resource_object(resource_qname, thread_safety, "result")
+                }
+                (None, None, None) => "result".to_string(),
+            }
+        };
+        if let Some(receiver) = receiver.as_ref().filter(|receiver| receiver.builder) {
+            // The Python object now holds the value the builder method returned.
+            match receiver.holder {
+                Holder::Resource(ThreadSafety::Locked) => {
+                    write!(lib_rs, "*guard = Some(result);")?;
+                    write!(lib_rs, "drop(guard);")?;
+                }
+                _ if receiver.shared => write!(lib_rs, "slf.0 = Some(std::sync::Arc::new(result));")?,
+                _ => write!(lib_rs, "slf.0 = Some(result);")?,
+            }
+            write!(lib_rs, "Ok(slf.into())")?;
+        } else if fallible {
+            match dataclass_output {
+                Some(dataclass_qname) => write!(lib_rs, "{}(py, result)", to_py_fn(dataclass_qname))?,
+                None if output_ty.is_unit() => write!(lib_rs, "Ok(())")?,
+                None => write!(lib_rs, "Ok({result})")?,
             }
+        } else if binds_result {
+            write!(lib_rs, "{result}")?;
         }
-        write!(lib_rs, ")")?;
         write!(lib_rs, "}}")?;
 
         Ok(())
@@ -136,7 +1374,7 @@ impl<'idl> RustCodeGenerator<'idl> {
             }
 
             TypeKind::Set { element, repr } => {
-                let name = self.map_name(repr)?;
+                let name = self.set_name(repr)?;
                 Ok(format!("{name}<{}>", self.generic_ty(element)?))
             }
 
@@ -147,7 +1385,7 @@ impl<'idl> RustCodeGenerator<'idl> {
 
             TypeKind::Vec {
                 element,
-                repr: VecRepr::SliceRef,
+                repr: VecRepr::SliceRef | VecRepr::ImplIntoIterator,
             } => Ok(format!("Vec<{}>", self.generic_ty(element)?)),
 
             TypeKind::Path {
@@ -183,13 +1421,15 @@ impl<'idl> RustCodeGenerator<'idl> {
 
             TypeKind::Tuple { .. } => Ok(self.generic_ty(input_ty)?),
 
+            TypeKind::NetAddr { .. } => Ok(self.generic_ty(input_ty)?),
+
             TypeKind::Scalar(scalar) => Ok(scalar.to_string()),
 
             TypeKind::Future { .. } => Ok(self.generic_ty(input_ty)?),
 
             TypeKind::Error { .. } => Ok(self.generic_ty(input_ty)?),
 
-            TypeKind::UserType { .. } => Ok(self.generic_ty(input_ty)?),
+            TypeKind::UserType { repr: UserTypeRepr::Direct, .. } => Ok(self.generic_ty(input_ty)?),
 
             _ => anyhow::bail!(
                 "{span}: unsupported type for `{name}`: {ty} (`{ty:?}`)",
@@ -202,11 +1442,11 @@ impl<'idl> RustCodeGenerator<'idl> {
 
     fn map_name(&mut self, v: &MapSetRepr) -> anyhow::Result<String> {
         match v {
-            MapSetRepr::Hash => Ok(format!("HashMap")),
-            MapSetRepr::BTree => Ok(format!("BTreeMap")),
+            MapSetRepr::Hash => Ok(format!("std::collections::HashMap")),
+            MapSetRepr::BTree => Ok(format!("std::collections::BTreeMap")),
             MapSetRepr::Index => {
-                self.features.push("indexmap");
-                Ok(format!("IndexMap"))
+                self.features.insert(Feature::IndexMap);
+                Ok(format!("indexmap::IndexMap"))
             }
             _ => anyhow::bail!("unknown map representation: `{v:?}`"),
         }
@@ -214,12 +1454,10 @@ impl<'idl> RustCodeGenerator<'idl> {
 
     fn set_name(&mut self, v: &MapSetRepr) -> anyhow::Result<String> {
         match v {
-            MapSetRepr::Hash => Ok(format!("HashSet")),
-            MapSetRepr::BTree => Ok(format!("BTreeSet")),
-            MapSetRepr::Index => {
-                self.features.push("indexmap");
-                Ok(format!("IndexSet"))
-            }
+            MapSetRepr::Hash => Ok(format!("std::collections::HashSet")),
+            MapSetRepr::BTree => Ok(format!("std::collections::BTreeSet")),
+            // pyo3's `indexmap` feature only covers `IndexMap`.
+            MapSetRepr::Index => anyhow::bail!("`IndexSet` is not supported by the Python backend"),
             _ => anyhow::bail!("unknown set representation: `{v:?}`"),
         }
     }
@@ -229,64 +1467,337 @@ impl<'idl> RustCodeGenerator<'idl> {
     ///
     /// Will only returned owned values.
     fn generic_ty(&mut self, ty: &Ty) -> anyhow::Result<String> {
-        match ty.kind() {
-            TypeKind::Map { key, value, repr } => Ok(format!(
-                "{}<{}, {}>",
-                self.map_name(repr)?,
-                self.generic_ty(key)?,
-                self.generic_ty(value)?
-            )),
-            TypeKind::Vec { element, repr } => match repr {
-                VecRepr::Vec | VecRepr::SliceRef => Ok(format!("Vec<{}>", self.generic_ty(element)?)),
-                _ => anyhow::bail!("unsupported: {repr:?}"),
-            },
-            TypeKind::Set { element, repr } => Ok(format!(
-                "{}<{}>",
-                self.set_name(repr)?,
-                self.generic_ty(element)?,
-            )),
-            TypeKind::Path { repr } => match repr {
-                gluegun_core::idl::PathRepr::PathBuf => Ok(format!("PathBuf")),
-                _ => anyhow::bail!("unsupported: {repr:?}"),
-            },
-            TypeKind::String { repr } => match repr {
-                StringRepr::String => Ok(format!("String")),
-                _ => anyhow::bail!("unsupported: {repr:?}"),
-            },
-            TypeKind::Option { element, repr } => match repr {
-                OptionRepr::Option => Ok(format!("Option<{}>", self.generic_ty(element)?)),
-                _ => anyhow::bail!("unsupported: {repr:?}"),
-            },
-            TypeKind::Result { ok, err, repr } => match repr {
-                ResultRepr::Result => Ok(format!(
-                    "Result<{}, {}>",
-                    self.generic_ty(ok)?,
-                    self.generic_ty(err)?
-                )),
-                _ => anyhow::bail!("unsupported: {repr:?}"),
-            },
-            TypeKind::Tuple { elements, repr } => match repr {
-                TupleRepr::Tuple(_) => Ok(format!(
-                    "({})",
-                    elements
-                        .iter()
-                        .map(|e| self.generic_ty(e))
-                        .collect::<anyhow::Result<Vec<_>>>()?
-                        .join(", ")
-                )),
-                _ => anyhow::bail!("unsupported: {repr:?}"),
-            },
-            TypeKind::Scalar(scalar) => Ok(scalar.to_string()),
-            TypeKind::Future { output, repr } => match repr {
-                FutureRepr::PinBoxDynFuture(_auto_traits) => Ok(format!(
-                    "Pin<Box<dyn Future<Output = {}>>>",
-                    self.generic_ty(output)?
-                )),
-                _ => anyhow::bail!("unsupported: {repr:?}"),
+        self.map_ty(ty)
+    }
+}
+
+/// Maps types to the (owned) Rust types used as generic arguments, see [`RustCodeGenerator::generic_ty`][].
+impl TypeMapper for RustCodeGenerator<'_> {
+    type Output = String;
+
+    fn map_map(&mut self, _ty: &Ty, key: String, value: String, repr: &MapSetRepr) -> anyhow::Result<String> {
+        Ok(format!("{}<{key}, {value}>", self.map_name(repr)?))
+    }
+
+    fn map_vec(&mut self, ty: &Ty, element: String, repr: &VecRepr) -> anyhow::Result<String> {
+        match repr {
+            VecRepr::Vec | VecRepr::SliceRef | VecRepr::ImplIntoIterator => Ok(format!("Vec<{element}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_set(&mut self, _ty: &Ty, element: String, repr: &MapSetRepr) -> anyhow::Result<String> {
+        Ok(format!("{}<{element}>", self.set_name(repr)?))
+    }
+
+    fn map_path(&mut self, ty: &Ty, repr: &PathRepr) -> anyhow::Result<String> {
+        match repr {
+            PathRepr::PathBuf | PathRepr::PathRef => Ok(format!("std::path::PathBuf")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    /// pyo3 converts IP addresses to and from `ipaddress` objects. Other types exchanged through their
+    /// string form are only converted as function arguments and return types (see `textual_ty`).
+    fn map_net_addr(&mut self, ty: &Ty, repr: &NetAddrRepr) -> anyhow::Result<String> {
+        match self.metadata.textual_types {
+            TextualTypes::Native if !repr.is_socket() => Ok(repr.rust_path()),
+            _ => textual_unsupported(ty),
+        }
+    }
+
+    fn map_uuid(&mut self, ty: &Ty, _repr: &UuidRepr) -> anyhow::Result<String> {
+        textual_unsupported(ty)
+    }
+
+    fn map_string(&mut self, ty: &Ty, repr: &StringRepr) -> anyhow::Result<String> {
+        match repr {
+            StringRepr::String | StringRepr::StrRef => Ok(format!("String")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_option(&mut self, ty: &Ty, element: String, repr: &OptionRepr) -> anyhow::Result<String> {
+        match repr {
+            OptionRepr::Option => Ok(format!("Option<{element}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_result(&mut self, ty: &Ty, ok: String, err: String, repr: &ResultRepr) -> anyhow::Result<String> {
+        match repr {
+            ResultRepr::Result => Ok(format!("Result<{ok}, {err}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_tuple(&mut self, ty: &Ty, elements: Vec<String>, repr: &TupleRepr) -> anyhow::Result<String> {
+        match repr {
+            TupleRepr::Tuple(_) => match &elements[..] {
+                [element] => Ok(format!("({element},)")),
+                _ => Ok(format!("({})", elements.join(", "))),
             },
-            TypeKind::Error { repr } => anyhow::bail!("unsupported: {repr:?}"),
-            TypeKind::UserType { qname } => Ok(format!("{}", qname.dotted())),
-            _ => todo!(),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_scalar(&mut self, _ty: &Ty, scalar: &Scalar) -> anyhow::Result<String> {
+        Ok(scalar.to_string())
+    }
+
+    fn map_future(&mut self, ty: &Ty, output: String, repr: &FutureRepr) -> anyhow::Result<String> {
+        match repr {
+            FutureRepr::PinBoxDynFuture(_auto_traits) => Ok(format!("Pin<Box<dyn Future<Output = {output}>>>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_user_type(&mut self, ty: &Ty, qname: &QualifiedName) -> anyhow::Result<String> {
+        if self.enum_ty(ty).is_some() {
+            return Ok(enum_class(qname));
         }
+        if self.idl.newtype(ty).is_some() {
+            anyhow::bail!(
+                "`{qname}` is a newtype, which the Python backend only supports as a function argument or return type",
+                qname = qname.colon_colon(),
+            );
+        }
+        Ok(format!("{}", qname.dotted()))
     }
+
+    fn map_custom(&mut self, ty: &Ty, path: &str) -> anyhow::Result<String> {
+        let type_override = self.type_overrides.expect(ty, path)?;
+        if type_override.to_repr().is_some() || type_override.from_repr().is_some() {
+            anyhow::bail!(
+                "{span}: `{path}` has conversions in its `type-overrides` entry, which the Python backend only applies to function arguments and return types",
+                span = ty.span(),
+            );
+        }
+        Ok(type_override.repr_or(path).to_string())
+    }
+}
+
+/// Check that all fields of the variant `qname` have types that we can convert to and from Python.
+/// Nested user types are not supported yet.
+fn check_variant_fields(qname: &QualifiedName, variant: &Variant) -> anyhow::Result<()> {
+    for arm in variant.arms() {
+        check_fields(&format!("{}::{}", qname.colon_colon(), arm.name()), arm.fields())?;
+    }
+    Ok(())
+}
+
+/// Check that all fields of the record `qname` have types that we can convert to and from Python
+/// (see [`check_variant_fields`]).
+fn check_record_fields(qname: &QualifiedName, record: &Record) -> anyhow::Result<()> {
+    check_fields(&qname.colon_colon(), record.fields())
+}
+
+/// Check that none of the `fields` of `owner` refers to a user type.
+fn check_fields(owner: &str, fields: &[Field]) -> anyhow::Result<()> {
+    struct FindUserType<'idl>(Option<&'idl QualifiedName>);
+
+    impl<'idl> Visitor<'idl> for FindUserType<'idl> {
+        fn visit_ty(&mut self, ty: &'idl Ty) -> anyhow::Result<()> {
+            if let TypeKind::UserType { qname, repr: _ } = ty.kind() {
+                self.0.get_or_insert(qname);
+            }
+            visit::walk_ty(self, ty)
+        }
+    }
+
+    for field in fields {
+        let mut visitor = FindUserType(None);
+        visitor.visit_ty(field.ty())?;
+        if let Some(user_type) = visitor.0 {
+            anyhow::bail!(
+                "field `{field}` of `{owner}` refers to `{user_type}`, which the Python backend cannot convert yet",
+                field = field.name(),
+                user_type = user_type.colon_colon(),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Check that the method of the interface `qname` only exchanges values that pyo3 converts as is,
+/// so that the adapter generated by `generate_interface_adapter` can pass them to Python and back.
+fn check_interface_method(qname: &QualifiedName, method: &Method) -> anyhow::Result<()> {
+    struct FindUnsupported<'idl>(Option<&'idl Ty>);
+
+    impl<'idl> Visitor<'idl> for FindUnsupported<'idl> {
+        fn visit_ty(&mut self, ty: &'idl Ty) -> anyhow::Result<()> {
+            match ty.kind() {
+                TypeKind::Map { repr: MapSetRepr::Hash | MapSetRepr::BTree, .. }
+                | TypeKind::Set { repr: MapSetRepr::Hash | MapSetRepr::BTree, .. }
+                | TypeKind::Vec { .. }
+                | TypeKind::Option { .. }
+                | TypeKind::Tuple { .. }
+                | TypeKind::Path { .. }
+                | TypeKind::String { .. }
+                | TypeKind::Scalar(_) => {}
+                _ => {
+                    self.0.get_or_insert(ty);
+                }
+            }
+            visit::walk_ty(self, ty)
+        }
+    }
+
+    let signature = method.signature();
+    let mut visitor = FindUnsupported(None);
+    visitor.visit_signature(signature)?;
+    if let Some(ty) = visitor.0 {
+        anyhow::bail!(
+            "{span}: `{qname}::{name}` exchanges `{ty}`, which Python implementations of an interface cannot handle yet",
+            span = ty.span(),
+            qname = qname.colon_colon(),
+            name = method.name(),
+        );
+    }
+    if signature.output_ty().requires_copy_out() || signature.output_ty().error_ty().is_some() {
+        anyhow::bail!(
+            "{span}: `{qname}::{name}` must return an owned value, not a reference or a `Result`, to be implemented in Python",
+            span = method.span(),
+            qname = qname.colon_colon(),
+            name = method.name(),
+        );
+    }
+    Ok(())
+}
+
+/// Report that `ty`, which is exchanged through its string form, appears somewhere other than
+/// as a function argument or return type (e.g., as the element of a `Vec`).
+fn textual_unsupported<T>(ty: &Ty) -> anyhow::Result<T> {
+    anyhow::bail!(
+        "{span}: `{ty}` is only supported by the Python backend as a function argument or return type",
+        span = ty.span(),
+    )
+}
+
+/// True if the fields are those of a tuple-like arm, which the IDL names `f0`, `f1`, etc.
+fn is_tuple_like(fields: &[Field]) -> bool {
+    !fields.is_empty()
+        && fields
+            .iter()
+            .enumerate()
+            .all(|(index, field)| *field.name().text() == format!("f{index}"))
+}
+
+/// Name of the function generated by `generate_variant_from_py` or `generate_record_from_py` for `qname`.
+fn from_py_fn(qname: &QualifiedName) -> String {
+    format!("{}_from_py", qname.to_string("_"))
+}
+
+/// How the Python class of a resource makes it safe to use from the threads of the interpreter.
+#[derive(Copy, Clone)]
+enum ThreadSafety {
+    /// The resource is `Send` and `Sync`, so it can be used from any thread as is.
+    Shared,
+
+    /// The resource is `Send` but not `Sync`, so calls are serialized through a `Mutex`.
+    Locked,
+
+    /// The resource is not `Send`, so pyo3 confines it to the thread that created it.
+    Unsendable,
+}
+
+impl ThreadSafety {
+    fn of(resource: &Resource) -> Self {
+        match (resource.auto_traits().send(), resource.auto_traits().sync()) {
+            (true, true) => ThreadSafety::Shared,
+            (true, false) => ThreadSafety::Locked,
+            (false, _) => ThreadSafety::Unsendable,
+        }
+    }
+}
+
+/// How the Python object that a method is called on holds the Rust value.
+#[derive(Copy, Clone)]
+enum Holder {
+    /// The object wraps a resource, which methods borrow or take (see `generate_resource`).
+    Resource(ThreadSafety),
+
+    /// The object is a value of the class mirroring an enum, which methods convert (see `generate_enum`).
+    Enum,
+}
+
+/// The `self` of a method on a resource or an enum.
+struct Receiver<'a> {
+    qname: &'a QualifiedName,
+    holder: Holder,
+    self_kind: &'a SelfKind,
+
+    /// True for a builder method (`fn with_x(self, ...) -> Self`), which puts the value it returns
+    /// back into the Python object and returns the object, so that calls can be chained.
+    builder: bool,
+
+    /// True if the resource is held in an `Arc` (see `shares_ownership`).
+    shared: bool,
+}
+
+/// True if the Python class of `resource` holds it in an `Arc`, which its methods taking `self: Arc<Self>` need.
+fn shares_ownership(resource: &Resource) -> bool {
+    resource
+        .methods()
+        .iter()
+        .any(|method| matches!(method.category().self_kind(), Some(SelfKind::ByArc)))
+}
+
+/// How a type exchanged through its string form (see `RustCodeGenerator::textual_ty`) is represented in Python.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum TextualPy {
+    /// A `str`.
+    Str,
+
+    /// A `uuid.UUID`. Strings are accepted as arguments too.
+    Uuid,
+}
+
+impl TextualPy {
+    /// The type of `gluegun_py_util` that converts the value (see `gluegun_py_util::FromPy` and `ToPy`).
+    fn wrapper(self) -> &'static str {
+        match self {
+            TextualPy::Str => "gluegun_py_util::Text",
+            TextualPy::Uuid => "gluegun_py_util::Uuid",
+        }
+    }
+
+    /// An expression converting the Rust value `expr` into a Python object.
+    fn output_expr(self, expr: &str) -> String {
+        format!("gluegun_py_util::ToPy::to_py({}({expr}), py)?", self.wrapper())
+    }
+}
+
+/// What a callable generated by `generate_callable` invokes.
+enum Callee {
+    /// A Rust function, e.g. `my_crate::greet`, or method, e.g. `this.add`, called with the arguments.
+    Call(String),
+
+    /// A field of the resource, e.g. `this.name` (see [`MethodCategory::Getter`]).
+    Field(String),
+}
+
+/// Name of the Python class generated by `generate_resource` for the resource `qname`.
+fn resource_class(qname: &QualifiedName) -> String {
+    qname.to_string("_")
+}
+
+/// Name of the Python class generated by `generate_enum` for the enum `qname`.
+fn enum_class(qname: &QualifiedName) -> String {
+    qname.to_string("_")
+}
+
+/// Name of the adapter generated by `generate_interface_adapter` for the interface `qname`.
+fn interface_adapter(qname: &QualifiedName) -> String {
+    qname.to_string("_")
+}
+
+/// Name of the function generated by `generate_variant_to_py` or `generate_record_to_py` for `qname`.
+fn to_py_fn(qname: &QualifiedName) -> String {
+    format!("{}_to_py", qname.to_string("_"))
+}
+
+/// Name of the function generated by `generate_error_variant_to_py_err` or `generate_error_enum_to_py_err` for `qname`.
+fn to_py_err_fn(qname: &QualifiedName) -> String {
+    format!("{}_to_py_err", qname.to_string("_"))
 }
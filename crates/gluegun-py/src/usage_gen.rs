@@ -0,0 +1,98 @@
+use gluegun_core::{
+    codegen::LibraryCrate,
+    idl::{Function, Idl, IsAsync, Item, QualifiedName, Variant},
+};
+
+use crate::metadata::PythonMetadata;
+
+/// Registers the build steps and a usage snippet for each function and enum with associated data,
+/// which gluegun assembles into the `README.md` of the generated crate, e.g.
+///
+/// ```python
+/// from my_crate import greet
+///
+/// result = greet(name)
+/// ```
+///
+/// It also registers the Python function or class generated for each item, along with its methods,
+/// for the `bindings.json` manifest.
+pub(crate) struct UsageGenerator<'idl> {
+    idl: &'idl Idl,
+    metadata: &'idl PythonMetadata,
+}
+
+impl<'idl> UsageGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl, metadata: &'idl PythonMetadata) -> Self {
+        Self { idl, metadata }
+    }
+
+    pub(crate) fn generate(self, lib: &mut LibraryCrate) {
+        lib.add_build_step(
+            "Build the native module and install it, along with the `python` package, into the current virtualenv",
+            "maturin develop",
+        );
+
+        for (qname, item) in self.idl.definitions() {
+            match item {
+                Item::Function(function) => lib.add_usage(qname, "python", function_usage(self.idl, self.metadata, qname, function)),
+                Item::Variant(variant) => lib.add_usage(qname, "python", variant_usage(self.idl, self.metadata, qname, variant)),
+                _ => {}
+            }
+            register_bindings(lib, self.idl, self.metadata, qname, item);
+        }
+    }
+}
+
+/// Register the function or class generated for `item` (e.g., `my_crate.greet`), and the methods of the class.
+fn register_bindings(lib: &mut LibraryCrate, idl: &Idl, metadata: &PythonMetadata, qname: &QualifiedName, item: &Item) {
+    let methods = match item {
+        Item::Function(function) => {
+            let name = metadata.member_name(function.name());
+            lib.add_binding(qname, format!("{}.{name}", metadata.python_module(idl, &qname.module_path()).dotted()));
+            return;
+        }
+        Item::Resource(resource) => resource.methods(),
+        Item::Record(record) => record.methods(),
+        Item::Newtype(newtype) => newtype.methods(),
+        Item::Variant(variant) => variant.methods(),
+        Item::Enum(an_enum) => an_enum.methods(),
+        Item::Interface(interface) => interface.methods(),
+        _ => return,
+    };
+
+    let symbol = metadata.python_name(idl, qname);
+    lib.add_binding(qname, &symbol);
+    for method in methods {
+        lib.add_member_binding(qname, method.name(), format!("{symbol}.{}", metadata.method_name(method)));
+    }
+}
+
+fn function_usage(idl: &Idl, metadata: &PythonMetadata, qname: &QualifiedName, function: &Function) -> String {
+    let arguments: Vec<String> = function
+        .signature()
+        .inputs()
+        .iter()
+        .map(|input| input.name().to_string())
+        .collect();
+    let await_kw = match function.signature().is_async() {
+        IsAsync::Yes => "await ",
+        IsAsync::No => "",
+    };
+    let result = if function.signature().output_ty().is_unit() { "" } else { "result = " };
+    format!(
+        "from {module} import {name}\n\n{result}{await_kw}{name}({arguments})",
+        module = metadata.python_module(idl, &qname.module_path()).dotted(),
+        name = metadata.member_name(function.name()),
+        arguments = arguments.join(", "),
+    )
+}
+
+fn variant_usage(idl: &Idl, metadata: &PythonMetadata, qname: &QualifiedName, variant: &Variant) -> String {
+    let name = qname.tail_name();
+    let mut usage = format!("from {module} import {name}\n\nmatch value:", module = metadata.python_module(idl, &qname.module_path()).dotted());
+    for arm in variant.arms() {
+        let fields: Vec<String> = arm.fields().iter().map(|field| field.name().to_string()).collect();
+        usage.push_str(&format!("\n    case {name}.{arm}({fields}):\n        ...", arm = arm.name(), fields = fields.join(", ")));
+    }
+    usage
+}
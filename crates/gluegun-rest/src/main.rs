@@ -0,0 +1,44 @@
+use anyhow::Context;
+use gluegun_core::{
+    cli::{GenerateCx, GlueGunHelper},
+    codegen::LibraryCrate,
+};
+
+mod openapi_gen;
+mod rs_gen;
+mod util;
+
+pub fn main() -> anyhow::Result<()> {
+    gluegun_core::cli::run(GlueGunRest)
+}
+
+/// Generates an [axum](https://docs.rs/axum) server crate exposing each function as a `POST` endpoint
+/// taking and returning JSON, along with an OpenAPI description of the routes.
+#[derive(Clone)]
+struct GlueGunRest;
+
+impl GlueGunHelper for GlueGunRest {
+    type Metadata = ();
+
+    fn name(&self) -> String {
+        "rest".to_string()
+    }
+
+    fn generate(self, cx: &mut GenerateCx, _metadata: &(), output: &mut LibraryCrate) -> anyhow::Result<()> {
+        util::check_schema_names(cx.idl())?;
+
+        output.add_dependency("axum").version("0.8");
+        output.add_dependency("serde").version("1").feature("derive");
+        output.add_dependency("tokio").version("1").feature("net");
+
+        rs_gen::RustCodeGenerator::new(cx.idl(), cx.type_overrides())
+            .generate(output)
+            .context("generating Rust sources")?;
+
+        openapi_gen::OpenApiGenerator::new(cx.idl())
+            .generate(output)
+            .context("generating OpenAPI description")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use gluegun_core::{
+    codegen::LibraryCrate,
+    idl::{export::json_schema, Function, Idl, Item, QualifiedName, Ty},
+};
+use serde_json::{json, Map, Value};
+
+use crate::util;
+
+/// Generates `openapi.json`, an [OpenAPI 3.1](https://spec.openapis.org/oas/v3.1.0) description of
+/// the routes served by the generated crate. Mirror types and request types are described as schemas
+/// under `components`; their serde representation is the one described by [`json_schema`][].
+pub(crate) struct OpenApiGenerator<'idl> {
+    idl: &'idl Idl,
+}
+
+impl<'idl> OpenApiGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl) -> Self {
+        Self { idl }
+    }
+
+    pub(crate) fn generate(self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        let mut schemas = Map::new();
+        let mut paths = Map::new();
+
+        schemas.insert(
+            "GlueGunError".to_string(),
+            json!({
+                "type": "object",
+                "properties": { "error": { "type": "string" } },
+                "required": ["error"],
+            }),
+        );
+
+        for (qname, item) in self.idl.definitions() {
+            if let Item::Function(function) = item {
+                schemas.insert(util::request_name(qname), self.request_schema(function)?);
+                paths.insert(util::route_path(qname), self.path_item(qname, function)?);
+            } else if let Some(schema) = json_schema::item_schema(self.idl, item, &schema_ref_uri)? {
+                schemas.insert(util::schema_name(qname), schema);
+            }
+        }
+
+        let document = json!({
+            "openapi": "3.1.0",
+            "info": {
+                "title": self.idl.crate_name().to_string(),
+                "version": self.idl.crate_metadata().version().as_deref().unwrap_or("0.0.0"),
+            },
+            "paths": paths,
+            "components": { "schemas": schemas },
+        });
+
+        // The pretty-printed JSON is indented the same way `CodeWriter` would indent it.
+        let mut openapi_json = lib.add_file("openapi.json")?;
+        for line in serde_json::to_string_pretty(&document)?.lines() {
+            write!(openapi_json, "{}", line.trim_start())?;
+        }
+
+        Ok(())
+    }
+
+    fn ty_schema(&self, ty: &Ty) -> anyhow::Result<Value> {
+        Ok(json_schema::ty_schema(self.idl, ty, &schema_ref_uri)?)
+    }
+
+    fn request_schema(&self, function: &Function) -> anyhow::Result<Value> {
+        let mut properties = Map::new();
+        let mut required = vec![];
+        for input in function.signature().inputs() {
+            let ty = input.refd_ty().ty();
+            properties.insert(input.name().to_string(), self.ty_schema(ty)?);
+            if !util::is_option(ty) {
+                required.push(input.name().to_string());
+            }
+        }
+        Ok(json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        }))
+    }
+
+    fn path_item(&self, qname: &QualifiedName, function: &Function) -> anyhow::Result<Value> {
+        let output_ty = function.signature().output_ty();
+
+        let mut responses = Map::new();
+        if output_ty.is_unit() {
+            responses.insert(
+                "204".to_string(),
+                json!({ "description": "The function returned successfully." }),
+            );
+        } else {
+            responses.insert(
+                "200".to_string(),
+                json!({
+                    "description": "The function returned successfully.",
+                    "content": { "application/json": { "schema": self.ty_schema(output_ty.main_ty().ty())? } },
+                }),
+            );
+        }
+        if output_ty.error_ty().is_some() {
+            responses.insert(
+                "500".to_string(),
+                json!({
+                    "description": "The function returned an error.",
+                    "content": { "application/json": { "schema": schema_ref("GlueGunError") } },
+                }),
+            );
+        }
+
+        let operation = json!({
+            "operationId": util::handler_name(qname),
+            "requestBody": {
+                "required": true,
+                "content": { "application/json": { "schema": schema_ref(&util::request_name(qname)) } },
+            },
+            "responses": responses,
+        });
+
+        Ok(json!({ "post": operation }))
+    }
+}
+
+/// Mirror types are described under their own names (see [`util::schema_name`][]).
+fn schema_ref_uri(qname: &QualifiedName) -> String {
+    format!("#/components/schemas/{}", util::schema_name(qname))
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
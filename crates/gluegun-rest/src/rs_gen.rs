@@ -0,0 +1,417 @@
+use gluegun_core::{
+    cli::TypeOverrides,
+    codegen::{copy_out, CodeWriter, LibraryCrate},
+    idl::{
+        Enum, Function, Idl, Item, MapSetRepr, Newtype, OptionRepr, PathRepr, QualifiedName, Record, RefdTy, Scalar,
+        StringRepr, TupleRepr, Ty, TypeKind, UserTypeRepr, Variant, VecRepr,
+    },
+    type_mapper::{self, TypeMapper},
+};
+
+use crate::util::{self, convert, needs_conversion, Direction};
+
+/// Generates the server crate. Each record, variant, and enum gets a mirror type deriving
+/// `serde::Serialize` and `serde::Deserialize` (e.g., `Point` for `my_crate::geometry::Point`)
+/// along with `From` conversions in both directions. Each function gets a request type
+/// with a field per argument and a handler, which are assembled into a `router()`.
+/// Third-party types are exchanged as the `repr` type of their override (see [`TypeOverrides`][]).
+pub(crate) struct RustCodeGenerator<'idl> {
+    idl: &'idl Idl,
+    type_overrides: &'idl TypeOverrides,
+}
+
+impl<'idl> RustCodeGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl, type_overrides: &'idl TypeOverrides) -> Self {
+        Self { idl, type_overrides }
+    }
+
+    pub(crate) fn generate(mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        let mut lib_rs = lib.add_file("src/lib.rs")?;
+
+        self.generate_error(&mut lib_rs)?;
+
+        let mut functions = vec![];
+        for (qname, item) in self.idl.definitions() {
+            write!(lib_rs, "")?;
+            match item {
+                Item::Record(record) => lib_rs.with_span(record.span(), |lib_rs| self.generate_record(lib_rs, qname, record))?,
+                Item::Newtype(newtype) => lib_rs.with_span(newtype.span(), |lib_rs| self.generate_newtype(lib_rs, qname, newtype))?,
+                Item::Variant(variant) => lib_rs.with_span(variant.span(), |lib_rs| self.generate_variant(lib_rs, qname, variant))?,
+                Item::Enum(an_enum) => lib_rs.with_span(an_enum.span(), |lib_rs| self.generate_enum(lib_rs, qname, an_enum))?,
+                Item::Function(function) => {
+                    lib_rs.with_span(function.span(), |lib_rs| self.generate_function(lib_rs, qname, function))?;
+                    functions.push(qname);
+                }
+                _ => anyhow::bail!(
+                    "`{}`: only records, newtypes, variants, enums, and functions are currently supported by the rest backend",
+                    qname.colon_colon()
+                ),
+            }
+        }
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "/// The routes for all functions, each accepting a JSON request via `POST`.")?;
+        write!(lib_rs, "pub fn router() -> axum::Router {{")?;
+        write!(lib_rs, "axum::Router::new()")?;
+        for qname in functions {
+            write!(
+                lib_rs,
+                ".route({:?}, axum::routing::post({}))",
+                util::route_path(qname),
+                handler_fn_name(qname)
+            )?;
+        }
+        write!(lib_rs, "}}")?;
+
+        write!(lib_rs, "")?;
+        write!(lib_rs, "/// Serve [`router`][] on `addr` until the process is terminated.")?;
+        write!(lib_rs, "pub async fn serve(addr: impl tokio::net::ToSocketAddrs) -> std::io::Result<()> {{")?;
+        write!(lib_rs, "let listener = tokio::net::TcpListener::bind(addr).await?;")?;
+        write!(lib_rs, "axum::serve(listener, router()).await")?;
+        write!(lib_rs, "}}")?;
+
+        Ok(())
+    }
+
+    /// Errors returned from Rust are reported as a `500 Internal Server Error`
+    /// with a JSON body like `{"error": "..."}` carrying the error's message.
+    fn generate_error(&mut self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "#[derive(Debug, serde::Serialize)]")?;
+        write!(lib_rs, "pub struct GlueGunError {{")?;
+        write!(lib_rs, "pub error: String,")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "impl axum::response::IntoResponse for GlueGunError {{")?;
+        write!(lib_rs, "fn into_response(self) -> axum::response::Response {{")?;
+        write!(
+            lib_rs,
+            "(axum::http::StatusCode::INTERNAL_SERVER_ERROR, axum::Json(self)).into_response()"
+        )?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Generate `From` impls converting between the mirror type and the original type `qname`
+    /// in both directions. `body` generates the body of `from`, given the source and target type
+    /// and the direction of the conversion.
+    fn generate_conversions(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        mut body: impl FnMut(&mut CodeWriter<'_>, &str, &str, Direction) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let mirror = util::schema_name(qname);
+        let original = qname.colon_colon();
+        for (source, target, direction) in [(&original, &mirror, Direction::Export), (&mirror, &original, Direction::Import)] {
+            write!(lib_rs, "")?;
+            write!(lib_rs, "impl From<{source}> for {target} {{")?;
+            write!(lib_rs, "fn from(value: {source}) -> Self {{")?;
+            body(lib_rs, source, target, direction)?;
+            write!(lib_rs, "}}")?;
+            write!(lib_rs, "}}")?;
+        }
+        Ok(())
+    }
+
+    fn generate_record(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, record: &Record) -> anyhow::Result<()> {
+        util::check_no_methods(qname, record.methods().is_empty())?;
+        util::check_named_fields(record.span(), record.fields())?;
+
+        write!(lib_rs, "#[derive(serde::Serialize, serde::Deserialize)]")?;
+        write!(lib_rs, "pub struct {} {{", util::schema_name(qname))?;
+        for field in record.fields() {
+            write!(lib_rs, "pub {}: {},", field.name(), self.map_ty(field.ty())?)?;
+        }
+        write!(lib_rs, "}}")?;
+
+        let type_overrides = self.type_overrides;
+        self.generate_conversions(lib_rs, qname, |lib_rs, _source, target, direction| {
+            write!(lib_rs, "{target} {{")?;
+            for field in record.fields() {
+                let name = field.name();
+                write!(
+                    lib_rs,
+                    "{name}: {},",
+                    convert(&format!("value.{name}"), field.ty(), type_overrides, direction)
+                )?;
+            }
+            write!(lib_rs, "}}")?;
+            Ok(())
+        })
+    }
+
+    /// Newtypes are (de)serialized as their field, like serde does for the original type.
+    fn generate_newtype(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, newtype: &Newtype) -> anyhow::Result<()> {
+        util::check_no_methods(qname, newtype.methods().is_empty())?;
+        if *newtype.validated() {
+            anyhow::bail!("`{}`: validated newtypes (`#[gluegun::marshal_as]`) are not yet supported by the rest backend", qname.colon_colon());
+        }
+
+        write!(lib_rs, "#[derive(serde::Serialize, serde::Deserialize)]")?;
+        write!(lib_rs, "#[serde(transparent)]")?;
+        write!(lib_rs, "pub struct {}(pub {});", util::schema_name(qname), self.map_ty(newtype.ty())?)?;
+
+        let type_overrides = self.type_overrides;
+        self.generate_conversions(lib_rs, qname, |lib_rs, _source, target, direction| {
+            write!(lib_rs, "{target}({})", convert("value.0", newtype.ty(), type_overrides, direction))?;
+            Ok(())
+        })
+    }
+
+    fn generate_variant(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        variant: &Variant,
+    ) -> anyhow::Result<()> {
+        util::check_no_methods(qname, variant.methods().is_empty())?;
+        for arm in variant.arms() {
+            util::check_named_fields(arm.span(), arm.fields())?;
+        }
+
+        write!(lib_rs, "#[derive(serde::Serialize, serde::Deserialize)]")?;
+        write!(lib_rs, "pub enum {} {{", util::schema_name(qname))?;
+        for arm in variant.arms() {
+            if arm.fields().is_empty() {
+                write!(lib_rs, "{},", arm.name())?;
+            } else {
+                write!(lib_rs, "{} {{", arm.name())?;
+                for field in arm.fields() {
+                    write!(lib_rs, "{}: {},", field.name(), self.map_ty(field.ty())?)?;
+                }
+                write!(lib_rs, "}},")?;
+            }
+        }
+        write!(lib_rs, "}}")?;
+
+        let type_overrides = self.type_overrides;
+        self.generate_conversions(lib_rs, qname, |lib_rs, source, target, direction| {
+            write!(lib_rs, "match value {{")?;
+            for arm in variant.arms() {
+                let name = arm.name();
+                let field_names = arm.fields().iter().map(|field| field.name().to_string()).collect::<Vec<_>>();
+                let conversions = arm
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        let conversion = convert(&field.name().to_string(), field.ty(), type_overrides, direction);
+                        format!("{}: {conversion}", field.name())
+                    })
+                    .collect::<Vec<_>>();
+                write!(
+                    lib_rs,
+                    "{source}::{name} {{ {} }} => {target}::{name} {{ {} }},",
+                    field_names.join(", "),
+                    conversions.join(", "),
+                )?;
+            }
+            write!(lib_rs, "}}")?;
+            Ok(())
+        })
+    }
+
+    fn generate_enum(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, an_enum: &Enum) -> anyhow::Result<()> {
+        util::check_no_methods(qname, an_enum.methods().is_empty())?;
+
+        write!(lib_rs, "#[derive(serde::Serialize, serde::Deserialize)]")?;
+        write!(lib_rs, "pub enum {} {{", util::schema_name(qname))?;
+        for arm in an_enum.arms() {
+            write!(lib_rs, "{},", arm.name())?;
+        }
+        write!(lib_rs, "}}")?;
+
+        self.generate_conversions(lib_rs, qname, |lib_rs, source, target, _direction| {
+            write!(lib_rs, "match value {{")?;
+            for arm in an_enum.arms() {
+                let name = arm.name();
+                write!(lib_rs, "{source}::{name} => {target}::{name},")?;
+            }
+            write!(lib_rs, "}}")?;
+            Ok(())
+        })
+    }
+
+    fn generate_function(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        function: &Function,
+    ) -> anyhow::Result<()> {
+        let signature = function.signature();
+        let request = util::request_name(qname);
+
+        write!(lib_rs, "#[derive(serde::Deserialize)]")?;
+        write!(lib_rs, "pub struct {request} {{")?;
+        for input in signature.inputs() {
+            write!(lib_rs, "pub {}: {},", input.name(), self.map_ty(input.refd_ty().ty())?)?;
+        }
+        write!(lib_rs, "}}")?;
+
+        let output_ty = signature.output_ty();
+        let main_ty = self.map_ty(output_ty.main_ty().ty())?;
+        write!(lib_rs, "")?;
+        let binding = if signature.inputs().is_empty() { "_request" } else { "request" };
+        // Unit-returning functions (including `Result<(), E>`) answer `204 No Content`.
+        let success_ty = if output_ty.is_unit() {
+            "axum::http::StatusCode".to_string()
+        } else {
+            format!("axum::Json<{main_ty}>")
+        };
+        let response_ty = match output_ty.error_ty() {
+            Some(_) => format!("Result<{success_ty}, GlueGunError>"),
+            None => success_ty,
+        };
+        write!(
+            lib_rs,
+            "async fn {}(axum::Json({binding}): axum::Json<{request}>) -> {response_ty} {{",
+            handler_fn_name(qname)
+        )?;
+
+        // Arguments are received as owned values (converted from the mirror types where needed)
+        // and borrowed if the wrapped function expects a reference. Third-party types are not annotated
+        // as the IDL does not know their generic arguments.
+        for input in signature.inputs() {
+            let ty = input.refd_ty().ty();
+            let name = input.name();
+            if needs_conversion(ty) {
+                let conversion = convert(&format!("request.{name}"), ty, self.type_overrides, Direction::Import);
+                match ty.kind() {
+                    TypeKind::Custom { .. } => write!(lib_rs, "let {name} = {conversion};")?,
+                    _ => write!(lib_rs, "let {name}: {} = {conversion};", ty.rust_owned_spelling())?,
+                }
+            } else {
+                write!(lib_rs, "let {name} = request.{name};")?;
+            }
+        }
+
+        let arguments: Vec<String> = signature
+            .inputs()
+            .iter()
+            .map(|input| match input.refd_ty() {
+                RefdTy::Owned(..) => input.name().to_string(),
+                RefdTy::Ref(..) => format!("&{}", input.name()),
+            })
+            .collect();
+        let mut call = format!("{}({})", qname.colon_colon(), arguments.join(","));
+        if output_ty.error_ty().is_some() {
+            call.push_str(".map_err(|err| GlueGunError { error: err.to_string() })?");
+        }
+        if !output_ty.is_unit() {
+            write!(lib_rs, "let result = ")?;
+        }
+        write!(lib_rs, "{};", copy_out(output_ty, &call))?;
+
+        let result = if output_ty.is_unit() {
+            "axum::http::StatusCode::NO_CONTENT".to_string()
+        } else {
+            format!(
+                "axum::Json({})",
+                convert("result", output_ty.main_ty().ty(), self.type_overrides, Direction::Export)
+            )
+        };
+        match output_ty.error_ty() {
+            Some(_) => write!(lib_rs, "Ok({result})")?,
+            None => write!(lib_rs, "{result}")?,
+        }
+        write!(lib_rs, "}}")?;
+
+        Ok(())
+    }
+}
+
+/// The prefix keeps handlers from colliding with `router` and `serve`.
+fn handler_fn_name(qname: &QualifiedName) -> String {
+    format!("handle_{}", util::handler_name(qname))
+}
+
+/// Maps types to the owned Rust types used in requests and responses,
+/// with user types mapped to their mirror types.
+impl TypeMapper for RustCodeGenerator<'_> {
+    type Output = String;
+
+    fn map_map(&mut self, ty: &Ty, key: String, value: String, repr: &MapSetRepr) -> anyhow::Result<String> {
+        // JSON objects are keyed by strings; serde also accepts integers, which it stringifies.
+        let TypeKind::Map { key: key_ty, .. } = ty.kind() else {
+            unreachable!("map_map called for `{ty}`")
+        };
+        match key_ty.kind() {
+            TypeKind::String { .. } | TypeKind::Scalar(Scalar::I8 | Scalar::I16 | Scalar::I32 | Scalar::I64)
+            | TypeKind::Scalar(Scalar::U8 | Scalar::U16 | Scalar::U32 | Scalar::U64) => (),
+            _ => return type_mapper::unsupported(ty),
+        }
+        match repr {
+            MapSetRepr::Hash => Ok(format!("std::collections::HashMap<{key}, {value}>")),
+            MapSetRepr::BTree => Ok(format!("std::collections::BTreeMap<{key}, {value}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_set(&mut self, ty: &Ty, element: String, repr: &MapSetRepr) -> anyhow::Result<String> {
+        // Mirror types do not implement `Hash` or `Ord`, so they cannot be set elements.
+        if matches!(ty.kind(), TypeKind::Set { element, .. } if needs_conversion(element)) {
+            return type_mapper::unsupported(ty);
+        }
+        match repr {
+            MapSetRepr::Hash => Ok(format!("std::collections::HashSet<{element}>")),
+            MapSetRepr::BTree => Ok(format!("std::collections::BTreeSet<{element}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_vec(&mut self, ty: &Ty, element: String, repr: &VecRepr) -> anyhow::Result<String> {
+        match repr {
+            VecRepr::Vec | VecRepr::SliceRef | VecRepr::ImplIntoIterator => Ok(format!("Vec<{element}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_path(&mut self, ty: &Ty, repr: &PathRepr) -> anyhow::Result<String> {
+        match repr {
+            PathRepr::PathBuf | PathRepr::PathRef => Ok("std::path::PathBuf".to_string()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_string(&mut self, ty: &Ty, repr: &StringRepr) -> anyhow::Result<String> {
+        match repr {
+            StringRepr::String | StringRepr::StrRef => Ok("String".to_string()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_option(&mut self, ty: &Ty, element: String, repr: &OptionRepr) -> anyhow::Result<String> {
+        match repr {
+            OptionRepr::Option => Ok(format!("Option<{element}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_tuple(&mut self, ty: &Ty, elements: Vec<String>, _repr: &TupleRepr) -> anyhow::Result<String> {
+        // `()` is fine as a return type (the handler answers `204 No Content`).
+        if elements.is_empty() {
+            Ok("()".to_string())
+        } else {
+            type_mapper::unsupported(ty)
+        }
+    }
+
+    fn map_scalar(&mut self, _ty: &Ty, scalar: &Scalar) -> anyhow::Result<String> {
+        Ok(scalar.to_string())
+    }
+
+    fn map_user_type(&mut self, ty: &Ty, qname: &QualifiedName) -> anyhow::Result<String> {
+        let schema_name = match self.idl.item(qname) {
+            Some(Item::Record(_) | Item::Newtype(_) | Item::Variant(_) | Item::Enum(_)) => util::schema_name(qname),
+            _ => return type_mapper::unsupported(ty),
+        };
+        match ty.kind() {
+            TypeKind::UserType { repr: UserTypeRepr::Boxed, .. } => Ok(format!("Box<{schema_name}>")),
+            _ => Ok(schema_name),
+        }
+    }
+
+    fn map_custom(&mut self, ty: &Ty, path: &str) -> anyhow::Result<String> {
+        Ok(self.type_overrides.expect(ty, path)?.repr_or(path).to_string())
+    }
+}
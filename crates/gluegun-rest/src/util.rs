@@ -0,0 +1,133 @@
+use gluegun_core::{
+    cli::TypeOverrides,
+    idl::{Field, Idl, Item, QualifiedName, Span, Ty, TypeKind, UserTypeRepr},
+};
+
+/// The name of the mirror type (and OpenAPI schema) for the user type `qname`,
+/// e.g., `Point` for `my_crate::geometry::Point`.
+pub(crate) fn schema_name(qname: &QualifiedName) -> String {
+    qname.tail_name().upper_camel_case().to_string()
+}
+
+/// The request type for the function `qname`, e.g., `GeometryAreaRequest` for `my_crate::geometry::area`.
+pub(crate) fn request_name(qname: &QualifiedName) -> String {
+    let rpc: String = qname.names()[1..]
+        .iter()
+        .map(|name| name.upper_camel_case().to_string())
+        .collect();
+    format!("{rpc}Request")
+}
+
+/// The route for the function `qname`, e.g., `/geometry/area` for `my_crate::geometry::area`.
+pub(crate) fn route_path(qname: &QualifiedName) -> String {
+    qname.names()[1..]
+        .iter()
+        .map(|name| format!("/{name}"))
+        .collect()
+}
+
+/// The name identifying the function `qname` in the generated code and as the OpenAPI `operationId`,
+/// e.g., `geometry_area` for `my_crate::geometry::area`.
+pub(crate) fn handler_name(qname: &QualifiedName) -> String {
+    qname.names()[1..]
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Mirror types and request types are all defined at the root of the generated crate and
+/// in the `components` of the OpenAPI description, so their names must be unique.
+pub(crate) fn check_schema_names(idl: &Idl) -> anyhow::Result<()> {
+    let mut names = std::collections::BTreeMap::new();
+    for (qname, item) in idl.definitions() {
+        let name = match item {
+            Item::Function(_) => request_name(qname),
+            _ => schema_name(qname),
+        };
+        if let Some(previous) = names.insert(name, qname) {
+            anyhow::bail!(
+                "`{}` and `{}` would generate types with the same name, which is not supported by the rest backend",
+                previous.colon_colon(),
+                qname.colon_colon()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The IDL names the fields of tuple structs and tuple-like variant arms `f0`, `f1`, etc,
+/// but we generate types with named fields.
+pub(crate) fn check_named_fields(span: &Span, fields: &[Field]) -> anyhow::Result<()> {
+    let tuple_like = !fields.is_empty()
+        && fields
+            .iter()
+            .enumerate()
+            .all(|(index, field)| *field.name().text() == format!("f{index}"));
+    if tuple_like {
+        anyhow::bail!("{span}: tuple-like types are not supported by the rest backend");
+    }
+    Ok(())
+}
+
+pub(crate) fn check_no_methods(qname: &QualifiedName, no_methods: bool) -> anyhow::Result<()> {
+    if !no_methods {
+        anyhow::bail!("`{}`: methods are not yet supported by the rest backend", qname.colon_colon());
+    }
+    Ok(())
+}
+
+/// True if `ty` is an `Option`, in which case serde permits the field to be omitted.
+pub(crate) fn is_option(ty: &Ty) -> bool {
+    matches!(ty.kind(), TypeKind::Option { .. })
+}
+
+/// The direction of a conversion done by [`convert`].
+#[derive(Clone, Copy)]
+pub(crate) enum Direction {
+    /// From the original types to the types used in requests and responses.
+    Export,
+
+    /// From the types used in requests and responses to the original types.
+    Import,
+}
+
+/// True if `ty` contains a user type, whose values must be converted to or from the mirror type,
+/// or a third-party type, whose values may have to be converted to or from the `repr` type of its override.
+pub(crate) fn needs_conversion(ty: &Ty) -> bool {
+    match ty.kind() {
+        TypeKind::UserType { .. } | TypeKind::Custom { .. } => true,
+        TypeKind::Vec { element, .. } | TypeKind::Option { element, .. } => needs_conversion(element),
+        TypeKind::Map { value, .. } => needs_conversion(value),
+        _ => false,
+    }
+}
+
+/// Return an expression converting `expr` (of type `ty`) between the original types and
+/// the mirror types in the given `direction` (for user types the `From` impls go both ways,
+/// but third-party types are converted with the snippets of their override).
+pub(crate) fn convert(expr: &str, ty: &Ty, type_overrides: &TypeOverrides, direction: Direction) -> String {
+    if !needs_conversion(ty) {
+        return expr.to_string();
+    }
+    match ty.kind() {
+        TypeKind::Vec { element, .. } => {
+            format!("{expr}.into_iter().map(|e| {}).collect()", convert("e", element, type_overrides, direction))
+        }
+        TypeKind::Option { element, .. } => {
+            format!("{expr}.map(|e| {})", convert("e", element, type_overrides, direction))
+        }
+        TypeKind::Map { value, .. } => format!(
+            "{expr}.into_iter().map(|(k, v)| (k, {})).collect()",
+            convert("v", value, type_overrides, direction)
+        ),
+        TypeKind::Custom { path } => match (type_overrides.get(path), direction) {
+            (Some(type_override), Direction::Export) => type_override.to_repr_expr(expr),
+            (Some(type_override), Direction::Import) => type_override.from_repr_expr(expr),
+            (None, _) => expr.to_string(),
+        },
+        // Boxed user types (e.g., in recursive records) map to a boxed mirror type.
+        TypeKind::UserType { repr: UserTypeRepr::Boxed, .. } => format!("Box::new((*{expr}).into())"),
+        _ => format!("{expr}.into()"),
+    }
+}
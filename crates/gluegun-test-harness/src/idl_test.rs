@@ -17,8 +17,11 @@ pub fn idl_tests() -> anyhow::Result<()> {
 }
 
 fn assemble_idl_tests() -> anyhow::Result<Vec<IdlTest>> {
+    // The tests are read by absolute path, which the IDL records; the snapshots render the paths relative to the
+    // current directory (see `canonical_text_options`), so that they do not depend on where they are run.
+    let idl_tests_dir = std::env::current_dir()?.join("idl-tests");
     let mut tests = vec![];
-    for entry in std::fs::read_dir("idl-tests").with_context(|| "failed to read `idl-tests` directory")? {
+    for entry in std::fs::read_dir(&idl_tests_dir).with_context(|| "failed to read `idl-tests` directory")? {
         let entry = entry.with_context(|| "reading directory entry from `idl`")?;
         let path = entry.path();
         if path.is_dir() {
@@ -75,25 +78,166 @@ fn run_idl_test(test: &IdlTest) -> anyhow::Result<()> {
             test.rs_path.display()
         )
     })?;
-    let parsed_idl = gluegun_idl::Parser::new()
+    // Each `.rs` file in a directory with the same name as the test (e.g., `foo/bar.rs` for `foo.rs`)
+    // is parsed as an extern crate (e.g., `bar`).
+    let mut parser = gluegun_idl::Parser::new().with_doc_examples(true);
+    let extern_crates_dir = test.rs_path.with_extension("");
+    if extern_crates_dir.is_dir() {
+        let mut extern_rs_paths = vec![];
+        for entry in std::fs::read_dir(&extern_crates_dir)
+            .with_context(|| format!("failed to read `{}`", extern_crates_dir.display()))?
+        {
+            let path = entry?.path();
+            if is_eq(&path, Path::extension, "rs") {
+                extern_rs_paths.push(path);
+            }
+        }
+        extern_rs_paths.sort();
+
+        for extern_rs_path in extern_rs_paths {
+            let Some(extern_crate_name) = extern_rs_path.file_stem().and_then(|s| s.to_str()) else {
+                anyhow::bail!("non-utf8 file name `{}`", extern_rs_path.display());
+            };
+            parser = parser.with_extern_crate(extern_crate_name, &extern_rs_path, &extern_rs_path);
+        }
+    }
+
+    // A `.custom_types` file lists the paths of third-party types to accept, one per line.
+    let custom_types_path = test.rs_path.with_extension("custom_types");
+    if custom_types_path.exists() {
+        let custom_types = std::fs::read_to_string(&custom_types_path)
+            .with_context(|| format!("failed to read `{}`", custom_types_path.display()))?;
+        for path in custom_types.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            parser = parser.with_custom_type(path);
+        }
+    }
+
+    // A `.features` file lists the enabled features of the crate, one per line.
+    let features_path = test.rs_path.with_extension("features");
+    if features_path.exists() {
+        let features = std::fs::read_to_string(&features_path)
+            .with_context(|| format!("failed to read `{}`", features_path.display()))?;
+        let features: Vec<&str> = features.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+        parser = parser.with_crate_features(crate_name, features);
+    }
+
+    // A `.cfg` file lists the cfg options that are set, one per line (e.g., `unix` or `target_os = "linux"`).
+    let cfg_path = test.rs_path.with_extension("cfg");
+    if cfg_path.exists() {
+        let cfg = std::fs::read_to_string(&cfg_path)
+            .with_context(|| format!("failed to read `{}`", cfg_path.display()))?;
+        for option in cfg.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            parser = match option.split_once('=') {
+                Some((name, value)) => parser.with_cfg(name.trim(), Some(value.trim().trim_matches('"'))),
+                None => parser.with_cfg(option, None),
+            };
+        }
+    }
+
+    // An `.edition` file gives the edition of the crate (e.g., `2015`).
+    let edition_path = test.rs_path.with_extension("edition");
+    if edition_path.exists() {
+        let edition = std::fs::read_to_string(&edition_path)
+            .with_context(|| format!("failed to read `{}`", edition_path.display()))?;
+        parser = parser.with_edition(edition.trim().parse()?);
+    }
+
+    // A `.errors` file lists the errors expected from parsing, one per line, along with their locations.
+    // The definitions they concern are left out (see `Parser::with_error_recovery`) and the rest is checked as usual.
+    let errors_path = test.rs_path.with_extension("errors");
+    let expects_errors = errors_path.exists();
+    parser = parser.with_error_recovery(expects_errors);
+
+    let parsed_idl = parser
         .parse_crate_named(crate_name, &test.rs_path, &test.rs_path)
         .with_context(|| format!("failed to load `{}`", test.rs_path.display()))?;
-    let idl_json = serde_json::to_string_pretty(&parsed_idl)
-        .with_context(|| format!("failed to serialize json from `{}`", test.rs_path.display()))?;
-    let reference_json = std::fs::read_to_string(&test.idl_path).unwrap_or_default();
 
-    if idl_json != reference_json {
+    if expects_errors {
+        let source_root = format!("{}/", std::env::current_dir()?.display());
+        let errors: String = parser
+            .take_recovered_errors()
+            .iter()
+            .map(|error| format!("{}\n", error.to_string().replace(&source_root, "")))
+            .collect();
+        compare_with_reference(test, &errors_path, errors)?;
+    }
+
+    // A freshly parsed IDL should always be closed.
+    let diagnostics = parsed_idl.validate();
+    if !diagnostics.is_empty() {
+        anyhow::bail!(
+            "validation of `{}` failed:\n{}",
+            test.rs_path.display(),
+            diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("\n"),
+        );
+    }
+
+    // The text format must read back as the IDL it was written from (up to what it does not record).
+    let text_options = canonical_text_options(test)?;
+    let idl_text = gluegun_idl::text::to_text_with(&parsed_idl, &text_options);
+    let reread_idl = gluegun_idl::text::from_text(&idl_text)
+        .with_context(|| format!("failed to read back the IDL text of `{}`", test.rs_path.display()))?;
+    let reread_text = gluegun_idl::text::to_text_with(&reread_idl, &text_options);
+    if reread_text != idl_text {
+        let diff = similar::udiff::unified_diff(similar::Algorithm::Myers, &idl_text, &reread_text, 2, Some(("written", "reread")));
+        anyhow::bail!("IDL text of `{}` does not read back as written\n\n{diff}", test.rs_path.display());
+    }
+    compare_with_reference(test, &test.idl_path, idl_text)?;
+
+    // Tests opt into checking the JSON Schema and OpenAPI exports by having
+    // a `.schema.json` or `.openapi.json` file, respectively.
+    let exports = [
+        ("schema.json", gluegun_idl::export::json_schema::json_schema as fn(&_) -> _),
+        ("openapi.json", gluegun_idl::export::json_schema::openapi),
+    ];
+    for (extension, export) in exports {
+        let export_path = test.rs_path.with_extension(extension);
+        if export_path.exists() {
+            let exported = export(&parsed_idl)
+                .with_context(|| format!("failed to export `{extension}` from `{}`", test.rs_path.display()))?;
+            compare_with_reference(test, &export_path, serde_json::to_string_pretty(&exported)?)?;
+        }
+    }
+
+    // Tests opt into checking `Idl::dependency_order` by having a `.order` file, with one definition per line.
+    let order_path = test.rs_path.with_extension("order");
+    if order_path.exists() {
+        let order: String = parsed_idl
+            .dependency_order()
+            .iter()
+            .map(|(qname, _)| format!("{}\n", qname.colon_colon()))
+            .collect();
+        compare_with_reference(test, &order_path, order)?;
+    }
+
+    Ok(())
+}
+
+/// How the `.idl` snapshot of `test` renders the IDL: with paths relative to the current directory
+/// and, unless there is a `.nospans` file, with spans. Leaving spans out keeps the snapshot of a test
+/// from changing when its source moves around.
+fn canonical_text_options(test: &IdlTest) -> anyhow::Result<gluegun_idl::text::TextOptions> {
+    Ok(gluegun_idl::text::TextOptions::new()
+        .with_source_root(std::env::current_dir()?)
+        .with_spans(!test.rs_path.with_extension("nospans").exists()))
+}
+
+/// Compare `actual` against the contents of `reference_path` (or overwrite them if `BLESS=1`).
+fn compare_with_reference(test: &IdlTest, reference_path: &Path, actual: String) -> anyhow::Result<()> {
+    let reference = std::fs::read_to_string(reference_path).unwrap_or_default();
+
+    if actual != reference {
         if *BLESS {
             eprintln!("test `{}` blessed because BLESS=1", test.rs_path.display());
-            std::fs::write(&test.idl_path, idl_json)
-                .with_context(|| format!("failed to write `{}`", test.idl_path.display()))?;
+            std::fs::write(reference_path, actual)
+                .with_context(|| format!("failed to write `{}`", reference_path.display()))?;
         } else {
             let diff = similar::udiff::unified_diff(
                 similar::Algorithm::Myers,
-                &reference_json,
-                &idl_json,
+                &reference,
+                &actual,
                 2,
-                Some((&test.idl_path.display().to_string(), "new")),
+                Some((&reference_path.display().to_string(), "new")),
             );
 
             return Err(anyhow::anyhow!(
@@ -5,5 +5,8 @@ lazy_static::lazy_static! {
 mod test_definition;
 pub use test_definition::Test;
 
+mod matrix;
+pub use matrix::Matrix;
+
 mod idl_test;
 pub use idl_test::idl_tests;
\ No newline at end of file
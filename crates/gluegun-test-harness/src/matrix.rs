@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use camino::Utf8PathBuf;
+use cp_r::CopyOptions;
+use gluegun_core::codegen::ToolReport;
+use temp_dir::TempDir;
+
+use crate::{test_definition::plugin_command, Test};
+
+/// A test declared once and run with each of a list of plugins, as a separate [`Test`][].
+/// Plugins whose required tools (those checked by `cargo gluegun doctor`) are missing on this machine
+/// are skipped and reported, so that the test exercises whatever languages are installed.
+pub struct Matrix {
+    test_crate: String,
+    source_directory: Utf8PathBuf,
+    plugins: Vec<String>,
+}
+
+impl Matrix {
+    pub fn new(
+        test_crate: impl ToString,
+        plugins: impl IntoIterator<Item: ToString>,
+        source_directory: impl Into<Utf8PathBuf>,
+    ) -> Self {
+        Self {
+            test_crate: test_crate.to_string(),
+            source_directory: source_directory.into(),
+            plugins: plugins.into_iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    /// Execute the test built by `define` (from a [`Test`][] of the test crate with just that plugin, and the plugin)
+    /// for each plugin whose tools are installed. Fails if the test fails for any of them,
+    /// after running it for the others.
+    pub fn execute(self, define: impl Fn(Test, &str) -> Test) -> anyhow::Result<()> {
+        let tools = self
+            .required_tools()
+            .with_context(|| format!("checking the tools required to test `{}`", self.test_crate))?;
+
+        let mut skipped = vec![];
+        let mut failures = vec![];
+        for plugin in &self.plugins {
+            let missing: Vec<&str> = tools
+                .get(plugin)
+                .into_iter()
+                .flatten()
+                .filter(|tool| tool.found().is_none())
+                .map(|tool| tool.name().as_str())
+                .collect();
+            if !missing.is_empty() {
+                skipped.push((plugin, missing));
+                continue;
+            }
+
+            let test = define(Test::new(&self.test_crate, [plugin], &self.source_directory), plugin);
+            if let Err(err) = test.execute() {
+                eprintln!("Test failure: test `{}` failed with plugin `{plugin}`: {err:?}", self.test_crate);
+                failures.push(plugin);
+            }
+        }
+
+        let ran = self.plugins.len() - skipped.len();
+        eprintln!("# matrix `{}`: ran {ran} plugins, skipped {}", self.test_crate, skipped.len());
+        for (plugin, missing) in &skipped {
+            eprintln!("#   skipped `{plugin}`: missing {}", missing.join(", "));
+        }
+
+        if failures.is_empty() {
+            return Ok(());
+        }
+
+        anyhow::bail!(
+            "{failed} out of {ran} plugins failed: {plugins}",
+            failed = failures.len(),
+            plugins = failures.iter().map(|p| format!("`{p}`")).collect::<Vec<_>>().join(", "),
+        )
+    }
+
+    /// The tools required by each plugin, as `cargo gluegun doctor` finds them for the test crate.
+    /// Plugins that cannot report their tools are left out (and so run).
+    fn required_tools(&self) -> anyhow::Result<BTreeMap<String, Vec<ToolReport>>> {
+        // Work on a copy, as `cargo metadata` may write a `Cargo.lock` next to the test crate.
+        let temp = TempDir::new()?;
+        let temp_dir = Utf8PathBuf::try_from(temp.path().to_path_buf())?;
+        CopyOptions::new().copy_tree(&self.source_directory, &temp_dir)?;
+
+        cargo_gluegun::Builder::new(
+            &temp_dir,
+            ["cargo-gluegun", "doctor", "--package", &self.test_crate]
+                .into_iter()
+                .chain(self.plugins.iter().map(|p| &p[..])),
+        )?
+        .plugin_command(plugin_command)
+        .required_tools()
+    }
+}
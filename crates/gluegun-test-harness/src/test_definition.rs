@@ -1,8 +1,14 @@
-use std::{process::Command, sync::Arc};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    process::{Command, Stdio},
+    sync::Arc,
+};
 
 use anyhow::Context;
 use camino::Utf8PathBuf;
 use cp_r::CopyOptions;
+use gluegun_core::codegen::SourceMap;
 use temp_dir::TempDir;
 
 pub struct Test {
@@ -14,11 +20,16 @@ pub struct Test {
 
 #[derive(Debug)]
 pub enum TestAction {
-    /// Invoke cargo with the given `$OPTIONS`
-    Cargo { options: Vec<String> },
+    /// Invoke cargo with the given `$OPTIONS`, in `directory` (relative to the test crate)
+    Cargo { directory: Utf8PathBuf, options: Vec<String> },
 
-    /// Invoke cargo-gluegun with the given `$OPTIONS`
-    CargoGluegun { options: Vec<String> },
+    /// Invoke cargo-gluegun with the given `$OPTIONS`, in `directory` (relative to the test crate)
+    CargoGluegun { directory: Utf8PathBuf, options: Vec<String> },
+
+    /// Invoke `cargo build` on the given crate generated by a plugin. Errors reported in
+    /// generated files are followed by the Rust source location they were generated from,
+    /// taken from the file's [`SourceMap`][] (if any).
+    CargoBuildPluginCrate { package: String },
 
     /// Find the given text and replace it
     Replace {
@@ -26,6 +37,19 @@ pub enum TestAction {
         find: String,
         replace: String,
     },
+
+    /// Record the contents of the crates generated by each plugin
+    SnapshotPluginCrates,
+
+    /// Check that the crates generated by each plugin are byte-for-byte identical
+    /// to the contents recorded by the last [`TestAction::SnapshotPluginCrates`][]
+    ComparePluginCrates,
+
+    /// Check whether something exists at `path` (relative to the test crate)
+    CheckExists { path: Utf8PathBuf, exists: bool },
+
+    /// Check whether the file at `path` (relative to the test crate) contains `text`
+    CheckContains { path: Utf8PathBuf, text: String, contains: bool },
 }
 
 impl Test {
@@ -46,7 +70,8 @@ impl Test {
     pub fn cargo_builder(self, command: impl ToString) -> CommandBuilder {
         CommandBuilder {
             test: self,
-            make_action: |options| TestAction::Cargo { options },
+            make_action: |directory, options| TestAction::Cargo { directory, options },
+            directory: Utf8PathBuf::new(),
             options: vec!["--verbose".to_string(), command.to_string()],
         }
     }
@@ -55,7 +80,8 @@ impl Test {
     pub fn cargo_glue_gun_builder(self) -> CommandBuilder {
         CommandBuilder {
             test: self,
-            make_action: |options| TestAction::CargoGluegun { options },
+            make_action: |directory, options| TestAction::CargoGluegun { directory, options },
+            directory: Utf8PathBuf::new(),
             options: vec![],
         }
     }
@@ -76,15 +102,51 @@ impl Test {
         let test_crate = self.test_crate.clone();
         let plugins = self.plugins.clone();
         for plugin in &plugins[..] {
-            self = self
-                .cargo_builder("build")
-                .option("--package")
-                .option(format!("{}-{}", test_crate, plugin))
-                .finish()
+            self.actions.push(TestAction::CargoBuildPluginCrate {
+                package: format!("{}-{}", test_crate, plugin),
+            });
         }
         self
     }
 
+    /// Add a step to record the contents of the crates generated from the plugin,
+    /// so that they can later be checked with [`Self::compare_plugin_crates`][].
+    pub fn snapshot_plugin_crates(mut self) -> Self {
+        self.actions.push(TestAction::SnapshotPluginCrates);
+        self
+    }
+
+    /// Add a step to check that the crates generated from the plugin are identical to
+    /// the last [snapshot](`Self::snapshot_plugin_crates`). Useful to check that generation is deterministic.
+    pub fn compare_plugin_crates(mut self) -> Self {
+        self.actions.push(TestAction::ComparePluginCrates);
+        self
+    }
+
+    /// Add a step to check that `path` (relative to the test crate) exists, e.g. a generated crate.
+    pub fn expect_path(mut self, path: impl Into<Utf8PathBuf>) -> Self {
+        self.actions.push(TestAction::CheckExists { path: path.into(), exists: true });
+        self
+    }
+
+    /// Add a step to check that `path` (relative to the test crate) does not exist.
+    pub fn expect_no_path(mut self, path: impl Into<Utf8PathBuf>) -> Self {
+        self.actions.push(TestAction::CheckExists { path: path.into(), exists: false });
+        self
+    }
+
+    /// Add a step to check that the file at `path` (relative to the test crate) contains `text`.
+    pub fn expect_file_containing(mut self, path: impl Into<Utf8PathBuf>, text: impl ToString) -> Self {
+        self.actions.push(TestAction::CheckContains { path: path.into(), text: text.to_string(), contains: true });
+        self
+    }
+
+    /// Add a step to check that the file at `path` (relative to the test crate) does not contain `text`.
+    pub fn expect_file_not_containing(mut self, path: impl Into<Utf8PathBuf>, text: impl ToString) -> Self {
+        self.actions.push(TestAction::CheckContains { path: path.into(), text: text.to_string(), contains: false });
+        self
+    }
+
     pub fn replace(
         mut self,
         path: impl Into<Utf8PathBuf>,
@@ -108,11 +170,18 @@ impl Test {
 
 pub struct CommandBuilder {
     test: Test,
-    make_action: fn(Vec<String>) -> TestAction,
+    make_action: fn(Utf8PathBuf, Vec<String>) -> TestAction,
+    directory: Utf8PathBuf,
     options: Vec<String>,
 }
 
 impl CommandBuilder {
+    /// Run the command in `directory`, relative to the test crate (by default, the test crate itself).
+    pub fn in_directory(mut self, directory: impl Into<Utf8PathBuf>) -> Self {
+        self.directory = directory.into();
+        self
+    }
+
     pub fn option(mut self, option: impl ToString) -> Self {
         self.options.push(option.to_string());
         self
@@ -125,7 +194,7 @@ impl CommandBuilder {
     }
 
     pub fn finish(mut self) -> Test {
-        self.test.actions.push((self.make_action)(self.options));
+        self.test.actions.push((self.make_action)(self.directory, self.options));
         self.test
     }
 }
@@ -134,6 +203,9 @@ struct TestExecutor {
     test: Test,
     temp_dir: Utf8PathBuf,
     temp_dir_cleanup: Option<TempDir>,
+
+    /// Contents of the generated plugin crates, recorded by [`TestAction::SnapshotPluginCrates`][].
+    snapshot: RefCell<Option<BTreeMap<Utf8PathBuf, Vec<u8>>>>,
 }
 
 impl TestExecutor {
@@ -143,6 +215,7 @@ impl TestExecutor {
             test,
             temp_dir: Utf8PathBuf::try_from(temp_dir.path().to_path_buf()).unwrap(),
             temp_dir_cleanup: Some(temp_dir),
+            snapshot: Default::default(),
         })
     }
 
@@ -174,7 +247,9 @@ impl TestExecutor {
     fn execute_action(&self, action: &TestAction) -> anyhow::Result<()> {
         eprintln!("## execute action {action:?}");
         match action {
-            TestAction::Cargo { options } => self.cargo_action(options),
+            TestAction::Cargo { directory, options } => self.cargo_action(directory, options),
+
+            TestAction::CargoBuildPluginCrate { package } => self.cargo_build_plugin_crate_action(package),
 
             TestAction::Replace {
                 path,
@@ -182,32 +257,46 @@ impl TestExecutor {
                 replace,
             } => self.replace_action(path, find, replace),
 
-            TestAction::CargoGluegun { options } => cargo_gluegun::Builder::new(
-                &self.temp_dir,
+            TestAction::SnapshotPluginCrates => {
+                let contents = self.read_plugin_crates()?;
+                *self.snapshot.borrow_mut() = Some(contents);
+                Ok(())
+            }
+
+            TestAction::ComparePluginCrates => self.compare_plugin_crates_action(),
+
+            TestAction::CheckExists { path, exists } => {
+                if self.temp_dir.join(path).exists() != *exists {
+                    let expected = if *exists { "to exist" } else { "not to exist" };
+                    anyhow::bail!("expected `{path}` {expected}");
+                }
+                Ok(())
+            }
+
+            TestAction::CheckContains { path, text, contains } => {
+                let content = std::fs::read_to_string(self.temp_dir.join(path))
+                    .with_context(|| format!("reading `{path}`"))?;
+                if content.contains(text) != *contains {
+                    let expected = if *contains { "to contain" } else { "not to contain" };
+                    anyhow::bail!("expected `{path}` {expected} `{text}`, found:\n{content}");
+                }
+                Ok(())
+            }
+
+            TestAction::CargoGluegun { directory, options } => cargo_gluegun::Builder::new(
+                self.temp_dir.join(directory),
                 Some("cargo-gluegun")
                     .into_iter()
                     .chain(options.iter().map(|o| &o[..])),
             )?
-            .plugin_command(|_gluegun_metadata, plugin| {
-                let manifest_path = std::env::var("CARGO_MANIFEST_PATH")
-                    .with_context(|| format!("fetching `CARGO_MANIFEST_PATH` variable"))?;
-                let mut c = Command::new("cargo");
-                c
-                    .arg("run")
-                    .arg("--manifest-path")
-                    .arg(manifest_path)
-                    .arg("-p")
-                    .arg(format!("gluegun-{plugin}"))
-                    .arg("--");
-                Ok(c)
-            })
+            .plugin_command(plugin_command)
             .execute(),
         }
     }
 
-    fn cargo_action(&self, options: &[String]) -> anyhow::Result<()> {
+    fn cargo_action(&self, directory: &Utf8PathBuf, options: &[String]) -> anyhow::Result<()> {
         let mut command = std::process::Command::new("cargo");
-        command.current_dir(&self.temp_dir);
+        command.current_dir(self.temp_dir.join(directory));
         command.args(options);
         let status = command.status()?;
         if !status.success() {
@@ -216,6 +305,100 @@ impl TestExecutor {
         Ok(())
     }
 
+    fn cargo_build_plugin_crate_action(&self, package: &str) -> anyhow::Result<()> {
+        let output = Command::new("cargo")
+            .current_dir(&self.temp_dir)
+            .args(["--verbose", "build", "--message-format=json", "--package", package])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .output()?;
+
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let Ok(message) = serde_json::from_str::<serde_json::Value>(line) else {
+                println!("{line}");
+                continue;
+            };
+            if message["reason"] != "compiler-message" {
+                continue;
+            }
+
+            let diagnostic = &message["message"];
+            if let Some(rendered) = diagnostic["rendered"].as_str() {
+                eprint!("{rendered}");
+            }
+            for note in self.generated_from_notes(diagnostic)? {
+                eprintln!("{note}\n");
+            }
+        }
+
+        if !output.status.success() {
+            anyhow::bail!("cargo command failed");
+        }
+        Ok(())
+    }
+
+    /// For each primary span of the rustc `diagnostic` that lies in a generated file with a [`SourceMap`][],
+    /// a note giving the Rust source location that the code was generated from.
+    fn generated_from_notes(&self, diagnostic: &serde_json::Value) -> anyhow::Result<Vec<String>> {
+        let mut notes = vec![];
+        for span in diagnostic["spans"].as_array().into_iter().flatten() {
+            if span["is_primary"] != true {
+                continue;
+            }
+            let (Some(file_name), Some(line)) = (span["file_name"].as_str(), span["line_start"].as_u64()) else {
+                continue;
+            };
+
+            // rustc reports paths relative to the workspace root
+            let Some(source_map) = SourceMap::load(self.temp_dir.join(file_name).as_std_path())? else {
+                continue;
+            };
+            if let Some(source_span) = source_map.span_for_line(line as usize) {
+                notes.push(format!("note: `{file_name}:{line}` was generated from `{source_span}`"));
+            }
+        }
+        Ok(notes)
+    }
+
+    /// Read the contents of all files in the crates generated for each plugin.
+    /// Assumes the crates were generated in the default location (a child of the test crate).
+    fn read_plugin_crates(&self) -> anyhow::Result<BTreeMap<Utf8PathBuf, Vec<u8>>> {
+        let mut contents = BTreeMap::default();
+        for plugin in &self.test.plugins[..] {
+            let crate_dir = Utf8PathBuf::from(format!("{}-{}", self.test.test_crate, plugin));
+            read_tree(&self.temp_dir, &crate_dir, &mut contents)?;
+        }
+        Ok(contents)
+    }
+
+    fn compare_plugin_crates_action(&self) -> anyhow::Result<()> {
+        let Some(snapshot) = self.snapshot.borrow_mut().take() else {
+            anyhow::bail!("no snapshot of the plugin crates was taken");
+        };
+        let contents = self.read_plugin_crates()?;
+
+        let differing_paths: Vec<_> = snapshot
+            .keys()
+            .chain(contents.keys())
+            .filter(|path| snapshot.get(*path) != contents.get(*path))
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        if !differing_paths.is_empty() {
+            anyhow::bail!(
+                "generated files differ from snapshot:\n{}",
+                differing_paths
+                    .iter()
+                    .map(|p| format!("    {p}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+        }
+
+        Ok(())
+    }
+
     fn replace_action(&self, path: &Utf8PathBuf, find: &str, replace: &str) -> anyhow::Result<()> {
         let file_path = self.temp_dir.join(path);
 
@@ -226,7 +409,45 @@ impl TestExecutor {
         }
 
         let content = content.replace(find, replace);
-        std::fs::write(path, content)?;
+        std::fs::write(&file_path, content)?;
         Ok(())
     }
 }
+
+/// Run the helper of `plugin` from the gluegun workspace (the one being tested), whatever the test crate.
+pub(crate) fn plugin_command(_gluegun_metadata: &serde_json::Value, plugin: &str) -> anyhow::Result<Command> {
+    let manifest_path = std::env::var("CARGO_MANIFEST_PATH")
+        .with_context(|| format!("fetching `CARGO_MANIFEST_PATH` variable"))?;
+    let mut c = Command::new("cargo");
+    c
+        .arg("run")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("-p")
+        .arg(format!("gluegun-{plugin}"))
+        .arg("--");
+    Ok(c)
+}
+
+/// Read the contents of each file found under `root/dir` (recursively) into `contents`,
+/// keyed by its path relative to `root`. Skips `target` directories.
+fn read_tree(
+    root: &Utf8PathBuf,
+    dir: &Utf8PathBuf,
+    contents: &mut BTreeMap<Utf8PathBuf, Vec<u8>>,
+) -> anyhow::Result<()> {
+    for entry in root.join(dir).read_dir_utf8()? {
+        let entry = entry?;
+        let path = dir.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            if entry.file_name() != "target" {
+                read_tree(root, &path, contents)?;
+            }
+        } else {
+            let data = std::fs::read(entry.path())
+                .with_context(|| format!("reading `{}`", entry.path()))?;
+            contents.insert(path, data);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anyhow::Context;
+use gluegun_core::{
+    cli::{GenerateCx, GlueGunHelper},
+    codegen::LibraryCrate,
+};
+
+mod rs_gen;
+
+pub fn main() -> anyhow::Result<()> {
+    gluegun_core::cli::run(GlueGunUniffi)
+}
+
+/// Generates a wrapper crate annotated with [UniFFI](https://mozilla.github.io/uniffi-rs/) proc-macros,
+/// so that the existing `uniffi-bindgen` tooling can produce Kotlin, Swift, Python, etc bindings.
+#[derive(Clone)]
+struct GlueGunUniffi;
+
+impl GlueGunHelper for GlueGunUniffi {
+    type Metadata = ();
+
+    fn name(&self) -> String {
+        "uniffi".to_string()
+    }
+
+    fn generate(self, cx: &mut GenerateCx, _metadata: &(), output: &mut LibraryCrate) -> anyhow::Result<()> {
+        output.add_dependency("uniffi").version("0.28");
+
+        rs_gen::RustCodeGenerator::new(cx.idl(), cx.type_overrides())
+            .generate(output)
+            .context("generating Rust sources")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,438 @@
+use std::collections::BTreeMap;
+
+use gluegun_core::{
+    cli::TypeOverrides,
+    codegen::{copy_out, CodeWriter, LibraryCrate},
+    idl::{
+        Enum, Field, Function, Idl, Item, MapSetRepr, Name, Newtype, OptionRepr, QualifiedName, Record, RefdTy, Scalar,
+        Span, StringRepr, TupleRepr, Ty, TypeKind, UserTypeRepr, Variant, VecRepr,
+    },
+    type_mapper::{self, TypeMapper},
+};
+
+/// Generates the wrapper crate. UniFFI can only export types defined in the crate that
+/// invokes `setup_scaffolding!`, so each record, newtype, variant, and enum gets a mirror type here
+/// (e.g., `Point` for `my_crate::geometry::Point`) along with `From` conversions in both directions.
+/// Functions are exported through `#[uniffi::export]` wrappers that convert their arguments and results.
+/// Third-party types are exchanged as the `repr` type of their override (see [`TypeOverrides`][]).
+pub(crate) struct RustCodeGenerator<'idl> {
+    idl: &'idl Idl,
+    type_overrides: &'idl TypeOverrides,
+}
+
+impl<'idl> RustCodeGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl, type_overrides: &'idl TypeOverrides) -> Self {
+        Self { idl, type_overrides }
+    }
+
+    pub(crate) fn generate(mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        self.check_mirror_names()?;
+        self.generate_lib_rs(lib)
+    }
+
+    /// Mirror types are all defined at the root of the generated crate (UniFFI's
+    /// namespace is flat anyway), so their names must be unique.
+    fn check_mirror_names(&self) -> anyhow::Result<()> {
+        let mut names: BTreeMap<Name, &QualifiedName> = BTreeMap::new();
+        for (qname, item) in self.idl.definitions() {
+            if let Item::Function(_) = item {
+                continue;
+            }
+            if let Some(previous) = names.insert(qname.tail_name(), qname) {
+                anyhow::bail!(
+                    "`{}` and `{}` have the same name, which is not supported by the uniffi backend",
+                    previous.colon_colon(),
+                    qname.colon_colon()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_lib_rs(&mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        let mut lib_rs = lib.add_file("src/lib.rs")?;
+
+        write!(lib_rs, "uniffi::setup_scaffolding!();")?;
+        write!(lib_rs, "")?;
+        self.generate_error(&mut lib_rs)?;
+
+        for (qname, item) in self.idl.definitions() {
+            write!(lib_rs, "")?;
+            match item {
+                Item::Record(record) => lib_rs.with_span(record.span(), |lib_rs| self.generate_record(lib_rs, qname, record))?,
+                Item::Newtype(newtype) => lib_rs.with_span(newtype.span(), |lib_rs| self.generate_newtype(lib_rs, qname, newtype))?,
+                Item::Variant(variant) => lib_rs.with_span(variant.span(), |lib_rs| self.generate_variant(lib_rs, qname, variant))?,
+                Item::Enum(an_enum) => lib_rs.with_span(an_enum.span(), |lib_rs| self.generate_enum(lib_rs, qname, an_enum))?,
+                Item::Function(function) => lib_rs.with_span(function.span(), |lib_rs| self.generate_function(lib_rs, qname, function))?,
+                _ => anyhow::bail!(
+                    "`{}`: only records, newtypes, variants, enums, and functions are currently supported by the uniffi backend",
+                    qname.colon_colon()
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// UniFFI requires errors to be of a type known to it, so errors returned from Rust
+    /// are converted into this type, carrying the error's message.
+    fn generate_error(&mut self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "#[derive(Debug, uniffi::Error)]")?;
+        write!(lib_rs, "#[uniffi(flat_error)]")?;
+        write!(lib_rs, "pub enum GlueGunError {{")?;
+        write!(lib_rs, "Message(String),")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "impl std::fmt::Display for GlueGunError {{")?;
+        write!(lib_rs, "fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{")?;
+        write!(lib_rs, "match self {{")?;
+        write!(lib_rs, "GlueGunError::Message(message) => write!(f, \"{{message}}\"),")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    /// Generate `From` impls converting between the mirror type and the original type `qname`
+    /// in both directions. `body` generates the body of `from`, given the source and target type
+    /// and the direction of the conversion.
+    fn generate_conversions(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        mut body: impl FnMut(&mut CodeWriter<'_>, &str, &str, Direction) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let mirror = qname.tail_name().to_string();
+        let original = qname.colon_colon();
+        for (source, target, direction) in [(&original, &mirror, Direction::Export), (&mirror, &original, Direction::Import)] {
+            write!(lib_rs, "")?;
+            write!(lib_rs, "impl From<{source}> for {target} {{")?;
+            write!(lib_rs, "fn from(value: {source}) -> Self {{")?;
+            body(lib_rs, source, target, direction)?;
+            write!(lib_rs, "}}")?;
+            write!(lib_rs, "}}")?;
+        }
+        Ok(())
+    }
+
+    fn generate_record(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, record: &Record) -> anyhow::Result<()> {
+        check_no_methods(qname, record.methods().is_empty())?;
+        check_named_fields(record.span(), record.fields())?;
+
+        write!(lib_rs, "#[derive(uniffi::Record)]")?;
+        write!(lib_rs, "pub struct {} {{", record.name())?;
+        for field in record.fields() {
+            write!(lib_rs, "pub {}: {},", field.name(), self.map_ty(field.ty())?)?;
+        }
+        write!(lib_rs, "}}")?;
+
+        let type_overrides = self.type_overrides;
+        self.generate_conversions(lib_rs, qname, |lib_rs, _source, target, direction| {
+            write!(lib_rs, "{target} {{")?;
+            for field in record.fields() {
+                let name = field.name();
+                write!(
+                    lib_rs,
+                    "{name}: {},",
+                    convert(&format!("value.{name}"), field.ty(), type_overrides, direction)
+                )?;
+            }
+            write!(lib_rs, "}}")?;
+            Ok(())
+        })
+    }
+
+    /// Newtypes become a UniFFI [custom type](https://mozilla.github.io/uniffi-rs/latest/types/custom_types.html),
+    /// which appears as a type alias of the field type in the foreign bindings.
+    fn generate_newtype(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, newtype: &Newtype) -> anyhow::Result<()> {
+        check_no_methods(qname, newtype.methods().is_empty())?;
+        if *newtype.validated() {
+            anyhow::bail!("`{}`: validated newtypes (`#[gluegun::marshal_as]`) are not yet supported by the uniffi backend", qname.colon_colon());
+        }
+
+        let field_ty = self.map_ty(newtype.ty())?;
+        write!(lib_rs, "pub struct {}(pub {field_ty});", newtype.name())?;
+        write!(lib_rs, "uniffi::custom_newtype!({}, {field_ty});", newtype.name())?;
+
+        let type_overrides = self.type_overrides;
+        self.generate_conversions(lib_rs, qname, |lib_rs, _source, target, direction| {
+            write!(lib_rs, "{target}({})", convert("value.0", newtype.ty(), type_overrides, direction))?;
+            Ok(())
+        })
+    }
+
+    fn generate_variant(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        variant: &Variant,
+    ) -> anyhow::Result<()> {
+        check_no_methods(qname, variant.methods().is_empty())?;
+        for arm in variant.arms() {
+            check_named_fields(arm.span(), arm.fields())?;
+        }
+
+        write!(lib_rs, "#[derive(uniffi::Enum)]")?;
+        write!(lib_rs, "pub enum {} {{", variant.name())?;
+        for arm in variant.arms() {
+            if arm.fields().is_empty() {
+                write!(lib_rs, "{},", arm.name())?;
+            } else {
+                write!(lib_rs, "{} {{", arm.name())?;
+                for field in arm.fields() {
+                    write!(lib_rs, "{}: {},", field.name(), self.map_ty(field.ty())?)?;
+                }
+                write!(lib_rs, "}},")?;
+            }
+        }
+        write!(lib_rs, "}}")?;
+
+        let type_overrides = self.type_overrides;
+        self.generate_conversions(lib_rs, qname, |lib_rs, source, target, direction| {
+            write!(lib_rs, "match value {{")?;
+            for arm in variant.arms() {
+                let name = arm.name();
+                let field_names = arm.fields().iter().map(|field| field.name().to_string()).collect::<Vec<_>>();
+                let conversions = arm
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        let conversion = convert(&field.name().to_string(), field.ty(), type_overrides, direction);
+                        format!("{}: {conversion}", field.name())
+                    })
+                    .collect::<Vec<_>>();
+                write!(
+                    lib_rs,
+                    "{source}::{name} {{ {} }} => {target}::{name} {{ {} }},",
+                    field_names.join(", "),
+                    conversions.join(", "),
+                )?;
+            }
+            write!(lib_rs, "}}")?;
+            Ok(())
+        })
+    }
+
+    fn generate_enum(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, an_enum: &Enum) -> anyhow::Result<()> {
+        check_no_methods(qname, an_enum.methods().is_empty())?;
+
+        write!(lib_rs, "#[derive(uniffi::Enum)]")?;
+        write!(lib_rs, "pub enum {} {{", an_enum.name())?;
+        for arm in an_enum.arms() {
+            write!(lib_rs, "{},", arm.name())?;
+        }
+        write!(lib_rs, "}}")?;
+
+        self.generate_conversions(lib_rs, qname, |lib_rs, source, target, _direction| {
+            write!(lib_rs, "match value {{")?;
+            for arm in an_enum.arms() {
+                let name = arm.name();
+                write!(lib_rs, "{source}::{name} => {target}::{name},")?;
+            }
+            write!(lib_rs, "}}")?;
+            Ok(())
+        })
+    }
+
+    fn generate_function(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        function: &Function,
+    ) -> anyhow::Result<()> {
+        let signature = function.signature();
+
+        write!(lib_rs, "#[uniffi::export]")?;
+        write!(lib_rs, "pub fn {}(", function.name())?;
+        for input in signature.inputs() {
+            write!(lib_rs, "{}: {},", input.name(), self.map_ty(input.refd_ty().ty())?)?;
+        }
+
+        let output_ty = signature.output_ty();
+        let main_ty = self.map_ty(output_ty.main_ty().ty())?;
+        match output_ty.error_ty() {
+            Some(_) => write!(lib_rs, ") -> Result<{main_ty}, GlueGunError> {{")?,
+            None if output_ty.is_unit() => write!(lib_rs, ") {{")?,
+            None => write!(lib_rs, ") -> {main_ty} {{")?,
+        }
+
+        // Arguments are received as owned values (converted from the mirror types where needed)
+        // and borrowed if the wrapped function expects a reference. Third-party types are not annotated
+        // as the IDL does not know their generic arguments.
+        for input in signature.inputs() {
+            let ty = input.refd_ty().ty();
+            if needs_conversion(ty) {
+                let name = input.name();
+                let conversion = convert(&name.to_string(), ty, self.type_overrides, Direction::Import);
+                match ty.kind() {
+                    TypeKind::Custom { .. } => write!(lib_rs, "let {name} = {conversion};")?,
+                    _ => write!(lib_rs, "let {name}: {} = {conversion};", ty.rust_owned_spelling())?,
+                }
+            }
+        }
+
+        // Unit-returning functions are called as a statement.
+        let arguments: Vec<String> = signature
+            .inputs()
+            .iter()
+            .map(|input| match input.refd_ty() {
+                RefdTy::Owned(..) => input.name().to_string(),
+                RefdTy::Ref(..) => format!("&{}", input.name()),
+            })
+            .collect();
+        let mut call = format!("{}({})", qname.colon_colon(), arguments.join(","));
+        if output_ty.error_ty().is_some() {
+            call.push_str(".map_err(|err| GlueGunError::Message(err.to_string()))?");
+        }
+        if !output_ty.is_unit() {
+            write!(lib_rs, "let result = ")?;
+        }
+        write!(lib_rs, "{};", copy_out(output_ty, &call))?;
+
+        let result = if output_ty.is_unit() {
+            "()".to_string()
+        } else {
+            convert("result", output_ty.main_ty().ty(), self.type_overrides, Direction::Export)
+        };
+        match output_ty.error_ty() {
+            Some(_) => write!(lib_rs, "Ok({result})")?,
+            None if output_ty.is_unit() => {}
+            None => write!(lib_rs, "{result}")?,
+        }
+        write!(lib_rs, "}}")?;
+
+        Ok(())
+    }
+}
+
+fn check_no_methods(qname: &QualifiedName, no_methods: bool) -> anyhow::Result<()> {
+    if !no_methods {
+        anyhow::bail!("`{}`: methods are not yet supported by the uniffi backend", qname.colon_colon());
+    }
+    Ok(())
+}
+
+/// The IDL names the fields of tuple structs and tuple-like variant arms `f0`, `f1`, etc,
+/// but UniFFI records and enums need named fields.
+fn check_named_fields(span: &Span, fields: &[Field]) -> anyhow::Result<()> {
+    let tuple_like = !fields.is_empty()
+        && fields
+            .iter()
+            .enumerate()
+            .all(|(index, field)| *field.name().text() == format!("f{index}"));
+    if tuple_like {
+        anyhow::bail!("{span}: tuple-like types are not supported by the uniffi backend");
+    }
+    Ok(())
+}
+
+/// The direction of a conversion done by [`convert`].
+#[derive(Clone, Copy)]
+enum Direction {
+    /// From the original types to the types exported through UniFFI.
+    Export,
+
+    /// From the types exported through UniFFI to the original types.
+    Import,
+}
+
+/// True if `ty` contains a user type, whose values must be converted to or from the mirror type,
+/// or a third-party type, whose values may have to be converted to or from the `repr` type of its override.
+fn needs_conversion(ty: &Ty) -> bool {
+    match ty.kind() {
+        TypeKind::UserType { .. } | TypeKind::Custom { .. } => true,
+        TypeKind::Vec { element, .. } | TypeKind::Option { element, .. } => needs_conversion(element),
+        TypeKind::Map { key, value, .. } => needs_conversion(key) || needs_conversion(value),
+        _ => false,
+    }
+}
+
+/// Return an expression converting `expr` (of type `ty`) between the original types and
+/// the mirror types in the given `direction` (for user types the `From` impls go both ways,
+/// but third-party types are converted with the snippets of their override).
+fn convert(expr: &str, ty: &Ty, type_overrides: &TypeOverrides, direction: Direction) -> String {
+    if !needs_conversion(ty) {
+        return expr.to_string();
+    }
+    match ty.kind() {
+        TypeKind::Vec { element, .. } => {
+            format!("{expr}.into_iter().map(|e| {}).collect()", convert("e", element, type_overrides, direction))
+        }
+        TypeKind::Option { element, .. } => {
+            format!("{expr}.map(|e| {})", convert("e", element, type_overrides, direction))
+        }
+        TypeKind::Map { key, value, .. } => format!(
+            "{expr}.into_iter().map(|(k, v)| ({}, {})).collect()",
+            convert("k", key, type_overrides, direction),
+            convert("v", value, type_overrides, direction)
+        ),
+        TypeKind::Custom { path } => match (type_overrides.get(path), direction) {
+            (Some(type_override), Direction::Export) => type_override.to_repr_expr(expr),
+            (Some(type_override), Direction::Import) => type_override.from_repr_expr(expr),
+            (None, _) => expr.to_string(),
+        },
+        _ => format!("{expr}.into()"),
+    }
+}
+
+/// Maps types to the owned Rust types that UniFFI knows how to lift and lower,
+/// with user types mapped to their mirror types.
+impl TypeMapper for RustCodeGenerator<'_> {
+    type Output = String;
+
+    fn map_map(&mut self, ty: &Ty, key: String, value: String, repr: &MapSetRepr) -> anyhow::Result<String> {
+        match repr {
+            MapSetRepr::Hash => Ok(format!("std::collections::HashMap<{key}, {value}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_vec(&mut self, ty: &Ty, element: String, repr: &VecRepr) -> anyhow::Result<String> {
+        match repr {
+            VecRepr::Vec | VecRepr::SliceRef | VecRepr::ImplIntoIterator => Ok(format!("Vec<{element}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_string(&mut self, ty: &Ty, repr: &StringRepr) -> anyhow::Result<String> {
+        match repr {
+            StringRepr::String | StringRepr::StrRef => Ok("String".to_string()),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_option(&mut self, ty: &Ty, element: String, repr: &OptionRepr) -> anyhow::Result<String> {
+        match repr {
+            OptionRepr::Option => Ok(format!("Option<{element}>")),
+            _ => type_mapper::unsupported(ty),
+        }
+    }
+
+    fn map_tuple(&mut self, ty: &Ty, elements: Vec<String>, _repr: &TupleRepr) -> anyhow::Result<String> {
+        // UniFFI has no tuples, but `()` is fine as a return type.
+        if elements.is_empty() {
+            Ok("()".to_string())
+        } else {
+            type_mapper::unsupported(ty)
+        }
+    }
+
+    fn map_scalar(&mut self, ty: &Ty, scalar: &Scalar) -> anyhow::Result<String> {
+        match scalar {
+            Scalar::Char => type_mapper::unsupported(ty),
+            _ => Ok(scalar.to_string()),
+        }
+    }
+
+    fn map_user_type(&mut self, ty: &Ty, qname: &QualifiedName) -> anyhow::Result<String> {
+        // UniFFI cannot lift or lower boxed records, so recursive types (e.g., `next: Option<Box<Node>>`) are not supported.
+        if let TypeKind::UserType { repr: UserTypeRepr::Boxed, .. } = ty.kind() {
+            anyhow::bail!("{}: boxed types like `{}` are not supported by the uniffi backend", ty.span(), ty.rust_spelling());
+        }
+        Ok(qname.tail_name().to_string())
+    }
+
+    fn map_custom(&mut self, ty: &Ty, path: &str) -> anyhow::Result<String> {
+        Ok(self.type_overrides.expect(ty, path)?.repr_or(path).to_string())
+    }
+}
@@ -1,35 +1,65 @@
+use anyhow::Context;
 use gluegun_core::{
-    cli::{GenerateCx, GlueGunHelper},
-    codegen::LibraryCrate,
+    cli::{GenerateCx, GlueGunHelper, TargetCfg},
+    codegen::{LibraryCrate, RequiredTools},
 };
+use metadata::WasmMetadata;
 use rs_gen::RustCodeGenerator;
 
 pub fn main() -> anyhow::Result<()> {
     gluegun_core::cli::run(GlueGunWasm)
 }
 
+mod metadata;
+mod npm_gen;
 mod rs_gen;
 
+#[derive(Clone)]
 struct GlueGunWasm;
 
 impl GlueGunHelper for GlueGunWasm {
-    type Metadata = ();
+    type Metadata = WasmMetadata;
+
+    const SUPPORTS_TARGET_GATES: bool = true;
 
     fn name(&self) -> String {
         format!("wasm")
     }
 
+    /// `wasm-pack build --target web` builds for `wasm32-unknown-unknown` (see `npm_gen`).
+    fn target(&self, _metadata: &WasmMetadata) -> Option<TargetCfg> {
+        Some(
+            TargetCfg::new()
+                .name_value("target_arch", "wasm32")
+                .name_value("target_family", "wasm")
+                .name_value("target_os", "unknown")
+                .name_value("target_env", "")
+                .name_value("target_vendor", "unknown")
+                .name_value("target_pointer_width", "32")
+                .name_value("target_endian", "little"),
+        )
+    }
+
     fn generate(
         self,
         cx: &mut GenerateCx,
-        _metadata: &Self::Metadata,
+        metadata: &Self::Metadata,
         output: &mut LibraryCrate,
     ) -> anyhow::Result<()> {
         output.require_helper_command("cargo-component").or_run_cargo_install("cargo-component");
 
-        RustCodeGenerator::new(cx.idl()).generate(output)?;
-        output.add_dependency("wasm-bindgen").version("0.2");
+        RustCodeGenerator::new(cx.idl(), cx.target_gates()).generate(output)?;
+
+        npm_gen::NpmGenerator::new(cx.idl(), metadata)
+            .generate(output)
+            .context("generating the npm package")?;
 
         Ok(())
     }
+
+    /// The crate is built with `cargo-component` and the npm package around it with `wasm-pack` (see `npm_gen`).
+    fn required_tools(&self, _metadata: &WasmMetadata, tools: &mut RequiredTools) {
+        tools.require_helper_command("cargo-component").or_run_cargo_install("cargo-component");
+        tools.require_helper_command("wasm-pack").or_run_cargo_install("wasm-pack");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,30 @@
+use gluegun_core::cli::{DescribeMetadata, MetadataKind, MetadataSchema};
+use serde::Deserialize;
+
+/// Configuration for the wasm helper, read from `[package.metadata.gluegun.wasm]`
+/// (or the `workspace` equivalent).
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct WasmMetadata {
+    /// The name of the npm package; defaults to the name of the generated crate.
+    pub npm_name: Option<String>,
+
+    /// The version of the npm package; defaults to the version of the crate.
+    pub npm_version: Option<String>,
+}
+
+impl DescribeMetadata for WasmMetadata {
+    fn describe() -> MetadataSchema {
+        MetadataSchema::new()
+            .option(
+                "npm-name",
+                MetadataKind::String,
+                "The name of the npm package (e.g., `@my-org/my-crate`); defaults to the name of the generated crate",
+            )
+            .option(
+                "npm-version",
+                MetadataKind::String,
+                "The version of the npm package; defaults to the version of the crate",
+            )
+    }
+}
@@ -0,0 +1,137 @@
+use gluegun_core::{
+    codegen::LibraryCrate,
+    idl::{Idl, Item, TypeKind},
+};
+use serde_json::{json, Map, Value};
+
+use crate::metadata::WasmMetadata;
+
+/// Generates the scaffold of an npm package around the output of `wasm-pack build --target web`, which goes to `pkg/`:
+/// `package.json`, `index.js` (re-exporting the functions along with a `load` function instantiating the module),
+/// and `examples/node.mjs`.
+pub(crate) struct NpmGenerator<'idl> {
+    idl: &'idl Idl,
+    metadata: &'idl WasmMetadata,
+}
+
+impl<'idl> NpmGenerator<'idl> {
+    pub(crate) fn new(idl: &'idl Idl, metadata: &'idl WasmMetadata) -> Self {
+        Self { idl, metadata }
+    }
+
+    pub(crate) fn generate(self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
+        // `wasm-pack` names its output after the library of the crate.
+        let module = lib.crate_name().replace('-', "_");
+        self.generate_package_json(lib, &module)?;
+        self.generate_index_js(lib, &module)?;
+        self.generate_example(lib, &module)?;
+        Ok(())
+    }
+
+    fn generate_package_json(&self, lib: &mut LibraryCrate, module: &str) -> anyhow::Result<()> {
+        let crate_metadata = self.idl.crate_metadata();
+        let name = self.metadata.npm_name.clone().unwrap_or_else(|| lib.crate_name().to_string());
+        let version = self
+            .metadata
+            .npm_version
+            .as_deref()
+            .or(crate_metadata.version().as_deref())
+            .unwrap_or("0.1.0");
+
+        let mut package = Map::new();
+        package.insert("name".to_string(), json!(name));
+        package.insert("version".to_string(), json!(version));
+        if let Some(description) = crate_metadata.description() {
+            package.insert("description".to_string(), json!(description));
+        }
+        if let Some(repository) = crate_metadata.repository() {
+            package.insert("repository".to_string(), json!(repository));
+        }
+        package.insert("type".to_string(), json!("module"));
+        package.insert("main".to_string(), json!("index.js"));
+        package.insert("types".to_string(), json!(format!("pkg/{module}.d.ts")));
+        package.insert(
+            "files".to_string(),
+            json!([
+                "index.js",
+                format!("pkg/{module}.js"),
+                format!("pkg/{module}.d.ts"),
+                format!("pkg/{module}_bg.wasm"),
+            ]),
+        );
+        package.insert(
+            "scripts".to_string(),
+            json!({
+                "build": "wasm-pack build --target web",
+                "prepack": "wasm-pack build --target web",
+                "example": "node examples/node.mjs",
+            }),
+        );
+
+        // The pretty-printed JSON is indented the same way `CodeWriter` would indent it.
+        let mut package_json = lib.add_file("package.json")?;
+        for line in serde_json::to_string_pretty(&Value::Object(package))?.lines() {
+            write!(package_json, "{}", line.trim_start())?;
+        }
+        Ok(())
+    }
+
+    fn generate_index_js(&self, lib: &mut LibraryCrate, module: &str) -> anyhow::Result<()> {
+        let mut index_js = lib.add_file("index.js")?;
+        write!(index_js, "// The functions of the package, once `load` has instantiated the WebAssembly module.")?;
+        write!(index_js, "import init from \"./pkg/{module}.js\";")?;
+        write!(index_js, "")?;
+        write!(index_js, "export * from \"./pkg/{module}.js\";")?;
+        write!(index_js, "")?;
+        if self.returns_streams() {
+            write!(index_js, "// Streams are async iterables, consumed with `for await (const item of stream)`.")?;
+            write!(index_js, "import {{ Stream }} from \"./pkg/{module}.js\";")?;
+            write!(index_js, "Stream.prototype[Symbol.asyncIterator] = function () {{")?;
+            write!(index_js, "return this;")?;
+            write!(index_js, "}};")?;
+            write!(index_js, "")?;
+        }
+        write!(index_js, "let loaded;")?;
+        write!(index_js, "")?;
+        write!(index_js, "// Instantiate the WebAssembly module (only the first time it is called); the other functions")?;
+        write!(index_js, "// can be called once the returned promise resolves. Browsers fetch `pkg/{module}_bg.wasm`")?;
+        write!(index_js, "// unless given a `module`; elsewhere (e.g., in Node.js), pass its bytes or a `WebAssembly.Module`.")?;
+        write!(index_js, "export function load(module) {{")?;
+        write!(index_js, "loaded ??= init(module === undefined ? undefined : {{ module_or_path: module }});")?;
+        write!(index_js, "return loaded;")?;
+        write!(index_js, "}}")?;
+        Ok(())
+    }
+
+    /// True if some function returns a stream, which needs the `Stream` class of the generated crate.
+    fn returns_streams(&self) -> bool {
+        self.idl.definitions().values().any(|item| match item {
+            Item::Function(function) => {
+                matches!(function.signature().output_ty().main_ty().ty().kind(), TypeKind::Stream { .. })
+            }
+            _ => false,
+        })
+    }
+
+    fn generate_example(&self, lib: &mut LibraryCrate, module: &str) -> anyhow::Result<()> {
+        let functions: Vec<String> = self
+            .idl
+            .definitions()
+            .values()
+            .filter_map(|item| match item {
+                Item::Function(function) => Some(function.name().to_string()),
+                _ => None,
+            })
+            .collect();
+
+        let mut example = lib.add_file("examples/node.mjs")?;
+        write!(example, "// Run with `npm run example` once the package is built with `npm run build`.")?;
+        write!(example, "import {{ readFile }} from \"node:fs/promises\";")?;
+        let imports = std::iter::once("load").chain(functions.iter().map(String::as_str));
+        write!(example, "import {{ {} }} from \"../index.js\";", imports.collect::<Vec<_>>().join(", "))?;
+        write!(example, "")?;
+        write!(example, "await load(await readFile(new URL(\"../pkg/{module}_bg.wasm\", import.meta.url)));")?;
+        write!(example, "console.log(\"functions:\", [{}].map((f) => f.name));", functions.join(", "))?;
+        Ok(())
+    }
+}
@@ -1,25 +1,39 @@
+use std::collections::BTreeMap;
+
 use gluegun_core::{
-    codegen::LibraryCrate,
-    idl::Idl,
+    cli::TargetGates,
+    codegen::{copy_out, CodeWriter, LibraryCrate},
+    idl::{Function, Idl, IsAsync, Item, QualifiedName, RefdTy, Ty, TypeKind, VecRepr},
 };
 
 pub(crate) struct RustCodeGenerator<'idl> {
-    #[expect(dead_code)]
     idl: &'idl Idl,
-    features: Vec<&'static str>,
+    /// Definitions excluded on WebAssembly are already left out of `idl`; the others may still be gated
+    /// on more than the target (e.g., a feature).
+    target_gates: &'idl TargetGates,
+    /// Dependencies of the generated crate (and their versions) needed by the code generated so far, besides `wasm-bindgen`.
+    dependencies: BTreeMap<&'static str, &'static str>,
+    /// True once a function returning a stream was generated, which needs the `Stream` class (see `generate_stream_class`).
+    uses_streams: bool,
 }
 
 impl<'idl> RustCodeGenerator<'idl> {
-    pub(crate) fn new(idl: &'idl Idl) -> Self {
+    pub(crate) fn new(idl: &'idl Idl, target_gates: &'idl TargetGates) -> Self {
         Self {
             idl,
-            features: Default::default(),
+            target_gates,
+            dependencies: Default::default(),
+            uses_streams: false,
         }
     }
 
-    pub(crate) fn generate(mut self, lib: &mut LibraryCrate) -> anyhow::Result<Vec<&'static str>> {
+    pub(crate) fn generate(mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
         self.generate_lib_rs(lib)?;
-        Ok(self.features)
+        lib.add_dependency("wasm-bindgen").version("0.2");
+        for (crate_name, version) in self.dependencies {
+            lib.add_dependency(crate_name).version(version);
+        }
+        Ok(())
     }
 
     fn generate_lib_rs(&mut self, lib: &mut LibraryCrate) -> anyhow::Result<()> {
@@ -27,6 +41,177 @@ impl<'idl> RustCodeGenerator<'idl> {
 
         write!(lib_rs, "#![allow(non_snake_case)]")?; // FIXME: bug in duchess
 
+        self.generate_panic_hook(&mut lib_rs)?;
+
+        for (qname, item) in self.idl.definitions() {
+            lib_rs.with_span(item.span(), |lib_rs| self.generate_item(lib_rs, qname, item))?;
+        }
+        if self.uses_streams {
+            self.generate_stream_class(&mut lib_rs)?;
+        }
+
+        Ok(())
+    }
+
+    /// Generate the panic hook installed when the module is instantiated. Panics abort on `wasm32`,
+    /// which JavaScript sees as an opaque `RuntimeError: unreachable`; the hook throws an `Error`
+    /// carrying the panic's message instead (the module should not be used any further afterwards).
+    fn generate_panic_hook(&mut self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        write!(lib_rs, "#[wasm_bindgen::prelude::wasm_bindgen(start)]")?;
+        write!(lib_rs, "fn gluegun_start() {{")?;
+        write!(lib_rs, "std::panic::set_hook(Box::new(|info| {{")?;
+        write!(lib_rs, "wasm_bindgen::throw_str(&format!(\"Rust panicked: {{info}}\"));")?;
+        write!(lib_rs, "}}));")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "")?;
+        Ok(())
+    }
+
+    /// Generate the `Stream` class returned by functions returning an `impl Stream`. Its `next` method
+    /// returns a `Promise` of the results of a JavaScript async iterator (`{ value, done }`),
+    /// and `index.js` makes it an async iterable (see `npm_gen`), so that it can be consumed with `for await`.
+    fn generate_stream_class(&mut self, lib_rs: &mut CodeWriter<'_>) -> anyhow::Result<()> {
+        self.dependencies.insert("futures", "0.3");
+        self.dependencies.insert("js-sys", "0.3");
+        self.dependencies.insert("wasm-bindgen-futures", "0.4");
+
+        write!(lib_rs, "#[allow(non_camel_case_types)]")?;
+        write!(lib_rs, "type gluegun_JsStream = std::pin::Pin<Box<dyn futures::Stream<Item = wasm_bindgen::JsValue>>>;")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[wasm_bindgen::prelude::wasm_bindgen(js_name = \"Stream\")]")?;
+        write!(lib_rs, "#[allow(non_camel_case_types)]")?;
+        write!(lib_rs, "pub struct gluegun_Stream(std::rc::Rc<futures::lock::Mutex<gluegun_JsStream>>);")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "impl gluegun_Stream {{")?;
+        write!(
+            lib_rs,
+            "fn new<T: Into<wasm_bindgen::JsValue>>(stream: impl futures::Stream<Item = T> + 'static) -> Self {{"
+        )?;
+        write!(lib_rs, "let stream = futures::StreamExt::map(stream, Into::into);")?;
+        write!(lib_rs, "Self(std::rc::Rc::new(futures::lock::Mutex::new(Box::pin(stream))))")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "")?;
+        write!(lib_rs, "#[wasm_bindgen::prelude::wasm_bindgen(js_class = \"Stream\")]")?;
+        write!(lib_rs, "impl gluegun_Stream {{")?;
+        write!(lib_rs, "pub fn next(&self) -> js_sys::Promise {{")?;
+        write!(lib_rs, "let stream = std::rc::Rc::clone(&self.0);")?;
+        write!(lib_rs, "wasm_bindgen_futures::future_to_promise(async move {{")?;
+        write!(lib_rs, "let item = futures::StreamExt::next(&mut *stream.lock().await).await;")?;
+        write!(lib_rs, "let result = js_sys::Object::new();")?;
+        write!(lib_rs, "js_sys::Reflect::set(&result, &\"done\".into(), &item.is_none().into())?;")?;
+        write!(
+            lib_rs,
+            "js_sys::Reflect::set(&result, &\"value\".into(), &item.unwrap_or(wasm_bindgen::JsValue::UNDEFINED))?;"
+        )?;
+        write!(lib_rs, "Ok(result.into())")?;
+        write!(lib_rs, "}})")?;
+        write!(lib_rs, "}}")?;
+        write!(lib_rs, "}}")?;
+        Ok(())
+    }
+
+    fn generate_item(&mut self, lib_rs: &mut CodeWriter<'_>, qname: &QualifiedName, item: &Item) -> anyhow::Result<()> {
+        match item {
+            Item::Function(function) => self.generate_function(lib_rs, qname, function),
+            _ => anyhow::bail!("`{}`: only functions are currently supported by the wasm backend", qname.colon_colon()),
+        }
+    }
+
+    fn generate_function(
+        &mut self,
+        lib_rs: &mut CodeWriter<'_>,
+        qname: &QualifiedName,
+        function: &Function,
+    ) -> anyhow::Result<()> {
+        let signature = function.signature();
+        let output_ty = signature.output_ty();
+
+        // Async functions, and functions returning an `impl Future`, map to `async` functions,
+        // which `wasm-bindgen-futures` turns into functions returning a JavaScript `Promise`.
+        let (is_async, main_ty) = match (signature.is_async(), output_ty.main_ty().ty().kind()) {
+            (_, TypeKind::Future { output, repr: _ }) => (true, output),
+            (IsAsync::Yes, _) => (true, output_ty.main_ty().ty()),
+            (IsAsync::No, _) => (false, output_ty.main_ty().ty()),
+        };
+        if is_async {
+            self.dependencies.insert("wasm-bindgen-futures", "0.4");
+        }
+
+        // Streams are returned as a `Stream` object, which JavaScript iterates with `for await`.
+        let returns_stream = matches!(main_ty.kind(), TypeKind::Stream { .. });
+        if returns_stream {
+            if is_async {
+                anyhow::bail!(
+                    "`{}`: the wasm backend does not support async functions returning a stream",
+                    qname.colon_colon()
+                );
+            }
+            self.uses_streams = true;
+        }
+
+        if let Some(cfg_attr) = self.target_gates.cfg_attr(qname) {
+            write!(lib_rs, "{cfg_attr}")?;
+        }
+        write!(lib_rs, "#[wasm_bindgen::prelude::wasm_bindgen]")?;
+        let async_kw = if is_async { "async " } else { "" };
+        write!(lib_rs, "pub {async_kw}fn {}(", function.name())?;
+        for input in signature.inputs() {
+            // wasm-bindgen functions cannot be generic, so `impl IntoIterator<Item = T>` is given a `Vec<T>`.
+            // The future of an async function must own its arguments, which are lent to the function being called.
+            let ty = match input.refd_ty().ty().kind() {
+                TypeKind::Vec { repr: VecRepr::ImplIntoIterator, .. } => input.refd_ty().ty().rust_owned_spelling(),
+                _ if is_async => input.refd_ty().ty().rust_owned_spelling(),
+                _ => input.refd_ty().rust_spelling(),
+            };
+            write!(lib_rs, "{}: {ty},", input.name())?;
+        }
+
+        // Errors are reported to JavaScript as an `Error` carrying the error's message, whose `name` is the Rust error type;
+        // async functions reject their promise with it.
+        let main_ty = if returns_stream { "gluegun_Stream".to_string() } else { main_ty.rust_owned_spelling() };
+        match output_ty.error_ty() {
+            Some(_) => write!(lib_rs, ") -> Result<{main_ty}, wasm_bindgen::JsValue> {{")?,
+            None => write!(lib_rs, ") -> {main_ty} {{")?,
+        }
+        let await_kw = if is_async { ".await" } else { "" };
+        let arguments: Vec<String> = signature
+            .inputs()
+            .iter()
+            .map(|input| match input.refd_ty() {
+                RefdTy::Ref(..) if is_async => format!("&{}", input.name()),
+                _ => input.name().to_string(),
+            })
+            .collect();
+        let call = format!("{}({}){await_kw}", qname.colon_colon(), arguments.join(","));
+        let returned = |value: &str| {
+            if returns_stream {
+                format!("gluegun_Stream::new({value})")
+            } else {
+                copy_out(output_ty, value)
+            }
+        };
+        if let Some(error_ty) = output_ty.error_ty() {
+            self.dependencies.insert("js-sys", "0.3");
+            write!(lib_rs, "let result = {call}.map_err(|err| {{")?;
+            write!(lib_rs, "let error = js_sys::Error::new(&err.to_string());")?;
+            write!(lib_rs, "error.set_name({:?});", error_name(error_ty))?;
+            write!(lib_rs, "wasm_bindgen::JsValue::from(error)")?;
+            write!(lib_rs, "}})?;")?;
+            write!(lib_rs, "Ok({})", returned("result"))?;
+        } else {
+            write!(lib_rs, "{}", returned(&call))?;
+        }
+        write!(lib_rs, "}}")?;
+
         Ok(())
     }
 }
+
+/// The `name` of the JavaScript `Error` reporting an error of type `ty`, e.g. `ParseError`.
+fn error_name(ty: &Ty) -> String {
+    match ty.kind() {
+        TypeKind::UserType { qname, repr: _ } => qname.tail_name().to_string(),
+        _ => "Error".to_string(),
+    }
+}
@@ -0,0 +1,49 @@
+//! A resource with methods taking `self: Arc<Self>`, which the bindings hold in an `Arc` to give those methods a clone of.
+
+use std::sync::Arc;
+
+/// A counter that can be watched while it is shared.
+pub struct Counter {
+    value: u32,
+}
+
+impl Counter {
+    pub fn new(start: u32) -> Self {
+        Counter { value: start }
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    /// Fails in the bindings while a `Watcher` shares the counter.
+    pub fn increment(&mut self) {
+        self.value += 1;
+    }
+
+    /// Shares the counter with the returned watcher.
+    pub fn watch(self: Arc<Self>) -> Watcher {
+        Watcher { counter: self }
+    }
+
+    /// Fails in the bindings while a `Watcher` shares the counter.
+    pub fn finish(self) -> u32 {
+        self.value
+    }
+}
+
+/// Reads a shared counter.
+pub struct Watcher {
+    counter: Arc<Counter>,
+}
+
+impl Watcher {
+    pub fn value(&self) -> u32 {
+        self.counter.value
+    }
+
+    /// The number of references to the counter, including the one of the watcher.
+    pub fn sharers(&self) -> u32 {
+        Arc::strong_count(&self.counter) as u32
+    }
+}
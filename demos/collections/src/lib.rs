@@ -0,0 +1,31 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use indexmap::IndexMap;
+
+pub fn count_words(text: &str) -> HashMap<String, u32> {
+    let mut counts = HashMap::new();
+    for word in text.split_whitespace() {
+        *counts.entry(word.to_string()).or_insert(0) += 1;
+    }
+    counts
+}
+
+pub fn sorted_counts(counts: HashMap<String, u32>) -> BTreeMap<String, u32> {
+    counts.into_iter().collect()
+}
+
+pub fn first_seen(text: &str) -> IndexMap<String, u32> {
+    let mut counts = IndexMap::new();
+    for word in text.split_whitespace() {
+        *counts.entry(word.to_string()).or_insert(0) += 1;
+    }
+    counts
+}
+
+pub fn unique_words(text: &str) -> HashSet<String> {
+    text.split_whitespace().map(|word| word.to_string()).collect()
+}
+
+pub fn sorted_words(words: HashSet<String>) -> BTreeSet<String> {
+    words.into_iter().collect()
+}
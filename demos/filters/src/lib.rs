@@ -0,0 +1,39 @@
+//! Definitions and methods selected by the `include` and `exclude` patterns of the tests.
+
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+pub struct Client {
+    requests: u32,
+}
+
+impl Client {
+    pub fn new() -> Self {
+        Client { requests: 0 }
+    }
+
+    pub fn fetch(&mut self) -> u32 {
+        self.requests += 1;
+        self.requests
+    }
+
+    pub fn reset_internal(&mut self) {
+        self.requests = 0;
+    }
+}
+
+pub fn api_distance(point: Point) -> f64 {
+    point.x.hypot(point.y)
+}
+
+pub fn api_version() -> u32 {
+    1
+}
+
+pub fn dump_internal() -> String {
+    String::from("internal state")
+}
+
+pub mod shapes;
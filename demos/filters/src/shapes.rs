@@ -0,0 +1,5 @@
+pub mod solids;
+
+pub fn square_area(side: f64) -> f64 {
+    side * side
+}
@@ -0,0 +1,3 @@
+pub fn cube_volume(side: f64) -> f64 {
+    side * side * side
+}
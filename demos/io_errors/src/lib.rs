@@ -0,0 +1,22 @@
+//! Functions failing with `std::io::Error`, which the generated bindings raise as the I/O exception
+//! of the target language, and with `Box<dyn Error>`, raised like other errors.
+
+use std::{error::Error, io};
+
+/// Fails with the OS error (e.g., `ENOENT`) if `path` cannot be read.
+pub fn read_text(path: &str) -> io::Result<String> {
+    std::fs::read_to_string(path)
+}
+
+/// Fails with an error of kind `InvalidInput`, which has no OS error code.
+pub fn check_name(name: &str) -> Result<u32, io::Error> {
+    if name.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "the name is empty"));
+    }
+    Ok(name.len() as u32)
+}
+
+/// Fails if `text` is not a port number.
+pub fn parse_port(text: &str) -> Result<u16, Box<dyn Error + Send + Sync>> {
+    Ok(text.parse()?)
+}
@@ -0,0 +1,6 @@
+pub mod geometry;
+
+/// Rounds to the nearest multiple of `step`.
+pub fn snap(value: f64, step: f64) -> f64 {
+    (value / step).round() * step
+}
@@ -0,0 +1,467 @@
+//! Exercises every feature of the IDL, so that each backend is tested against all of them
+//! (see the `kitchen_sink_*` tests in `tests/idl_tests.rs`). A backend that cannot map something here
+//! should report an error, not generate code that fails to build.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use indexmap::{IndexMap, IndexSet};
+
+// Each scalar type as an argument and a result.
+
+pub fn negate_bool(value: bool) -> bool {
+    !value
+}
+
+pub fn next_char(value: char) -> char {
+    char::from_u32(value as u32 + 1).unwrap_or(value)
+}
+
+pub fn add_i8(a: i8, b: i8) -> i8 {
+    a.wrapping_add(b)
+}
+
+pub fn add_i16(a: i16, b: i16) -> i16 {
+    a.wrapping_add(b)
+}
+
+pub fn add_i32(a: i32, b: i32) -> i32 {
+    a.wrapping_add(b)
+}
+
+pub fn add_i64(a: i64, b: i64) -> i64 {
+    a.wrapping_add(b)
+}
+
+pub fn add_u8(a: u8, b: u8) -> u8 {
+    a.wrapping_add(b)
+}
+
+pub fn add_u16(a: u16, b: u16) -> u16 {
+    a.wrapping_add(b)
+}
+
+pub fn add_u32(a: u32, b: u32) -> u32 {
+    a.wrapping_add(b)
+}
+
+pub fn add_u64(a: u64, b: u64) -> u64 {
+    a.wrapping_add(b)
+}
+
+pub fn add_f32(a: f32, b: f32) -> f32 {
+    a + b
+}
+
+pub fn add_f64(a: f64, b: f64) -> f64 {
+    a + b
+}
+
+/// Strings, borrowed and owned.
+pub fn concat(a: &str, b: String) -> String {
+    format!("{a}{b}")
+}
+
+/// A function with no arguments and no result.
+pub fn nothing() {}
+
+// Collections, tuples, and paths, as arguments and results.
+
+pub fn sum_slice(values: &[u32]) -> u32 {
+    values.iter().sum()
+}
+
+pub fn reversed(values: Vec<String>) -> Vec<String> {
+    values.into_iter().rev().collect()
+}
+
+pub fn collect(values: impl IntoIterator<Item = u32>) -> Vec<u32> {
+    values.into_iter().collect()
+}
+
+pub fn hash_map(keys: Vec<String>) -> HashMap<String, u32> {
+    keys.into_iter().zip(0..).collect()
+}
+
+pub fn btree_map(map: HashMap<String, u32>) -> BTreeMap<String, u32> {
+    map.into_iter().collect()
+}
+
+pub fn index_map(map: BTreeMap<String, u32>) -> IndexMap<String, u32> {
+    map.into_iter().collect()
+}
+
+pub fn hash_set(values: Vec<u32>) -> HashSet<u32> {
+    values.into_iter().collect()
+}
+
+pub fn btree_set(values: HashSet<u32>) -> BTreeSet<u32> {
+    values.into_iter().collect()
+}
+
+pub fn index_set(values: BTreeSet<u32>) -> IndexSet<u32> {
+    values.into_iter().collect()
+}
+
+pub fn nested(values: Vec<Vec<u32>>) -> HashMap<String, Vec<u32>> {
+    values.into_iter().enumerate().map(|(index, values)| (index.to_string(), values)).collect()
+}
+
+pub fn swap(pair: (u32, String)) -> (String, u32) {
+    (pair.1, pair.0)
+}
+
+pub fn file_name(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+pub fn with_extension(path: PathBuf, extension: &str) -> PathBuf {
+    path.with_extension(extension)
+}
+
+// Options of scalars, strings, and collections.
+
+pub fn or_zero(value: Option<u32>) -> u32 {
+    value.unwrap_or(0)
+}
+
+pub fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+pub fn first(values: Vec<i64>) -> Option<i64> {
+    values.first().copied()
+}
+
+pub fn maybe_values(count: Option<u32>) -> Option<Vec<u32>> {
+    count.map(|count| (0..count).collect())
+}
+
+// Fallible functions, with and without a value.
+
+/// The error of the functions in this module.
+#[derive(Debug)]
+pub struct ParseError {
+    message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<&str> for ParseError {
+    fn from(message: &str) -> Self {
+        ParseError { message: message.to_string() }
+    }
+}
+
+pub fn parse_u32(text: &str) -> Result<u32, ParseError> {
+    text.parse().map_err(|err: std::num::ParseIntError| ParseError { message: err.to_string() })
+}
+
+pub fn check_not_empty(text: &str) -> Result<(), ParseError> {
+    match text.is_empty() {
+        true => Err(ParseError::from("empty")),
+        false => Ok(()),
+    }
+}
+
+pub fn parse_all(texts: Vec<String>) -> Result<Vec<u32>, ParseError> {
+    texts.iter().map(|text| parse_u32(text)).collect()
+}
+
+// Async functions, fallible or not.
+
+pub async fn double(value: u32) -> u32 {
+    value * 2
+}
+
+pub async fn parse_later(text: String) -> Result<u32, ParseError> {
+    parse_u32(&text)
+}
+
+/// Written out to return an `impl Future`.
+#[allow(clippy::manual_async_fn)]
+pub fn greet_later(name: String) -> impl Future<Output = String> {
+    async move { format!("Hello, {name}!") }
+}
+
+// Records, enums, variants, and newtypes, passed by value.
+
+/// A record: a struct whose fields are all public.
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+    pub label: Option<String>,
+}
+
+/// A record containing other user types.
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+    pub style: Style,
+}
+
+/// An enum without associated data.
+#[derive(Copy, Clone)]
+pub enum Style {
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl Style {
+    pub fn is_solid(&self) -> bool {
+        matches!(self, Style::Solid)
+    }
+}
+
+/// An enum with associated data, with named, tuple-like, and empty arms.
+pub enum Shape {
+    Circle { radius: f64 },
+    Rect(u32, u32),
+    Empty,
+}
+
+impl Shape {
+    pub fn area(&self) -> f64 {
+        match self {
+            Shape::Circle { radius } => std::f64::consts::PI * radius * radius,
+            Shape::Rect(width, height) => f64::from(width * height),
+            Shape::Empty => 0.0,
+        }
+    }
+}
+
+/// A newtype.
+pub struct UserId(pub u64);
+
+/// A unit struct: a record without fields.
+pub struct Unbounded;
+
+pub fn origin() -> Point {
+    Point { x: 0.0, y: 0.0, label: None }
+}
+
+pub fn length(segment: Segment) -> f64 {
+    (segment.end.x - segment.start.x).hypot(segment.end.y - segment.start.y)
+}
+
+pub fn next_style(style: Style) -> Style {
+    match style {
+        Style::Solid => Style::Dashed,
+        Style::Dashed => Style::Dotted,
+        Style::Dotted => Style::Solid,
+    }
+}
+
+pub fn circle(radius: f64) -> Shape {
+    Shape::Circle { radius }
+}
+
+pub fn total_area(shapes: Vec<Shape>) -> f64 {
+    shapes.iter().map(Shape::area).sum()
+}
+
+pub fn next_user(id: UserId) -> UserId {
+    UserId(id.0 + 1)
+}
+
+pub fn unbounded() -> Unbounded {
+    Unbounded
+}
+
+pub fn limit(_bound: Unbounded) -> Option<u64> {
+    None
+}
+
+// Resources following the class pattern, with a method of each category.
+
+/// A resource with private fields.
+pub struct Counter {
+    name: String,
+    count: u64,
+}
+
+impl Counter {
+    /// The constructor.
+    pub fn new(name: &str) -> Self {
+        Counter { name: name.to_string(), count: 0 }
+    }
+
+    /// A named constructor.
+    pub fn starting_at(name: &str, count: u64) -> Self {
+        Counter { name: name.to_string(), count }
+    }
+
+    /// A fallible named constructor.
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let count = parse_u32(text)?;
+        Ok(Counter::starting_at(text, count.into()))
+    }
+
+    /// A static method.
+    pub fn default_name() -> String {
+        "counter".to_string()
+    }
+
+    /// A `&self` method.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// A `&self` method returning a reference.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// A `&mut self` method.
+    pub fn increment(&mut self, by: u64) {
+        self.count += by;
+    }
+
+    /// A fallible `&mut self` method.
+    pub fn decrement(&mut self) -> Result<u64, ParseError> {
+        self.count = self.count.checked_sub(1).ok_or_else(|| ParseError::from("already at zero"))?;
+        Ok(self.count)
+    }
+
+    /// A builder method.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// A method consuming `self`.
+    pub fn finish(self) -> u64 {
+        self.count
+    }
+
+    /// An async method.
+    pub async fn count_later(&self) -> u64 {
+        self.count
+    }
+
+    /// A method taking and returning other user types.
+    pub fn to_point(&self, y: f64) -> Point {
+        Point { x: self.count as f64, y, label: Some(self.name.clone()) }
+    }
+}
+
+/// A resource passed to and returned by other resources.
+pub struct Registry {
+    counters: Vec<Counter>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry { counters: vec![] }
+    }
+
+    pub fn add(&mut self, counter: Counter) {
+        self.counters.push(counter);
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counters.iter().map(Counter::count).sum()
+    }
+
+    pub fn take(&mut self, name: &str) -> Option<Counter> {
+        let index = self.counters.iter().position(|counter| counter.name == name)?;
+        Some(self.counters.remove(index))
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A resource that is `Send` but not `Sync`.
+pub struct Journal {
+    entries: RefCell<Vec<String>>,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Journal { entries: Default::default() }
+    }
+
+    pub fn record(&self, entry: &str) {
+        self.entries.borrow_mut().push(entry.to_string());
+    }
+
+    pub fn entries(&self) -> Vec<String> {
+        self.entries.borrow().clone()
+    }
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A free function creating a resource.
+pub fn counter_named(name: &str) -> Counter {
+    Counter::new(name)
+}
+
+// Traits implemented in the target language, and by a resource.
+
+/// An interface implemented by foreign objects.
+pub trait Listener: Send + Sync {
+    fn on_event(&self, name: &str, count: u32) -> u32;
+
+    fn on_close(&self);
+}
+
+pub fn notify(listener: &dyn Listener, name: &str) -> u32 {
+    let result = listener.on_event(name, 1);
+    listener.on_close();
+    result
+}
+
+pub fn notify_boxed(listener: Box<dyn Listener>, count: u32) -> u32 {
+    listener.on_event("boxed", count)
+}
+
+/// A resource implementing the interface.
+pub struct Tally {
+    total: AtomicU32,
+}
+
+impl Tally {
+    pub fn new() -> Self {
+        Tally { total: Default::default() }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.total.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for Tally {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Listener for Tally {
+    fn on_event(&self, _name: &str, count: u32) -> u32 {
+        self.total.fetch_add(count, Ordering::Relaxed) + count
+    }
+
+    fn on_close(&self) {}
+}
@@ -0,0 +1,22 @@
+//! An API that gluegun cannot fully bind, to exercise `cargo gluegun lint`.
+
+/// A singly linked list; boxed types are not supported by every backend.
+pub struct Node {
+    pub value: i64,
+    pub next: Option<Box<Node>>,
+}
+
+/// Sum of the values in the list.
+pub fn sum(list: &Node) -> i64 {
+    list.value + list.next.as_deref().map_or(0, sum)
+}
+
+/// Generic functions cannot be bound.
+pub fn first<T: Clone>(items: &[T]) -> Option<T> {
+    items.first().cloned()
+}
+
+/// Returns a greeting for `name`.
+pub fn greet(name: &str) -> String {
+    format!("Hello, {name}!")
+}
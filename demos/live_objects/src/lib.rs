@@ -0,0 +1,20 @@
+//! A resource whose wrappers `debug-bindings` (see `Cargo.toml`) counts while they are live.
+
+/// A counter, owned by the foreign object wrapping it.
+pub struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    pub fn new() -> Self {
+        Self { count: 0 }
+    }
+
+    pub fn increment(&mut self) {
+        self.count += 1;
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
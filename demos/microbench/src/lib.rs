@@ -0,0 +1,55 @@
+//! Functions exercising the different kinds of calls measured by `cargo gluegun bench`.
+
+use std::collections::HashMap;
+
+/// Scalars only.
+pub fn add(a: u64, b: u64) -> u64 {
+    a.wrapping_add(b)
+}
+
+/// A string in and a string out.
+pub fn greet(name: &str) -> String {
+    format!("Hello, {name}!")
+}
+
+/// A collection in.
+pub fn sum_values(values: Vec<u64>) -> u64 {
+    values.iter().sum()
+}
+
+/// A collection out.
+pub fn histogram(words: Vec<String>) -> HashMap<String, u32> {
+    let mut counts = HashMap::new();
+    for word in words {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// An object passed across the boundary.
+#[derive(Default)]
+pub struct Accumulator {
+    total: u64,
+}
+
+impl Accumulator {
+    pub fn new() -> Self {
+        Accumulator { total: 0 }
+    }
+
+    pub fn with_total(total: u64) -> Self {
+        Accumulator { total }
+    }
+
+    pub fn add(&mut self, value: u64) {
+        self.total = self.total.wrapping_add(value);
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn finish(self) -> u64 {
+        self.total
+    }
+}
@@ -0,0 +1,40 @@
+//! Options of primitives, strings, collections, and user types, which `option = "optional"` (see `Cargo.toml`)
+//! maps to `java.util.Optional`.
+
+pub struct Contact {
+    pub name: String,
+    pub email: Option<String>,
+}
+
+pub enum Channel {
+    Email,
+    Phone,
+}
+
+pub fn parse_port(text: &str) -> Option<u16> {
+    text.parse().ok()
+}
+
+pub fn or_else(value: Option<i64>, fallback: i64) -> i64 {
+    value.unwrap_or(fallback)
+}
+
+pub fn greeting(name: Option<String>) -> String {
+    format!("Hello, {}!", name.as_deref().unwrap_or("stranger"))
+}
+
+pub fn first_word(words: Vec<String>) -> Option<String> {
+    words.into_iter().next()
+}
+
+pub fn preferred_channel(contact: Contact) -> Option<Channel> {
+    contact.email.map(|_| Channel::Email)
+}
+
+pub fn find(contacts: Vec<Contact>, name: &str) -> Option<Contact> {
+    contacts.into_iter().find(|contact| contact.name == name)
+}
+
+pub fn lengths(words: Option<Vec<String>>) -> Option<Vec<u32>> {
+    words.map(|words| words.iter().map(|word| word.len() as u32).collect())
+}
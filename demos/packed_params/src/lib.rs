@@ -0,0 +1,28 @@
+//! Functions with more parameters than `pack-params` (see `Cargo.toml`) also take them packed in a record.
+
+/// A user of the service.
+pub struct User {
+    pub name: String,
+    pub age: u32,
+    pub email: String,
+    pub admin: bool,
+}
+
+/// Creates a user; with `pack-params = 3`, its parameters are also packed in `CreateUserParams`.
+pub fn create_user(name: &str, age: u32, email: String, admin: bool) -> User {
+    User { name: name.to_string(), age, email, admin }
+}
+
+/// Records a meeting and returns its length, failing if it ends before it starts.
+pub fn schedule(title: String, start: u32, end: u32, attendees: Vec<String>) -> Result<u32, String> {
+    if end < start {
+        return Err(format!("`{title}` ends before it starts"));
+    }
+    let _ = attendees;
+    Ok(end - start)
+}
+
+/// Few enough parameters to be left as they are.
+pub fn greeting(name: &str) -> String {
+    format!("Hello, {name}!")
+}
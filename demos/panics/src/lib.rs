@@ -0,0 +1,13 @@
+//! Functions that panic, which the generated bindings raise as errors rather than letting the panic
+//! unwind into (and abort) the foreign process.
+
+/// Panics if `b` is zero.
+pub fn divide(a: u32, b: u32) -> u32 {
+    a / b
+}
+
+/// Panics if `text` is empty, or fails if it is not a number.
+pub fn parse_nonempty(text: &str) -> Result<u32, String> {
+    assert!(!text.is_empty(), "expected a non-empty string");
+    text.parse().map_err(|_| format!("not a number: {text}"))
+}
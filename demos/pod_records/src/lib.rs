@@ -0,0 +1,35 @@
+//! Records made only of scalars, which `repr-c-records` (see `Cargo.toml`) mirrors with `#[repr(C)]` structs.
+
+/// A POD-compatible record.
+pub struct Extent {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A newtype of a scalar, which its mirror unwraps.
+pub struct Meters(pub f64);
+
+/// A POD-compatible record containing another one, whose mirror contains the other's mirror.
+pub struct Placement {
+    pub extent: Extent,
+    pub elevation: Meters,
+    pub visible: bool,
+}
+
+/// Not POD-compatible, because of the `String`.
+pub struct Label {
+    pub extent: Extent,
+    pub text: String,
+}
+
+pub fn area(extent: Extent) -> u64 {
+    u64::from(extent.width) * u64::from(extent.height)
+}
+
+pub fn is_shown(placement: Placement) -> bool {
+    placement.visible && placement.extent.width > 0 && placement.extent.height > 0
+}
+
+pub fn label_text(label: Label) -> String {
+    label.text
+}
@@ -0,0 +1,35 @@
+//! A C-like enum, a resource, and functions, which the Python package wraps in pure Python
+//! (an `enum.Enum`, a re-exported class, and annotated functions calling the native module).
+
+pub mod palette;
+
+/// A color, which becomes an `enum.Enum` in Python.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+/// A brush, holding the color it paints with.
+pub struct Brush {
+    color: Color,
+}
+
+impl Brush {
+    pub fn new() -> Self {
+        Self { color: Color::Red }
+    }
+
+    pub fn strokes(&self, count: u32) -> String {
+        format!("{count} {:?} strokes", self.color)
+    }
+}
+
+pub fn complement(color: Color) -> Color {
+    match color {
+        Color::Red => Color::Green,
+        Color::Green => Color::Blue,
+        Color::Blue => Color::Red,
+    }
+}
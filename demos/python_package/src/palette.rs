@@ -0,0 +1,5 @@
+use crate::Color;
+
+pub fn favorite() -> Color {
+    Color::Blue
+}
@@ -0,0 +1,50 @@
+//! Types that contain themselves, through a collection or a `Box`.
+
+/// A tree whose nodes own their children.
+pub struct Tree {
+    pub value: u32,
+    pub children: Vec<Tree>,
+}
+
+/// A singly linked list.
+pub struct Node {
+    pub value: i64,
+    pub next: Option<Box<Node>>,
+}
+
+/// An arithmetic expression.
+pub enum Expr {
+    Number { value: f64 },
+    Negate { operand: Box<Expr> },
+    Add { left: Box<Expr>, right: Box<Expr> },
+}
+
+/// Number of values in the tree.
+pub fn size(tree: &Tree) -> u32 {
+    1 + tree.children.iter().map(size).sum::<u32>()
+}
+
+/// Sum of the values in the list.
+pub fn sum(list: &Node) -> i64 {
+    list.value + list.next.as_deref().map_or(0, sum)
+}
+
+/// The list in the opposite order.
+pub fn reverse(list: Box<Node>) -> Box<Node> {
+    let mut reversed = None;
+    let mut current = Some(list);
+    while let Some(mut node) = current {
+        current = node.next.take();
+        node.next = reversed;
+        reversed = Some(node);
+    }
+    reversed.expect("the list is not empty")
+}
+
+pub fn evaluate(expr: &Expr) -> f64 {
+    match expr {
+        Expr::Number { value } => *value,
+        Expr::Negate { operand } => -evaluate(operand),
+        Expr::Add { left, right } => evaluate(left) + evaluate(right),
+    }
+}
@@ -0,0 +1,43 @@
+//! Tuples of primitives, strings, user types, and other tuples, which Java maps to the `TupleN` classes.
+
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+pub enum Unit {
+    Metric,
+    Imperial,
+}
+
+pub struct Meters(pub f64);
+
+pub fn min_max(values: Vec<i32>) -> (i32, i32) {
+    let min = values.iter().copied().min().unwrap_or_default();
+    let max = values.iter().copied().max().unwrap_or_default();
+    (min, max)
+}
+
+pub fn swap(pair: (String, u64)) -> (u64, String) {
+    (pair.1, pair.0)
+}
+
+/// Tuples nest.
+pub fn flatten(nested: ((u8, u16), Option<bool>)) -> (u8, u16, bool) {
+    let ((a, b), c) = nested;
+    (a, b, c.unwrap_or_default())
+}
+
+/// Tuples hold records, enums, and newtypes.
+pub fn measure(point: Point, unit: Unit) -> (Point, Unit, Meters) {
+    let distance = f64::from(point.x).hypot(f64::from(point.y));
+    let distance = match unit {
+        Unit::Metric => distance,
+        Unit::Imperial => distance * 0.3048,
+    };
+    (point, unit, Meters(distance))
+}
+
+pub fn enumerate(labels: Vec<String>) -> Vec<(u32, String)> {
+    labels.into_iter().enumerate().map(|(index, label)| (index as u32, label)).collect()
+}
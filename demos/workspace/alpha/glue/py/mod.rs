@@ -0,0 +1,7 @@
+//! Hand-written code included in the generated crate as `mod custom;`.
+
+/// Greets each of `names` on its own line.
+#[allow(dead_code)]
+pub(crate) fn alpha_all(names: &[&str]) -> String {
+    names.iter().map(|name| alpha::alpha(name)).collect::<Vec<_>>().join("\n")
+}
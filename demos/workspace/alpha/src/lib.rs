@@ -0,0 +1,3 @@
+pub fn alpha(name: &str) -> String {
+    format!("alpha: {name}")
+}
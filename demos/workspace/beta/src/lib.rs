@@ -0,0 +1,8 @@
+pub fn beta(name: &str) -> String {
+    format!("beta: {name}")
+}
+
+/// There is no file system on WebAssembly, so this is gated with `target-gates` in `Cargo.toml`.
+pub fn beta_file(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap_or_default()
+}
@@ -0,0 +1,36 @@
+/// A singly linked list.
+pub struct Node {
+    pub value: i64,
+    pub next: Option<Box<Node>>,
+}
+
+pub enum Expr {
+    Number(f64),
+    Negate(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+}
+
+impl Node {
+    pub fn new(value: i64) -> Self {
+        Node { value, next: None }
+    }
+
+    pub fn push(self, value: i64) -> Box<Self> {
+        Box::new(Node { value, next: Some(Box::new(self)) })
+    }
+}
+
+pub fn sum(list: &Node) -> i64 {
+    list.value + list.next.as_ref().map_or(0, |next| sum(next))
+}
+
+pub fn reverse(list: Box<Node>) -> Box<Node> {
+    let mut reversed = None;
+    let mut current = Some(list);
+    while let Some(mut node) = current {
+        current = node.next.take();
+        node.next = reversed;
+        reversed = Some(node);
+    }
+    reversed.unwrap()
+}
@@ -0,0 +1,11 @@
+//! `Box<dyn Error>` is recognized as an error type, with its `Send` and `Sync` bounds.
+
+use std::error::Error;
+
+pub fn parse_port(text: &str) -> Result<u16, Box<dyn Error>> {
+    Ok(text.parse()?)
+}
+
+pub fn parse_host(text: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    Ok(text.to_string())
+}
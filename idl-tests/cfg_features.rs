@@ -0,0 +1,63 @@
+pub struct Client {
+    url: String,
+}
+
+impl Client {
+    pub fn new(url: String) -> Self {
+        Client { url }
+    }
+
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    #[cfg(feature = "blocking")]
+    pub fn fetch_blocking(&self) -> String {
+        self.url.clone()
+    }
+
+    #[cfg(feature = "tracing")]
+    pub fn trace(&self) {}
+}
+
+#[cfg(all(feature = "blocking", not(feature = "tracing")))]
+impl Client {
+    pub fn fetch_quietly(&self) -> String {
+        self.url.clone()
+    }
+}
+
+pub enum Format {
+    Text,
+    #[cfg(feature = "json")]
+    Json,
+    #[cfg(any(feature = "yaml", feature = "toml"))]
+    Config,
+}
+
+#[cfg(feature = "blocking")]
+pub fn fetch(url: &str) -> String {
+    url.to_string()
+}
+
+#[cfg(feature = "tracing")]
+pub fn enable_tracing() {}
+
+#[cfg(not(feature = "tracing"))]
+pub fn tracing_enabled() -> u32 {
+    0
+}
+
+#[cfg(not(test))]
+pub fn version() -> u32 {
+    1
+}
+
+#[cfg(test)]
+pub fn test_helper() {}
+
+#[cfg(target_os = "linux")]
+pub fn linux_only() {}
+
+#[cfg(any(feature = "blocking", target_os = "linux"))]
+pub fn blocking_or_linux() {}
@@ -0,0 +1,20 @@
+#[cfg(unix)]
+pub fn unix_only() {}
+
+#[cfg(windows)]
+pub fn windows_only() {}
+
+#[cfg(not(windows))]
+pub fn not_windows() {}
+
+#[cfg(target_os = "linux")]
+pub fn linux_only() {}
+
+#[cfg(target_os = "macos")]
+pub fn macos_only() {}
+
+#[cfg(all(unix, feature = "blocking"))]
+pub fn blocking_unix() {}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+pub fn desktop() {}
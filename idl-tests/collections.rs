@@ -0,0 +1,27 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use indexmap::{IndexMap, IndexSet};
+
+pub fn hash_map(map: HashMap<String, u32>) -> HashMap<String, u32> {
+    map
+}
+
+pub fn btree_map(map: BTreeMap<String, u32>) -> BTreeMap<String, u32> {
+    map
+}
+
+pub fn index_map(map: IndexMap<String, u32>) -> IndexMap<String, u32> {
+    map
+}
+
+pub fn hash_set(set: HashSet<String>) -> HashSet<String> {
+    set
+}
+
+pub fn btree_set(set: BTreeSet<String>) -> BTreeSet<String> {
+    set
+}
+
+pub fn index_set(set: IndexSet<String>) -> IndexSet<String> {
+    set
+}
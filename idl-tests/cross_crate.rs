@@ -0,0 +1,12 @@
+pub use settings::Config;
+
+pub fn default_config() -> Config {
+    Config {
+        name: String::new(),
+        retries: 3,
+    }
+}
+
+pub fn describe(config: &Config, mode: settings::Mode) -> String {
+    format!("{}", config.name)
+}
@@ -0,0 +1,9 @@
+pub struct Config {
+    pub name: String,
+    pub retries: u32,
+}
+
+pub enum Mode {
+    Fast,
+    Thorough,
+}
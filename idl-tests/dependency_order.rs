@@ -0,0 +1,37 @@
+pub struct Order {
+    pub customer: Customer,
+    pub lines: Vec<Line>,
+}
+
+pub struct Line {
+    pub product: ProductId,
+    pub quantity: u32,
+}
+
+pub struct ProductId(pub u64);
+
+pub struct Customer {
+    pub name: String,
+    pub contact: Contact,
+}
+
+pub enum Contact {
+    Email(String),
+    Phone(PhoneNumber),
+}
+
+pub struct PhoneNumber(pub String);
+
+pub struct Session {
+    orders: Vec<Order>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Session { orders: vec![] }
+    }
+
+    pub fn place(&mut self, order: Order) {
+        self.orders.push(order);
+    }
+}
@@ -0,0 +1,30 @@
+/// Adds two numbers.
+///
+/// ```
+/// # use doc_examples::add;
+/// assert_eq!(add(1, 2), 3);
+/// ```
+///
+/// Examples that rustdoc does not run are skipped:
+///
+/// ```ignore
+/// add(1, 2, 3);
+/// ```
+///
+/// ```text
+/// 1 + 2 = 3
+/// ```
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+/** Greets `name`.
+
+```rust,no_run
+let greeting = doc_examples::greet("world");
+assert_eq!(greeting, "Hello, world!");
+```
+*/
+pub fn greet(name: &str) -> String {
+    format!("Hello, {name}!")
+}
@@ -0,0 +1,36 @@
+/// Items tagged `#[doc(hidden)]` are public for technical reasons only, so they are ignored.
+pub struct Counter {
+    value: u64,
+}
+
+impl Counter {
+    pub fn new() -> Self {
+        Counter { value: 0 }
+    }
+
+    #[doc(alias = "incr")]
+    pub fn increment(&mut self) -> u64 {
+        self.value += 1;
+        self.value
+    }
+
+    /// Hidden methods are ignored, even if their types are not supported.
+    #[doc(hidden)]
+    pub fn __raw(&self) -> *const u64 {
+        &self.value
+    }
+}
+
+#[doc(hidden)]
+pub struct Internals {
+    pub table: Vec<u64>,
+}
+
+#[doc(hidden)]
+pub fn __private_api(internals: Internals) -> usize {
+    internals.table.len()
+}
+
+pub fn count(counter: &Counter) -> u64 {
+    counter.value
+}
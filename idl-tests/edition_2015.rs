@@ -0,0 +1,7 @@
+#[path = "edition_2015/src/geometry.rs"]
+pub mod geometry;
+
+#[path = "edition_2015/src/shapes.rs"]
+pub mod shapes;
+
+pub use shapes::Circle;
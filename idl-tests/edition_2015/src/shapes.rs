@@ -0,0 +1,7 @@
+// In the 2015 edition, this is `crate::geometry::Point`.
+use geometry::Point;
+
+pub struct Circle {
+    pub center: Point,
+    pub radius: f64,
+}
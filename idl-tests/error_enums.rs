@@ -0,0 +1,48 @@
+//! Enums implementing `std::error::Error` are marked in the IDL, so that backends can raise
+//! an exception for each arm when a function fails with one of them.
+
+/// Derived with `thiserror`, whose `#[error]` attributes are ignored.
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error("the input is empty")]
+    Empty,
+    #[error("unexpected `{found}` at {offset}")]
+    Unexpected { found: String, offset: u32 },
+}
+
+/// Implemented by hand, for a C-like enum.
+#[derive(Debug)]
+pub enum Fault {
+    Timeout,
+    Refused,
+}
+
+impl std::fmt::Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl std::error::Error for Fault {}
+
+/// Not an error, although it has a `Display` implementation.
+#[derive(Debug)]
+pub enum Mode {
+    Fast,
+    Slow,
+}
+
+impl std::fmt::Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+pub fn parse(text: String) -> Result<u32, ParseError> {
+    text.parse().map_err(|_| ParseError::Empty)
+}
+
+pub fn connect(mode: Mode) -> Result<(), Fault> {
+    let _ = mode;
+    Err(Fault::Refused)
+}
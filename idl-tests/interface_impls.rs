@@ -0,0 +1,45 @@
+use std::fmt;
+
+/// Receives progress notifications.
+pub trait Listener: Send + Sync {
+    fn on_event(&self, name: &str, count: u32) -> u32;
+
+    fn on_done(&mut self);
+}
+
+pub fn notify(listener: &dyn Listener, name: &str) -> u32 {
+    listener.on_event(name, 1)
+}
+
+/// Counts the events it is notified of.
+pub struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    pub fn new() -> Self {
+        Counter { count: 0 }
+    }
+
+    /// Defined on `Counter` as well, so only `on_done` is inherited from `Listener`.
+    pub fn on_event(&self, _name: &str, count: u32) -> u32 {
+        self.count + count
+    }
+}
+
+impl Listener for Counter {
+    fn on_event(&self, name: &str, count: u32) -> u32 {
+        Counter::on_event(self, name, count)
+    }
+
+    fn on_done(&mut self) {
+        self.count = 0;
+    }
+}
+
+// Not an interface, so not recorded.
+impl fmt::Display for Counter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.count)
+    }
+}
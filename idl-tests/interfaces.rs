@@ -0,0 +1,15 @@
+/// Receives progress notifications.
+pub trait Listener: Send + Sync {
+    /// Called for each event, returning how many more to send.
+    fn on_event(&self, name: &str, count: u32) -> u32;
+
+    fn on_done(&mut self);
+}
+
+pub fn notify(listener: &dyn Listener, name: &str) -> u32 {
+    listener.on_event(name, 1)
+}
+
+pub fn finish(mut listener: Box<dyn Listener + Send>) {
+    listener.on_done();
+}
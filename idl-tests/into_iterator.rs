@@ -0,0 +1,21 @@
+pub fn total(values: impl IntoIterator<Item = u32>) -> u64 {
+    values.into_iter().map(u64::from).sum()
+}
+
+pub fn join(words: impl IntoIterator<Item = String>, separator: &str) -> String {
+    words.into_iter().collect::<Vec<_>>().join(separator)
+}
+
+pub struct Tags {
+    tags: Vec<String>,
+}
+
+impl Tags {
+    pub fn new(tags: impl IntoIterator<Item = String>) -> Self {
+        Tags { tags: tags.into_iter().collect() }
+    }
+
+    pub fn count_known(&self, names: impl IntoIterator<Item = String>) -> u32 {
+        names.into_iter().filter(|name| self.tags.contains(name)).count() as u32
+    }
+}
@@ -0,0 +1,20 @@
+//! `std::io::Result<T>` is recognized when qualified, failing with a `std::io::Error`.
+//! A bare `Result<T>` is still taken to be `anyhow::Result<T>`.
+
+use std::io;
+
+pub fn read_config(path: &std::path::Path) -> io::Result<String> {
+    std::fs::read_to_string(path)
+}
+
+pub fn write_config(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+pub fn check_config(contents: &str) -> Result<u32, io::Error> {
+    Ok(contents.len() as u32)
+}
+
+pub fn parse_config(contents: &str) -> Result<u32> {
+    Ok(contents.len() as u32)
+}
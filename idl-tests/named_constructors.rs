@@ -0,0 +1,28 @@
+use std::path::Path;
+
+pub struct Config {
+    name: String,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self { name: String::new() }
+    }
+
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let name = std::fs::read_to_string(path)?;
+        Ok(Self { name })
+    }
+
+    pub fn with_name(name: String) -> Self {
+        Self { name }
+    }
+
+    pub fn default_name() -> String {
+        "config".to_string()
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+}
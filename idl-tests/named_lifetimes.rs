@@ -0,0 +1,55 @@
+//! Named lifetimes are accepted wherever an anonymous reference would be,
+//! unless the result would have to borrow from an argument after the call.
+
+pub struct Document {
+    title: String,
+    tags: Vec<String>,
+}
+
+impl Document {
+    /// Same as `fn title(&self) -> &str`.
+    pub fn title<'a>(&'a self) -> &'a str {
+        &self.title
+    }
+
+    /// Same as `fn has_tag(&self, tag: &str) -> bool`.
+    pub fn has_tag<'a, 'b>(&'a self, tag: &'b str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// The result is copied out, so it does not matter that it borrows from an argument.
+    pub fn tag_or<'a>(&'a self, index: u32, default: &'a str) -> &'a str {
+        default
+    }
+
+    /// Reported at `'a`: the future would borrow from `self` after the call.
+    pub fn fetch_title<'a>(&'a self) -> impl std::future::Future<Output = String> + 'a {
+        async move { self.title.clone() }
+    }
+}
+
+/// Same as `fn longest(a: &str, b: &str) -> &str`.
+pub fn longest<'a>(a: &'a str, b: &'a str) -> &'a str {
+    a
+}
+
+/// Returning `'static` data is fine.
+pub fn version() -> &'static str {
+    "1.0"
+}
+
+/// Reported at `'static`: what the caller passes is converted into a temporary.
+pub fn register(name: &'static str) {}
+
+/// Reported: type parameters are still not permitted...
+pub fn first<'a, T>(values: &'a [T]) -> u32 {
+    0
+}
+
+/// ...nor are where-clauses.
+pub fn shortest<'a, 'b>(a: &'a str, b: &'b str) -> u32
+where
+    'b: 'a,
+{
+    0
+}
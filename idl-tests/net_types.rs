@@ -0,0 +1,29 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use uuid::Uuid;
+
+pub struct Host(pub IpAddr);
+
+pub struct Endpoint {
+    pub name: String,
+    pub addr: SocketAddr,
+    pub id: Uuid,
+}
+
+pub fn loopback() -> Ipv4Addr {
+    Ipv4Addr::LOCALHOST
+}
+
+pub fn loopback_count(addr: IpAddr, fallback: Option<Ipv6Addr>) -> u32 {
+    addr.is_loopback() as u32 + fallback.is_some_and(|addr| addr.is_loopback()) as u32
+}
+
+pub fn resolve(host: Host, port: u16) -> std::net::SocketAddrV4 {
+    match host.0 {
+        IpAddr::V4(addr) => std::net::SocketAddrV4::new(addr, port),
+        IpAddr::V6(_) => std::net::SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, port),
+    }
+}
+
+pub fn ids(endpoints: &[Endpoint]) -> Vec<Uuid> {
+    endpoints.iter().map(|endpoint| endpoint.id).collect()
+}
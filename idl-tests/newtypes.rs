@@ -0,0 +1,22 @@
+pub struct UserId(pub u64);
+
+impl UserId {
+    pub fn next(&self) -> UserId {
+        UserId(self.0 + 1)
+    }
+}
+
+pub struct Name(pub String);
+
+pub struct Account {
+    pub id: UserId,
+    pub names: Vec<Name>,
+}
+
+pub fn greet(id: UserId, name: &Name) -> String {
+    format!("{} {}", id.0, name.0)
+}
+
+pub fn parse_id(s: &str) -> anyhow::Result<UserId> {
+    Ok(UserId(s.parse()?))
+}
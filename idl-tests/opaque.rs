@@ -0,0 +1,24 @@
+/// A struct with public fields that is still passed by handle.
+#[gluegun::opaque]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub name: String,
+    /// Private fields are not exposed.
+    pixels: Vec<u32>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32) -> Self {
+        Image { width, height, name: String::new(), pixels: vec![] }
+    }
+
+    /// Takes precedence over the getter of the field of the same name.
+    pub fn name(&self) -> String {
+        self.name.to_uppercase()
+    }
+}
+
+/// Tuple structs are supported, with getters named like the fields of a record.
+#[gluegun::opaque]
+pub struct Pair(pub u64, pub String);
@@ -0,0 +1,30 @@
+//! Arguments the function writes its results to are reported with a suggestion to return them instead.
+
+pub struct Reader {
+    data: Vec<u8>,
+}
+
+impl Reader {
+    pub fn len(&self) -> u64 {
+        self.data.len() as u64
+    }
+
+    /// Reported at the whole type, `&mut [u8]`.
+    pub fn read_into(&self, buf: &mut [u8]) -> u64 {
+        0
+    }
+
+    /// `&mut self` is a receiver, not an out-parameter.
+    pub fn clear(&mut self) {}
+}
+
+/// Reported at the whole type, `&mut String`.
+pub fn append_greeting(name: &str, out: &mut String) {}
+
+/// Reported even when the slice is not the first argument.
+pub fn fill(value: u32, values: &mut [u32]) {}
+
+/// Shared slices are fine.
+pub fn sum(values: &[u32]) -> u32 {
+    0
+}
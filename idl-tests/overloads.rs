@@ -0,0 +1,34 @@
+pub struct Color {
+    rgb: u32,
+}
+
+impl Color {
+    #[gluegun::overload(from)]
+    pub fn from_hex(hex: &str) -> Self {
+        Color { rgb: u32::from_str_radix(hex.trim_start_matches('#'), 16).unwrap_or(0) }
+    }
+
+    #[gluegun::overload(from)]
+    pub fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
+        Color { rgb: u32::from_be_bytes([0, red, green, blue]) }
+    }
+
+    #[overload(from)]
+    pub fn gray(level: u8) -> Self {
+        Color::from_rgb(level, level, level)
+    }
+
+    #[gluegun::overload(blend)]
+    pub fn blend_with(&self, other: &Color) -> Color {
+        Color { rgb: (self.rgb & 0xfefefe) / 2 + (other.rgb & 0xfefefe) / 2 }
+    }
+
+    #[gluegun::overload]
+    pub fn blend_hex(&self, hex: &str) -> Color {
+        self.blend_with(&Color::from_hex(hex))
+    }
+
+    pub fn rgb(&self) -> u32 {
+        self.rgb
+    }
+}
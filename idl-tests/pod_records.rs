@@ -0,0 +1,27 @@
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+pub struct Meters(pub f32);
+
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+    pub width: Meters,
+    pub visible: bool,
+}
+
+pub struct Label {
+    pub at: Point,
+    pub text: String,
+}
+
+pub struct Glyph {
+    pub at: Point,
+    pub character: char,
+}
+
+pub struct Outline {
+    pub points: Vec<Point>,
+}
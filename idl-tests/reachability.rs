@@ -0,0 +1,27 @@
+#[path = "reachability/src/shapes/mod.rs"]
+mod shapes;
+
+#[path = "reachability/src/geometry.rs"]
+pub mod geometry;
+
+#[path = "reachability/src/internal.rs"]
+pub(crate) mod internal;
+
+/// Defined in a private module, so only reachable through this re-export.
+pub use crate::shapes::Circle;
+
+pub use crate::shapes::square::Square as Block;
+
+/// Also reachable as `geometry::Point`, which is longer.
+pub use crate::geometry::Point;
+
+/// Only re-exported within the crate.
+pub(crate) use crate::internal::Secret;
+
+pub fn unit_circle() -> Circle {
+    Circle { radius: 1.0 }
+}
+
+pub fn origin() -> Point {
+    Point { x: 0.0, y: 0.0 }
+}
@@ -0,0 +1,18 @@
+const ORIGIN: Point = Point { x: 0.0, y: 0.0 };
+
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+pub fn distance(a: &Point, b: &Point) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
+
+pub fn norm(point: &Point) -> f64 {
+    distance(&ORIGIN, point)
+}
+
+pub(crate) fn scale(point: &Point, factor: f64) -> Point {
+    Point { x: point.x * factor, y: point.y * factor }
+}
@@ -0,0 +1,7 @@
+pub struct Secret {
+    pub code: u32,
+}
+
+pub fn reveal(secret: &Secret) -> u32 {
+    secret.code
+}
@@ -0,0 +1,17 @@
+/// Public, but in a private module: its definitions are only reachable if they are re-exported.
+pub mod square;
+
+pub struct Circle {
+    pub radius: f64,
+}
+
+impl Circle {
+    pub fn area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+}
+
+/// Not re-exported, so it is not part of the interface (or else its generics would be an error).
+pub struct Triangle<T> {
+    pub base: T,
+}
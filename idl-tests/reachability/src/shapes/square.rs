@@ -0,0 +1,9 @@
+pub struct Square {
+    pub side: f64,
+}
+
+impl Square {
+    pub fn circumscribed(&self) -> crate::shapes::Circle {
+        crate::shapes::Circle { radius: self.side * std::f64::consts::SQRT_2 / 2.0 }
+    }
+}
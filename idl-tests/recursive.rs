@@ -0,0 +1,20 @@
+pub struct Tree {
+    pub value: u32,
+    pub children: Vec<Tree>,
+}
+
+pub enum Expr {
+    Literal(u32),
+    Call(Call),
+}
+
+pub struct Call {
+    pub name: String,
+    pub args: Vec<Expr>,
+}
+
+impl Tree {
+    pub fn leaf(value: u32) -> Tree {
+        Tree { value, children: vec![] }
+    }
+}
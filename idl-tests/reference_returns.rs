@@ -0,0 +1,27 @@
+use std::path::Path;
+
+pub struct Document {
+    title: String,
+    pages: Vec<u32>,
+}
+
+impl Document {
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn pages(&self) -> &[u32] {
+        &self.pages
+    }
+
+    pub fn checked_title(&self) -> anyhow::Result<&str> {
+        if self.title.is_empty() {
+            anyhow::bail!("no title");
+        }
+        Ok(&self.title)
+    }
+}
+
+pub fn file_name(path: &Path) -> &Path {
+    path
+}
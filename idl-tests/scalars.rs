@@ -0,0 +1,15 @@
+pub fn negate(value: bool) -> bool {
+    !value
+}
+
+pub fn next_char(value: char) -> char {
+    char::from_u32(value as u32 + 1).unwrap_or(value)
+}
+
+pub fn add_u8(a: u8, b: u8) -> u8 {
+    a.wrapping_add(b)
+}
+
+pub fn add_i8(a: i8, b: i8) -> i8 {
+    a.wrapping_add(b)
+}
@@ -0,0 +1,19 @@
+use std::{rc::Rc, sync::Arc};
+
+pub struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    pub fn new() -> Self {
+        Counter { count: 0 }
+    }
+
+    pub fn count(self: Arc<Self>) -> u32 {
+        self.count
+    }
+
+    pub fn doubled(self: Rc<Self>) -> u32 {
+        self.count * 2
+    }
+}
@@ -0,0 +1,19 @@
+//! Its snapshot leaves out the spans (see `spans_omitted.nospans`), so comments like this one,
+//! or blank lines, can be added without changing it.
+
+pub struct Settings {
+    pub verbose: bool,
+    pub retries: u32,
+}
+
+pub enum Level {
+    Low,
+    High,
+}
+
+pub fn apply(settings: Settings, level: Level) -> u32 {
+    match level {
+        Level::Low => settings.retries,
+        Level::High => settings.retries * 2,
+    }
+}
@@ -0,0 +1,36 @@
+//! Resources emitting events return streams, which are only accepted as the result of a function or method.
+
+pub struct Tick {
+    pub index: u32,
+}
+
+pub struct Ticker {
+    count: u32,
+}
+
+impl Ticker {
+    pub fn new(count: u32) -> Self {
+        Ticker { count }
+    }
+
+    pub fn ticks(&self) -> impl futures::Stream<Item = Tick> + Send + 'static {
+        futures::stream::iter((0..self.count).map(|index| Tick { index }))
+    }
+
+    /// Without bounds, the stream is not `Send` (which the Python backend rejects).
+    pub fn counts(&self) -> impl futures::stream::Stream<Item = u32> {
+        futures::stream::iter(0..self.count)
+    }
+}
+
+pub fn lines(text: String) -> impl futures::Stream<Item = String> + Send + Sync {
+    futures::stream::iter(text.lines().map(str::to_string).collect::<Vec<_>>())
+}
+
+/// Reported: streams cannot be arguments...
+pub fn consume(values: impl futures::Stream<Item = u32>) {}
+
+/// ...nor be nested in another type.
+pub fn maybe_ticks() -> Option<impl futures::Stream<Item = u32>> {
+    None
+}
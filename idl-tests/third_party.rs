@@ -0,0 +1,19 @@
+use chrono::DateTime;
+
+pub struct Event {
+    pub name: String,
+    pub at: DateTime<chrono::Utc>,
+    pub id: uuid::Uuid,
+}
+
+pub fn parse_id(s: &str) -> uuid::Uuid {
+    s.parse().unwrap()
+}
+
+pub fn ids(events: &[Event]) -> Vec<uuid::Uuid> {
+    events.iter().map(|event| event.id).collect()
+}
+
+pub fn latest(events: &[Event]) -> Option<DateTime<chrono::Utc>> {
+    events.iter().map(|event| event.at).max()
+}
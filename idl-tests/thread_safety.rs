@@ -0,0 +1,62 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+/// `Send` and `Sync`.
+pub struct Shared {
+    values: Mutex<Vec<u32>>,
+}
+
+impl Shared {
+    pub fn push(&self, value: u32) {
+        self.values.lock().unwrap().push(value);
+    }
+}
+
+/// `Send` but not `Sync`.
+pub struct Local {
+    values: RefCell<Vec<u32>>,
+}
+
+impl Local {
+    pub fn push(&self, value: u32) {
+        self.values.borrow_mut().push(value);
+    }
+}
+
+/// Neither `Send` nor `Sync`.
+pub struct Handle {
+    inner: Rc<String>,
+}
+
+impl Handle {
+    pub fn name(&self) -> String {
+        self.inner.to_string()
+    }
+}
+
+/// Explicitly opts out of being used across threads.
+#[gluegun::not_threadsafe]
+pub struct Session {
+    id: u64,
+}
+
+impl Session {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+/// A raw pointer, asserted to be `Send`.
+pub struct Buffer {
+    data: *mut u8,
+    len: u64,
+}
+
+unsafe impl Send for Buffer {}
+
+impl Buffer {
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+}
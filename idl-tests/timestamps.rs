@@ -0,0 +1,15 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+pub struct Reservation {
+    pub created_at: DateTime<Utc>,
+    pub day: NaiveDate,
+    pub confirmed_at: Option<time::OffsetDateTime>,
+}
+
+pub fn reserve(day: NaiveDate, now: chrono::DateTime<chrono::Utc>) -> Reservation {
+    Reservation { created_at: now, day, confirmed_at: None }
+}
+
+pub fn latest(reservations: &[Reservation], since: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+    reservations.iter().map(|reservation| reservation.created_at).filter(|at| at >= since).max()
+}
@@ -0,0 +1,22 @@
+/// A tuple struct with several public fields, which is a record whose fields are positional.
+pub struct Point(pub f64, pub f64);
+
+impl Point {
+    pub fn norm(&self) -> f64 {
+        (self.0 * self.0 + self.1 * self.1).sqrt()
+    }
+}
+
+/// Not plain old data, so not `pod`.
+pub struct Entry(pub String, pub u32, pub Option<Point>);
+
+/// A single public field makes a newtype rather than a record.
+pub struct Meters(pub f64);
+
+pub fn origin() -> Point {
+    Point(0.0, 0.0)
+}
+
+pub fn entry(name: String, point: Point) -> Entry {
+    Entry(name, 1, Some(point))
+}
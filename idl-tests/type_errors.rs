@@ -0,0 +1,49 @@
+//! Errors in types are reported at the offending type (or part of it), even deep inside other types.
+
+use std::collections::HashMap;
+
+pub struct Config {
+    pub name: String,
+}
+
+pub struct Widget {
+    id: u32,
+}
+
+impl Widget {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Reported at the extra type argument, `u32`.
+    pub fn resize(&self, sizes: Vec<u32, u32>) {}
+}
+
+/// Definitions without errors are kept.
+pub fn lookup(config: &crate::Config) -> Option<u32> {
+    None
+}
+
+/// Reported at the extra type argument, `String`.
+pub fn extra_argument(values: Vec<u32, String>) {}
+
+/// Reported at the type, as an argument is missing.
+pub fn missing_argument(values: HashMap<String>) {}
+
+/// Reported at the extra type argument, `u16`, even inside other types.
+pub fn nested(values: Option<Vec<HashMap<String, u32, u16>>>) {}
+
+/// Reported at the type argument, `u32`.
+pub fn generic_user_type(config: Config<u32>) {}
+
+/// Reported at `Missing`, the first part of the path that does not resolve.
+pub fn unresolved_path(config: crate::Missing) {}
+
+/// Reported at the type of the unexpected binding, `u32`.
+pub fn unexpected_binding(names: impl IntoIterator<Item = String, Extra = u32>) {}
+
+/// Reported at the boxed type, `u32`.
+pub fn boxed_scalar(value: Box<u32>) {}
+
+/// Reported at the time zone, `chrono::Local`.
+pub fn local_time(at: chrono::DateTime<chrono::Local>) {}
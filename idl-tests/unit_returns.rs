@@ -0,0 +1,29 @@
+pub struct Counter {
+    value: u32,
+}
+
+impl Counter {
+    pub fn reset(&mut self) {
+        self.value = 0;
+    }
+
+    pub fn increment(&mut self) -> anyhow::Result<()> {
+        self.value = self.value.checked_add(1).ok_or_else(|| anyhow::anyhow!("overflow"))?;
+        Ok(())
+    }
+}
+
+pub fn log(message: &str) {
+    eprintln!("{message}");
+}
+
+pub fn check(value: u32) -> anyhow::Result<()> {
+    if value == 0 {
+        anyhow::bail!("zero");
+    }
+    Ok(())
+}
+
+pub fn wrap(value: u32) -> (u32,) {
+    (value,)
+}
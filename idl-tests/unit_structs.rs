@@ -0,0 +1,27 @@
+/// A unit struct, which is a record without fields.
+pub struct Marker;
+
+/// Also a record without fields.
+pub struct Empty {}
+
+impl Marker {
+    pub fn describe(&self) -> String {
+        "marker".to_string()
+    }
+}
+
+/// Opaque, so a resource rather than a record.
+#[gluegun::opaque]
+pub struct Handle;
+
+pub fn marker() -> Marker {
+    Marker
+}
+
+pub fn is_marker(_marker: Marker, _empty: Empty) -> bool {
+    true
+}
+
+pub fn handle() -> Handle {
+    Handle
+}
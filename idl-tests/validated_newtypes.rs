@@ -0,0 +1,38 @@
+/// An ID that is passed as a `String`, checked by `TryFrom<String>`.
+#[gluegun::marshal_as(String)]
+pub struct OrderId(String);
+
+impl TryFrom<String> for OrderId {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, String> {
+        if value.starts_with("ord-") {
+            Ok(OrderId(value))
+        } else {
+            Err(format!("`{value}` is not an order ID"))
+        }
+    }
+}
+
+impl From<OrderId> for String {
+    fn from(value: OrderId) -> String {
+        value.0
+    }
+}
+
+impl OrderId {
+    pub fn is_test(&self) -> bool {
+        self.0.ends_with("-test")
+    }
+}
+
+/// Any fields are permitted, as they are not exchanged.
+#[marshal_as(u64)]
+pub struct Quantity {
+    count: u64,
+}
+
+pub fn place(order: OrderId, quantity: Quantity) -> OrderId {
+    let _ = quantity.count;
+    order
+}
@@ -7,3 +7,22 @@ pub fn ignore(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     item
 }
+
+/// `#[gluegun::overload(name)]` has no effect either: it adds the method to the group of overloads `name`,
+/// which backends for languages with overloading can give the same name (e.g., `from_str` and `from_bytes` as `from`).
+#[proc_macro_attribute]
+pub fn overload(attr: TokenStream, item: TokenStream) -> TokenStream {
+    syn::parse_macro_input!(attr as syn::Ident);
+
+    item
+}
+
+/// `#[gluegun::marshal_as(Type)]` has no effect either: it makes the struct a newtype that is passed across the boundary
+/// as a `Type` (e.g., `String` or `u64`), whatever its fields. The struct converts from `Type` with `TryFrom<Type>`,
+/// which validates the value (errors are reported to the caller), and into it with `From<Struct> for Type`.
+#[proc_macro_attribute]
+pub fn marshal_as(attr: TokenStream, item: TokenStream) -> TokenStream {
+    syn::parse_macro_input!(attr as syn::Type);
+
+    item
+}
@@ -21,4 +21,629 @@ fn hello_world() -> anyhow::Result<()> {
     .cargo_glue_gun()
     .cargo_build_plugin_crates()
     .execute()
-}
\ No newline at end of file
+}
+
+/// Generates the bindings of `hello_world` with each plugin whose required tools are installed, skipping the others.
+#[test]
+fn hello_world_matrix() -> anyhow::Result<()> {
+    let plugins = ["bench", "cli", "dart", "dummy", "grpc", "java", "lua", "panama", "py", "rest", "uniffi", "wasm"];
+    gluegun_test_harness::Matrix::new("hello_world", plugins, demo_directory("hello_world"))
+    .execute(|test, plugin| test.cargo_glue_gun().expect_path(format!("hello_world-{plugin}")))
+}
+
+/// Exercises each flavor of map and set (`Hash`, `BTree`, and `Index`).
+#[test]
+fn collections() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("collections", PLUGINS, demo_directory("collections"))
+    .cargo_glue_gun()
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// Generates the micro-benchmarks of a demo covering scalar, string, collection, and object-passing calls.
+#[test]
+fn microbench() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("microbench", &["bench"], demo_directory("microbench"))
+    .cargo_glue_gun()
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// Exercises types that contain themselves, through a collection or a `Box`.
+#[test]
+fn recursive_types() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("recursive", &["rest", "bench"], demo_directory("recursive"))
+    .cargo_glue_gun()
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// Generates and builds the bindings of a demo using every feature of the IDL (see `demos/kitchen_sink`),
+/// with one test per backend, so that a backend that cannot handle something fails its own test.
+/// The tests of the backends that cannot map all of it yet are ignored, giving the first thing they lack;
+/// `cargo test -- --ignored` shows what is still missing.
+fn kitchen_sink(plugin: &str) -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("kitchen_sink", &[plugin], demo_directory("kitchen_sink"))
+    .cargo_glue_gun()
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+#[test]
+fn kitchen_sink_bench() -> anyhow::Result<()> {
+    kitchen_sink("bench")
+}
+
+#[test]
+#[ignore = "the cli backend only supports functions"]
+fn kitchen_sink_cli() -> anyhow::Result<()> {
+    kitchen_sink("cli")
+}
+
+#[test]
+#[ignore = "the dart backend only supports functions and resources"]
+fn kitchen_sink_dart() -> anyhow::Result<()> {
+    kitchen_sink("dart")
+}
+
+#[test]
+fn kitchen_sink_dummy() -> anyhow::Result<()> {
+    kitchen_sink("dummy")
+}
+
+#[test]
+#[ignore = "the grpc backend does not support resources"]
+fn kitchen_sink_grpc() -> anyhow::Result<()> {
+    kitchen_sink("grpc")
+}
+
+#[test]
+#[ignore = "the java backend does not support async functions"]
+fn kitchen_sink_java() -> anyhow::Result<()> {
+    kitchen_sink("java")
+}
+
+#[test]
+#[ignore = "the lua backend only supports methods taking `&self` or `&mut self`"]
+fn kitchen_sink_lua() -> anyhow::Result<()> {
+    kitchen_sink("lua")
+}
+
+#[test]
+#[ignore = "the panama backend only supports functions"]
+fn kitchen_sink_panama() -> anyhow::Result<()> {
+    kitchen_sink("panama")
+}
+
+#[test]
+#[ignore = "the py backend does not support `impl Future` results"]
+fn kitchen_sink_py() -> anyhow::Result<()> {
+    kitchen_sink("py")
+}
+
+#[test]
+#[ignore = "the rest backend does not support resources"]
+fn kitchen_sink_rest() -> anyhow::Result<()> {
+    kitchen_sink("rest")
+}
+
+#[test]
+#[ignore = "the uniffi backend does not support resources"]
+fn kitchen_sink_uniffi() -> anyhow::Result<()> {
+    kitchen_sink("uniffi")
+}
+
+#[test]
+#[ignore = "the wasm backend only supports functions"]
+fn kitchen_sink_wasm() -> anyhow::Result<()> {
+    kitchen_sink("wasm")
+}
+
+/// A plugin that fails is reported along with the package, the phase it failed in, and the error it returned
+/// (here, the cli backend, which only supports functions, on the resources of the kitchen sink).
+#[test]
+fn plugin_failure_is_reported() {
+    let error = gluegun_test_harness::Test::new("kitchen_sink", &["cli"], demo_directory("kitchen_sink"))
+        .cargo_glue_gun()
+        .execute()
+        .unwrap_err();
+    let error = format!("{error:#}");
+    assert!(error.contains("gluegun-cli failed with code"), "{error}");
+    assert!(error.contains("package: kitchen_sink"), "{error}");
+    assert!(error.contains("phase:   generating code"), "{error}");
+    assert!(error.contains("error:   generating Rust sources"), "{error}");
+}
+
+#[test]
+fn hello_world_deterministic() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("hello_world", PLUGINS, demo_directory("hello_world"))
+    .cargo_glue_gun()
+    .snapshot_plugin_crates()
+    // Without `--force`, the second run would find the input unchanged and leave the crates alone.
+    .cargo_glue_gun_builder()
+    .options(["--force", "--package", "hello_world"])
+    .options(PLUGINS)
+    .finish()
+    .compare_plugin_crates()
+    .execute()
+}
+
+/// A second run with the same input leaves the generated crate alone (keeping the edit made to it),
+/// unless `--force` is given.
+#[test]
+fn unchanged_input_skips_generation() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("hello_world", &["dummy"], demo_directory("hello_world"))
+    .cargo_glue_gun()
+    .replace("hello_world-dummy/README.md", "Dummy", "Edited")
+    .snapshot_plugin_crates()
+    .cargo_glue_gun()
+    .compare_plugin_crates()
+    .cargo_glue_gun_builder()
+    .options(["--force", "--package", "hello_world", "dummy"])
+    .finish()
+    .replace("hello_world-dummy/README.md", "Dummy", "Edited")
+    .execute()
+}
+
+/// Without `--package`, cargo-gluegun selects packages like cargo: at the root of a virtual workspace, all of its members...
+#[test]
+fn workspace_root_selects_members() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("workspace", &["dummy"], demo_directory("workspace"))
+    .cargo_glue_gun_builder()
+    .option("dummy")
+    .finish()
+    .expect_path("alpha-dummy")
+    .expect_path("beta-dummy")
+    .execute()
+}
+
+/// ...and, in the directory of a member, just that member.
+#[test]
+fn member_directory_selects_member() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("workspace", &["dummy"], demo_directory("workspace"))
+    .cargo_glue_gun_builder()
+    .in_directory("beta/src")
+    .option("dummy")
+    .finish()
+    .expect_no_path("alpha-dummy")
+    .expect_path("beta-dummy")
+    .execute()
+}
+
+/// Once the crates of the members are generated, plugins can add files for the workspace as a whole:
+/// here, a Maven project aggregating the Java sources of the generated crates.
+#[test]
+fn workspace_files_are_generated() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("workspace", &["java"], demo_directory("workspace"))
+    .cargo_glue_gun_builder()
+    .option("java")
+    .finish()
+    .expect_file_containing("alpha-java/pom.xml", "<artifactId>alpha-java</artifactId>")
+    .expect_file_containing("pom.xml", "    <module>alpha-java</module>\n    <module>beta-java</module>\n")
+    .execute()
+}
+
+/// Files matched by `extra-files` in the plugin metadata are copied into the generated crate.
+#[test]
+fn extra_files_are_copied() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("workspace", &["dummy"], demo_directory("workspace"))
+    .cargo_glue_gun_builder()
+    .in_directory("alpha")
+    .option("dummy")
+    .finish()
+    .expect_path("alpha-dummy/NOTICE.txt")
+    .expect_no_path("alpha-dummy/assets")
+    .execute()
+}
+
+/// The files in the `custom-src` directory of the plugin metadata are copied into `src/custom/`
+/// and declared from the generated `src/lib.rs`.
+#[test]
+fn custom_src_is_included() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("workspace", &["py"], demo_directory("workspace"))
+    .cargo_glue_gun_builder()
+    .in_directory("alpha")
+    .option("py")
+    .finish()
+    .expect_file_containing("alpha-py/src/lib.rs", "\nmod custom;\n")
+    .expect_file_containing("alpha-py/src/custom/mod.rs", "pub(crate) fn alpha_all(")
+    .execute()
+}
+
+/// The `file-header` in the plugin metadata is prepended to each generated file, as a comment in its language.
+#[test]
+fn file_header_is_prepended() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("workspace", &["py"], demo_directory("workspace"))
+    .cargo_glue_gun_builder()
+    .in_directory("alpha")
+    .option("py")
+    .finish()
+    .expect_file_containing("alpha-py/src/lib.rs", "// Copyright (c) the alpha authors\n// SPDX-License-Identifier: MIT\n\n")
+    .expect_file_containing("alpha-py/python/alpha/_interface_hash.py", "# Copyright (c) the alpha authors\n")
+    .execute()
+}
+
+/// With `bindings-manifest = true`, the generated crate lists its symbols and the Rust items they bind in `bindings.json`.
+#[test]
+fn bindings_manifest_is_generated() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("workspace", &["py"], demo_directory("workspace"))
+    .cargo_glue_gun_builder()
+    .in_directory("alpha")
+    .option("py")
+    .finish()
+    .expect_file_containing("alpha-py/bindings.json", "\"symbol\": \"alpha.alpha\",\n      \"kind\": \"function\",\n      \"rust\": \"alpha::alpha\",")
+    .execute()
+}
+
+/// Definitions matched by `target-gates` in the plugin metadata are wrapped in `#[cfg]`...
+#[test]
+fn target_gates_wrap_items_in_cfg() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("workspace", &["py"], demo_directory("workspace"))
+    .cargo_glue_gun_builder()
+    .in_directory("beta")
+    .option("py")
+    .finish()
+    .expect_file_containing("beta-py/src/lib.rs", "#[cfg(not(target_family = \"wasm\"))]\n#[pyo3::pyfunction]\nfn beta_file(")
+    .execute()
+}
+
+/// ...and left out by plugins that always build for a target on which they are not available.
+#[test]
+fn target_gates_omit_excluded_items() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("workspace", &["wasm"], demo_directory("workspace"))
+    .cargo_glue_gun_builder()
+    .in_directory("beta")
+    .option("wasm")
+    .finish()
+    .expect_file_containing("beta-wasm/examples/node.mjs", "import { load, beta } from \"../index.js\";")
+    .execute()
+}
+
+/// `exclude` leaves out the definitions and methods it matches, here those ending in `_internal` at any depth.
+#[test]
+fn filters_exclude_definitions_and_methods() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("filters", &["dummy"], demo_directory("filters"))
+    .cargo_glue_gun()
+    .expect_file_containing("filters-dummy/README.md", "text: \"api_version\"")
+    .expect_file_containing("filters-dummy/README.md", "text: \"fetch\"")
+    .expect_file_not_containing("filters-dummy/README.md", "text: \"reset_internal\"")
+    .expect_file_not_containing("filters-dummy/README.md", "text: \"dump_internal\"")
+    .execute()
+}
+
+/// `include` keeps the definitions it matches with all of their methods, and the types whose methods it matches
+/// with only those; `**` matches the definitions of nested modules too.
+#[test]
+fn filters_include_definitions_and_methods() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("filters", &["dummy"], demo_directory("filters"))
+    .replace("Cargo.toml", "include = []", r#"include = ["api_*", "Point", "Client::fetch", "shapes::**"]"#)
+    .cargo_glue_gun()
+    .expect_file_containing("filters-dummy/README.md", "text: \"api_distance\"")
+    .expect_file_containing("filters-dummy/README.md", "text: \"fetch\"")
+    .expect_file_not_containing("filters-dummy/README.md", "text: \"new\"")
+    .expect_file_containing("filters-dummy/README.md", "text: \"square_area\"")
+    .expect_file_containing("filters-dummy/README.md", "text: \"cube_volume\"")
+    .execute()
+}
+
+/// A `*` does not match across `::`, so `shapes::*` leaves out the definitions of `shapes::solids`.
+#[test]
+fn filters_glob_matches_within_names() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("filters", &["dummy"], demo_directory("filters"))
+    .replace("Cargo.toml", "include = []", r#"include = ["shapes::*"]"#)
+    .cargo_glue_gun()
+    .expect_file_containing("filters-dummy/README.md", "text: \"square_area\"")
+    .expect_file_not_containing("filters-dummy/README.md", "text: \"cube_volume\"")
+    .expect_file_not_containing("filters-dummy/README.md", "text: \"api_version\"")
+    .execute()
+}
+
+/// Excluding a type that a kept definition references is an error (here `api_distance` takes a `Point`).
+#[test]
+fn filters_reject_references_to_excluded_types() {
+    let error = gluegun_test_harness::Test::new("filters", &["dummy"], demo_directory("filters"))
+        .replace("Cargo.toml", r#"exclude = ["*_internal"]"#, r#"exclude = ["Point"]"#)
+        .cargo_glue_gun()
+        .execute()
+        .unwrap_err();
+    let error = format!("{error:#}");
+    assert!(error.contains("gluegun-dummy failed with code"), "{error}");
+    assert!(error.contains("phase:   filtering definitions"), "{error}");
+}
+
+/// An `include` that matches nothing is an error, as nothing would be exported.
+#[test]
+fn filters_reject_include_matching_nothing() {
+    let error = gluegun_test_harness::Test::new("filters", &["dummy"], demo_directory("filters"))
+        .replace("Cargo.toml", "include = []", r#"include = ["nothing_*"]"#)
+        .cargo_glue_gun()
+        .execute()
+        .unwrap_err();
+    let error = format!("{error:#}");
+    assert!(error.contains("gluegun-dummy failed with code"), "{error}");
+    assert!(error.contains("phase:   filtering definitions"), "{error}");
+}
+
+/// With `repr-c-records`, records made only of scalars get `#[repr(C)]` mirrors in `src/repr_c.rs`.
+#[test]
+fn repr_c_records() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("pod_records", &["uniffi"], demo_directory("pod_records"))
+    .cargo_glue_gun()
+    .expect_file_containing("pod_records-uniffi/src/lib.rs", "pub mod repr_c;")
+    .expect_file_containing("pod_records-uniffi/src/repr_c.rs", "#[repr(C)]\n#[derive(Clone, Copy, Debug)]\n#[allow(non_camel_case_types)]\npub struct pod_records_Extent {\n    pub width: u32,")
+    .expect_file_containing("pod_records-uniffi/src/repr_c.rs", "pub struct pod_records_Placement {\n    pub extent: pod_records_Extent,\n    pub elevation: f64,")
+    .expect_file_containing("pod_records-uniffi/src/repr_c.rs", "elevation: pod_records::Meters(value.elevation),")
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// `cargo gluegun lint` reports what keeps each plugin from generating bindings, without generating anything...
+#[test]
+fn lint_reports_problems() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("lint", &["dummy", "uniffi"], demo_directory("lint"))
+    .cargo_glue_gun_builder()
+    .options(["lint", "dummy", "uniffi"])
+    .finish()
+    .expect_no_path("lint-dummy")
+    .expect_no_path("lint-uniffi")
+    .execute()
+}
+
+/// ...and, with `--deny-warnings`, fails if there are any: here, the generic function (for both plugins)
+/// and the boxed field (for uniffi).
+#[test]
+fn lint_denies_problems() {
+    let error = gluegun_test_harness::Test::new("lint", &["dummy", "uniffi"], demo_directory("lint"))
+        .cargo_glue_gun_builder()
+        .options(["lint", "--deny-warnings", "dummy", "uniffi"])
+        .finish()
+        .execute()
+        .unwrap_err();
+    assert!(format!("{error:#}").contains("found 2 problems"), "{error:#}");
+}
+
+/// `cargo gluegun doctor` checks for the tools each plugin requires, without generating anything:
+/// the dummy plugin requires none.
+#[test]
+fn doctor_checks_tools() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("lint", &["dummy"], demo_directory("lint"))
+    .cargo_glue_gun_builder()
+    .options(["doctor", "dummy"])
+    .finish()
+    .expect_no_path("lint-dummy")
+    .execute()
+}
+
+/// Panics are caught at the boundary and raised as errors in each language.
+#[test]
+fn panics_are_caught() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("panics", &["py", "java", "panama", "dart", "wasm"], demo_directory("panics"))
+    .cargo_glue_gun()
+    .expect_file_containing("panics-py/src/lib.rs", "gluegun_py_util::catch_panic(|| panics::divide(")
+    .expect_file_containing("panics-java/src/lib.rs", "gluegun_java_util::jni_support::catch_panic(|| ::panics::divide(")
+    .expect_file_containing("panics-panama/src/lib.rs", "gluegun_catch_panic(err_ptr, err_len, || panics::divide(a, b))")
+    .expect_file_containing("panics-panama/java_src/panics/Functions.java", "checkError(errPtr, errLen);")
+    .expect_file_containing("panics-dart/src/lib.rs", "gluegun_catch_panic(err_ptr, err_len, || panics::divide(a, b))")
+    .expect_file_containing("panics-wasm/src/lib.rs", "wasm_bindgen::throw_str(&format!(\"Rust panicked: {info}\"));")
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// The lua crate is not built, as `mlua` is not otherwise a dependency of the workspace.
+#[test]
+fn panics_are_caught_in_lua() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("panics", &["lua"], demo_directory("panics"))
+    .cargo_glue_gun()
+    .expect_file_containing("panics-lua/src/lib.rs", "gluegun_catch_panic(|| panics::divide(a, b))?")
+    .execute()
+}
+
+/// With `debug-bindings`, resource wrappers count their live instances, which each language can query.
+#[test]
+fn debug_bindings_count_live_objects() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("live_objects", &["py", "dart"], demo_directory("live_objects"))
+    .cargo_glue_gun()
+    .expect_file_containing("live_objects-py/src/lib.rs", "\npub mod gluegun_debug;\n")
+    .expect_file_containing("live_objects-py/src/lib.rs", "(Option<live_objects::Counter>, crate::gluegun_debug::LiveObject);")
+    .expect_file_containing("live_objects-py/src/gluegun_debug.rs", "pub fn live_objects() -> usize {")
+    .expect_file_containing("live_objects-py/python/live_objects/_gluegun_debug.py", "return native.gluegun_live_objects()")
+    .expect_file_containing("live_objects-dart/src/lib.rs", "crate::gluegun_debug::release();")
+    .expect_file_containing("live_objects-dart/dart/lib/live_objects.dart", "static int liveObjects() => _liveObjects();")
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// The lua crate is not built, as `mlua` is not otherwise a dependency of the workspace.
+#[test]
+fn debug_bindings_count_live_objects_in_lua() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("live_objects", &["lua"], demo_directory("live_objects"))
+    .cargo_glue_gun()
+    .expect_file_containing("live_objects-lua/src/lib.rs", "Ok(Counter(result, crate::gluegun_debug::LiveObject::new()))")
+    .expect_file_containing("live_objects-lua/src/lib.rs", "exports.set(\"gluegun_debug\", gluegun_debug)?;")
+    .execute()
+}
+
+/// Plugins that do not count their wrappers reject `debug-bindings` (when reading their metadata).
+#[test]
+fn debug_bindings_require_support() {
+    let error = gluegun_test_harness::Test::new("live_objects", &["dummy"], demo_directory("live_objects"))
+        .cargo_glue_gun()
+        .execute()
+        .unwrap_err();
+    let error = format!("{error:#}");
+    assert!(error.contains("gluegun-dummy failed with code"), "{error}");
+    assert!(error.contains("phase:   parsing the metadata"), "{error}");
+}
+
+/// With `pack-params`, functions with more parameters also take them packed in a record.
+#[test]
+fn pack_params_add_packed_forms() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("packed_params", &["py"], demo_directory("packed_params"))
+    .cargo_glue_gun()
+    .expect_file_containing("packed_params-py/python/packed_params/__init__.py", "class CreateUserParams:")
+    .expect_file_containing("packed_params-py/src/lib.rs", "fn create_user_packed(")
+    .expect_file_containing("packed_params-py/src/lib.rs", "let name = params.getattr(\"name\")?;")
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// The packed records are converted through their support class by raw JNI functions.
+#[test]
+fn pack_params_add_packed_forms_in_java() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("packed_params", &["java"], demo_directory("packed_params"))
+    .cargo_glue_gun()
+    .expect_file_containing(
+        "packed_params-java/java_src/packedParams/Functions.java",
+        "public static int schedule(packedParams.ScheduleParams params) {",
+    )
+    .expect_file_containing("packed_params-java/java_src/packedParams/CreateUserParams.java", "public String email;")
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// The Python package wraps the native module: it re-exports its classes, turns C-like enums into an `enum.Enum`,
+/// and calls its functions from annotated ones; `maturin` installs the native module into it as `_native`.
+#[test]
+fn python_package_wraps_native_module() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("python_package", &["py"], demo_directory("python_package"))
+    .cargo_glue_gun()
+    .expect_file_containing("python_package-py/pyproject.toml", "module-name = \"python_package._native\"")
+    .expect_file_containing("python_package-py/src/lib.rs", "#[pyo3(name = \"_native\")]")
+    .expect_file_containing(
+        "python_package-py/src/lib.rs",
+        "module.add(\"python_package_Brush\", module.py().get_type::<python_package_Brush>())?;",
+    )
+    .expect_file_containing("python_package-py/python/python_package/__init__.py", "_interface_hash.check(_native)")
+    .expect_file_containing("python_package-py/python/python_package/__init__.py", "class Color(enum.Enum):")
+    .expect_file_containing("python_package-py/python/python_package/__init__.py", "Brush = _native.python_package_Brush")
+    .expect_file_containing(
+        "python_package-py/python/python_package/__init__.py",
+        "    color = Color(color).value\n    return Color(_native.complement(color))",
+    )
+    .expect_file_containing(
+        "python_package-py/python/python_package/palette/__init__.py",
+        "return python_package.Color(_native.favorite())",
+    )
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// Resources with methods taking `self: Arc<Self>` are held in an `Arc`, which those methods are given a clone of.
+#[test]
+fn arc_receivers_share_the_value() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("arc_receivers", &["java", "py"], demo_directory("arc_receivers"))
+    .cargo_glue_gun()
+    .expect_file_containing("arc_receivers-java/src/lib.rs", "std::sync::Arc::increment_strong_count(pointer);")
+    .expect_file_containing("arc_receivers-java/java_src/arcReceivers/Counter.java", "public synchronized void increment(")
+    .expect_file_containing("arc_receivers-py/src/lib.rs", "pub struct arc_receivers_Counter(Option<std::sync::Arc<arc_receivers::Counter>>);")
+    .expect_file_containing("arc_receivers-py/src/lib.rs", "self.0.as_ref().map(std::sync::Arc::clone)")
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// Tuples map to the `TupleN` classes, whose components can be user types or other tuples.
+#[test]
+fn tuples_map_to_tuple_classes_in_java() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("tuples", &["java"], demo_directory("tuples"))
+    .cargo_glue_gun()
+    .expect_file_containing(
+        "tuples-java/java_src/tuples/Functions.java",
+        "public static tuples.gluegun.Tuple3<tuples.Point, tuples.Unit, tuples.Meters> measure(",
+    )
+    .expect_file_containing("tuples-java/java_src/tuples/gluegun/Tuple3.java", "public record Tuple3<")
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// With `option = "optional"`, options map to `java.util.Optional`, whose elements are converted like other values.
+#[test]
+fn optionals_map_to_java_optional() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("optionals", &["java"], demo_directory("optionals"))
+    .cargo_glue_gun()
+    .expect_file_containing(
+        "optionals-java/java_src/optionals/Functions.java",
+        "public static java.util.Optional<optionals.Contact> find(",
+    )
+    .expect_file_containing(
+        "optionals-java/java_src/optionals/Functions.java",
+        "public static java.util.Optional<java.util.List<Integer>> lengths(",
+    )
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// `std::io::Error`s are raised as an `OSError` in Python, with their `errno`, and thrown as an `UncheckedIOException`
+/// in Java; `Box<dyn Error>`s are raised like other errors.
+#[test]
+fn io_errors_are_raised() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("io_errors", &["py"], demo_directory("io_errors"))
+    .cargo_glue_gun()
+    .expect_file_containing("io_errors-py/src/lib.rs", "))?.map_err(gluegun_py_util::io_error)?;")
+    .expect_file_containing("io_errors-py/src/lib.rs", "))?.map_err(gluegun_py_util::runtime_error)?;")
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// I/O errors become the matching `java.io` exceptions, which the generated Java creates from the kind.
+#[test]
+fn io_errors_are_thrown_in_java() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("io_errors", &["java"], demo_directory("io_errors"))
+    .cargo_glue_gun()
+    .expect_file_containing("io_errors-java/src/lib.rs", ").map_err(gluegun_io_error_to_java)?)")
+    .expect_file_containing(
+        "io_errors-java/java_src/ioErrors/gluegun/IoErrors.java",
+        "case \"NotFound\" -> new java.nio.file.NoSuchFileException(message);",
+    )
+    .cargo_build_plugin_crates()
+    .execute()
+}
+
+/// Plugins without packed forms reject `pack-params` (when reading their metadata).
+#[test]
+fn pack_params_require_support() {
+    let error = gluegun_test_harness::Test::new("packed_params", &["dummy"], demo_directory("packed_params"))
+        .cargo_glue_gun()
+        .execute()
+        .unwrap_err();
+    let error = format!("{error:#}");
+    assert!(error.contains("gluegun-dummy failed with code"), "{error}");
+    assert!(error.contains("phase:   parsing the metadata"), "{error}");
+}
+
+/// With `module-name`, the Java sources are in the directory of the module, whose `module-info.java`
+/// exports the generated packages. Only the generated Java is checked.
+#[test]
+fn java_module_info() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("java_module", &["java"], demo_directory("java_module"))
+    .cargo_glue_gun()
+    .expect_file_containing("java_module-java/java_src/org.example.shapes/module-info.java", "module org.example.shapes {")
+    .expect_file_containing("java_module-java/java_src/org.example.shapes/module-info.java", "requires java.logging;")
+    .expect_file_containing("java_module-java/java_src/org.example.shapes/module-info.java", "exports org.example.shapes.geometry;")
+    .expect_path("java_module-java/java_src/org.example.shapes/org/example/shapes/geometry/Circle.java")
+    .expect_no_path("java_module-java/java_src/org")
+    .execute()
+}
+
+/// Each run records the crates it generated in `.gluegun/state.json`, from which `cargo gluegun clean`
+/// removes those of the selected packages (and the file itself, once none remain).
+#[test]
+fn clean_removes_generated_crates() -> anyhow::Result<()> {
+    gluegun_test_harness::Test::new("workspace", &["dummy"], demo_directory("workspace"))
+    .cargo_glue_gun_builder()
+    .option("dummy")
+    .finish()
+    .expect_file_containing(".gluegun/state.json", r#""alpha-dummy": {"#)
+    .expect_file_containing(".gluegun/state.json", r#""plugin_version": "0.1.0""#)
+    .cargo_glue_gun_builder()
+    .options(["clean", "--package", "alpha"])
+    .finish()
+    .expect_no_path("alpha-dummy")
+    .expect_path("beta-dummy")
+    .expect_file_containing("Cargo.toml", r#"members = ["alpha", "beta", "beta-dummy"]"#)
+    .cargo_glue_gun_builder()
+    .option("clean")
+    .finish()
+    .expect_no_path("beta-dummy")
+    .expect_no_path(".gluegun")
+    .execute()
+}